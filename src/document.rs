@@ -2,11 +2,12 @@
 
 use crate::classes::DxfClassCollection;
 use crate::entities::{EntityCommon, EntityType};
-use crate::objects::ObjectType;
+use crate::objects::{ObjectType, SortEntitiesTable};
 use crate::tables::*;
-use crate::types::{DxfVersion, Color, Handle, Vector2, Vector3};
+use crate::types::{DxfVersion, Color, Handle, RawGroupCode, Vector2, Vector3};
 use crate::Result;
-use std::collections::HashMap;
+use indexmap::IndexMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// DWG header variables containing drawing settings
 #[derive(Debug, Clone)]
@@ -14,7 +15,9 @@ pub struct HeaderVariables {
     // ==================== Version-specific Flags ====================
     /// REQUIREDVERSIONS (R2013+) - Bit coded required versions
     pub required_versions: i64,
-    
+    /// ACADMAINTVER - Maintenance version number
+    pub maintenance_version: i32,
+
     // ==================== Drawing Mode Flags ====================
     /// DIMASO - Associates dimensions with geometry
     pub associate_dimensions: bool,
@@ -60,7 +63,57 @@ pub struct HeaderVariables {
     pub attribute_request: bool,
     /// ATTDIA - Attribute dialog mode
     pub attribute_dialog: bool,
-    
+    /// SKPOLY - Sketch creates polylines instead of lines
+    pub sketch_polylines: bool,
+    /// REALWORLDSCALE - Drawing is scaled to real-world units (R2007+)
+    pub real_world_scale: bool,
+    /// SOLIDHIST - History recording for new solids (R2007+)
+    pub solid_history: i16,
+    /// SHOWHIST - History display of solids (R2007+)
+    pub show_history: i16,
+    /// DWFFRAME - DWF underlay frame visibility (R2007+)
+    pub dwf_frame: i16,
+    /// DGNFRAME - DGN underlay frame visibility (R2007+)
+    pub dgn_frame: i16,
+    /// LIGHTGLYPHDISPLAY - Light glyph display (R2007+)
+    pub light_glyph_display: i16,
+    /// TILEMODELIGHTSYNCH - Light synchronization between model space and layouts (R2007+)
+    pub tile_model_light_sync: i16,
+
+    // ==================== R12 Drawing Settings ====================
+    // Stored in the *ACTIVE viewport from R13 on; only R12 files carry
+    // them in the header.
+    /// AXISMODE - Axis display on/off
+    pub axis_mode: bool,
+    /// AXISUNIT - Axis tick spacing
+    pub axis_unit: Vector2,
+    /// FASTZOOM - Fast zoom on/off
+    pub fast_zoom: bool,
+    /// GRIDMODE - Grid display on/off
+    pub grid_mode: bool,
+    /// GRIDUNIT - Grid spacing
+    pub grid_unit: Vector2,
+    /// HANDLING - Handles enabled
+    pub handling: bool,
+    /// SNAPANG - Snap grid rotation angle
+    pub snap_angle: f64,
+    /// SNAPBASE - Snap and grid base point
+    pub snap_base: Vector2,
+    /// SNAPISOPAIR - Isometric plane (0=left, 1=top, 2=right)
+    pub snap_isopair: i16,
+    /// SNAPMODE - Snap on/off
+    pub snap_mode: bool,
+    /// SNAPSTYLE - Snap style (0=standard, 1=isometric)
+    pub snap_style: i16,
+    /// SNAPUNIT - Snap spacing
+    pub snap_unit: Vector2,
+    /// VIEWCTR - Center of the current view
+    pub view_center: Vector2,
+    /// VIEWDIR - Viewing direction of the current view
+    pub view_direction: Vector3,
+    /// VIEWSIZE - Height of the current view
+    pub view_size: f64,
+
     // ==================== Unit Settings ====================
     /// LUNITS - Linear units format (0=Scientific, 1=Decimal, 2=Engineering, 3=Architectural, 4=Fractional)
     pub linear_unit_format: i16,
@@ -138,7 +191,13 @@ pub struct HeaderVariables {
     pub dimension_associativity: i16,
     /// PROJECTNAME - Project name
     pub project_name: String,
-    
+    /// UNITMODE - Display units as entered (0) or in the current format (1)
+    pub unit_mode: i16,
+    /// CSHADOW - Shadow mode of new objects (R2007+)
+    pub current_shadow_mode: i16,
+    /// INTERFERECOLOR - Color of interference objects (R2007+)
+    pub interference_color: Color,
+
     // ==================== Scale/Size Defaults ====================
     /// LTSCALE - Global linetype scale
     pub linetype_scale: f64,
@@ -220,7 +279,13 @@ pub struct HeaderVariables {
     pub camera_height: f64,
     /// CAMERADISPLAY - Camera display mode
     pub camera_display: bool,
-    
+    /// 3DDWFPREC - 3D DWF publishing precision (R2007+)
+    pub dwf_precision_3d: f64,
+    /// PSOLWIDTH - Default polysolid width (R2007+)
+    pub polysolid_width: f64,
+    /// PSOLHEIGHT - Default polysolid height (R2007+)
+    pub polysolid_height: f64,
+
     // ==================== Current Entity Settings ====================
     /// CECOLOR - Current entity color
     pub current_entity_color: Color,
@@ -228,6 +293,8 @@ pub struct HeaderVariables {
     pub current_line_weight: i16,
     /// CEPSNTYPE - Current plot style name type
     pub current_plotstyle_type: i16,
+    /// CEPSNID - Plot style of new objects when CEPSNTYPE is 3
+    pub current_plotstyle_handle: Handle,
     /// ENDCAPS - Line end cap style
     pub end_caps: i16,
     /// JOINSTYLE - Line join style
@@ -366,7 +433,29 @@ pub struct HeaderVariables {
     pub dim_arrow_block2: String,
     /// DIMLDRBLK - Leader arrow block name
     pub dim_leader_arrow_block: String,
-    
+    /// DIMTXSTY - Dimension text style name
+    pub dim_text_style_name: String,
+    /// DIMLTYPE - Dimension line linetype name (R2007+)
+    pub dim_linetype_name: String,
+    /// DIMLTEX1 - First extension line linetype name (R2007+)
+    pub dim_linetype1_name: String,
+    /// DIMLTEX2 - Second extension line linetype name (R2007+)
+    pub dim_linetype2_name: String,
+    /// DIMFXL - Fixed extension line length (R2007+)
+    pub dim_fixed_ext_line_length: f64,
+    /// DIMFXLON - Fixed length extension lines on/off (R2007+)
+    pub dim_fixed_ext_line_enabled: bool,
+    /// DIMJOGANG - Jog angle of jogged radius dimensions (R2007+)
+    pub dim_jog_angle: f64,
+    /// DIMTFILL - Text background fill (0=none, 1=drawing background, 2=DIMTFILLCLR) (R2007+)
+    pub dim_text_fill: i16,
+    /// DIMTFILLCLR - Text background fill color (R2007+)
+    pub dim_text_fill_color: Color,
+    /// DIMARCSYM - Arc length symbol position (0=before, 1=above, 2=none) (R2007+)
+    pub dim_arc_symbol_position: i16,
+    /// DIMTXTDIRECTION - Text reading direction (R2010+)
+    pub dim_text_direction: bool,
+
     // ==================== Extents and Limits ====================
     /// INSBASE - Model space insertion base point
     pub model_space_insertion_base: Vector3,
@@ -417,7 +506,37 @@ pub struct HeaderVariables {
     pub paper_ucs_ortho_ref: Handle,
     /// PUCSORTHOVIEW - Paper space UCS orthographic view type
     pub paper_ucs_ortho_view: i16,
-    
+    /// UCSORTHOREF - Name of the UCS orthographic reference, as written in DXF
+    pub ucs_ortho_ref_name: String,
+    /// PUCSORTHOREF - Name of the paper space UCS orthographic reference, as written in DXF
+    pub paper_ucs_ortho_ref_name: String,
+    /// PUCSBASE - Paper space UCS base name
+    pub paper_ucs_base: String,
+    /// UCSORGTOP - Model space origin of the TOP orthographic UCS
+    pub model_space_ucs_origin_top: Vector3,
+    /// UCSORGBOTTOM - Model space origin of the BOTTOM orthographic UCS
+    pub model_space_ucs_origin_bottom: Vector3,
+    /// UCSORGLEFT - Model space origin of the LEFT orthographic UCS
+    pub model_space_ucs_origin_left: Vector3,
+    /// UCSORGRIGHT - Model space origin of the RIGHT orthographic UCS
+    pub model_space_ucs_origin_right: Vector3,
+    /// UCSORGFRONT - Model space origin of the FRONT orthographic UCS
+    pub model_space_ucs_origin_front: Vector3,
+    /// UCSORGBACK - Model space origin of the BACK orthographic UCS
+    pub model_space_ucs_origin_back: Vector3,
+    /// PUCSORGTOP - Paper space origin of the TOP orthographic UCS
+    pub paper_space_ucs_origin_top: Vector3,
+    /// PUCSORGBOTTOM - Paper space origin of the BOTTOM orthographic UCS
+    pub paper_space_ucs_origin_bottom: Vector3,
+    /// PUCSORGLEFT - Paper space origin of the LEFT orthographic UCS
+    pub paper_space_ucs_origin_left: Vector3,
+    /// PUCSORGRIGHT - Paper space origin of the RIGHT orthographic UCS
+    pub paper_space_ucs_origin_right: Vector3,
+    /// PUCSORGFRONT - Paper space origin of the FRONT orthographic UCS
+    pub paper_space_ucs_origin_front: Vector3,
+    /// PUCSORGBACK - Paper space origin of the BACK orthographic UCS
+    pub paper_space_ucs_origin_back: Vector3,
+
     // ==================== Handles/References ====================
     /// HANDSEED - Next available handle
     pub handle_seed: u64,
@@ -433,6 +552,12 @@ pub struct HeaderVariables {
     pub current_multiline_style_handle: Handle,
     /// Current material handle
     pub current_material_handle: Handle,
+    /// DRAGVS - Visual style used while creating 3D solids (R2007+)
+    pub drag_visual_style_handle: Handle,
+    /// INTERFEREOBJVS - Visual style of interference objects (R2007+)
+    pub interference_object_visual_style_handle: Handle,
+    /// INTERFEREVPVS - Visual style of the viewport during interference checking (R2007+)
+    pub interference_viewport_visual_style_handle: Handle,
     /// Dimension text style handle
     pub dim_text_style_handle: Handle,
     /// Dimension linetype handle
@@ -511,6 +636,10 @@ pub struct HeaderVariables {
     pub create_date_julian: f64,
     /// Document update time (Julian date)
     pub update_date_julian: f64,
+    /// TDUCREATE - Document creation time in universal time (Julian date)
+    pub utc_create_date_julian: f64,
+    /// TDUUPDATE - Document update time in universal time (Julian date)
+    pub utc_update_date_julian: f64,
     /// Total editing time in days
     pub total_editing_time: f64,
     /// User elapsed time in days
@@ -549,6 +678,12 @@ pub struct HeaderVariables {
     pub current_text_style_name: String,
     /// DIMSTYLE - Current dimension style name
     pub current_dimstyle_name: String,
+
+    // ==================== Unmodelled Variables ====================
+    /// Variables read from a DXF HEADER that no field above stores, keyed by
+    /// name (with the `$`) in file order. They are written back unchanged,
+    /// after the modelled variables.
+    pub unknown_variables: IndexMap<String, Vec<RawGroupCode>>,
 }
 
 impl Default for HeaderVariables {
@@ -556,7 +691,8 @@ impl Default for HeaderVariables {
         Self {
             // Version-specific flags
             required_versions: 0,
-            
+            maintenance_version: 0,
+
             // Drawing mode flags
             associate_dimensions: true,
             update_dimensions_while_dragging: true,
@@ -580,6 +716,31 @@ impl Default for HeaderVariables {
             blip_mode: false,
             attribute_request: true,
             attribute_dialog: true,
+            sketch_polylines: false,
+            real_world_scale: true,
+            solid_history: 0,
+            show_history: 1,
+            dwf_frame: 2,
+            dgn_frame: 0,
+            light_glyph_display: 1,
+            tile_model_light_sync: 1,
+
+            // R12 drawing settings
+            axis_mode: false,
+            axis_unit: Vector2::new(0.0, 0.0),
+            fast_zoom: true,
+            grid_mode: false,
+            grid_unit: Vector2::new(0.5, 0.5),
+            handling: false,
+            snap_angle: 0.0,
+            snap_base: Vector2::new(0.0, 0.0),
+            snap_isopair: 0,
+            snap_mode: false,
+            snap_style: 0,
+            snap_unit: Vector2::new(0.5, 0.5),
+            view_center: Vector2::new(6.0, 4.5),
+            view_direction: Vector3::new(0.0, 0.0, 1.0),
+            view_size: 9.0,
             
             // Unit settings
             linear_unit_format: 2,  // Decimal
@@ -618,6 +779,9 @@ impl Default for HeaderVariables {
             intersection_color: 257,
             dimension_associativity: 2,
             project_name: String::new(),
+            unit_mode: 0,
+            current_shadow_mode: 0,
+            interference_color: Color::Index(1),
             
             // Scale/size defaults
             linetype_scale: 1.0,
@@ -658,11 +822,15 @@ impl Default for HeaderVariables {
             lens_length: 50.0,
             camera_height: 0.0,
             camera_display: false,
+            dwf_precision_3d: 2.0,
+            polysolid_width: 0.25,
+            polysolid_height: 4.0,
             
             // Current entity settings
             current_entity_color: Color::ByLayer,
             current_line_weight: -1, // ByLayer
             current_plotstyle_type: 0,
+            current_plotstyle_handle: Handle::NULL,
             end_caps: 0,
             join_style: 0,
             lineweight_display: false,
@@ -733,6 +901,17 @@ impl Default for HeaderVariables {
             dim_arrow_block1: String::new(),
             dim_arrow_block2: String::new(),
             dim_leader_arrow_block: String::new(),
+            dim_text_style_name: String::from("Standard"),
+            dim_linetype_name: String::new(),
+            dim_linetype1_name: String::new(),
+            dim_linetype2_name: String::new(),
+            dim_fixed_ext_line_length: 1.0,
+            dim_fixed_ext_line_enabled: false,
+            dim_jog_angle: std::f64::consts::FRAC_PI_4,
+            dim_text_fill: 0,
+            dim_text_fill_color: Color::ByBlock,
+            dim_arc_symbol_position: 0,
+            dim_text_direction: false,
             
             // Extents and limits - Model space
             model_space_insertion_base: Vector3::ZERO,
@@ -762,6 +941,21 @@ impl Default for HeaderVariables {
             ucs_ortho_view: 0,
             paper_ucs_ortho_ref: Handle::NULL,
             paper_ucs_ortho_view: 0,
+            ucs_ortho_ref_name: String::new(),
+            paper_ucs_ortho_ref_name: String::new(),
+            paper_ucs_base: String::new(),
+            model_space_ucs_origin_top: Vector3::ZERO,
+            model_space_ucs_origin_bottom: Vector3::ZERO,
+            model_space_ucs_origin_left: Vector3::ZERO,
+            model_space_ucs_origin_right: Vector3::ZERO,
+            model_space_ucs_origin_front: Vector3::ZERO,
+            model_space_ucs_origin_back: Vector3::ZERO,
+            paper_space_ucs_origin_top: Vector3::ZERO,
+            paper_space_ucs_origin_bottom: Vector3::ZERO,
+            paper_space_ucs_origin_left: Vector3::ZERO,
+            paper_space_ucs_origin_right: Vector3::ZERO,
+            paper_space_ucs_origin_front: Vector3::ZERO,
+            paper_space_ucs_origin_back: Vector3::ZERO,
             
             // Handles
             handle_seed: 1,
@@ -771,6 +965,9 @@ impl Default for HeaderVariables {
             current_dimstyle_handle: Handle::NULL,
             current_multiline_style_handle: Handle::NULL,
             current_material_handle: Handle::NULL,
+            drag_visual_style_handle: Handle::NULL,
+            interference_object_visual_style_handle: Handle::NULL,
+            interference_viewport_visual_style_handle: Handle::NULL,
             dim_text_style_handle: Handle::NULL,
             dim_linetype_handle: Handle::NULL,
             dim_linetype1_handle: Handle::NULL,
@@ -814,6 +1011,8 @@ impl Default for HeaderVariables {
             // Date/time
             create_date_julian: 0.0,
             update_date_julian: 0.0,
+            utc_create_date_julian: 0.0,
+            utc_update_date_julian: 0.0,
             total_editing_time: 0.0,
             user_elapsed_time: 0.0,
             
@@ -835,10 +1034,83 @@ impl Default for HeaderVariables {
             current_layer_name: String::from("0"),
             current_text_style_name: String::from("Standard"),
             current_dimstyle_name: String::from("Standard"),
+
+            unknown_variables: IndexMap::new(),
         }
     }
 }
 
+/// A file the drawing depends on (font, xref, image, ...), as listed in
+/// the DWG `AcDb:FileDepList` section
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileDependency {
+    /// Feature the file belongs to ("Acad:XRef", "Acad:Text", "Acad:Image", ...)
+    pub feature: String,
+    /// File name as referenced by the drawing
+    pub full_filename: String,
+    /// Path the file was found at when the drawing was saved
+    pub found_path: String,
+    /// Fingerprint GUID (xrefs only)
+    pub fingerprint_guid: String,
+    /// Version GUID (xrefs only)
+    pub version_guid: String,
+    /// Modification timestamp of the file (seconds since 1980-01-01)
+    pub timestamp: i32,
+    /// File size in bytes
+    pub file_size: i32,
+    /// Whether changes to the file affect the drawing's graphics
+    pub affects_graphics: bool,
+    /// Number of references to the file in the drawing
+    pub reference_count: i32,
+}
+
+/// Revision history stored in the DWG `AcDb:RevHistory` section
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RevisionHistory {
+    /// First header value (meaning undocumented)
+    pub unknown1: u32,
+    /// Second header value (meaning undocumented)
+    pub unknown2: u32,
+    /// Revision entries
+    pub entries: Vec<u32>,
+}
+
+/// Drawing template information stored in the DWG `AcDb:Template` section
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrawingTemplate {
+    /// Template description
+    pub description: String,
+    /// MEASUREMENT value of the template (0 = English, 1 = Metric)
+    pub measurement: i16,
+}
+
+/// Drawing properties (DWGPROPS): stored in the DWG `AcDb:SummaryInfo`
+/// section and in the `DWGPROPS` XRecord of the named object dictionary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CadSummaryInfo {
+    pub title: String,
+    pub subject: String,
+    pub author: String,
+    pub keywords: String,
+    pub comments: String,
+    pub last_saved_by: String,
+    pub revision_number: String,
+    pub hyperlink_base: String,
+    /// (julian_date, milliseconds)
+    pub created_date: (i32, i32),
+    /// (julian_date, milliseconds)
+    pub modified_date: (i32, i32),
+    /// Custom key/value properties.
+    pub properties: BTreeMap<String, String>,
+}
+
+impl CadSummaryInfo {
+    /// Whether no property has been set
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// A CAD document containing all drawing data
 #[derive(Debug, Clone)]
 pub struct CadDocument {
@@ -881,8 +1153,20 @@ pub struct CadDocument {
     /// Notifications collected during the last read/write operation
     pub notifications: crate::notification::NotificationCollection,
 
-    /// All entities in the document (indexed by handle)
-    entities: HashMap<Handle, EntityType>,
+    /// External files the drawing depends on (DWG AcDb:FileDepList)
+    pub file_dependencies: Vec<FileDependency>,
+
+    /// Revision history (DWG AcDb:RevHistory)
+    pub revision_history: Option<RevisionHistory>,
+
+    /// Template description and measurement (DWG AcDb:Template)
+    pub template: Option<DrawingTemplate>,
+
+    /// Drawing properties (DWG AcDb:SummaryInfo, DXF DWGPROPS XRecord)
+    pub summary_info: CadSummaryInfo,
+
+    /// All entities in the document, in file order (indexed by handle)
+    entities: IndexMap<Handle, EntityType>,
 
     /// All objects in the document (indexed by handle)
     pub objects: HashMap<Handle, ObjectType>,
@@ -908,7 +1192,11 @@ impl CadDocument {
             ucss: Table::new(),
             classes: DxfClassCollection::new(),
             notifications: crate::notification::NotificationCollection::new(),
-            entities: HashMap::new(),
+            file_dependencies: Vec::new(),
+            revision_history: None,
+            template: None,
+            summary_info: CadSummaryInfo::default(),
+            entities: IndexMap::new(),
             objects: HashMap::new(),
             // Start handle allocation above reserved table handles (0x1-0xA)
             // Table handles are well-known fixed values used by AutoCAD
@@ -1022,20 +1310,41 @@ impl CadDocument {
         self.next_handle
     }
 
-    /// Add an entity to the document
+    /// Add an entity to the document.
+    ///
+    /// The entity goes after the entities already added; an entity with the
+    /// handle of an existing one replaces it in its place.
     pub fn add_entity(&mut self, mut entity: EntityType) -> Result<Handle> {
-        // Allocate a handle if the entity doesn't have one
-        let handle = if entity.as_entity().handle().is_null() {
+        let handle = self.prepare_entity_handle(&mut entity);
+        self.entities.insert(handle, entity);
+        Ok(handle)
+    }
+
+    /// Insert an entity at `index` in the entity order, shifting the
+    /// entities after it. An entity with the handle of an existing one is
+    /// moved there.
+    pub fn insert_entity(&mut self, index: usize, mut entity: EntityType) -> Result<Handle> {
+        let handle = self.prepare_entity_handle(&mut entity);
+        // An existing entity is moved, so the last valid place is the end of
+        // the order without it
+        let last = if self.entities.contains_key(&handle) {
+            self.entities.len() - 1
+        } else {
+            self.entities.len()
+        };
+        self.entities.shift_insert(index.min(last), handle, entity);
+        Ok(handle)
+    }
+
+    /// Allocate a handle for an entity that has none
+    fn prepare_entity_handle(&mut self, entity: &mut EntityType) -> Handle {
+        if entity.as_entity().handle().is_null() {
             let h = self.allocate_handle();
             entity.as_entity_mut().set_handle(h);
             h
         } else {
             entity.as_entity().handle()
-        };
-
-        // Store the entity
-        self.entities.insert(handle, entity);
-        Ok(handle)
+        }
     }
 
     /// Get an entity by handle
@@ -1048,9 +1357,24 @@ impl CadDocument {
         self.entities.get_mut(&handle)
     }
 
-    /// Remove an entity by handle
+    /// Remove an entity by handle, keeping the order of the others.
+    ///
+    /// This shifts the entities after it; use [`retain_entities`] to
+    /// remove many at once.
+    ///
+    /// [`retain_entities`]: Self::retain_entities
     pub fn remove_entity(&mut self, handle: Handle) -> Option<EntityType> {
-        self.entities.remove(&handle)
+        self.entities.shift_remove(&handle)
+    }
+
+    /// Remove all entities and return them in order
+    pub(crate) fn take_entities(&mut self) -> Vec<EntityType> {
+        std::mem::take(&mut self.entities).into_values().collect()
+    }
+
+    /// Keep only the entities for which `keep` returns true, in order
+    pub fn retain_entities(&mut self, mut keep: impl FnMut(&EntityType) -> bool) {
+        self.entities.retain(|_, entity| keep(entity));
     }
 
     /// Get the number of entities
@@ -1058,16 +1382,120 @@ impl CadDocument {
         self.entities.len()
     }
 
-    /// Iterate over all entities
+    /// Iterate over all entities, in file order for a document that was
+    /// read, and in the order they were added otherwise
     pub fn entities(&self) -> impl Iterator<Item = &EntityType> {
         self.entities.values()
     }
 
-    /// Iterate over all entities mutably
+    /// Iterate over all entities mutably, in order
     pub fn entities_mut(&mut self) -> impl Iterator<Item = &mut EntityType> {
         self.entities.values_mut()
     }
 
+    /// Position of an entity in the entity order
+    pub fn entity_index(&self, handle: Handle) -> Option<usize> {
+        self.entities.get_index_of(&handle)
+    }
+
+    /// Get the entity at `index` in the entity order
+    pub fn entity_at(&self, index: usize) -> Option<&EntityType> {
+        self.entities.get_index(index).map(|(_, entity)| entity)
+    }
+
+    /// Move an entity to `index` in the entity order, shifting the entities
+    /// in between. Returns false if there is no entity with that handle.
+    pub fn move_entity(&mut self, handle: Handle, index: usize) -> bool {
+        let Some(from) = self.entities.get_index_of(&handle) else {
+            return false;
+        };
+        let to = index.min(self.entities.len() - 1);
+        self.entities.move_index(from, to);
+        true
+    }
+
+    /// Move an entity to the end of the entity order, so it is written
+    /// last and drawn on top of the others
+    pub fn bring_entity_to_front(&mut self, handle: Handle) -> bool {
+        self.move_entity(handle, usize::MAX)
+    }
+
+    /// Move an entity to the start of the entity order, so it is written
+    /// first and drawn below the others
+    pub fn send_entity_to_back(&mut self, handle: Handle) -> bool {
+        self.move_entity(handle, 0)
+    }
+
+    /// Sort the entities with a comparison function. The sort is stable.
+    pub fn sort_entities_by(
+        &mut self,
+        mut compare: impl FnMut(&EntityType, &EntityType) -> std::cmp::Ordering,
+    ) {
+        self.entities.sort_by(|_, a, _, b| compare(a, b));
+    }
+
+    /// SORTENTSTABLE object of a block record (model space, paper space
+    /// or a block), if the document has one
+    pub fn sort_entities_table(&self, block_record: Handle) -> Option<&SortEntitiesTable> {
+        self.objects.values().find_map(|object| match object {
+            ObjectType::SortEntitiesTable(table) if table.block_owner_handle == block_record => {
+                Some(table)
+            }
+            _ => None,
+        })
+    }
+
+    /// Entities in the order they are drawn.
+    ///
+    /// This is the entity order, except where the space that owns the
+    /// entities has a SORTENTSTABLE: its entities are then drawn by sort
+    /// handle, each entity not in the table sorting by its own handle.
+    pub fn entities_in_draw_order(&self) -> Vec<&EntityType> {
+        let mut entities: Vec<&EntityType> = self.entities.values().collect();
+        for (block_record, positions) in self.positions_by_owner() {
+            let Some(table) = self.sort_entities_table(block_record) else {
+                continue;
+            };
+            let mut sorted: Vec<&EntityType> = positions.iter().map(|&i| entities[i]).collect();
+            sorted.sort_by_key(|e| table.effective_sort_handle(e.common().handle).value());
+            for (&i, entity) in positions.iter().zip(sorted) {
+                entities[i] = entity;
+            }
+        }
+        entities
+    }
+
+    /// Reorder the entities into their draw order (see
+    /// [`entities_in_draw_order`](Self::entities_in_draw_order)), so files
+    /// written afterwards list them in the order they are drawn.
+    pub fn apply_draw_order(&mut self) {
+        let order: Vec<Handle> = self
+            .entities_in_draw_order()
+            .iter()
+            .map(|e| e.common().handle)
+            .collect();
+        for (index, handle) in order.into_iter().enumerate() {
+            if let Some(from) = self.entities.get_index_of(&handle) {
+                self.entities.move_index(from, index);
+            }
+        }
+    }
+
+    /// Positions in the entity order of the entities of each owner; entities
+    /// without an owner belong to model space
+    fn positions_by_owner(&self) -> BTreeMap<Handle, Vec<usize>> {
+        let model_space = self.header.model_space_block_handle;
+        let mut positions: BTreeMap<Handle, Vec<usize>> = BTreeMap::new();
+        for (i, entity) in self.entities.values().enumerate() {
+            let owner = match entity.common().owner_handle {
+                owner if owner.is_null() => model_space,
+                owner => owner,
+            };
+            positions.entry(owner).or_default().push(i);
+        }
+        positions
+    }
+
     /// Resolve handle references after reading a DXF file.
     ///
     /// This performs a simplified version of ACadSharp's two-phase build:
@@ -1079,6 +1507,8 @@ impl CadDocument {
     /// 3. Updates `next_handle` to be above the maximum handle seen in the
     ///    document so that subsequent `allocate_handle()` calls produce unique
     ///    values.
+    /// 4. Allocates handles for table entries, block entities and block-owned
+    ///    entities read without one, as in R12 files written without handles.
     ///
     /// Call this once after loading (the DXF reader calls it automatically).
     pub fn resolve_references(&mut self) {
@@ -1103,9 +1533,10 @@ impl CadDocument {
 
         // Check block record entities
         for br in self.block_records.iter() {
-            let h = br.handle.value();
-            if h >= max_handle {
-                max_handle = h + 1;
+            for h in [br.handle, br.block_entity_handle, br.block_end_handle] {
+                if h.value() >= max_handle {
+                    max_handle = h.value() + 1;
+                }
             }
             for entity in &br.entities {
                 let h = entity.common().handle.value();
@@ -1115,9 +1546,47 @@ impl CadDocument {
             }
         }
 
+        // Check table entries
+        raise_above_table(&self.layers, &mut max_handle);
+        raise_above_table(&self.line_types, &mut max_handle);
+        raise_above_table(&self.text_styles, &mut max_handle);
+        raise_above_table(&self.dim_styles, &mut max_handle);
+        raise_above_table(&self.app_ids, &mut max_handle);
+        raise_above_table(&self.views, &mut max_handle);
+        raise_above_table(&self.vports, &mut max_handle);
+        raise_above_table(&self.ucss, &mut max_handle);
+
         self.next_handle = max_handle;
 
-        // --- 2. Assign owner handles ---
+        // --- 2. Assign handles to records read without one (R12 DXF) ---
+        let mut next = self.next_handle;
+        assign_table_handles(&mut self.layers, &mut next);
+        assign_table_handles(&mut self.line_types, &mut next);
+        assign_table_handles(&mut self.text_styles, &mut next);
+        assign_table_handles(&mut self.block_records, &mut next);
+        assign_table_handles(&mut self.dim_styles, &mut next);
+        assign_table_handles(&mut self.app_ids, &mut next);
+        assign_table_handles(&mut self.views, &mut next);
+        assign_table_handles(&mut self.vports, &mut next);
+        assign_table_handles(&mut self.ucss, &mut next);
+        for br in self.block_records.iter_mut() {
+            for handle in [&mut br.block_entity_handle, &mut br.block_end_handle] {
+                if handle.is_null() {
+                    *handle = Handle::new(next);
+                    next += 1;
+                }
+            }
+            for entity in &mut br.entities {
+                let common = get_common_mut(entity);
+                if common.handle.is_null() {
+                    common.handle = Handle::new(next);
+                    next += 1;
+                }
+            }
+        }
+        self.next_handle = next;
+
+        // --- 3. Assign owner handles ---
         let model_handle = self.header.model_space_block_handle;
         let paper_handle = self.header.paper_space_block_handle;
 
@@ -1164,6 +1633,26 @@ impl CadDocument {
     }
 }
 
+/// Raise `max_handle` above the handles of a table's entries.
+fn raise_above_table<T: TableEntry>(table: &Table<T>, max_handle: &mut u64) {
+    for entry in table.iter() {
+        let h = entry.handle().value();
+        if h >= *max_handle {
+            *max_handle = h + 1;
+        }
+    }
+}
+
+/// Give every table entry without a handle the next free one.
+fn assign_table_handles<T: TableEntry>(table: &mut Table<T>, next: &mut u64) {
+    for entry in table.iter_mut() {
+        if entry.handle().is_null() {
+            entry.set_handle(Handle::new(*next));
+            *next += 1;
+        }
+    }
+}
+
 /// Helper to get a mutable reference to EntityCommon for non-Dimension entities.
 fn get_common_mut(entity: &mut EntityType) -> &mut EntityCommon {
     match entity {
@@ -1219,3 +1708,101 @@ impl Default for CadDocument {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, Line, Point};
+
+    fn handles(doc: &CadDocument) -> Vec<u64> {
+        doc.entities().map(|e| e.common().handle.value()).collect()
+    }
+
+    fn document_with_entities() -> (CadDocument, Vec<Handle>) {
+        let mut doc = CadDocument::new();
+        let mut added = Vec::new();
+        for i in 0..4 {
+            let mut point = Point::new();
+            point.common.handle = Handle::new(0x100 - i);
+            added.push(doc.add_entity(EntityType::Point(point)).unwrap());
+        }
+        (doc, added)
+    }
+
+    #[test]
+    fn test_entities_keep_insertion_order() {
+        let (mut doc, added) = document_with_entities();
+        assert_eq!(handles(&doc), [0x100, 0xFF, 0xFE, 0xFD]);
+        assert_eq!(doc.entity_index(added[2]), Some(2));
+
+        doc.remove_entity(added[1]);
+        assert_eq!(handles(&doc), [0x100, 0xFE, 0xFD]);
+
+        let line = EntityType::Line(Line::new());
+        let handle = doc.insert_entity(1, line).unwrap();
+        assert_eq!(doc.entity_index(handle), Some(1));
+        assert_eq!(doc.entity_at(1).unwrap().common().handle, handle);
+    }
+
+    #[test]
+    fn test_insert_existing_entity_at_end() {
+        let (mut doc, added) = document_with_entities();
+
+        let first = doc.get_entity(added[0]).unwrap().clone();
+        let count = doc.entity_count();
+        assert_eq!(doc.insert_entity(count, first).unwrap(), added[0]);
+        assert_eq!(handles(&doc), [0xFF, 0xFE, 0xFD, 0x100]);
+
+        let second = doc.get_entity(added[1]).unwrap().clone();
+        assert_eq!(doc.insert_entity(usize::MAX, second).unwrap(), added[1]);
+        assert_eq!(handles(&doc), [0xFE, 0xFD, 0x100, 0xFF]);
+        assert_eq!(doc.entity_count(), 4);
+    }
+
+    #[test]
+    fn test_reorder_entities() {
+        let (mut doc, added) = document_with_entities();
+        assert!(doc.bring_entity_to_front(added[0]));
+        assert_eq!(handles(&doc), [0xFF, 0xFE, 0xFD, 0x100]);
+        assert!(doc.send_entity_to_back(added[3]));
+        assert_eq!(handles(&doc), [0xFD, 0xFF, 0xFE, 0x100]);
+        assert!(doc.move_entity(added[1], 2));
+        assert_eq!(handles(&doc), [0xFD, 0xFE, 0xFF, 0x100]);
+        assert!(!doc.move_entity(Handle::new(0x999), 0));
+
+        doc.sort_entities_by(|a, b| b.common().handle.cmp(&a.common().handle));
+        assert_eq!(handles(&doc), [0x100, 0xFF, 0xFE, 0xFD]);
+    }
+
+    #[test]
+    fn test_draw_order_follows_sort_entities_table() {
+        let (mut doc, added) = document_with_entities();
+        let mut circle = Circle::new();
+        circle.common.owner_handle = doc.header.paper_space_block_handle;
+        let paper = doc.insert_entity(0, EntityType::Circle(circle)).unwrap();
+
+        // Without a table, entities are drawn in order
+        let drawn: Vec<Handle> =
+            doc.entities_in_draw_order().iter().map(|e| e.common().handle).collect();
+        assert_eq!(drawn[0], paper);
+        assert_eq!(drawn[1..], added[..]);
+
+        let mut table = SortEntitiesTable::for_block(doc.header.model_space_block_handle);
+        table.handle = doc.allocate_handle();
+        table.add_entry(added[0], Handle::new(0x1));
+        table.add_entry(added[3], Handle::new(0x200));
+        doc.objects.insert(table.handle, ObjectType::SortEntitiesTable(table));
+
+        // 0x100 is sent to the back and 0xFD to the front; the others sort
+        // by their own handle. The paper space entity keeps its place.
+        let drawn: Vec<u64> = doc
+            .entities_in_draw_order()
+            .iter()
+            .map(|e| e.common().handle.value())
+            .collect();
+        assert_eq!(drawn, [paper.value(), 0x100, 0xFE, 0xFF, 0xFD]);
+
+        doc.apply_draw_order();
+        assert_eq!(handles(&doc), drawn);
+    }
+}
@@ -39,6 +39,7 @@ pub mod underlay;
 pub mod seqend;
 pub mod ole2frame;
 pub mod polygon_mesh;
+pub mod proxy_graphics;
 pub mod unknown_entity;
 
 pub use point::Point;
@@ -112,6 +113,7 @@ pub use ole2frame::{Ole2Frame, OleObjectType};
 pub use polygon_mesh::{
     PolygonMesh as PolygonMeshEntity, PolygonMeshVertex, PolygonMeshFlags, SurfaceSmoothType,
 };
+pub use proxy_graphics::{ProxyGraphicGeometry, ProxyGraphicPrimitive, ProxyGraphics};
 pub use unknown_entity::UnknownEntity;
 
 /// Base trait for all CAD entities
@@ -216,6 +218,8 @@ pub struct EntityCommon {
     pub xdictionary_handle: Option<Handle>,
     /// Owner handle (soft pointer, code 330)
     pub owner_handle: Handle,
+    /// Group codes read from a file that no other field stores
+    pub unknown_codes: crate::types::UnknownCodes,
 }
 
 impl EntityCommon {
@@ -232,6 +236,7 @@ impl EntityCommon {
             reactors: Vec::new(),
             xdictionary_handle: None,
             owner_handle: Handle::NULL,
+            unknown_codes: crate::types::UnknownCodes::new(),
         }
     }
 
@@ -251,6 +256,9 @@ impl Default for EntityCommon {
 }
 
 /// Enumeration of all entity types for type-safe storage
+// Entities are matched and built by value throughout the crate; boxing the
+// larger variants would only move the allocation behind the same `IndexMap`
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum EntityType {
     /// Point entity
@@ -483,5 +491,101 @@ impl EntityType {
             EntityType::Unknown(e) => &e.common,
         }
     }
+
+    /// Get a mutable reference to the entity's common data
+    pub fn common_mut(&mut self) -> &mut EntityCommon {
+        match self {
+            EntityType::Point(e) => &mut e.common,
+            EntityType::Line(e) => &mut e.common,
+            EntityType::Circle(e) => &mut e.common,
+            EntityType::Arc(e) => &mut e.common,
+            EntityType::Ellipse(e) => &mut e.common,
+            EntityType::Polyline(e) => &mut e.common,
+            EntityType::Polyline2D(e) => &mut e.common,
+            EntityType::Polyline3D(e) => &mut e.common,
+            EntityType::LwPolyline(e) => &mut e.common,
+            EntityType::Text(e) => &mut e.common,
+            EntityType::MText(e) => &mut e.common,
+            EntityType::Spline(e) => &mut e.common,
+            EntityType::Dimension(e) => &mut e.base_mut().common,
+            EntityType::Hatch(e) => &mut e.common,
+            EntityType::Solid(e) => &mut e.common,
+            EntityType::Face3D(e) => &mut e.common,
+            EntityType::Insert(e) => &mut e.common,
+            EntityType::Block(e) => &mut e.common,
+            EntityType::BlockEnd(e) => &mut e.common,
+            EntityType::Ray(e) => &mut e.common,
+            EntityType::XLine(e) => &mut e.common,
+            EntityType::Viewport(e) => &mut e.common,
+            EntityType::AttributeDefinition(e) => &mut e.common,
+            EntityType::AttributeEntity(e) => &mut e.common,
+            EntityType::Leader(e) => &mut e.common,
+            EntityType::MultiLeader(e) => &mut e.common,
+            EntityType::MLine(e) => &mut e.common,
+            EntityType::Mesh(e) => &mut e.common,
+            EntityType::RasterImage(e) => &mut e.common,
+            EntityType::Solid3D(e) => &mut e.common,
+            EntityType::Region(e) => &mut e.common,
+            EntityType::Body(e) => &mut e.common,
+            EntityType::Table(e) => &mut e.common,
+            EntityType::Tolerance(e) => &mut e.common,
+            EntityType::PolyfaceMesh(e) => &mut e.common,
+            EntityType::Wipeout(e) => &mut e.common,
+            EntityType::Shape(e) => &mut e.common,
+            EntityType::Underlay(e) => &mut e.common,
+            EntityType::Seqend(e) => &mut e.common,
+            EntityType::Ole2Frame(e) => &mut e.common,
+            EntityType::PolygonMesh(e) => &mut e.common,
+            EntityType::Unknown(e) => &mut e.common,
+        }
+    }
+
+    /// Get the DXF entity type name (e.g. "LWPOLYLINE")
+    pub fn dxf_name(&self) -> &str {
+        match self {
+            EntityType::Point(_) => "POINT",
+            EntityType::Line(_) => "LINE",
+            EntityType::Circle(_) => "CIRCLE",
+            EntityType::Arc(_) => "ARC",
+            EntityType::Ellipse(_) => "ELLIPSE",
+            EntityType::Polyline(_)
+            | EntityType::Polyline2D(_)
+            | EntityType::Polyline3D(_)
+            | EntityType::PolyfaceMesh(_)
+            | EntityType::PolygonMesh(_) => "POLYLINE",
+            EntityType::LwPolyline(_) => "LWPOLYLINE",
+            EntityType::Text(_) => "TEXT",
+            EntityType::MText(_) => "MTEXT",
+            EntityType::Spline(_) => "SPLINE",
+            EntityType::Dimension(_) => "DIMENSION",
+            EntityType::Hatch(_) => "HATCH",
+            EntityType::Solid(_) => "SOLID",
+            EntityType::Face3D(_) => "3DFACE",
+            EntityType::Insert(_) => "INSERT",
+            EntityType::Block(_) => "BLOCK",
+            EntityType::BlockEnd(_) => "ENDBLK",
+            EntityType::Ray(_) => "RAY",
+            EntityType::XLine(_) => "XLINE",
+            EntityType::Viewport(_) => "VIEWPORT",
+            EntityType::AttributeDefinition(_) => "ATTDEF",
+            EntityType::AttributeEntity(_) => "ATTRIB",
+            EntityType::Leader(_) => "LEADER",
+            EntityType::MultiLeader(_) => "MULTILEADER",
+            EntityType::MLine(_) => "MLINE",
+            EntityType::Mesh(_) => "MESH",
+            EntityType::RasterImage(_) => "IMAGE",
+            EntityType::Solid3D(_) => "3DSOLID",
+            EntityType::Region(_) => "REGION",
+            EntityType::Body(_) => "BODY",
+            EntityType::Table(_) => "ACAD_TABLE",
+            EntityType::Tolerance(_) => "TOLERANCE",
+            EntityType::Wipeout(_) => "WIPEOUT",
+            EntityType::Shape(_) => "SHAPE",
+            EntityType::Underlay(e) => e.underlay_type.entity_name(),
+            EntityType::Seqend(_) => "SEQEND",
+            EntityType::Ole2Frame(_) => "OLE2FRAME",
+            EntityType::Unknown(e) => &e.dxf_name,
+        }
+    }
 }
 
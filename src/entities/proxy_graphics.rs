@@ -0,0 +1,831 @@
+//! Proxy graphics — the drawable representation stored with custom entities.
+//!
+//! Entities of classes unknown to the reading application (Civil 3D, MEP,
+//! Architecture objects, ACAD_PROXY_ENTITY) carry a "proxy graphics" byte
+//! stream: a list of commands drawing lines, arcs, circles, polygons and
+//! text, interleaved with attribute changes (color, layer, line weight,
+//! fill) and model transforms.
+//!
+//! [`ProxyGraphics`] keeps the raw stream, which is written back unchanged,
+//! together with the primitives decoded from it.
+//!
+//! Stream layout (all values little-endian):
+//!
+//! ```text
+//! i32 total size, i32 command count
+//! per command: i32 size (including this 8-byte header), i32 type, data
+//! ```
+
+use crate::error::{DxfError, Result};
+use crate::types::{BoundingBox3D, Color, LineWeight, Matrix4, Vector3};
+
+/// Command type codes of the proxy graphics stream.
+mod command {
+    pub const EXTENTS: i32 = 1;
+    pub const CIRCLE: i32 = 2;
+    pub const CIRCLE_3P: i32 = 3;
+    pub const CIRCULAR_ARC: i32 = 4;
+    pub const CIRCULAR_ARC_3P: i32 = 5;
+    pub const POLYLINE: i32 = 6;
+    pub const POLYGON: i32 = 7;
+    pub const TEXT: i32 = 10;
+    pub const TEXT2: i32 = 11;
+    pub const SUBENT_COLOR: i32 = 14;
+    pub const SUBENT_LAYER: i32 = 16;
+    pub const SUBENT_FILL_ON: i32 = 20;
+    pub const SUBENT_TRUE_COLOR: i32 = 22;
+    pub const SUBENT_LINEWEIGHT: i32 = 23;
+    pub const PUSH_MODEL_TRANSFORM: i32 = 29;
+    pub const PUSH_MODEL_TRANSFORM2: i32 = 30;
+    pub const POP_MODEL_TRANSFORM: i32 = 31;
+    pub const POLYLINE_WITH_NORMALS: i32 = 32;
+    pub const UNICODE_TEXT: i32 = 36;
+    pub const UNICODE_TEXT2: i32 = 38;
+}
+
+/// Geometry of a single proxy graphics primitive, in WCS.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProxyGraphicGeometry {
+    /// Open polyline; a line is a polyline of two points.
+    Polyline(Vec<Vector3>),
+    /// Closed polygon, filled when [`ProxyGraphicPrimitive::filled`] is set.
+    Polygon(Vec<Vector3>),
+    /// Full circle.
+    Circle {
+        center: Vector3,
+        radius: f64,
+        normal: Vector3,
+    },
+    /// Circular arc starting at `center + start_vector * radius` and
+    /// sweeping counter-clockwise around `normal` (radians).
+    Arc {
+        center: Vector3,
+        radius: f64,
+        normal: Vector3,
+        start_vector: Vector3,
+        sweep_angle: f64,
+    },
+    /// Single line text.
+    Text {
+        insertion_point: Vector3,
+        normal: Vector3,
+        direction: Vector3,
+        height: f64,
+        width_factor: f64,
+        oblique_angle: f64,
+        value: String,
+    },
+}
+
+/// A decoded primitive with the attributes active when it was drawn.
+///
+/// Attributes left at `None` inherit the value of the owning entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProxyGraphicPrimitive {
+    /// Primitive geometry.
+    pub geometry: ProxyGraphicGeometry,
+    /// Color set by the stream, if any.
+    pub color: Option<Color>,
+    /// Index of the layer set by the stream, if any.
+    pub layer_index: Option<u32>,
+    /// Line weight set by the stream, if any.
+    pub line_weight: Option<LineWeight>,
+    /// Whether polygons are filled.
+    pub filled: bool,
+}
+
+impl ProxyGraphicPrimitive {
+    /// Create a primitive inheriting every attribute from its entity.
+    pub fn new(geometry: ProxyGraphicGeometry) -> Self {
+        Self {
+            geometry,
+            color: None,
+            layer_index: None,
+            line_weight: None,
+            filled: false,
+        }
+    }
+}
+
+/// Proxy graphics of a custom entity.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProxyGraphics {
+    /// Raw proxy graphics stream, written back unchanged.
+    pub data: Vec<u8>,
+    /// Primitives decoded from `data`. Commands without a drawable
+    /// equivalent here (meshes, shells, clipping) are skipped.
+    pub primitives: Vec<ProxyGraphicPrimitive>,
+}
+
+impl ProxyGraphics {
+    /// Decode a proxy graphics stream.
+    pub fn from_data(data: Vec<u8>) -> Result<Self> {
+        let primitives = decode(&data)?;
+        Ok(Self { data, primitives })
+    }
+
+    /// Keep a stream without decoding it, e.g. one that failed to decode,
+    /// so it is still written back unchanged.
+    pub fn undecoded(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            primitives: Vec::new(),
+        }
+    }
+
+    /// Build a proxy graphics stream drawing the given primitives.
+    ///
+    /// The stream cannot reset an attribute to the entity's value: once
+    /// set, an attribute stays in effect until a later primitive sets
+    /// another value.
+    pub fn from_primitives(primitives: Vec<ProxyGraphicPrimitive>) -> Self {
+        let data = encode(&primitives);
+        Self { data, primitives }
+    }
+
+    /// Whether no primitives were decoded.
+    pub fn is_empty(&self) -> bool {
+        self.primitives.is_empty()
+    }
+
+    /// Bounding box of the decoded primitives.
+    pub fn bounding_box(&self) -> Option<BoundingBox3D> {
+        primitives_bounds(&self.primitives)
+    }
+}
+
+fn primitives_bounds(primitives: &[ProxyGraphicPrimitive]) -> Option<BoundingBox3D> {
+    let mut points = Vec::new();
+    for primitive in primitives {
+        match &primitive.geometry {
+            ProxyGraphicGeometry::Polyline(p) | ProxyGraphicGeometry::Polygon(p) => {
+                points.extend_from_slice(p)
+            }
+            ProxyGraphicGeometry::Circle { center, radius, .. }
+            | ProxyGraphicGeometry::Arc { center, radius, .. } => {
+                let r = Vector3::new(*radius, *radius, *radius);
+                points.push(*center - r);
+                points.push(*center + r);
+            }
+            ProxyGraphicGeometry::Text { insertion_point, .. } => points.push(*insertion_point),
+        }
+    }
+    BoundingBox3D::from_points(&points)
+}
+
+// ── Decoding ──────────────────────────────────────────────────────────────
+
+/// Little-endian reader over the data of one command.
+struct CommandReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> CommandReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8]> {
+        let end = self.position + count;
+        let bytes = self.data.get(self.position..end).ok_or_else(|| {
+            DxfError::InvalidFormat("Proxy graphics command is truncated".into())
+        })?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn read_i32(&mut self) -> Result<i32> {
+        let b = self.take(4)?;
+        Ok(i32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        let mut b = [0u8; 8];
+        b.copy_from_slice(self.take(8)?);
+        Ok(f64::from_le_bytes(b))
+    }
+
+    fn read_vertex(&mut self) -> Result<Vector3> {
+        Ok(Vector3::new(self.read_f64()?, self.read_f64()?, self.read_f64()?))
+    }
+
+    fn read_count(&mut self) -> Result<usize> {
+        let count = self.read_i32()?;
+        if count < 0 || count as usize > self.data.len() {
+            return Err(DxfError::InvalidFormat(format!(
+                "Invalid proxy graphics vertex count {}",
+                count
+            )));
+        }
+        Ok(count as usize)
+    }
+
+    fn read_vertices(&mut self) -> Result<Vec<Vector3>> {
+        let count = self.read_count()?;
+        (0..count).map(|_| self.read_vertex()).collect()
+    }
+
+    fn align(&mut self) {
+        self.position = (self.position + 3) & !3;
+    }
+
+    /// Null-terminated 8-bit string padded to 4 bytes.
+    fn read_padded_string(&mut self) -> Result<String> {
+        let rest = &self.data[self.position.min(self.data.len())..];
+        let length = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        let value = match std::str::from_utf8(&rest[..length]) {
+            Ok(s) => s.to_string(),
+            Err(_) => rest[..length].iter().map(|&b| b as char).collect(),
+        };
+        self.position += length + 1;
+        self.align();
+        Ok(value)
+    }
+
+    /// Null-terminated UTF-16 string padded to 4 bytes.
+    fn read_padded_unicode_string(&mut self) -> Result<String> {
+        let mut units = Vec::new();
+        loop {
+            let b = self.take(2)?;
+            let unit = u16::from_le_bytes([b[0], b[1]]);
+            if unit == 0 {
+                break;
+            }
+            units.push(unit);
+        }
+        self.align();
+        Ok(String::from_utf16_lossy(&units))
+    }
+}
+
+/// Attribute state and transform stack while walking the commands.
+struct DecodeState {
+    color: Option<Color>,
+    layer_index: Option<u32>,
+    line_weight: Option<LineWeight>,
+    filled: bool,
+    transforms: Vec<Matrix4>,
+    primitives: Vec<ProxyGraphicPrimitive>,
+}
+
+impl DecodeState {
+    fn push(&mut self, geometry: ProxyGraphicGeometry) {
+        let geometry = match self.transforms.last() {
+            Some(matrix) => transform_geometry(geometry, matrix),
+            None => geometry,
+        };
+        self.primitives.push(ProxyGraphicPrimitive {
+            geometry,
+            color: self.color,
+            layer_index: self.layer_index,
+            line_weight: self.line_weight,
+            filled: self.filled,
+        });
+    }
+}
+
+/// Decode the primitives of a proxy graphics stream.
+fn decode(data: &[u8]) -> Result<Vec<ProxyGraphicPrimitive>> {
+    let mut header = CommandReader::new(data);
+    let _total_size = header.read_i32()?;
+    let count = header.read_i32()?;
+
+    let mut state = DecodeState {
+        color: None,
+        layer_index: None,
+        line_weight: None,
+        filled: false,
+        transforms: Vec::new(),
+        primitives: Vec::new(),
+    };
+
+    let mut offset = 8usize;
+    for _ in 0..count.max(0) {
+        if offset + 8 > data.len() {
+            break;
+        }
+        let mut command_header = CommandReader::new(&data[offset..offset + 8]);
+        let size = command_header.read_i32()?;
+        let command_type = command_header.read_i32()?;
+        if size < 8 || offset + size as usize > data.len() {
+            return Err(DxfError::InvalidFormat(format!(
+                "Invalid size {} of proxy graphics command {} at offset {}",
+                size, command_type, offset
+            )));
+        }
+
+        let mut reader = CommandReader::new(&data[offset + 8..offset + size as usize]);
+        decode_command(command_type, &mut reader, &mut state)?;
+        offset += size as usize;
+    }
+
+    Ok(state.primitives)
+}
+
+fn decode_command(
+    command_type: i32,
+    reader: &mut CommandReader,
+    state: &mut DecodeState,
+) -> Result<()> {
+    match command_type {
+        command::CIRCLE => {
+            let center = reader.read_vertex()?;
+            let radius = reader.read_f64()?;
+            let normal = reader.read_vertex()?;
+            state.push(ProxyGraphicGeometry::Circle { center, radius, normal });
+        }
+        command::CIRCLE_3P => {
+            let (p1, p2, p3) = (reader.read_vertex()?, reader.read_vertex()?, reader.read_vertex()?);
+            if let Some((center, radius, normal)) = circle_through(p1, p2, p3) {
+                state.push(ProxyGraphicGeometry::Circle { center, radius, normal });
+            }
+        }
+        command::CIRCULAR_ARC => {
+            let center = reader.read_vertex()?;
+            let radius = reader.read_f64()?;
+            let normal = reader.read_vertex()?;
+            let start_vector = reader.read_vertex()?;
+            let sweep_angle = reader.read_f64()?;
+            state.push(ProxyGraphicGeometry::Arc {
+                center,
+                radius,
+                normal,
+                start_vector: start_vector.normalize(),
+                sweep_angle,
+            });
+        }
+        command::CIRCULAR_ARC_3P => {
+            let (p1, p2, p3) = (reader.read_vertex()?, reader.read_vertex()?, reader.read_vertex()?);
+            if let Some((center, radius, normal)) = circle_through(p1, p2, p3) {
+                let start = (p1 - center).normalize();
+                let end = (p3 - center).normalize();
+                let mut sweep_angle = start.cross(&end).dot(&normal).atan2(start.dot(&end));
+                if sweep_angle < 0.0 {
+                    sweep_angle += std::f64::consts::TAU;
+                }
+                state.push(ProxyGraphicGeometry::Arc {
+                    center,
+                    radius,
+                    normal,
+                    start_vector: start,
+                    sweep_angle,
+                });
+            }
+        }
+        command::POLYLINE | command::POLYLINE_WITH_NORMALS => {
+            let points = reader.read_vertices()?;
+            state.push(ProxyGraphicGeometry::Polyline(points));
+        }
+        command::POLYGON => {
+            let points = reader.read_vertices()?;
+            state.push(ProxyGraphicGeometry::Polygon(points));
+        }
+        command::TEXT | command::UNICODE_TEXT => {
+            let insertion_point = reader.read_vertex()?;
+            let normal = reader.read_vertex()?;
+            let direction = reader.read_vertex()?;
+            let height = reader.read_f64()?;
+            let width_factor = reader.read_f64()?;
+            let oblique_angle = reader.read_f64()?;
+            let value = if command_type == command::UNICODE_TEXT {
+                reader.read_padded_unicode_string()?
+            } else {
+                reader.read_padded_string()?
+            };
+            state.push(ProxyGraphicGeometry::Text {
+                insertion_point,
+                normal,
+                direction,
+                height,
+                width_factor,
+                oblique_angle,
+                value,
+            });
+        }
+        command::TEXT2 | command::UNICODE_TEXT2 => {
+            let insertion_point = reader.read_vertex()?;
+            let normal = reader.read_vertex()?;
+            let direction = reader.read_vertex()?;
+            let value = if command_type == command::UNICODE_TEXT2 {
+                reader.read_padded_unicode_string()?
+            } else {
+                reader.read_padded_string()?
+            };
+            // Ignore length of string, raw flag
+            reader.read_i32()?;
+            reader.read_i32()?;
+            let height = reader.read_f64()?;
+            let width_factor = reader.read_f64()?;
+            let oblique_angle = reader.read_f64()?;
+            state.push(ProxyGraphicGeometry::Text {
+                insertion_point,
+                normal,
+                direction,
+                height,
+                width_factor,
+                oblique_angle,
+                value,
+            });
+        }
+        command::SUBENT_COLOR => {
+            let index = reader.read_i32()?;
+            state.color = Some(Color::from_index(index.clamp(0, 256) as i16));
+        }
+        command::SUBENT_TRUE_COLOR => {
+            let value = reader.read_i32()? as u32;
+            state.color = Some(match value >> 24 {
+                0xC0 => Color::ByLayer,
+                0xC1 => Color::ByBlock,
+                0xC3 => Color::from_index((value & 0xFF) as i16),
+                _ => Color::from_rgb((value >> 16) as u8, (value >> 8) as u8, value as u8),
+            });
+        }
+        command::SUBENT_LAYER => {
+            state.layer_index = Some(reader.read_i32()? as u32);
+        }
+        command::SUBENT_LINEWEIGHT => {
+            state.line_weight = Some(LineWeight::from_value(reader.read_i32()? as i16));
+        }
+        command::SUBENT_FILL_ON => {
+            state.filled = reader.read_i32()? != 0;
+        }
+        command::PUSH_MODEL_TRANSFORM | command::PUSH_MODEL_TRANSFORM2 => {
+            let mut matrix = Matrix4::zero();
+            for row in matrix.m.iter_mut() {
+                for value in row.iter_mut() {
+                    *value = reader.read_f64()?;
+                }
+            }
+            let matrix = match state.transforms.last() {
+                Some(current) => *current * matrix,
+                None => matrix,
+            };
+            state.transforms.push(matrix);
+        }
+        command::POP_MODEL_TRANSFORM => {
+            state.transforms.pop();
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Circle through three points as (center, radius, normal).
+fn circle_through(p1: Vector3, p2: Vector3, p3: Vector3) -> Option<(Vector3, f64, Vector3)> {
+    let a = p2 - p1;
+    let b = p3 - p1;
+    let n = a.cross(&b);
+    let n2 = n.length_squared();
+    if n2 < 1e-20 {
+        return None;
+    }
+    let offset = (n.cross(&a) * b.length_squared() + b.cross(&n) * a.length_squared()) * (0.5 / n2);
+    let center = p1 + offset;
+    Some((center, offset.length(), n.normalize()))
+}
+
+fn transform_geometry(geometry: ProxyGraphicGeometry, matrix: &Matrix4) -> ProxyGraphicGeometry {
+    let point = |p: Vector3| matrix.transform_point(p);
+    let direction = |d: Vector3| matrix.transform_direction(d);
+    let unit = |d: Vector3| matrix.transform_direction(d).normalize();
+
+    match geometry {
+        ProxyGraphicGeometry::Polyline(points) => {
+            ProxyGraphicGeometry::Polyline(points.into_iter().map(point).collect())
+        }
+        ProxyGraphicGeometry::Polygon(points) => {
+            ProxyGraphicGeometry::Polygon(points.into_iter().map(point).collect())
+        }
+        ProxyGraphicGeometry::Circle { center, radius, normal } => {
+            let scale = direction(any_perpendicular(normal)).length();
+            ProxyGraphicGeometry::Circle {
+                center: point(center),
+                radius: radius * scale,
+                normal: unit(normal),
+            }
+        }
+        ProxyGraphicGeometry::Arc { center, radius, normal, start_vector, sweep_angle } => {
+            let scale = direction(start_vector).length();
+            ProxyGraphicGeometry::Arc {
+                center: point(center),
+                radius: radius * scale,
+                normal: unit(normal),
+                start_vector: unit(start_vector),
+                sweep_angle,
+            }
+        }
+        ProxyGraphicGeometry::Text {
+            insertion_point,
+            normal,
+            direction: text_direction,
+            height,
+            width_factor,
+            oblique_angle,
+            value,
+        } => {
+            let scale = direction(text_direction.normalize()).length();
+            ProxyGraphicGeometry::Text {
+                insertion_point: point(insertion_point),
+                normal: unit(normal),
+                direction: unit(text_direction),
+                height: height * scale,
+                width_factor,
+                oblique_angle,
+                value,
+            }
+        }
+    }
+}
+
+fn any_perpendicular(normal: Vector3) -> Vector3 {
+    let candidate = if normal.x.abs() < 0.9 {
+        Vector3::new(1.0, 0.0, 0.0)
+    } else {
+        Vector3::new(0.0, 1.0, 0.0)
+    };
+    normal.cross(&candidate).normalize()
+}
+
+// ── Encoding ──────────────────────────────────────────────────────────────
+
+/// Little-endian writer for the data of one command.
+#[derive(Default)]
+struct CommandWriter {
+    data: Vec<u8>,
+}
+
+impl CommandWriter {
+    fn write_i32(&mut self, value: i32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_f64(&mut self, value: f64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_vertex(&mut self, v: Vector3) {
+        self.write_f64(v.x);
+        self.write_f64(v.y);
+        self.write_f64(v.z);
+    }
+
+    fn write_vertices(&mut self, points: &[Vector3]) {
+        self.write_i32(points.len() as i32);
+        for p in points {
+            self.write_vertex(*p);
+        }
+    }
+
+    fn align(&mut self) {
+        while !self.data.len().is_multiple_of(4) {
+            self.data.push(0);
+        }
+    }
+
+    fn write_padded_unicode_string(&mut self, value: &str) {
+        for unit in value.encode_utf16().chain(std::iter::once(0)) {
+            self.data.extend_from_slice(&unit.to_le_bytes());
+        }
+        self.align();
+    }
+}
+
+/// Encode primitives as a proxy graphics stream, emitting attribute
+/// commands whenever they change.
+fn encode(primitives: &[ProxyGraphicPrimitive]) -> Vec<u8> {
+    let mut commands: Vec<(i32, CommandWriter)> = Vec::new();
+
+    if let Some(bounds) = primitives_bounds(primitives) {
+        let mut w = CommandWriter::default();
+        w.write_vertex(bounds.min);
+        w.write_vertex(bounds.max);
+        commands.push((command::EXTENTS, w));
+    }
+
+    let mut color = None;
+    let mut layer_index = None;
+    let mut line_weight = None;
+    let mut filled = false;
+
+    for primitive in primitives {
+        if primitive.color != color {
+            if let Some(c) = primitive.color {
+                let mut w = CommandWriter::default();
+                match c {
+                    Color::Rgb { r, g, b } => {
+                        w.write_i32((0xC2u32 << 24 | (r as u32) << 16 | (g as u32) << 8 | b as u32) as i32);
+                        commands.push((command::SUBENT_TRUE_COLOR, w));
+                    }
+                    _ => {
+                        w.write_i32(c.index().unwrap_or(256) as i32);
+                        commands.push((command::SUBENT_COLOR, w));
+                    }
+                }
+            }
+            color = primitive.color;
+        }
+        if primitive.layer_index != layer_index {
+            if let Some(index) = primitive.layer_index {
+                let mut w = CommandWriter::default();
+                w.write_i32(index as i32);
+                commands.push((command::SUBENT_LAYER, w));
+            }
+            layer_index = primitive.layer_index;
+        }
+        if primitive.line_weight != line_weight {
+            if let Some(weight) = primitive.line_weight {
+                let mut w = CommandWriter::default();
+                w.write_i32(weight.value() as i32);
+                commands.push((command::SUBENT_LINEWEIGHT, w));
+            }
+            line_weight = primitive.line_weight;
+        }
+        if primitive.filled != filled {
+            let mut w = CommandWriter::default();
+            w.write_i32(primitive.filled as i32);
+            commands.push((command::SUBENT_FILL_ON, w));
+            filled = primitive.filled;
+        }
+
+        let mut w = CommandWriter::default();
+        let command_type = match &primitive.geometry {
+            ProxyGraphicGeometry::Polyline(points) => {
+                w.write_vertices(points);
+                command::POLYLINE
+            }
+            ProxyGraphicGeometry::Polygon(points) => {
+                w.write_vertices(points);
+                command::POLYGON
+            }
+            ProxyGraphicGeometry::Circle { center, radius, normal } => {
+                w.write_vertex(*center);
+                w.write_f64(*radius);
+                w.write_vertex(*normal);
+                command::CIRCLE
+            }
+            ProxyGraphicGeometry::Arc { center, radius, normal, start_vector, sweep_angle } => {
+                w.write_vertex(*center);
+                w.write_f64(*radius);
+                w.write_vertex(*normal);
+                w.write_vertex(*start_vector);
+                w.write_f64(*sweep_angle);
+                // Arc type: open arc
+                w.write_i32(0);
+                command::CIRCULAR_ARC
+            }
+            ProxyGraphicGeometry::Text {
+                insertion_point,
+                normal,
+                direction,
+                height,
+                width_factor,
+                oblique_angle,
+                value,
+            } => {
+                w.write_vertex(*insertion_point);
+                w.write_vertex(*normal);
+                w.write_vertex(*direction);
+                w.write_f64(*height);
+                w.write_f64(*width_factor);
+                w.write_f64(*oblique_angle);
+                w.write_padded_unicode_string(value);
+                command::UNICODE_TEXT
+            }
+        };
+        commands.push((command_type, w));
+    }
+
+    let total: usize = 8 + commands.iter().map(|(_, w)| 8 + w.data.len()).sum::<usize>();
+    let mut data = Vec::with_capacity(total);
+    data.extend_from_slice(&(total as i32).to_le_bytes());
+    data.extend_from_slice(&(commands.len() as i32).to_le_bytes());
+    for (command_type, w) in commands {
+        data.extend_from_slice(&((8 + w.data.len()) as i32).to_le_bytes());
+        data.extend_from_slice(&command_type.to_le_bytes());
+        data.extend_from_slice(&w.data);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_primitives() -> Vec<ProxyGraphicPrimitive> {
+        let mut line = ProxyGraphicPrimitive::new(ProxyGraphicGeometry::Polyline(vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 5.0, 0.0),
+        ]));
+        line.color = Some(Color::Index(1));
+
+        let circle = ProxyGraphicPrimitive {
+            geometry: ProxyGraphicGeometry::Circle {
+                center: Vector3::new(5.0, 5.0, 0.0),
+                radius: 2.5,
+                normal: Vector3::new(0.0, 0.0, 1.0),
+            },
+            layer_index: Some(2),
+            ..line.clone()
+        };
+
+        let mut fill = ProxyGraphicPrimitive::new(ProxyGraphicGeometry::Polygon(vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        ]));
+        fill.filled = true;
+        fill.layer_index = Some(3);
+        fill.color = Some(Color::from_rgb(10, 20, 30));
+        fill.line_weight = Some(LineWeight::Value(35));
+
+        let text = ProxyGraphicPrimitive {
+            geometry: ProxyGraphicGeometry::Text {
+                insertion_point: Vector3::new(1.0, 2.0, 0.0),
+                normal: Vector3::new(0.0, 0.0, 1.0),
+                direction: Vector3::new(1.0, 0.0, 0.0),
+                height: 2.5,
+                width_factor: 1.0,
+                oblique_angle: 0.0,
+                value: "Pipe Ø100".to_string(),
+            },
+            ..fill.clone()
+        };
+
+        vec![line, circle, fill, text]
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let graphics = ProxyGraphics::from_primitives(sample_primitives());
+        let decoded = ProxyGraphics::from_data(graphics.data.clone()).unwrap();
+        assert_eq!(decoded.primitives, sample_primitives());
+    }
+
+    #[test]
+    fn test_decode_arc_and_transform() {
+        let mut w = CommandWriter::default();
+        // Translate by (100, 0, 0), scale by 2
+        let m = [
+            [2.0, 0.0, 0.0, 100.0],
+            [0.0, 2.0, 0.0, 0.0],
+            [0.0, 0.0, 2.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        for row in m {
+            for v in row {
+                w.write_f64(v);
+            }
+        }
+        let mut arc = CommandWriter::default();
+        arc.write_vertex(Vector3::new(1.0, 0.0, 0.0));
+        arc.write_f64(1.0);
+        arc.write_vertex(Vector3::new(0.0, 0.0, 1.0));
+        arc.write_vertex(Vector3::new(1.0, 0.0, 0.0));
+        arc.write_f64(std::f64::consts::PI);
+        arc.write_i32(0);
+
+        let mut data = Vec::new();
+        let commands = [
+            (command::PUSH_MODEL_TRANSFORM, w.data),
+            (command::CIRCULAR_ARC, arc.data),
+            (command::POP_MODEL_TRANSFORM, Vec::new()),
+        ];
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&(commands.len() as i32).to_le_bytes());
+        for (t, d) in commands {
+            data.extend_from_slice(&((8 + d.len()) as i32).to_le_bytes());
+            data.extend_from_slice(&t.to_le_bytes());
+            data.extend_from_slice(&d);
+        }
+
+        let graphics = ProxyGraphics::from_data(data).unwrap();
+        match &graphics.primitives[0].geometry {
+            ProxyGraphicGeometry::Arc { center, radius, .. } => {
+                assert_eq!(*center, Vector3::new(102.0, 0.0, 0.0));
+                assert_eq!(*radius, 2.0);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_circle_through_three_points() {
+        let (center, radius, normal) = circle_through(
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(-1.0, 0.0, 0.0),
+        )
+        .unwrap();
+        assert!((center - Vector3::ZERO).length() < 1e-12);
+        assert!((radius - 1.0).abs() < 1e-12);
+        assert_eq!(normal, Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_truncated_stream_is_an_error() {
+        let graphics = ProxyGraphics::from_primitives(sample_primitives());
+        let truncated = graphics.data[..graphics.data.len() - 10].to_vec();
+        assert!(ProxyGraphics::from_data(truncated).is_err());
+    }
+}
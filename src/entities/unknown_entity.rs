@@ -1,26 +1,33 @@
 //! Unknown entity type for round-trip preservation.
 //!
 //! When the reader encounters an entity type that is not directly supported,
-//! it can still capture the common entity properties (handle, layer, color, …)
-//! wrapped in this type.  The entity-specific codes are discarded — this
-//! matches the ACadSharp `UnknownEntity` behavior.
+//! it still captures the common entity properties (handle, layer, color, …)
+//! wrapped in this type.  The codes of the entity's own subclasses are kept
+//! as read in `common.unknown_codes`, and its proxy graphics, if any, are
+//! decoded into drawable primitives.
 //!
-//! Unknown entities are **never written back** to DXF output, following the
-//! same convention as ACadSharp.
+//! DXF output writes an unknown entity back with its kept codes.  One
+//! without kept codes is written as `ACAD_PROXY_ENTITY` when it carries
+//! proxy graphics, so the drawing keeps its visible geometry, and skipped
+//! otherwise.
 
+use crate::entities::proxy_graphics::ProxyGraphics;
 use crate::entities::{Entity, EntityCommon};
 use crate::types::{BoundingBox3D, Color, Handle, LineWeight, Transform, Transparency, Vector3};
 
 /// An entity whose type is not directly supported by the library.
 ///
-/// Preserves the DXF type name (e.g. `"ACAD_PROXY_ENTITY"`) and common entity
-/// properties.  Entity-specific codes are discarded.
+/// Preserves the DXF type name (e.g. `"ACAD_PROXY_ENTITY"`), the common
+/// entity properties and, in `common.unknown_codes`, the entity-specific
+/// codes as read.
 #[derive(Debug, Clone)]
 pub struct UnknownEntity {
     /// Common entity data (handle, layer, color, reactors, …).
     pub common: EntityCommon,
     /// The DXF type name as it appeared in the file (e.g. `"ACAD_PROXY_ENTITY"`).
     pub dxf_name: String,
+    /// Proxy graphics drawn in place of the entity, if the file had any.
+    pub proxy_graphics: Option<ProxyGraphics>,
 }
 
 impl UnknownEntity {
@@ -29,6 +36,7 @@ impl UnknownEntity {
         Self {
             common: EntityCommon::new(),
             dxf_name: dxf_name.into(),
+            proxy_graphics: None,
         }
     }
 }
@@ -46,7 +54,12 @@ impl Entity for UnknownEntity {
     fn set_transparency(&mut self, transparency: Transparency) { self.common.transparency = transparency; }
     fn is_invisible(&self) -> bool { self.common.invisible }
     fn set_invisible(&mut self, invisible: bool) { self.common.invisible = invisible; }
-    fn bounding_box(&self) -> BoundingBox3D { BoundingBox3D::from_point(Vector3::ZERO) }
+    fn bounding_box(&self) -> BoundingBox3D {
+        self.proxy_graphics
+            .as_ref()
+            .and_then(|g| g.bounding_box())
+            .unwrap_or_else(|| BoundingBox3D::from_point(Vector3::ZERO))
+    }
     fn translate(&mut self, _offset: Vector3) { /* no geometry */ }
     fn entity_type(&self) -> &'static str { "UNKNOWN" }
     fn apply_transform(&mut self, _transform: &Transform) { /* no geometry */ }
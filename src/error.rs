@@ -1,5 +1,7 @@
 //! Error types for acadrust library
 
+use crate::types::Handle;
+use std::fmt;
 use std::io;
 use thiserror::Error;
 
@@ -40,7 +42,7 @@ pub enum DxfError {
 
     /// CRC checksum mismatch
     #[error("CRC checksum mismatch: expected {expected:#X}, got {actual:#X}")]
-    ChecksumMismatch { expected: u32, actual: u32 },
+    ChecksumMismatch { expected: u64, actual: u64 },
 
     /// Invalid file header
     #[error("Invalid file header: {0}")]
@@ -73,6 +75,109 @@ pub enum DxfError {
     /// Generic error with custom message
     #[error("{0}")]
     Custom(String),
+
+    /// Error in a DXF file, with the place it was found
+    #[error("{source} at {location}")]
+    Located {
+        /// The error
+        source: Box<DxfError>,
+        /// Where in the file it was found
+        location: Box<DxfLocation>,
+    },
+}
+
+impl DxfError {
+    /// Attach the place in a DXF file the error was found, unless it has
+    /// one. I/O errors are about the stream rather than its content and are
+    /// returned as they are.
+    pub fn at(self, location: DxfLocation) -> Self {
+        match self {
+            DxfError::Located { .. } | DxfError::Io(_) => self,
+            error => DxfError::Located {
+                source: Box::new(error),
+                location: Box::new(location),
+            },
+        }
+    }
+
+    /// Place in a DXF file the error was found, if known
+    pub fn location(&self) -> Option<&DxfLocation> {
+        match self {
+            DxfError::Located { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Split the error from its location
+    pub fn into_parts(self) -> (DxfError, Option<DxfLocation>) {
+        match self {
+            DxfError::Located { source, location } => (*source, Some(*location)),
+            error => (error, None),
+        }
+    }
+}
+
+/// Where a group code was read in a DXF file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DxfPosition {
+    /// Line of the group code in an ASCII file, from 1
+    Line(usize),
+    /// Byte offset of the group code in a binary file
+    Offset(u64),
+}
+
+impl fmt::Display for DxfPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DxfPosition::Line(line) => write!(f, "line {}", line),
+            DxfPosition::Offset(offset) => write!(f, "offset {:#X}", offset),
+        }
+    }
+}
+
+/// Place in a DXF file: the group code read last and the record it belongs to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DxfLocation {
+    /// Position of the group code
+    pub position: Option<DxfPosition>,
+    /// Section being read
+    pub section: Option<String>,
+    /// Type of the record being read: entity, object or table entry type,
+    /// or the variable name in the HEADER section
+    pub entity_type: Option<String>,
+    /// Handle of the record being read
+    pub handle: Option<Handle>,
+    /// Group code
+    pub code: Option<i32>,
+    /// Value of the group code, as text
+    pub value: Option<String>,
+}
+
+impl fmt::Display for DxfLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(position) = self.position {
+            parts.push(position.to_string());
+        }
+        if let Some(section) = &self.section {
+            parts.push(format!("section {}", section));
+        }
+        match (&self.entity_type, self.handle) {
+            (Some(entity_type), Some(handle)) => parts.push(format!("{} {:X}", entity_type, handle.value())),
+            (Some(entity_type), None) => parts.push(entity_type.clone()),
+            (None, Some(handle)) => parts.push(format!("handle {:X}", handle.value())),
+            (None, None) => {}
+        }
+        match (self.code, &self.value) {
+            (Some(code), Some(value)) => parts.push(format!("code {} = {:?}", code, value)),
+            (Some(code), None) => parts.push(format!("code {}", code)),
+            _ => {}
+        }
+        if parts.is_empty() {
+            return write!(f, "unknown location");
+        }
+        write!(f, "{}", parts.join(", "))
+    }
 }
 
 /// Result type alias for acadrust operations
@@ -113,6 +218,30 @@ mod tests {
         assert!(err.to_string().contains("0x5678"));
     }
 
+    #[test]
+    fn test_located_error() {
+        let location = DxfLocation {
+            position: Some(DxfPosition::Line(42)),
+            section: Some("ENTITIES".to_string()),
+            entity_type: Some("LINE".to_string()),
+            handle: Some(Handle::new(0x2A)),
+            code: Some(10),
+            value: Some("abc".to_string()),
+        };
+        let err = DxfError::Parse("bad value".to_string()).at(location.clone());
+        assert_eq!(
+            err.to_string(),
+            "Parse error: bad value at line 42, section ENTITIES, LINE 2A, code 10 = \"abc\""
+        );
+        assert_eq!(err.location(), Some(&location));
+
+        // The first location is kept
+        let err = err.at(DxfLocation::default());
+        assert_eq!(err.location(), Some(&location));
+        let (err, _) = err.into_parts();
+        assert!(matches!(err, DxfError::Parse(_)));
+    }
+
     #[test]
     fn test_io_error_conversion() {
         let io_err = io::Error::new(io::ErrorKind::NotFound, "file not found");
@@ -0,0 +1,667 @@
+//! Built-in entity converters
+
+use crate::document::CadDocument;
+use crate::entities::{
+    AttachmentPoint, Ellipse, EntityCommon, EntityType, Insert, Leader, LeaderContentType,
+    LeaderPathType, Line, LwPolyline, Mesh, MText, MultiLeader, MultiLeaderPathType,
+    PolyfaceFace, PolyfaceMesh, PolyfaceVertex, Polyline2D, Polyline3D, Spline, Table, Text,
+    TextAttachmentPointType, TextHorizontalAlignment, TextVerticalAlignment, Vertex2D,
+};
+use crate::tables::BlockRecord;
+use crate::types::{DxfVersion, Handle, Vector3};
+
+use super::EntityConverter;
+
+/// Segments used to approximate a full ellipse
+const ELLIPSE_SEGMENTS: usize = 64;
+
+/// Segments per control point used to approximate a spline
+const SPLINE_SEGMENTS_PER_POINT: usize = 8;
+
+/// AutoCAD's default MTEXT line spacing as a multiple of the text height
+const MTEXT_LINE_SPACING: f64 = 5.0 / 3.0;
+
+/// LWPOLYLINE as a POLYLINE with 2D vertices
+pub struct LwPolylineConverter;
+
+impl EntityConverter for LwPolylineConverter {
+    fn convert(&self, entity: &EntityType, _: DxfVersion, _: &mut CadDocument) -> Option<Vec<EntityType>> {
+        match entity {
+            EntityType::LwPolyline(lwpoly) => Some(vec![lwpolyline_to_polyline(lwpoly)]),
+            _ => None,
+        }
+    }
+}
+
+/// ELLIPSE, SPLINE and LEADER exploded into a polyline
+pub struct CurveConverter;
+
+impl EntityConverter for CurveConverter {
+    fn convert(&self, entity: &EntityType, _: DxfVersion, _: &mut CadDocument) -> Option<Vec<EntityType>> {
+        let polyline = match entity {
+            EntityType::Ellipse(ellipse) => {
+                let (points, closed) = ellipse_points(ellipse);
+                polyline_through(&ellipse.common, points, closed)
+            }
+            EntityType::Spline(spline) => {
+                polyline_through(&spline.common, spline_points(spline), spline.flags.closed)
+            }
+            EntityType::Leader(leader) if leader.vertices.len() >= 2 => {
+                polyline_through(&leader.common, leader.vertices.clone(), false)
+            }
+            _ => return None,
+        };
+        Some(vec![polyline])
+    }
+}
+
+/// MTEXT as one TEXT per paragraph
+pub struct MTextConverter;
+
+impl EntityConverter for MTextConverter {
+    fn convert(&self, entity: &EntityType, _: DxfVersion, _: &mut CadDocument) -> Option<Vec<EntityType>> {
+        match entity {
+            EntityType::MText(mtext) => Some(mtext_to_text(mtext)),
+            _ => None,
+        }
+    }
+}
+
+/// MULTILEADER as one LEADER per leader line, with its content as MTEXT or
+/// INSERT. The LEADERs are annotated by the MTEXT, which keeps the handle.
+pub struct MultiLeaderConverter;
+
+impl EntityConverter for MultiLeaderConverter {
+    fn convert(&self, entity: &EntityType, _: DxfVersion, document: &mut CadDocument) -> Option<Vec<EntityType>> {
+        match entity {
+            EntityType::MultiLeader(mleader) => Some(multileader_to_leaders(mleader, document)),
+            _ => None,
+        }
+    }
+}
+
+/// ACAD_TABLE as an INSERT of an anonymous block holding the grid lines and
+/// cell text. The table's own graphics block is reused when it has content.
+pub struct TableConverter;
+
+impl EntityConverter for TableConverter {
+    fn convert(&self, entity: &EntityType, _: DxfVersion, document: &mut CadDocument) -> Option<Vec<EntityType>> {
+        match entity {
+            EntityType::Table(table) => Some(vec![table_to_insert(table, document)]),
+            _ => None,
+        }
+    }
+}
+
+/// MESH as a polyface mesh; faces with more than four vertices are split
+/// into triangles with invisible inner edges. Subdivision and creases are
+/// lost.
+pub struct MeshConverter;
+
+impl EntityConverter for MeshConverter {
+    fn convert(&self, entity: &EntityType, _: DxfVersion, _: &mut CadDocument) -> Option<Vec<EntityType>> {
+        match entity {
+            EntityType::Mesh(mesh) => mesh_to_polyface(mesh).map(|polyface| vec![polyface]),
+            _ => None,
+        }
+    }
+}
+
+/// Underlays have no older equivalent and are dropped
+pub struct UnderlayConverter;
+
+impl EntityConverter for UnderlayConverter {
+    fn convert(&self, entity: &EntityType, _: DxfVersion, _: &mut CadDocument) -> Option<Vec<EntityType>> {
+        match entity {
+            EntityType::Underlay(_) => Some(Vec::new()),
+            _ => None,
+        }
+    }
+}
+
+fn lwpolyline_to_polyline(lwpoly: &LwPolyline) -> EntityType {
+    let mut polyline = Polyline2D::new();
+    polyline.common = lwpoly.common.clone();
+    polyline.start_width = lwpoly.constant_width;
+    polyline.end_width = lwpoly.constant_width;
+    polyline.thickness = lwpoly.thickness;
+    polyline.elevation = lwpoly.elevation;
+    polyline.normal = lwpoly.normal;
+    for vertex in &lwpoly.vertices {
+        let location = Vector3::new(vertex.location.x, vertex.location.y, lwpoly.elevation);
+        polyline.add_vertex(
+            Vertex2D::new(location)
+                .with_bulge(vertex.bulge)
+                .with_width(vertex.start_width, vertex.end_width),
+        );
+    }
+    if lwpoly.is_closed {
+        polyline.close();
+    }
+    EntityType::Polyline2D(polyline)
+}
+
+/// A 2D polyline when all points share one elevation, otherwise a 3D one
+fn polyline_through(common: &EntityCommon, mut points: Vec<Vector3>, closed: bool) -> EntityType {
+    let common = common.clone();
+    if closed && points.len() > 2 && points[0].distance(&points[points.len() - 1]) < 1e-9 {
+        points.pop();
+    }
+
+    let elevation = points.first().map_or(0.0, |p| p.z);
+    if points.iter().all(|p| (p.z - elevation).abs() < 1e-9) {
+        let mut polyline = Polyline2D::new();
+        polyline.common = common;
+        polyline.elevation = elevation;
+        for point in points {
+            polyline.add_vertex(Vertex2D::new(point));
+        }
+        if closed {
+            polyline.close();
+        }
+        EntityType::Polyline2D(polyline)
+    } else {
+        let mut polyline = Polyline3D::from_points(points);
+        for vertex in &mut polyline.vertices {
+            vertex.layer = common.layer.clone();
+        }
+        polyline.common = common;
+        if closed {
+            polyline.close();
+        }
+        EntityType::Polyline3D(polyline)
+    }
+}
+
+fn ellipse_points(ellipse: &Ellipse) -> (Vec<Vector3>, bool) {
+    let normal = ellipse.normal.normalize();
+    let minor = normal.cross(&ellipse.major_axis) * ellipse.minor_axis_ratio;
+    let full = ellipse.is_full();
+
+    let start = ellipse.start_parameter;
+    let mut sweep = ellipse.end_parameter - start;
+    if full || sweep <= 0.0 {
+        sweep += std::f64::consts::TAU;
+    }
+    let segments = ((ELLIPSE_SEGMENTS as f64 * sweep / std::f64::consts::TAU).ceil() as usize).max(4);
+
+    // A full ellipse is closed, so its last point would repeat the first
+    let count = if full { segments } else { segments + 1 };
+    let points = (0..count)
+        .map(|i| {
+            let t = start + sweep * i as f64 / segments as f64;
+            ellipse.center + ellipse.major_axis * t.cos() + minor * t.sin()
+        })
+        .collect();
+    (points, full)
+}
+
+/// Sample a spline, falling back to its fit or control points when the
+/// knot vector does not match the control points
+fn spline_points(spline: &Spline) -> Vec<Vector3> {
+    let degree = spline.degree.max(1) as usize;
+    let control = &spline.control_points;
+    let count = control.len();
+    if count <= degree || spline.knots.len() != count + degree + 1 {
+        return if spline.fit_points.len() >= 2 {
+            spline.fit_points.clone()
+        } else {
+            control.clone()
+        };
+    }
+
+    let weights: Vec<f64> = if spline.weights.len() == count {
+        spline.weights.clone()
+    } else {
+        vec![1.0; count]
+    };
+    let knots = &spline.knots;
+    let (t0, t1) = (knots[degree], knots[count]);
+    let segments = count * SPLINE_SEGMENTS_PER_POINT;
+
+    (0..=segments)
+        .map(|i| {
+            let t = t0 + (t1 - t0) * i as f64 / segments as f64;
+            de_boor(t, degree, knots, control, &weights)
+        })
+        .collect()
+}
+
+/// Evaluate a (rational) B-spline with de Boor's algorithm
+fn de_boor(t: f64, degree: usize, knots: &[f64], control: &[Vector3], weights: &[f64]) -> Vector3 {
+    let count = control.len();
+    let mut span = degree;
+    while span + 1 < count && t >= knots[span + 1] {
+        span += 1;
+    }
+
+    // Homogeneous coordinates: (x·w, y·w, z·w, w)
+    let mut points: Vec<(Vector3, f64)> = (0..=degree)
+        .map(|j| {
+            let index = j + span - degree;
+            (control[index] * weights[index], weights[index])
+        })
+        .collect();
+
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let left = knots[j + span - degree];
+            let right = knots[j + 1 + span - r];
+            let alpha = if right > left { (t - left) / (right - left) } else { 0.0 };
+            let (p0, w0) = points[j - 1];
+            let (p1, w1) = points[j];
+            points[j] = (p0 * (1.0 - alpha) + p1 * alpha, w0 * (1.0 - alpha) + w1 * alpha);
+        }
+    }
+
+    let (point, weight) = points[degree];
+    if weight != 0.0 {
+        point / weight
+    } else {
+        point
+    }
+}
+
+/// One TEXT per MTEXT paragraph, aligned like the MTEXT attachment point
+fn mtext_to_text(mtext: &MText) -> Vec<EntityType> {
+    let plain = plain_text(&mtext.value);
+    let lines: Vec<&str> = plain.lines().filter(|line| !line.trim().is_empty()).collect();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let (horizontal, vertical) = match mtext.attachment_point {
+        AttachmentPoint::TopLeft => (TextHorizontalAlignment::Left, TextVerticalAlignment::Top),
+        AttachmentPoint::TopCenter => (TextHorizontalAlignment::Center, TextVerticalAlignment::Top),
+        AttachmentPoint::TopRight => (TextHorizontalAlignment::Right, TextVerticalAlignment::Top),
+        AttachmentPoint::MiddleLeft => (TextHorizontalAlignment::Left, TextVerticalAlignment::Middle),
+        AttachmentPoint::MiddleCenter => (TextHorizontalAlignment::Center, TextVerticalAlignment::Middle),
+        AttachmentPoint::MiddleRight => (TextHorizontalAlignment::Right, TextVerticalAlignment::Middle),
+        AttachmentPoint::BottomLeft => (TextHorizontalAlignment::Left, TextVerticalAlignment::Bottom),
+        AttachmentPoint::BottomCenter => (TextHorizontalAlignment::Center, TextVerticalAlignment::Bottom),
+        AttachmentPoint::BottomRight => (TextHorizontalAlignment::Right, TextVerticalAlignment::Bottom),
+    };
+
+    // Offset of the first line from the attachment point, in lines
+    let spacing = mtext.height * MTEXT_LINE_SPACING * mtext.line_spacing_factor;
+    let last = (lines.len() - 1) as f64;
+    let first_offset = match vertical {
+        TextVerticalAlignment::Middle => last / 2.0,
+        TextVerticalAlignment::Bottom => last,
+        _ => 0.0,
+    };
+    let down = Vector3::new(mtext.rotation.sin(), -mtext.rotation.cos(), 0.0);
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let position = mtext.insertion_point + down * ((i as f64 - first_offset) * spacing);
+            let mut text = Text::with_value(line.to_string(), position);
+            text.common = mtext.common.clone();
+            if i > 0 {
+                text.common.handle = Handle::NULL;
+            }
+            text.height = mtext.height;
+            text.rotation = mtext.rotation;
+            text.style = mtext.style.clone();
+            text.normal = mtext.normal;
+            text.horizontal_alignment = horizontal;
+            text.vertical_alignment = vertical;
+            text.alignment_point = Some(position);
+            EntityType::Text(text)
+        })
+        .collect()
+}
+
+/// Strip MTEXT inline formatting, turning paragraph breaks into newlines
+fn plain_text(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' => {}
+            '\\' => match chars.next() {
+                Some('P') => result.push('\n'),
+                Some('~') => result.push(' '),
+                Some(escaped @ ('\\' | '{' | '}')) => result.push(escaped),
+                Some('S') => {
+                    // Stacked text "\Snum^den;" reads as "num/den"
+                    for c in chars.by_ref().take_while(|&c| c != ';') {
+                        result.push(if c == '^' || c == '#' { '/' } else { c });
+                    }
+                }
+                Some('U') if chars.peek() == Some(&'+') => {
+                    chars.next();
+                    let hex: String = chars.by_ref().take(4).collect();
+                    if let Some(c) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        result.push(c);
+                    }
+                }
+                Some('L' | 'l' | 'O' | 'o' | 'K' | 'k' | 'N') => {}
+                Some(_) => {
+                    // Property codes such as \H2.5; \C1; \fArial|b0;
+                    for c in chars.by_ref() {
+                        if c == ';' {
+                            break;
+                        }
+                    }
+                }
+                None => {}
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+fn multileader_to_leaders(mleader: &MultiLeader, document: &CadDocument) -> Vec<EntityType> {
+    let context = &mleader.context;
+    let mut converted = Vec::new();
+
+    let has_text = mleader.content_type == LeaderContentType::MText && !context.text_string.is_empty();
+    if has_text {
+        let mut mtext = MText::with_value(context.text_string.clone(), context.text_location);
+        mtext.common = mleader.common.clone();
+        mtext.height = if context.text_height > 0.0 { context.text_height } else { mleader.text_height };
+        mtext.rectangle_width = context.text_width;
+        mtext.rotation = context.text_rotation;
+        mtext.normal = context.text_normal;
+        mtext.line_spacing_factor = context.line_spacing_factor;
+        mtext.attachment_point = match context.text_attachment_point {
+            TextAttachmentPointType::Left => AttachmentPoint::TopLeft,
+            TextAttachmentPointType::Center => AttachmentPoint::TopCenter,
+            TextAttachmentPointType::Right => AttachmentPoint::TopRight,
+        };
+        converted.push(EntityType::MText(mtext));
+    }
+
+    let block_name = context
+        .block_content_handle
+        .filter(|_| mleader.content_type == LeaderContentType::Block)
+        .and_then(|handle| document.block_records.iter().find(|b| b.handle == handle))
+        .map(|block| block.name.clone());
+    if let Some(block_name) = block_name {
+        let mut insert = Insert::new(block_name, context.block_content_location);
+        insert.common = mleader.common.clone();
+        insert.common.handle = Handle::NULL;
+        insert.x_scale = context.block_content_scale.x;
+        insert.y_scale = context.block_content_scale.y;
+        insert.z_scale = context.block_content_scale.z;
+        insert.rotation = context.block_rotation;
+        insert.normal = context.block_content_normal;
+        converted.push(EntityType::Insert(insert));
+    }
+
+    if mleader.path_type != MultiLeaderPathType::Invisible {
+        for root in &context.leader_roots {
+            for line in &root.lines {
+                let mut vertices = line.points.clone();
+                vertices.push(root.connection_point);
+                if mleader.enable_dogleg && root.landing_distance > 0.0 {
+                    vertices.push(root.connection_point + root.direction.normalize() * root.landing_distance);
+                }
+                if vertices.len() < 2 {
+                    continue;
+                }
+
+                let mut leader = Leader::from_vertices(vertices);
+                leader.common = mleader.common.clone();
+                leader.common.handle = Handle::NULL;
+                if mleader.path_type == MultiLeaderPathType::Spline {
+                    leader.path_type = LeaderPathType::Spline;
+                }
+                leader.text_height = mleader.text_height;
+                if has_text {
+                    leader.annotation_handle = mleader.common.handle;
+                }
+                converted.push(EntityType::Leader(leader));
+            }
+        }
+    }
+
+    // The first entity keeps the MULTILEADER handle
+    if let Some(first) = converted.first_mut() {
+        first.common_mut().handle = mleader.common.handle;
+    }
+    converted
+}
+
+fn table_to_insert(table: &Table, document: &mut CadDocument) -> EntityType {
+    let existing = table
+        .block_record_handle
+        .and_then(|handle| document.block_records.iter().find(|b| b.handle == handle))
+        .filter(|block| !block.entities.is_empty())
+        .map(|block| block.name.clone());
+    let block_name = existing.unwrap_or_else(|| add_table_block(table, document));
+
+    let mut insert = Insert::new(block_name, table.insertion_point);
+    insert.common = table.common.clone();
+    insert.rotation = table.horizontal_direction.y.atan2(table.horizontal_direction.x);
+    insert.normal = table.normal;
+    EntityType::Insert(insert)
+}
+
+/// Add an anonymous `*T` block with the table grid and cell text, laid out
+/// from the top left corner at the origin; returns the block name
+fn add_table_block(table: &Table, document: &mut CadDocument) -> String {
+    let mut xs = vec![0.0];
+    for column in &table.columns {
+        xs.push(xs[xs.len() - 1] + column.width);
+    }
+    let mut ys = vec![0.0];
+    for row in &table.rows {
+        ys.push(ys[ys.len() - 1] - row.height);
+    }
+    let (rows, columns) = (table.rows.len(), table.columns.len());
+
+    // Top left cell of the merged range covering each cell
+    let mut owner: Vec<Vec<(usize, usize)>> =
+        (0..rows).map(|r| (0..columns).map(|c| (r, c)).collect()).collect();
+    for r in 0..rows {
+        for c in 0..columns {
+            let Some(cell) = table.cell(r, c) else { continue };
+            if cell.is_merged() && owner[r][c] == (r, c) {
+                let bottom = (r + cell.merge_height.max(1) as usize).min(rows);
+                let right = (c + cell.merge_width.max(1) as usize).min(columns);
+                for covered in &mut owner[r..bottom] {
+                    for slot in &mut covered[c..right] {
+                        *slot = (r, c);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut entities = Vec::new();
+    let mut line = |start: Vector3, end: Vector3| {
+        entities.push(EntityType::Line(Line::from_points(start, end)));
+    };
+
+    // Horizontal borders, split where a merged range spans the row boundary
+    for r in 0..=rows {
+        let mut start = None;
+        for c in 0..=columns {
+            let drawn = c < columns && (r == 0 || r == rows || owner[r - 1][c] != owner[r][c]);
+            match (drawn, start) {
+                (true, None) => start = Some(c),
+                (false, Some(from)) => {
+                    line(Vector3::new(xs[from], ys[r], 0.0), Vector3::new(xs[c], ys[r], 0.0));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    // Vertical borders
+    for c in 0..=columns {
+        let mut start = None;
+        for r in 0..=rows {
+            let drawn = r < rows && (c == 0 || c == columns || owner[r][c - 1] != owner[r][c]);
+            match (drawn, start) {
+                (true, None) => start = Some(r),
+                (false, Some(from)) => {
+                    line(Vector3::new(xs[c], ys[from], 0.0), Vector3::new(xs[c], ys[r], 0.0));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Cell text, centered in the cell or merged range
+    for r in 0..rows {
+        for c in 0..columns {
+            let Some(cell) = table.cell(r, c).filter(|_| owner[r][c] == (r, c)) else { continue };
+            let value = cell.text_value();
+            if value.is_empty() {
+                continue;
+            }
+            let bottom = (r + cell.merge_height.max(1) as usize).min(rows);
+            let right = (c + cell.merge_width.max(1) as usize).min(columns);
+            let center = Vector3::new((xs[c] + xs[right]) / 2.0, (ys[r] + ys[bottom]) / 2.0, 0.0);
+
+            let mut mtext = MText::with_value(value, center);
+            mtext.attachment_point = AttachmentPoint::MiddleCenter;
+            if let Some(content) = cell.content() {
+                mtext.height = content.text_height;
+                mtext.common.color = content.color;
+            }
+            entities.extend(mtext_to_text(&mtext));
+        }
+    }
+
+    let mut index = 1;
+    while document.block_records.get(&format!("*T{}", index)).is_some() {
+        index += 1;
+    }
+    let name = format!("*T{}", index);
+
+    let mut block = BlockRecord::new(name.clone());
+    block.flags.anonymous = true;
+    block.handle = document.allocate_handle();
+    block.block_entity_handle = document.allocate_handle();
+    block.block_end_handle = document.allocate_handle();
+    for mut entity in entities {
+        let handle = document.allocate_handle();
+        entity.common_mut().handle = handle;
+        entity.common_mut().owner_handle = block.handle;
+        block.entities.push(entity);
+    }
+    let _ = document.block_records.add(block);
+    name
+}
+
+fn mesh_to_polyface(mesh: &Mesh) -> Option<EntityType> {
+    // Polyface vertex indices are 16-bit
+    if mesh.vertices.len() > i16::MAX as usize {
+        return None;
+    }
+
+    let mut polyface = PolyfaceMesh::new();
+    polyface.common = mesh.common.clone();
+    for vertex in &mesh.vertices {
+        polyface.add_vertex(PolyfaceVertex::new(*vertex));
+    }
+
+    for face in &mesh.faces {
+        let indices: Vec<i16> = face.vertices.iter().map(|&v| v as i16 + 1).collect();
+        match indices.len() {
+            0..=2 => {}
+            3 => polyface.add_triangle(indices[0], indices[1], indices[2]),
+            4 => polyface.add_quad(indices[0], indices[1], indices[2], indices[3]),
+            n => {
+                // Triangle fan; only the edges on the face outline stay visible
+                for i in 1..n - 1 {
+                    polyface.add_face(PolyfaceFace::triangle_with_visibility(
+                        indices[0],
+                        indices[i],
+                        indices[i + 1],
+                        i != 1,
+                        false,
+                        i + 1 != n - 1,
+                    ));
+                }
+            }
+        }
+    }
+    Some(EntityType::PolyfaceMesh(polyface))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text() {
+        assert_eq!(plain_text("{\\fArial|b1;Bold}\\PLine 2"), "Bold\nLine 2");
+        assert_eq!(plain_text("\\H2.5;1\\S1^2; in\\~x"), "11/2 in x");
+        assert_eq!(plain_text("a\\\\b\\{c\\}"), "a\\b{c}");
+    }
+
+    #[test]
+    fn test_spline_samples_pass_through_end_points() {
+        let mut spline = Spline::from_control_points(
+            2,
+            vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(5.0, 10.0, 0.0),
+                Vector3::new(10.0, 0.0, 0.0),
+            ],
+        );
+        spline.knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+
+        let points = spline_points(&spline);
+        assert_eq!(points.len(), 3 * SPLINE_SEGMENTS_PER_POINT + 1);
+        assert!(points[0].distance(&Vector3::new(0.0, 0.0, 0.0)) < 1e-9);
+        assert!(points[points.len() - 1].distance(&Vector3::new(10.0, 0.0, 0.0)) < 1e-9);
+        // Quadratic Bezier midpoint
+        let mid = points[points.len() / 2];
+        assert!(mid.distance(&Vector3::new(5.0, 5.0, 0.0)) < 1e-9);
+    }
+
+    #[test]
+    fn test_mesh_to_polyface_splits_polygons() {
+        let mut mesh = Mesh::create_unit_cube();
+        mesh.add_vertex(Vector3::new(2.0, 0.0, 0.0));
+        mesh.add_face(crate::entities::MeshFace::new(vec![0, 1, 8, 2, 3]));
+
+        let Some(EntityType::PolyfaceMesh(polyface)) = mesh_to_polyface(&mesh) else {
+            panic!("expected a polyface mesh");
+        };
+        assert_eq!(polyface.vertices.len(), 9);
+        // Six quads plus the pentagon as three triangles
+        assert_eq!(polyface.faces.len(), 9);
+        let fan: Vec<_> = polyface.faces[6..].iter().map(|f| (f.index1, f.index2, f.index3)).collect();
+        assert_eq!(fan, [(1, 2, -9), (-1, 9, -3), (-1, 3, 4)]);
+    }
+
+    #[test]
+    fn test_table_block_skips_merged_borders() {
+        let mut table = Table::new(Vector3::new(5.0, 5.0, 0.0), 2, 2);
+        table.set_uniform_row_height(1.0);
+        table.set_uniform_column_width(3.0);
+        table.set_cell_text(0, 0, "Title");
+        table.set_cell_text(1, 1, "B");
+        table.merge_cells(crate::entities::CellRange::new(0, 0, 0, 1));
+
+        let mut document = CadDocument::new();
+        let EntityType::Insert(insert) = table_to_insert(&table, &mut document) else {
+            panic!("expected an insert");
+        };
+        let block = document.block_records.get(&insert.block_name).unwrap();
+        assert!(block.flags.anonymous);
+        let lines = block.entities.iter().filter(|e| matches!(e, EntityType::Line(_))).count();
+        // Three horizontal borders, two outer verticals and one split by the merge
+        assert_eq!(lines, 6);
+        let title = block
+            .entities
+            .iter()
+            .find_map(|e| match e {
+                EntityType::Text(t) if t.value == "Title" => Some(t),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(title.alignment_point, Some(Vector3::new(3.0, -0.5, 0.0)));
+    }
+}
@@ -0,0 +1,362 @@
+//! Down-conversion of entities for older file versions
+//!
+//! Before a document is written as an older release, every entity that
+//! release cannot store goes through a [`DowngradePass`]. The pass asks its
+//! [`EntityConverter`]s, in order, to rewrite the entity into older types
+//! and reports each conversion or drop in the document notifications.
+//!
+//! The built-in converters cover:
+//!
+//! | Entity       | Target       | Result                                   |
+//! |--------------|--------------|------------------------------------------|
+//! | LWPOLYLINE   | R13 and R12  | POLYLINE/VERTEX                          |
+//! | ELLIPSE      | R12          | POLYLINE through sampled points          |
+//! | SPLINE       | R12          | POLYLINE through sampled points          |
+//! | LEADER       | R12          | POLYLINE along the leader vertices       |
+//! | MTEXT        | R12          | One TEXT per paragraph                   |
+//! | ACAD_TABLE   | before R2004 | Anonymous block of LINE and TEXT, INSERT |
+//! | MULTILEADER  | before R2007 | LEADER per leader line, MTEXT, INSERT    |
+//! | MESH         | before R2010 | Polyface mesh                            |
+//! | *UNDERLAY    | older target | Dropped with a warning                   |
+//!
+//! Converted entities that the target still cannot store are converted
+//! again, so a MULTILEADER written as R12 ends up as polylines and text.
+
+mod converters;
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::notification::NotificationType;
+use crate::types::DxfVersion;
+
+pub use converters::{
+    CurveConverter, LwPolylineConverter, MTextConverter, MeshConverter, MultiLeaderConverter,
+    TableConverter, UnderlayConverter,
+};
+
+/// How many times the output of a conversion is converted again
+const MAX_CONVERSION_DEPTH: usize = 4;
+
+/// Rewrites an entity the target version cannot store
+pub trait EntityConverter {
+    /// Convert `entity` for `version`, or return `None` to leave it to the
+    /// next converter. An empty result drops the entity.
+    ///
+    /// The first entity returned should keep the handle of `entity`; the
+    /// others may have a null handle and get one assigned by the pass.
+    fn convert(
+        &self,
+        entity: &EntityType,
+        version: DxfVersion,
+        document: &mut CadDocument,
+    ) -> Option<Vec<EntityType>>;
+}
+
+/// Converts the entities of a document for an older version
+pub struct DowngradePass {
+    converters: Vec<Box<dyn EntityConverter>>,
+}
+
+impl DowngradePass {
+    /// Pass without converters: unsupported entities are dropped
+    pub fn new() -> Self {
+        Self { converters: Vec::new() }
+    }
+
+    /// Add a converter, tried before the converters already registered
+    pub fn with_converter<C: EntityConverter + 'static>(mut self, converter: C) -> Self {
+        self.converters.insert(0, Box::new(converter));
+        self
+    }
+
+    /// Convert every model space and block entity `version` cannot store
+    pub fn run(&self, document: &mut CadDocument, version: DxfVersion) {
+        if version == DxfVersion::Unknown {
+            return;
+        }
+
+        // Converted entities take the place of the original: rebuild the
+        // entity order in one go rather than shifting it per conversion
+        if document.entities().any(|e| !is_supported(e, version)) {
+            let entities = document.take_entities();
+            for entity in entities {
+                for converted in self.downgrade(entity, version, document) {
+                    let _ = document.add_entity(converted);
+                }
+            }
+        }
+
+        let names: Vec<String> = document
+            .block_records
+            .iter()
+            .filter(|b| b.entities.iter().any(|e| !is_supported(e, version)))
+            .map(|b| b.name.clone())
+            .collect();
+        for name in names {
+            let Some(entities) = document
+                .block_records
+                .get_mut(&name)
+                .map(|b| std::mem::take(&mut b.entities))
+            else {
+                continue;
+            };
+            let mut converted = Vec::with_capacity(entities.len());
+            for entity in entities {
+                for mut entity in self.downgrade(entity, version, document) {
+                    if entity.common().handle.is_null() {
+                        let handle = document.allocate_handle();
+                        entity.common_mut().handle = handle;
+                    }
+                    converted.push(entity);
+                }
+            }
+            if let Some(block_record) = document.block_records.get_mut(&name) {
+                block_record.entities = converted;
+            }
+        }
+    }
+
+    /// Convert one entity and report the result
+    pub(crate) fn downgrade(
+        &self,
+        entity: EntityType,
+        version: DxfVersion,
+        document: &mut CadDocument,
+    ) -> Vec<EntityType> {
+        if is_supported(&entity, version) {
+            return vec![entity];
+        }
+
+        let name = entity.dxf_name().to_string();
+        let handle = entity.common().handle;
+        match self.convert(&entity, version, document, 0) {
+            Some(converted) if !converted.is_empty() => {
+                let mut names: Vec<&str> = Vec::new();
+                for entity in &converted {
+                    if !names.contains(&entity.dxf_name()) {
+                        names.push(entity.dxf_name());
+                    }
+                }
+                document.notifications.notify(
+                    NotificationType::Warning,
+                    format!(
+                        "{} {} was converted into {} {} for {}",
+                        name,
+                        handle,
+                        converted.len(),
+                        names.join("/"),
+                        version
+                    ),
+                );
+                converted
+            }
+            Some(_) => {
+                document.notifications.notify(
+                    NotificationType::Warning,
+                    format!("{} {} is not supported by {} and was dropped", name, handle, version),
+                );
+                Vec::new()
+            }
+            None => {
+                document.notifications.notify(
+                    NotificationType::NotSupported,
+                    format!("{} {} is not supported by {} and was dropped", name, handle, version),
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// Run the converters on `entity`, then again on any result `version`
+    /// still cannot store; results no converter takes are reported and
+    /// dropped. `None` when no converter takes the entity
+    fn convert(
+        &self,
+        entity: &EntityType,
+        version: DxfVersion,
+        document: &mut CadDocument,
+        depth: usize,
+    ) -> Option<Vec<EntityType>> {
+        if depth == MAX_CONVERSION_DEPTH {
+            return None;
+        }
+        let converted = self
+            .converters
+            .iter()
+            .find_map(|converter| converter.convert(entity, version, document))?;
+
+        let mut result = Vec::with_capacity(converted.len());
+        for output in converted {
+            if is_supported(&output, version) {
+                result.push(output);
+            } else if let Some(converted) = self.convert(&output, version, document, depth + 1) {
+                result.extend(converted);
+            } else {
+                document.notifications.notify(
+                    NotificationType::NotSupported,
+                    format!(
+                        "{} {} converted from {} {} is not supported by {} and was dropped",
+                        output.dxf_name(),
+                        output.common().handle,
+                        entity.dxf_name(),
+                        entity.common().handle,
+                        version
+                    ),
+                );
+            }
+        }
+        Some(result)
+    }
+}
+
+impl Default for DowngradePass {
+    /// Pass with all built-in converters
+    fn default() -> Self {
+        Self::new()
+            .with_converter(UnderlayConverter)
+            .with_converter(MeshConverter)
+            .with_converter(TableConverter)
+            .with_converter(MultiLeaderConverter)
+            .with_converter(MTextConverter)
+            .with_converter(CurveConverter)
+            .with_converter(LwPolylineConverter)
+    }
+}
+
+/// Release that introduced an entity or object type, by DXF name. Types not
+/// listed exist since R13, the first release with subclassed entities.
+pub(crate) fn introduced_in(dxf_name: &str) -> DxfVersion {
+    match dxf_name {
+        "LWPOLYLINE" | "HATCH" | "IMAGE" | "IMAGEDEF" | "IMAGEDEF_REACTOR" | "RASTERVARIABLES"
+        | "SORTENTSTABLE" | "SPATIAL_FILTER" | "SPATIAL_INDEX" | "LAYER_INDEX" | "IDBUFFER"
+        | "DICTIONARYVAR" | "ACDBDICTIONARYWDFLT" | "ACDBPLACEHOLDER" => DxfVersion::AC1014,
+        "WIPEOUT" | "WIPEOUTVARIABLES" | "LAYOUT" | "PLOTSETTINGS" => DxfVersion::AC1015,
+        "ACAD_TABLE" | "TABLESTYLE" | "DBCOLOR" | "FIELD" => DxfVersion::AC1018,
+        "MULTILEADER" | "MLEADERSTYLE" | "SCALE" | "VISUALSTYLE" | "MATERIAL" | "DWFUNDERLAY"
+        | "DGNUNDERLAY" | "DWFDEFINITION" | "DGNDEFINITION" => DxfVersion::AC1021,
+        "MESH" | "GEODATA" | "PDFUNDERLAY" | "PDFDEFINITION" => DxfVersion::AC1024,
+        _ => DxfVersion::AC1012,
+    }
+}
+
+/// Whether `version` can store `entity` as is
+pub(crate) fn is_supported(entity: &EntityType, version: DxfVersion) -> bool {
+    if version >= DxfVersion::AC1012 {
+        return introduced_in(entity.dxf_name()) <= version;
+    }
+    matches!(
+        entity,
+        EntityType::Point(_)
+            | EntityType::Line(_)
+            | EntityType::Circle(_)
+            | EntityType::Arc(_)
+            | EntityType::Polyline(_)
+            | EntityType::Polyline2D(_)
+            | EntityType::Polyline3D(_)
+            | EntityType::PolyfaceMesh(_)
+            | EntityType::PolygonMesh(_)
+            | EntityType::Text(_)
+            | EntityType::Dimension(_)
+            | EntityType::Solid(_)
+            | EntityType::Face3D(_)
+            | EntityType::Insert(_)
+            | EntityType::Block(_)
+            | EntityType::BlockEnd(_)
+            | EntityType::Viewport(_)
+            | EntityType::AttributeDefinition(_)
+            | EntityType::AttributeEntity(_)
+            | EntityType::Shape(_)
+            | EntityType::Seqend(_)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Line, Mesh, MultiLeader, Table};
+    use crate::types::Vector3;
+
+    #[test]
+    fn test_version_support() {
+        let line = EntityType::Line(Line::new());
+        assert!(is_supported(&line, DxfVersion::AC1009));
+        let mesh = EntityType::Mesh(Mesh::create_unit_cube());
+        assert!(!is_supported(&mesh, DxfVersion::AC1021));
+        assert!(is_supported(&mesh, DxfVersion::AC1024));
+        let table = EntityType::Table(Table::new(Vector3::ZERO, 1, 1));
+        assert!(!is_supported(&table, DxfVersion::AC1015));
+        assert!(is_supported(&table, DxfVersion::AC1018));
+    }
+
+    #[test]
+    fn test_nested_conversion_for_r12() {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::MultiLeader(MultiLeader::with_text(
+            "Note",
+            Vector3::new(10.0, 5.0, 0.0),
+            vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(8.0, 5.0, 0.0)],
+        )))
+        .unwrap();
+        let last = doc.add_entity(EntityType::Line(Line::new())).unwrap();
+
+        DowngradePass::default().run(&mut doc, DxfVersion::AC1009);
+        assert!(doc.entities().all(|e| is_supported(e, DxfVersion::AC1009)));
+        // Converted entities stay where the original was
+        assert_eq!(doc.entities().last().unwrap().common().handle, last);
+        assert!(doc.entities().any(|e| matches!(e, EntityType::Text(t) if t.value == "Note")));
+        assert!(doc.entities().any(|e| matches!(e, EntityType::Polyline2D(_))));
+        assert_eq!(doc.notifications.len(), 1);
+    }
+
+    struct DropMeshes;
+
+    impl EntityConverter for DropMeshes {
+        fn convert(&self, entity: &EntityType, _: DxfVersion, _: &mut CadDocument) -> Option<Vec<EntityType>> {
+            matches!(entity, EntityType::Mesh(_)).then(Vec::new)
+        }
+    }
+
+    #[test]
+    fn test_custom_converter_runs_first() {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Mesh(Mesh::create_unit_cube())).unwrap();
+
+        DowngradePass::default().with_converter(DropMeshes).run(&mut doc, DxfVersion::AC1015);
+        assert_eq!(doc.entities().count(), 0);
+        let notification = doc.notifications.iter().next().unwrap();
+        assert_eq!(notification.notification_type, NotificationType::Warning);
+        assert!(notification.message.contains("dropped"));
+    }
+
+    struct MeshToTable;
+
+    impl EntityConverter for MeshToTable {
+        fn convert(&self, entity: &EntityType, _: DxfVersion, _: &mut CadDocument) -> Option<Vec<EntityType>> {
+            matches!(entity, EntityType::Mesh(_)).then(|| {
+                vec![
+                    EntityType::Line(Line::new()),
+                    EntityType::Table(Table::new(Vector3::ZERO, 1, 1)),
+                ]
+            })
+        }
+    }
+
+    #[test]
+    fn test_unsupported_converter_output_is_reported() {
+        let mut doc = CadDocument::new();
+        let mesh = doc.add_entity(EntityType::Mesh(Mesh::create_unit_cube())).unwrap();
+
+        DowngradePass::new().with_converter(MeshToTable).run(&mut doc, DxfVersion::AC1015);
+        assert_eq!(doc.entities().count(), 1);
+        assert!(matches!(doc.entities().next().unwrap(), EntityType::Line(_)));
+
+        let dropped = doc
+            .notifications
+            .iter()
+            .find(|n| n.notification_type == NotificationType::NotSupported)
+            .expect("dropped output reported");
+        assert!(dropped.message.starts_with("ACAD_TABLE"), "{}", dropped.message);
+        assert!(dropped.message.contains(&format!("MESH {}", mesh)), "{}", dropped.message);
+        assert!(dropped.message.contains("dropped"));
+    }
+}
@@ -144,6 +144,41 @@ pub fn crc32_update(seed: u32, value: u8) -> u32 {
     (seed >> 8) ^ CRC32_TABLE[((seed ^ value as u32) & 0xFF) as usize]
 }
 
+/// CRC-64 polynomial (ECMA-182) used by R2007 file headers and pages.
+const CRC64_POLYNOMIAL: u64 = 0x42F0_E1EB_A9EA_3693;
+
+/// CRC-64 lookup table (256 entries), most significant bit first.
+pub static CRC64_TABLE: [u64; 256] = crc64_table();
+
+const fn crc64_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u64) << 56;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & (1 << 63) != 0 {
+                (crc << 1) ^ CRC64_POLYNOMIAL
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-64 calculation over a byte slice.
+pub fn crc64(seed: u64, data: &[u8]) -> u64 {
+    let mut crc = seed;
+    for &byte in data {
+        crc = CRC64_TABLE[((crc >> 56) as u8 ^ byte) as usize] ^ (crc << 8);
+    }
+    crc
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -180,4 +215,10 @@ mod tests {
         let result = crc32_update(seed, 0);
         assert_eq!(result, (seed >> 8) ^ CRC32_TABLE[(seed & 0xFF) as usize]);
     }
+
+    #[test]
+    fn test_crc64_check_value() {
+        assert_eq!(crc64(0, b"123456789"), 0x6C40_DF5F_0B49_7347);
+        assert_eq!(crc64(0x1234, &[]), 0x1234);
+    }
 }
@@ -2,15 +2,53 @@
 //!
 //! Ported from ACadSharp `DwgDocumentBuilder.cs`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use crate::document::CadDocument;
-use crate::entities::EntityType;
+use crate::entities::attribute_definition::{
+    AttributeFlags, HorizontalAlignment, MTextFlag, VerticalAlignment,
+};
+use crate::entities::leader::{HooklineDirection, LeaderCreationType, LeaderPathType};
+use crate::entities::mtext::{AttachmentPoint, DrawingDirection};
+use crate::entities::text::{TextHorizontalAlignment, TextVerticalAlignment};
+use crate::entities::face3d::InvisibleEdgeFlags;
+use crate::entities::lwpolyline::LwVertex;
+use crate::entities::mline::{MLineFlags, MLineJustification, MLineSegment, MLineVertex};
+use crate::entities::polyline::SmoothSurfaceType;
+use crate::entities::polyline3d::SmoothSurfaceType as Polyline3DSmoothType;
+use crate::entities::solid3d::{AcisData, Silhouette, Wire, WireType};
+use crate::entities::viewport::{ViewportRenderMode, ViewportStatusFlags};
+use crate::entities::{
+    Arc, AttributeDefinition, AttributeEntity, Body, Circle, Ellipse, EntityCommon, EntityType,
+    Face3D, Insert, Leader, Line, LwPolyline, MLine, MText, Ole2Frame, Point, PolyfaceFace,
+    PolyfaceMesh, PolyfaceVertex, PolyfaceVertexFlags, PolygonMeshEntity, PolygonMeshFlags,
+    PolygonMeshVertex, Polyline2D, Polyline3D, PolylineFlags, ProxyGraphics, Ray, Region, Shape,
+    Solid, Solid3D, Spline, SurfaceSmoothType, Text, Tolerance, UnknownEntity, Vertex2D,
+    Vertex3DPolyline, VertexFlags, Viewport, XLine,
+};
 use crate::notification::{Notification, NotificationType};
-use crate::types::DxfVersion;
+use crate::objects::{
+    Dictionary, DictionaryCloningFlags, DictionaryWithDefault, ObjectType, XRecord, XRecordEntry,
+    XRecordValue,
+};
+use crate::tables::{
+    AppId, BlockRecord, DimStyle, Layer, LineType, LineTypeElement, Table, TableEntry, TextStyle,
+    Ucs, VPort, View,
+};
+use crate::types::{Color, DxfVersion, Handle, LineWeight, Vector2, Vector3};
+use crate::xdata::{ExtendedData, ExtendedDataRecord, XDataValue};
 
-use super::dwg_header_handles_collection::DwgHeaderHandlesCollection;
+use super::dwg_header_handles_collection::{handle_names, DwgHeaderHandlesCollection};
 use super::dwg_reader_configuration::DwgReaderConfiguration;
+use super::dwg_stream_readers::dwg_object_reader::{DwgRawObject, RawObjectType};
+
+/// ACIS data and wireframe shared by REGION, 3DSOLID and BODY.
+struct ModelerGeometry {
+    point_of_reference: Vector3,
+    acis_data: AcisData,
+    wires: Vec<Wire>,
+    silhouettes: Vec<Silhouette>,
+}
 
 /// Assembles a [`CadDocument`] from the raw sections read from a DWG file.
 ///
@@ -32,10 +70,30 @@ pub struct DwgDocumentBuilder {
     pub model_space_entities: Vec<EntityType>,
     /// Handle → resolved object name cache (for header resolution).
     pub handle_name_map: HashMap<u64, String>,
+    /// Objects decoded from the objects section, in read order.
+    pub raw_objects: Vec<DwgRawObject>,
     /// Accumulated notifications.
     pub notifications: Vec<Notification>,
 }
 
+/// A single object converted on demand by
+/// [`DwgDocumentBuilder::build_single_object`].
+#[derive(Debug, Clone)]
+pub enum DwgObject {
+    /// A graphical entity.
+    Entity(EntityType),
+    /// A non-graphical object.
+    Object(ObjectType),
+}
+
+/// Where a decoded entity ends up in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntityContainer {
+    ModelSpace,
+    PaperSpace,
+    Block(u64),
+}
+
 impl DwgDocumentBuilder {
     /// Create a new builder for the given version and document.
     pub fn new(
@@ -51,6 +109,7 @@ impl DwgDocumentBuilder {
             paper_space_entities: Vec::new(),
             model_space_entities: Vec::new(),
             handle_name_map: HashMap::new(),
+            raw_objects: Vec::new(),
             notifications: Vec::new(),
         }
     }
@@ -87,11 +146,24 @@ impl DwgDocumentBuilder {
     /// the builder. It resolves cross-references, updates the header, and
     /// attaches entities to their block records.
     pub fn build_document(mut self) -> CadDocument {
+        let raw_objects = std::mem::take(&mut self.raw_objects);
+        self.build_tables(&raw_objects);
+
         // Resolve header handle names
         let map = self.handle_name_map.clone();
         self.header_handles
             .update_header(&mut self.document.header, |h| map.get(&h).cloned());
 
+        self.build_block_records(&raw_objects);
+        self.build_objects(&raw_objects);
+        self.build_entities(&raw_objects);
+
+        self.document.resolve_references();
+
+        if self.configuration.recover {
+            self.rebuild_table_controls(&raw_objects);
+        }
+
         // Transfer notifications
         for n in self.notifications {
             self.document
@@ -101,6 +173,2022 @@ impl DwgDocumentBuilder {
 
         self.document
     }
+
+    /// Handle of the `*Model_Space` block record.
+    fn model_space_handle(&self) -> u64 {
+        self.header_handles
+            .get(handle_names::MODEL_SPACE)
+            .unwrap_or_else(|| self.document.header.model_space_block_handle.value())
+    }
+
+    /// Handle of the `*Paper_Space` block record.
+    fn paper_space_handle(&self) -> u64 {
+        self.header_handles
+            .get(handle_names::PAPER_SPACE)
+            .unwrap_or_else(|| self.document.header.paper_space_block_handle.value())
+    }
+
+    /// Convert table control objects and their entries into the document
+    /// tables and register every entry name for handle resolution.
+    fn build_tables(&mut self, raw_objects: &[DwgRawObject]) {
+        // Names first: entries reference each other (layer -> linetype,
+        // dimstyle -> text style), as do the header and the entities.
+        for raw in raw_objects {
+            self.register_entry_name(raw);
+        }
+
+        for raw in raw_objects {
+            let Some(raw_type) = raw.raw_type else {
+                continue;
+            };
+            let handle = Handle::new(raw.handle);
+            let header = &mut self.document.header;
+            match raw_type {
+                RawObjectType::BlockControl => {
+                    self.document.block_records.set_handle(handle);
+                    header.block_control_handle = handle;
+                }
+                RawObjectType::LayerControl => {
+                    self.document.layers.set_handle(handle);
+                    header.layer_control_handle = handle;
+                }
+                RawObjectType::TextStyleControl => {
+                    self.document.text_styles.set_handle(handle);
+                    header.style_control_handle = handle;
+                }
+                RawObjectType::LineTypeControl => {
+                    self.document.line_types.set_handle(handle);
+                    header.linetype_control_handle = handle;
+                }
+                RawObjectType::ViewControl => {
+                    self.document.views.set_handle(handle);
+                    header.view_control_handle = handle;
+                }
+                RawObjectType::UcsControl => {
+                    self.document.ucss.set_handle(handle);
+                    header.ucs_control_handle = handle;
+                }
+                RawObjectType::VPortControl => {
+                    self.document.vports.set_handle(handle);
+                    header.vport_control_handle = handle;
+                }
+                RawObjectType::AppIdControl => {
+                    self.document.app_ids.set_handle(handle);
+                    header.appid_control_handle = handle;
+                }
+                RawObjectType::DimStyleControl => {
+                    self.document.dim_styles.set_handle(handle);
+                    header.dimstyle_control_handle = handle;
+                }
+                RawObjectType::VpEntHdrControl => {
+                    header.vpent_hdr_control_handle = handle;
+                }
+                _ => {}
+            }
+
+            let Some(name) = self.try_get_name(raw.handle).map(str::to_string) else {
+                continue;
+            };
+            match raw_type {
+                RawObjectType::BlockHeader => {
+                    let record = self.build_block_record(raw, name);
+                    upsert_entry(&mut self.document.block_records, record);
+                }
+                RawObjectType::Layer => {
+                    let layer = self.build_layer(raw, name);
+                    upsert_entry(&mut self.document.layers, layer);
+                }
+                RawObjectType::TextStyle => {
+                    upsert_entry(&mut self.document.text_styles, build_text_style(raw, name));
+                }
+                RawObjectType::LineType => {
+                    upsert_entry(&mut self.document.line_types, build_line_type(raw, name));
+                }
+                RawObjectType::DimStyle => {
+                    let style = self.build_dim_style(raw, name);
+                    upsert_entry(&mut self.document.dim_styles, style);
+                }
+                RawObjectType::AppId => {
+                    let mut app_id = AppId::new(name);
+                    app_id.handle = handle;
+                    upsert_entry(&mut self.document.app_ids, app_id);
+                }
+                RawObjectType::View => {
+                    upsert_entry(&mut self.document.views, build_view(raw, name));
+                }
+                RawObjectType::Ucs => {
+                    upsert_entry(&mut self.document.ucss, build_ucs(raw, name));
+                }
+                RawObjectType::VPort => {
+                    upsert_entry(&mut self.document.vports, build_vport(raw, name));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Register the name of a table entry for handle resolution. Other
+    /// objects are ignored.
+    pub fn register_entry_name(&mut self, raw: &DwgRawObject) {
+        if !raw.raw_type.is_some_and(is_table_entry) {
+            return;
+        }
+        let model_space = self.model_space_handle();
+        let paper_space = self.paper_space_handle();
+        let name = match raw.handle {
+            h if raw.raw_type == Some(RawObjectType::BlockHeader) && h == model_space => {
+                "*Model_Space".to_string()
+            }
+            h if raw.raw_type == Some(RawObjectType::BlockHeader) && h == paper_space => {
+                "*Paper_Space".to_string()
+            }
+            _ => match raw.text_props.get("entry_name") {
+                Some(name) if !name.is_empty() => name.clone(),
+                _ => return,
+            },
+        };
+        self.register_name(raw.handle, name);
+    }
+
+    /// Convert a single object read on demand. Names are resolved through
+    /// the entries registered so far; `objects` holds the vertices of a
+    /// polyline. Table entries, table controls and vertices have no typed
+    /// form of their own and give `None`, as do objects that are not
+    /// decoded.
+    pub fn build_single_object(
+        &mut self,
+        raw: &DwgRawObject,
+        objects: &HashMap<u64, &DwgRawObject>,
+    ) -> Option<DwgObject> {
+        let raw_type = raw.raw_type?;
+        if self.is_entity(raw) {
+            if is_vertex(raw_type) {
+                return None;
+            }
+            self.build_entity(raw, objects).map(DwgObject::Entity)
+        } else if is_table_object(raw_type) {
+            None
+        } else {
+            self.build_object(raw).map(DwgObject::Object)
+        }
+    }
+
+    /// Give every table whose control object was not read a fresh handle,
+    /// so the rebuilt control does not collide with objects of the file.
+    fn rebuild_table_controls(&mut self, raw_objects: &[DwgRawObject]) {
+        let found: HashSet<RawObjectType> = raw_objects
+            .iter()
+            .filter_map(|raw| raw.raw_type)
+            .filter(|t| is_table_object(*t))
+            .collect();
+
+        let controls = [
+            (RawObjectType::BlockControl, "BLOCK_RECORD"),
+            (RawObjectType::LayerControl, "LAYER"),
+            (RawObjectType::TextStyleControl, "STYLE"),
+            (RawObjectType::LineTypeControl, "LTYPE"),
+            (RawObjectType::ViewControl, "VIEW"),
+            (RawObjectType::UcsControl, "UCS"),
+            (RawObjectType::VPortControl, "VPORT"),
+            (RawObjectType::AppIdControl, "APPID"),
+            (RawObjectType::DimStyleControl, "DIMSTYLE"),
+        ];
+
+        for (control, table_name) in controls {
+            if found.contains(&control) {
+                continue;
+            }
+
+            let handle = self.document.allocate_handle();
+            let doc = &mut self.document;
+            match control {
+                RawObjectType::BlockControl => {
+                    doc.block_records.set_handle(handle);
+                    doc.header.block_control_handle = handle;
+                }
+                RawObjectType::LayerControl => {
+                    doc.layers.set_handle(handle);
+                    doc.header.layer_control_handle = handle;
+                }
+                RawObjectType::TextStyleControl => {
+                    doc.text_styles.set_handle(handle);
+                    doc.header.style_control_handle = handle;
+                }
+                RawObjectType::LineTypeControl => {
+                    doc.line_types.set_handle(handle);
+                    doc.header.linetype_control_handle = handle;
+                }
+                RawObjectType::ViewControl => {
+                    doc.views.set_handle(handle);
+                    doc.header.view_control_handle = handle;
+                }
+                RawObjectType::UcsControl => {
+                    doc.ucss.set_handle(handle);
+                    doc.header.ucs_control_handle = handle;
+                }
+                RawObjectType::VPortControl => {
+                    doc.vports.set_handle(handle);
+                    doc.header.vport_control_handle = handle;
+                }
+                RawObjectType::AppIdControl => {
+                    doc.app_ids.set_handle(handle);
+                    doc.header.appid_control_handle = handle;
+                }
+                RawObjectType::DimStyleControl => {
+                    doc.dim_styles.set_handle(handle);
+                    doc.header.dimstyle_control_handle = handle;
+                }
+                _ => {}
+            }
+
+            self.notify(
+                format!(
+                    "Rebuilt missing {} table control object with handle {:#X}",
+                    table_name,
+                    handle.value()
+                ),
+                NotificationType::Warning,
+            );
+        }
+    }
+
+    fn build_block_record(&self, raw: &DwgRawObject, name: String) -> BlockRecord {
+        let mut record = BlockRecord::new(name);
+        record.handle = Handle::new(raw.handle);
+        record.flags.anonymous = flag(raw, "block_anonymous");
+        record.flags.has_attributes = flag(raw, "block_has_attributes");
+        record.flags.is_xref = flag(raw, "block_is_xref");
+        record.flags.is_xref_overlay = flag(raw, "block_is_xref_overlay");
+        record.block_entity_handle = handle(raw, "block_entity_handle").unwrap_or(Handle::NULL);
+        record.block_end_handle = handle(raw, "block_end_handle").unwrap_or(Handle::NULL);
+        record.layout = handle(raw, "block_layout_handle").unwrap_or(Handle::NULL);
+        if let Some(&units) = raw.int_props.get("block_units") {
+            record.units = units as i16;
+        }
+        if let Some(&explodable) = raw.bool_props.get("block_explodable") {
+            record.explodable = explodable;
+        }
+        if let Some(&uniform) = raw.bool_props.get("block_scale_uniformly") {
+            record.scale_uniformly = uniform;
+        }
+        record
+    }
+
+    fn build_layer(&self, raw: &DwgRawObject, name: String) -> Layer {
+        let mut layer = Layer::new(name);
+        layer.handle = Handle::new(raw.handle);
+        layer.flags.frozen = flag(raw, "layer_frozen");
+        layer.flags.off = flag(raw, "layer_off");
+        layer.flags.locked = flag(raw, "layer_locked");
+        layer.is_plottable = raw.bool_props.get("layer_plot").copied().unwrap_or(true);
+        if let Some(color) = raw.color {
+            layer.color = color;
+        }
+        if let Some(index) = raw.line_weight {
+            layer.line_weight = line_weight_from_index(index);
+        }
+        if let Some(line_type) = self.handle_name(raw, "layer_linetype_handle") {
+            layer.line_type = line_type;
+        }
+        layer.material = handle(raw, "layer_material_handle").unwrap_or(Handle::NULL);
+        layer
+    }
+
+    fn build_dim_style(&self, raw: &DwgRawObject, name: String) -> DimStyle {
+        let mut style = DimStyle::new(name);
+        style.handle = Handle::new(raw.handle);
+
+        let real = |key: &str, value: &mut f64| {
+            if let Some(&v) = raw.float_props.get(key) {
+                *value = v;
+            }
+        };
+        real("dimscale", &mut style.dimscale);
+        real("dimasz", &mut style.dimasz);
+        real("dimexo", &mut style.dimexo);
+        real("dimdli", &mut style.dimdli);
+        real("dimexe", &mut style.dimexe);
+        real("dimrnd", &mut style.dimrnd);
+        real("dimdle", &mut style.dimdle);
+        real("dimtp", &mut style.dimtp);
+        real("dimtm", &mut style.dimtm);
+        real("dimfxl", &mut style.dimfxl);
+        real("dimjogang", &mut style.dimjogang);
+        real("dimtxt", &mut style.dimtxt);
+        real("dimcen", &mut style.dimcen);
+        real("dimtsz", &mut style.dimtsz);
+        real("dimaltf", &mut style.dimaltf);
+        real("dimlfac", &mut style.dimlfac);
+        real("dimtvp", &mut style.dimtvp);
+        real("dimtfac", &mut style.dimtfac);
+        real("dimgap", &mut style.dimgap);
+        real("dimaltrnd", &mut style.dimaltrnd);
+
+        let short = |key: &str, value: &mut i16| {
+            if let Some(&v) = raw.int_props.get(key) {
+                *value = v as i16;
+            }
+        };
+        short("dimtfill", &mut style.dimtfill);
+        short("dimtad", &mut style.dimtad);
+        short("dimzin", &mut style.dimzin);
+        short("dimazin", &mut style.dimazin);
+        short("dimarcsym", &mut style.dimarcsym);
+        short("dimaltd", &mut style.dimaltd);
+        short("dimclrd", &mut style.dimclrd);
+        short("dimclre", &mut style.dimclre);
+        short("dimclrt", &mut style.dimclrt);
+        short("dimadec", &mut style.dimadec);
+        short("dimdec", &mut style.dimdec);
+        short("dimtdec", &mut style.dimtdec);
+        short("dimaltu", &mut style.dimaltu);
+        short("dimalttd", &mut style.dimalttd);
+        short("dimaunit", &mut style.dimaunit);
+        short("dimfrac", &mut style.dimfrac);
+        short("dimlunit", &mut style.dimlunit);
+        short("dimdsep", &mut style.dimdsep);
+        short("dimtmove", &mut style.dimtmove);
+        short("dimjust", &mut style.dimjust);
+        short("dimtolj", &mut style.dimtolj);
+        short("dimtzin", &mut style.dimtzin);
+        short("dimaltz", &mut style.dimaltz);
+        short("dimalttz", &mut style.dimalttz);
+        short("dimatfit", &mut style.dimatfit);
+        short("dimfit", &mut style.dimfit);
+        short("dimunit", &mut style.dimunit);
+        short("dimlwd", &mut style.dimlwd);
+        short("dimlwe", &mut style.dimlwe);
+
+        let bit = |key: &str, value: &mut bool| {
+            if let Some(&v) = raw.bool_props.get(key) {
+                *value = v;
+            }
+        };
+        bit("dimtol", &mut style.dimtol);
+        bit("dimlim", &mut style.dimlim);
+        bit("dimtih", &mut style.dimtih);
+        bit("dimtoh", &mut style.dimtoh);
+        bit("dimse1", &mut style.dimse1);
+        bit("dimse2", &mut style.dimse2);
+        bit("dimalt", &mut style.dimalt);
+        bit("dimtofl", &mut style.dimtofl);
+        bit("dimsah", &mut style.dimsah);
+        bit("dimtix", &mut style.dimtix);
+        bit("dimsoxd", &mut style.dimsoxd);
+        bit("dimsd1", &mut style.dimsd1);
+        bit("dimsd2", &mut style.dimsd2);
+        bit("dimupt", &mut style.dimupt);
+        bit("dimfxlon", &mut style.dimfxlon);
+        bit("dimtxtdirection", &mut style.dimtxtdirection);
+
+        if let Some(post) = raw.text_props.get("dimpost") {
+            style.dimpost = post.clone();
+        }
+        if let Some(apost) = raw.text_props.get("dimapost") {
+            style.dimapost = apost.clone();
+        }
+
+        if let Some(text_style) = handle(raw, "dimtxsty") {
+            style.dimtxsty_handle = text_style;
+            if let Some(name) = self.try_get_name(text_style.value()) {
+                style.dimtxsty = name.to_string();
+            }
+        }
+        for (key, target) in [
+            ("dimblk", &mut style.dimblk),
+            ("dimblk1", &mut style.dimblk1),
+            ("dimblk2", &mut style.dimblk2),
+            ("dimldrblk", &mut style.dimldrblk),
+            ("dimltype", &mut style.dimltex_handle),
+            ("dimltex1", &mut style.dimltex1_handle),
+            ("dimltex2", &mut style.dimltex2_handle),
+        ] {
+            if let Some(h) = handle(raw, key) {
+                *target = h;
+            }
+        }
+        style
+    }
+
+    /// Create or update the block records referenced by BLOCK / ENDBLK entities.
+    ///
+    /// The owner of a BLOCK entity is its block record, so the record handle
+    /// and name are both known once the BLOCK entity has been read.
+    fn build_block_records(&mut self, raw_objects: &[DwgRawObject]) {
+        let model_space = self.model_space_handle();
+        let paper_space = self.paper_space_handle();
+
+        for (name, handle) in [("*Model_Space", model_space), ("*Paper_Space", paper_space)] {
+            if let Some(record) = self.document.block_records.get_mut(name) {
+                record.handle = Handle::new(handle);
+            }
+            self.register_name(handle, name.to_string());
+        }
+
+        for raw in raw_objects {
+            if raw.raw_type != Some(RawObjectType::Block) {
+                continue;
+            }
+            let Some(record_handle) = raw.owner_handle else {
+                continue;
+            };
+            let name = match record_handle {
+                h if h == model_space => "*Model_Space".to_string(),
+                h if h == paper_space => "*Paper_Space".to_string(),
+                _ => match raw.text_props.get("block_name") {
+                    Some(name) if !name.is_empty() => name.clone(),
+                    _ => continue,
+                },
+            };
+
+            if self.document.block_records.get(&name).is_none() {
+                let _ = self.document.block_records.add(BlockRecord::new(name.clone()));
+            }
+            if let Some(record) = self.document.block_records.get_mut(&name) {
+                record.handle = Handle::new(record_handle);
+                record.block_entity_handle = Handle::new(raw.handle);
+            }
+            self.register_name(record_handle, name);
+        }
+
+        for raw in raw_objects {
+            if raw.raw_type != Some(RawObjectType::EndBlk) {
+                continue;
+            }
+            let Some(name) = raw.owner_handle.and_then(|h| self.try_get_name(h)) else {
+                continue;
+            };
+            let name = name.to_string();
+            if let Some(record) = self.document.block_records.get_mut(&name) {
+                record.block_end_handle = Handle::new(raw.handle);
+            }
+        }
+
+        self.document.header.model_space_block_handle = Handle::new(model_space);
+        self.document.header.paper_space_block_handle = Handle::new(paper_space);
+    }
+
+    /// Convert non-graphical objects and store them in `document.objects`.
+    fn build_objects(&mut self, raw_objects: &[DwgRawObject]) {
+        let mut unsupported: BTreeMap<String, usize> = BTreeMap::new();
+
+        for raw in raw_objects {
+            let Some(raw_type) = raw.raw_type else {
+                continue;
+            };
+            if self.is_entity(raw) || is_table_object(raw_type) {
+                continue;
+            }
+
+            if !matches!(
+                raw_type,
+                RawObjectType::Dictionary
+                    | RawObjectType::DictionaryWithDefault
+                    | RawObjectType::XRecord
+            ) {
+                *unsupported.entry(self.dxf_name(raw)).or_default() += 1;
+            }
+
+            if let Some(object) = self.build_object(raw) {
+                self.document.objects.insert(Handle::new(raw.handle), object);
+            }
+        }
+
+        for (type_name, count) in unsupported {
+            self.notify(
+                format!("Object not supported: {} ({} instances)", type_name, count),
+                NotificationType::NotImplemented,
+            );
+        }
+    }
+
+    /// Convert a raw non-graphical object into its typed representation.
+    ///
+    /// Returns `None` for object types that are not decoded (yet), unless the
+    /// configuration asks to keep unknown objects.
+    fn build_object(&self, raw: &DwgRawObject) -> Option<ObjectType> {
+        let object = match raw.raw_type? {
+            RawObjectType::Dictionary => ObjectType::Dictionary(self.build_dictionary(raw)),
+            RawObjectType::DictionaryWithDefault => {
+                ObjectType::DictionaryWithDefault(self.build_dictionary_with_default(raw))
+            }
+            RawObjectType::XRecord => ObjectType::XRecord(self.build_xrecord(raw)),
+            _ if self.keep_unknown_non_graphical_objects() => ObjectType::Unknown {
+                type_name: self.dxf_name(raw),
+                handle: Handle::new(raw.handle),
+                codes: Vec::new(),
+            },
+            _ => return None,
+        };
+        Some(object)
+    }
+
+    /// Convert entities and attach them to model space, paper space or their block record.
+    fn build_entities(&mut self, raw_objects: &[DwgRawObject]) {
+        let model_space = self.model_space_handle();
+        let paper_space = self.paper_space_handle();
+
+        // Effective owner of every entity, used to follow ATTRIB -> INSERT chains.
+        let owners: HashMap<u64, u64> = raw_objects
+            .iter()
+            .filter(|raw| self.is_entity(raw))
+            .filter_map(|raw| self.entity_owner(raw, model_space, paper_space).map(|o| (raw.handle, o)))
+            .collect();
+
+        // Vertices are looked up by handle when their polyline is built.
+        let objects: HashMap<u64, &DwgRawObject> =
+            raw_objects.iter().map(|raw| (raw.handle, raw)).collect();
+
+        let mut unsupported: BTreeMap<String, usize> = BTreeMap::new();
+
+        for raw in raw_objects {
+            if !self.is_entity(raw) || raw.raw_type.is_some_and(is_vertex) {
+                continue;
+            }
+
+            let entity = match self.build_entity(raw, &objects) {
+                Some(entity) => entity,
+                None => {
+                    if !matches!(
+                        raw.raw_type,
+                        Some(RawObjectType::Block | RawObjectType::EndBlk | RawObjectType::SeqEnd)
+                    ) {
+                        *unsupported.entry(self.dxf_name(raw)).or_default() += 1;
+                    }
+                    continue;
+                }
+            };
+
+            let container = owners.get(&raw.handle).and_then(|&owner| {
+                self.resolve_container(owner, &owners, model_space, paper_space)
+            });
+
+            match container {
+                Some(EntityContainer::ModelSpace) | Some(EntityContainer::PaperSpace) => {
+                    let _ = self.document.add_entity(entity);
+                }
+                Some(EntityContainer::Block(record_handle)) => {
+                    let name = self.try_get_name(record_handle).map(str::to_string);
+                    match name.and_then(|n| self.document.block_records.get_mut(&n)) {
+                        Some(record) => record.entities.push(entity),
+                        None => self.notify(
+                            format!(
+                                "Block record {:#X} not found for entity {:#X}",
+                                record_handle, raw.handle
+                            ),
+                            NotificationType::Warning,
+                        ),
+                    }
+                }
+                None => self.notify(
+                    format!("Owner not found for entity {:#X}", raw.handle),
+                    NotificationType::Warning,
+                ),
+            }
+        }
+
+        for (type_name, count) in unsupported {
+            self.notify(
+                format!("Entity not supported: {} ({} instances)", type_name, count),
+                NotificationType::NotImplemented,
+            );
+        }
+    }
+
+    /// Owner handle of an entity, falling back to the entity mode for
+    /// model / paper space entities that do not store it explicitly.
+    fn entity_owner(&self, raw: &DwgRawObject, model_space: u64, paper_space: u64) -> Option<u64> {
+        match raw.int_props.get("entity_mode") {
+            Some(1) => Some(paper_space),
+            Some(2) => Some(model_space),
+            _ => raw.owner_handle.filter(|&h| h != 0),
+        }
+    }
+
+    /// Walk the owner chain (ATTRIB -> INSERT -> block) up to a container.
+    fn resolve_container(
+        &self,
+        mut owner: u64,
+        owners: &HashMap<u64, u64>,
+        model_space: u64,
+        paper_space: u64,
+    ) -> Option<EntityContainer> {
+        for _ in 0..8 {
+            if owner == model_space {
+                return Some(EntityContainer::ModelSpace);
+            }
+            if owner == paper_space {
+                return Some(EntityContainer::PaperSpace);
+            }
+            if self.try_get_name(owner).is_some() && !owners.contains_key(&owner) {
+                return Some(EntityContainer::Block(owner));
+            }
+            owner = *owners.get(&owner)?;
+        }
+        None
+    }
+
+    /// Whether a raw object was decoded as an entity.
+    fn is_entity(&self, raw: &DwgRawObject) -> bool {
+        raw.int_props.contains_key("entity_mode")
+    }
+
+    /// DXF name of a raw object, using the class table for custom types.
+    fn dxf_name(&self, raw: &DwgRawObject) -> String {
+        match raw.raw_type {
+            Some(RawObjectType::Unknown(code)) => self
+                .document
+                .classes
+                .iter()
+                .find(|c| c.class_number == code as i16)
+                .map(|c| c.dxf_name.clone())
+                .unwrap_or_else(|| format!("UNKNOWN({})", code)),
+            Some(raw_type) => fixed_dxf_name(raw_type).to_string(),
+            None => "UNKNOWN".to_string(),
+        }
+    }
+
+    /// Convert a raw entity into its typed representation.
+    ///
+    /// Returns `None` for entity types that are not decoded (yet), unless the
+    /// configuration asks to keep unknown entities.
+    fn build_entity(
+        &mut self,
+        raw: &DwgRawObject,
+        objects: &HashMap<u64, &DwgRawObject>,
+    ) -> Option<EntityType> {
+        let common = self.entity_common(raw);
+        let entity = match raw.raw_type? {
+            RawObjectType::Text => EntityType::Text(self.build_text(raw, common)),
+            RawObjectType::Attrib => EntityType::AttributeEntity(self.build_attribute(raw, common)),
+            RawObjectType::AttDef => {
+                EntityType::AttributeDefinition(self.build_attribute_definition(raw, common))
+            }
+            RawObjectType::Insert | RawObjectType::MInsert => {
+                EntityType::Insert(self.build_insert(raw, common))
+            }
+            RawObjectType::MText => EntityType::MText(self.build_mtext(raw, common)),
+            RawObjectType::Leader => EntityType::Leader(self.build_leader(raw, common)),
+            RawObjectType::Line => EntityType::Line(self.build_line(raw, common)),
+            RawObjectType::Point => EntityType::Point(self.build_point(raw, common)),
+            RawObjectType::Circle => EntityType::Circle(self.build_circle(raw, common)),
+            RawObjectType::Arc => EntityType::Arc(self.build_arc(raw, common)),
+            RawObjectType::Ellipse => EntityType::Ellipse(self.build_ellipse(raw, common)),
+            RawObjectType::Spline => EntityType::Spline(self.build_spline(raw, common)),
+            RawObjectType::LwPolyline => {
+                EntityType::LwPolyline(self.build_lw_polyline(raw, common))
+            }
+            RawObjectType::Polyline2D => {
+                let vertices = self.polyline_vertices(raw, objects);
+                EntityType::Polyline2D(self.build_polyline_2d(raw, common, &vertices))
+            }
+            RawObjectType::Polyline3D => {
+                let vertices = self.polyline_vertices(raw, objects);
+                EntityType::Polyline3D(self.build_polyline_3d(raw, common, &vertices))
+            }
+            RawObjectType::PolylinePFace => {
+                let vertices = self.polyline_vertices(raw, objects);
+                EntityType::PolyfaceMesh(self.build_polyface_mesh(raw, common, &vertices))
+            }
+            RawObjectType::PolylineMesh => {
+                let vertices = self.polyline_vertices(raw, objects);
+                EntityType::PolygonMesh(self.build_polygon_mesh(raw, common, &vertices))
+            }
+            RawObjectType::Solid => EntityType::Solid(self.build_solid(raw, common)),
+            RawObjectType::Face3D => EntityType::Face3D(self.build_face3d(raw, common)),
+            RawObjectType::Ray => {
+                let mut ray = Ray::new(point3(raw, "base_point"), point3(raw, "direction"));
+                ray.common = common;
+                EntityType::Ray(ray)
+            }
+            RawObjectType::XLine => {
+                let mut xline = XLine::new(point3(raw, "base_point"), point3(raw, "direction"));
+                xline.common = common;
+                EntityType::XLine(xline)
+            }
+            RawObjectType::Shape => EntityType::Shape(self.build_shape(raw, common)),
+            RawObjectType::Viewport => EntityType::Viewport(self.build_viewport(raw, common)),
+            RawObjectType::MLine => EntityType::MLine(self.build_mline(raw, common)),
+            RawObjectType::Tolerance => EntityType::Tolerance(self.build_tolerance(raw, common)),
+            RawObjectType::OLE2Frame => EntityType::Ole2Frame(self.build_ole2_frame(raw, common)),
+            RawObjectType::Solid3D => {
+                let geometry = self.build_modeler_geometry(raw);
+                let mut solid = Solid3D::new();
+                solid.common = common;
+                solid.point_of_reference = geometry.point_of_reference;
+                solid.acis_data = geometry.acis_data;
+                solid.wires = geometry.wires;
+                solid.silhouettes = geometry.silhouettes;
+                solid.history_handle = handle(raw, "history_handle");
+                EntityType::Solid3D(solid)
+            }
+            RawObjectType::Region => {
+                let geometry = self.build_modeler_geometry(raw);
+                let mut region = Region::new();
+                region.common = common;
+                region.point_of_reference = geometry.point_of_reference;
+                region.acis_data = geometry.acis_data;
+                region.wires = geometry.wires;
+                region.silhouettes = geometry.silhouettes;
+                EntityType::Region(region)
+            }
+            RawObjectType::Body => {
+                let geometry = self.build_modeler_geometry(raw);
+                let mut body = Body::new();
+                body.common = common;
+                body.point_of_reference = geometry.point_of_reference;
+                body.acis_data = geometry.acis_data;
+                body.wires = geometry.wires;
+                body.silhouettes = geometry.silhouettes;
+                EntityType::Body(body)
+            }
+            RawObjectType::Block | RawObjectType::EndBlk | RawObjectType::SeqEnd => return None,
+            // Entities with proxy graphics are kept so the drawing still
+            // shows them.
+            _ if self.keep_unknown_entities() || raw.binary_props.contains_key("proxy_graphics") => {
+                let mut unknown = UnknownEntity::new(self.dxf_name(raw));
+                unknown.common = common;
+                unknown.proxy_graphics = self.build_proxy_graphics(raw);
+                EntityType::Unknown(unknown)
+            }
+            _ => return None,
+        };
+        Some(entity)
+    }
+
+    /// Decode the proxy graphics of an entity. An undecodable stream is
+    /// still kept so it is written back unchanged.
+    fn build_proxy_graphics(&mut self, raw: &DwgRawObject) -> Option<ProxyGraphics> {
+        let data = raw.binary_props.get("proxy_graphics")?.clone();
+        match ProxyGraphics::from_data(data.clone()) {
+            Ok(graphics) => Some(graphics),
+            Err(e) => {
+                self.notify(
+                    format!("Could not decode the proxy graphics of entity {:#X}: {}", raw.handle, e),
+                    NotificationType::Warning,
+                );
+                Some(ProxyGraphics::undecoded(data))
+            }
+        }
+    }
+
+    /// Fill the data shared by every entity.
+    fn entity_common(&mut self, raw: &DwgRawObject) -> EntityCommon {
+        let mut common = EntityCommon::new();
+        common.handle = Handle::new(raw.handle);
+        if let Some(layer) = raw.handle_props.get("layer_handle").and_then(|&h| self.try_get_name(h)) {
+            common.layer = layer.to_string();
+        }
+        if let Some(color) = raw.color {
+            common.color = color;
+        }
+        if let Some(transparency) = raw.transparency {
+            common.transparency = transparency;
+        }
+        if let Some(index) = raw.line_weight {
+            common.line_weight = line_weight_from_index(index);
+        }
+        common.invisible = raw.bool_props.get("invisible").copied().unwrap_or(false);
+        common.reactors = raw.reactors.iter().map(|&h| Handle::new(h)).collect();
+        common.xdictionary_handle = raw.xdict_handle.filter(|&h| h != 0).map(Handle::new);
+        let model_space = self.model_space_handle();
+        let paper_space = self.paper_space_handle();
+        if let Some(owner) = self.entity_owner(raw, model_space, paper_space) {
+            common.owner_handle = Handle::new(owner);
+        }
+        common.extended_data = self.build_extended_data(raw);
+        common
+    }
+
+    /// Convert the raw EED blocks, resolving application handles to APPID names.
+    fn build_extended_data(&mut self, raw: &DwgRawObject) -> ExtendedData {
+        let mut data = ExtendedData::new();
+        for (&app_handle, records) in &raw.eed {
+            let Some(app_name) = self.try_get_name(app_handle).map(str::to_string) else {
+                self.notify(
+                    format!(
+                        "Extended data of {:#X} references unknown application {:#X}",
+                        raw.handle, app_handle
+                    ),
+                    NotificationType::Warning,
+                );
+                continue;
+            };
+
+            let mut record = ExtendedDataRecord::new(app_name);
+            for r in records {
+                let value = match r.code {
+                    1000 => XDataValue::String(r.text.clone().unwrap_or_default()),
+                    1002 => XDataValue::ControlString(
+                        if r.integer.unwrap_or(0) == 0 { "{" } else { "}" }.to_string(),
+                    ),
+                    1003 => {
+                        let handle = handle_from_bytes(&r.bytes);
+                        XDataValue::LayerName(
+                            self.try_get_name(handle)
+                                .map(str::to_string)
+                                .unwrap_or_else(|| format!("{:X}", handle)),
+                        )
+                    }
+                    1004 => XDataValue::BinaryData(r.bytes.clone()),
+                    1005 => XDataValue::Handle(Handle::new(handle_from_bytes(&r.bytes))),
+                    1010 => XDataValue::Point3D(r.point.unwrap_or(Vector3::ZERO)),
+                    1011 => XDataValue::Position3D(r.point.unwrap_or(Vector3::ZERO)),
+                    1012 => XDataValue::Displacement3D(r.point.unwrap_or(Vector3::ZERO)),
+                    1013 => XDataValue::Direction3D(r.point.unwrap_or(Vector3::ZERO)),
+                    1040 => XDataValue::Real(r.number.unwrap_or(0.0)),
+                    1041 => XDataValue::Distance(r.number.unwrap_or(0.0)),
+                    1042 => XDataValue::ScaleFactor(r.number.unwrap_or(0.0)),
+                    1070 => XDataValue::Integer16(r.integer.unwrap_or(0) as i16),
+                    1071 => XDataValue::Integer32(r.integer.unwrap_or(0) as i32),
+                    _ => continue,
+                };
+                record.add_value(value);
+            }
+            data.add_record(record);
+        }
+        data
+    }
+
+    fn build_text(&self, raw: &DwgRawObject, common: EntityCommon) -> Text {
+        let mut text = Text::new();
+        text.common = common;
+        text.value = raw.text_props.get("value").cloned().unwrap_or_default();
+        text.insertion_point = point3(raw, "insert_point");
+        text.height = raw.float_props.get("height").copied().unwrap_or(text.height);
+        text.rotation = raw.float_props.get("rotation").copied().unwrap_or(0.0);
+        text.width_factor = raw.float_props.get("width_factor").copied().unwrap_or(1.0);
+        text.oblique_angle = raw.float_props.get("oblique_angle").copied().unwrap_or(0.0);
+        text.normal = raw.point3_props.get("normal").copied().unwrap_or(Vector3::UNIT_Z);
+        text.horizontal_alignment =
+            text_horizontal_alignment(int(raw, "horizontal_alignment") as i16);
+        text.vertical_alignment = text_vertical_alignment(int(raw, "vertical_alignment") as i16);
+        if text.horizontal_alignment != TextHorizontalAlignment::Left
+            || text.vertical_alignment != TextVerticalAlignment::Baseline
+        {
+            text.alignment_point = raw.point3_props.get("alignment_point").copied();
+        }
+        if let Some(style) = self.handle_name(raw, "style_handle") {
+            text.style = style;
+        }
+        text
+    }
+
+    fn build_attribute(&self, raw: &DwgRawObject, common: EntityCommon) -> AttributeEntity {
+        let mut attrib = AttributeEntity::new(
+            raw.text_props.get("attribute_tag").cloned().unwrap_or_default(),
+            raw.text_props.get("value").cloned().unwrap_or_default(),
+        );
+        attrib.common = common;
+        attrib.insertion_point = point3(raw, "insert_point");
+        attrib.alignment_point = raw
+            .point3_props
+            .get("alignment_point")
+            .copied()
+            .unwrap_or(attrib.insertion_point);
+        attrib.height = raw.float_props.get("height").copied().unwrap_or(attrib.height);
+        attrib.rotation = raw.float_props.get("rotation").copied().unwrap_or(0.0);
+        attrib.width_factor = raw.float_props.get("width_factor").copied().unwrap_or(1.0);
+        attrib.oblique_angle = raw.float_props.get("oblique_angle").copied().unwrap_or(0.0);
+        attrib.normal = raw.point3_props.get("normal").copied().unwrap_or(Vector3::UNIT_Z);
+        attrib.text_generation_flags = int(raw, "mirror") as i16;
+        attrib.horizontal_alignment =
+            HorizontalAlignment::from_value(int(raw, "horizontal_alignment") as i16);
+        attrib.vertical_alignment =
+            VerticalAlignment::from_value(int(raw, "vertical_alignment") as i16);
+        attrib.flags = AttributeFlags::from_bits(int(raw, "attribute_flags") as i32);
+        attrib.field_length = int(raw, "attribute_field_length") as i16;
+        attrib.mtext_flag = MTextFlag::from_value(int(raw, "attribute_type") as i16);
+        attrib.is_multiline = attrib.mtext_flag != MTextFlag::SingleLine;
+        attrib.lock_position = raw
+            .bool_props
+            .get("attribute_lock_position")
+            .copied()
+            .unwrap_or(false);
+        if let Some(style) = self.handle_name(raw, "style_handle") {
+            attrib.text_style = style;
+        }
+        attrib
+    }
+
+    fn build_attribute_definition(
+        &self,
+        raw: &DwgRawObject,
+        common: EntityCommon,
+    ) -> AttributeDefinition {
+        let mut attdef = AttributeDefinition::new(
+            raw.text_props.get("attribute_tag").cloned().unwrap_or_default(),
+            raw.text_props.get("attdef_prompt").cloned().unwrap_or_default(),
+            raw.text_props.get("value").cloned().unwrap_or_default(),
+        );
+        attdef.common = common;
+        attdef.insertion_point = point3(raw, "insert_point");
+        attdef.alignment_point = raw
+            .point3_props
+            .get("alignment_point")
+            .copied()
+            .unwrap_or(attdef.insertion_point);
+        attdef.height = raw.float_props.get("height").copied().unwrap_or(attdef.height);
+        attdef.rotation = raw.float_props.get("rotation").copied().unwrap_or(0.0);
+        attdef.width_factor = raw.float_props.get("width_factor").copied().unwrap_or(1.0);
+        attdef.oblique_angle = raw.float_props.get("oblique_angle").copied().unwrap_or(0.0);
+        attdef.normal = raw.point3_props.get("normal").copied().unwrap_or(Vector3::UNIT_Z);
+        attdef.text_generation_flags = int(raw, "mirror") as i16;
+        attdef.horizontal_alignment =
+            HorizontalAlignment::from_value(int(raw, "horizontal_alignment") as i16);
+        attdef.vertical_alignment =
+            VerticalAlignment::from_value(int(raw, "vertical_alignment") as i16);
+        attdef.flags = AttributeFlags::from_bits(int(raw, "attribute_flags") as i32);
+        attdef.field_length = int(raw, "attribute_field_length") as i16;
+        attdef.mtext_flag = MTextFlag::from_value(int(raw, "attribute_type") as i16);
+        attdef.is_multiline = attdef.mtext_flag != MTextFlag::SingleLine;
+        attdef.lock_position = raw
+            .bool_props
+            .get("attribute_lock_position")
+            .copied()
+            .unwrap_or(false);
+        if let Some(style) = self.handle_name(raw, "style_handle") {
+            attdef.text_style = style;
+        }
+        attdef
+    }
+
+    fn build_insert(&self, raw: &DwgRawObject, common: EntityCommon) -> Insert {
+        let block_name = self.handle_name(raw, "block_header_handle").unwrap_or_default();
+        let mut insert = Insert::new(block_name, point3(raw, "insert_point"));
+        insert.common = common;
+        insert.x_scale = raw.float_props.get("x_scale").copied().unwrap_or(1.0);
+        insert.y_scale = raw.float_props.get("y_scale").copied().unwrap_or(1.0);
+        insert.z_scale = raw.float_props.get("z_scale").copied().unwrap_or(1.0);
+        insert.rotation = raw.float_props.get("rotation").copied().unwrap_or(0.0);
+        insert.normal = raw.point3_props.get("normal").copied().unwrap_or(Vector3::UNIT_Z);
+        if raw.raw_type == Some(RawObjectType::MInsert) {
+            insert.column_count = int(raw, "column_count").max(1) as u16;
+            insert.row_count = int(raw, "row_count").max(1) as u16;
+            insert.column_spacing = raw.float_props.get("column_spacing").copied().unwrap_or(0.0);
+            insert.row_spacing = raw.float_props.get("row_spacing").copied().unwrap_or(0.0);
+        }
+        insert
+    }
+
+    fn build_mtext(&self, raw: &DwgRawObject, common: EntityCommon) -> MText {
+        let mut mtext = MText::new();
+        mtext.common = common;
+        mtext.value = raw.text_props.get("mtext_value").cloned().unwrap_or_default();
+        mtext.insertion_point = point3(raw, "mtext_insert_point");
+        mtext.normal = raw
+            .point3_props
+            .get("mtext_normal")
+            .copied()
+            .unwrap_or(Vector3::UNIT_Z);
+        if let Some(dir) = raw.point3_props.get("mtext_x_axis_dir") {
+            mtext.rotation = dir.y.atan2(dir.x);
+        }
+        mtext.rectangle_width = raw.float_props.get("mtext_rect_width").copied().unwrap_or(0.0);
+        mtext.rectangle_height = raw
+            .float_props
+            .get("mtext_rect_height")
+            .copied()
+            .filter(|h| *h != 0.0);
+        mtext.height = raw.float_props.get("mtext_height").copied().unwrap_or(mtext.height);
+        mtext.attachment_point = attachment_point(int(raw, "mtext_attachment") as i16);
+        mtext.drawing_direction = drawing_direction(int(raw, "mtext_drawing_dir") as i16);
+        mtext.line_spacing_factor = raw.float_props.get("mtext_line_spacing").copied().unwrap_or(1.0);
+        if let Some(style) = self.handle_name(raw, "mtext_style_handle") {
+            mtext.style = style;
+        }
+        mtext
+    }
+
+    fn build_leader(&self, raw: &DwgRawObject, common: EntityCommon) -> Leader {
+        let mut leader = Leader::new();
+        leader.common = common;
+        leader.arrow_enabled = raw
+            .bool_props
+            .get("leader_arrow_enabled")
+            .copied()
+            .unwrap_or(true);
+        leader.path_type = if int(raw, "leader_path_type") == 1 {
+            LeaderPathType::Spline
+        } else {
+            LeaderPathType::StraightLine
+        };
+        leader.creation_type = match int(raw, "leader_creation_type") {
+            0 => LeaderCreationType::WithText,
+            1 => LeaderCreationType::WithTolerance,
+            2 => LeaderCreationType::WithBlock,
+            _ => LeaderCreationType::NoAnnotation,
+        };
+        leader.hookline_direction = if raw
+            .bool_props
+            .get("leader_hook_line_same_dir")
+            .copied()
+            .unwrap_or(false)
+        {
+            HooklineDirection::Same
+        } else {
+            HooklineDirection::Opposite
+        };
+        leader.text_height = raw.float_props.get("leader_text_height").copied().unwrap_or(0.0);
+        leader.text_width = raw.float_props.get("leader_text_width").copied().unwrap_or(0.0);
+        leader.vertices = raw
+            .point3_list_props
+            .get("leader_vertices")
+            .cloned()
+            .unwrap_or_default();
+        leader.normal = raw
+            .point3_props
+            .get("leader_normal")
+            .copied()
+            .unwrap_or(Vector3::UNIT_Z);
+        leader.horizontal_direction = raw
+            .point3_props
+            .get("leader_horizontal_dir")
+            .copied()
+            .unwrap_or(Vector3::UNIT_X);
+        leader.block_offset = point3(raw, "leader_block_offset");
+        leader.annotation_offset = point3(raw, "leader_annotation_offset");
+        leader.annotation_handle = raw
+            .handle_props
+            .get("leader_annotation_handle")
+            .map(|&h| Handle::new(h))
+            .unwrap_or(Handle::NULL);
+        if let Some(style) = self.handle_name(raw, "leader_dimstyle_handle") {
+            leader.dimension_style = style;
+        }
+        leader
+    }
+
+    fn build_line(&self, raw: &DwgRawObject, common: EntityCommon) -> Line {
+        let mut line = Line::from_points(point3(raw, "start_point"), point3(raw, "end_point"));
+        line.common = common;
+        line.thickness = float(raw, "thickness");
+        line.normal = normal(raw);
+        line
+    }
+
+    fn build_point(&self, raw: &DwgRawObject, common: EntityCommon) -> Point {
+        let mut point = Point::new();
+        point.common = common;
+        point.location = point3(raw, "location");
+        point.thickness = float(raw, "thickness");
+        point.normal = normal(raw);
+        point
+    }
+
+    fn build_circle(&self, raw: &DwgRawObject, common: EntityCommon) -> Circle {
+        let mut circle = Circle::from_center_radius(point3(raw, "center"), float(raw, "radius"));
+        circle.common = common;
+        circle.thickness = float(raw, "thickness");
+        circle.normal = normal(raw);
+        circle
+    }
+
+    fn build_arc(&self, raw: &DwgRawObject, common: EntityCommon) -> Arc {
+        let mut arc = Arc::new();
+        arc.common = common;
+        arc.center = point3(raw, "center");
+        arc.radius = float(raw, "radius");
+        arc.start_angle = float(raw, "start_angle");
+        arc.end_angle = float(raw, "end_angle");
+        arc.thickness = float(raw, "thickness");
+        arc.normal = normal(raw);
+        arc
+    }
+
+    fn build_ellipse(&self, raw: &DwgRawObject, common: EntityCommon) -> Ellipse {
+        let mut ellipse = Ellipse::from_center_axes(
+            point3(raw, "center"),
+            point3(raw, "major_axis"),
+            raw.float_props.get("axis_ratio").copied().unwrap_or(1.0),
+        );
+        ellipse.common = common;
+        ellipse.normal = normal(raw);
+        ellipse.start_parameter = float(raw, "start_parameter");
+        ellipse.end_parameter = raw
+            .float_props
+            .get("end_parameter")
+            .copied()
+            .unwrap_or(std::f64::consts::TAU);
+        ellipse
+    }
+
+    fn build_spline(&self, raw: &DwgRawObject, common: EntityCommon) -> Spline {
+        let mut spline = Spline::new();
+        spline.common = common;
+        spline.degree = int(raw, "degree") as i32;
+        spline.flags.rational = flag(raw, "rational");
+        spline.flags.closed = flag(raw, "closed");
+        spline.flags.periodic = flag(raw, "periodic");
+        spline.knots = raw.float_list_props.get("knots").cloned().unwrap_or_default();
+        spline.weights = raw.float_list_props.get("weights").cloned().unwrap_or_default();
+        spline.control_points = raw
+            .point3_list_props
+            .get("control_points")
+            .cloned()
+            .unwrap_or_default();
+        spline.fit_points = raw
+            .point3_list_props
+            .get("fit_points")
+            .cloned()
+            .unwrap_or_default();
+        spline
+    }
+
+    /// Vertices of a polyline, in order: the owned object list for R2004+,
+    /// the chain of next entity links from the first to the last vertex
+    /// before.
+    fn polyline_vertices<'a>(
+        &mut self,
+        raw: &DwgRawObject,
+        objects: &HashMap<u64, &'a DwgRawObject>,
+    ) -> Vec<&'a DwgRawObject> {
+        let handles = match raw.handle_list_props.get("owned_object_handles") {
+            Some(owned) => owned.clone(),
+            None => {
+                let last = raw.handle_props.get("last_vertex_handle").copied().unwrap_or(0);
+                let mut chain = Vec::new();
+                let mut visited = HashSet::new();
+                let mut current = raw.handle_props.get("first_vertex_handle").copied().unwrap_or(0);
+                while current != 0 && visited.insert(current) {
+                    chain.push(current);
+                    if current == last {
+                        break;
+                    }
+                    current = objects
+                        .get(&current)
+                        .and_then(|vertex| vertex.handle_props.get("next_entity_handle"))
+                        .copied()
+                        .unwrap_or(0);
+                }
+                chain
+            }
+        };
+
+        let mut vertices = Vec::with_capacity(handles.len());
+        for h in handles {
+            match objects.get(&h) {
+                Some(vertex) if vertex.raw_type.is_some_and(is_vertex) => vertices.push(*vertex),
+                _ => self.notify(
+                    format!("Vertex {:#X} of polyline {:#X} not found", h, raw.handle),
+                    NotificationType::Warning,
+                ),
+            }
+        }
+        vertices
+    }
+
+    fn build_polyline_2d(
+        &mut self,
+        raw: &DwgRawObject,
+        common: EntityCommon,
+        vertices: &[&DwgRawObject],
+    ) -> Polyline2D {
+        let mut polyline = Polyline2D::new();
+        polyline.common = common;
+        polyline.flags = PolylineFlags::from_bits(int(raw, "polyline_flags") as u16);
+        polyline.smooth_surface = SmoothSurfaceType::from(int(raw, "curve_type") as i16);
+        polyline.start_width = float(raw, "start_width");
+        polyline.end_width = float(raw, "end_width");
+        polyline.thickness = float(raw, "thickness");
+        polyline.elevation = float(raw, "elevation");
+        polyline.normal = normal(raw);
+
+        for v in vertices.iter().filter(|v| v.raw_type == Some(RawObjectType::Vertex2D)) {
+            let mut vertex = Vertex2D::new(point3(v, "location"));
+            vertex.flags = VertexFlags::from_bits(int(v, "vertex_flags") as u8);
+            vertex.start_width = float(v, "start_width");
+            vertex.end_width = float(v, "end_width");
+            vertex.bulge = float(v, "bulge");
+            vertex.curve_tangent = float(v, "curve_tangent");
+            vertex.id = int(v, "vertex_id") as i32;
+            polyline.vertices.push(vertex);
+        }
+        polyline
+    }
+
+    fn build_polyline_3d(
+        &mut self,
+        raw: &DwgRawObject,
+        common: EntityCommon,
+        vertices: &[&DwgRawObject],
+    ) -> Polyline3D {
+        let mut polyline = Polyline3D::new();
+        polyline.common = common;
+        polyline.flags.closed = int(raw, "polyline_flags") & 1 != 0;
+        // Spline type: 1 = quadratic, 2 = cubic
+        polyline.smooth_type = match int(raw, "curve_type") {
+            1 => Polyline3DSmoothType::QuadraticBSpline,
+            2 => Polyline3DSmoothType::CubicBSpline,
+            _ => Polyline3DSmoothType::None,
+        };
+        polyline.flags.spline_fit = polyline.smooth_type != Polyline3DSmoothType::None;
+
+        for v in vertices.iter().filter(|v| v.raw_type == Some(RawObjectType::Vertex3D)) {
+            let mut vertex = Vertex3DPolyline::new(point3(v, "location"));
+            vertex.handle = Handle::new(v.handle);
+            vertex.flags = int(v, "vertex_flags") as i32;
+            if let Some(layer) = v.handle_props.get("layer_handle").and_then(|&h| self.try_get_name(h)) {
+                vertex.layer = layer.to_string();
+            }
+            polyline.vertices.push(vertex);
+        }
+        polyline
+    }
+
+    fn build_polyface_mesh(
+        &mut self,
+        raw: &DwgRawObject,
+        common: EntityCommon,
+        vertices: &[&DwgRawObject],
+    ) -> PolyfaceMesh {
+        let mut mesh = PolyfaceMesh::new();
+        mesh.common = common;
+        mesh.seqend_handle = handle(raw, "seqend_handle");
+
+        for v in vertices {
+            match v.raw_type {
+                Some(RawObjectType::VertexPFace) => {
+                    let mut vertex = PolyfaceVertex::new(point3(v, "location"));
+                    vertex.common = self.entity_common(v);
+                    vertex.flags =
+                        PolyfaceVertexFlags::from_bits_truncate(int(v, "vertex_flags") as i16);
+                    mesh.vertices.push(vertex);
+                }
+                Some(RawObjectType::VertexPFaceFace) => {
+                    mesh.faces.push(PolyfaceFace {
+                        common: self.entity_common(v),
+                        index1: int(v, "face_index_1") as i16,
+                        index2: int(v, "face_index_2") as i16,
+                        index3: int(v, "face_index_3") as i16,
+                        index4: int(v, "face_index_4") as i16,
+                        ..Default::default()
+                    });
+                }
+                _ => {}
+            }
+        }
+        mesh
+    }
+
+    fn build_polygon_mesh(
+        &mut self,
+        raw: &DwgRawObject,
+        common: EntityCommon,
+        vertices: &[&DwgRawObject],
+    ) -> PolygonMeshEntity {
+        let mut mesh = PolygonMeshEntity::new();
+        mesh.common = common;
+        mesh.flags = PolygonMeshFlags::from_bits_truncate(int(raw, "polyline_flags") as i16);
+        mesh.smooth_type = SurfaceSmoothType::from_i16(int(raw, "curve_type") as i16);
+        mesh.m_vertex_count = int(raw, "m_vertex_count") as i16;
+        mesh.n_vertex_count = int(raw, "n_vertex_count") as i16;
+        mesh.m_smooth_density = int(raw, "m_density") as i16;
+        mesh.n_smooth_density = int(raw, "n_density") as i16;
+
+        for v in vertices.iter().filter(|v| v.raw_type == Some(RawObjectType::VertexMesh)) {
+            let mut vertex = PolygonMeshVertex::at(point3(v, "location"));
+            vertex.common = self.entity_common(v);
+            vertex.flags = int(v, "vertex_flags") as i16;
+            mesh.vertices.push(vertex);
+        }
+        mesh
+    }
+
+    fn build_lw_polyline(&self, raw: &DwgRawObject, common: EntityCommon) -> LwPolyline {
+        let mut polyline = LwPolyline::new();
+        polyline.common = common;
+        polyline.is_closed = int(raw, "lwpolyline_flags") & 0x200 != 0;
+        polyline.constant_width = float(raw, "constant_width");
+        polyline.elevation = float(raw, "elevation");
+        polyline.thickness = float(raw, "thickness");
+        polyline.normal = normal(raw);
+
+        let bulges = raw.float_list_props.get("bulges");
+        let widths = raw.float_list_props.get("widths");
+        for (i, location) in raw
+            .point3_list_props
+            .get("vertices")
+            .into_iter()
+            .flatten()
+            .enumerate()
+        {
+            let mut vertex = LwVertex::new(Vector2::new(location.x, location.y));
+            if let Some(&bulge) = bulges.and_then(|b| b.get(i)) {
+                vertex.bulge = bulge;
+            }
+            if let Some(pair) = widths.and_then(|w| w.get(i * 2..i * 2 + 2)) {
+                vertex.start_width = pair[0];
+                vertex.end_width = pair[1];
+            }
+            polyline.vertices.push(vertex);
+        }
+        polyline
+    }
+
+    fn build_solid(&self, raw: &DwgRawObject, common: EntityCommon) -> Solid {
+        let mut solid = Solid::new(
+            point3(raw, "first_corner"),
+            point3(raw, "second_corner"),
+            point3(raw, "third_corner"),
+            point3(raw, "fourth_corner"),
+        );
+        solid.common = common;
+        solid.thickness = float(raw, "thickness");
+        solid.normal = normal(raw);
+        solid
+    }
+
+    fn build_face3d(&self, raw: &DwgRawObject, common: EntityCommon) -> Face3D {
+        let mut face = Face3D::new(
+            point3(raw, "first_corner"),
+            point3(raw, "second_corner"),
+            point3(raw, "third_corner"),
+            point3(raw, "fourth_corner"),
+        );
+        face.common = common;
+        face.invisible_edges = InvisibleEdgeFlags::from_bits(int(raw, "invisible_edges") as u8);
+        face
+    }
+
+    fn build_shape(&self, raw: &DwgRawObject, common: EntityCommon) -> Shape {
+        let mut shape = Shape::new();
+        shape.common = common;
+        shape.insertion_point = point3(raw, "insert_point");
+        shape.size = raw.float_props.get("size").copied().unwrap_or(shape.size);
+        shape.rotation = float(raw, "rotation");
+        shape.relative_x_scale = raw.float_props.get("width_factor").copied().unwrap_or(1.0);
+        shape.oblique_angle = float(raw, "oblique_angle");
+        shape.thickness = float(raw, "thickness");
+        shape.shape_number = int(raw, "shape_number") as i32;
+        shape.normal = normal(raw);
+        shape.style_handle = handle(raw, "style_handle");
+        if let Some(style) = self.handle_name(raw, "style_handle") {
+            shape.style_name = style;
+        }
+        shape
+    }
+
+    fn build_viewport(&self, raw: &DwgRawObject, common: EntityCommon) -> Viewport {
+        let mut viewport = Viewport::new();
+        viewport.common = common;
+        viewport.center = point3(raw, "center");
+        viewport.width = float(raw, "width");
+        viewport.height = float(raw, "height");
+        viewport.view_target = point3(raw, "view_target");
+        viewport.view_direction = raw
+            .point3_props
+            .get("view_direction")
+            .copied()
+            .unwrap_or(viewport.view_direction);
+        viewport.twist_angle = float(raw, "twist_angle");
+        viewport.view_height = raw.float_props.get("view_height").copied().unwrap_or(viewport.view_height);
+        viewport.lens_length = raw.float_props.get("lens_length").copied().unwrap_or(viewport.lens_length);
+        viewport.front_clip_z = float(raw, "front_clip_z");
+        viewport.back_clip_z = float(raw, "back_clip_z");
+        viewport.snap_angle = float(raw, "snap_angle");
+        for (key, target) in [
+            ("view_center", &mut viewport.view_center),
+            ("snap_base", &mut viewport.snap_base),
+            ("snap_spacing", &mut viewport.snap_spacing),
+            ("grid_spacing", &mut viewport.grid_spacing),
+        ] {
+            if let Some(p) = raw.point2_props.get(key) {
+                *target = Vector3::new(p.x, p.y, 0.0);
+            }
+        }
+        if let Some(&sides) = raw.int_props.get("circle_zoom") {
+            viewport.circle_sides = sides as i16;
+        }
+        if let Some(&major) = raw.int_props.get("grid_major") {
+            viewport.grid_major = major as i16;
+        }
+        if let Some(&status) = raw.int_props.get("status_flags") {
+            viewport.status = ViewportStatusFlags::from_bits(status as i32);
+        }
+        viewport.render_mode = ViewportRenderMode::from_value(int(raw, "render_mode") as i16);
+        viewport.ucs_per_viewport = flag(raw, "ucs_per_viewport");
+        viewport.ucs_origin = point3(raw, "ucs_origin");
+        viewport.ucs_x_axis = raw.point3_props.get("ucs_x_axis").copied().unwrap_or(Vector3::UNIT_X);
+        viewport.ucs_y_axis = raw.point3_props.get("ucs_y_axis").copied().unwrap_or(Vector3::UNIT_Y);
+        viewport.elevation = float(raw, "elevation");
+        viewport.ucs_ortho_type = int(raw, "ucs_ortho_type") as i16;
+        viewport.shade_plot_mode = int(raw, "shade_plot_mode") as i16;
+        viewport.default_lighting = flag(raw, "default_lighting");
+        viewport.default_lighting_type = int(raw, "default_lighting_type") as i16;
+        viewport.brightness = float(raw, "brightness");
+        viewport.contrast = float(raw, "contrast");
+        viewport.frozen_layers = raw
+            .handle_list_props
+            .get("frozen_layer_handles")
+            .into_iter()
+            .flatten()
+            .filter(|&&h| h != 0)
+            .map(|&h| Handle::new(h))
+            .collect();
+        for (key, target) in [
+            ("named_ucs_handle", &mut viewport.ucs_handle),
+            ("base_ucs_handle", &mut viewport.base_ucs_handle),
+            ("background_handle", &mut viewport.background_handle),
+            ("visual_style_handle", &mut viewport.visual_style_handle),
+            ("shade_plot_handle", &mut viewport.shade_plot_handle),
+        ] {
+            if let Some(h) = handle(raw, key) {
+                *target = h;
+            }
+        }
+        viewport
+    }
+
+    fn build_mline(&self, raw: &DwgRawObject, common: EntityCommon) -> MLine {
+        let mut mline = MLine::new();
+        mline.common = common;
+        mline.scale_factor = raw.float_props.get("scale_factor").copied().unwrap_or(1.0);
+        mline.justification = MLineJustification::from(int(raw, "justification") as i16);
+        mline.start_point = point3(raw, "start_point");
+        mline.normal = normal(raw);
+        mline.style_element_count = int(raw, "line_count").max(0) as usize;
+        mline.style_handle = handle(raw, "mline_style_handle");
+        if let Some(style) = self.handle_name(raw, "mline_style_handle") {
+            mline.style_name = style;
+        }
+
+        let positions = raw.point3_list_props.get("vertices");
+        let directions = raw.point3_list_props.get("directions");
+        let miters = raw.point3_list_props.get("miters");
+        for (v, &position) in positions.into_iter().flatten().enumerate() {
+            let mut vertex = MLineVertex::new(position);
+            vertex.direction = directions.and_then(|d| d.get(v)).copied().unwrap_or_default();
+            vertex.miter = miters.and_then(|m| m.get(v)).copied().unwrap_or_default();
+            for l in 0..mline.style_element_count {
+                let mut segment = MLineSegment::new();
+                segment.parameters = raw
+                    .float_list_props
+                    .get(&format!("vertex_{v}_line_{l}_parameters"))
+                    .cloned()
+                    .unwrap_or_default();
+                segment.area_fill_parameters = raw
+                    .float_list_props
+                    .get(&format!("vertex_{v}_line_{l}_fill_parameters"))
+                    .cloned()
+                    .unwrap_or_default();
+                vertex.segments.push(segment);
+            }
+            mline.vertices.push(vertex);
+        }
+
+        let mut flags = MLineFlags::empty();
+        if !mline.vertices.is_empty() {
+            flags |= MLineFlags::HAS_VERTICES;
+        }
+        if int(raw, "open_closed") == 3 {
+            flags |= MLineFlags::CLOSED;
+        }
+        mline.flags = flags;
+        mline
+    }
+
+    fn build_tolerance(&self, raw: &DwgRawObject, common: EntityCommon) -> Tolerance {
+        let mut tolerance = Tolerance::new();
+        tolerance.common = common;
+        tolerance.insertion_point = point3(raw, "insert_point");
+        tolerance.direction = raw
+            .point3_props
+            .get("direction")
+            .copied()
+            .unwrap_or(Vector3::UNIT_X);
+        tolerance.normal = normal(raw);
+        tolerance.text = raw.text_props.get("value").cloned().unwrap_or_default();
+        tolerance.text_height = raw.float_props.get("height").copied().unwrap_or(tolerance.text_height);
+        tolerance.dimension_gap = raw
+            .float_props
+            .get("dimension_gap")
+            .copied()
+            .unwrap_or(tolerance.dimension_gap);
+        tolerance.dimension_style_handle = handle(raw, "dimstyle_handle");
+        if let Some(style) = self.handle_name(raw, "dimstyle_handle") {
+            tolerance.dimension_style_name = style;
+        }
+        tolerance
+    }
+
+    fn build_ole2_frame(&self, raw: &DwgRawObject, common: EntityCommon) -> Ole2Frame {
+        let mut frame = Ole2Frame::new();
+        frame.common = common;
+        frame.version = raw.int_props.get("ole_version").map(|&v| v as i16).unwrap_or(frame.version);
+        frame.binary_data = raw.binary_props.get("ole_data").cloned().unwrap_or_default();
+        frame.is_paper_space = raw.int_props.get("entity_mode") == Some(&1);
+        frame
+    }
+
+    /// Shared modeler data of REGION, 3DSOLID and BODY.
+    fn build_modeler_geometry(&self, raw: &DwgRawObject) -> ModelerGeometry {
+        let acis_data = match (raw.text_props.get("acis_sat_data"), raw.binary_props.get("acis_sab_data")) {
+            (_, Some(sab)) => AcisData::from_sab(sab.clone()),
+            (Some(sat), None) => AcisData::from_sat(sat),
+            (None, None) => AcisData::new(),
+        };
+
+        let wires = (0..int(raw, "wire_count"))
+            .map(|i| build_wire(raw, &format!("wire_{i}")))
+            .collect();
+        let silhouettes = (0..int(raw, "silhouette_count"))
+            .map(|i| {
+                let prefix = format!("silhouette_{i}");
+                let mut silhouette = Silhouette::new(int(raw, &format!("{prefix}_viewport_id")));
+                silhouette.target = point3(raw, &format!("{prefix}_target"));
+                if let Some(&dir) = raw.point3_props.get(&format!("{prefix}_view_direction")) {
+                    silhouette.view_direction = dir;
+                }
+                if let Some(&up) = raw.point3_props.get(&format!("{prefix}_up_vector")) {
+                    silhouette.up_vector = up;
+                }
+                silhouette.is_perspective = flag(raw, &format!("{prefix}_perspective"));
+                silhouette.wires = (0..int(raw, &format!("{prefix}_wire_count")))
+                    .map(|w| build_wire(raw, &format!("{prefix}_wire_{w}")))
+                    .collect();
+                silhouette
+            })
+            .collect();
+
+        ModelerGeometry {
+            point_of_reference: point3(raw, "point_of_reference"),
+            acis_data,
+            wires,
+            silhouettes,
+        }
+    }
+
+    fn build_dictionary(&self, raw: &DwgRawObject) -> Dictionary {
+        let mut dict = Dictionary::new();
+        dict.handle = Handle::new(raw.handle);
+        dict.owner = Handle::new(raw.owner_handle.unwrap_or(0));
+        dict.reactors = raw.reactors.iter().map(|&h| Handle::new(h)).collect();
+        dict.xdictionary_handle = raw.xdict_handle.filter(|&h| h != 0).map(Handle::new);
+        dict.duplicate_cloning = raw
+            .int_props
+            .get("dictionary_cloning_flags")
+            .map(|&v| v as i16)
+            .unwrap_or(dict.duplicate_cloning);
+        dict.hard_owner = raw
+            .bool_props
+            .get("dictionary_hard_owner_flag")
+            .copied()
+            .unwrap_or(false);
+        for (name, handle) in dictionary_entries(raw) {
+            dict.add_entry(name, Handle::new(handle));
+        }
+        dict
+    }
+
+    fn build_dictionary_with_default(&self, raw: &DwgRawObject) -> DictionaryWithDefault {
+        let dict = self.build_dictionary(raw);
+        let mut with_default = DictionaryWithDefault::new();
+        with_default.handle = dict.handle;
+        with_default.owner = dict.owner;
+        with_default.entries = dict.entries;
+        with_default.duplicate_cloning = dict.duplicate_cloning;
+        with_default.hard_owner = dict.hard_owner;
+        with_default.default_handle = raw
+            .handle_props
+            .get("dictionary_default_entry_handle")
+            .map(|&h| Handle::new(h))
+            .unwrap_or(Handle::NULL);
+        with_default
+    }
+
+    fn build_xrecord(&self, raw: &DwgRawObject) -> XRecord {
+        let mut xrecord = XRecord::new();
+        xrecord.handle = Handle::new(raw.handle);
+        xrecord.owner = Handle::new(raw.owner_handle.unwrap_or(0));
+        if let Some(&flags) = raw.int_props.get("xrecord_cloning_flags") {
+            xrecord.cloning_flags = DictionaryCloningFlags::from_value(flags as i16);
+        }
+
+        // Items are stored as `xrecord_<code>_<index>` across the typed maps.
+        let mut items: BTreeMap<usize, XRecordEntry> = BTreeMap::new();
+        let mut collect = |key: &str, make: &dyn Fn(i32) -> XRecordValue| {
+            if let Some((code, index)) = parse_xrecord_key(key) {
+                items.insert(index, XRecordEntry::new(code, make(code)));
+            }
+        };
+        for (key, value) in &raw.text_props {
+            collect(key, &|_| XRecordValue::String(value.clone()));
+        }
+        for (key, value) in &raw.float_props {
+            collect(key, &|_| XRecordValue::Double(*value));
+        }
+        for (key, value) in &raw.point3_props {
+            collect(key, &|_| XRecordValue::Point3D(value.x, value.y, value.z));
+        }
+        for (key, value) in &raw.int_props {
+            let value = *value;
+            collect(key, &|code| match code {
+                280..=289 => XRecordValue::Byte(value as u8),
+                60..=79 | 170..=179 | 270..=279 | 370..=389 | 400..=409 => {
+                    XRecordValue::Int16(value as i16)
+                }
+                160..=169 => XRecordValue::Int64(value),
+                _ => XRecordValue::Int32(value as i32),
+            });
+        }
+        for (key, value) in &raw.bool_props {
+            collect(key, &|_| XRecordValue::Bool(*value));
+        }
+        for (key, value) in &raw.binary_props {
+            collect(key, &|_| XRecordValue::Chunk(value.clone()));
+        }
+        for (key, value) in &raw.handle_props {
+            collect(key, &|_| XRecordValue::Handle(Handle::new(*value)));
+        }
+
+        xrecord.entries = items.into_values().collect();
+        xrecord
+    }
+
+    /// Resolve a handle property to the name registered for it.
+    fn handle_name(&self, raw: &DwgRawObject, key: &str) -> Option<String> {
+        raw.handle_props
+            .get(key)
+            .and_then(|&h| self.try_get_name(h))
+            .map(str::to_string)
+    }
+}
+
+/// Table controls and entries are handled by the table builders.
+fn is_table_object(raw_type: RawObjectType) -> bool {
+    is_table_entry(raw_type)
+        || matches!(
+            raw_type,
+            RawObjectType::BlockControl
+                | RawObjectType::LayerControl
+                | RawObjectType::TextStyleControl
+                | RawObjectType::LineTypeControl
+                | RawObjectType::ViewControl
+                | RawObjectType::UcsControl
+                | RawObjectType::VPortControl
+                | RawObjectType::AppIdControl
+                | RawObjectType::DimStyleControl
+                | RawObjectType::VpEntHdrControl
+        )
+}
+
+fn is_table_entry(raw_type: RawObjectType) -> bool {
+    matches!(
+        raw_type,
+        RawObjectType::BlockHeader
+            | RawObjectType::Layer
+            | RawObjectType::TextStyle
+            | RawObjectType::LineType
+            | RawObjectType::View
+            | RawObjectType::Ucs
+            | RawObjectType::VPort
+            | RawObjectType::AppId
+            | RawObjectType::DimStyle
+            | RawObjectType::VpEntHdr
+    )
+}
+
+/// Replace the entry with the same name, keeping its position, or append it.
+fn upsert_entry<T: TableEntry>(table: &mut Table<T>, entry: T) {
+    match table.get_mut(entry.name()) {
+        Some(existing) => *existing = entry,
+        None => {
+            let _ = table.add(entry);
+        }
+    }
+}
+
+fn build_text_style(raw: &DwgRawObject, name: String) -> TextStyle {
+    let mut style = TextStyle::new(name);
+    style.handle = Handle::new(raw.handle);
+    style.height = float(raw, "style_height");
+    style.width_factor = raw.float_props.get("style_width_factor").copied().unwrap_or(1.0);
+    style.oblique_angle = float(raw, "style_oblique_angle");
+    let generation = int(raw, "style_generation");
+    style.flags.backward = generation & 0x2 != 0;
+    style.flags.upside_down = generation & 0x4 != 0;
+    style.font_file = raw.text_props.get("style_font_file").cloned().unwrap_or_default();
+    style.big_font_file = raw
+        .text_props
+        .get("style_big_font_file")
+        .cloned()
+        .unwrap_or_default();
+    style
+}
+
+fn build_line_type(raw: &DwgRawObject, name: String) -> LineType {
+    let mut line_type = LineType::new(name);
+    line_type.handle = Handle::new(raw.handle);
+    line_type.description = raw
+        .text_props
+        .get("linetype_description")
+        .cloned()
+        .unwrap_or_default();
+    if let Some(&alignment) = raw.int_props.get("linetype_alignment") {
+        line_type.alignment = alignment as u8 as char;
+    }
+    line_type.elements = raw
+        .float_list_props
+        .get("linetype_dash_lengths")
+        .into_iter()
+        .flatten()
+        .map(|&length| LineTypeElement { length })
+        .collect();
+    line_type.pattern_length = raw
+        .float_props
+        .get("linetype_pattern_length")
+        .copied()
+        .unwrap_or_else(|| line_type.elements.iter().map(|e| e.length.abs()).sum());
+    line_type
+}
+
+fn build_view(raw: &DwgRawObject, name: String) -> View {
+    let mut view = View::new(name);
+    view.handle = Handle::new(raw.handle);
+    view.height = float(raw, "view_height");
+    view.width = float(raw, "view_width");
+    if let Some(center) = raw.point2_props.get("view_center") {
+        view.center = Vector3::new(center.x, center.y, 0.0);
+    }
+    view.target = point3(raw, "view_target");
+    view.direction = raw
+        .point3_props
+        .get("view_direction")
+        .copied()
+        .unwrap_or(Vector3::UNIT_Z);
+    view.twist_angle = float(raw, "view_twist_angle");
+    view.lens_length = raw.float_props.get("view_lens_length").copied().unwrap_or(view.lens_length);
+    view.front_clip = float(raw, "view_front_clip");
+    view.back_clip = float(raw, "view_back_clip");
+    view
+}
+
+fn build_ucs(raw: &DwgRawObject, name: String) -> Ucs {
+    let mut ucs = Ucs::new(name);
+    ucs.handle = Handle::new(raw.handle);
+    ucs.origin = point3(raw, "ucs_origin");
+    ucs.x_axis = raw.point3_props.get("ucs_x_axis").copied().unwrap_or(Vector3::UNIT_X);
+    ucs.y_axis = raw.point3_props.get("ucs_y_axis").copied().unwrap_or(Vector3::UNIT_Y);
+    ucs
+}
+
+fn build_vport(raw: &DwgRawObject, name: String) -> VPort {
+    let mut vport = VPort::new(name);
+    vport.handle = Handle::new(raw.handle);
+    for (key, target) in [
+        ("vport_lower_left", &mut vport.lower_left),
+        ("vport_upper_right", &mut vport.upper_right),
+        ("view_center", &mut vport.view_center),
+        ("vport_snap_base", &mut vport.snap_base),
+        ("vport_snap_spacing", &mut vport.snap_spacing),
+        ("vport_grid_spacing", &mut vport.grid_spacing),
+    ] {
+        if let Some(&p) = raw.point2_props.get(key) {
+            *target = p;
+        }
+    }
+    vport.view_target = point3(raw, "view_target");
+    vport.view_direction = raw
+        .point3_props
+        .get("view_direction")
+        .copied()
+        .unwrap_or(Vector3::UNIT_Z);
+    vport.view_height = raw.float_props.get("view_height").copied().unwrap_or(vport.view_height);
+    // The file stores the aspect ratio multiplied by the view height.
+    if let Some(&width) = raw.float_props.get("view_width") {
+        if vport.view_height != 0.0 {
+            vport.aspect_ratio = width / vport.view_height;
+        }
+    }
+    vport.lens_length = raw.float_props.get("view_lens_length").copied().unwrap_or(vport.lens_length);
+    vport
+}
+
+/// DXF entity / object name of the fixed DWG object types.
+fn fixed_dxf_name(raw_type: RawObjectType) -> &'static str {
+    match raw_type {
+        RawObjectType::Text => "TEXT",
+        RawObjectType::Attrib => "ATTRIB",
+        RawObjectType::AttDef => "ATTDEF",
+        RawObjectType::Block => "BLOCK",
+        RawObjectType::EndBlk => "ENDBLK",
+        RawObjectType::SeqEnd => "SEQEND",
+        RawObjectType::Insert => "INSERT",
+        RawObjectType::MInsert => "INSERT",
+        RawObjectType::Vertex2D
+        | RawObjectType::Vertex3D
+        | RawObjectType::VertexPFace
+        | RawObjectType::VertexMesh
+        | RawObjectType::VertexPFaceFace => "VERTEX",
+        RawObjectType::Polyline2D
+        | RawObjectType::Polyline3D
+        | RawObjectType::PolylinePFace
+        | RawObjectType::PolylineMesh => "POLYLINE",
+        RawObjectType::Arc => "ARC",
+        RawObjectType::Circle => "CIRCLE",
+        RawObjectType::Line => "LINE",
+        RawObjectType::Point => "POINT",
+        RawObjectType::Face3D => "3DFACE",
+        RawObjectType::Solid => "SOLID",
+        RawObjectType::Trace => "TRACE",
+        RawObjectType::Shape => "SHAPE",
+        RawObjectType::Viewport => "VIEWPORT",
+        RawObjectType::Ellipse => "ELLIPSE",
+        RawObjectType::Spline => "SPLINE",
+        RawObjectType::Region => "REGION",
+        RawObjectType::Solid3D => "3DSOLID",
+        RawObjectType::Body => "BODY",
+        RawObjectType::Ray => "RAY",
+        RawObjectType::XLine => "XLINE",
+        RawObjectType::Dictionary => "DICTIONARY",
+        RawObjectType::DictionaryWithDefault => "ACDBDICTIONARYWDFLT",
+        RawObjectType::MText => "MTEXT",
+        RawObjectType::Leader => "LEADER",
+        RawObjectType::Hatch => "HATCH",
+        RawObjectType::ProxyEntity => "ACAD_PROXY_ENTITY",
+        RawObjectType::ProxyObject => "ACAD_PROXY_OBJECT",
+        RawObjectType::MultiLeader => "MULTILEADER",
+        RawObjectType::MultiLeaderStyle => "MLEADERSTYLE",
+        RawObjectType::ObjectContextData => "OBJECTCONTEXTDATA",
+        RawObjectType::AnnotScaleObjectContextData => "ACDB_ANNOTSCALEOBJECTCONTEXTDATA_CLASS",
+        RawObjectType::MultiLeaderAnnotContext => "ACDB_MLEADEROBJECTCONTEXTDATA_CLASS",
+        RawObjectType::Tolerance => "TOLERANCE",
+        RawObjectType::MLine => "MLINE",
+        RawObjectType::OLEFrame => "OLEFRAME",
+        RawObjectType::OLE2Frame => "OLE2FRAME",
+        RawObjectType::Dummy => "DUMMY",
+        RawObjectType::LongTransaction => "LONG_TRANSACTION",
+        RawObjectType::LwPolyline => "LWPOLYLINE",
+        RawObjectType::XRecord => "XRECORD",
+        RawObjectType::Layout => "LAYOUT",
+        RawObjectType::BlockControl => "BLOCK_CONTROL",
+        RawObjectType::BlockHeader => "BLOCK_RECORD",
+        RawObjectType::LayerControl => "LAYER_CONTROL",
+        RawObjectType::Layer => "LAYER",
+        RawObjectType::TextStyleControl => "STYLE_CONTROL",
+        RawObjectType::TextStyle => "STYLE",
+        RawObjectType::LineTypeControl => "LTYPE_CONTROL",
+        RawObjectType::LineType => "LTYPE",
+        RawObjectType::ViewControl => "VIEW_CONTROL",
+        RawObjectType::View => "VIEW",
+        RawObjectType::UcsControl => "UCS_CONTROL",
+        RawObjectType::Ucs => "UCS",
+        RawObjectType::VPortControl => "VPORT_CONTROL",
+        RawObjectType::VPort => "VPORT",
+        RawObjectType::AppIdControl => "APPID_CONTROL",
+        RawObjectType::AppId => "APPID",
+        RawObjectType::DimStyleControl => "DIMSTYLE_CONTROL",
+        RawObjectType::DimStyle => "DIMSTYLE",
+        RawObjectType::VpEntHdrControl => "VP_ENT_HDR_CONTROL",
+        RawObjectType::VpEntHdr => "VP_ENT_HDR",
+        RawObjectType::Unknown(_) => "UNKNOWN",
+    }
+}
+
+/// Decode the entity line weight index (R2000+ `ENC` lineweight byte).
+fn line_weight_from_index(index: i16) -> LineWeight {
+    const VALUES: [i16; 24] = [
+        0, 5, 9, 13, 15, 18, 20, 25, 30, 35, 40, 50, 53, 60, 70, 80, 90, 100, 106, 120, 140, 158,
+        200, 211,
+    ];
+    match index {
+        0..=23 => LineWeight::Value(VALUES[index as usize]),
+        29 => LineWeight::ByLayer,
+        30 => LineWeight::ByBlock,
+        31 => LineWeight::Default,
+        _ => LineWeight::ByLayer,
+    }
+}
+
+/// EED handles (codes 1003 / 1005) are stored as 8 big-endian bytes.
+fn handle_from_bytes(bytes: &[u8]) -> u64 {
+    bytes.iter().take(8).fold(0u64, |acc, &b| (acc << 8) | b as u64)
+}
+
+fn parse_xrecord_key(key: &str) -> Option<(i32, usize)> {
+    let rest = key.strip_prefix("xrecord_")?;
+    let (code, index) = rest.split_once('_')?;
+    Some((code.parse().ok()?, index.parse().ok()?))
+}
+
+fn dictionary_entries(raw: &DwgRawObject) -> Vec<(String, u64)> {
+    let names = raw
+        .text_props
+        .get("dictionary_entry_names")
+        .map(|s| s.split('\u{1f}').map(str::to_string).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let handles = raw
+        .handle_list_props
+        .get("dictionary_entry_handles")
+        .cloned()
+        .unwrap_or_default();
+    names.into_iter().zip(handles).collect()
+}
+
+/// Whether the type is a polyline vertex, which is only built as part of
+/// its polyline.
+fn is_vertex(raw_type: RawObjectType) -> bool {
+    matches!(
+        raw_type,
+        RawObjectType::Vertex2D
+            | RawObjectType::Vertex3D
+            | RawObjectType::VertexPFace
+            | RawObjectType::VertexMesh
+            | RawObjectType::VertexPFaceFace
+    )
+}
+
+fn int(raw: &DwgRawObject, key: &str) -> i64 {
+    raw.int_props.get(key).copied().unwrap_or(0)
+}
+
+fn point3(raw: &DwgRawObject, key: &str) -> Vector3 {
+    raw.point3_props.get(key).copied().unwrap_or(Vector3::ZERO)
+}
+
+fn float(raw: &DwgRawObject, key: &str) -> f64 {
+    raw.float_props.get(key).copied().unwrap_or(0.0)
+}
+
+fn flag(raw: &DwgRawObject, key: &str) -> bool {
+    raw.bool_props.get(key).copied().unwrap_or(false)
+}
+
+fn normal(raw: &DwgRawObject) -> Vector3 {
+    raw.point3_props.get("normal").copied().unwrap_or(Vector3::UNIT_Z)
+}
+
+/// Non-null handle stored under `key`.
+fn handle(raw: &DwgRawObject, key: &str) -> Option<Handle> {
+    raw.handle_props.get(key).filter(|&&h| h != 0).map(|&h| Handle::new(h))
+}
+
+fn build_wire(raw: &DwgRawObject, prefix: &str) -> Wire {
+    let mut wire = Wire::from_points(
+        raw.point3_list_props
+            .get(&format!("{prefix}_points"))
+            .cloned()
+            .unwrap_or_default(),
+    );
+    wire.wire_type = WireType::from(int(raw, &format!("{prefix}_type")) as u8);
+    wire.selection_marker = int(raw, &format!("{prefix}_selection_marker")) as i32;
+    wire.color = Color::from_index(int(raw, &format!("{prefix}_color")) as i16);
+    wire.acis_index = int(raw, &format!("{prefix}_acis_index")) as i32;
+    wire.has_transform = flag(raw, &format!("{prefix}_has_transform"));
+    if wire.has_transform {
+        wire.x_axis = point3(raw, &format!("{prefix}_x_axis"));
+        wire.y_axis = point3(raw, &format!("{prefix}_y_axis"));
+        wire.z_axis = point3(raw, &format!("{prefix}_z_axis"));
+        wire.translation = point3(raw, &format!("{prefix}_translation"));
+        wire.scale = point3(raw, &format!("{prefix}_scale")).x;
+        wire.has_rotation = flag(raw, &format!("{prefix}_has_rotation"));
+        wire.has_reflection = flag(raw, &format!("{prefix}_has_reflection"));
+        wire.has_shear = flag(raw, &format!("{prefix}_has_shear"));
+    }
+    wire
+}
+
+fn text_horizontal_alignment(value: i16) -> TextHorizontalAlignment {
+    match value {
+        1 => TextHorizontalAlignment::Center,
+        2 => TextHorizontalAlignment::Right,
+        3 => TextHorizontalAlignment::Aligned,
+        4 => TextHorizontalAlignment::Middle,
+        5 => TextHorizontalAlignment::Fit,
+        _ => TextHorizontalAlignment::Left,
+    }
+}
+
+fn text_vertical_alignment(value: i16) -> TextVerticalAlignment {
+    match value {
+        1 => TextVerticalAlignment::Bottom,
+        2 => TextVerticalAlignment::Middle,
+        3 => TextVerticalAlignment::Top,
+        _ => TextVerticalAlignment::Baseline,
+    }
+}
+
+fn attachment_point(value: i16) -> AttachmentPoint {
+    match value {
+        2 => AttachmentPoint::TopCenter,
+        3 => AttachmentPoint::TopRight,
+        4 => AttachmentPoint::MiddleLeft,
+        5 => AttachmentPoint::MiddleCenter,
+        6 => AttachmentPoint::MiddleRight,
+        7 => AttachmentPoint::BottomLeft,
+        8 => AttachmentPoint::BottomCenter,
+        9 => AttachmentPoint::BottomRight,
+        _ => AttachmentPoint::TopLeft,
+    }
+}
+
+fn drawing_direction(value: i16) -> DrawingDirection {
+    match value {
+        3 => DrawingDirection::TopToBottom,
+        5 => DrawingDirection::ByStyle,
+        _ => DrawingDirection::LeftToRight,
+    }
 }
 
 #[cfg(test)]
@@ -138,4 +2226,448 @@ mod tests {
         let result = builder.build_document();
         assert_eq!(result.header.current_layer_name, "LayerOne");
     }
+
+    #[test]
+    fn test_recover_rebuilds_missing_table_controls() {
+        let cfg = DwgReaderConfiguration {
+            recover: true,
+            ..Default::default()
+        };
+        let mut builder = DwgDocumentBuilder::new(DxfVersion::AC1015, CadDocument::new(), cfg);
+        builder.raw_objects.push(raw(0x2, RawObjectType::LayerControl));
+
+        let result = builder.build_document();
+        let rebuilt: Vec<&str> = result
+            .notifications
+            .iter()
+            .filter(|n| n.message.starts_with("Rebuilt missing"))
+            .map(|n| n.message.as_str())
+            .collect();
+        assert_eq!(rebuilt.len(), 8);
+        assert!(!rebuilt.iter().any(|m| m.contains(" LAYER ")));
+        assert!(!result.line_types.handle().is_null());
+        assert_eq!(result.header.linetype_control_handle, result.line_types.handle());
+    }
+
+    fn raw(handle: u64, raw_type: RawObjectType) -> DwgRawObject {
+        DwgRawObject {
+            handle,
+            raw_type: Some(raw_type),
+            ..Default::default()
+        }
+    }
+
+    fn raw_entity(handle: u64, raw_type: RawObjectType, owner: Option<u64>) -> DwgRawObject {
+        let mut r = raw(handle, raw_type);
+        match owner {
+            Some(owner) => {
+                r.int_props.insert("entity_mode".to_string(), 0);
+                r.owner_handle = Some(owner);
+            }
+            None => {
+                r.int_props.insert("entity_mode".to_string(), 2);
+            }
+        }
+        r
+    }
+
+    fn sample_builder() -> DwgDocumentBuilder {
+        let mut builder = DwgDocumentBuilder::new(
+            DxfVersion::AC1018,
+            CadDocument::new(),
+            DwgReaderConfiguration::default(),
+        );
+        builder.header_handles.set(handle_names::MODEL_SPACE, 0x1F);
+        builder.header_handles.set(handle_names::PAPER_SPACE, 0x1E);
+        builder.register_name(0x80, "Walls".to_string());
+        builder.register_name(0x81, "Notes".to_string());
+
+        let mut block = raw_entity(0x51, RawObjectType::Block, Some(0x50));
+        block.text_props.insert("block_name".to_string(), "Door".to_string());
+        builder.raw_objects.push(block);
+        builder.raw_objects.push(raw_entity(0x52, RawObjectType::EndBlk, Some(0x50)));
+
+        let mut text = raw_entity(0x60, RawObjectType::Text, None);
+        text.text_props.insert("value".to_string(), "Hello".to_string());
+        text.point3_props.insert("insert_point".to_string(), Vector3::new(1.0, 2.0, 0.0));
+        text.float_props.insert("height".to_string(), 2.5);
+        text.handle_props.insert("layer_handle".to_string(), 0x80);
+        text.handle_props.insert("style_handle".to_string(), 0x81);
+        builder.raw_objects.push(text);
+
+        let mut attrib = raw_entity(0x62, RawObjectType::Attrib, Some(0x61));
+        attrib.text_props.insert("attribute_tag".to_string(), "TAG".to_string());
+        attrib.text_props.insert("value".to_string(), "42".to_string());
+        builder.raw_objects.push(attrib);
+
+        let mut insert = raw_entity(0x61, RawObjectType::Insert, None);
+        insert.handle_props.insert("block_header_handle".to_string(), 0x50);
+        insert.float_props.insert("x_scale".to_string(), 2.0);
+        builder.raw_objects.push(insert);
+
+        let mut in_block = raw_entity(0x53, RawObjectType::MText, Some(0x50));
+        in_block.text_props.insert("mtext_value".to_string(), "Inside".to_string());
+        in_block.int_props.insert("mtext_attachment".to_string(), 5);
+        builder.raw_objects.push(in_block);
+
+        let mut dict = raw(0x0C, RawObjectType::Dictionary);
+        dict.text_props.insert(
+            "dictionary_entry_names".to_string(),
+            "ACAD_GROUP\u{1f}ACAD_LAYOUT".to_string(),
+        );
+        dict.handle_list_props
+            .insert("dictionary_entry_handles".to_string(), vec![0x0D, 0x1A]);
+        builder.raw_objects.push(dict);
+
+        let mut xrecord = raw(0x90, RawObjectType::XRecord);
+        xrecord.owner_handle = Some(0x0C);
+        xrecord.text_props.insert("xrecord_1_0".to_string(), "data".to_string());
+        xrecord.int_props.insert("xrecord_70_1".to_string(), 7);
+        xrecord
+            .point3_props
+            .insert("xrecord_10_2".to_string(), Vector3::new(1.0, 2.0, 3.0));
+        xrecord.int_props.insert("xrecord_item_count".to_string(), 3);
+        builder.raw_objects.push(xrecord);
+
+        builder
+    }
+
+    #[test]
+    fn test_build_entities_attached_to_owners() {
+        let doc = sample_builder().build_document();
+
+        let text = doc.get_entity(Handle::new(0x60)).expect("text in model space");
+        match text {
+            EntityType::Text(t) => {
+                assert_eq!(t.value, "Hello");
+                assert_eq!(t.height, 2.5);
+                assert_eq!(t.insertion_point, Vector3::new(1.0, 2.0, 0.0));
+                assert_eq!(t.common.layer, "Walls");
+                assert_eq!(t.style, "Notes");
+                assert_eq!(t.common.owner_handle, Handle::new(0x1F));
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        match doc.get_entity(Handle::new(0x61)) {
+            Some(EntityType::Insert(insert)) => {
+                assert_eq!(insert.block_name, "Door");
+                assert_eq!(insert.x_scale, 2.0);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        // ATTRIB follows its INSERT into model space
+        match doc.get_entity(Handle::new(0x62)) {
+            Some(EntityType::AttributeEntity(attrib)) => {
+                assert_eq!(attrib.tag, "TAG");
+                assert_eq!(attrib.value, "42");
+                assert_eq!(attrib.common.owner_handle, Handle::new(0x61));
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        let door = doc.block_records.get("Door").expect("block record");
+        assert_eq!(door.handle, Handle::new(0x50));
+        assert_eq!(door.block_entity_handle, Handle::new(0x51));
+        assert_eq!(door.block_end_handle, Handle::new(0x52));
+        assert_eq!(door.entities.len(), 1);
+        match &door.entities[0] {
+            EntityType::MText(m) => {
+                assert_eq!(m.value, "Inside");
+                assert_eq!(m.attachment_point, AttachmentPoint::MiddleCenter);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+
+        assert_eq!(
+            doc.block_records.get("*Model_Space").unwrap().handle,
+            Handle::new(0x1F)
+        );
+        assert_eq!(doc.entity_count(), 3);
+    }
+
+    #[test]
+    fn test_build_objects() {
+        let doc = sample_builder().build_document();
+
+        match doc.objects.get(&Handle::new(0x0C)) {
+            Some(ObjectType::Dictionary(dict)) => {
+                assert_eq!(dict.get("ACAD_GROUP"), Some(Handle::new(0x0D)));
+                assert_eq!(dict.get("ACAD_LAYOUT"), Some(Handle::new(0x1A)));
+            }
+            other => panic!("unexpected object {:?}", other),
+        }
+
+        match doc.objects.get(&Handle::new(0x90)) {
+            Some(ObjectType::XRecord(xrecord)) => {
+                assert_eq!(xrecord.owner, Handle::new(0x0C));
+                assert_eq!(xrecord.entries.len(), 3);
+                assert_eq!(xrecord.entries[0].value, XRecordValue::String("data".to_string()));
+                assert_eq!(xrecord.entries[1].value, XRecordValue::Int16(7));
+                assert_eq!(xrecord.entries[2].value, XRecordValue::Point3D(1.0, 2.0, 3.0));
+            }
+            other => panic!("unexpected object {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unsupported_entities_are_reported() {
+        let mut builder = sample_builder();
+        builder.raw_objects.push(raw_entity(0x70, RawObjectType::Unknown(0x1F4), None));
+        let doc = builder.build_document();
+        assert!(doc.get_entity(Handle::new(0x70)).is_none());
+        assert!(doc
+            .notifications
+            .iter()
+            .any(|n| n.message.starts_with("Entity not supported")));
+
+        let mut builder = sample_builder();
+        builder.configuration.keep_unknown_entities = true;
+        builder.raw_objects.push(raw_entity(0x70, RawObjectType::Unknown(0x1F4), None));
+        let doc = builder.build_document();
+        assert!(matches!(
+            doc.get_entity(Handle::new(0x70)),
+            Some(EntityType::Unknown(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_geometry_entities() {
+        let mut builder = sample_builder();
+
+        let mut line = raw_entity(0x70, RawObjectType::Line, None);
+        line.point3_props.insert("start_point".to_string(), Vector3::new(0.0, 0.0, 0.0));
+        line.point3_props.insert("end_point".to_string(), Vector3::new(10.0, 5.0, 0.0));
+        line.float_props.insert("thickness".to_string(), 0.5);
+        builder.raw_objects.push(line);
+
+        let mut arc = raw_entity(0x71, RawObjectType::Arc, None);
+        arc.point3_props.insert("center".to_string(), Vector3::new(1.0, 1.0, 0.0));
+        arc.float_props.insert("radius".to_string(), 3.0);
+        arc.float_props.insert("end_angle".to_string(), 1.5);
+        builder.raw_objects.push(arc);
+
+        let mut lwpolyline = raw_entity(0x72, RawObjectType::LwPolyline, None);
+        lwpolyline.int_props.insert("lwpolyline_flags".to_string(), 0x200 | 0x10);
+        lwpolyline.point3_list_props.insert(
+            "vertices".to_string(),
+            vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(4.0, 0.0, 0.0)],
+        );
+        lwpolyline.float_list_props.insert("bulges".to_string(), vec![0.0, 1.0]);
+        builder.raw_objects.push(lwpolyline);
+
+        let mut spline = raw_entity(0x73, RawObjectType::Spline, None);
+        spline.int_props.insert("degree".to_string(), 3);
+        spline.bool_props.insert("rational".to_string(), true);
+        spline.float_list_props.insert("knots".to_string(), vec![0.0, 0.0, 1.0, 1.0]);
+        spline.point3_list_props.insert(
+            "control_points".to_string(),
+            vec![Vector3::ZERO, Vector3::new(1.0, 2.0, 0.0)],
+        );
+        builder.raw_objects.push(spline);
+
+        let mut shape = raw_entity(0x74, RawObjectType::Shape, None);
+        shape.int_props.insert("shape_number".to_string(), 132);
+        shape.handle_props.insert("style_handle".to_string(), 0x81);
+        builder.raw_objects.push(shape);
+
+        let mut face = raw_entity(0x75, RawObjectType::Face3D, None);
+        face.point3_props.insert("third_corner".to_string(), Vector3::new(1.0, 1.0, 1.0));
+        face.int_props.insert("invisible_edges".to_string(), 0b0101);
+        builder.raw_objects.push(face);
+
+        let doc = builder.build_document();
+
+        match doc.get_entity(Handle::new(0x70)) {
+            Some(EntityType::Line(line)) => {
+                assert_eq!(line.end, Vector3::new(10.0, 5.0, 0.0));
+                assert_eq!(line.thickness, 0.5);
+                assert_eq!(line.normal, Vector3::UNIT_Z);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+        match doc.get_entity(Handle::new(0x71)) {
+            Some(EntityType::Arc(arc)) => {
+                assert_eq!(arc.radius, 3.0);
+                assert_eq!(arc.end_angle, 1.5);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+        match doc.get_entity(Handle::new(0x72)) {
+            Some(EntityType::LwPolyline(pl)) => {
+                assert!(pl.is_closed);
+                assert_eq!(pl.vertices.len(), 2);
+                assert_eq!(pl.vertices[1].location, Vector2::new(4.0, 0.0));
+                assert_eq!(pl.vertices[1].bulge, 1.0);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+        match doc.get_entity(Handle::new(0x73)) {
+            Some(EntityType::Spline(spline)) => {
+                assert_eq!(spline.degree, 3);
+                assert!(spline.flags.rational);
+                assert_eq!(spline.knots.len(), 4);
+                assert_eq!(spline.control_points.len(), 2);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+        match doc.get_entity(Handle::new(0x74)) {
+            Some(EntityType::Shape(shape)) => {
+                assert_eq!(shape.shape_number, 132);
+                assert_eq!(shape.style_name, "Notes");
+                assert_eq!(shape.style_handle, Some(Handle::new(0x81)));
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+        match doc.get_entity(Handle::new(0x75)) {
+            Some(EntityType::Face3D(face)) => {
+                assert_eq!(face.third_corner, Vector3::new(1.0, 1.0, 1.0));
+                assert_eq!(face.invisible_edges, InvisibleEdgeFlags::from_bits(0b0101));
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_build_mline_and_solid3d() {
+        let mut builder = sample_builder();
+
+        let mut mline = raw_entity(0x70, RawObjectType::MLine, None);
+        mline.int_props.insert("line_count".to_string(), 2);
+        mline.int_props.insert("open_closed".to_string(), 3);
+        mline.point3_list_props.insert(
+            "vertices".to_string(),
+            vec![Vector3::ZERO, Vector3::new(5.0, 0.0, 0.0)],
+        );
+        mline
+            .float_list_props
+            .insert("vertex_1_line_1_parameters".to_string(), vec![0.0, 5.0]);
+        builder.raw_objects.push(mline);
+
+        let mut solid = raw_entity(0x71, RawObjectType::Solid3D, None);
+        solid
+            .text_props
+            .insert("acis_sat_data".to_string(), "400 0 1 0".to_string());
+        solid.int_props.insert("wire_count".to_string(), 1);
+        solid.int_props.insert("wire_0_type".to_string(), 2);
+        solid.point3_list_props.insert(
+            "wire_0_points".to_string(),
+            vec![Vector3::ZERO, Vector3::UNIT_X],
+        );
+        builder.raw_objects.push(solid);
+
+        let doc = builder.build_document();
+
+        match doc.get_entity(Handle::new(0x70)) {
+            Some(EntityType::MLine(mline)) => {
+                assert!(mline.flags.contains(MLineFlags::CLOSED));
+                assert!(mline.flags.contains(MLineFlags::HAS_VERTICES));
+                assert_eq!(mline.vertices.len(), 2);
+                assert_eq!(mline.vertices[1].segments.len(), 2);
+                assert_eq!(mline.vertices[1].segments[1].parameters, vec![0.0, 5.0]);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+        match doc.get_entity(Handle::new(0x71)) {
+            Some(EntityType::Solid3D(solid)) => {
+                assert_eq!(solid.acis_data.sat_data, "400 0 1 0");
+                assert!(!solid.acis_data.is_binary);
+                assert_eq!(solid.wires.len(), 1);
+                assert_eq!(solid.wires[0].wire_type, WireType::VisibleEdge);
+                assert_eq!(solid.wires[0].points.len(), 2);
+            }
+            other => panic!("unexpected entity {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_build_tables() {
+        let mut builder = DwgDocumentBuilder::new(
+            DxfVersion::AC1018,
+            CadDocument::new(),
+            DwgReaderConfiguration::default(),
+        );
+        builder.header_handles.set(handle_names::CLAYER, 0x40);
+
+        let mut control = raw(0x02, RawObjectType::LayerControl);
+        control
+            .handle_list_props
+            .insert("entry_handles".to_string(), vec![0x10, 0x40]);
+        builder.raw_objects.push(control);
+
+        let mut dashed = raw(0x41, RawObjectType::LineType);
+        dashed.text_props.insert("entry_name".to_string(), "DASHED".to_string());
+        dashed
+            .float_list_props
+            .insert("linetype_dash_lengths".to_string(), vec![0.5, -0.25]);
+        builder.raw_objects.push(dashed);
+
+        let mut layer = raw(0x40, RawObjectType::Layer);
+        layer.text_props.insert("entry_name".to_string(), "Walls".to_string());
+        layer.bool_props.insert("layer_locked".to_string(), true);
+        layer.color = Some(Color::from_index(3));
+        layer.line_weight = Some(7);
+        layer.handle_props.insert("layer_linetype_handle".to_string(), 0x41);
+        builder.raw_objects.push(layer);
+
+        let mut zero = raw(0x10, RawObjectType::Layer);
+        zero.text_props.insert("entry_name".to_string(), "0".to_string());
+        builder.raw_objects.push(zero);
+
+        let mut style = raw(0x42, RawObjectType::TextStyle);
+        style.text_props.insert("entry_name".to_string(), "Notes".to_string());
+        style.text_props.insert("style_font_file".to_string(), "romans.shx".to_string());
+        style.int_props.insert("style_generation".to_string(), 2);
+        builder.raw_objects.push(style);
+
+        let mut dimstyle = raw(0x43, RawObjectType::DimStyle);
+        dimstyle.text_props.insert("entry_name".to_string(), "ISO-25".to_string());
+        dimstyle.float_props.insert("dimtxt".to_string(), 2.5);
+        dimstyle.handle_props.insert("dimtxsty".to_string(), 0x42);
+        builder.raw_objects.push(dimstyle);
+
+        let mut text = raw_entity(0x60, RawObjectType::Text, None);
+        text.handle_props.insert("layer_handle".to_string(), 0x40);
+        builder.raw_objects.push(text);
+
+        let doc = builder.build_document();
+
+        assert_eq!(doc.layers.handle(), Handle::new(0x02));
+        assert_eq!(doc.header.layer_control_handle, Handle::new(0x02));
+        assert_eq!(doc.layers.get("0").unwrap().handle, Handle::new(0x10));
+        let walls = doc.layers.get("Walls").expect("layer");
+        assert_eq!(walls.handle, Handle::new(0x40));
+        assert!(walls.flags.locked);
+        assert_eq!(walls.color, Color::from_index(3));
+        assert_eq!(walls.line_weight, LineWeight::Value(25));
+        assert_eq!(walls.line_type, "DASHED");
+        assert_eq!(doc.header.current_layer_name, "Walls");
+
+        let dashed = doc.line_types.get("DASHED").expect("linetype");
+        assert_eq!(dashed.elements.len(), 2);
+        assert_eq!(dashed.pattern_length, 0.75);
+
+        let notes = doc.text_styles.get("Notes").expect("text style");
+        assert_eq!(notes.font_file, "romans.shx");
+        assert!(notes.flags.backward);
+
+        let iso = doc.dim_styles.get("ISO-25").expect("dimstyle");
+        assert_eq!(iso.dimtxt, 2.5);
+        assert_eq!(iso.dimtxsty, "Notes");
+
+        match doc.get_entity(Handle::new(0x60)) {
+            Some(EntityType::Text(t)) => assert_eq!(t.common.layer, "Walls"),
+            other => panic!("unexpected entity {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_line_weight_from_index() {
+        assert_eq!(line_weight_from_index(0), LineWeight::Value(0));
+        assert_eq!(line_weight_from_index(23), LineWeight::Value(211));
+        assert_eq!(line_weight_from_index(29), LineWeight::ByLayer);
+        assert_eq!(line_weight_from_index(30), LineWeight::ByBlock);
+        assert_eq!(line_weight_from_index(31), LineWeight::Default);
+    }
 }
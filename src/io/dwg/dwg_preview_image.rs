@@ -0,0 +1,553 @@
+//! Decoding and encoding of DWG preview (thumbnail) images.
+//!
+//! DWG files store the thumbnail either as a device independent bitmap
+//! (a BMP without its file header) or as a PNG stream. Both are converted
+//! to and from 8-bit RGBA pixels here.
+
+use std::io::{Read, Write};
+
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::{Compression, Crc};
+
+use crate::error::{DxfError, Result};
+
+/// A decoded preview image: RGBA pixels, 4 bytes per pixel, rows top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreviewImage {
+    /// Width in pixels.
+    pub width: u32,
+    /// Height in pixels.
+    pub height: u32,
+    /// RGBA pixel data, `width * height * 4` bytes.
+    pub pixels: Vec<u8>,
+}
+
+impl PreviewImage {
+    /// Create an image from RGBA pixel data.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self> {
+        if pixels.len() as u64 != width as u64 * height as u64 * 4 {
+            return Err(DxfError::InvalidFormat(format!(
+                "Expected {} RGBA bytes for a {}x{} image, got {}",
+                width as u64 * height as u64 * 4,
+                width,
+                height,
+                pixels.len()
+            )));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// RGBA value of the pixel at (`x`, `y`), counted from the top left.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels.get(i..i + 4).map(|p| [p[0], p[1], p[2], p[3]])
+    }
+}
+
+// ── BMP ────────────────────────────────────────────────────────────────
+
+/// Size of a BITMAPINFOHEADER.
+const BMP_INFO_HEADER_SIZE: usize = 40;
+
+/// Decode a device independent bitmap. A leading BITMAPFILEHEADER ("BM")
+/// is accepted and skipped. Supports uncompressed 1, 4, 8, 16, 24 and
+/// 32 bits per pixel.
+pub fn decode_bmp(data: &[u8]) -> Result<PreviewImage> {
+    let (dib, pixel_offset) = if data.starts_with(b"BM") && data.len() >= 14 {
+        let offset = u32_le(data, 10)? as usize;
+        (&data[14..], Some(offset.saturating_sub(14)))
+    } else {
+        (data, None)
+    };
+
+    let header_size = u32_le(dib, 0)? as usize;
+    let (width, height, bit_count, compression, colors_used, palette_entry_size) =
+        if header_size == 12 {
+            // BITMAPCOREHEADER
+            let width = u16_le(dib, 4)? as i32;
+            let height = u16_le(dib, 6)? as i16 as i32;
+            (width, height, u16_le(dib, 10)?, 0, 0, 3)
+        } else if header_size >= BMP_INFO_HEADER_SIZE {
+            (
+                u32_le(dib, 4)? as i32,
+                u32_le(dib, 8)? as i32,
+                u16_le(dib, 14)?,
+                u32_le(dib, 16)?,
+                u32_le(dib, 32)? as usize,
+                4,
+            )
+        } else {
+            return Err(DxfError::InvalidFormat(format!(
+                "Unsupported bitmap header size {}",
+                header_size
+            )));
+        };
+
+    if compression != 0 {
+        return Err(DxfError::NotImplemented(format!(
+            "Compressed bitmaps (compression {}) are not supported",
+            compression
+        )));
+    }
+    if width <= 0 || height == 0 {
+        return Err(DxfError::InvalidFormat(format!(
+            "Invalid bitmap size {}x{}",
+            width, height
+        )));
+    }
+
+    let palette_len = match bit_count {
+        1 | 4 | 8 if colors_used == 0 => 1usize << bit_count,
+        1 | 4 | 8 => colors_used,
+        16 | 24 | 32 => 0,
+        _ => {
+            return Err(DxfError::InvalidFormat(format!(
+                "Unsupported bitmap bit count {}",
+                bit_count
+            )))
+        }
+    };
+    let palette_start = header_size;
+    let palette_end = palette_start + palette_len * palette_entry_size;
+    let palette = dib.get(palette_start..palette_end).ok_or_else(|| {
+        DxfError::InvalidFormat("Bitmap palette extends beyond the image data".into())
+    })?;
+
+    let width = width as usize;
+    let top_down = height < 0;
+    let height = height.unsigned_abs() as usize;
+    let stride = (width * bit_count as usize).div_ceil(32) * 4;
+    let pixel_start = pixel_offset.unwrap_or(palette_end);
+    let rows = dib
+        .get(pixel_start..pixel_start + stride * height)
+        .ok_or_else(|| DxfError::InvalidFormat("Bitmap pixels extend beyond the image data".into()))?;
+
+    let mut pixels = vec![0u8; width * height * 4];
+    for y in 0..height {
+        let row = &rows[y * stride..(y + 1) * stride];
+        let target_y = if top_down { y } else { height - 1 - y };
+        for x in 0..width {
+            let (b, g, r) = match bit_count {
+                1 | 4 | 8 => {
+                    let bits = bit_count as usize;
+                    let byte = row[x * bits / 8];
+                    let shift = 8 - bits - (x * bits % 8);
+                    let index = ((byte >> shift) & ((1u16 << bits) - 1) as u8) as usize;
+                    match palette.get(index * palette_entry_size..index * palette_entry_size + 3) {
+                        Some(entry) => (entry[0], entry[1], entry[2]),
+                        None => (0, 0, 0),
+                    }
+                }
+                16 => {
+                    // 5-5-5
+                    let value = u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]);
+                    let scale = |v: u16| ((v & 0x1F) * 255 / 31) as u8;
+                    (scale(value), scale(value >> 5), scale(value >> 10))
+                }
+                24 => (row[x * 3], row[x * 3 + 1], row[x * 3 + 2]),
+                // The fourth byte of uncompressed 32-bit bitmaps is unused.
+                _ => (row[x * 4], row[x * 4 + 1], row[x * 4 + 2]),
+            };
+            let i = (target_y * width + x) * 4;
+            pixels[i..i + 4].copy_from_slice(&[r, g, b, 255]);
+        }
+    }
+
+    PreviewImage::new(width as u32, height as u32, pixels)
+}
+
+/// Encode an image as a 24-bit device independent bitmap (no file header),
+/// the form DWG files store BMP thumbnails in. Alpha is dropped.
+pub fn encode_bmp(image: &PreviewImage) -> Vec<u8> {
+    let width = image.width as usize;
+    let height = image.height as usize;
+    let stride = (width * 3).div_ceil(4) * 4;
+
+    let mut out = Vec::with_capacity(BMP_INFO_HEADER_SIZE + stride * height);
+    out.extend_from_slice(&(BMP_INFO_HEADER_SIZE as u32).to_le_bytes());
+    out.extend_from_slice(&(width as i32).to_le_bytes());
+    out.extend_from_slice(&(height as i32).to_le_bytes());
+    // Planes, bit count, compression (BI_RGB), image size
+    out.extend_from_slice(&1u16.to_le_bytes());
+    out.extend_from_slice(&24u16.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&((stride * height) as u32).to_le_bytes());
+    // Resolution, colors used, important colors
+    for _ in 0..4 {
+        out.extend_from_slice(&0u32.to_le_bytes());
+    }
+
+    // Rows are stored bottom-up.
+    for y in (0..height).rev() {
+        let start = out.len();
+        for x in 0..width {
+            let i = (y * width + x) * 4;
+            out.extend_from_slice(&[image.pixels[i + 2], image.pixels[i + 1], image.pixels[i]]);
+        }
+        out.resize(start + stride, 0);
+    }
+    out
+}
+
+// ── PNG ────────────────────────────────────────────────────────────────
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Decode a non-interlaced PNG stream of any color type and bit depth.
+pub fn decode_png(data: &[u8]) -> Result<PreviewImage> {
+    if !data.starts_with(&PNG_SIGNATURE) {
+        return Err(DxfError::InvalidFormat("Missing PNG signature".into()));
+    }
+
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut transparency: &[u8] = &[];
+    let mut compressed = Vec::new();
+
+    let mut pos = PNG_SIGNATURE.len();
+    while pos + 8 <= data.len() {
+        let length = u32_be(data, pos)? as usize;
+        let chunk_type = &data[pos + 4..pos + 8];
+        let body = data
+            .get(pos + 8..pos + 8 + length)
+            .ok_or_else(|| DxfError::InvalidFormat("PNG chunk extends beyond the data".into()))?;
+        let expected = u32_be(data, pos + 8 + length)?;
+
+        let mut crc = Crc::new();
+        crc.update(chunk_type);
+        crc.update(body);
+        if crc.sum() != expected {
+            return Err(DxfError::ChecksumMismatch {
+                expected: expected.into(),
+                actual: crc.sum().into(),
+            });
+        }
+
+        match chunk_type {
+            b"IHDR" => header = Some(PngHeader::parse(body)?),
+            b"PLTE" => palette = body,
+            b"tRNS" => transparency = body,
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + length;
+    }
+
+    let header = header.ok_or_else(|| DxfError::InvalidFormat("PNG has no IHDR chunk".into()))?;
+    if header.interlace != 0 {
+        return Err(DxfError::NotImplemented("Interlaced PNG images are not supported".into()));
+    }
+
+    let mut raw = Vec::new();
+    ZlibDecoder::new(compressed.as_slice())
+        .read_to_end(&mut raw)
+        .map_err(|e| DxfError::Decompression(format!("PNG image data: {}", e)))?;
+
+    let bits_per_pixel = header.channels() * header.bit_depth as usize;
+    let stride = (header.width as usize * bits_per_pixel).div_ceil(8);
+    let filter_step = bits_per_pixel.div_ceil(8);
+    let height = header.height as usize;
+    if raw.len() < (stride + 1) * height {
+        return Err(DxfError::InvalidFormat("PNG image data is truncated".into()));
+    }
+
+    let mut previous = vec![0u8; stride];
+    let mut current = vec![0u8; stride];
+    let mut pixels = Vec::with_capacity(header.width as usize * height * 4);
+    for y in 0..height {
+        let line = &raw[y * (stride + 1)..(y + 1) * (stride + 1)];
+        current.copy_from_slice(&line[1..]);
+        unfilter(line[0], &mut current, &previous, filter_step)?;
+        header.expand_row(&current, palette, transparency, &mut pixels)?;
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    PreviewImage::new(header.width, header.height, pixels)
+}
+
+/// Encode an image as an 8-bit RGBA PNG stream.
+pub fn encode_png(image: &PreviewImage) -> Result<Vec<u8>> {
+    let stride = image.width as usize * 4;
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    for row in image.pixels.chunks_exact(stride.max(1)) {
+        // Filter type 0 (none)
+        encoder.write_all(&[0])?;
+        encoder.write_all(row)?;
+    }
+    let compressed = encoder.finish()?;
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend_from_slice(&image.width.to_be_bytes());
+    ihdr.extend_from_slice(&image.height.to_be_bytes());
+    // Bit depth 8, color type 6 (RGBA), compression, filter, interlace
+    ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut out = PNG_SIGNATURE.to_vec();
+    write_png_chunk(&mut out, b"IHDR", &ihdr);
+    write_png_chunk(&mut out, b"IDAT", &compressed);
+    write_png_chunk(&mut out, b"IEND", &[]);
+    Ok(out)
+}
+
+fn write_png_chunk(out: &mut Vec<u8>, chunk_type: &[u8; 4], body: &[u8]) {
+    let mut crc = Crc::new();
+    crc.update(chunk_type);
+    crc.update(body);
+
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend_from_slice(chunk_type);
+    out.extend_from_slice(body);
+    out.extend_from_slice(&crc.sum().to_be_bytes());
+}
+
+/// Reverse the PNG filter of one row in place.
+fn unfilter(filter: u8, row: &mut [u8], previous: &[u8], step: usize) -> Result<()> {
+    match filter {
+        0 => {}
+        1 => {
+            for i in step..row.len() {
+                row[i] = row[i].wrapping_add(row[i - step]);
+            }
+        }
+        2 => {
+            for i in 0..row.len() {
+                row[i] = row[i].wrapping_add(previous[i]);
+            }
+        }
+        3 => {
+            for i in 0..row.len() {
+                let left = if i >= step { row[i - step] } else { 0 };
+                row[i] = row[i].wrapping_add(((left as u16 + previous[i] as u16) / 2) as u8);
+            }
+        }
+        4 => {
+            for i in 0..row.len() {
+                let (left, upper_left) = if i >= step {
+                    (row[i - step], previous[i - step])
+                } else {
+                    (0, 0)
+                };
+                row[i] = row[i].wrapping_add(paeth(left, previous[i], upper_left));
+            }
+        }
+        _ => {
+            return Err(DxfError::InvalidFormat(format!(
+                "Unknown PNG filter type {}",
+                filter
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let pa = (p - a as i16).abs();
+    let pb = (p - b as i16).abs();
+    let pc = (p - c as i16).abs();
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+struct PngHeader {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+    interlace: u8,
+}
+
+impl PngHeader {
+    fn parse(body: &[u8]) -> Result<Self> {
+        if body.len() < 13 {
+            return Err(DxfError::InvalidFormat("PNG IHDR chunk is too short".into()));
+        }
+        let header = Self {
+            width: u32_be(body, 0)?,
+            height: u32_be(body, 4)?,
+            bit_depth: body[8],
+            color_type: body[9],
+            interlace: body[12],
+        };
+        let valid = match header.color_type {
+            0 => matches!(header.bit_depth, 1 | 2 | 4 | 8 | 16),
+            3 => matches!(header.bit_depth, 1 | 2 | 4 | 8),
+            2 | 4 | 6 => matches!(header.bit_depth, 8 | 16),
+            _ => false,
+        };
+        if !valid {
+            return Err(DxfError::InvalidFormat(format!(
+                "Invalid PNG color type {} with bit depth {}",
+                header.color_type, header.bit_depth
+            )));
+        }
+        Ok(header)
+    }
+
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    /// Convert one unfiltered row to RGBA and append it to `out`.
+    fn expand_row(&self, row: &[u8], palette: &[u8], transparency: &[u8], out: &mut Vec<u8>) -> Result<()> {
+        let depth = self.bit_depth as usize;
+        let max = (1u32 << depth) - 1;
+        let sample = |index: usize| -> u16 {
+            match depth {
+                16 => u16::from_be_bytes([row[index * 2], row[index * 2 + 1]]),
+                8 => row[index] as u16,
+                _ => {
+                    let byte = row[index * depth / 8];
+                    let shift = 8 - depth - (index * depth % 8);
+                    ((byte >> shift) as u16) & max as u16
+                }
+            }
+        };
+        let to_u8 = |value: u16| (value as u32 * 255 / max) as u8;
+        // Single transparent color key of gray / RGB images.
+        let key = |i: usize| u16_be(transparency, i * 2).ok();
+
+        let channels = self.channels();
+        for x in 0..self.width as usize {
+            let base = x * channels;
+            let rgba = match self.color_type {
+                0 => {
+                    let v = sample(base);
+                    let alpha = if key(0) == Some(v) { 0 } else { 255 };
+                    let g = to_u8(v);
+                    [g, g, g, alpha]
+                }
+                2 => {
+                    let (r, g, b) = (sample(base), sample(base + 1), sample(base + 2));
+                    let alpha = if key(0) == Some(r) && key(1) == Some(g) && key(2) == Some(b) {
+                        0
+                    } else {
+                        255
+                    };
+                    [to_u8(r), to_u8(g), to_u8(b), alpha]
+                }
+                3 => {
+                    let index = sample(base) as usize;
+                    let entry = palette.get(index * 3..index * 3 + 3).ok_or_else(|| {
+                        DxfError::InvalidFormat(format!("PNG palette index {} out of range", index))
+                    })?;
+                    let alpha = transparency.get(index).copied().unwrap_or(255);
+                    [entry[0], entry[1], entry[2], alpha]
+                }
+                4 => {
+                    let g = to_u8(sample(base));
+                    [g, g, g, to_u8(sample(base + 1))]
+                }
+                _ => [
+                    to_u8(sample(base)),
+                    to_u8(sample(base + 1)),
+                    to_u8(sample(base + 2)),
+                    to_u8(sample(base + 3)),
+                ],
+            };
+            out.extend_from_slice(&rgba);
+        }
+        Ok(())
+    }
+}
+
+// ── Byte helpers ───────────────────────────────────────────────────────
+
+fn slice<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N]> {
+    data.get(offset..offset + N)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| DxfError::InvalidFormat("Preview image data is truncated".into()))
+}
+
+fn u16_le(data: &[u8], offset: usize) -> Result<u16> {
+    slice(data, offset).map(u16::from_le_bytes)
+}
+
+fn u32_le(data: &[u8], offset: usize) -> Result<u32> {
+    slice(data, offset).map(u32::from_le_bytes)
+}
+
+fn u16_be(data: &[u8], offset: usize) -> Result<u16> {
+    slice(data, offset).map(u16::from_be_bytes)
+}
+
+fn u32_be(data: &[u8], offset: usize) -> Result<u32> {
+    slice(data, offset).map(u32::from_be_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_image() -> PreviewImage {
+        let mut pixels = Vec::new();
+        for y in 0..3u8 {
+            for x in 0..5u8 {
+                pixels.extend_from_slice(&[x * 50, y * 100, 200, 255]);
+            }
+        }
+        PreviewImage::new(5, 3, pixels).unwrap()
+    }
+
+    #[test]
+    fn test_bmp_roundtrip() {
+        let image = sample_image();
+        let decoded = decode_bmp(&encode_bmp(&image)).unwrap();
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn test_png_roundtrip() {
+        let mut image = sample_image();
+        image.pixels[3] = 128;
+        let decoded = decode_png(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn test_decode_palette_bmp() {
+        // 2x2, 1 bit per pixel, black / white palette, bottom-up rows
+        let mut dib = Vec::new();
+        dib.extend_from_slice(&40u32.to_le_bytes());
+        dib.extend_from_slice(&2i32.to_le_bytes());
+        dib.extend_from_slice(&2i32.to_le_bytes());
+        dib.extend_from_slice(&1u16.to_le_bytes());
+        dib.extend_from_slice(&1u16.to_le_bytes());
+        dib.extend_from_slice(&[0u8; 24]);
+        dib.extend_from_slice(&[0, 0, 0, 0, 255, 255, 255, 0]);
+        // Bottom row: white, black; top row: black, white
+        dib.extend_from_slice(&[0b1000_0000, 0, 0, 0]);
+        dib.extend_from_slice(&[0b0100_0000, 0, 0, 0]);
+
+        let image = decode_bmp(&dib).unwrap();
+        assert_eq!(image.pixel(0, 0), Some([0, 0, 0, 255]));
+        assert_eq!(image.pixel(1, 0), Some([255, 255, 255, 255]));
+        assert_eq!(image.pixel(0, 1), Some([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_decode_png_rejects_bad_crc() {
+        let mut png = encode_png(&sample_image()).unwrap();
+        png[PNG_SIGNATURE.len() + 10] ^= 0xFF;
+        assert!(matches!(decode_png(&png), Err(DxfError::ChecksumMismatch { .. })));
+    }
+}
@@ -8,38 +8,43 @@
 //! read each section (header variables, classes, handles, objects, etc.)
 //! and assembles the final [`CadDocument`] through a [`DwgDocumentBuilder`].
 
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::fs::File;
 use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
-use crate::document::CadDocument;
+use crate::classes::{DxfClass, ProxyFlags};
+use crate::document::{CadDocument, HeaderVariables};
 use crate::error::{DxfError, Result};
+use crate::io::probe::DrawingInfo;
 use crate::notification::{Notification, NotificationType};
-use crate::types::DxfVersion;
+use crate::types::{DxfVersion, Handle};
 
-// CRC and checksum modules available for future use:
-// use super::crc::{crc8_decode, CRC_TABLE};
-// use super::dwg_checksum_calculator;
-use super::dwg_document_builder::DwgDocumentBuilder;
+use super::crc::{crc64, crc8_value};
+use super::crc32_stream_handler::Crc32StreamHandler;
+use super::dwg_checksum_calculator::{calculate, MAGIC_SEQUENCE};
+use super::dwg_document_builder::{DwgDocumentBuilder, DwgObject};
 use super::dwg_reader_configuration::DwgReaderConfiguration;
 use super::dwg_stream_readers::dwg_app_info_reader::DwgAppInfoReader;
-use super::dwg_stream_readers::dwg_classes_reader::{DwgClassDef, DwgClassesReader};
+use super::dwg_stream_readers::dwg_classes_reader::DwgClassesReader;
+use super::dwg_stream_readers::dwg_file_dep_list_reader::DwgFileDepListReader;
 use super::dwg_stream_readers::dwg_handle_reader::DwgHandleReader;
 use super::dwg_stream_readers::dwg_header_reader::DwgHeaderReader;
 use super::dwg_stream_readers::dwg_lz77_ac18_decompressor::DwgLz77Ac18Decompressor;
 use super::dwg_stream_readers::dwg_lz77_ac21_decompressor::DwgLz77Ac21Decompressor;
-use super::dwg_stream_readers::dwg_object_reader::DwgObjectReader;
+use super::dwg_stream_readers::dwg_object_reader::{DwgObjectReader, DwgRawObject};
 use super::dwg_stream_readers::dwg_preview_reader::{DwgPreview, DwgPreviewReader};
+use super::dwg_stream_readers::dwg_rev_history_reader::DwgRevHistoryReader;
 use super::dwg_stream_readers::dwg_stream_reader_base::DwgStreamReaderBase;
 use super::dwg_stream_readers::dwg_summary_info_reader::{CadSummaryInfo, DwgSummaryInfoReader};
+use super::dwg_stream_readers::dwg_template_reader::DwgTemplateReader;
 use super::dwg_stream_readers::idwg_stream_reader::DwgStreamReader;
 use super::file_headers::{
     Dwg21CompressedMetadata, DwgFileHeader, DwgFileHeaderAC18,
     DwgFileHeaderData, DwgLocalSectionMap, DwgSectionDefinition,
-    DwgSectionDescriptor, DwgSectionHash,
+    DwgSectionDescriptor, DwgSectionHash, DwgSectionLocatorRecord,
 };
 
 // ── Constants ─────────────────────────────────────────────────────────────
@@ -59,24 +64,9 @@ const AC15_END_SENTINEL: [u8; 16] = [
 /// DWG magic string: "AC" prefix.
 const MAGIC_NUMBER: &[u8; 2] = b"AC";
 
-/// Size of the AC18 system section XOR mask seed.
+/// Seed of the XOR mask applied to AC18 data page headers.
 const SYSTEM_SECTION_RANDOM_SEED: u32 = 0x4164536B;
 
-/// Page type for AC21 section pages.
-const AC21_PAGE_TYPE: i64 = 0x4163043B;
-
-// ── Page header data for AC21 ─────────────────────────────────────────────
-
-/// On-disk header for an AC21 section page.
-#[derive(Debug, Default)]
-struct PageHeaderData {
-    pub section_type: i64,
-    pub decompressed_size: i64,
-    pub compressed_size: i64,
-    pub compression_type: i64,
-    pub checksum: i64,
-}
-
 // ── DwgReader ─────────────────────────────────────────────────────────────
 
 /// Reads DWG binary files and produces a [`CadDocument`].
@@ -104,6 +94,10 @@ pub struct DwgReader<R: Read + Seek> {
     encoding: String,
     /// Collected notifications.
     notifications: Vec<Notification>,
+    /// Object reader over the indexed objects section, for on-demand access.
+    object_index: Option<DwgObjectReader>,
+    /// Objects already decoded on demand, by handle.
+    object_cache: HashMap<Handle, Option<DwgObject>>,
 }
 
 impl DwgReader<BufReader<File>> {
@@ -134,7 +128,7 @@ impl<R: Read + Seek> DwgReader<R> {
         let version = Self::detect_version(&mut stream)?;
 
         let file_header = DwgFileHeader::create(version)?;
-        let document = CadDocument::default();
+        let document = CadDocument::with_version(version);
         let builder = DwgDocumentBuilder::new(version, document, configuration.clone());
 
         Ok(Self {
@@ -145,6 +139,8 @@ impl<R: Read + Seek> DwgReader<R> {
             configuration,
             encoding: String::new(),
             notifications: Vec::new(),
+            object_index: None,
+            object_cache: HashMap::new(),
         })
     }
 
@@ -157,7 +153,9 @@ impl<R: Read + Seek> DwgReader<R> {
     /// 4. Read classes
     /// 5. Read handles (object map)
     /// 6. Read summary info (optional, AC18+)
-    /// 7. Read objects
+    /// 7. Read app info, file dependencies and revision history (AC18+)
+    /// 8. Read template
+    /// 9. Read objects
     pub fn read(&mut self) -> Result<CadDocument> {
         // 1. File header
         self.read_file_header()?;
@@ -173,13 +171,28 @@ impl<R: Read + Seek> DwgReader<R> {
         }
 
         // 3. Header variables
-        self.read_header()?;
+        if let Err(e) = self.read_header() {
+            self.recover_from(e, "Header section unreadable, using default header variables")?;
+        }
 
         // 4. Classes
-        self.read_classes()?;
+        if let Err(e) = self.read_classes() {
+            self.recover_from(e, "Classes section unreadable, custom objects may be skipped")?;
+        }
 
-        // 5. Handle / object map
-        let handle_map = self.read_handles()?;
+        // 5. Handle / object map. In recover mode an unusable map is
+        // rebuilt from the objects section.
+        let handle_map = match self.read_handles() {
+            Ok(map) if !map.is_empty() || !self.configuration.recover => Some(map),
+            Ok(_) => {
+                self.notify("Handles section is empty", NotificationType::Error);
+                None
+            }
+            Err(e) => {
+                self.recover_from(e, "Handles section unreadable")?;
+                None
+            }
+        };
 
         // 6. Summary info (AC18+ only, if configured)
         if self.configuration.read_summary_info
@@ -203,7 +216,31 @@ impl<R: Read + Seek> DwgReader<R> {
             }
         }
 
-        // 8. ObjFreeSpace (non-fatal)
+        // 8. File dependencies and revision history (AC18+, non-fatal)
+        if self.version >= DxfVersion::AC1018 {
+            if let Err(e) = self.read_file_dep_list() {
+                self.notify(
+                    format!("Failed to read file dependency list: {}", e),
+                    NotificationType::Warning,
+                );
+            }
+            if let Err(e) = self.read_rev_history() {
+                self.notify(
+                    format!("Failed to read revision history: {}", e),
+                    NotificationType::Warning,
+                );
+            }
+        }
+
+        // 9. Template (non-fatal)
+        if let Err(e) = self.read_template() {
+            self.notify(
+                format!("Failed to read template: {}", e),
+                NotificationType::Warning,
+            );
+        }
+
+        // 10. ObjFreeSpace (non-fatal)
         if let Err(e) = self.read_obj_free_space() {
             self.notify(
                 format!("Failed to read ObjFreeSpace: {}", e),
@@ -211,8 +248,10 @@ impl<R: Read + Seek> DwgReader<R> {
             );
         }
 
-        // 9. Object section
-        self.read_objects(handle_map)?;
+        // 11. Object section
+        if let Err(e) = self.read_objects(handle_map) {
+            self.recover_from(e, "Objects section unreadable")?;
+        }
 
         // Build and return document
         let notifications = std::mem::take(&mut self.notifications);
@@ -244,77 +283,229 @@ impl<R: Read + Seek> DwgReader<R> {
                 "No preview image address in file header".into(),
             ));
         }
-        self.stream
-            .seek(SeekFrom::Start(self.file_header.preview_address as u64))?;
-
-        let buffer = self.get_section_stream(DwgSectionDefinition::PREVIEW)?;
-        let mut reader =
-            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
-        DwgPreviewReader::read(&mut reader)
+        self.read_preview_section()
     }
 
     /// Read only the summary info from the DWG (AC18+).
     pub fn read_summary_info(&mut self) -> Result<CadSummaryInfo> {
         self.read_file_header()?;
-        let buffer = self.get_section_stream(DwgSectionDefinition::SUMMARY_INFO)?;
-        let mut reader =
-            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
-        DwgSummaryInfoReader::read(&mut reader, self.version)
+        self.read_summary_info_section()
+    }
+
+    /// Read only the drawing metadata: file header, header variables,
+    /// summary info (AC18+) and preview. Classes, handles and objects are
+    /// not read; an unreadable summary or preview is left empty.
+    pub fn probe(&mut self) -> Result<DrawingInfo> {
+        self.read_file_header()?;
+        self.read_header()?;
+
+        let mut info = DrawingInfo::from_header(self.version, &self.builder.document.header);
+        info.code_page = self.file_header.drawing_code_page.clone();
+
+        if self.version >= DxfVersion::AC1018 {
+            if let Ok(summary_info) = self.read_summary_info_section() {
+                info.summary_info = summary_info;
+            }
+        }
+
+        if self.file_header.preview_address > 0 {
+            info.preview = self.read_preview_section().ok();
+        }
+
+        Ok(info)
+    }
+
+    // ── On-demand object access ───────────────────────────────────────
+
+    /// Read the file header, header variables, classes and handle map so
+    /// that single objects can be decoded with [`DwgReader::read_object`]
+    /// without parsing the whole objects section. Calling it again is a
+    /// no-op.
+    pub fn load_object_index(&mut self) -> Result<()> {
+        if self.object_index.is_some() {
+            return Ok(());
+        }
+
+        self.read_file_header()?;
+        self.read_header()?;
+        self.read_classes()?;
+        let handle_map = self.read_handles()?;
+
+        let obj_reader = self.create_object_reader(VecDeque::new(), handle_map)?;
+        self.object_index = Some(obj_reader);
+        Ok(())
+    }
+
+    /// Decode the object stored under `handle` into its typed entity or
+    /// object, loading the object index first if needed. The table entries
+    /// it references are read as well, so layer, style and block names
+    /// resolve. Results are cached by handle, so repeated lookups do not
+    /// read the file again. Returns `None` when the handle is not in the
+    /// handle map or the object has no typed form of its own (see
+    /// [`DwgDocumentBuilder::build_single_object`]).
+    pub fn read_object(&mut self, handle: Handle) -> Result<Option<&DwgObject>> {
+        self.load_object_index()?;
+
+        if !self.object_cache.contains_key(&handle) {
+            let object = self.build_single_object(handle.value())?;
+            self.object_cache.insert(handle, object);
+        }
+
+        Ok(self.object_cache.get(&handle).and_then(|o| o.as_ref()))
+    }
+
+    /// Handles of every object in the handle map, in ascending order.
+    pub fn object_handles(&mut self) -> Result<Vec<Handle>> {
+        self.load_object_index()?;
+        Ok(self
+            .object_index
+            .as_ref()
+            .map(|r| r.map_handles().map(Handle::new).collect())
+            .unwrap_or_default())
+    }
+
+    /// Header variables read by [`DwgReader::load_object_index`]; they hold
+    /// the handles of the table controls, dictionaries and block records
+    /// used as entry points for [`DwgReader::read_object`].
+    pub fn header(&self) -> &HeaderVariables {
+        &self.builder.document.header
+    }
+
+    /// Read the raw object under `handle`, with the vertices of a polyline,
+    /// and convert it through the builder.
+    fn build_single_object(&mut self, handle: u64) -> Result<Option<DwgObject>> {
+        let obj_reader = self
+            .object_index
+            .as_mut()
+            .expect("object index loaded by read_object");
+        let Some(raw) = obj_reader.read_handle(handle)? else {
+            return Ok(None);
+        };
+
+        // Vertices: the owned object list for R2004+, the chain of next
+        // entity links before.
+        let mut vertices = HashMap::new();
+        if let Some(owned) = raw.handle_list_props.get("owned_object_handles") {
+            for &h in owned {
+                if let Some(vertex) = obj_reader.read_handle(h)? {
+                    vertices.insert(h, vertex);
+                }
+            }
+        } else if let Some(&first) = raw.handle_props.get("first_vertex_handle") {
+            let last = raw.handle_props.get("last_vertex_handle").copied().unwrap_or(0);
+            let mut current = first;
+            while current != 0 && !vertices.contains_key(&current) {
+                let Some(vertex) = obj_reader.read_handle(current)? else {
+                    break;
+                };
+                let next = vertex.handle_props.get("next_entity_handle").copied().unwrap_or(0);
+                vertices.insert(current, vertex);
+                if current == last {
+                    break;
+                }
+                current = next;
+            }
+        }
+
+        for object in std::iter::once(&raw).chain(vertices.values()) {
+            let referenced: Vec<u64> =
+                object.handle_props.values().chain(object.eed.keys()).copied().collect();
+            self.register_entry_names(&referenced)?;
+        }
+
+        let objects: HashMap<u64, &DwgRawObject> =
+            vertices.iter().map(|(&h, vertex)| (h, vertex)).collect();
+        Ok(self.builder.build_single_object(&raw, &objects))
+    }
+
+    /// Read the table entries among `handles` that are not named yet and
+    /// register their names with the builder.
+    fn register_entry_names(&mut self, handles: &[u64]) -> Result<()> {
+        let obj_reader = self
+            .object_index
+            .as_mut()
+            .expect("object index loaded by read_object");
+        for &h in handles {
+            if h == 0 || self.builder.try_get_name(h).is_some() {
+                continue;
+            }
+            if let Some(entry) = obj_reader.read_handle(h)? {
+                self.builder.register_entry_name(&entry);
+            }
+        }
+        Ok(())
     }
 
     // ── Private section readers ───────────────────────────────────────
 
     /// Read preview image data into the builder.
     fn read_preview_internal(&mut self) -> Result<()> {
+        let _preview = self.read_preview_section()?;
+        // Preview data is available but not stored on the document in this port.
+        Ok(())
+    }
+
+    /// Read and parse the PREVIEW section.
+    fn read_preview_section(&mut self) -> Result<DwgPreview> {
         self.stream
             .seek(SeekFrom::Start(self.file_header.preview_address as u64))?;
 
         let buffer = self.get_section_stream(DwgSectionDefinition::PREVIEW)?;
         let mut reader =
             DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
-        let _preview = DwgPreviewReader::read(&mut reader)?;
-        // Preview data is available but not stored on the document in this port.
-        Ok(())
+        DwgPreviewReader::read(&mut reader)
     }
 
     /// Read HEADER section variables into the builder.
     fn read_header(&mut self) -> Result<()> {
         let buffer = self.get_section_stream(DwgSectionDefinition::HEADER)?;
-        let mut reader =
-            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
+        if self.configuration.crc_check {
+            self.verify_section_crc(DwgSectionDefinition::HEADER, &buffer)?;
+        }
 
         let result = DwgHeaderReader::read(
             self.version,
             self.file_header.acad_maintenance_version,
-            &mut reader,
+            &buffer,
         )?;
 
-        // Transfer object pointers to builder's header handles collection
-        for (key, value) in result.object_pointers.handles.iter() {
-            self.builder.header_handles.set(key, *value);
-        }
-
-        // Store header data in document via builder
-        for (key, value) in result.header.vars.iter() {
-            // Convert header vars to document header fields.
-            // The actual mapping is done by the builder during build_document().
-            let _ = (key, value); // Header vars stored but mapping deferred.
-        }
+        self.builder.document.header = result.header;
+        self.builder.header_handles = result.object_pointers;
+        self.builder.notifications.extend(result.notifications);
 
         Ok(())
     }
 
-    /// Read CLASSES section.
+    /// Read CLASSES section into the document class collection.
     fn read_classes(&mut self) -> Result<()> {
         let buffer = self.get_section_stream(DwgSectionDefinition::CLASSES)?;
-        let mut reader =
-            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
+        if self.configuration.crc_check {
+            self.verify_section_crc(DwgSectionDefinition::CLASSES, &buffer)?;
+        }
 
-        let _classes: Vec<DwgClassDef> = DwgClassesReader::read(&mut reader, self.version)?;
+        let result = DwgClassesReader::read(
+            self.version,
+            self.file_header.acad_maintenance_version,
+            &buffer,
+        )?;
 
         // Classes are used to resolve custom object types in the object reader.
-        // Store them on the builder for later use.
-        // (In the C# code these feed into the DwgDocumentBuilder.Classes list.)
+        for def in result.classes {
+            self.builder.document.classes.add_or_update(DxfClass {
+                dxf_name: def.dxf_name,
+                cpp_class_name: def.cplusplus_name,
+                application_name: def.app_name,
+                proxy_flags: ProxyFlags(def.proxy_cap_flags as u16),
+                instance_count: def.instance_count,
+                was_zombie: def.was_zombie,
+                is_an_entity: def.is_an_entity,
+                class_number: def.class_number,
+                item_class_id: def.item_class_id,
+                dwg_version: def.dwg_version as i16,
+                maintenance_version: def.maintenance_version as i16,
+            });
+        }
+        self.builder.notifications.extend(result.notifications);
 
         Ok(())
     }
@@ -322,6 +513,9 @@ impl<R: Read + Seek> DwgReader<R> {
     /// Read HANDLES (object map) section.
     fn read_handles(&mut self) -> Result<BTreeMap<u64, i64>> {
         let buffer = self.get_section_stream(DwgSectionDefinition::HANDLES)?;
+        if self.configuration.crc_check {
+            self.verify_handles_crc(&buffer)?;
+        }
         let mut reader =
             DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
 
@@ -334,13 +528,16 @@ impl<R: Read + Seek> DwgReader<R> {
 
     /// Read SUMMARY_INFO section.
     fn read_summary_info_internal(&mut self) -> Result<()> {
+        self.builder.document.summary_info = self.read_summary_info_section()?;
+        Ok(())
+    }
+
+    /// Read and parse the SUMMARY_INFO section.
+    fn read_summary_info_section(&mut self) -> Result<CadSummaryInfo> {
         let buffer = self.get_section_stream(DwgSectionDefinition::SUMMARY_INFO)?;
         let mut reader =
             DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
-
-        let _summary = DwgSummaryInfoReader::read(&mut reader, self.version)?;
-        // Summary info could be stored on the document; left for future integration.
-        Ok(())
+        DwgSummaryInfoReader::read(&mut reader, self.version)
     }
 
     /// Read APP_INFO section (AC18+ only).
@@ -353,6 +550,46 @@ impl<R: Read + Seek> DwgReader<R> {
         Ok(())
     }
 
+    /// Read FILE_DEP_LIST section (AC18+ only).
+    fn read_file_dep_list(&mut self) -> Result<()> {
+        let buffer = self.get_section_stream(DwgSectionDefinition::FILE_DEP_LIST)?;
+        let mut reader =
+            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
+
+        self.builder.document.file_dependencies = DwgFileDepListReader::read(&mut reader)?;
+        Ok(())
+    }
+
+    /// Read REV_HISTORY section (AC18+ only).
+    fn read_rev_history(&mut self) -> Result<()> {
+        let buffer = self.get_section_stream(DwgSectionDefinition::REV_HISTORY)?;
+        let mut reader =
+            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
+
+        self.builder.document.revision_history = Some(DwgRevHistoryReader::read(&mut reader)?);
+        Ok(())
+    }
+
+    /// Read TEMPLATE section. Older AC15 files carry no template record,
+    /// which is not an error.
+    fn read_template(&mut self) -> Result<()> {
+        if let Some(ac15) = self.file_header.as_ac15() {
+            let record = DwgSectionDefinition::get_section_locator_by_name(
+                DwgSectionDefinition::TEMPLATE,
+            );
+            if !record.is_some_and(|r| ac15.records.contains_key(&r)) {
+                return Ok(());
+            }
+        }
+
+        let buffer = self.get_section_stream(DwgSectionDefinition::TEMPLATE)?;
+        let mut reader =
+            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(buffer));
+
+        self.builder.document.template = Some(DwgTemplateReader::read(&mut reader, self.version)?);
+        Ok(())
+    }
+
     /// Read OBJ_FREE_SPACE section (non-critical).
     fn read_obj_free_space(&mut self) -> Result<()> {
         let buffer = self.get_section_stream(DwgSectionDefinition::OBJ_FREE_SPACE)?;
@@ -366,31 +603,64 @@ impl<R: Read + Seek> DwgReader<R> {
         Ok(())
     }
 
-    /// Read OBJECTS section by traversing the handle map.
-    fn read_objects(&mut self, handle_map: BTreeMap<u64, i64>) -> Result<()> {
-        let buffer = self.get_section_stream(DwgSectionDefinition::ACDB_OBJECTS)?;
+    /// Read OBJECTS section by traversing the handle map. Without a map
+    /// (recover mode) the objects are located by scanning the section.
+    fn read_objects(&mut self, handle_map: Option<BTreeMap<u64, i64>>) -> Result<()> {
+        let rebuild_map = handle_map.is_none();
+        let handle_map = handle_map.unwrap_or_default();
 
-        // Build the handle queue from the header object pointers.
+        // Build the handle queue from the header object pointers, then every
+        // remaining handle of the map so objects not reachable through the
+        // header (block contents, table entries) are read as well.
         let mut handles: VecDeque<u64> = VecDeque::new();
         for handle in self.builder.header_handles.all_handles() {
             handles.push_back(handle);
         }
+        handles.extend(handle_map.keys().copied());
 
-        let mut obj_reader = DwgObjectReader::new(
-            self.version,
-            buffer,
-            handles,
-            handle_map,
-        );
+        let mut obj_reader = self.create_object_reader(handles, handle_map)?;
+
+        if rebuild_map {
+            let count = obj_reader.rebuild_handle_map();
+            self.notify(
+                format!("Rebuilt the object map by scanning the objects section: {} objects found", count),
+                NotificationType::Warning,
+            );
+        }
 
-        let _objects = obj_reader.read()?;
-        // Objects are parsed; attaching them to the document is done by
-        // the builder in build_document(). In a full port the raw objects
-        // would be converted to typed CadObjects and handed to the builder.
+        // Objects are converted to typed entities/objects and attached to
+        // their owners by the builder in build_document().
+        self.builder.raw_objects = obj_reader.read()?;
+        self.builder.notifications.extend(obj_reader.take_notifications());
 
         Ok(())
     }
 
+    /// Create an object reader over the OBJECTS section, resolving custom
+    /// types through the classes read so far.
+    fn create_object_reader(
+        &mut self,
+        handles: VecDeque<u64>,
+        handle_map: BTreeMap<u64, i64>,
+    ) -> Result<DwgObjectReader> {
+        let buffer = self.get_section_stream(DwgSectionDefinition::ACDB_OBJECTS)?;
+
+        let mut classes = BTreeMap::new();
+        let mut entity_classes = HashSet::new();
+        for class in self.builder.document.classes.iter() {
+            classes.insert(class.class_number, class.dxf_name.clone());
+            if class.is_an_entity {
+                entity_classes.insert(class.class_number);
+            }
+        }
+
+        Ok(DwgObjectReader::new(self.version, buffer, handles, handle_map)
+            .with_classes(classes)
+            .with_entity_classes(entity_classes)
+            .with_failsafe(self.configuration.failsafe || self.configuration.recover)
+            .with_crc_check(self.configuration.crc_check))
+    }
+
     // ── File header reading ───────────────────────────────────────────
 
     /// Detect the DWG version from the first 6 bytes of the stream.
@@ -453,17 +723,19 @@ impl<R: Read + Seek> DwgReader<R> {
     /// Read an AC15 record-based file header.
     ///
     /// Layout (after the 6-byte version magic):
-    /// - 7 unknown bytes
+    /// - 5 unknown bytes
     /// - 1 byte: acad maintenance version
     /// - 1 byte: drawing byte (ignored)
-    /// - 2 bytes: code page number
+    /// - 1 int32: preview image seeker (offset 0x0D)
+    /// - 2 bytes: unknown (0x1B 0x19)
+    /// - 2 bytes: code page number (offset 0x13)
     /// - 1 int32: number of section records (typically 3–6)
-    /// - N × (record_number, seeker, size) — each 3 × i32
+    /// - N × (RC record_number, RL seeker, RL size)
     /// - CRC (2 bytes)
     /// - 16 bytes: sentinel
     fn read_file_header_ac15(&mut self) -> Result<()> {
-        // 7 unknown bytes
-        let mut unknown = [0u8; 7];
+        // 5 unknown bytes
+        let mut unknown = [0u8; 5];
         self.stream.read_exact(&mut unknown)?;
 
         // Acad maintenance version
@@ -473,9 +745,16 @@ impl<R: Read + Seek> DwgReader<R> {
         // Drawing byte (unused)
         let _drawing_byte = self.stream.read_u8()?;
 
+        // Preview address (image seeker)
+        self.file_header.preview_address =
+            self.stream.read_i32::<LittleEndian>()? as i64;
+
+        // Unknown bytes (0x1B, 0x19)
+        let _unknown = self.stream.read_u16::<LittleEndian>()?;
+
         // Code page
         let code_page = self.stream.read_u16::<LittleEndian>()?;
-        self.encoding = format!("ANSI_{}", code_page);
+        self.encoding = code_page_name(code_page);
         self.file_header.drawing_code_page = self.encoding.clone();
 
         // Number of section locator records
@@ -492,7 +771,7 @@ impl<R: Read + Seek> DwgReader<R> {
         };
 
         for _ in 0..num_records {
-            let record_number = self.stream.read_i32::<LittleEndian>()?;
+            let record_number = self.stream.read_u8()? as i32;
             let seeker = self.stream.read_i32::<LittleEndian>()?;
             let size = self.stream.read_i32::<LittleEndian>()?;
 
@@ -503,19 +782,24 @@ impl<R: Read + Seek> DwgReader<R> {
             );
         }
 
-        // CRC (2 bytes, validate if configured)
-        let _crc = self.stream.read_u16::<LittleEndian>()?;
+        // CRC of the file header up to this point
+        let crc_position = self.stream.stream_position()?;
+        let crc = self.stream.read_u16::<LittleEndian>()?;
+        if self.configuration.crc_check {
+            let mut data = vec![0u8; crc_position as usize];
+            self.stream.seek(SeekFrom::Start(0))?;
+            self.stream.read_exact(&mut data)?;
+            self.stream.seek(SeekFrom::Start(crc_position + 2))?;
+
+            let actual = crc8_value(0xC0C1, &data, 0, data.len());
+            self.check_crc("AC15 file header", crc as u32, actual as u32)?;
+        }
 
         // Read sentinel (16 bytes)
         let mut sentinel = [0u8; 16];
         self.stream.read_exact(&mut sentinel)?;
         // Validation of sentinel is optional.
 
-        // Preview address (image seeker) at offset 0x0D from start
-        self.stream.seek(SeekFrom::Start(0x0D))?;
-        self.file_header.preview_address =
-            self.stream.read_i32::<LittleEndian>()? as i64;
-
         Ok(())
     }
 
@@ -528,60 +812,83 @@ impl<R: Read + Seek> DwgReader<R> {
     /// - 1 byte: acad maintenance version
     /// - 1 byte: drawing byte
     /// - 4 bytes: preview address
-    /// - 2 bytes: dwg version
-    /// - 2 bytes: app release version
-    /// - 16 bytes: unknown
-    /// - 0x80 bytes encrypted system section (XOR with randseed mask)
+    /// - 1 byte: dwg version
+    /// - 1 byte: app release version
+    /// - 2 bytes: code page
+    /// - 3 zero bytes, then security type, summary info, VBA project and
+    ///   app info addresses
+    /// - 0x6C bytes at 0x80: system section encrypted with the magic sequence
     /// - Section page map and section map follow
     fn read_file_header_ac18(&mut self) -> Result<()> {
-        // 5 unknown bytes
+        // 5 bytes of 0x00
         let mut buf5 = [0u8; 5];
         self.stream.read_exact(&mut buf5)?;
 
-        // Acad maintenance version
+        // 0x0B: maintenance release version
         self.file_header.acad_maintenance_version =
             self.stream.read_u8()? as i32;
 
-        // Drawing byte
+        // 0x0C: byte 0x00, 0x01 or 0x03
         let _drawing_byte = self.stream.read_u8()?;
 
-        // Preview address
+        // 0x0D: preview address, points at the image page + page header size
         self.file_header.preview_address =
             self.stream.read_i32::<LittleEndian>()? as i64;
 
-        // DWG version + app release version
+        // 0x11: DWG version, 0x12: application maintenance release version
         let dwg_version = self.stream.read_u8()?;
         let app_release_version = self.stream.read_u8()?;
 
-        // Skip 2 bytes unknown
-        let mut skip2 = [0u8; 2];
-        self.stream.read_exact(&mut skip2)?;
+        // 0x13: code page
+        let code_page = self.stream.read_u16::<LittleEndian>()?;
+        self.encoding = code_page_name(code_page);
+        self.file_header.drawing_code_page = self.encoding.clone();
+
+        // 0x15: 3 bytes of 0x00
+        let mut buf3 = [0u8; 3];
+        self.stream.read_exact(&mut buf3)?;
 
-        // Read and decrypt system section (0x14 offset → 20 bytes read so far from start+6)
-        // The AC18 encrypted header starts at offset 0x20 in the file (32 bytes from start).
-        // Move to offset 0x20 from the beginning of the file.
-        self.stream.seek(SeekFrom::Start(0x20))?;
+        let security_type = self.stream.read_i32::<LittleEndian>()? as i64;
+        let _unknown = self.stream.read_i32::<LittleEndian>()?;
+        let summary_info_addr = self.stream.read_i32::<LittleEndian>()? as i64;
+        let vba_project_addr = self.stream.read_i32::<LittleEndian>()? as i64;
+        // 0x28: 0x00000080, 0x2C: app info address
+        let _0x80 = self.stream.read_i32::<LittleEndian>()?;
+        let _app_info_addr = self.stream.read_i32::<LittleEndian>()?;
 
-        // Read 0x6C bytes of encrypted section data
+        // 0x80: 0x6C bytes of data encrypted with the magic sequence
+        self.stream.seek(SeekFrom::Start(0x80))?;
         let mut encrypted_header = vec![0u8; 0x6C];
         self.stream.read_exact(&mut encrypted_header)?;
-
-        // Decrypt with XOR mask
         Self::decrypt_system_section(&mut encrypted_header);
 
-        // Parse decrypted header
+        if self.configuration.crc_check {
+            // CRC-32 of the header with its CRC field (0x68) zeroed
+            let expected = u32::from_le_bytes([
+                encrypted_header[0x68],
+                encrypted_header[0x69],
+                encrypted_header[0x6A],
+                encrypted_header[0x6B],
+            ]);
+            let mut data = encrypted_header.clone();
+            data[0x68..0x6C].fill(0);
+            let mut crc_handler = Crc32StreamHandler::new(Cursor::new(data), 0);
+            std::io::copy(&mut crc_handler, &mut std::io::sink())?;
+            self.check_crc("AC18 file header", expected, crc_handler.seed())?;
+        }
+
         let mut cursor = Cursor::new(&encrypted_header);
 
-        let code_page_str = {
-            let mut cp_buf = [0u8; 12];
-            cursor.read_exact(&mut cp_buf)?;
-            let end = cp_buf.iter().position(|&b| b == 0).unwrap_or(12);
-            String::from_utf8_lossy(&cp_buf[..end]).to_string()
-        };
-        self.file_header.drawing_code_page = code_page_str;
-        self.encoding = self.file_header.drawing_code_page.clone();
+        // 0x00: "AcFssFcAJMB" file id string
+        let mut file_id = [0u8; 12];
+        cursor.read_exact(&mut file_id)?;
+        if &file_id[..11] != b"AcFssFcAJMB" {
+            self.notify(
+                "AC18 file header id string is not 'AcFssFcAJMB'",
+                NotificationType::Warning,
+            );
+        }
 
-        // Ensure we have AC18 data.
         let ac18 = match &mut self.file_header.data {
             DwgFileHeaderData::AC18(ac18) => ac18,
             DwgFileHeaderData::AC21(ac21) => &mut ac21.ac18,
@@ -594,39 +901,36 @@ impl<R: Read + Seek> DwgReader<R> {
 
         ac18.dwg_version = dwg_version;
         ac18.app_release_version = app_release_version;
-
-        // Parse remaining fields from decrypted data.
-        // Skip code page (12 bytes already read).
-        let _unknown_long_0 = cursor.read_i32::<LittleEndian>()?;
-        ac18.security_type = cursor.read_i32::<LittleEndian>()? as i64;
-        let _unknown_long_1 = cursor.read_i32::<LittleEndian>()?;
-        ac18.summary_info_addr = cursor.read_i32::<LittleEndian>()? as i64;
-        ac18.vba_project_addr = cursor.read_i32::<LittleEndian>()? as i64;
-
-        let _unknown_long_2 = cursor.read_i32::<LittleEndian>()?;
-
-        // Skip the next 0x54 - 0x28 = 0x2C bytes of additional encrypted header fields.
-        // These include the remaining encrypted fields:
-        //  root_tree_node_gap, gap_array_size, crc_seed, last_page_id,
-        //  last_section_addr, second_header_addr, gap_amount, section_amount,
-        //  section_page_map_id, page_map_address, section_map_id,
-        //  section_array_page_size, right_gap, left_gap
+        ac18.security_type = security_type;
+        ac18.summary_info_addr = summary_info_addr;
+        ac18.vba_project_addr = vba_project_addr;
+
+        // 0x0C: 0x00, 0x10: 0x6C, 0x14: 0x04
+        let _ = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
         ac18.root_tree_node_gap = cursor.read_i32::<LittleEndian>()?;
-        ac18.gap_array_size = cursor.read_u32::<LittleEndian>()?;
-        ac18.crc_seed = cursor.read_u32::<LittleEndian>()?;
+        ac18.left_gap = cursor.read_i32::<LittleEndian>()?;
+        ac18.right_gap = cursor.read_i32::<LittleEndian>()?;
+        let _unknown_1 = cursor.read_i32::<LittleEndian>()?;
         ac18.last_page_id = cursor.read_i32::<LittleEndian>()?;
         ac18.last_section_addr = cursor.read_u64::<LittleEndian>()?;
         ac18.second_header_addr = cursor.read_u64::<LittleEndian>()?;
         ac18.gap_amount = cursor.read_u32::<LittleEndian>()?;
         ac18.section_amount = cursor.read_u32::<LittleEndian>()?;
+        // 0x44: 0x20, 0x48: 0x80, 0x4C: 0x40
+        let _ = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
         ac18.section_page_map_id = cursor.read_u32::<LittleEndian>()?;
-        ac18.page_map_address = cursor.read_u64::<LittleEndian>()?;
+        // Stored relative to the end of the 0x100-byte file header
+        ac18.page_map_address = cursor.read_u64::<LittleEndian>()? + 0x100;
         ac18.section_map_id = cursor.read_u32::<LittleEndian>()?;
         ac18.section_array_page_size = cursor.read_u32::<LittleEndian>()?;
-        ac18.right_gap = cursor.read_i32::<LittleEndian>()?;
-        ac18.left_gap = cursor.read_i32::<LittleEndian>()?;
+        ac18.gap_array_size = cursor.read_u32::<LittleEndian>()?;
+        ac18.crc_seed = cursor.read_u32::<LittleEndian>()?;
 
-        // Read the page map (section locators).
+        // Read the page map (section page locations).
         self.read_page_map_ac18()?;
 
         // Read the section map (section descriptors).
@@ -636,101 +940,86 @@ impl<R: Read + Seek> DwgReader<R> {
     }
 
     /// Read the page map for AC18.
-    /// The page map lists all pages by their id and file position.
+    ///
+    /// The page map lists every page by its number and size; page addresses
+    /// are accumulated from the end of the file header. Negative page
+    /// numbers mark gaps and carry 16 extra bytes.
     fn read_page_map_ac18(&mut self) -> Result<()> {
-        let (page_map_address, section_page_map_id) = {
-            let ac18 = self.get_ac18()?;
-            (ac18.page_map_address, ac18.section_page_map_id)
-        };
+        let page_map_address = self.get_ac18()?.page_map_address;
 
-        self.stream
-            .seek(SeekFrom::Start(page_map_address + 0x100))?;
-
-        // Read page map section header.
-        let section_type = self.stream.read_i32::<LittleEndian>()?;
-        let decompressed_size = self.stream.read_i32::<LittleEndian>()? as usize;
-        let compressed_size = self.stream.read_i32::<LittleEndian>()? as usize;
-        let compression_type = self.stream.read_i32::<LittleEndian>()?;
-        let _checksum = self.stream.read_i32::<LittleEndian>()?;
-
-        let _ = section_type; // typically 0x41630E3B
-
-        // Read compressed page map data.
-        let mut compressed = vec![0u8; compressed_size];
-        self.stream.read_exact(&mut compressed)?;
-
-        let page_data = if compression_type == 2 {
-            DwgLz77Ac18Decompressor::decompress(Cursor::new(compressed), decompressed_size)?
-        } else {
-            compressed
-        };
+        self.stream.seek(SeekFrom::Start(page_map_address))?;
+        let page_data = self.read_system_page_ac18()?;
 
-        // Parse page map entries. Each entry: page_id (i32), page_seeker (i64), page_size (i64).
         let mut cursor = Cursor::new(&page_data);
+        let mut records = HashMap::new();
+        let mut address: i64 = 0x100;
 
-        let mut local_sections: Vec<DwgLocalSectionMap> = Vec::new();
-        let mut address: i64 = 0x100; // Pages start after the 0x100-byte file header.
-
-        loop {
-            let id = cursor.read_i32::<LittleEndian>();
-            if id.is_err() {
-                break;
-            }
-            let id = id.unwrap();
-
-            if id == 0 {
-                break;
-            }
-
+        while (cursor.position() as usize) + 8 <= page_data.len() {
+            let number = cursor.read_i32::<LittleEndian>()?;
             let size = cursor.read_i32::<LittleEndian>()? as i64;
 
-            let mut local_map = DwgLocalSectionMap::new();
-            local_map.page_number = id;
-            local_map.seeker = address;
-            local_map.size = size;
-            local_map.oda = section_page_map_id;
-
-            // Check if this is the page map itself.
-            if address == page_map_address as i64 + 0x100 {
-                local_map.section_map = section_page_map_id as i32;
+            if number >= 0 {
+                records.insert(
+                    number,
+                    DwgSectionLocatorRecord {
+                        number: Some(number),
+                        seeker: address,
+                        size,
+                    },
+                );
+            } else {
+                // Gap: parent, left, right, 0x00
+                cursor.seek(SeekFrom::Current(16))?;
             }
 
-            local_sections.push(local_map);
             address += size;
         }
 
-        // Store local sections. For now save them on a temporary descriptor.
-        let mut page_map_desc = DwgSectionDescriptor::with_name("PageMap");
-        page_map_desc.local_sections = local_sections;
-        self.file_header
-            .add_section_descriptor(page_map_desc)
-            .ok();
+        match &mut self.file_header.data {
+            DwgFileHeaderData::AC18(ac18) => ac18.ac15.records = records,
+            DwgFileHeaderData::AC21(ac21) => ac21.ac18.ac15.records = records,
+            DwgFileHeaderData::AC15(_) => {}
+        }
 
         Ok(())
     }
 
     /// Read the section map for AC18.
-    /// Maps section names to page collections.
+    /// Maps section names to the pages that hold their data.
     fn read_section_map_ac18(&mut self) -> Result<()> {
-        let (section_map_id, _page_map_address) = {
+        let (seeker, records) = {
             let ac18 = self.get_ac18()?;
-            (ac18.section_map_id, ac18.page_map_address)
+            let record = ac18
+                .ac15
+                .records
+                .get(&(ac18.section_map_id as i32))
+                .ok_or_else(|| {
+                    DxfError::InvalidFormat(format!(
+                        "Section map page {} not found in the page map",
+                        ac18.section_map_id
+                    ))
+                })?;
+            (record.seeker, ac18.ac15.records.clone())
         };
 
-        // Build a full section buffer from the section map pages.
-        let buf = self.get_section_buffer_18_by_id(section_map_id)?;
+        self.stream.seek(SeekFrom::Start(seeker as u64))?;
+        let buf = self.read_system_page_ac18()?;
         let mut cursor = Cursor::new(&buf);
 
+        // Number of descriptions, 0x02, 0x7400, 0x00, unknown
         let num_sections = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
+        let _ = cursor.read_i32::<LittleEndian>()?;
 
         for _ in 0..num_sections {
-            // Section descriptor fields.
-            let decompressed_size = cursor.read_u64::<LittleEndian>()?;
-            let compressed_size = cursor.read_u64::<LittleEndian>()?;
-            let section_id = cursor.read_i32::<LittleEndian>()?;
+            let size = cursor.read_u64::<LittleEndian>()?;
             let page_count = cursor.read_i32::<LittleEndian>()?;
-            let max_decompressed_size = cursor.read_u64::<LittleEndian>()?;
+            let max_decompressed_size = cursor.read_i32::<LittleEndian>()? as u64;
+            let _unknown = cursor.read_i32::<LittleEndian>()?;
             let compressed_code = cursor.read_i32::<LittleEndian>()?;
+            let section_id = cursor.read_i32::<LittleEndian>()?;
             let encrypted = cursor.read_i32::<LittleEndian>()?;
 
             // Section name: 64-byte null-terminated.
@@ -741,7 +1030,7 @@ impl<R: Read + Seek> DwgReader<R> {
 
             let mut desc = DwgSectionDescriptor::with_name(&name);
             desc.decompressed_size = max_decompressed_size;
-            desc.compressed_size = compressed_size;
+            desc.compressed_size = size;
             desc.section_id = section_id;
             desc.page_count = page_count;
             desc.encrypted = encrypted;
@@ -749,35 +1038,87 @@ impl<R: Read + Seek> DwgReader<R> {
                 desc.set_compressed_code(compressed_code);
             }
 
-            let _ = decompressed_size; // total decompressed, stored implicitly
-
-            // Read per-page local section maps.
+            // Per-page local section maps.
             for _ in 0..page_count {
                 let page_number = cursor.read_i32::<LittleEndian>()?;
-                let data_size = cursor.read_u64::<LittleEndian>()?;
+                let data_size = cursor.read_i32::<LittleEndian>()? as u64;
                 let start_offset = cursor.read_u64::<LittleEndian>()?;
 
                 let mut local = DwgLocalSectionMap::new();
                 local.page_number = page_number;
                 local.compressed_size = data_size;
                 local.offset = start_offset;
+                local.decompressed_size = max_decompressed_size;
                 local.section_map = section_id;
+                if let Some(record) = records.get(&page_number) {
+                    local.seeker = record.seeker;
+                    local.size = record.size;
+                }
 
                 desc.local_sections.push(local);
             }
 
+            // The last page only holds the remainder of the section.
+            if max_decompressed_size > 0 {
+                let size_left = size % max_decompressed_size;
+                if size_left > 0 {
+                    if let Some(last) = desc.local_sections.last_mut() {
+                        last.decompressed_size = size_left;
+                    }
+                }
+            }
+
             self.file_header.add_section_descriptor(desc).ok();
         }
 
         Ok(())
     }
 
+    /// Read a system page (page map or section map) at the current stream
+    /// position and return its decompressed data.
+    fn read_system_page_ac18(&mut self) -> Result<Vec<u8>> {
+        let mut header = [0u8; 20];
+        self.stream.read_exact(&mut header)?;
+
+        let mut cursor = Cursor::new(&header);
+        let section_type = cursor.read_i32::<LittleEndian>()?;
+        let decompressed_size = cursor.read_i32::<LittleEndian>()? as usize;
+        let compressed_size = cursor.read_i32::<LittleEndian>()? as usize;
+        let compression_type = cursor.read_i32::<LittleEndian>()?;
+        let checksum = cursor.read_u32::<LittleEndian>()?;
+
+        let mut compressed = vec![0u8; compressed_size];
+        self.stream.read_exact(&mut compressed)?;
+
+        if self.configuration.crc_check {
+            // Header with a zero checksum, then the compressed data
+            header[16..20].fill(0);
+            let seed = calculate(0, &header, 0, header.len());
+            let actual = calculate(seed, &compressed, 0, compressed.len());
+            self.check_crc(
+                &format!("system page 0x{:08X}", section_type),
+                checksum,
+                actual,
+            )?;
+        }
+
+        if compression_type == 2 {
+            DwgLz77Ac18Decompressor::decompress(Cursor::new(compressed), decompressed_size)
+        } else {
+            Ok(compressed)
+        }
+    }
+
     // ── AC21 (R2007) file header ─────────────────────────────────────
 
-    /// Read an AC21 (2007) file header with Reed-Solomon and compressed metadata.
+    /// Read an AC21 (2007) file header.
+    ///
+    /// The first 0x80 bytes follow the AC18 layout. At 0x80 a 0x400-byte
+    /// block, Reed-Solomon encoded as 3 interleaved blocks of 239 bytes,
+    /// holds the LZ77-AC21 compressed metadata. Page addresses are
+    /// accumulated from 0x480.
     fn read_file_header_ac21(&mut self) -> Result<()> {
-        // First read the same initial fields as AC18.
-        // 5 unknown bytes.
+        // 5 bytes of 0x00
         let mut buf5 = [0u8; 5];
         self.stream.read_exact(&mut buf5)?;
 
@@ -791,38 +1132,67 @@ impl<R: Read + Seek> DwgReader<R> {
         let dwg_version = self.stream.read_u8()?;
         let app_release_version = self.stream.read_u8()?;
 
-        // Seek to the start of the compressed file header at offset 0x80.
-        self.stream.seek(SeekFrom::Start(0x80))?;
+        // 0x13: code page
+        let code_page = self.stream.read_u16::<LittleEndian>()?;
+        self.encoding = code_page_name(code_page);
+        self.file_header.drawing_code_page = self.encoding.clone();
+
+        // 0x15: 3 bytes of 0x00
+        let mut buf3 = [0u8; 3];
+        self.stream.read_exact(&mut buf3)?;
 
-        // Read the Reed-Solomon encoded data (0x400 bytes = 3 × 239 + remainder).
+        let security_type = self.stream.read_i32::<LittleEndian>()? as i64;
+        let _unknown = self.stream.read_i32::<LittleEndian>()?;
+        let summary_info_addr = self.stream.read_i32::<LittleEndian>()? as i64;
+        let vba_project_addr = self.stream.read_i32::<LittleEndian>()? as i64;
+
+        // 0x80: Reed-Solomon encoded file header
+        self.stream.seek(SeekFrom::Start(0x80))?;
         let mut rs_encoded = [0u8; 0x400];
         self.stream.read_exact(&mut rs_encoded)?;
 
-        // Reed-Solomon decode into 3 × 239 = 0x2CD bytes.
         let mut rs_decoded = vec![0u8; 3 * 239];
-        Self::reed_solomon_decoding(&rs_encoded, &mut rs_decoded);
+        Self::reed_solomon_decoding(&rs_encoded, &mut rs_decoded, 3, 239);
+
+        // CRC, unknown key, compressed data CRC, ComprLen, Length2
+        let mut cursor = Cursor::new(&rs_decoded);
+        let crc = cursor.read_u64::<LittleEndian>()?;
+        let _unknown_key = cursor.read_u64::<LittleEndian>()?;
+        let compressed_crc = cursor.read_u64::<LittleEndian>()?;
+        let compressed_length = cursor.read_i32::<LittleEndian>()?;
+        let _length2 = cursor.read_i32::<LittleEndian>()?;
 
-        // Decompress the decoded data using LZ77-AC21.
         let mut header_buf = vec![0u8; 0x110];
-        DwgLz77Ac21Decompressor::decompress(&rs_decoded, 0, rs_decoded.len() as u32, &mut header_buf);
+        let length = if compressed_length < 0 {
+            // Stored uncompressed
+            let length = (-compressed_length as usize).min(header_buf.len()).min(rs_decoded.len() - 32);
+            header_buf[..length].copy_from_slice(&rs_decoded[32..32 + length]);
+            length
+        } else {
+            let length = (compressed_length as usize).min(rs_decoded.len() - 32);
+            DwgLz77Ac21Decompressor::decompress(&rs_decoded, 32, length as u32, &mut header_buf);
+            length
+        };
 
-        // Parse the compressed metadata.
-        let meta = Self::read_compressed_metadata(&header_buf)?;
+        if self.configuration.crc_check {
+            // CRC-64 of the header data, then of the 32-byte prefix and the
+            // data with the CRC field zeroed, then of the metadata with its
+            // own CRC field zeroed
+            let actual = crc64(0, &rs_decoded[32..32 + length]);
+            self.check_crc("AC21 file header data", compressed_crc, actual)?;
+
+            let mut block = rs_decoded[..32 + length].to_vec();
+            block[..8].fill(0);
+            self.check_crc("AC21 file header", crc, crc64(0, &block))?;
+
+            let mut metadata = header_buf.clone();
+            let header_crc64 = u64::from_le_bytes(metadata[0x108..0x110].try_into().unwrap());
+            metadata[0x108..0x110].fill(0);
+            self.check_crc("AC21 file header metadata", header_crc64, crc64(0, &metadata))?;
+        }
 
-        // Validate code page.
-        let code_page_str = {
-            let mut cp_buf = [0u8; 12];
-            let offset = 0x6C; // Offset within the decompressed block.
-            if header_buf.len() > offset + 12 {
-                cp_buf.copy_from_slice(&header_buf[offset..offset + 12]);
-            }
-            let end = cp_buf.iter().position(|&b| b == 0).unwrap_or(12);
-            String::from_utf8_lossy(&cp_buf[..end]).to_string()
-        };
-        self.file_header.drawing_code_page = code_page_str;
-        self.encoding = self.file_header.drawing_code_page.clone();
+        let meta = Self::read_compressed_metadata(&header_buf)?;
 
-        // Store on the AC21 header.
         let ac21 = match &mut self.file_header.data {
             DwgFileHeaderData::AC21(ac21) => ac21,
             _ => {
@@ -834,6 +1204,9 @@ impl<R: Read + Seek> DwgReader<R> {
 
         ac21.ac18.dwg_version = dwg_version;
         ac21.ac18.app_release_version = app_release_version;
+        ac21.ac18.security_type = security_type;
+        ac21.ac18.summary_info_addr = summary_info_addr;
+        ac21.ac18.vba_project_addr = vba_project_addr;
         ac21.compressed_metadata = meta;
 
         // Read pages map.
@@ -845,7 +1218,7 @@ impl<R: Read + Seek> DwgReader<R> {
         Ok(())
     }
 
-    /// Parse the AC21 compressed metadata block (0x70 bytes).
+    /// Parse the AC21 compressed metadata block (0x110 bytes).
     fn read_compressed_metadata(data: &[u8]) -> Result<Dwg21CompressedMetadata> {
         let mut cursor = Cursor::new(data);
         let mut m = Dwg21CompressedMetadata::new();
@@ -858,20 +1231,42 @@ impl<R: Read + Seek> DwgReader<R> {
         m.map2_offset = cursor.read_u64::<LittleEndian>()?;
         m.map2_id = cursor.read_u64::<LittleEndian>()?;
         m.pages_map_offset = cursor.read_u64::<LittleEndian>()?;
+        m.pages_map_id = cursor.read_u64::<LittleEndian>()?;
         m.header2_offset = cursor.read_u64::<LittleEndian>()?;
         m.pages_map_size_compressed = cursor.read_u64::<LittleEndian>()?;
         m.pages_map_size_uncompressed = cursor.read_u64::<LittleEndian>()?;
         m.pages_amount = cursor.read_u64::<LittleEndian>()?;
         m.pages_max_id = cursor.read_u64::<LittleEndian>()?;
+        m.unknown_0x20 = cursor.read_u64::<LittleEndian>()?;
+        m.unknown_0x40 = cursor.read_u64::<LittleEndian>()?;
+        m.pages_map_crc_uncompressed = cursor.read_u64::<LittleEndian>()?;
+        m.unknown_0xf800 = cursor.read_u64::<LittleEndian>()?;
+        m.unknown_4 = cursor.read_u64::<LittleEndian>()?;
+        m.unknown_1 = cursor.read_u64::<LittleEndian>()?;
+        m.sections_amount = cursor.read_u64::<LittleEndian>()?;
+        m.sections_map_crc_uncompressed = cursor.read_u64::<LittleEndian>()?;
+        m.sections_map_size_compressed = cursor.read_u64::<LittleEndian>()?;
         m.sections_map2_id = cursor.read_u64::<LittleEndian>()?;
-        m.pages_map_id = cursor.read_u64::<LittleEndian>()?;
+        m.sections_map_id = cursor.read_u64::<LittleEndian>()?;
+        m.sections_map_size_uncompressed = cursor.read_u64::<LittleEndian>()?;
+        m.sections_map_crc_compressed = cursor.read_u64::<LittleEndian>()?;
+        m.sections_map_correction_factor = cursor.read_u64::<LittleEndian>()?;
+        m.sections_map_crc_seed = cursor.read_u64::<LittleEndian>()?;
+        m.stream_version = cursor.read_u64::<LittleEndian>()?;
+        m.crc_seed = cursor.read_u64::<LittleEndian>()?;
+        m.crc_seed_encoded = cursor.read_u64::<LittleEndian>()?;
+        m.random_seed = cursor.read_u64::<LittleEndian>()?;
+        m.header_crc64 = cursor.read_u64::<LittleEndian>()?;
 
         Ok(m)
     }
 
     /// Read the AC21 page map.
+    ///
+    /// Each entry is a page size and id; addresses are accumulated from
+    /// 0x480. Negative ids mark gaps.
     fn read_page_map_ac21(&mut self) -> Result<()> {
-        let (offset, comp_size, decomp_size, _correction_factor, _crc_seed) = {
+        let (offset, comp_size, decomp_size, correction_factor) = {
             let ac21 = match &self.file_header.data {
                 DwgFileHeaderData::AC21(ac21) => ac21,
                 _ => return Err(DxfError::InvalidHeader("Expected AC21".into())),
@@ -882,121 +1277,177 @@ impl<R: Read + Seek> DwgReader<R> {
                 m.pages_map_size_compressed,
                 m.pages_map_size_uncompressed,
                 m.pages_map_correction_factor,
-                m.pages_map_crc_seed,
             )
         };
 
-        self.stream.seek(SeekFrom::Start(offset + 0x480))?;
-
-        // Read and decompress the page map.
-        let mut compressed = vec![0u8; comp_size as usize];
-        self.stream.read_exact(&mut compressed)?;
-
-        let mut decompressed = vec![0u8; decomp_size as usize];
-        DwgLz77Ac21Decompressor::decompress(&compressed, 0, comp_size as u32, &mut decompressed);
-
-        // Parse page map entries. Each is: address (u64), size (u64), id (u64).
-        let mut cursor = Cursor::new(&decompressed);
-        let mut address: u64 = 0x480;
-
-        loop {
-            let size = match cursor.read_u64::<LittleEndian>() {
-                Ok(v) => v,
-                Err(_) => break,
-            };
-            let id = cursor.read_u64::<LittleEndian>()?;
+        let page_data = self.read_system_page_ac21(
+            offset + 0x480,
+            comp_size,
+            decomp_size,
+            correction_factor,
+        )?;
 
-            if size == 0 {
-                break;
+        let mut cursor = Cursor::new(&page_data);
+        let mut records = HashMap::new();
+        let mut address: i64 = 0x480;
+
+        while (cursor.position() as usize) + 16 <= page_data.len() {
+            let size = cursor.read_i64::<LittleEndian>()?;
+            let id = cursor.read_i64::<LittleEndian>()?;
+
+            if id >= 0 {
+                records.insert(
+                    id as i32,
+                    DwgSectionLocatorRecord {
+                        number: Some(id as i32),
+                        seeker: address,
+                        size,
+                    },
+                );
             }
 
-            let mut local = DwgLocalSectionMap::new();
-            local.page_number = id as i32;
-            local.seeker = address as i64;
-            local.size = size as i64;
-            local.page_size = size as i64;
-
-            // Store on a temporary PageMap descriptor (collected after the loop).
-            let _ = &local;
-
             address += size;
         }
 
+        if let DwgFileHeaderData::AC21(ac21) = &mut self.file_header.data {
+            ac21.ac18.ac15.records = records;
+        }
+
         Ok(())
     }
 
     /// Read the AC21 section map.
     fn read_section_map_ac21(&mut self) -> Result<()> {
-        let sections_map_id = match &self.file_header.data {
-            DwgFileHeaderData::AC21(ac21) => ac21.compressed_metadata.sections_map_id,
-            _ => return Err(DxfError::InvalidHeader("Expected AC21".into())),
-        };
-
-        // Build the section map buffer by combining pages identified by sections_map_id.
-        let buf = self.get_section_buffer_21_by_id(sections_map_id)?;
-        let mut cursor = Cursor::new(&buf);
+        let (sections_map_id, comp_size, decomp_size, correction_factor) =
+            match &self.file_header.data {
+                DwgFileHeaderData::AC21(ac21) => {
+                    let m = &ac21.compressed_metadata;
+                    (
+                        m.sections_map_id,
+                        m.sections_map_size_compressed,
+                        m.sections_map_size_uncompressed,
+                        m.sections_map_correction_factor,
+                    )
+                }
+                _ => return Err(DxfError::InvalidHeader("Expected AC21".into())),
+            };
 
-        let num_sections = cursor.read_i32::<LittleEndian>()?;
+        let records = self.get_ac18()?.ac15.records.clone();
+        let record = records.get(&(sections_map_id as i32)).ok_or_else(|| {
+            DxfError::InvalidFormat(format!(
+                "Section map page {} not found in the page map",
+                sections_map_id
+            ))
+        })?;
 
-        for _ in 0..num_sections {
-            let decompressed_size = cursor.read_u64::<LittleEndian>()?;
-            let compressed_size = cursor.read_u64::<LittleEndian>()?;
-            let section_id = cursor.read_i32::<LittleEndian>()?;
-            let page_count = cursor.read_i32::<LittleEndian>()?;
-            let max_decompressed_size = cursor.read_u64::<LittleEndian>()?;
-            let compressed_code = cursor.read_i32::<LittleEndian>()?;
-            let encrypted = cursor.read_i32::<LittleEndian>()?;
+        let buf = self.read_system_page_ac21(
+            record.seeker as u64,
+            comp_size,
+            decomp_size,
+            correction_factor,
+        )?;
+        let mut cursor = Cursor::new(&buf);
 
-            // Section name: first, hash code (4 bytes), then name string.
-            let hash_code = cursor.read_i32::<LittleEndian>()?;
-            let name_length = cursor.read_i32::<LittleEndian>()?;
-            let name_length_clamped = (name_length.max(0) as usize).min(256);
-            let mut name_bytes = vec![0u8; name_length_clamped];
+        while (cursor.position() as usize) + 64 <= buf.len() {
+            let data_size = cursor.read_u64::<LittleEndian>()?;
+            let max_size = cursor.read_u64::<LittleEndian>()?;
+            let encrypted = cursor.read_u64::<LittleEndian>()?;
+            let hash_code = cursor.read_u64::<LittleEndian>()?;
+            let name_length = cursor.read_u64::<LittleEndian>()? as usize;
+            let _unknown = cursor.read_u64::<LittleEndian>()?;
+            let encoding = cursor.read_u64::<LittleEndian>()?;
+            let page_count = cursor.read_u64::<LittleEndian>()?;
+
+            // Section name: UTF-16, null terminated
+            let mut name_bytes = vec![0u8; name_length];
             cursor.read_exact(&mut name_bytes)?;
-            let end = name_bytes.iter().position(|&b| b == 0).unwrap_or(name_length_clamped);
-            let name = String::from_utf8_lossy(&name_bytes[..end]).to_string();
+            let name: String = char::decode_utf16(
+                name_bytes
+                    .chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .take_while(|&c| c != 0),
+            )
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
 
             // Map hash to a known section name if the name is empty.
-            let section_name = if name.is_empty() {
-                Self::section_name_from_hash(hash_code).unwrap_or(name)
+            let name = if name.is_empty() && hash_code != 0 {
+                Self::section_name_from_hash(hash_code as i32).unwrap_or(name)
             } else {
                 name
             };
 
-            let mut desc = DwgSectionDescriptor::with_name(&section_name);
-            desc.decompressed_size = max_decompressed_size;
-            desc.compressed_size = compressed_size;
-            desc.section_id = section_id;
-            desc.page_count = page_count;
-            desc.encrypted = encrypted;
-            if compressed_code == 1 || compressed_code == 2 {
-                desc.set_compressed_code(compressed_code);
-            }
-            desc.hash_code = Some(hash_code as u64);
-
-            let _ = decompressed_size;
+            let mut desc = DwgSectionDescriptor::with_name(&name);
+            desc.compressed_size = data_size;
+            desc.decompressed_size = max_size;
+            desc.encrypted = encrypted as i32;
+            desc.hash_code = Some(hash_code);
+            desc.encoding = Some(encoding);
+            desc.page_count = page_count as i32;
 
-            // Read per-page data.
             for _ in 0..page_count {
-                let page_number = cursor.read_i32::<LittleEndian>()?;
-                let data_size = cursor.read_u64::<LittleEndian>()?;
-                let start_offset = cursor.read_u64::<LittleEndian>()?;
+                let offset = cursor.read_u64::<LittleEndian>()?;
+                let size = cursor.read_i64::<LittleEndian>()?;
+                let page_number = cursor.read_i64::<LittleEndian>()? as i32;
+                let decompressed_size = cursor.read_u64::<LittleEndian>()?;
+                let compressed_size = cursor.read_u64::<LittleEndian>()?;
+                let checksum = cursor.read_u64::<LittleEndian>()?;
+                let crc = cursor.read_u64::<LittleEndian>()?;
 
                 let mut local = DwgLocalSectionMap::new();
                 local.page_number = page_number;
-                local.compressed_size = data_size;
-                local.offset = start_offset;
-                local.section_map = section_id;
+                local.offset = offset;
+                local.size = size;
+                local.page_size = size;
+                local.decompressed_size = decompressed_size;
+                local.compressed_size = compressed_size;
+                local.checksum = checksum;
+                local.crc = crc;
+                if let Some(record) = records.get(&page_number) {
+                    local.seeker = record.seeker;
+                }
 
                 desc.local_sections.push(local);
             }
 
-            self.file_header.add_section_descriptor(desc).ok();
+            // The leading entry of the map has no name and no pages.
+            if !desc.name.is_empty() {
+                self.file_header.add_section_descriptor(desc).ok();
+            }
         }
 
         Ok(())
     }
 
+    /// Read a Reed-Solomon encoded, LZ77-AC21 compressed system page (page
+    /// map or section map).
+    fn read_system_page_ac21(
+        &mut self,
+        seeker: u64,
+        compressed_size: u64,
+        decompressed_size: u64,
+        correction_factor: u64,
+    ) -> Result<Vec<u8>> {
+        let aligned_size = ((compressed_size + 7) & !7) * correction_factor.max(1);
+        let block_count = aligned_size.div_ceil(239) as usize;
+
+        self.stream.seek(SeekFrom::Start(seeker))?;
+        let mut encoded = vec![0u8; block_count * 255];
+        self.stream.read_exact(&mut encoded)?;
+
+        let mut decoded = vec![0u8; block_count * 239];
+        Self::reed_solomon_decoding(&encoded, &mut decoded, block_count, 239);
+
+        let mut output = vec![0u8; decompressed_size as usize];
+        if compressed_size < decompressed_size {
+            DwgLz77Ac21Decompressor::decompress(&decoded, 0, compressed_size as u32, &mut output);
+        } else {
+            let n = output.len().min(decoded.len());
+            output[..n].copy_from_slice(&decoded[..n]);
+        }
+        Ok(output)
+    }
+
     /// Map an AC21 section hash to a known section name.
     fn section_name_from_hash(hash: i32) -> Option<String> {
         DwgSectionHash::from_i32(hash).map(|h| match h {
@@ -1034,6 +1485,35 @@ impl<R: Read + Seek> DwgReader<R> {
 
     /// AC15: read a section identified by its record number.
     fn get_section_buffer_15(&mut self, section_name: &str) -> Result<Vec<u8>> {
+        // The objects have no locator record: handle map offsets are
+        // absolute file positions, so the whole file is the buffer.
+        if section_name == DwgSectionDefinition::ACDB_OBJECTS {
+            self.stream.seek(SeekFrom::Start(0))?;
+            let mut buf = Vec::new();
+            self.stream.read_to_end(&mut buf)?;
+            return Ok(buf);
+        }
+
+        // Nor does the preview: the file header holds its address. Read the
+        // sentinel, the RL size of the image area, that area and the end
+        // sentinel.
+        if section_name == DwgSectionDefinition::PREVIEW {
+            self.stream
+                .seek(SeekFrom::Start(self.file_header.preview_address as u64))?;
+            let mut buf = vec![0u8; 20];
+            self.stream.read_exact(&mut buf)?;
+            let size = i32::from_le_bytes([buf[16], buf[17], buf[18], buf[19]]);
+            if size < 0 {
+                return Err(DxfError::InvalidFormat(format!(
+                    "Invalid preview size {}",
+                    size
+                )));
+            }
+            buf.resize(20 + size as usize + 16, 0);
+            self.stream.read_exact(&mut buf[20..])?;
+            return Ok(buf);
+        }
+
         let record_number = DwgSectionDefinition::get_section_locator_by_name(section_name)
             .ok_or_else(|| {
                 DxfError::InvalidFormat(format!(
@@ -1083,99 +1563,100 @@ impl<R: Read + Seek> DwgReader<R> {
             })?
             .clone();
 
-        let mut result = Vec::with_capacity(desc.decompressed_size as usize);
+        let mut result = Vec::with_capacity(
+            desc.decompressed_size as usize * desc.local_sections.len(),
+        );
 
         for local in &desc.local_sections {
-            let seeker = local.seeker;
-            let size = local.size;
+            // Pages holding only zeroes are not written to the file.
+            if local.offset as usize > result.len() {
+                result.resize(local.offset as usize, 0);
+            }
 
-            if seeker <= 0 || size <= 0 {
+            if local.seeker <= 0 {
                 continue;
             }
 
-            // Seek to the page and read the page header.
-            self.stream.seek(SeekFrom::Start(seeker as u64))?;
-
-            let section_type = self.stream.read_i32::<LittleEndian>()?;
-            let decompressed_size = self.stream.read_i32::<LittleEndian>()? as usize;
-            let compressed_size = self.stream.read_i32::<LittleEndian>()? as usize;
-            let compression_type = self.stream.read_i32::<LittleEndian>()?;
-            let checksum = self.stream.read_i32::<LittleEndian>()?;
-
-            let _ = (section_type, checksum);
-
-            let mut page_data = vec![0u8; compressed_size];
-            self.stream.read_exact(&mut page_data)?;
-
-            // Decrypt the page data if encrypted.
-            if desc.encrypted != 0 {
-                page_data = Self::decrypt_data_section(
-                    &page_data,
-                    local.page_number as u32,
-                    0,
-                );
+            match self.get_page_buffer_18(local, &desc) {
+                Ok(page) => result.extend_from_slice(&page),
+                Err(e) if self.configuration.recover => {
+                    self.notify(
+                        format!(
+                            "Skipped unreadable page {} of section '{}': {}",
+                            local.page_number, section_name, e
+                        ),
+                        NotificationType::Error,
+                    );
+                    result.resize(result.len() + local.decompressed_size as usize, 0);
+                }
+                Err(e) => return Err(e),
             }
+        }
 
-            // Decompress if needed.
-            if compression_type == 2 {
-                let decompressed =
-                    DwgLz77Ac18Decompressor::decompress(Cursor::new(page_data), decompressed_size)?;
-                result.extend_from_slice(&decompressed);
-            } else {
-                result.extend_from_slice(&page_data);
-            }
+        // Trailing all-zero pages are not written either.
+        if result.len() < desc.compressed_size as usize {
+            result.resize(desc.compressed_size as usize, 0);
         }
 
         Ok(result)
     }
 
-    /// AC18: build section buffer by section id (used for page/section map reading).
-    fn get_section_buffer_18_by_id(&mut self, section_id: u32) -> Result<Vec<u8>> {
-        // Find the PageMap descriptor that contains location info for all pages.
-        let page_map_desc = self
-            .file_header
-            .get_descriptor("PageMap")
-            .ok_or_else(|| {
-                DxfError::InvalidFormat("PageMap descriptor not found".into())
-            })?
-            .clone();
-
-        let mut result = Vec::new();
-
-        // Find pages that belong to this section id.
-        for local in &page_map_desc.local_sections {
-            if local.oda != section_id && local.section_map != section_id as i32 {
-                continue;
-            }
-
-            let seeker = local.seeker;
-            let size = local.size;
-
-            if seeker <= 0 || size <= 0 {
-                continue;
-            }
+    /// Read and decompress a single AC18 data page.
+    fn get_page_buffer_18(
+        &mut self,
+        local: &DwgLocalSectionMap,
+        desc: &DwgSectionDescriptor,
+    ) -> Result<Vec<u8>> {
+        // Data page header: 32 bytes masked with the page position.
+        self.stream.seek(SeekFrom::Start(local.seeker as u64))?;
+        let mut header = [0u8; 32];
+        self.stream.read_exact(&mut header)?;
+        let mask = (SYSTEM_SECTION_RANDOM_SEED ^ local.seeker as u32).to_le_bytes();
+        for (i, byte) in header.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
 
-            self.stream.seek(SeekFrom::Start(seeker as u64))?;
+        let mut cursor = Cursor::new(&header);
+        let _page_type = cursor.read_i32::<LittleEndian>()?;
+        let _section_number = cursor.read_i32::<LittleEndian>()?;
+        let compressed_size = cursor.read_i32::<LittleEndian>()? as usize;
+        // Page size on disk; the decompressed size comes from the
+        // section map.
+        let _page_size = cursor.read_i32::<LittleEndian>()?;
+        let _start_offset = cursor.read_u64::<LittleEndian>()?;
+        let header_checksum = cursor.read_u32::<LittleEndian>()?;
+        let data_checksum = cursor.read_u32::<LittleEndian>()?;
+
+        if local.size > 0 && compressed_size as i64 + 32 > local.size {
+            return Err(DxfError::InvalidFormat(format!(
+                "Page {} data size {} exceeds the page size {}",
+                local.page_number, compressed_size, local.size
+            )));
+        }
 
-            let _section_type = self.stream.read_i32::<LittleEndian>()?;
-            let decompressed_size = self.stream.read_i32::<LittleEndian>()? as usize;
-            let compressed_size = self.stream.read_i32::<LittleEndian>()? as usize;
-            let compression_type = self.stream.read_i32::<LittleEndian>()?;
-            let _checksum = self.stream.read_i32::<LittleEndian>()?;
+        let mut page_data = vec![0u8; compressed_size];
+        self.stream.read_exact(&mut page_data)?;
 
-            let mut page_data = vec![0u8; compressed_size];
-            self.stream.read_exact(&mut page_data)?;
+        if self.configuration.crc_check {
+            let context = format!("page {} of section '{}'", local.page_number, desc.name);
+            let actual = calculate(0, &page_data, 0, page_data.len());
+            self.check_crc(&context, data_checksum, actual)?;
 
-            if compression_type == 2 {
-                let decompressed =
-                    DwgLz77Ac18Decompressor::decompress(Cursor::new(page_data), decompressed_size)?;
-                result.extend_from_slice(&decompressed);
-            } else {
-                result.extend_from_slice(&page_data);
-            }
+            // Header checksum: seeded with the data checksum, computed
+            // with the header checksum field zeroed
+            header[24..28].fill(0);
+            let actual = calculate(data_checksum, &header, 0, header.len());
+            self.check_crc(&context, header_checksum, actual)?;
         }
 
-        Ok(result)
+        if desc.is_compressed() {
+            DwgLz77Ac18Decompressor::decompress(
+                Cursor::new(page_data),
+                local.decompressed_size as usize,
+            )
+        } else {
+            Ok(page_data)
+        }
     }
 
     /// AC21: build section buffer from pages with Reed-Solomon + LZ77-AC21.
@@ -1191,171 +1672,213 @@ impl<R: Read + Seek> DwgReader<R> {
             })?
             .clone();
 
-        let mut result = Vec::with_capacity(desc.decompressed_size as usize);
+        let mut result = Vec::with_capacity(desc.compressed_size as usize);
 
         for local in &desc.local_sections {
-            let page_buf = self.get_page_buffer_21(local, &desc)?;
-            result.extend_from_slice(&page_buf);
+            // Pages holding only zeroes are not written to the file.
+            if local.offset as usize > result.len() {
+                result.resize(local.offset as usize, 0);
+            }
+
+            match self.get_page_buffer_21(local, &desc) {
+                Ok(page) => result.extend_from_slice(&page),
+                Err(e) if self.configuration.recover => {
+                    self.notify(
+                        format!(
+                            "Skipped unreadable page {} of section '{}': {}",
+                            local.page_number, section_name, e
+                        ),
+                        NotificationType::Error,
+                    );
+                    result.resize(result.len() + local.decompressed_size as usize, 0);
+                }
+                Err(e) => return Err(e),
+            }
         }
 
-        Ok(result)
-    }
+        if result.len() < desc.compressed_size as usize {
+            result.resize(desc.compressed_size as usize, 0);
+        }
 
-    /// AC21: build section buffer by section id.
-    fn get_section_buffer_21_by_id(&mut self, _section_id: u64) -> Result<Vec<u8>> {
-        // In AC21, pages are identified by their section map id.
-        // We need to read raw pages from known locations.
-        // This is called during initial header parsing when descriptors aren't set up yet.
-        // For now return empty; the actual page reading will be fleshed out during integration.
-        Ok(Vec::new())
+        Ok(result)
     }
 
-    /// Read and decompress a single AC21 page.
+    /// Read a single AC21 data page: Reed-Solomon decode with 251-byte
+    /// blocks, then decompress if the page shrank.
     fn get_page_buffer_21(
         &mut self,
         local: &DwgLocalSectionMap,
-        _descriptor: &DwgSectionDescriptor,
+        desc: &DwgSectionDescriptor,
     ) -> Result<Vec<u8>> {
-        // Read the raw page from the file.
-        let seeker = local.seeker;
-        let size = local.size;
-
-        if seeker <= 0 || size <= 0 {
+        if local.seeker <= 0 || local.size <= 0 {
             return Ok(Vec::new());
         }
 
-        self.stream.seek(SeekFrom::Start(seeker as u64))?;
-        let mut raw_page = vec![0u8; size as usize];
-        self.stream.read_exact(&mut raw_page)?;
+        self.stream.seek(SeekFrom::Start(local.seeker as u64))?;
+        let mut encoded = vec![0u8; local.size as usize];
+        self.stream.read_exact(&mut encoded)?;
 
-        // Parse page header (first 32 bytes).
-        let header = Self::get_page_header_data(&raw_page, 0)?;
+        let block_count = encoded.len() / 255;
+        let mut decoded = vec![0u8; block_count * 251];
+        Self::reed_solomon_decoding(&encoded, &mut decoded, block_count, 251);
 
-        // Validate section type.
-        if header.section_type != AC21_PAGE_TYPE {
-            return Err(DxfError::InvalidFormat(format!(
-                "Invalid AC21 page type: 0x{:08X}",
-                header.section_type
-            )));
+        let compressed_size = local.compressed_size as usize;
+        let decompressed_size = local.decompressed_size as usize;
+        if compressed_size > decoded.len() {
+            return Err(DxfError::Decompression(
+                "Page data extends beyond page boundary".into(),
+            ));
         }
 
-        let data_offset = 32usize; // After the 32-byte page header.
-        let compressed_size = header.compressed_size as usize;
-        let decompressed_size = header.decompressed_size as usize;
+        let mut output = vec![0u8; decompressed_size];
+        if compressed_size < decompressed_size {
+            DwgLz77Ac21Decompressor::decompress(&decoded, 0, compressed_size as u32, &mut output);
+        } else {
+            output.copy_from_slice(&decoded[..decompressed_size]);
+        }
 
-        if data_offset + compressed_size > raw_page.len() {
-            return Err(DxfError::Decompression("Page data extends beyond page boundary".into()));
+        if self.configuration.crc_check {
+            // Checksum of the stored page data, CRC-64 of the page content
+            let context = format!("page {} of section '{}'", local.page_number, desc.name);
+            let actual = calculate(0, &decoded, 0, compressed_size);
+            self.check_crc(&context, local.checksum, actual)?;
+            self.check_crc(&context, local.crc, crc64(0, &output))?;
         }
 
-        let page_data = &raw_page[data_offset..data_offset + compressed_size];
+        Ok(output)
+    }
 
-        // Reed-Solomon decode + LZ77-AC21 decompress.
-        if header.compression_type == 2 {
-            // First decode with Reed-Solomon if the data is large enough.
-            let rs_block_count = (compressed_size + 0xFB - 1) / 0xFB;
-            let rs_encoded_size = rs_block_count * 0xFF;
+    // ── Encryption / decryption helpers ───────────────────────────────
 
-            if page_data.len() >= rs_encoded_size && rs_block_count > 0 {
-                let mut rs_decoded = vec![0u8; rs_block_count * 0xFB];
-                Self::reed_solomon_decoding(page_data, &mut rs_decoded);
+    /// Decrypt the AC18 system section header by XOR'ing it with the magic
+    /// sequence.
+    fn decrypt_system_section(data: &mut [u8]) {
+        let magic = &*MAGIC_SEQUENCE;
+        for (i, byte) in data.iter_mut().enumerate() {
+            *byte ^= magic[i % magic.len()];
+        }
+    }
 
-                let mut output = vec![0u8; decompressed_size];
-                DwgLz77Ac21Decompressor::decompress(&rs_decoded, 0, compressed_size as u32, &mut output);
-                Ok(output)
-            } else {
-                // Direct LZ77 decompression.
-                let mut output = vec![0u8; decompressed_size];
-                DwgLz77Ac21Decompressor::decompress(page_data, 0, compressed_size as u32, &mut output);
-                Ok(output)
+    /// Reed-Solomon de-interleaving used by AC21 pages and file headers.
+    ///
+    /// The encoded data holds `factor` interleaved blocks of 255 bytes:
+    /// byte `j` of block `i` is stored at `i + j * factor`. Only the
+    /// `block_size` data bytes of each block are kept; the check bytes are
+    /// not used for error correction.
+    fn reed_solomon_decoding(encoded: &[u8], buffer: &mut [u8], factor: usize, block_size: usize) {
+        for (i, block) in buffer.chunks_mut(block_size).take(factor).enumerate() {
+            for (j, byte) in block.iter_mut().enumerate() {
+                if let Some(&b) = encoded.get(i + j * factor) {
+                    *byte = b;
+                }
             }
-        } else {
-            // Uncompressed.
-            Ok(page_data.to_vec())
         }
     }
 
-    /// Parse an AC21 page header at the given offset.
-    fn get_page_header_data(data: &[u8], offset: usize) -> Result<PageHeaderData> {
-        if data.len() < offset + 32 {
-            return Err(DxfError::InvalidFormat(
-                "Not enough data for AC21 page header".into(),
-            ));
-        }
+    // ── Utility helpers ───────────────────────────────────────────────
 
-        let mut cursor = Cursor::new(&data[offset..]);
-        Ok(PageHeaderData {
-            section_type: cursor.read_i64::<LittleEndian>()?,
-            decompressed_size: cursor.read_i64::<LittleEndian>()?,
-            compressed_size: cursor.read_i64::<LittleEndian>()?,
-            compression_type: cursor.read_i64::<LittleEndian>()?,
-            checksum: 0, // Checksum not in fixed position; skip.
-        })
+    /// Get a reference to the AC18 header data (works for AC18 and AC21).
+    fn get_ac18(&self) -> Result<&DwgFileHeaderAC18> {
+        self.file_header
+            .as_ac18()
+            .ok_or_else(|| DxfError::InvalidHeader("Not an AC18+ header".into()))
     }
 
-    // ── Encryption / decryption helpers ───────────────────────────────
+    // ── CRC verification ──────────────────────────────────────────────
 
-    /// Decrypt the AC18 system section header using XOR with a pseudo-random mask.
-    fn decrypt_system_section(data: &mut [u8]) {
-        let mut seed: u32 = SYSTEM_SECTION_RANDOM_SEED;
-        for byte in data.iter_mut() {
-            seed = seed.wrapping_mul(0x343FD).wrapping_add(0x269EC3);
-            *byte ^= (seed >> 16) as u8;
+    /// In recover mode, report a section that could not be read and carry
+    /// on; otherwise propagate the error.
+    fn recover_from(&mut self, error: DxfError, message: &str) -> Result<()> {
+        if !self.configuration.recover {
+            return Err(error);
         }
+        self.notify(format!("{}: {}", message, error), NotificationType::Error);
+        Ok(())
     }
 
-    /// Decrypt AC18 page data using an XOR mask based on page number and offset.
-    ///
-    /// This covers the "encrypted" data section pages in AC18 format.
-    fn decrypt_data_section(data: &[u8], section_page: u32, start_offset: u32) -> Vec<u8> {
-        let mut seed = section_page.wrapping_add(start_offset);
-        seed = seed.wrapping_mul(0x343FD).wrapping_add(0x269EC3);
-
-        let mut out = data.to_vec();
-        for byte in out.iter_mut() {
-            seed = seed.wrapping_mul(0x343FD).wrapping_add(0x269EC3);
-            *byte ^= (seed >> 16) as u8;
+    /// Compare a stored CRC or checksum with the computed one. A mismatch
+    /// fails the read, or is reported as a notification in failsafe mode.
+    fn check_crc(
+        &mut self,
+        context: &str,
+        expected: impl Into<u64>,
+        actual: impl Into<u64>,
+    ) -> Result<()> {
+        let (expected, actual) = (expected.into(), actual.into());
+        if expected == actual {
+            return Ok(());
         }
 
-        out
+        if self.configuration.failsafe {
+            self.notify(
+                format!(
+                    "CRC mismatch in {}: expected 0x{:X}, computed 0x{:X}",
+                    context, expected, actual
+                ),
+                NotificationType::Error,
+            );
+            Ok(())
+        } else {
+            Err(DxfError::ChecksumMismatch { expected, actual })
+        }
     }
 
-    /// Simple Reed-Solomon interleave decoding used by AC21 file headers.
-    ///
-    /// The encoded data is arranged as 3 interleaved blocks of 255 bytes each
-    /// (251 data + 4 check bytes). The decoding simply extracts the data bytes,
-    /// ignoring the check bytes.
-    fn reed_solomon_decoding(encoded: &[u8], buffer: &mut [u8]) {
-        let block_count = (buffer.len() + 0xFB - 1) / 0xFB;
-        let data_bytes_per_block = 0xFB; // 251
-        let total_per_block = 0xFF; // 255
-
-        for i in 0..block_count {
-            let src_offset = i * total_per_block;
-            let dst_offset = i * data_bytes_per_block;
-            let remaining = buffer.len().saturating_sub(dst_offset);
-            let copy_len = remaining.min(data_bytes_per_block);
-
-            if src_offset + total_per_block <= encoded.len() {
-                // Copy data bytes, skip the 4 check bytes at the end of each block.
-                buffer[dst_offset..dst_offset + copy_len]
-                    .copy_from_slice(&encoded[src_offset..src_offset + copy_len]);
-            } else if src_offset < encoded.len() {
-                // Partial last block.
-                let avail = encoded.len() - src_offset;
-                let n = copy_len.min(avail);
-                buffer[dst_offset..dst_offset + n]
-                    .copy_from_slice(&encoded[src_offset..src_offset + n]);
+    /// Verify the CRC-8 of a sentinel-delimited section (header, classes):
+    /// start sentinel, RL size, [RL size high word], data, RS CRC.
+    fn verify_section_crc(&mut self, section_name: &str, buffer: &[u8]) -> Result<()> {
+        let start = 16;
+        let size = match buffer.get(start..start + 4) {
+            Some(b) => i32::from_le_bytes([b[0], b[1], b[2], b[3]]).max(0) as usize,
+            None => {
+                return Err(DxfError::InvalidFormat(format!(
+                    "Section '{}' is too short to hold its size",
+                    section_name
+                )))
             }
+        };
+
+        let mut crc_start = start + 4 + size;
+        if (self.version >= DxfVersion::AC1024 && self.file_header.acad_maintenance_version > 3)
+            || self.version > DxfVersion::AC1027
+        {
+            crc_start += 4;
         }
+
+        let Some(b) = buffer.get(crc_start..crc_start + 2) else {
+            return Err(DxfError::InvalidFormat(format!(
+                "Section '{}' is too short to hold its CRC",
+                section_name
+            )));
+        };
+        let expected = u16::from_le_bytes([b[0], b[1]]);
+        let actual = crc8_value(0xC0C1, buffer, start, crc_start - start);
+        self.check_crc(section_name, expected as u32, actual as u32)
     }
 
-    // ── Utility helpers ───────────────────────────────────────────────
+    /// Verify the CRC-8 of every block of the handles section. Each block
+    /// starts with its big-endian size (including the size bytes) and ends
+    /// with a big-endian CRC.
+    fn verify_handles_crc(&mut self, buffer: &[u8]) -> Result<()> {
+        let mut position = 0usize;
+        while position + 2 <= buffer.len() {
+            let size = u16::from_be_bytes([buffer[position], buffer[position + 1]]) as usize;
+            let Some(b) = buffer.get(position + size..position + size + 2) else {
+                return Err(DxfError::InvalidFormat(format!(
+                    "Handle block at {} extends beyond the section",
+                    position
+                )));
+            };
 
-    /// Get a reference to the AC18 header data (works for AC18 and AC21).
-    fn get_ac18(&self) -> Result<&DwgFileHeaderAC18> {
-        self.file_header
-            .as_ac18()
-            .ok_or_else(|| DxfError::InvalidHeader("Not an AC18+ header".into()))
+            let expected = u16::from_be_bytes([b[0], b[1]]);
+            let actual = crc8_value(0xC0C1, buffer, position, size);
+            self.check_crc(DwgSectionDefinition::HANDLES, expected as u32, actual as u32)?;
+
+            if size <= 2 {
+                break;
+            }
+            position += size + 2;
+        }
+        Ok(())
     }
 
     /// Record a notification.
@@ -1365,6 +1888,22 @@ impl<R: Read + Seek> DwgReader<R> {
     }
 }
 
+// ── Helpers ───────────────────────────────────────────────────────────────
+
+/// Map a DWG file header code page index to its `$DWGCODEPAGE` name.
+fn code_page_name(index: u16) -> String {
+    match index {
+        28 => "ANSI_1250".into(),
+        29 => "ANSI_1251".into(),
+        30 => "ANSI_1252".into(),
+        31 => "ANSI_932".into(),
+        33 => "ANSI_949".into(),
+        34 => "ANSI_950".into(),
+        35 => "ANSI_936".into(),
+        _ => format!("ANSI_{}", index),
+    }
+}
+
 // ── Tests ─────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -1443,31 +1982,13 @@ mod tests {
         encoded[254] = 0xDD;
 
         let mut decoded = vec![0u8; 251];
-        DwgReader::<Cursor<&[u8]>>::reed_solomon_decoding(&encoded, &mut decoded);
+        DwgReader::<Cursor<&[u8]>>::reed_solomon_decoding(&encoded, &mut decoded, 1, 251);
 
         for i in 0..251 {
             assert_eq!(decoded[i], (i & 0xFF) as u8);
         }
     }
 
-    #[test]
-    fn test_page_header_data() {
-        let mut data = vec![0u8; 64];
-        let mut cursor = Cursor::new(&mut data[..]);
-        use byteorder::WriteBytesExt;
-        cursor.write_i64::<LittleEndian>(AC21_PAGE_TYPE).unwrap();
-        cursor.write_i64::<LittleEndian>(1024).unwrap(); // decompressed
-        cursor.write_i64::<LittleEndian>(512).unwrap(); // compressed
-        cursor.write_i64::<LittleEndian>(2).unwrap(); // compression type
-
-        let header =
-            DwgReader::<Cursor<&[u8]>>::get_page_header_data(&data, 0).unwrap();
-        assert_eq!(header.section_type, AC21_PAGE_TYPE);
-        assert_eq!(header.decompressed_size, 1024);
-        assert_eq!(header.compressed_size, 512);
-        assert_eq!(header.compression_type, 2);
-    }
-
     #[test]
     fn test_section_name_from_hash() {
         let name = DwgReader::<Cursor<&[u8]>>::section_name_from_hash(
@@ -1485,4 +2006,279 @@ mod tests {
         );
         assert_eq!(name.unwrap(), DwgSectionDefinition::HANDLES);
     }
+
+    fn written_dwg(version: DxfVersion) -> Vec<u8> {
+        use crate::entities::{EntityType, Line};
+        use crate::types::Vector3;
+
+        let mut doc = CadDocument::new();
+        doc.version = version;
+        for i in 0..50 {
+            doc.add_entity(EntityType::Line(Line::from_points(
+                Vector3::new(i as f64, 0.0, 0.0),
+                Vector3::new(0.0, i as f64, 0.0),
+            )))
+            .unwrap();
+        }
+        crate::io::dwg::dwg_stream_writers::dwg_writer::write_dwg_to_bytes(doc).unwrap()
+    }
+
+    fn crc_configuration(failsafe: bool) -> DwgReaderConfiguration {
+        DwgReaderConfiguration {
+            crc_check: true,
+            failsafe,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_crc_check_accepts_valid_files() {
+        for version in [
+            DxfVersion::AC1015,
+            DxfVersion::AC1018,
+            DxfVersion::AC1021,
+            DxfVersion::AC1032,
+        ] {
+            let bytes = written_dwg(version);
+            let doc = DwgReader::read_from_stream(Cursor::new(bytes), crc_configuration(false))
+                .unwrap();
+            assert_eq!(doc.entities().count(), 50);
+        }
+    }
+
+    #[test]
+    fn test_crc_check_detects_corrupted_object() {
+        // AC15 stores the objects uncompressed: corrupt the last object's
+        // data, just before its CRC and the handles section
+        let mut bytes = written_dwg(DxfVersion::AC1015);
+        let mut reader =
+            DwgReader::new(Cursor::new(bytes.clone()), DwgReaderConfiguration::default()).unwrap();
+        reader.read_file_header().unwrap();
+        let handles_seeker = reader
+            .file_header
+            .as_ac15()
+            .unwrap()
+            .records
+            .get(&2)
+            .unwrap()
+            .seeker as usize;
+        bytes[handles_seeker - 3] ^= 0xFF;
+
+        let result = DwgReader::read_from_stream(Cursor::new(bytes.clone()), crc_configuration(false));
+        assert!(matches!(result, Err(DxfError::ChecksumMismatch { .. })));
+
+        let doc =
+            DwgReader::read_from_stream(Cursor::new(bytes), crc_configuration(true)).unwrap();
+        assert!(doc
+            .notifications
+            .iter()
+            .any(|n| n.message.contains("CRC")));
+    }
+
+    #[test]
+    fn test_crc_check_detects_corrupted_page() {
+        let mut bytes = written_dwg(DxfVersion::AC1018);
+        // Data of the first page, after its 32-byte header
+        bytes[0x100 + 32 + 4] ^= 0xFF;
+
+        let result = DwgReader::read_from_stream(Cursor::new(bytes), crc_configuration(false));
+        assert!(matches!(result, Err(DxfError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn test_crc_check_detects_corrupted_ac21_file() {
+        // First data byte of the first data page, after the file header
+        let mut bytes = written_dwg(DxfVersion::AC1021);
+        bytes[0x480 + 4] ^= 0xFF;
+        let result = DwgReader::read_from_stream(Cursor::new(bytes), crc_configuration(false));
+        assert!(matches!(result, Err(DxfError::ChecksumMismatch { .. })));
+
+        // Compressed data CRC of the file header: byte j of the first
+        // Reed-Solomon block is stored at 0x80 + 3 * j
+        let mut bytes = written_dwg(DxfVersion::AC1021);
+        bytes[0x80 + 3 * 16] ^= 0xFF;
+        let result =
+            DwgReader::read_from_stream(Cursor::new(bytes.clone()), crc_configuration(false));
+        assert!(matches!(result, Err(DxfError::ChecksumMismatch { .. })));
+
+        let doc =
+            DwgReader::read_from_stream(Cursor::new(bytes), crc_configuration(true)).unwrap();
+        assert_eq!(doc.entities().count(), 50);
+        assert!(doc
+            .notifications
+            .iter()
+            .any(|n| n.message.contains("AC21 file header data")));
+    }
+
+    #[test]
+    fn test_empty_metadata_sections_read_back_silently() {
+        // Empty summary info, file dependencies and revision history fit
+        // in all-zero pages, which the writer leaves out of the file
+        for version in [
+            DxfVersion::AC1018,
+            DxfVersion::AC1021,
+            DxfVersion::AC1024,
+            DxfVersion::AC1027,
+            DxfVersion::AC1032,
+        ] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            let bytes =
+                crate::io::dwg::dwg_stream_writers::dwg_writer::write_dwg_to_bytes(doc).unwrap();
+
+            let doc = DwgReader::read_from_stream(Cursor::new(bytes), crc_configuration(false))
+                .unwrap();
+            assert!(
+                doc.notifications.is_empty(),
+                "{:?}: {:?}",
+                version,
+                doc.notifications.iter().map(|n| &n.message).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    fn recover_configuration() -> DwgReaderConfiguration {
+        DwgReaderConfiguration {
+            recover: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_recover_rebuilds_object_map_from_objects_section() {
+        let mut bytes = written_dwg(DxfVersion::AC1015);
+        let mut reader =
+            DwgReader::new(Cursor::new(bytes.clone()), DwgReaderConfiguration::default()).unwrap();
+        reader.read_file_header().unwrap();
+        let handles_seeker = reader
+            .file_header
+            .as_ac15()
+            .unwrap()
+            .records
+            .get(&2)
+            .unwrap()
+            .seeker as usize;
+        // A first block claiming more bytes than the file holds
+        bytes[handles_seeker] = 0xFF;
+        bytes[handles_seeker + 1] = 0xFF;
+
+        let result = DwgReader::read_from_stream(
+            Cursor::new(bytes.clone()),
+            DwgReaderConfiguration::default(),
+        );
+        assert!(result.is_err() || result.unwrap().entities().count() < 50);
+
+        let doc =
+            DwgReader::read_from_stream(Cursor::new(bytes), recover_configuration()).unwrap();
+        assert_eq!(doc.entities().count(), 50);
+        assert!(doc
+            .notifications
+            .iter()
+            .any(|n| n.message.contains("Rebuilt the object map")));
+    }
+
+    #[test]
+    fn test_recover_skips_unreadable_pages() {
+        let mut bytes = written_dwg(DxfVersion::AC1018);
+        // Compressed size of the first data page, far beyond its page
+        bytes[0x100 + 8..0x100 + 12].copy_from_slice(&0x7FFF_FFFFu32.to_le_bytes());
+
+        let doc =
+            DwgReader::read_from_stream(Cursor::new(bytes), recover_configuration()).unwrap();
+        assert!(doc
+            .notifications
+            .iter()
+            .any(|n| n.message.contains("Skipped unreadable page")));
+    }
+
+    #[test]
+    fn test_read_object_on_demand() {
+        use crate::entities::{EntityType, Line};
+        use crate::objects::ObjectType;
+        use crate::tables::Layer;
+        use crate::types::Vector3;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            let mut layer = Layer::new("Walls");
+            layer.handle = doc.allocate_handle();
+            doc.layers.add(layer).unwrap();
+            let mut line =
+                Line::from_points(Vector3::new(1.0, 2.0, 0.0), Vector3::new(3.0, 4.0, 0.0));
+            line.common.layer = "Walls".into();
+            let line_handle = doc.add_entity(EntityType::Line(line)).unwrap();
+            let bytes =
+                crate::io::dwg::dwg_stream_writers::dwg_writer::write_dwg_to_bytes(doc).unwrap();
+
+            let mut reader =
+                DwgReader::new(Cursor::new(bytes), DwgReaderConfiguration::default()).unwrap();
+            reader.load_object_index().unwrap();
+            assert!(reader.object_handles().unwrap().contains(&line_handle));
+
+            match reader.read_object(line_handle).unwrap() {
+                Some(DwgObject::Entity(EntityType::Line(line))) => {
+                    assert_eq!(line.common.handle, line_handle);
+                    assert_eq!(line.common.layer, "Walls");
+                    assert_eq!(line.start, Vector3::new(1.0, 2.0, 0.0));
+                    assert_eq!(line.end, Vector3::new(3.0, 4.0, 0.0));
+                }
+                other => panic!("{:?}: expected a line, got {:?}", version, other),
+            }
+
+            let dictionary = reader.header().named_objects_dict_handle;
+            assert!(matches!(
+                reader.read_object(dictionary).unwrap(),
+                Some(DwgObject::Object(ObjectType::Dictionary(_)))
+            ));
+
+            // Table controls have no typed form of their own
+            let layer_control = reader.header().layer_control_handle;
+            assert!(reader.read_object(layer_control).unwrap().is_none());
+
+            // Served from the cache the second time
+            assert!(matches!(
+                reader.read_object(line_handle).unwrap(),
+                Some(DwgObject::Entity(EntityType::Line(_)))
+            ));
+            assert_eq!(reader.object_cache.len(), 3);
+            assert!(reader.object_cache.contains_key(&line_handle));
+
+            assert!(reader.read_object(Handle::new(0xFFFF_FFFF)).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_probe_reads_metadata_only() {
+        use crate::types::Vector3;
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1032] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            doc.header.model_space_extents_min = Vector3::new(-1.0, -2.0, 0.0);
+            doc.header.model_space_extents_max = Vector3::new(10.0, 20.0, 0.0);
+            doc.header.insertion_units = 4;
+            doc.header.create_date_julian = 2460000.5;
+            doc.summary_info.title = "Probe".into();
+            let bytes =
+                crate::io::dwg::dwg_stream_writers::dwg_writer::write_dwg_to_bytes(doc).unwrap();
+
+            let mut reader =
+                DwgReader::new(Cursor::new(bytes), DwgReaderConfiguration::default()).unwrap();
+            let info = reader.probe().unwrap();
+
+            assert_eq!(info.version, version);
+            assert_eq!(info.code_page, "ANSI_1252");
+            assert_eq!(info.extents_min, Vector3::new(-1.0, -2.0, 0.0));
+            assert_eq!(info.extents_max, Vector3::new(10.0, 20.0, 0.0));
+            assert_eq!(info.insertion_units, 4);
+            assert_eq!(info.create_date_julian, 2460000.5);
+            if version >= DxfVersion::AC1018 {
+                assert_eq!(info.summary_info.title, "Probe");
+            }
+            // Objects were never indexed
+            assert!(reader.object_index.is_none());
+            assert_eq!(reader.builder.document.entities().count(), 0);
+        }
+    }
 }
@@ -11,6 +11,13 @@ pub struct DwgReaderConfiguration {
     /// The DWG file format uses a modification of a standard CRC as an
     /// error-detecting mechanism. Enabling this flag causes the reader to
     /// perform this verification, but it will greatly increase the reading time.
+    ///
+    /// Verified: the file header CRC (AC15, AC18+), the page checksums of
+    /// AC18-style files, the header and classes section CRCs, the handle
+    /// section blocks and every object record. A mismatch fails the read
+    /// with [`DxfError::ChecksumMismatch`](crate::error::DxfError), or is
+    /// reported as a notification when `failsafe` is set. The CRC-64 values
+    /// of R2007 (AC1021) pages are not checked.
     pub crc_check: bool,
 
     /// If `false`, the reader will skip the summary info section.
@@ -18,7 +25,8 @@ pub struct DwgReaderConfiguration {
     pub read_summary_info: bool,
 
     /// When `true`, unknown/unrecognized entities are kept in the document
-    /// as opaque blobs rather than being dropped.
+    /// as opaque blobs rather than being dropped. Entities carrying proxy
+    /// graphics are always kept.
     /// Default: `false`.
     pub keep_unknown_entities: bool,
 
@@ -31,6 +39,15 @@ pub struct DwgReaderConfiguration {
     /// are caught and reported as notifications instead of aborting the read.
     /// Default: `false`.
     pub failsafe: bool,
+
+    /// When `true`, damaged files are read on a best-effort basis, like
+    /// AutoCAD's RECOVER command: unreadable pages are skipped, the object
+    /// map is rebuilt by scanning the objects section when the handles
+    /// section is unusable, and missing table control objects are rebuilt.
+    /// Every repair is reported in the document notifications. Implies
+    /// `failsafe` for individual objects.
+    /// Default: `false`.
+    pub recover: bool,
 }
 
 impl Default for DwgReaderConfiguration {
@@ -41,6 +58,7 @@ impl Default for DwgReaderConfiguration {
             keep_unknown_entities: false,
             keep_unknown_non_graphical_objects: false,
             failsafe: false,
+            recover: false,
         }
     }
 }
@@ -57,5 +75,6 @@ mod tests {
         assert!(!cfg.keep_unknown_entities);
         assert!(!cfg.keep_unknown_non_graphical_objects);
         assert!(!cfg.failsafe);
+        assert!(!cfg.recover);
     }
 }
@@ -1,6 +1,12 @@
+use std::io::Cursor;
+
 use crate::error::Result;
+use crate::io::dwg::dwg_section_io::{check_sentinel_from_reader, DwgSectionContext};
+use crate::io::dwg::{DwgSectionDefinition, START_SENTINELS};
+use crate::notification::Notification;
 use crate::types::DxfVersion;
 
+use super::dwg_stream_reader_base::DwgStreamReaderBase;
 use super::idwg_stream_reader::DwgStreamReader;
 
 /// Single class definition from DWG CLASSES section.
@@ -19,25 +25,88 @@ pub struct DwgClassDef {
     pub maintenance_version: i32,
 }
 
+/// Result of CLASSES section parsing.
+#[derive(Debug, Default, Clone)]
+pub struct DwgClassesReadResult {
+    /// Class definitions, in file order.
+    pub classes: Vec<DwgClassDef>,
+    /// Warnings raised while reading the section.
+    pub notifications: Vec<Notification>,
+}
+
 /// Reads DWG class records.
 pub struct DwgClassesReader;
 
 impl DwgClassesReader {
-    pub fn read(reader: &mut dyn DwgStreamReader, version: DxfVersion) -> Result<Vec<DwgClassDef>> {
+    /// Read the classes section from its raw (decompressed) buffer, starting
+    /// at the section start sentinel.
+    pub fn read(
+        version: DxfVersion,
+        acad_maintenance_version: i32,
+        buffer: &[u8],
+    ) -> Result<DwgClassesReadResult> {
+        let mut ctx = DwgSectionContext::new(version, DwgSectionDefinition::CLASSES);
+        let stream = || DwgStreamReaderBase::get_stream_handler(version, Cursor::new(buffer.to_vec()));
+        let mut reader = stream();
+
+        if let Some(sentinel) = START_SENTINELS.get(DwgSectionDefinition::CLASSES) {
+            check_sentinel_from_reader(&mut reader, sentinel, &mut ctx);
+        }
+
         // RL: size of class data area
         let size = reader.read_raw_long()? as u64;
-        let end_section = reader.position()? + size;
+        let mut end_section = reader.position()? + size;
+
+        // R2010+ (only present if the maintenance version is greater than 3)
+        if (version >= DxfVersion::AC1024 && acad_maintenance_version > 3)
+            || version > DxfVersion::AC1027
+        {
+            let _unknown = reader.read_raw_long()?;
+        }
+
+        // R2007+: strings live in a separate text stream at the end of the data
+        let mut text_reader = None;
+        if ctx.r2007_plus {
+            let flag_pos = reader.position_in_bits()? + reader.read_raw_long()? as u64 - 1;
+            let saved_offset = reader.position_in_bits()?;
+            end_section = reader.set_position_by_flag(flag_pos)?;
+            reader.set_position_in_bits(saved_offset)?;
+
+            let mut text = stream();
+            text.set_position_in_bits(end_section)?;
+            text_reader = Some(text);
+        }
+
+        if ctx.r2004_plus {
+            // BS: maximum class number
+            let _max_class_number = reader.read_bit_short()?;
+            // RC: 0x00, RC: 0x00, B: true
+            reader.read_raw_char()?;
+            reader.read_raw_char()?;
+            reader.read_bit()?;
+        }
 
         let mut classes = Vec::new();
 
         // Read until we exhaust the data (no class count field in the format)
-        while Self::get_curr_pos(reader, version)? < end_section {
+        while Self::get_curr_pos(&mut reader, version)? < end_section {
+            let class_number = reader.read_bit_short()?;
+            let proxy_cap_flags = reader.read_bit_short()?;
+
+            let text: &mut DwgStreamReaderBase = match text_reader.as_mut() {
+                Some(text) => text,
+                None => &mut reader,
+            };
+            let app_name = text.read_variable_text()?;
+            let cplusplus_name = text.read_variable_text()?;
+            let dxf_name = text.read_variable_text()?;
+
             let mut class_def = DwgClassDef {
-                class_number: reader.read_bit_short()?,
-                proxy_cap_flags: reader.read_bit_short()?,
-                app_name: reader.read_variable_text()?,
-                cplusplus_name: reader.read_variable_text()?,
-                dxf_name: reader.read_variable_text()?,
+                class_number,
+                proxy_cap_flags,
+                app_name,
+                cplusplus_name,
+                dxf_name,
                 was_zombie: reader.read_bit()?,
                 item_class_id: reader.read_bit_short()?,
                 ..Default::default()
@@ -47,7 +116,7 @@ impl DwgClassesReader {
             class_def.is_an_entity = class_def.item_class_id == 0x1F2;
 
             // R2004+ per-class fields
-            if version >= DxfVersion::AC1018 {
+            if ctx.r2004_plus {
                 class_def.instance_count = reader.read_bit_long()?;
                 class_def.dwg_version = reader.read_bit_long()?;
                 class_def.maintenance_version = reader.read_bit_long()?;
@@ -58,10 +127,10 @@ impl DwgClassesReader {
             classes.push(class_def);
         }
 
-        // RS: CRC
-        let _ = reader.reset_shift();
-
-        Ok(classes)
+        Ok(DwgClassesReadResult {
+            classes,
+            notifications: ctx.notifications,
+        })
     }
 
     fn get_curr_pos(reader: &mut dyn DwgStreamReader, version: DxfVersion) -> Result<u64> {
@@ -72,3 +141,47 @@ impl DwgClassesReader {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::DxfClass;
+    use crate::io::dwg::dwg_stream_writers::dwg_classes_writer::DwgClassesWriter;
+
+    fn sample_classes() -> Vec<DxfClass> {
+        let mut a = DxfClass::new("ACDBDICTIONARYWDFLT", "AcDbDictionaryWithDefault");
+        a.class_number = 500;
+        a.item_class_id = 0x1F3;
+        let mut b = DxfClass::new("MESH", "AcDbSubDMesh");
+        b.class_number = 501;
+        b.item_class_id = 0x1F2;
+        b.instance_count = 3;
+        vec![a, b]
+    }
+
+    #[test]
+    fn test_round_trip_all_versions() {
+        for version in [
+            DxfVersion::AC1015,
+            DxfVersion::AC1018,
+            DxfVersion::AC1021,
+            DxfVersion::AC1024,
+            DxfVersion::AC1032,
+        ] {
+            let maint = version.maintenance_version();
+            let bytes = DwgClassesWriter::write(version, &sample_classes(), maint).unwrap();
+            let result = DwgClassesReader::read(version, maint as i32, &bytes).unwrap();
+
+            assert_eq!(result.classes.len(), 2, "{:?}", version);
+            assert_eq!(result.classes[0].class_number, 500);
+            assert_eq!(result.classes[0].dxf_name, "ACDBDICTIONARYWDFLT");
+            assert_eq!(result.classes[0].cplusplus_name, "AcDbDictionaryWithDefault");
+            assert!(!result.classes[0].is_an_entity);
+            assert_eq!(result.classes[1].dxf_name, "MESH");
+            assert!(result.classes[1].is_an_entity);
+            if version >= DxfVersion::AC1018 {
+                assert_eq!(result.classes[1].instance_count, 3);
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::document::FileDependency;
+use crate::error::{DxfError, Result};
+
+use super::idwg_stream_reader::DwgStreamReader;
+
+/// Reads the DWG file dependency list (AcDb:FileDepList, R2004+).
+///
+/// Layout:
+/// - Int32 feature count, followed by the feature names (String32)
+/// - Int32 file count, followed by one record per file:
+///   full filename, found path, fingerprint GUID, version GUID (String32),
+///   Int32 feature index, Int32 timestamp, Int32 file size,
+///   Int16 affects graphics, Int32 reference count
+///
+/// A String32 is an Int32 byte length followed by the string bytes.
+pub struct DwgFileDepListReader;
+
+impl DwgFileDepListReader {
+    /// Read the FileDepList section.
+    pub fn read(reader: &mut dyn DwgStreamReader) -> Result<Vec<FileDependency>> {
+        let feature_count = Self::read_count(reader)?;
+        let mut features = Vec::with_capacity(feature_count);
+        for _ in 0..feature_count {
+            features.push(Self::read_string32(reader)?);
+        }
+
+        let file_count = Self::read_count(reader)?;
+        let mut files = Vec::with_capacity(file_count);
+        for _ in 0..file_count {
+            let full_filename = Self::read_string32(reader)?;
+            let found_path = Self::read_string32(reader)?;
+            let fingerprint_guid = Self::read_string32(reader)?;
+            let version_guid = Self::read_string32(reader)?;
+            let feature_index = reader.read_int()?;
+            let timestamp = reader.read_int()?;
+            let file_size = reader.read_int()?;
+            let affects_graphics = reader.read_short()? != 0;
+            let reference_count = reader.read_int()?;
+
+            let feature = usize::try_from(feature_index)
+                .ok()
+                .and_then(|i| features.get(i).cloned())
+                .unwrap_or_default();
+
+            files.push(FileDependency {
+                feature,
+                full_filename,
+                found_path,
+                fingerprint_guid,
+                version_guid,
+                timestamp,
+                file_size,
+                affects_graphics,
+                reference_count,
+            });
+        }
+
+        Ok(files)
+    }
+
+    fn read_count(reader: &mut dyn DwgStreamReader) -> Result<usize> {
+        let count = reader.read_int()?;
+        usize::try_from(count)
+            .map_err(|_| DxfError::InvalidFormat(format!("Invalid FileDepList count {}", count)))
+    }
+
+    fn read_string32(reader: &mut dyn DwgStreamReader) -> Result<String> {
+        let length = Self::read_count(reader)?;
+        if length == 0 {
+            return Ok(String::new());
+        }
+        let bytes = reader.read_bytes(length)?;
+        Ok(String::from_utf8_lossy(&bytes).replace('\0', ""))
+    }
+}
@@ -1,403 +1,800 @@
-use std::collections::BTreeMap;
-
-use crate::{
-    error::Result,
-    types::{DxfVersion, Vector2, Vector3},
-};
-
+//! DWG header (drawing variable) section reader.
+//!
+//! Mirror of `DwgHeaderWriter`: reads the HEADER section variables with the
+//! same version-conditional blocks (R13 through R2018+) straight into
+//! [`HeaderVariables`], and collects the object pointers (table controls,
+//! dictionaries, current layer/style handles) into a
+//! [`DwgHeaderHandlesCollection`].
+
+use std::io::Cursor;
+
+use crate::document::HeaderVariables;
+use crate::error::Result;
+use crate::io::dwg::dwg_header_handles_collection::{handle_names, DwgHeaderHandlesCollection};
+use crate::io::dwg::dwg_section_io::{check_sentinel_from_reader, DwgSectionContext};
+use crate::io::dwg::{DwgSectionDefinition, START_SENTINELS};
+use crate::notification::Notification;
+use crate::types::{DxfVersion, Handle};
+
+use super::dwg_stream_reader_base::DwgStreamReaderBase;
 use super::idwg_stream_reader::DwgStreamReader;
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum DwgHeaderValue {
-    Bool(bool),
-    I32(i32),
-    I64(i64),
-    F64(f64),
-    Text(String),
-    Handle(u64),
-    Point2(Vector2),
-    Point3(Vector3),
-    PairI32(i32, i32),
+/// Result of HEADER section parsing.
+#[derive(Debug, Default, Clone)]
+pub struct DwgHeaderReadResult {
+    /// Header variables read from the section.
+    pub header: HeaderVariables,
+    /// Object pointers (handles) referenced by the header.
+    pub object_pointers: DwgHeaderHandlesCollection,
+    /// Warnings raised while reading the section.
+    pub notifications: Vec<Notification>,
 }
 
-/// Raw DWG header bag with semantic keys.
-#[derive(Debug, Default, Clone)]
-pub struct DwgHeaderData {
-    pub vars: BTreeMap<String, DwgHeaderValue>,
+/// Main/text/handle streams of the header section.
+///
+/// Before R2007 all three share the same bit stream; from R2007 on strings
+/// and handles live in their own streams located after the main data.
+struct HeaderStreams {
+    main: DwgStreamReaderBase,
+    text: Option<DwgStreamReaderBase>,
+    handles: Option<DwgStreamReaderBase>,
 }
 
-impl DwgHeaderData {
-    fn set(&mut self, key: impl Into<String>, value: DwgHeaderValue) {
-        self.vars.insert(key.into(), value);
+impl HeaderStreams {
+    fn text(&mut self) -> &mut DwgStreamReaderBase {
+        match self.text.as_mut() {
+            Some(text) => text,
+            None => &mut self.main,
+        }
     }
-}
 
-/// Header object pointer handles extracted from DWG HEADER section.
-#[derive(Debug, Default, Clone)]
-pub struct DwgHeaderHandlesCollection {
-    pub handles: BTreeMap<String, u64>,
-}
+    fn handles(&mut self) -> &mut DwgStreamReaderBase {
+        match self.handles.as_mut() {
+            Some(handles) => handles,
+            None => &mut self.main,
+        }
+    }
 
-impl DwgHeaderHandlesCollection {
-    fn set(&mut self, key: impl Into<String>, value: u64) {
-        self.handles.insert(key.into(), value);
+    fn read_text(&mut self) -> Result<String> {
+        self.text().read_variable_text()
     }
-}
 
-/// Result of HEADER section parsing.
-#[derive(Debug, Default, Clone)]
-pub struct DwgHeaderReadResult {
-    pub header: DwgHeaderData,
-    pub object_pointers: DwgHeaderHandlesCollection,
+    fn read_handle(&mut self) -> Result<u64> {
+        self.handles().handle_reference()
+    }
 }
 
-/// Reads DWG HEADER section (semantic port of ACadSharp flow).
+/// Reads the DWG HEADER section (port of ACadSharp `DwgHeaderReader`).
 pub struct DwgHeaderReader;
 
 impl DwgHeaderReader {
+    /// Read the header section from its raw (decompressed) buffer, starting
+    /// at the section start sentinel.
     pub fn read(
         version: DxfVersion,
         acad_maintenance_version: i32,
-        reader: &mut dyn DwgStreamReader,
+        buffer: &[u8],
     ) -> Result<DwgHeaderReadResult> {
+        let mut ctx = DwgSectionContext::new(version, DwgSectionDefinition::HEADER);
         let mut out = DwgHeaderReadResult::default();
+        let header = &mut out.header;
+        let pointers = &mut out.object_pointers;
 
-        // Section size (RL)
-        let section_size = reader.read_raw_long()?;
-        out.header
-            .set("_section_size", DwgHeaderValue::I64(section_size as i64));
+        let stream = || DwgStreamReaderBase::get_stream_handler(version, Cursor::new(buffer.to_vec()));
+        let mut main = stream();
 
-        if (Self::r2010_plus(version) && acad_maintenance_version > 3) || Self::r2018_plus(version) {
-            out.header.set(
-                "_unknown64_part",
-                DwgHeaderValue::I64(reader.read_raw_long()?),
-            );
+        if let Some(sentinel) = START_SENTINELS.get(DwgSectionDefinition::HEADER) {
+            check_sentinel_from_reader(&mut main, sentinel, &mut ctx);
         }
 
-        if Self::r2013_plus(version) {
-            out.header
-                .set("required_versions", DwgHeaderValue::I64(reader.read_bit_long_long()?));
+        // RL: size of the section
+        let _size = main.read_raw_long()?;
+
+        // R2010+ (maintenance > 3) and R2018+: high part of a 64-bit size
+        if (ctx.r2010_plus && acad_maintenance_version > 3) || ctx.r2018_plus {
+            let _unknown = main.read_raw_long()?;
         }
 
-        Self::read_common_prelude(reader, &mut out.header)?;
-        Self::read_common_flags(version, reader, &mut out.header)?;
-        Self::read_common_numeric(version, reader, &mut out.header)?;
-        Self::read_common_dates(reader, &mut out.header)?;
+        let mut s = if ctx.r2007_plus {
+            let initial_pos = main.position_in_bits()?;
+            let size_in_bits = main.read_raw_long()? as u64;
+            let last_position_in_bits = initial_pos + size_in_bits - 1;
 
-        out.header.set(
-            "current_entity_color",
-            DwgHeaderValue::I32(reader.read_cm_color(false)?.approximate_index() as i32),
-        );
+            let mut text = stream();
+            text.set_position_by_flag(last_position_in_bits)?;
 
-        // HANDSEED is read from main stream in C# implementation; here we use same reader.
-        out.header
-            .set("handle_seed", DwgHeaderValue::Handle(reader.handle_reference()?));
+            let mut handles = stream();
+            handles.set_position_in_bits(last_position_in_bits + 1)?;
 
-        Self::read_primary_handles(version, reader, &mut out.object_pointers)?;
-        Self::read_space_data(version, reader, &mut out.header, &mut out.object_pointers)?;
-        Self::read_object_pointer_groups(version, reader, &mut out.object_pointers, &mut out.header)?;
+            HeaderStreams {
+                main,
+                text: Some(text),
+                handles: Some(handles),
+            }
+        } else {
+            HeaderStreams {
+                main,
+                text: None,
+                handles: None,
+            }
+        };
 
-        Ok(out)
-    }
+        // R2013+:
+        if ctx.r2013_plus {
+            header.required_versions = s.main.read_bit_long_long()?;
+        }
 
-    fn read_common_prelude(reader: &mut dyn DwgStreamReader, header: &mut DwgHeaderData) -> Result<()> {
-        header.set("_unknown_bd_1", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("_unknown_bd_2", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("_unknown_bd_3", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("_unknown_bd_4", DwgHeaderValue::F64(reader.read_bit_double()?));
+        // Common: unknown defaults (412148564080.0, 1.0, 1.0, 1.0, "m", "", "", "", 24, 0)
+        for _ in 0..4 {
+            s.main.read_bit_double()?;
+        }
+        for _ in 0..4 {
+            s.read_text()?;
+        }
+        s.main.read_bit_long()?;
+        s.main.read_bit_long()?;
 
-        header.set("_unknown_tv_1", DwgHeaderValue::Text(reader.read_variable_text()?));
-        header.set("_unknown_tv_2", DwgHeaderValue::Text(reader.read_variable_text()?));
-        header.set("_unknown_tv_3", DwgHeaderValue::Text(reader.read_variable_text()?));
-        header.set("_unknown_tv_4", DwgHeaderValue::Text(reader.read_variable_text()?));
+        // R13-R14 Only: unknown BS
+        if ctx.r13_14_only {
+            s.main.read_bit_short()?;
+        }
 
-        header.set("_unknown_bl_1", DwgHeaderValue::I32(reader.read_bit_long()?));
-        header.set("_unknown_bl_2", DwgHeaderValue::I32(reader.read_bit_long()?));
-        Ok(())
-    }
+        // Pre-2004: current viewport entity header
+        if ctx.r2004_pre {
+            s.read_handle()?;
+        }
 
-    fn read_common_flags(
-        version: DxfVersion,
-        reader: &mut dyn DwgStreamReader,
-        header: &mut DwgHeaderData,
-    ) -> Result<()> {
-        header.set("dimaso", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("dimsho", DwgHeaderValue::Bool(reader.read_bit()?));
+        header.associate_dimensions = s.main.read_bit()?;
+        header.update_dimensions_while_dragging = s.main.read_bit()?;
 
-        if Self::r13_14_only(version) {
-            header.set("dimsav", DwgHeaderValue::Bool(reader.read_bit()?));
+        if ctx.r13_14_only {
+            s.main.read_bit()?; // DIMSAV
         }
 
-        header.set("plinegen", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("orthomode", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("regenmode", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("fillmode", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("qtextmode", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("psltscale", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("limcheck", DwgHeaderValue::Bool(reader.read_bit()?));
+        header.polyline_linetype_generation = s.main.read_bit()?;
+        header.ortho_mode = s.main.read_bit()?;
+        header.regen_mode = s.main.read_bit()?;
+        header.fill_mode = s.main.read_bit()?;
+        header.quick_text_mode = s.main.read_bit()?;
+        header.paper_space_linetype_scaling = s.main.read_bit()?;
+        header.limit_check = s.main.read_bit()?;
 
-        if Self::r13_14_only(version) {
-            header.set("blipmode", DwgHeaderValue::Bool(reader.read_bit()?));
+        if ctx.r13_14_only {
+            header.blip_mode = s.main.read_bit()?;
         }
-        if Self::r2004_plus(version) {
-            let _ = reader.read_bit()?;
+
+        if ctx.r2004_plus {
+            s.main.read_bit()?; // Undocumented
         }
 
-        header.set("usrtimer", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("skpoly", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("angdir", DwgHeaderValue::I32(reader.read_bit_as_short()? as i32));
-        header.set("splframe", DwgHeaderValue::Bool(reader.read_bit()?));
+        header.user_timer = s.main.read_bit()?;
+        header.sketch_polylines = s.main.read_bit()?;
+        header.angle_direction = s.main.read_bit()? as i16;
+        header.spline_frame = s.main.read_bit()?;
 
-        if Self::r13_14_only(version) {
-            let _ = reader.read_bit()?; // ATTREQ
-            let _ = reader.read_bit()?; // ATTDIA
+        if ctx.r13_14_only {
+            header.attribute_request = s.main.read_bit()?;
+            header.attribute_dialog = s.main.read_bit()?;
         }
 
-        header.set("mirrtext", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("worldview", DwgHeaderValue::Bool(reader.read_bit()?));
-        if Self::r13_14_only(version) {
-            let _ = reader.read_bit()?; // WIREFRAME undocumented
+        header.mirror_text = s.main.read_bit()?;
+        header.world_view = s.main.read_bit()?;
+
+        if ctx.r13_14_only {
+            s.main.read_bit()?; // WIREFRAME
         }
 
-        header.set("tilemode", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("plimcheck", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("visretain", DwgHeaderValue::Bool(reader.read_bit()?));
-        if Self::r13_14_only(version) {
-            let _ = reader.read_bit()?; // DELOBJ
+        header.show_model_space = s.main.read_bit()?;
+        header.paper_space_limit_check = s.main.read_bit()?;
+        header.retain_xref_visibility = s.main.read_bit()?;
+
+        if ctx.r13_14_only {
+            header.delete_objects = s.main.read_bit()?;
         }
 
-        header.set("dispsilh", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("pellipse", DwgHeaderValue::Bool(reader.read_bit()?));
-        header.set("proxygraphics", DwgHeaderValue::Bool(reader.read_bit_short_as_bool()?));
+        header.display_silhouette = s.main.read_bit()?;
+        s.main.read_bit()?; // PELLIPSE
+        header.proxy_graphics = s.main.read_bit_short()?;
 
-        Ok(())
-    }
+        if ctx.r13_14_only {
+            header.drag_mode = s.main.read_bit_short()?;
+        }
 
-    fn read_common_numeric(
-        version: DxfVersion,
-        reader: &mut dyn DwgStreamReader,
-        header: &mut DwgHeaderData,
-    ) -> Result<()> {
-        if Self::r13_14_only(version) {
-            let _ = reader.read_bit_short()?; // DRAGMODE
+        header.tree_depth = s.main.read_bit_short()?;
+        header.linear_unit_format = s.main.read_bit_short()?;
+        header.linear_unit_precision = s.main.read_bit_short()?;
+        header.angular_unit_format = s.main.read_bit_short()?;
+        header.angular_unit_precision = s.main.read_bit_short()?;
+
+        if ctx.r13_14_only {
+            header.object_snap_mode = s.main.read_bit_short()? as i32;
         }
 
-        header.set("treedepth", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
-        header.set("lunits", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
-        header.set("luprec", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
-        header.set("aunits", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
-        header.set("auprec", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
+        header.attribute_visibility = s.main.read_bit_short()?;
 
-        if Self::r13_14_only(version) {
-            header.set("osmode", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
+        if ctx.r13_14_only {
+            header.coords_mode = s.main.read_bit_short()?;
         }
 
-        header.set("attmode", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
-        if Self::r13_14_only(version) {
-            let _ = reader.read_bit_short()?; // COORDS
+        header.point_display_mode = s.main.read_bit_short()?;
+
+        if ctx.r13_14_only {
+            header.pick_style = s.main.read_bit_short()?;
         }
 
-        header.set("pdmode", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
+        if ctx.r2004_plus {
+            s.main.read_bit_long()?;
+            s.main.read_bit_long()?;
+            s.main.read_bit_long()?;
+        }
 
-        // USERI1..5
-        for i in 1..=5 {
-            header.set(
-                format!("useri{i}"),
-                DwgHeaderValue::I32(reader.read_bit_short()? as i32),
-            );
+        header.user_int1 = s.main.read_bit_short()?;
+        header.user_int2 = s.main.read_bit_short()?;
+        header.user_int3 = s.main.read_bit_short()?;
+        header.user_int4 = s.main.read_bit_short()?;
+        header.user_int5 = s.main.read_bit_short()?;
+
+        header.spline_segments = s.main.read_bit_short()?;
+        header.surface_u_density = s.main.read_bit_short()?;
+        header.surface_v_density = s.main.read_bit_short()?;
+        header.surface_type = s.main.read_bit_short()?;
+        header.surface_tab1 = s.main.read_bit_short()?;
+        header.surface_tab2 = s.main.read_bit_short()?;
+        header.spline_type = s.main.read_bit_short()?;
+        header.shade_edge = s.main.read_bit_short()?;
+        header.shade_diffuse = s.main.read_bit_short()?;
+        header.unit_mode = s.main.read_bit_short()?;
+        header.max_active_viewports = s.main.read_bit_short()?;
+        header.isolines = s.main.read_bit_short()?;
+        header.multiline_justification = s.main.read_bit_short()?;
+        header.text_quality = s.main.read_bit_short()?;
+
+        header.linetype_scale = s.main.read_bit_double()?;
+        header.text_height = s.main.read_bit_double()?;
+        header.trace_width = s.main.read_bit_double()?;
+        header.sketch_increment = s.main.read_bit_double()?;
+        header.fillet_radius = s.main.read_bit_double()?;
+        header.thickness = s.main.read_bit_double()?;
+        header.angle_base = s.main.read_bit_double()?;
+        header.point_display_size = s.main.read_bit_double()?;
+        header.polyline_width = s.main.read_bit_double()?;
+        header.user_real1 = s.main.read_bit_double()?;
+        header.user_real2 = s.main.read_bit_double()?;
+        header.user_real3 = s.main.read_bit_double()?;
+        header.user_real4 = s.main.read_bit_double()?;
+        header.user_real5 = s.main.read_bit_double()?;
+        header.chamfer_distance_a = s.main.read_bit_double()?;
+        header.chamfer_distance_b = s.main.read_bit_double()?;
+        header.chamfer_length = s.main.read_bit_double()?;
+        header.chamfer_angle = s.main.read_bit_double()?;
+        header.facet_resolution = s.main.read_bit_double()?;
+        header.multiline_scale = s.main.read_bit_double()?;
+        header.current_entity_linetype_scale = s.main.read_bit_double()?;
+
+        header.menu_name = s.read_text()?;
+
+        // TDCREATE / TDUPDATE
+        header.create_date_julian = julian_to_f64(s.main.read_date_time()?);
+        header.update_date_julian = julian_to_f64(s.main.read_date_time()?);
+
+        if ctx.r2004_plus {
+            s.main.read_bit_long()?;
+            s.main.read_bit_long()?;
+            s.main.read_bit_long()?;
         }
 
-        header.set("ltscale", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("textsize", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("tracewid", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("sketchinc", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("filletrad", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("thickness", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("angbase", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("pdsize", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("plinewid", DwgHeaderValue::F64(reader.read_bit_double()?));
+        // TDINDWG / TDUSRTIMER
+        header.total_editing_time = julian_to_f64(s.main.read_time_span()?);
+        header.user_elapsed_time = julian_to_f64(s.main.read_time_span()?);
 
-        header.set("menuname", DwgHeaderValue::Text(reader.read_variable_text()?));
+        header.current_entity_color = s.main.read_cm_color(false)?;
 
-        Ok(())
-    }
+        // HANDSEED lives in the main stream, not the handle stream.
+        header.handle_seed = s.main.handle_reference()?;
 
-    fn read_common_dates(reader: &mut dyn DwgStreamReader, header: &mut DwgHeaderData) -> Result<()> {
-        let (d1, ms1) = reader.read_date_time()?;
-        header.set("tdcreate", DwgHeaderValue::PairI32(d1, ms1));
+        let clayer = s.read_handle()?;
+        header.current_layer_handle = Handle::new(clayer);
+        pointers.set(handle_names::CLAYER, clayer);
 
-        let (d2, ms2) = reader.read_date_time()?;
-        header.set("tdupdate", DwgHeaderValue::PairI32(d2, ms2));
+        let textstyle = s.read_handle()?;
+        header.current_text_style_handle = Handle::new(textstyle);
+        pointers.set(handle_names::TEXTSTYLE, textstyle);
 
-        let (d3, ms3) = reader.read_time_span()?;
-        header.set("tdindwg", DwgHeaderValue::PairI32(d3, ms3));
+        let celtype = s.read_handle()?;
+        header.current_linetype_handle = Handle::new(celtype);
+        pointers.set(handle_names::CELTYPE, celtype);
 
-        let (d4, ms4) = reader.read_time_span()?;
-        header.set("tdusrtimer", DwgHeaderValue::PairI32(d4, ms4));
+        if ctx.r2007_plus {
+            let cmaterial = s.read_handle()?;
+            header.current_material_handle = Handle::new(cmaterial);
+            pointers.set(handle_names::CMATERIAL, cmaterial);
+        }
 
-        Ok(())
-    }
+        let dimstyle = s.read_handle()?;
+        header.current_dimstyle_handle = Handle::new(dimstyle);
+        pointers.set(handle_names::DIMSTYLE, dimstyle);
 
-    fn read_primary_handles(
-        version: DxfVersion,
-        reader: &mut dyn DwgStreamReader,
-        pointers: &mut DwgHeaderHandlesCollection,
-    ) -> Result<()> {
-        pointers.set("CLAYER", reader.handle_reference()?);
-        pointers.set("TEXTSTYLE", reader.handle_reference()?);
-        pointers.set("CELTYPE", reader.handle_reference()?);
+        let cmlstyle = s.read_handle()?;
+        header.current_multiline_style_handle = Handle::new(cmlstyle);
+        pointers.set(handle_names::CMLSTYLE, cmlstyle);
 
-        if Self::r2007_plus(version) {
-            pointers.set("CMATERIAL", reader.handle_reference()?);
+        if ctx.r2000_plus {
+            header.viewport_scale_factor = s.main.read_bit_double()?;
         }
 
-        pointers.set("DIMSTYLE", reader.handle_reference()?);
-        pointers.set("CMLSTYLE", reader.handle_reference()?);
-        Ok(())
-    }
+        // Paper space
+        header.paper_space_insertion_base = s.main.read_3_bit_double()?;
+        header.paper_space_extents_min = s.main.read_3_bit_double()?;
+        header.paper_space_extents_max = s.main.read_3_bit_double()?;
+        header.paper_space_limits_min = s.main.read_2_raw_double()?;
+        header.paper_space_limits_max = s.main.read_2_raw_double()?;
+        header.paper_elevation = s.main.read_bit_double()?;
+        header.paper_space_ucs_origin = s.main.read_3_bit_double()?;
+        header.paper_space_ucs_x_axis = s.main.read_3_bit_double()?;
+        header.paper_space_ucs_y_axis = s.main.read_3_bit_double()?;
+
+        pointers.set(handle_names::UCSNAME_PSPACE, s.read_handle()?);
+
+        if ctx.r2000_plus {
+            let pucsorthoref = s.read_handle()?;
+            header.paper_ucs_ortho_ref = Handle::new(pucsorthoref);
+            pointers.set(handle_names::PUCSORTHOREF, pucsorthoref);
+            header.paper_ucs_ortho_view = s.main.read_bit_short()?;
+            pointers.set(handle_names::PUCSBASE, s.read_handle()?);
+            header.paper_space_ucs_origin_top = s.main.read_3_bit_double()?;
+            header.paper_space_ucs_origin_bottom = s.main.read_3_bit_double()?;
+            header.paper_space_ucs_origin_left = s.main.read_3_bit_double()?;
+            header.paper_space_ucs_origin_right = s.main.read_3_bit_double()?;
+            header.paper_space_ucs_origin_front = s.main.read_3_bit_double()?;
+            header.paper_space_ucs_origin_back = s.main.read_3_bit_double()?;
+        }
 
-    fn read_space_data(
-        version: DxfVersion,
-        reader: &mut dyn DwgStreamReader,
-        header: &mut DwgHeaderData,
-        pointers: &mut DwgHeaderHandlesCollection,
-    ) -> Result<()> {
-        if Self::r2000_plus(version) {
-            header.set("psvpscale", DwgHeaderValue::F64(reader.read_bit_double()?));
-        }
-
-        // PSPACE
-        header.set("insbase_pspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("extmin_pspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("extmax_pspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("limmin_pspace", DwgHeaderValue::Point2(reader.read_2_raw_double()?));
-        header.set("limmax_pspace", DwgHeaderValue::Point2(reader.read_2_raw_double()?));
-
-        header.set("elevation_pspace", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("ucsorg_pspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("ucsxdir_pspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("ucsydir_pspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-
-        pointers.set("UCSNAME_PSPACE", reader.handle_reference()?);
-
-        if Self::r2000_plus(version) {
-            pointers.set("PUCSORTHOREF", reader.handle_reference()?);
-            header.set("PUCSORTHOVIEW", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
-            pointers.set("PUCSBASE", reader.handle_reference()?);
-
-            header.set("pucsorgtop", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-            header.set("pucsorgbottom", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-            header.set("pucsorgleft", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-            header.set("pucsorgright", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-            header.set("pucsorgfront", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-            header.set("pucsorgback", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        }
-
-        // MSPACE
-        header.set("insbase_mspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("extmin_mspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("extmax_mspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("limmin_mspace", DwgHeaderValue::Point2(reader.read_2_raw_double()?));
-        header.set("limmax_mspace", DwgHeaderValue::Point2(reader.read_2_raw_double()?));
-        header.set("elevation_mspace", DwgHeaderValue::F64(reader.read_bit_double()?));
-        header.set("ucsorg_mspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("ucsxdir_mspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        header.set("ucsydir_mspace", DwgHeaderValue::Point3(reader.read_3_bit_double()?));
-        pointers.set("UCSNAME_MSPACE", reader.handle_reference()?);
-
-        Ok(())
-    }
+        // Model space
+        header.model_space_insertion_base = s.main.read_3_bit_double()?;
+        header.model_space_extents_min = s.main.read_3_bit_double()?;
+        header.model_space_extents_max = s.main.read_3_bit_double()?;
+        header.model_space_limits_min = s.main.read_2_raw_double()?;
+        header.model_space_limits_max = s.main.read_2_raw_double()?;
+        header.elevation = s.main.read_bit_double()?;
+        header.model_space_ucs_origin = s.main.read_3_bit_double()?;
+        header.model_space_ucs_x_axis = s.main.read_3_bit_double()?;
+        header.model_space_ucs_y_axis = s.main.read_3_bit_double()?;
+
+        pointers.set(handle_names::UCSNAME_MSPACE, s.read_handle()?);
+
+        if ctx.r2000_plus {
+            let ucsorthoref = s.read_handle()?;
+            header.ucs_ortho_ref = Handle::new(ucsorthoref);
+            pointers.set(handle_names::UCSORTHOREF, ucsorthoref);
+            header.ucs_ortho_view = s.main.read_bit_short()?;
+            pointers.set(handle_names::UCSBASE, s.read_handle()?);
+            header.model_space_ucs_origin_top = s.main.read_3_bit_double()?;
+            header.model_space_ucs_origin_bottom = s.main.read_3_bit_double()?;
+            header.model_space_ucs_origin_left = s.main.read_3_bit_double()?;
+            header.model_space_ucs_origin_right = s.main.read_3_bit_double()?;
+            header.model_space_ucs_origin_front = s.main.read_3_bit_double()?;
+            header.model_space_ucs_origin_back = s.main.read_3_bit_double()?;
+
+            header.dim_post = s.read_text()?;
+            header.dim_alt_post = s.read_text()?;
+        }
 
-    fn read_object_pointer_groups(
-        version: DxfVersion,
-        reader: &mut dyn DwgStreamReader,
-        pointers: &mut DwgHeaderHandlesCollection,
-        header: &mut DwgHeaderData,
-    ) -> Result<()> {
-        if Self::r2000_plus(version) {
-            pointers.set("UCSORTHOREF", reader.handle_reference()?);
-            header.set("UCSORTHOVIEW", DwgHeaderValue::I32(reader.read_bit_short()? as i32));
-            pointers.set("UCSBASE", reader.handle_reference()?);
-
-            header.set("dimpost", DwgHeaderValue::Text(reader.read_variable_text()?));
-            header.set("dimapost", DwgHeaderValue::Text(reader.read_variable_text()?));
-        }
-
-        // table/control object pointers
-        for key in [
-            "BLOCK_CONTROL_OBJECT",
-            "LAYER_CONTROL_OBJECT",
-            "STYLE_CONTROL_OBJECT",
-            "LINETYPE_CONTROL_OBJECT",
-            "VIEW_CONTROL_OBJECT",
-            "UCS_CONTROL_OBJECT",
-            "VPORT_CONTROL_OBJECT",
-            "APPID_CONTROL_OBJECT",
-            "DIMSTYLE_CONTROL_OBJECT",
-            "DICTIONARY_ACAD_GROUP",
-            "DICTIONARY_ACAD_MLINESTYLE",
-            "DICTIONARY_NAMED_OBJECTS",
-        ] {
-            pointers.set(key, reader.handle_reference()?);
+        if ctx.r13_14_only {
+            header.dim_tolerance = s.main.read_bit()?;
+            header.dim_limits = s.main.read_bit()?;
+            header.dim_text_inside_horizontal = s.main.read_bit()?;
+            header.dim_text_outside_horizontal = s.main.read_bit()?;
+            header.dim_suppress_ext1 = s.main.read_bit()?;
+            header.dim_suppress_ext2 = s.main.read_bit()?;
+            header.dim_alternate_units = s.main.read_bit()?;
+            header.dim_force_line_inside = s.main.read_bit()?;
+            header.dim_separate_arrows = s.main.read_bit()?;
+            header.dim_force_text_inside = s.main.read_bit()?;
+            header.dim_suppress_outside_ext = s.main.read_bit()?;
+            header.dim_alt_decimal_places = s.main.read_byte()? as i16;
+            header.dim_zero_suppression = s.main.read_byte()? as i16;
+            header.dim_suppress_line1 = s.main.read_bit()?;
+            header.dim_suppress_line2 = s.main.read_bit()?;
+            header.dim_tolerance_justification = s.main.read_byte()? as i16;
+            header.dim_horizontal_justification = s.main.read_byte()? as i16;
+            header.dim_fit = s.main.read_byte()? as i16;
+            header.dim_user_positioned_text = s.main.read_bit()?;
+            header.dim_tolerance_zero_suppression = s.main.read_byte()? as i16;
+            header.dim_alt_tolerance_zero_suppression = s.main.read_byte()? as i16;
+            header.dim_alt_tolerance_zero_tight = s.main.read_byte()? as i16;
+            header.dim_text_above = s.main.read_byte()? as i16;
+            s.main.read_bit_short()?; // DIMUNIT
+            header.dim_angular_decimal_places = s.main.read_bit_short()?;
+            header.dim_decimal_places = s.main.read_bit_short()?;
+            header.dim_tolerance_decimal_places = s.main.read_bit_short()?;
+            header.dim_alt_units_format = s.main.read_bit_short()?;
+            header.dim_alt_tolerance_decimal_places = s.main.read_bit_short()?;
+
+            let dimtxsty = s.read_handle()?;
+            header.dim_text_style_handle = Handle::new(dimtxsty);
+            pointers.set(handle_names::DIMTXSTY, dimtxsty);
+        }
+
+        header.dim_scale = s.main.read_bit_double()?;
+        header.dim_arrow_size = s.main.read_bit_double()?;
+        header.dim_ext_line_offset = s.main.read_bit_double()?;
+        header.dim_line_increment = s.main.read_bit_double()?;
+        header.dim_ext_line_extension = s.main.read_bit_double()?;
+        header.dim_rounding = s.main.read_bit_double()?;
+        header.dim_line_extension = s.main.read_bit_double()?;
+        header.dim_tolerance_plus = s.main.read_bit_double()?;
+        header.dim_tolerance_minus = s.main.read_bit_double()?;
+
+        if ctx.r2007_plus {
+            header.dim_fixed_ext_line_length = s.main.read_bit_double()?;
+            header.dim_jog_angle = s.main.read_bit_double()?;
+            header.dim_text_fill = s.main.read_bit_short()?;
+            header.dim_text_fill_color = s.main.read_cm_color(false)?;
+        }
+
+        if ctx.r2000_plus {
+            header.dim_tolerance = s.main.read_bit()?;
+            header.dim_limits = s.main.read_bit()?;
+            header.dim_text_inside_horizontal = s.main.read_bit()?;
+            header.dim_text_outside_horizontal = s.main.read_bit()?;
+            header.dim_suppress_ext1 = s.main.read_bit()?;
+            header.dim_suppress_ext2 = s.main.read_bit()?;
+            header.dim_text_above = s.main.read_bit_short()?;
+            header.dim_zero_suppression = s.main.read_bit_short()?;
+            header.dim_alt_zero_suppression = s.main.read_bit_short()?;
+        }
+
+        if ctx.r2007_plus {
+            header.dim_arc_symbol_position = s.main.read_bit_short()?;
+        }
+
+        header.dim_text_height = s.main.read_bit_double()?;
+        header.dim_center_mark = s.main.read_bit_double()?;
+        header.dim_tick_size = s.main.read_bit_double()?;
+        header.dim_alt_scale = s.main.read_bit_double()?;
+        header.dim_linear_scale = s.main.read_bit_double()?;
+        header.dim_text_vertical_pos = s.main.read_bit_double()?;
+        header.dim_tolerance_scale = s.main.read_bit_double()?;
+        header.dim_line_gap = s.main.read_bit_double()?;
+
+        if ctx.r13_14_only {
+            header.dim_post = s.read_text()?;
+            header.dim_alt_post = s.read_text()?;
+            header.dim_arrow_block = s.read_text()?;
+            header.dim_arrow_block1 = s.read_text()?;
+            header.dim_arrow_block2 = s.read_text()?;
+        }
+
+        if ctx.r2000_plus {
+            header.dim_alt_rounding = s.main.read_bit_double()?;
+            header.dim_alternate_units = s.main.read_bit()?;
+            header.dim_alt_decimal_places = s.main.read_bit_short()?;
+            header.dim_force_line_inside = s.main.read_bit()?;
+            header.dim_separate_arrows = s.main.read_bit()?;
+            header.dim_force_text_inside = s.main.read_bit()?;
+            header.dim_suppress_outside_ext = s.main.read_bit()?;
+        }
+
+        header.dim_line_color = s.main.read_cm_color(false)?;
+        header.dim_ext_line_color = s.main.read_cm_color(false)?;
+        header.dim_text_color = s.main.read_cm_color(false)?;
+
+        if ctx.r2000_plus {
+            header.dim_angular_decimal_places = s.main.read_bit_short()?;
+            header.dim_decimal_places = s.main.read_bit_short()?;
+            header.dim_tolerance_decimal_places = s.main.read_bit_short()?;
+            header.dim_alt_units_format = s.main.read_bit_short()?;
+            header.dim_alt_tolerance_decimal_places = s.main.read_bit_short()?;
+            header.dim_angular_units = s.main.read_bit_short()?;
+            header.dim_fraction_format = s.main.read_bit_short()?;
+            header.dim_linear_unit_format = s.main.read_bit_short()?;
+            header.dim_decimal_separator =
+                char::from_u32(s.main.read_bit_short()? as u16 as u32).unwrap_or('.');
+            header.dim_text_movement = s.main.read_bit_short()?;
+            header.dim_horizontal_justification = s.main.read_bit_short()?;
+            header.dim_suppress_line1 = s.main.read_bit()?;
+            header.dim_suppress_line2 = s.main.read_bit()?;
+            header.dim_tolerance_justification = s.main.read_bit_short()?;
+            header.dim_tolerance_zero_suppression = s.main.read_bit_short()?;
+            header.dim_alt_tolerance_zero_suppression = s.main.read_bit_short()?;
+            header.dim_alt_tolerance_zero_tight = s.main.read_bit_short()?;
+            header.dim_user_positioned_text = s.main.read_bit()?;
+            header.dim_fit = s.main.read_bit_short()?;
+        }
+
+        if ctx.r2007_plus {
+            header.dim_fixed_ext_line_enabled = s.main.read_bit()?;
+        }
+
+        if ctx.r2010_plus {
+            header.dim_text_direction = s.main.read_bit()?;
+            s.main.read_bit_double()?; // DIMALTMZF
+            s.read_text()?; // DIMALTMZS
+            s.main.read_bit_double()?; // DIMMZF
+            s.read_text()?; // DIMMZS
+        }
+
+        if ctx.r2000_plus {
+            let dimtxsty = s.read_handle()?;
+            header.dim_text_style_handle = Handle::new(dimtxsty);
+            pointers.set(handle_names::DIMTXSTY, dimtxsty);
+            pointers.set(handle_names::DIMLDRBLK, s.read_handle()?);
+            header.dim_arrow_block_handle = Handle::new(s.read_handle()?);
+            header.dim_arrow_block1_handle = Handle::new(s.read_handle()?);
+            header.dim_arrow_block2_handle = Handle::new(s.read_handle()?);
+            pointers.set(handle_names::DIMBLK, header.dim_arrow_block_handle.value());
+            pointers.set(handle_names::DIMBLK1, header.dim_arrow_block1_handle.value());
+            pointers.set(handle_names::DIMBLK2, header.dim_arrow_block2_handle.value());
+        }
+
+        if ctx.r2007_plus {
+            header.dim_linetype_handle = Handle::new(s.read_handle()?);
+            header.dim_linetype1_handle = Handle::new(s.read_handle()?);
+            header.dim_linetype2_handle = Handle::new(s.read_handle()?);
+            pointers.set(handle_names::DIMLTYPE, header.dim_linetype_handle.value());
+            pointers.set(handle_names::DIMLTEX1, header.dim_linetype1_handle.value());
+            pointers.set(handle_names::DIMLTEX2, header.dim_linetype2_handle.value());
+        }
+
+        if ctx.r2000_plus {
+            header.dim_line_weight = s.main.read_bit_short()?;
+            header.dim_ext_line_weight = s.main.read_bit_short()?;
         }
 
-        if Self::r2000_plus(version) {
-            header.set("hyperlinkbase", DwgHeaderValue::Text(reader.read_variable_text()?));
-            header.set("stylesheet", DwgHeaderValue::Text(reader.read_variable_text()?));
+        // Table control objects
+        header.block_control_handle = Handle::new(s.read_handle()?);
+        header.layer_control_handle = Handle::new(s.read_handle()?);
+        header.style_control_handle = Handle::new(s.read_handle()?);
+        header.linetype_control_handle = Handle::new(s.read_handle()?);
+        header.view_control_handle = Handle::new(s.read_handle()?);
+        header.ucs_control_handle = Handle::new(s.read_handle()?);
+        header.vport_control_handle = Handle::new(s.read_handle()?);
+        header.appid_control_handle = Handle::new(s.read_handle()?);
+        header.dimstyle_control_handle = Handle::new(s.read_handle()?);
+
+        pointers.set(handle_names::BLOCK_CONTROL_OBJECT, header.block_control_handle.value());
+        pointers.set(handle_names::LAYER_CONTROL_OBJECT, header.layer_control_handle.value());
+        pointers.set(handle_names::STYLE_CONTROL_OBJECT, header.style_control_handle.value());
+        pointers.set(handle_names::LINETYPE_CONTROL_OBJECT, header.linetype_control_handle.value());
+        pointers.set(handle_names::VIEW_CONTROL_OBJECT, header.view_control_handle.value());
+        pointers.set(handle_names::UCS_CONTROL_OBJECT, header.ucs_control_handle.value());
+        pointers.set(handle_names::VPORT_CONTROL_OBJECT, header.vport_control_handle.value());
+        pointers.set(handle_names::APPID_CONTROL_OBJECT, header.appid_control_handle.value());
+        pointers.set(handle_names::DIMSTYLE_CONTROL_OBJECT, header.dimstyle_control_handle.value());
+
+        if ctx.r13_15_only {
+            header.vpent_hdr_control_handle = Handle::new(s.read_handle()?);
+            pointers.set(handle_names::VIEWPORT_ENTITY_HEADER_CONTROL_OBJECT, header.vpent_hdr_control_handle.value());
+        }
 
-            pointers.set("DICTIONARY_LAYOUTS", reader.handle_reference()?);
-            pointers.set("DICTIONARY_PLOTSETTINGS", reader.handle_reference()?);
-            pointers.set("DICTIONARY_PLOTSTYLES", reader.handle_reference()?);
+        header.acad_group_dict_handle = Handle::new(s.read_handle()?);
+        header.acad_mlinestyle_dict_handle = Handle::new(s.read_handle()?);
+        header.named_objects_dict_handle = Handle::new(s.read_handle()?);
+        pointers.set(handle_names::DICTIONARY_ACAD_GROUP, header.acad_group_dict_handle.value());
+        pointers.set(handle_names::DICTIONARY_ACAD_MLINESTYLE, header.acad_mlinestyle_dict_handle.value());
+        pointers.set(handle_names::DICTIONARY_NAMED_OBJECTS, header.named_objects_dict_handle.value());
+
+        if ctx.r2000_plus {
+            s.main.read_bit_short()?; // TSTACKALIGN
+            s.main.read_bit_short()?; // TSTACKSIZE
+            header.hyperlink_base = s.read_text()?;
+            header.stylesheet = s.read_text()?;
+
+            header.acad_layout_dict_handle = Handle::new(s.read_handle()?);
+            header.acad_plotsettings_dict_handle = Handle::new(s.read_handle()?);
+            header.acad_plotstylename_dict_handle = Handle::new(s.read_handle()?);
+            pointers.set(handle_names::DICTIONARY_LAYOUTS, header.acad_layout_dict_handle.value());
+            pointers.set(handle_names::DICTIONARY_PLOTSETTINGS, header.acad_plotsettings_dict_handle.value());
+            pointers.set(handle_names::DICTIONARY_PLOTSTYLES, header.acad_plotstylename_dict_handle.value());
         }
 
-        if Self::r2004_plus(version) {
-            pointers.set("DICTIONARY_MATERIALS", reader.handle_reference()?);
-            pointers.set("DICTIONARY_COLORS", reader.handle_reference()?);
+        if ctx.r2004_plus {
+            header.acad_material_dict_handle = Handle::new(s.read_handle()?);
+            header.acad_color_dict_handle = Handle::new(s.read_handle()?);
+            pointers.set(handle_names::DICTIONARY_MATERIALS, header.acad_material_dict_handle.value());
+            pointers.set(handle_names::DICTIONARY_COLORS, header.acad_color_dict_handle.value());
         }
 
-        if Self::r2007_plus(version) {
-            pointers.set("DICTIONARY_VISUALSTYLE", reader.handle_reference()?);
-            if Self::r2013_plus(version) {
-                let _ = reader.handle_reference()?;
+        if ctx.r2007_plus {
+            header.acad_visualstyle_dict_handle = Handle::new(s.read_handle()?);
+            pointers.set(handle_names::DICTIONARY_VISUALSTYLE, header.acad_visualstyle_dict_handle.value());
+            if ctx.r2013_plus {
+                s.read_handle()?; // Unknown dictionary
             }
         }
 
-        // canonical base objects
-        pointers.set("PAPER_SPACE", reader.handle_reference()?);
-        pointers.set("MODEL_SPACE", reader.handle_reference()?);
-        pointers.set("BYLAYER", reader.handle_reference()?);
-        pointers.set("BYBLOCK", reader.handle_reference()?);
-        pointers.set("CONTINUOUS", reader.handle_reference()?);
+        if ctx.r2000_plus {
+            // Flags BL: CELWEIGHT, ENDCAPS, JOINSTYLE, LWDISPLAY, XEDIT, EXTNAMES, PSTYLEMODE, OLESTARTUP
+            let flags = s.main.read_bit_long()?;
+            header.current_line_weight = line_weight_from_flags(flags & 0x1F);
+            header.end_caps = ((flags >> 5) & 0x3) as i16;
+            header.join_style = ((flags >> 7) & 0x3) as i16;
+            header.lineweight_display = (flags & 0x200) == 0;
+            header.xedit = (flags & 0x400) == 0;
+            header.extended_names = (flags & 0x800) != 0;
+            header.plotstyle_mode = (flags & 0x2000) != 0;
+            header.ole_startup = (flags & 0x4000) != 0;
+
+            header.insertion_units = s.main.read_bit_short()?;
+            header.current_plotstyle_type = s.main.read_bit_short()?;
+
+            if header.current_plotstyle_type == 3 {
+                pointers.set(handle_names::CPSNID, s.read_handle()?);
+            }
+
+            header.fingerprint_guid = s.read_text()?;
+            header.version_guid = s.read_text()?;
+        }
 
-        Ok(())
-    }
+        if ctx.r2004_plus {
+            header.sort_entities = s.main.read_byte()? as i16;
+            header.index_control = s.main.read_byte()? as i16;
+            header.hide_text = s.main.read_byte()? as i16;
+            header.xclip_frame = s.main.read_byte()? as i16;
+            header.dimension_associativity = s.main.read_byte()? as i16;
+            header.halo_gap = s.main.read_byte()? as i16;
+            header.obscured_color = s.main.read_bit_short()?;
+            header.intersection_color = s.main.read_bit_short()?;
+            header.obscured_linetype = s.main.read_byte()? as i16;
+            header.intersection_display = s.main.read_byte()? as i16;
+            header.project_name = s.read_text()?;
+        }
+
+        header.paper_space_block_handle = Handle::new(s.read_handle()?);
+        header.model_space_block_handle = Handle::new(s.read_handle()?);
+        header.bylayer_linetype_handle = Handle::new(s.read_handle()?);
+        header.byblock_linetype_handle = Handle::new(s.read_handle()?);
+        header.continuous_linetype_handle = Handle::new(s.read_handle()?);
+        pointers.set(handle_names::PAPER_SPACE, header.paper_space_block_handle.value());
+        pointers.set(handle_names::MODEL_SPACE, header.model_space_block_handle.value());
+        pointers.set(handle_names::BYLAYER, header.bylayer_linetype_handle.value());
+        pointers.set(handle_names::BYBLOCK, header.byblock_linetype_handle.value());
+        pointers.set(handle_names::CONTINUOUS, header.continuous_linetype_handle.value());
+
+        if ctx.r2007_plus {
+            header.camera_display = s.main.read_bit()?;
+            s.main.read_bit_long()?;
+            s.main.read_bit_long()?;
+            s.main.read_bit_double()?;
+
+            header.steps_per_second = s.main.read_bit_double()?;
+            header.step_size = s.main.read_bit_double()?;
+            header.dwf_precision_3d = s.main.read_bit_double()?;
+            header.lens_length = s.main.read_bit_double()?;
+            header.camera_height = s.main.read_bit_double()?;
+            header.solid_history = s.main.read_byte()? as i16;
+            header.show_history = s.main.read_byte()? as i16;
+            header.polysolid_width = s.main.read_bit_double()?;
+            header.polysolid_height = s.main.read_bit_double()?;
+            header.loft_angle1 = s.main.read_bit_double()?;
+            header.loft_angle2 = s.main.read_bit_double()?;
+            header.loft_magnitude1 = s.main.read_bit_double()?;
+            header.loft_magnitude2 = s.main.read_bit_double()?;
+            header.loft_param = s.main.read_bit_short()?;
+            header.loft_normals = s.main.read_byte()? as i16;
+            header.latitude = s.main.read_bit_double()?;
+            header.longitude = s.main.read_bit_double()?;
+            header.north_direction = s.main.read_bit_double()?;
+            header.timezone = s.main.read_bit_long()?;
+            header.light_glyph_display = s.main.read_byte()? as i16;
+            header.tile_model_light_sync = s.main.read_byte()? as i16;
+            header.dwf_frame = s.main.read_byte()? as i16;
+            header.dgn_frame = s.main.read_byte()? as i16;
+
+            s.main.read_bit()?; // unknown
+
+            header.interference_color = s.main.read_cm_color(false)?;
+
+            let interfereobjvs = s.read_handle()?;
+            header.interference_object_visual_style_handle = Handle::new(interfereobjvs);
+            pointers.set(handle_names::INTERFEREOBJVS, interfereobjvs);
+            let interferevpvs = s.read_handle()?;
+            header.interference_viewport_visual_style_handle = Handle::new(interferevpvs);
+            pointers.set(handle_names::INTERFEREVPVS, interferevpvs);
+            let dragvs = s.read_handle()?;
+            header.drag_visual_style_handle = Handle::new(dragvs);
+            pointers.set(handle_names::DRAGVS, dragvs);
+
+            header.current_shadow_mode = s.main.read_byte()? as i16;
+            header.shadow_plane_location = s.main.read_bit_double()?;
+        }
 
-    #[inline]
-    fn r13_14_only(v: DxfVersion) -> bool {
-        matches!(v, DxfVersion::AC1012 | DxfVersion::AC1014)
+        // The trailing unknown shorts and the CRC are not needed: every
+        // value has been read at this point.
+
+        out.notifications = ctx.notifications;
+        Ok(out)
     }
-    #[inline]
-    fn r2000_plus(v: DxfVersion) -> bool {
-        v >= DxfVersion::AC1015
+}
+
+/// Convert a (julian day, milliseconds) pair into a fractional julian date.
+fn julian_to_f64((day, ms): (i32, i32)) -> f64 {
+    day as f64 + ms as f64 / 86_400_000.0
+}
+
+/// Decode the 5-bit CELWEIGHT value stored in the R2000+ header flags.
+fn line_weight_from_flags(value: i32) -> i16 {
+    match value {
+        0x1F => -1, // ByLayer
+        0x1E => -2, // ByBlock
+        0x1D => -3, // Default
+        v => v as i16,
     }
-    #[inline]
-    fn r2004_plus(v: DxfVersion) -> bool {
-        v >= DxfVersion::AC1018
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::dwg::dwg_stream_writers::dwg_header_writer::DwgHeaderWriter;
+    use crate::types::{Color, Vector3};
+
+    fn sample_header() -> HeaderVariables {
+        HeaderVariables {
+            handle_seed: 0x1234,
+            linetype_scale: 2.5,
+            text_height: 0.75,
+            menu_name: "acad".to_string(),
+            current_entity_color: Color::Index(3),
+            current_layer_handle: Handle::new(0x10),
+            current_text_style_handle: Handle::new(0x11),
+            current_linetype_handle: Handle::new(0x15),
+            current_dimstyle_handle: Handle::new(0x27),
+            model_space_extents_max: Vector3::new(100.0, 50.0, 0.0),
+            dim_scale: 4.0,
+            dim_post: "mm".to_string(),
+            layer_control_handle: Handle::new(0x2),
+            named_objects_dict_handle: Handle::new(0xC),
+            model_space_block_handle: Handle::new(0x1F),
+            paper_space_block_handle: Handle::new(0x1B),
+            continuous_linetype_handle: Handle::new(0x16),
+            ..Default::default()
+        }
     }
-    #[inline]
-    fn r2007_plus(v: DxfVersion) -> bool {
-        v >= DxfVersion::AC1021
+
+    fn round_trip(version: DxfVersion) -> DwgHeaderReadResult {
+        let header = sample_header();
+        let bytes = DwgHeaderWriter::write(version, &header).unwrap();
+        DwgHeaderReader::read(version, version.maintenance_version() as i32, &bytes).unwrap()
     }
-    #[inline]
-    fn r2010_plus(v: DxfVersion) -> bool {
-        v >= DxfVersion::AC1024
+
+    #[test]
+    fn test_round_trip_all_versions() {
+        for version in [
+            DxfVersion::AC1015,
+            DxfVersion::AC1018,
+            DxfVersion::AC1021,
+            DxfVersion::AC1024,
+            DxfVersion::AC1027,
+            DxfVersion::AC1032,
+        ] {
+            let result = round_trip(version);
+            let header = &result.header;
+            assert_eq!(header.handle_seed, 0x1234, "{:?}", version);
+            assert_eq!(header.linetype_scale, 2.5, "{:?}", version);
+            assert_eq!(header.text_height, 0.75, "{:?}", version);
+            assert_eq!(header.menu_name, "acad", "{:?}", version);
+            assert_eq!(header.current_entity_color, Color::Index(3), "{:?}", version);
+            assert_eq!(header.model_space_extents_max, Vector3::new(100.0, 50.0, 0.0));
+            assert_eq!(header.dim_scale, 4.0, "{:?}", version);
+            assert_eq!(header.dim_post, "mm", "{:?}", version);
+            assert_eq!(header.layer_control_handle, Handle::new(0x2), "{:?}", version);
+            assert_eq!(header.continuous_linetype_handle, Handle::new(0x16), "{:?}", version);
+        }
     }
-    #[inline]
-    fn r2013_plus(v: DxfVersion) -> bool {
-        v >= DxfVersion::AC1027
+
+    #[test]
+    fn test_object_pointers_collected() {
+        let result = round_trip(DxfVersion::AC1018);
+        let pointers = &result.object_pointers;
+        assert_eq!(pointers.get(handle_names::CLAYER), Some(0x10));
+        assert_eq!(pointers.get(handle_names::DIMSTYLE), Some(0x27));
+        assert_eq!(pointers.get(handle_names::MODEL_SPACE), Some(0x1F));
+        assert_eq!(pointers.get(handle_names::PAPER_SPACE), Some(0x1B));
+        assert_eq!(pointers.get(handle_names::DICTIONARY_NAMED_OBJECTS), Some(0xC));
     }
-    #[inline]
-    fn r2018_plus(v: DxfVersion) -> bool {
-        v >= DxfVersion::AC1032
+
+    #[test]
+    fn test_julian_to_f64() {
+        assert_eq!(julian_to_f64((2_451_545, 43_200_000)), 2_451_545.5);
     }
 }
@@ -194,7 +194,7 @@ impl DwgLz77Ac21Decompressor {
     //   src[0..4]   → dst[24..28], src[4..8]   → dst[28..32]
     // Remainders use sub-byte copy functions with specific ordering.
 
-    fn copy_reordered(src: &[u8], src_index: u32, dst: &mut [u8], dst_index: u32, mut length: u32) {
+    pub(crate) fn copy_reordered(src: &[u8], src_index: u32, dst: &mut [u8], dst_index: u32, mut length: u32) {
         let mut si = src_index as usize;
         let mut di = dst_index as usize;
 
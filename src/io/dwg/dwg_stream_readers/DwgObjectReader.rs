@@ -1,9 +1,12 @@
 use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::io::Cursor;
+use std::sync::Arc;
 
 use crate::{
-    error::Result,
+    error::{DxfError, Result},
+    io::dwg::crc8_value,
     io::dxf::GroupCodeValueType,
+    notification::{Notification, NotificationType},
     types::{Color, DxfVersion, Transparency, Vector2, Vector3},
 };
 
@@ -12,7 +15,7 @@ use super::{
     idwg_stream_reader::{DwgObjectType, DwgStreamReader},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RawObjectType {
     Text,
     Attrib,
@@ -26,6 +29,7 @@ pub enum RawObjectType {
     Vertex3D,
     VertexPFace,
     VertexMesh,
+    VertexPFaceFace,
     Polyline2D,
     Polyline3D,
     Arc,
@@ -67,6 +71,26 @@ pub enum RawObjectType {
     LwPolyline,
     XRecord,
     Layout,
+    BlockControl,
+    BlockHeader,
+    LayerControl,
+    Layer,
+    TextStyleControl,
+    TextStyle,
+    LineTypeControl,
+    LineType,
+    ViewControl,
+    View,
+    UcsControl,
+    Ucs,
+    VPortControl,
+    VPort,
+    AppIdControl,
+    AppId,
+    DimStyleControl,
+    DimStyle,
+    VpEntHdrControl,
+    VpEntHdr,
     Unknown(u16),
 }
 
@@ -86,6 +110,7 @@ impl RawObjectType {
             0x0B => Self::Vertex3D,
             0x0C => Self::VertexMesh,
             0x0D => Self::VertexPFace,
+            0x0E => Self::VertexPFaceFace,
             0x0F => Self::Polyline2D,
             0x10 => Self::Polyline3D,
             0x11 => Self::Arc,
@@ -112,6 +137,26 @@ impl RawObjectType {
             0x2D => Self::Leader,
             0x2E => Self::Tolerance,
             0x2F => Self::MLine,
+            0x30 => Self::BlockControl,
+            0x31 => Self::BlockHeader,
+            0x32 => Self::LayerControl,
+            0x33 => Self::Layer,
+            0x34 => Self::TextStyleControl,
+            0x35 => Self::TextStyle,
+            0x38 => Self::LineTypeControl,
+            0x39 => Self::LineType,
+            0x3C => Self::ViewControl,
+            0x3D => Self::View,
+            0x3E => Self::UcsControl,
+            0x3F => Self::Ucs,
+            0x40 => Self::VPortControl,
+            0x41 => Self::VPort,
+            0x42 => Self::AppIdControl,
+            0x43 => Self::AppId,
+            0x44 => Self::DimStyleControl,
+            0x45 => Self::DimStyle,
+            0x46 => Self::VpEntHdrControl,
+            0x47 => Self::VpEntHdr,
             0x4A => Self::OLE2Frame,
             0x4B => Self::Dummy,
             0x4C => Self::LongTransaction,
@@ -169,16 +214,22 @@ pub struct DwgRawObject {
     pub point3_props: BTreeMap<String, Vector3>,
     pub handle_props: BTreeMap<String, u64>,
     pub handle_list_props: BTreeMap<String, Vec<u64>>,
+    pub point3_list_props: BTreeMap<String, Vec<Vector3>>,
+    pub float_list_props: BTreeMap<String, Vec<f64>>,
     pub binary_props: BTreeMap<String, Vec<u8>>,
 }
 
 pub struct DwgObjectReader {
     version: DxfVersion,
-    buffer: Vec<u8>,
+    buffer: Arc<[u8]>,
     handles: VecDeque<u64>,
     map: BTreeMap<u64, i64>,
     read_objects: HashSet<u64>,
     classes: BTreeMap<i16, String>,
+    entity_classes: HashSet<i16>,
+    failsafe: bool,
+    crc_check: bool,
+    notifications: Vec<Notification>,
 }
 
 impl DwgObjectReader {
@@ -190,11 +241,15 @@ impl DwgObjectReader {
     ) -> Self {
         Self {
             version,
-            buffer,
+            buffer: Arc::from(buffer),
             handles,
             map: handle_map,
             read_objects: HashSet::new(),
             classes: BTreeMap::new(),
+            entity_classes: HashSet::new(),
+            failsafe: false,
+            crc_check: false,
+            notifications: Vec::new(),
         }
     }
 
@@ -203,6 +258,33 @@ impl DwgObjectReader {
         self
     }
 
+    /// Class numbers whose instances are entities (item class id 0x1F2).
+    pub fn with_entity_classes(mut self, entity_classes: HashSet<i16>) -> Self {
+        self.entity_classes = entity_classes;
+        self
+    }
+
+    /// Report per-object read errors as notifications instead of failing.
+    pub fn with_failsafe(mut self, failsafe: bool) -> Self {
+        self.failsafe = failsafe;
+        self
+    }
+
+    /// Verify the CRC stored after each object record.
+    pub fn with_crc_check(mut self, crc_check: bool) -> Self {
+        self.crc_check = crc_check;
+        self
+    }
+
+    /// Notifications raised while reading (failsafe mode).
+    pub fn take_notifications(&mut self) -> Vec<Notification> {
+        std::mem::take(&mut self.notifications)
+    }
+
+    fn stream(&self) -> DwgStreamReaderBase {
+        DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(Arc::clone(&self.buffer)))
+    }
+
     /// Compatibility helper retained from phase 1.
     pub fn read_one(reader: &mut dyn DwgStreamReader) -> Result<DwgRawObject> {
         let handle = reader.handle_reference()?;
@@ -232,21 +314,181 @@ impl DwgObjectReader {
                 continue;
             };
 
-            let parsed = self.get_entity_type(offset)?;
             self.read_objects.insert(handle);
 
-            if let Some(mut obj) = self.read_object(parsed, handle)? {
-                obj.handle = handle;
-                out.push(obj);
+            if self.crc_check {
+                if let Err(e) = self.verify_object_crc(offset) {
+                    if !self.failsafe {
+                        return Err(e);
+                    }
+                    self.notifications.push(Notification::new(
+                        NotificationType::Error,
+                        format!("Object with handle {:#X} failed its CRC check: {}", handle, e),
+                    ));
+                }
+            }
+
+            let result = self
+                .get_entity_type(offset)
+                .and_then(|parsed| self.read_object(parsed, handle));
+
+            match result {
+                Ok(Some(mut obj)) => {
+                    obj.handle = handle;
+                    out.push(obj);
+                }
+                Ok(None) => {}
+                Err(e) if self.failsafe => {
+                    self.notifications.push(Notification::new(
+                        NotificationType::Error,
+                        format!("Could not read object with handle {:#X}: {}", handle, e),
+                    ));
+                }
+                Err(e) => return Err(e),
             }
         }
 
         Ok(out)
     }
 
+    /// Decode the single object stored under `handle`, independently of the
+    /// traversal queue. Returns `None` when the handle is not in the map or
+    /// its object type is not decoded.
+    pub fn read_handle(&mut self, handle: u64) -> Result<Option<DwgRawObject>> {
+        let Some(offset) = self.map.get(&handle).copied() else {
+            return Ok(None);
+        };
+
+        if self.crc_check {
+            self.verify_object_crc(offset)?;
+        }
+
+        let result = self
+            .get_entity_type(offset)
+            .and_then(|parsed| self.read_object(parsed, handle));
+
+        // Handles referenced by the object are only followed by read().
+        self.handles.clear();
+
+        Ok(result?.map(|mut obj| {
+            obj.handle = handle;
+            obj
+        }))
+    }
+
+    /// Whether the handle map holds an object with the given handle.
+    pub fn contains_handle(&self, handle: u64) -> bool {
+        self.map.contains_key(&handle)
+    }
+
+    /// Handles of every object in the handle map, in ascending order.
+    pub fn map_handles(&self) -> impl Iterator<Item = u64> + '_ {
+        self.map.keys().copied()
+    }
+
+    /// Check the CRC that follows an object record: it covers the size
+    /// prefix (MS, plus the MC handle stream size for R2010+) and the data.
+    fn verify_object_crc(&self, offset: i64) -> Result<()> {
+        let mut reader = self.stream();
+        reader.set_position(offset as u64)?;
+
+        let size = reader.read_modular_short()? as usize;
+        if self.r2010_plus() {
+            reader.read_modular_char()?;
+        }
+
+        let start = offset as usize;
+        let end = reader.position()? as usize + size;
+        let Some(b) = self.buffer.get(end..end + 2) else {
+            return Err(DxfError::InvalidFormat(format!(
+                "Object at offset {} extends beyond the objects section",
+                offset
+            )));
+        };
+
+        let expected = u16::from_le_bytes([b[0], b[1]]) as u64;
+        let actual = crc8_value(0xC0C1, &self.buffer, start, end - start) as u64;
+        if expected != actual {
+            return Err(DxfError::ChecksumMismatch { expected, actual });
+        }
+        Ok(())
+    }
+
+    /// Replace the handle map with one rebuilt by scanning the buffer and
+    /// queue every object found. Returns the number of objects found.
+    pub fn rebuild_handle_map(&mut self) -> usize {
+        self.map = self.scan_handle_map();
+        self.handles.extend(self.map.keys().copied());
+        self.map.len()
+    }
+
+    /// Rebuild a handle map by scanning the buffer for object records,
+    /// used when the handles section is unusable. A record is accepted when
+    /// its CRC matches and its type is a known object type or class; the
+    /// scan then continues after it, or one byte further on a miss.
+    fn scan_handle_map(&self) -> BTreeMap<u64, i64> {
+        let mut map = BTreeMap::new();
+        let mut offset = 0usize;
+
+        while offset + 4 <= self.buffer.len() {
+            match self.scan_object_at(offset) {
+                Some((handle, end)) => {
+                    map.entry(handle).or_insert(offset as i64);
+                    offset = end;
+                }
+                None => offset += 1,
+            }
+        }
+
+        map
+    }
+
+    /// Try to read an object record at `offset`. Returns its handle and the
+    /// offset following its CRC.
+    fn scan_object_at(&self, offset: usize) -> Option<(u64, usize)> {
+        let mut reader = self.stream();
+        reader.set_position(offset as u64).ok()?;
+
+        let size = reader.read_modular_short().ok()?;
+        if size <= 0 {
+            return None;
+        }
+        if self.r2010_plus() {
+            reader.read_modular_char().ok()?;
+        }
+
+        let data_start = reader.position().ok()? as usize;
+        let end = data_start.checked_add(size as usize)?;
+        if end + 2 > self.buffer.len() {
+            return None;
+        }
+
+        let object_type = reader.read_object_type().ok()?;
+        let raw_type = RawObjectType::from_code_for_version(self.version, object_type.0);
+        if matches!(raw_type, RawObjectType::Unknown(_))
+            && !self.classes.contains_key(&(object_type.0 as i16))
+        {
+            return None;
+        }
+
+        let expected = u16::from_le_bytes([self.buffer[end], self.buffer[end + 1]]);
+        if crc8_value(0xC0C1, &self.buffer, offset, end - offset) != expected {
+            return None;
+        }
+
+        if self.version >= DxfVersion::AC1015 && self.version < DxfVersion::AC1024 {
+            reader.read_raw_long().ok()?;
+        }
+        let handle = reader.handle_reference().ok()?;
+        if handle == 0 {
+            return None;
+        }
+
+        Some((handle, end + 2))
+    }
+
     fn get_entity_type(&self, offset: i64) -> Result<ParsedObjectStreams> {
-        let mut crc_reader =
-            DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(self.buffer.clone()));
+        let mut crc_reader = self.stream();
         crc_reader.set_position(offset as u64)?;
 
         let size = crc_reader.read_modular_short()? as u32;
@@ -264,22 +506,15 @@ impl DwgObjectReader {
             let handle_section_offset = crc_reader.position_in_bits()? + size_in_bits as u64 - handle_size;
 
             // Object reader positioned AFTER both modularShort and modularChar
-            let mut object_reader =
-                DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(self.buffer.clone()));
+            let mut object_reader = self.stream();
             object_reader.set_position_in_bits(crc_reader.position_in_bits()?)?;
             let object_initial_pos = object_reader.position_in_bits()?;
             let object_type = object_reader.read_object_type()?;
 
-            let mut handles_reader = DwgStreamReaderBase::get_stream_handler(
-                self.version,
-                Cursor::new(self.buffer.clone()),
-            );
+            let mut handles_reader = self.stream();
             handles_reader.set_position_in_bits(handle_section_offset)?;
 
-            let mut text_reader = DwgStreamReaderBase::get_stream_handler(
-                self.version,
-                Cursor::new(self.buffer.clone()),
-            );
+            let mut text_reader = self.stream();
             let _ = text_reader.set_position_by_flag(handle_section_offset.saturating_sub(1));
 
             Ok(ParsedObjectStreams {
@@ -288,27 +523,24 @@ impl DwgObjectReader {
                 object_type,
                 object_reader,
                 handles_reader,
-                text_reader,
+                text_reader: Some(text_reader),
             })
         } else {
             // Pre-R2010: object reader positioned after modularShort only
-            let mut object_reader =
-                DwgStreamReaderBase::get_stream_handler(self.version, Cursor::new(self.buffer.clone()));
+            let mut object_reader = self.stream();
             object_reader.set_position_in_bits(crc_reader.position_in_bits()?)?;
             let object_initial_pos = object_reader.position_in_bits()?;
             let object_type = object_reader.read_object_type()?;
 
-            let handles_reader = DwgStreamReaderBase::get_stream_handler(
-                self.version,
-                Cursor::new(self.buffer.clone()),
-            );
-            // Pre-R2010: text reader IS the object reader (they share the same stream)
-            // We create a separate reader at the same position as a workaround
-            // since Rust can't alias mutable references
-            let text_reader = DwgStreamReaderBase::get_stream_handler(
-                self.version,
-                Cursor::new(self.buffer.clone()),
-            );
+            let handles_reader = self.stream();
+
+            // R2007 keeps strings in a separate stream located by the
+            // handle reader update; older versions read them inline.
+            let text_reader = if self.version == DxfVersion::AC1021 {
+                Some(self.stream())
+            } else {
+                None
+            };
 
             Ok(ParsedObjectStreams {
                 object_initial_pos,
@@ -401,46 +633,139 @@ impl DwgObjectReader {
             RawObjectType::Leader => {
                 self.read_leader(&mut parsed, &mut template)?;
             }
-            RawObjectType::Block
-            | RawObjectType::EndBlk
+            RawObjectType::Block => {
+                self.read_common_entity_data(&mut parsed, &mut template)?;
+                template
+                    .text_props
+                    .insert("block_name".to_string(), parsed.text_reader().read_variable_text()?);
+            }
+            RawObjectType::Line => {
+                self.read_line(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Point => {
+                self.read_point(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Circle => {
+                self.read_circle(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Arc => {
+                self.read_arc(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Ellipse => {
+                self.read_ellipse(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Ray | RawObjectType::XLine => {
+                self.read_ray_like(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Solid | RawObjectType::Trace => {
+                self.read_solid_like(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Face3D => {
+                self.read_face3d(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Shape => {
+                self.read_shape(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Spline => {
+                self.read_spline(&mut parsed, &mut template)?;
+            }
+            RawObjectType::LwPolyline => {
+                self.read_lw_polyline(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Viewport => {
+                self.read_viewport(&mut parsed, &mut template)?;
+            }
+            RawObjectType::MLine => {
+                self.read_mline(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Tolerance => {
+                self.read_tolerance(&mut parsed, &mut template)?;
+            }
+            RawObjectType::OLE2Frame => {
+                self.read_ole2_frame(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Region | RawObjectType::Solid3D | RawObjectType::Body => {
+                self.read_modeler_geometry(&mut parsed, &mut template)?;
+            }
+            RawObjectType::BlockControl
+            | RawObjectType::LayerControl
+            | RawObjectType::TextStyleControl
+            | RawObjectType::LineTypeControl
+            | RawObjectType::ViewControl
+            | RawObjectType::UcsControl
+            | RawObjectType::VPortControl
+            | RawObjectType::AppIdControl
+            | RawObjectType::DimStyleControl
+            | RawObjectType::VpEntHdrControl => {
+                self.read_table_control(&mut parsed, &mut template, raw_type)?;
+            }
+            RawObjectType::BlockHeader => {
+                self.read_block_header(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Layer => {
+                self.read_layer(&mut parsed, &mut template)?;
+            }
+            RawObjectType::TextStyle => {
+                self.read_text_style(&mut parsed, &mut template)?;
+            }
+            RawObjectType::LineType => {
+                self.read_line_type(&mut parsed, &mut template)?;
+            }
+            RawObjectType::View => {
+                self.read_view(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Ucs => {
+                self.read_ucs(&mut parsed, &mut template)?;
+            }
+            RawObjectType::VPort => {
+                self.read_vport(&mut parsed, &mut template)?;
+            }
+            RawObjectType::AppId => {
+                self.read_app_id(&mut parsed, &mut template)?;
+            }
+            RawObjectType::DimStyle => {
+                self.read_dim_style(&mut parsed, &mut template)?;
+            }
+            RawObjectType::VpEntHdr => {
+                self.read_vp_ent_hdr(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Polyline2D => {
+                self.read_polyline_2d(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Polyline3D => {
+                self.read_polyline_3d(&mut parsed, &mut template)?;
+            }
+            RawObjectType::PolylinePFace => {
+                self.read_polyline_pface(&mut parsed, &mut template)?;
+            }
+            RawObjectType::PolylineMesh => {
+                self.read_polyline_mesh(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Vertex2D => {
+                self.read_vertex_2d(&mut parsed, &mut template)?;
+            }
+            RawObjectType::Vertex3D | RawObjectType::VertexPFace | RawObjectType::VertexMesh => {
+                self.read_vertex_3d(&mut parsed, &mut template)?;
+            }
+            RawObjectType::VertexPFaceFace => {
+                self.read_vertex_pface_face(&mut parsed, &mut template)?;
+            }
+            RawObjectType::EndBlk
             | RawObjectType::SeqEnd
-            | RawObjectType::Vertex2D
-            | RawObjectType::Vertex3D
-            | RawObjectType::VertexPFace
-            | RawObjectType::VertexMesh
-            | RawObjectType::Polyline2D
-            | RawObjectType::Polyline3D
-            | RawObjectType::Arc
-            | RawObjectType::Circle
-            | RawObjectType::Line
-            | RawObjectType::Point
-            | RawObjectType::Face3D
-            | RawObjectType::PolylinePFace
-            | RawObjectType::PolylineMesh
-            | RawObjectType::Solid
-            | RawObjectType::Trace
-            | RawObjectType::Shape
-            | RawObjectType::Viewport
-            | RawObjectType::Ellipse
-            | RawObjectType::Spline
-            | RawObjectType::Region
-            | RawObjectType::Solid3D
-            | RawObjectType::Body
-            | RawObjectType::Ray
-            | RawObjectType::XLine
-            | RawObjectType::Tolerance
-            | RawObjectType::MLine
             | RawObjectType::OLEFrame
-            | RawObjectType::OLE2Frame
             | RawObjectType::Dummy
             | RawObjectType::LongTransaction
-            | RawObjectType::LwPolyline
             | RawObjectType::Layout
             | RawObjectType::Unknown(_) => {
-                if matches!(raw_type, RawObjectType::Dictionary | RawObjectType::Unknown(_)) {
-                    self.read_common_non_entity_data(&mut parsed, &mut template)?;
-                } else {
+                let is_entity = match raw_type {
+                    RawObjectType::Layout | RawObjectType::Dummy | RawObjectType::LongTransaction => false,
+                    RawObjectType::Unknown(code) => self.entity_classes.contains(&(code as i16)),
+                    _ => true,
+                };
+                if is_entity {
                     self.read_common_entity_data(&mut parsed, &mut template)?;
+                } else {
+                    self.read_common_non_entity_data(&mut parsed, &mut template)?;
                 }
             }
         }
@@ -475,7 +800,7 @@ impl DwgObjectReader {
             }
             template
                 .text_props
-                .insert("attdef_prompt".to_string(), parsed.text_reader.read_variable_text()?);
+                .insert("attdef_prompt".to_string(), parsed.text_reader().read_variable_text()?);
         }
 
         Ok(())
@@ -522,7 +847,7 @@ impl DwgObjectReader {
                 .insert("width_factor".to_string(), parsed.object_reader.read_bit_double()?);
             template
                 .text_props
-                .insert("value".to_string(), parsed.text_reader.read_variable_text()?);
+                .insert("value".to_string(), parsed.text_reader().read_variable_text()?);
             template
                 .int_props
                 .insert("mirror".to_string(), parsed.object_reader.read_bit_short()? as i64);
@@ -587,7 +912,7 @@ impl DwgObjectReader {
 
         template
             .text_props
-            .insert("value".to_string(), parsed.text_reader.read_variable_text()?);
+            .insert("value".to_string(), parsed.text_reader().read_variable_text()?);
 
         if (data_flags & 0x20) == 0 {
             template
@@ -633,7 +958,7 @@ impl DwgObjectReader {
 
         template
             .text_props
-            .insert("attribute_tag".to_string(), parsed.text_reader.read_variable_text()?);
+            .insert("attribute_tag".to_string(), parsed.text_reader().read_variable_text()?);
         template
             .int_props
             .insert("attribute_field_length".to_string(), parsed.object_reader.read_bit_short()? as i64);
@@ -807,7 +1132,7 @@ impl DwgObjectReader {
         let mut names = Vec::new();
         let mut handles = Vec::new();
         for _ in 0..nentries {
-            let name = parsed.text_reader.read_variable_text()?;
+            let name = parsed.text_reader().read_variable_text()?;
             let handle = self.handle_reference(parsed, 0)?;
             if handle != 0 && !name.is_empty() {
                 names.push(name);
@@ -880,7 +1205,7 @@ impl DwgObjectReader {
         let _ = parsed.object_reader.read_bit_double()?;
         template
             .text_props
-            .insert("mtext_value".to_string(), parsed.text_reader.read_variable_text()?);
+            .insert("mtext_value".to_string(), parsed.text_reader().read_variable_text()?);
         template
             .handle_props
             .insert("mtext_style_handle".to_string(), self.handle_reference(parsed, 0)?);
@@ -989,6 +1314,9 @@ impl DwgObjectReader {
                 .point3_props
                 .insert("leader_first_vertex".to_string(), first);
         }
+        template
+            .point3_list_props
+            .insert("leader_vertices".to_string(), pts);
 
         let _ = parsed.object_reader.read_3_bit_double()?;
         template
@@ -1185,7 +1513,7 @@ impl DwgObjectReader {
                 .entry("mleader_block_attribute_handles".to_string())
                 .or_default()
                 .push(h);
-            let txt = parsed.text_reader.read_variable_text()?;
+            let txt = parsed.text_reader().read_variable_text()?;
             template
                 .text_props
                 .insert(format!("mleader_block_attribute_text_{i}"), txt);
@@ -1325,7 +1653,7 @@ impl DwgObjectReader {
         // TV 3 Style description
         template.text_props.insert(
             "mleader_style_description".to_string(),
-            parsed.text_reader.read_variable_text()?,
+            parsed.text_reader().read_variable_text()?,
         );
 
         // H 341 Arrow head block handle (hard pointer)
@@ -1342,7 +1670,7 @@ impl DwgObjectReader {
         // TV 300 Text default
         template.text_props.insert(
             "mleader_style_default_text".to_string(),
-            parsed.text_reader.read_variable_text()?,
+            parsed.text_reader().read_variable_text()?,
         );
 
         // H 342 Text style handle (hard pointer)
@@ -1592,7 +1920,7 @@ impl DwgObjectReader {
         if has_text_contents {
             template
                 .text_props
-                .insert("mleader_ctx_text_label".to_string(), parsed.text_reader.read_variable_text()?);
+                .insert("mleader_ctx_text_label".to_string(), parsed.text_reader().read_variable_text()?);
             template
                 .point3_props
                 .insert("mleader_ctx_text_normal".to_string(), parsed.object_reader.read_3_bit_double()?);
@@ -1911,7 +2239,7 @@ impl DwgObjectReader {
                         .insert(format!("xrecord_{code}_{item_index}"), parsed.object_reader.read_text_unicode()?);
                 }
                 GroupCodeValueType::Double => {
-                    if code == 10 {
+                    if (10..=39).contains(&code) {
                         let p = Vector3::new(
                             parsed.object_reader.read_double()?,
                             parsed.object_reader.read_double()?,
@@ -1952,22 +2280,15 @@ impl DwgObjectReader {
                     );
                 }
                 GroupCodeValueType::Handle => {
-                    if code == 330 || code == 1005 {
-                        template
-                            .handle_list_props
-                            .entry("xrecord_handle_refs".to_string())
-                            .or_default()
-                            .push(parsed.object_reader.read_raw_u_long()?);
+                    let value = if code == 330 || code == 1005 {
+                        parsed.object_reader.read_raw_u_long()?
                     } else {
                         let text = parsed.object_reader.read_text_unicode()?;
-                        if let Ok(value) = u64::from_str_radix(text.trim(), 16) {
-                            template
-                                .handle_list_props
-                                .entry("xrecord_handle_refs".to_string())
-                                .or_default()
-                                .push(value);
-                        }
-                    }
+                        u64::from_str_radix(text.trim(), 16).unwrap_or(0)
+                    };
+                    template
+                        .handle_props
+                        .insert(format!("xrecord_{code}_{item_index}"), value);
                 }
                 GroupCodeValueType::Bool => {
                     template.bool_props.insert(
@@ -2060,7 +2381,7 @@ impl DwgObjectReader {
 
             template
                 .text_props
-                .insert("hatch_gradient_name".to_string(), parsed.text_reader.read_variable_text()?);
+                .insert("hatch_gradient_name".to_string(), parsed.text_reader().read_variable_text()?);
         }
 
         template
@@ -2071,7 +2392,7 @@ impl DwgObjectReader {
             .insert("hatch_normal".to_string(), parsed.object_reader.read_3_bit_double()?);
         template
             .text_props
-            .insert("hatch_pattern_name".to_string(), parsed.text_reader.read_variable_text()?);
+            .insert("hatch_pattern_name".to_string(), parsed.text_reader().read_variable_text()?);
         template
             .bool_props
             .insert("hatch_is_solid".to_string(), parsed.object_reader.read_bit()?);
@@ -2243,7 +2564,7 @@ impl DwgObjectReader {
             if self.version > DxfVersion::AC1015 {
                 template
                     .text_props
-                    .insert("proxy_subclass".to_string(), parsed.object_reader.read_variable_text()?);
+                    .insert("proxy_subclass".to_string(), parsed.text_reader().read_variable_text()?);
             }
 
             if self.version < DxfVersion::AC1032 {
@@ -2291,319 +2612,3513 @@ impl DwgObjectReader {
         Ok(())
     }
 
-    fn read_common_data(
-        &mut self,
-        parsed: &mut ParsedObjectStreams,
-        template: &mut DwgRawObject,
-    ) -> Result<()> {
-        if self.version >= DxfVersion::AC1015 && self.version < DxfVersion::AC1024 {
-            self.update_handle_reader(parsed)?;
+    fn read_line(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        if self.r13_14_only() {
+            template
+                .point3_props
+                .insert("start_point".to_string(), parsed.object_reader.read_3_bit_double()?);
+            template
+                .point3_props
+                .insert("end_point".to_string(), parsed.object_reader.read_3_bit_double()?);
+        } else {
+            // Z's are zero bit B
+            let z_is_zero = parsed.object_reader.read_bit()?;
+            let start_x = parsed.object_reader.read_double()?;
+            let end_x = parsed.object_reader.read_bit_double_with_default(start_x)?;
+            let start_y = parsed.object_reader.read_double()?;
+            let end_y = parsed.object_reader.read_bit_double_with_default(start_y)?;
+            let (start_z, end_z) = if z_is_zero {
+                (0.0, 0.0)
+            } else {
+                let start_z = parsed.object_reader.read_double()?;
+                (start_z, parsed.object_reader.read_bit_double_with_default(start_z)?)
+            };
+            template
+                .point3_props
+                .insert("start_point".to_string(), Vector3::new(start_x, start_y, start_z));
+            template
+                .point3_props
+                .insert("end_point".to_string(), Vector3::new(end_x, end_y, end_z));
         }
 
-        template.handle = parsed.object_reader.handle_reference()?;
-        self.read_extended_data(parsed, template)?;
+        template
+            .float_props
+            .insert("thickness".to_string(), parsed.object_reader.read_bit_thickness()?);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_bit_extrusion()?);
         Ok(())
     }
 
-    fn read_common_entity_data(
-        &mut self,
-        parsed: &mut ParsedObjectStreams,
-        template: &mut DwgRawObject,
-    ) -> Result<()> {
-        self.read_common_data(parsed, template)?;
+    fn read_point(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+        template
+            .point3_props
+            .insert("location".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .float_props
+            .insert("thickness".to_string(), parsed.object_reader.read_bit_thickness()?);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_bit_extrusion()?);
+        // X-axis angle BD 50
+        template
+            .float_props
+            .insert("x_axis_angle".to_string(), parsed.object_reader.read_bit_double()?);
+        Ok(())
+    }
 
-        // Graphic present flag.
-        if parsed.object_reader.read_bit()? {
-            let graphic_size = if self.version >= DxfVersion::AC1024 {
-                parsed.object_reader.read_bit_long_long()?
-            } else {
-                parsed.object_reader.read_raw_long()?
-            };
-            if graphic_size > 0 {
-                parsed.object_reader.advance(graphic_size as usize)?;
-            }
-        }
+    fn read_circle(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+        template
+            .point3_props
+            .insert("center".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .float_props
+            .insert("radius".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("thickness".to_string(), parsed.object_reader.read_bit_thickness()?);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_bit_extrusion()?);
+        Ok(())
+    }
 
-        if self.r13_14_only() {
-            self.update_handle_reader(parsed)?;
-        }
+    fn read_arc(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_circle(parsed, template)?;
+        template
+            .float_props
+            .insert("start_angle".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("end_angle".to_string(), parsed.object_reader.read_bit_double()?);
+        Ok(())
+    }
 
-        self.read_entity_mode(parsed, template)
+    fn read_ellipse(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+        template
+            .point3_props
+            .insert("center".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .point3_props
+            .insert("major_axis".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .float_props
+            .insert("axis_ratio".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("start_parameter".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("end_parameter".to_string(), parsed.object_reader.read_bit_double()?);
+        Ok(())
     }
 
-    fn read_entity_mode(
-        &mut self,
-        parsed: &mut ParsedObjectStreams,
-        template: &mut DwgRawObject,
-    ) -> Result<()> {
-        let ent_mode = parsed.object_reader.read_2_bits()?;
+    /// RAY and XLINE share the same layout.
+    fn read_ray_like(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+        template
+            .point3_props
+            .insert("base_point".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .point3_props
+            .insert("direction".to_string(), parsed.object_reader.read_3_bit_double()?);
+        Ok(())
+    }
 
-        if ent_mode == 0 {
-            template.owner_handle = Some(parsed.handles_reader.handle_reference_from(template.handle)?);
+    /// SOLID and TRACE share the same layout.
+    fn read_solid_like(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+        template
+            .float_props
+            .insert("thickness".to_string(), parsed.object_reader.read_bit_thickness()?);
+
+        let elevation = parsed.object_reader.read_bit_double()?;
+        for key in ["first_corner", "second_corner", "third_corner", "fourth_corner"] {
+            let corner = parsed.object_reader.read_2_raw_double()?;
+            template
+                .point3_props
+                .insert(key.to_string(), Vector3::new(corner.x, corner.y, elevation));
         }
 
-        self.read_reactors_and_dictionary_handle(parsed, template)?;
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_bit_extrusion()?);
+        Ok(())
+    }
+
+    fn read_face3d(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
 
         if self.r13_14_only() {
-            let _layer = self.handle_reference(parsed, 0)?;
-            if !parsed.object_reader.read_bit()? {
-                let _line_type = self.handle_reference(parsed, 0)?;
+            for key in ["first_corner", "second_corner", "third_corner", "fourth_corner"] {
+                template
+                    .point3_props
+                    .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
             }
+            template
+                .int_props
+                .insert("invisible_edges".to_string(), parsed.object_reader.read_bit_short()? as i64);
+            return Ok(());
         }
 
-        // R13-R2000 only: previous/next entity handles
-        // Nolinks B - 1 if major links are assumed +1/-1, else 0
+        // Has no flag ind. B / Z is zero bit B
+        let has_no_flags = parsed.object_reader.read_bit()?;
+        let z_is_zero = parsed.object_reader.read_bit()?;
+
+        let x = parsed.object_reader.read_double()?;
+        let y = parsed.object_reader.read_double()?;
+        let z = if z_is_zero { 0.0 } else { parsed.object_reader.read_double()? };
+        let mut previous = Vector3::new(x, y, z);
+        template.point3_props.insert("first_corner".to_string(), previous);
+
+        for key in ["second_corner", "third_corner", "fourth_corner"] {
+            previous = parsed.object_reader.read_3_bit_double_with_default(previous)?;
+            template.point3_props.insert(key.to_string(), previous);
+        }
+
+        if !has_no_flags {
+            template
+                .int_props
+                .insert("invisible_edges".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+        Ok(())
+    }
+
+    fn read_shape(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+        template
+            .point3_props
+            .insert("insert_point".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .float_props
+            .insert("size".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("rotation".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("width_factor".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("oblique_angle".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("thickness".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .int_props
+            .insert("shape_number".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_3_bit_double()?);
+        // H SHAPEFILE (hard pointer)
+        template
+            .handle_props
+            .insert("style_handle".to_string(), self.handle_reference(parsed, 0)?);
+        Ok(())
+    }
+
+    fn read_spline(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        // Scenario BL: 1 = control points / knots, 2 = fit points
+        let mut scenario = parsed.object_reader.read_bit_long()?;
+        if self.r2013_plus() {
+            let spline_flags = parsed.object_reader.read_bit_long()?;
+            let knot_parameter = parsed.object_reader.read_bit_long()?;
+            template
+                .int_props
+                .insert("spline_flags1".to_string(), spline_flags as i64);
+            template
+                .int_props
+                .insert("spline_knot_parameter".to_string(), knot_parameter as i64);
+            if spline_flags & 1 != 0 {
+                scenario = 2;
+            }
+            if knot_parameter == 15 {
+                scenario = 1;
+            }
+        }
+        template
+            .int_props
+            .insert("spline_scenario".to_string(), scenario as i64);
+        template
+            .int_props
+            .insert("degree".to_string(), parsed.object_reader.read_bit_long()? as i64);
+
+        if scenario == 2 {
+            template
+                .float_props
+                .insert("fit_tolerance".to_string(), parsed.object_reader.read_bit_double()?);
+            template
+                .point3_props
+                .insert("start_tangent".to_string(), parsed.object_reader.read_3_bit_double()?);
+            template
+                .point3_props
+                .insert("end_tangent".to_string(), parsed.object_reader.read_3_bit_double()?);
+            let fit_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+            let mut fit_points = Vec::with_capacity(fit_count);
+            for _ in 0..fit_count {
+                fit_points.push(parsed.object_reader.read_3_bit_double()?);
+            }
+            template
+                .point3_list_props
+                .insert("fit_points".to_string(), fit_points);
+            return Ok(());
+        }
+
+        template
+            .bool_props
+            .insert("rational".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .bool_props
+            .insert("closed".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .bool_props
+            .insert("periodic".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .float_props
+            .insert("knot_tolerance".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("control_point_tolerance".to_string(), parsed.object_reader.read_bit_double()?);
+
+        let knot_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+        let control_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+        let weighted = parsed.object_reader.read_bit()?;
+
+        let mut knots = Vec::with_capacity(knot_count);
+        for _ in 0..knot_count {
+            knots.push(parsed.object_reader.read_bit_double()?);
+        }
+
+        let mut control_points = Vec::with_capacity(control_count);
+        let mut weights = Vec::new();
+        for _ in 0..control_count {
+            control_points.push(parsed.object_reader.read_3_bit_double()?);
+            if weighted {
+                weights.push(parsed.object_reader.read_bit_double()?);
+            }
+        }
+
+        template.float_list_props.insert("knots".to_string(), knots);
+        template
+            .point3_list_props
+            .insert("control_points".to_string(), control_points);
+        template.float_list_props.insert("weights".to_string(), weights);
+        Ok(())
+    }
+
+    fn read_lw_polyline(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        let flags = parsed.object_reader.read_bit_short()?;
+        template.int_props.insert("lwpolyline_flags".to_string(), flags as i64);
+
+        if flags & 0x4 != 0 {
+            template
+                .float_props
+                .insert("constant_width".to_string(), parsed.object_reader.read_bit_double()?);
+        }
+        if flags & 0x8 != 0 {
+            template
+                .float_props
+                .insert("elevation".to_string(), parsed.object_reader.read_bit_double()?);
+        }
+        if flags & 0x2 != 0 {
+            template
+                .float_props
+                .insert("thickness".to_string(), parsed.object_reader.read_bit_double()?);
+        }
+        if flags & 0x1 != 0 {
+            template
+                .point3_props
+                .insert("normal".to_string(), parsed.object_reader.read_3_bit_double()?);
+        }
+
+        let point_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+        let bulge_count = if flags & 0x10 != 0 {
+            parsed.object_reader.read_bit_long()?.max(0) as usize
+        } else {
+            0
+        };
+        // R2010+: vertex identifiers
+        let id_count = if self.r2010_plus() && flags & 0x400 != 0 {
+            parsed.object_reader.read_bit_long()?.max(0) as usize
+        } else {
+            0
+        };
+        let width_count = if flags & 0x20 != 0 {
+            parsed.object_reader.read_bit_long()?.max(0) as usize
+        } else {
+            0
+        };
+
+        let mut points = Vec::with_capacity(point_count);
+        for i in 0..point_count {
+            let point = if self.r13_14_only() || i == 0 {
+                parsed.object_reader.read_2_raw_double()?
+            } else {
+                let previous: Vector2 = points[i - 1];
+                parsed.object_reader.read_2_bit_double_with_default(previous)?
+            };
+            points.push(point);
+        }
+
+        let mut bulges = Vec::with_capacity(bulge_count);
+        for _ in 0..bulge_count {
+            bulges.push(parsed.object_reader.read_bit_double()?);
+        }
+
+        for _ in 0..id_count {
+            let _vertex_id = parsed.object_reader.read_bit_long()?;
+        }
+
+        let mut widths = Vec::with_capacity(width_count * 2);
+        for _ in 0..width_count {
+            widths.push(parsed.object_reader.read_bit_double()?);
+            widths.push(parsed.object_reader.read_bit_double()?);
+        }
+
+        template.point3_list_props.insert(
+            "vertices".to_string(),
+            points.iter().map(|p| Vector3::new(p.x, p.y, 0.0)).collect(),
+        );
+        template.float_list_props.insert("bulges".to_string(), bulges);
+        template.float_list_props.insert("widths".to_string(), widths);
+        Ok(())
+    }
+
+    fn read_polyline_2d(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        template
+            .int_props
+            .insert("polyline_flags".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        template
+            .int_props
+            .insert("curve_type".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        template
+            .float_props
+            .insert("start_width".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("end_width".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("thickness".to_string(), parsed.object_reader.read_bit_thickness()?);
+        template
+            .float_props
+            .insert("elevation".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_bit_extrusion()?);
+
+        self.read_polyline_owned(parsed, template)
+    }
+
+    fn read_polyline_3d(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        // Spline flags RC (1 = quadratic, 2 = cubic), Closed flags RC
+        template
+            .int_props
+            .insert("curve_type".to_string(), parsed.object_reader.read_byte()? as i64);
+        template
+            .int_props
+            .insert("polyline_flags".to_string(), parsed.object_reader.read_byte()? as i64);
+
+        self.read_polyline_owned(parsed, template)
+    }
+
+    fn read_polyline_pface(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        template
+            .int_props
+            .insert("vertex_count".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        template
+            .int_props
+            .insert("face_count".to_string(), parsed.object_reader.read_bit_short()? as i64);
+
+        self.read_polyline_owned(parsed, template)
+    }
+
+    fn read_polyline_mesh(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        for key in [
+            "polyline_flags",
+            "curve_type",
+            "m_vertex_count",
+            "n_vertex_count",
+            "m_density",
+            "n_density",
+        ] {
+            template
+                .int_props
+                .insert(key.to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+
+        self.read_polyline_owned(parsed, template)
+    }
+
+    /// Owned vertex handles of a polyline: first / last vertex for
+    /// R13-R2000, the full owned list for R2004+, then the SEQEND.
+    fn read_polyline_owned(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        if self.r2004_plus() {
+            let count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+            let mut handles = Vec::with_capacity(count);
+            for _ in 0..count {
+                handles.push(self.handle_reference(parsed, 0)?);
+            }
+            template
+                .handle_list_props
+                .insert("owned_object_handles".to_string(), handles);
+        } else {
+            template
+                .handle_props
+                .insert("first_vertex_handle".to_string(), self.handle_reference(parsed, 0)?);
+            template
+                .handle_props
+                .insert("last_vertex_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+
+        template
+            .handle_props
+            .insert("seqend_handle".to_string(), self.handle_reference(parsed, 0)?);
+        Ok(())
+    }
+
+    fn read_vertex_2d(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        template
+            .int_props
+            .insert("vertex_flags".to_string(), parsed.object_reader.read_byte()? as i64);
+        template
+            .point3_props
+            .insert("location".to_string(), parsed.object_reader.read_3_bit_double()?);
+
+        // A negative start width means the end width is the same.
+        let start_width = parsed.object_reader.read_bit_double()?;
+        let (start_width, end_width) = if start_width < 0.0 {
+            (-start_width, -start_width)
+        } else {
+            (start_width, parsed.object_reader.read_bit_double()?)
+        };
+        template.float_props.insert("start_width".to_string(), start_width);
+        template.float_props.insert("end_width".to_string(), end_width);
+
+        template
+            .float_props
+            .insert("bulge".to_string(), parsed.object_reader.read_bit_double()?);
+        if self.r2010_plus() {
+            template
+                .int_props
+                .insert("vertex_id".to_string(), parsed.object_reader.read_bit_long()? as i64);
+        }
+        template
+            .float_props
+            .insert("curve_tangent".to_string(), parsed.object_reader.read_bit_double()?);
+        Ok(())
+    }
+
+    /// VERTEX (3D), VERTEX (MESH) and VERTEX (PFACE) share one layout.
+    fn read_vertex_3d(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        template
+            .int_props
+            .insert("vertex_flags".to_string(), parsed.object_reader.read_byte()? as i64);
+        template
+            .point3_props
+            .insert("location".to_string(), parsed.object_reader.read_3_bit_double()?);
+        Ok(())
+    }
+
+    fn read_vertex_pface_face(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        for i in 1..=4 {
+            template
+                .int_props
+                .insert(format!("face_index_{i}"), parsed.object_reader.read_bit_short()? as i64);
+        }
+        Ok(())
+    }
+
+    fn read_viewport(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        template
+            .point3_props
+            .insert("center".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .float_props
+            .insert("width".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("height".to_string(), parsed.object_reader.read_bit_double()?);
+
+        let r2000_plus = self.version >= DxfVersion::AC1015;
+        if r2000_plus {
+            for key in ["view_target", "view_direction"] {
+                template
+                    .point3_props
+                    .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
+            }
+            for key in [
+                "twist_angle",
+                "view_height",
+                "lens_length",
+                "front_clip_z",
+                "back_clip_z",
+                "snap_angle",
+            ] {
+                template
+                    .float_props
+                    .insert(key.to_string(), parsed.object_reader.read_bit_double()?);
+            }
+            for key in ["view_center", "snap_base", "snap_spacing", "grid_spacing"] {
+                template
+                    .point2_props
+                    .insert(key.to_string(), parsed.object_reader.read_2_raw_double()?);
+            }
+            template
+                .int_props
+                .insert("circle_zoom".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+
+        if self.r2007_plus() {
+            template
+                .int_props
+                .insert("grid_major".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+
+        let mut frozen_layer_count = 0;
+        if r2000_plus {
+            frozen_layer_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+            template
+                .int_props
+                .insert("status_flags".to_string(), parsed.object_reader.read_bit_long()? as i64);
+            template
+                .text_props
+                .insert("style_sheet".to_string(), parsed.text_reader().read_variable_text()?);
+            template
+                .int_props
+                .insert("render_mode".to_string(), parsed.object_reader.read_byte()? as i64);
+            template
+                .bool_props
+                .insert("ucs_at_origin".to_string(), parsed.object_reader.read_bit()?);
+            template
+                .bool_props
+                .insert("ucs_per_viewport".to_string(), parsed.object_reader.read_bit()?);
+            for key in ["ucs_origin", "ucs_x_axis", "ucs_y_axis"] {
+                template
+                    .point3_props
+                    .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
+            }
+            template
+                .float_props
+                .insert("elevation".to_string(), parsed.object_reader.read_bit_double()?);
+            template
+                .int_props
+                .insert("ucs_ortho_type".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+
+        if self.r2004_plus() {
+            template
+                .int_props
+                .insert("shade_plot_mode".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+
+        if self.r2007_plus() {
+            template
+                .bool_props
+                .insert("default_lighting".to_string(), parsed.object_reader.read_bit()?);
+            template
+                .int_props
+                .insert("default_lighting_type".to_string(), parsed.object_reader.read_byte()? as i64);
+            template
+                .float_props
+                .insert("brightness".to_string(), parsed.object_reader.read_bit_double()?);
+            template
+                .float_props
+                .insert("contrast".to_string(), parsed.object_reader.read_bit_double()?);
+            let _ambient_color = parsed.object_reader.read_cm_color(false)?;
+        }
+
+        if self.r13_14_only() {
+            let _viewport_entity_header = self.handle_reference(parsed, 0)?;
+        }
+
+        if r2000_plus {
+            let mut frozen_layers = Vec::with_capacity(frozen_layer_count);
+            for _ in 0..frozen_layer_count {
+                frozen_layers.push(self.handle_reference(parsed, 0)?);
+            }
+            template
+                .handle_list_props
+                .insert("frozen_layer_handles".to_string(), frozen_layers);
+            template
+                .handle_props
+                .insert("clip_boundary_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+
+        if self.version == DxfVersion::AC1015 {
+            let _viewport_entity_header = self.handle_reference(parsed, 0)?;
+        }
+
+        if r2000_plus {
+            template
+                .handle_props
+                .insert("named_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+            template
+                .handle_props
+                .insert("base_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+
+        if self.r2007_plus() {
+            for key in [
+                "background_handle",
+                "visual_style_handle",
+                "shade_plot_handle",
+                "sun_handle",
+            ] {
+                template
+                    .handle_props
+                    .insert(key.to_string(), self.handle_reference(parsed, 0)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_mline(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        template
+            .float_props
+            .insert("scale_factor".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .int_props
+            .insert("justification".to_string(), parsed.object_reader.read_byte()? as i64);
+        template
+            .point3_props
+            .insert("start_point".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_bit_extrusion()?);
+        // Open (1) / closed (3)
+        template
+            .int_props
+            .insert("open_closed".to_string(), parsed.object_reader.read_bit_short()? as i64);
+
+        let line_count = parsed.object_reader.read_byte()? as usize;
+        let vertex_count = parsed.object_reader.read_bit_short()?.max(0) as usize;
+        template.int_props.insert("line_count".to_string(), line_count as i64);
+
+        let mut positions = Vec::with_capacity(vertex_count);
+        let mut directions = Vec::with_capacity(vertex_count);
+        let mut miters = Vec::with_capacity(vertex_count);
+        for v in 0..vertex_count {
+            positions.push(parsed.object_reader.read_3_bit_double()?);
+            directions.push(parsed.object_reader.read_3_bit_double()?);
+            miters.push(parsed.object_reader.read_3_bit_double()?);
+
+            for l in 0..line_count {
+                let segment_count = parsed.object_reader.read_bit_short()?.max(0) as usize;
+                let mut parameters = Vec::with_capacity(segment_count);
+                for _ in 0..segment_count {
+                    parameters.push(parsed.object_reader.read_bit_double()?);
+                }
+                let fill_count = parsed.object_reader.read_bit_short()?.max(0) as usize;
+                let mut fill_parameters = Vec::with_capacity(fill_count);
+                for _ in 0..fill_count {
+                    fill_parameters.push(parsed.object_reader.read_bit_double()?);
+                }
+                template
+                    .float_list_props
+                    .insert(format!("vertex_{v}_line_{l}_parameters"), parameters);
+                template
+                    .float_list_props
+                    .insert(format!("vertex_{v}_line_{l}_fill_parameters"), fill_parameters);
+            }
+        }
+        template.point3_list_props.insert("vertices".to_string(), positions);
+        template.point3_list_props.insert("directions".to_string(), directions);
+        template.point3_list_props.insert("miters".to_string(), miters);
+
+        // H MLINESTYLE (hard pointer)
+        template
+            .handle_props
+            .insert("mline_style_handle".to_string(), self.handle_reference(parsed, 0)?);
+        Ok(())
+    }
+
+    fn read_tolerance(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        if self.r13_14_only() {
+            let _unknown = parsed.object_reader.read_bit_short()?;
+            template
+                .float_props
+                .insert("height".to_string(), parsed.object_reader.read_bit_double()?);
+            template
+                .float_props
+                .insert("dimension_gap".to_string(), parsed.object_reader.read_bit_double()?);
+        }
+
+        template
+            .point3_props
+            .insert("insert_point".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .point3_props
+            .insert("direction".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .point3_props
+            .insert("normal".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .text_props
+            .insert("value".to_string(), parsed.text_reader().read_variable_text()?);
+
+        // H DIMSTYLE (hard pointer)
+        template
+            .handle_props
+            .insert("dimstyle_handle".to_string(), self.handle_reference(parsed, 0)?);
+        Ok(())
+    }
+
+    fn read_ole2_frame(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        template
+            .int_props
+            .insert("ole_version".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        if self.version >= DxfVersion::AC1015 {
+            template
+                .int_props
+                .insert("ole_mode".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+        let length = parsed.object_reader.read_bit_long()?.max(0) as usize;
+        template
+            .binary_props
+            .insert("ole_data".to_string(), parsed.object_reader.read_bytes(length)?);
+        if self.version >= DxfVersion::AC1015 {
+            let _unknown = parsed.object_reader.read_byte()?;
+        }
+        Ok(())
+    }
+
+    /// REGION, 3DSOLID and BODY: ACIS modeler data plus wireframe.
+    fn read_modeler_geometry(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_entity_data(parsed, template)?;
+
+        let acis_empty = parsed.object_reader.read_bit()?;
+        if !acis_empty {
+            let _unknown = parsed.object_reader.read_bit()?;
+            let version = parsed.object_reader.read_bit_short()?;
+            template.int_props.insert("acis_version".to_string(), version as i64);
+
+            if version != 1 {
+                // SAB data runs up to the end of the object and is not
+                // length-prefixed; keep it as-is and stop decoding here.
+                let end_bits = parsed.object_initial_pos + parsed.size as u64 * 8;
+                let current = parsed.object_reader.position_in_bits()?;
+                let remaining = (end_bits.saturating_sub(current) / 8) as usize;
+                template
+                    .binary_props
+                    .insert("acis_sab_data".to_string(), parsed.object_reader.read_bytes(remaining)?);
+                return Ok(());
+            }
+
+            let mut sat = Vec::new();
+            loop {
+                let block_size = parsed.object_reader.read_bit_long()?;
+                if block_size <= 0 {
+                    break;
+                }
+                sat.extend(
+                    parsed
+                        .object_reader
+                        .read_bytes(block_size as usize)?
+                        .into_iter()
+                        .map(|c| if c <= 32 { c } else { 159 - c }),
+                );
+            }
+            template
+                .text_props
+                .insert("acis_sat_data".to_string(), String::from_utf8_lossy(&sat).into_owned());
+
+            if parsed.object_reader.read_bit()? {
+                // Wireframe data present
+                if parsed.object_reader.read_bit()? {
+                    template
+                        .point3_props
+                        .insert("point_of_reference".to_string(), parsed.object_reader.read_3_bit_double()?);
+                }
+                let _isolines = parsed.object_reader.read_bit_long()?;
+                if parsed.object_reader.read_bit()? {
+                    let wire_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+                    template.int_props.insert("wire_count".to_string(), wire_count as i64);
+                    for i in 0..wire_count {
+                        self.read_wire(parsed, template, &format!("wire_{i}"))?;
+                    }
+
+                    let silhouette_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+                    template
+                        .int_props
+                        .insert("silhouette_count".to_string(), silhouette_count as i64);
+                    for i in 0..silhouette_count {
+                        let prefix = format!("silhouette_{i}");
+                        template.int_props.insert(
+                            format!("{prefix}_viewport_id"),
+                            parsed.object_reader.read_bit_long()? as i64,
+                        );
+                        for key in ["target", "view_direction", "up_vector"] {
+                            template.point3_props.insert(
+                                format!("{prefix}_{key}"),
+                                parsed.object_reader.read_3_bit_double()?,
+                            );
+                        }
+                        template
+                            .bool_props
+                            .insert(format!("{prefix}_perspective"), parsed.object_reader.read_bit()?);
+                        let wire_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+                        template
+                            .int_props
+                            .insert(format!("{prefix}_wire_count"), wire_count as i64);
+                        for w in 0..wire_count {
+                            self.read_wire(parsed, template, &format!("{prefix}_wire_{w}"))?;
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.r2007_plus() {
+            template
+                .handle_props
+                .insert("history_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        Ok(())
+    }
+
+    fn read_wire(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject, prefix: &str) -> Result<()> {
+        template
+            .int_props
+            .insert(format!("{prefix}_type"), parsed.object_reader.read_byte()? as i64);
+        template.int_props.insert(
+            format!("{prefix}_selection_marker"),
+            parsed.object_reader.read_bit_long()? as i64,
+        );
+        let color = if self.r2004_plus() {
+            parsed.object_reader.read_bit_long()? as i64
+        } else {
+            parsed.object_reader.read_bit_short()? as i64
+        };
+        template.int_props.insert(format!("{prefix}_color"), color);
+        template.int_props.insert(
+            format!("{prefix}_acis_index"),
+            parsed.object_reader.read_bit_long()? as i64,
+        );
+
+        let point_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+        let mut points = Vec::with_capacity(point_count);
+        for _ in 0..point_count {
+            points.push(parsed.object_reader.read_3_bit_double()?);
+        }
+        template.point3_list_props.insert(format!("{prefix}_points"), points);
+
+        let has_transform = parsed.object_reader.read_bit()?;
+        template
+            .bool_props
+            .insert(format!("{prefix}_has_transform"), has_transform);
+        if has_transform {
+            for key in ["x_axis", "y_axis", "z_axis", "translation", "scale"] {
+                template.point3_props.insert(
+                    format!("{prefix}_{key}"),
+                    parsed.object_reader.read_3_bit_double()?,
+                );
+            }
+            for key in ["has_rotation", "has_reflection", "has_shear"] {
+                template
+                    .bool_props
+                    .insert(format!("{prefix}_{key}"), parsed.object_reader.read_bit()?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Table control objects: the list of entries owned by the table.
+    fn read_table_control(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+        kind: RawObjectType,
+    ) -> Result<()> {
+        self.read_common_non_entity_data(parsed, template)?;
+
+        // Numentries BL 70
+        let entry_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+
+        // R2000+: DIMSTYLE_CONTROL has an undocumented count of extra handles
+        let mut extra_count = 0;
+        if kind == RawObjectType::DimStyleControl && self.version >= DxfVersion::AC1015 {
+            extra_count = parsed.object_reader.read_byte()? as usize;
+        }
+
+        let mut entries = Vec::with_capacity(entry_count);
+        for _ in 0..entry_count {
+            let handle = self.handle_reference(parsed, 0)?;
+            if handle != 0 {
+                entries.push(handle);
+            }
+        }
+
+        match kind {
+            // *MODEL_SPACE and *PAPER_SPACE (hard owner)
+            RawObjectType::BlockControl => {
+                template
+                    .handle_props
+                    .insert("model_space_handle".to_string(), self.handle_reference(parsed, 0)?);
+                template
+                    .handle_props
+                    .insert("paper_space_handle".to_string(), self.handle_reference(parsed, 0)?);
+            }
+            // BYLAYER and BYBLOCK (hard owner)
+            RawObjectType::LineTypeControl => {
+                template
+                    .handle_props
+                    .insert("bylayer_handle".to_string(), self.handle_reference(parsed, 0)?);
+                template
+                    .handle_props
+                    .insert("byblock_handle".to_string(), self.handle_reference(parsed, 0)?);
+            }
+            _ => {}
+        }
+
+        for _ in 0..extra_count {
+            let _unknown = self.handle_reference(parsed, 0)?;
+        }
+
+        template
+            .handle_list_props
+            .insert("entry_handles".to_string(), entries);
+        Ok(())
+    }
+
+    /// Common data of every table entry: name, xref flags and the
+    /// external reference block handle.
+    fn read_common_table_data(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+    ) -> Result<()> {
+        self.read_common_non_entity_data(parsed, template)?;
+
+        template
+            .text_props
+            .insert("entry_name".to_string(), parsed.text_reader().read_variable_text()?);
+
+        if self.r2007_plus() {
+            // xrefindex+1 BS 70, bit 0x100 is the xref dependent flag
+            let xref_index = parsed.object_reader.read_bit_short()?;
+            template
+                .bool_props
+                .insert("entry_xref_dependent".to_string(), xref_index & 0x100 != 0);
+        } else {
+            // 64-flag B / xrefindex+1 BS / Xdep B
+            template
+                .bool_props
+                .insert("entry_referenced".to_string(), parsed.object_reader.read_bit()?);
+            let _xref_index = parsed.object_reader.read_bit_short()?;
+            template
+                .bool_props
+                .insert("entry_xref_dependent".to_string(), parsed.object_reader.read_bit()?);
+        }
+
+        // External reference block handle (hard pointer)
+        template
+            .handle_props
+            .insert("xref_block_handle".to_string(), self.handle_reference(parsed, 0)?);
+        Ok(())
+    }
+
+    fn read_block_header(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        template
+            .bool_props
+            .insert("block_anonymous".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .bool_props
+            .insert("block_has_attributes".to_string(), parsed.object_reader.read_bit()?);
+        let is_xref = parsed.object_reader.read_bit()?;
+        let is_overlay = parsed.object_reader.read_bit()?;
+        template.bool_props.insert("block_is_xref".to_string(), is_xref);
+        template.bool_props.insert("block_is_xref_overlay".to_string(), is_overlay);
+
+        if self.version >= DxfVersion::AC1015 {
+            // Loaded Bit B: 0 indicates loaded for an xref
+            template
+                .bool_props
+                .insert("block_xref_unloaded".to_string(), parsed.object_reader.read_bit()?);
+        }
+
+        let mut owned_count = 0;
+        if self.r2004_plus() && !is_xref && !is_overlay {
+            owned_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+        }
+
+        template
+            .point3_props
+            .insert("block_base_point".to_string(), parsed.object_reader.read_3_bit_double()?);
+        template
+            .text_props
+            .insert("block_xref_path".to_string(), parsed.text_reader().read_variable_text()?);
+
+        let mut insert_count = 0;
+        if self.version >= DxfVersion::AC1015 {
+            // Sequence of non-zero RC's terminated by a 0 RC, one per insert handle
+            while parsed.object_reader.read_byte()? != 0 {
+                insert_count += 1;
+            }
+            template
+                .text_props
+                .insert("block_description".to_string(), parsed.text_reader().read_variable_text()?);
+            let preview_size = parsed.object_reader.read_bit_long()?.max(0) as usize;
+            template
+                .binary_props
+                .insert("block_preview".to_string(), parsed.object_reader.read_bytes(preview_size)?);
+        }
+
+        if self.r2007_plus() {
+            template
+                .int_props
+                .insert("block_units".to_string(), parsed.object_reader.read_bit_short()? as i64);
+            template
+                .bool_props
+                .insert("block_explodable".to_string(), parsed.object_reader.read_bit()?);
+            template
+                .bool_props
+                .insert("block_scale_uniformly".to_string(), parsed.object_reader.read_byte()? == 0);
+        }
+
+        template
+            .handle_props
+            .insert("block_entity_handle".to_string(), self.handle_reference(parsed, 0)?);
+
+        if !self.r2004_plus() && !is_xref && !is_overlay {
+            // R13-R2000: first and last entity of the definition (soft pointer)
+            template
+                .handle_props
+                .insert("block_first_entity_handle".to_string(), self.handle_reference(parsed, 0)?);
+            template
+                .handle_props
+                .insert("block_last_entity_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+
+        if self.r2004_plus() {
+            let mut owned = Vec::with_capacity(owned_count);
+            for _ in 0..owned_count {
+                owned.push(self.handle_reference(parsed, 0)?);
+            }
+            template
+                .handle_list_props
+                .insert("block_owned_handles".to_string(), owned);
+        }
+
+        template
+            .handle_props
+            .insert("block_end_handle".to_string(), self.handle_reference(parsed, 0)?);
+
+        if self.version >= DxfVersion::AC1015 {
+            let mut inserts = Vec::with_capacity(insert_count);
+            for _ in 0..insert_count {
+                inserts.push(self.handle_reference(parsed, 0)?);
+            }
+            template
+                .handle_list_props
+                .insert("block_insert_handles".to_string(), inserts);
+            template
+                .handle_props
+                .insert("block_layout_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        Ok(())
+    }
+
+    fn read_layer(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        if self.r13_14_only() {
+            // Frozen B / On B / Frz in new B / Locked B
+            let frozen = parsed.object_reader.read_bit()?;
+            let on = parsed.object_reader.read_bit()?;
+            let _frozen_in_new = parsed.object_reader.read_bit()?;
+            let locked = parsed.object_reader.read_bit()?;
+            template.bool_props.insert("layer_frozen".to_string(), frozen);
+            template.bool_props.insert("layer_off".to_string(), !on);
+            template.bool_props.insert("layer_locked".to_string(), locked);
+        } else {
+            // Flags BS: frozen, off, frozen in new, locked, plot, lineweight (0x03E0)
+            let values = parsed.object_reader.read_bit_short()?;
+            template.bool_props.insert("layer_frozen".to_string(), values & 0x1 != 0);
+            template.bool_props.insert("layer_off".to_string(), values & 0x2 != 0);
+            template.bool_props.insert("layer_locked".to_string(), values & 0x8 != 0);
+            template.bool_props.insert("layer_plot".to_string(), values & 0x10 != 0);
+            template.line_weight = Some((values & 0x3E0) >> 5);
+        }
+
+        template.color = Some(parsed.object_reader.read_cm_color(false)?);
+
+        if self.version >= DxfVersion::AC1015 {
+            template
+                .handle_props
+                .insert("layer_plot_style_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        if self.r2007_plus() {
+            template
+                .handle_props
+                .insert("layer_material_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        template
+            .handle_props
+            .insert("layer_linetype_handle".to_string(), self.handle_reference(parsed, 0)?);
+        if self.r2013_plus() {
+            let _unknown = self.handle_reference(parsed, 0)?;
+        }
+        Ok(())
+    }
+
+    fn read_text_style(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        template
+            .bool_props
+            .insert("style_is_shape_file".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .bool_props
+            .insert("style_vertical".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .float_props
+            .insert("style_height".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("style_width_factor".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("style_oblique_angle".to_string(), parsed.object_reader.read_bit_double()?);
+        // Generation RC 71 (not bit-pair coded)
+        template
+            .int_props
+            .insert("style_generation".to_string(), parsed.object_reader.read_byte()? as i64);
+        template
+            .float_props
+            .insert("style_last_height".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .text_props
+            .insert("style_font_file".to_string(), parsed.text_reader().read_variable_text()?);
+        template
+            .text_props
+            .insert("style_big_font_file".to_string(), parsed.text_reader().read_variable_text()?);
+        Ok(())
+    }
+
+    fn read_line_type(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        template
+            .text_props
+            .insert("linetype_description".to_string(), parsed.text_reader().read_variable_text()?);
+        template
+            .float_props
+            .insert("linetype_pattern_length".to_string(), parsed.object_reader.read_bit_double()?);
+        // Alignment RC 72, always 'A'
+        template
+            .int_props
+            .insert("linetype_alignment".to_string(), parsed.object_reader.read_byte()? as i64);
+
+        let dash_count = parsed.object_reader.read_byte()? as usize;
+        let mut lengths = Vec::with_capacity(dash_count);
+        let mut has_text = false;
+        for i in 0..dash_count {
+            lengths.push(parsed.object_reader.read_bit_double()?);
+            template.int_props.insert(
+                format!("linetype_dash_{i}_shape_number"),
+                parsed.object_reader.read_bit_short()? as i64,
+            );
+            let offset_x = parsed.object_reader.read_double()?;
+            let offset_y = parsed.object_reader.read_double()?;
+            template
+                .point2_props
+                .insert(format!("linetype_dash_{i}_offset"), Vector2::new(offset_x, offset_y));
+            template.float_props.insert(
+                format!("linetype_dash_{i}_scale"),
+                parsed.object_reader.read_bit_double()?,
+            );
+            template.float_props.insert(
+                format!("linetype_dash_{i}_rotation"),
+                parsed.object_reader.read_bit_double()?,
+            );
+            let shape_flag = parsed.object_reader.read_bit_short()?;
+            has_text |= shape_flag & 0x2 != 0;
+            template
+                .int_props
+                .insert(format!("linetype_dash_{i}_shape_flag"), shape_flag as i64);
+        }
+        template
+            .float_list_props
+            .insert("linetype_dash_lengths".to_string(), lengths);
+
+        // Strings area: 256 bytes up to R2004, 512 bytes for R2007+ when text is used
+        if self.version <= DxfVersion::AC1018 {
+            let _text_area = parsed.object_reader.read_bytes(256)?;
+        } else if has_text {
+            let _text_area = parsed.object_reader.read_bytes(512)?;
+        }
+
+        // 340 shapefile for each dash/shape (hard pointer)
+        let mut styles = Vec::with_capacity(dash_count);
+        for _ in 0..dash_count {
+            styles.push(self.handle_reference(parsed, 0)?);
+        }
+        template
+            .handle_list_props
+            .insert("linetype_dash_style_handles".to_string(), styles);
+        Ok(())
+    }
+
+    fn read_view(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        for key in ["view_height", "view_width"] {
+            template
+                .float_props
+                .insert(key.to_string(), parsed.object_reader.read_bit_double()?);
+        }
+        template
+            .point2_props
+            .insert("view_center".to_string(), parsed.object_reader.read_2_raw_double()?);
+        for key in ["view_target", "view_direction"] {
+            template
+                .point3_props
+                .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
+        }
+        for key in ["view_twist_angle", "view_lens_length", "view_front_clip", "view_back_clip"] {
+            template
+                .float_props
+                .insert(key.to_string(), parsed.object_reader.read_bit_double()?);
+        }
+        self.read_view_mode(parsed, template)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            template
+                .int_props
+                .insert("view_render_mode".to_string(), parsed.object_reader.read_byte()? as i64);
+        }
+        if self.r2007_plus() {
+            self.read_view_lighting(parsed, template)?;
+        }
+
+        // Pspace flag B 70
+        template
+            .bool_props
+            .insert("view_paper_space".to_string(), parsed.object_reader.read_bit()?);
+
+        let mut ucs_associated = false;
+        if self.version >= DxfVersion::AC1015 {
+            ucs_associated = parsed.object_reader.read_bit()?;
+            template
+                .bool_props
+                .insert("view_ucs_associated".to_string(), ucs_associated);
+            if ucs_associated {
+                for key in ["ucs_origin", "ucs_x_axis", "ucs_y_axis"] {
+                    template
+                        .point3_props
+                        .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
+                }
+                template
+                    .float_props
+                    .insert("ucs_elevation".to_string(), parsed.object_reader.read_bit_double()?);
+                template
+                    .int_props
+                    .insert("ucs_ortho_type".to_string(), parsed.object_reader.read_bit_short()? as i64);
+            }
+        }
+
+        if self.r2007_plus() {
+            template
+                .bool_props
+                .insert("view_plottable".to_string(), parsed.object_reader.read_bit()?);
+            for key in ["background_handle", "visual_style_handle", "sun_handle"] {
+                template
+                    .handle_props
+                    .insert(key.to_string(), self.handle_reference(parsed, 0)?);
+            }
+        }
+        if ucs_associated {
+            template
+                .handle_props
+                .insert("base_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+            template
+                .handle_props
+                .insert("named_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        if self.r2007_plus() {
+            template
+                .handle_props
+                .insert("live_section_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        Ok(())
+    }
+
+    /// View mode X 71: perspective, front clip, back clip, front clip at eye.
+    fn read_view_mode(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        let mut mode = 0;
+        for bit in [0x1, 0x2, 0x4, 0x10] {
+            if parsed.object_reader.read_bit()? {
+                mode |= bit;
+            }
+        }
+        template.int_props.insert("view_mode".to_string(), mode);
+        Ok(())
+    }
+
+    fn read_view_lighting(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        template
+            .bool_props
+            .insert("default_lighting".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .int_props
+            .insert("default_lighting_type".to_string(), parsed.object_reader.read_byte()? as i64);
+        template
+            .float_props
+            .insert("brightness".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .float_props
+            .insert("contrast".to_string(), parsed.object_reader.read_bit_double()?);
+        let _ambient_color = parsed.object_reader.read_cm_color(false)?;
+        Ok(())
+    }
+
+    fn read_ucs(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        for key in ["ucs_origin", "ucs_x_axis", "ucs_y_axis"] {
+            template
+                .point3_props
+                .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
+        }
+
+        if self.version >= DxfVersion::AC1015 {
+            template
+                .float_props
+                .insert("ucs_elevation".to_string(), parsed.object_reader.read_bit_double()?);
+            template
+                .int_props
+                .insert("ucs_ortho_view_type".to_string(), parsed.object_reader.read_bit_short()? as i64);
+            template
+                .int_props
+                .insert("ucs_ortho_type".to_string(), parsed.object_reader.read_bit_short()? as i64);
+
+            template
+                .handle_props
+                .insert("base_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+            template
+                .handle_props
+                .insert("named_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        Ok(())
+    }
+
+    fn read_vport(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        template
+            .float_props
+            .insert("view_height".to_string(), parsed.object_reader.read_bit_double()?);
+        // Aspect ratio times the view height
+        template
+            .float_props
+            .insert("view_width".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .point2_props
+            .insert("view_center".to_string(), parsed.object_reader.read_2_raw_double()?);
+        for key in ["view_target", "view_direction"] {
+            template
+                .point3_props
+                .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
+        }
+        for key in ["view_twist_angle", "view_lens_length", "view_front_clip", "view_back_clip"] {
+            template
+                .float_props
+                .insert(key.to_string(), parsed.object_reader.read_bit_double()?);
+        }
+        self.read_view_mode(parsed, template)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            template
+                .int_props
+                .insert("view_render_mode".to_string(), parsed.object_reader.read_byte()? as i64);
+        }
+        if self.r2007_plus() {
+            self.read_view_lighting(parsed, template)?;
+        }
+
+        template
+            .point2_props
+            .insert("vport_lower_left".to_string(), parsed.object_reader.read_2_raw_double()?);
+        template
+            .point2_props
+            .insert("vport_upper_right".to_string(), parsed.object_reader.read_2_raw_double()?);
+        template
+            .bool_props
+            .insert("vport_ucs_follow".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .int_props
+            .insert("vport_circle_zoom".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        template
+            .bool_props
+            .insert("vport_fast_zoom".to_string(), parsed.object_reader.read_bit()?);
+        // UCSICON X 74: on, at origin
+        template
+            .bool_props
+            .insert("vport_ucs_icon_on".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .bool_props
+            .insert("vport_ucs_icon_at_origin".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .bool_props
+            .insert("vport_grid_on".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .point2_props
+            .insert("vport_grid_spacing".to_string(), parsed.object_reader.read_2_raw_double()?);
+        template
+            .bool_props
+            .insert("vport_snap_on".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .bool_props
+            .insert("vport_snap_isometric".to_string(), parsed.object_reader.read_bit()?);
+        template
+            .int_props
+            .insert("vport_snap_iso_pair".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        template
+            .float_props
+            .insert("vport_snap_rotation".to_string(), parsed.object_reader.read_bit_double()?);
+        template
+            .point2_props
+            .insert("vport_snap_base".to_string(), parsed.object_reader.read_2_raw_double()?);
+        template
+            .point2_props
+            .insert("vport_snap_spacing".to_string(), parsed.object_reader.read_2_raw_double()?);
+
+        if self.version >= DxfVersion::AC1015 {
+            let _unknown = parsed.object_reader.read_bit()?;
+            template
+                .bool_props
+                .insert("ucs_per_viewport".to_string(), parsed.object_reader.read_bit()?);
+            for key in ["ucs_origin", "ucs_x_axis", "ucs_y_axis"] {
+                template
+                    .point3_props
+                    .insert(key.to_string(), parsed.object_reader.read_3_bit_double()?);
+            }
+            template
+                .float_props
+                .insert("ucs_elevation".to_string(), parsed.object_reader.read_bit_double()?);
+            template
+                .int_props
+                .insert("ucs_ortho_type".to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+
+        if self.r2007_plus() {
+            template
+                .int_props
+                .insert("vport_grid_flags".to_string(), parsed.object_reader.read_bit_short()? as i64);
+            template
+                .int_props
+                .insert("vport_grid_major".to_string(), parsed.object_reader.read_bit_short()? as i64);
+
+            for key in ["background_handle", "visual_style_handle", "sun_handle"] {
+                template
+                    .handle_props
+                    .insert(key.to_string(), self.handle_reference(parsed, 0)?);
+            }
+        }
+
+        if self.version >= DxfVersion::AC1015 {
+            template
+                .handle_props
+                .insert("named_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+            template
+                .handle_props
+                .insert("base_ucs_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+        Ok(())
+    }
+
+    fn read_app_id(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+        // Unknown RC 71: undocumented 71-group
+        let _unknown = parsed.object_reader.read_byte()?;
+        Ok(())
+    }
+
+    fn read_vp_ent_hdr(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+        // 1-flag B: the 1-bit of the 70 group
+        let _flag = parsed.object_reader.read_bit()?;
+        template
+            .handle_props
+            .insert("viewport_handle".to_string(), self.handle_reference(parsed, 0)?);
+        Ok(())
+    }
+
+    fn read_dim_style(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject) -> Result<()> {
+        self.read_common_table_data(parsed, template)?;
+
+        let r2000_plus = self.version >= DxfVersion::AC1015;
+
+        if self.r13_14_only() {
+            self.read_dim_bits(parsed, template, &[
+                "dimtol", "dimlim", "dimtih", "dimtoh", "dimse1", "dimse2", "dimalt", "dimtofl",
+                "dimsah", "dimtix", "dimsoxd",
+            ])?;
+            self.read_dim_bytes(parsed, template, &["dimaltd", "dimzin"])?;
+            self.read_dim_bits(parsed, template, &["dimsd1", "dimsd2"])?;
+            self.read_dim_bytes(parsed, template, &[
+                "dimtolj", "dimjust", "dimfit",
+            ])?;
+            self.read_dim_bits(parsed, template, &["dimupt"])?;
+            self.read_dim_bytes(parsed, template, &["dimtzin", "dimaltz", "dimalttz", "dimtad"])?;
+            self.read_dim_shorts(parsed, template, &[
+                "dimunit", "dimaunit", "dimdec", "dimtdec", "dimaltu", "dimalttd",
+            ])?;
+            self.read_dim_doubles(parsed, template, &[
+                "dimscale", "dimasz", "dimexo", "dimdli", "dimexe", "dimrnd", "dimdle", "dimtp",
+                "dimtm", "dimtxt", "dimcen", "dimtsz", "dimaltf", "dimlfac", "dimtvp", "dimtfac",
+                "dimgap",
+            ])?;
+            self.read_dim_texts(parsed, template, &[
+                "dimpost", "dimapost", "dimblk_name", "dimblk1_name", "dimblk2_name",
+            ])?;
+            self.read_dim_shorts(parsed, template, &["dimclrd", "dimclre", "dimclrt"])?;
+        }
+
+        if r2000_plus {
+            self.read_dim_texts(parsed, template, &["dimpost", "dimapost"])?;
+            self.read_dim_doubles(parsed, template, &[
+                "dimscale", "dimasz", "dimexo", "dimdli", "dimexe", "dimrnd", "dimdle", "dimtp",
+                "dimtm",
+            ])?;
+        }
+
+        if self.r2007_plus() {
+            self.read_dim_doubles(parsed, template, &["dimfxl", "dimjogang"])?;
+            self.read_dim_shorts(parsed, template, &["dimtfill"])?;
+            let _dimtfillclr = parsed.object_reader.read_cm_color(false)?;
+        }
+
+        if r2000_plus {
+            self.read_dim_bits(parsed, template, &[
+                "dimtol", "dimlim", "dimtih", "dimtoh", "dimse1", "dimse2",
+            ])?;
+            self.read_dim_shorts(parsed, template, &["dimtad", "dimzin", "dimazin"])?;
+        }
+
+        if self.r2007_plus() {
+            self.read_dim_shorts(parsed, template, &["dimarcsym"])?;
+        }
+
+        if r2000_plus {
+            self.read_dim_doubles(parsed, template, &[
+                "dimtxt", "dimcen", "dimtsz", "dimaltf", "dimlfac", "dimtvp", "dimtfac", "dimgap",
+                "dimaltrnd",
+            ])?;
+            self.read_dim_bits(parsed, template, &["dimalt"])?;
+            self.read_dim_shorts(parsed, template, &["dimaltd"])?;
+            self.read_dim_bits(parsed, template, &["dimtofl", "dimsah", "dimtix", "dimsoxd"])?;
+            self.read_dim_shorts(parsed, template, &[
+                "dimclrd", "dimclre", "dimclrt", "dimadec", "dimdec", "dimtdec", "dimaltu",
+                "dimalttd", "dimaunit", "dimfrac", "dimlunit", "dimdsep", "dimtmove", "dimjust",
+            ])?;
+            self.read_dim_bits(parsed, template, &["dimsd1", "dimsd2"])?;
+            self.read_dim_shorts(parsed, template, &["dimtolj", "dimtzin", "dimaltz", "dimalttz"])?;
+            self.read_dim_bits(parsed, template, &["dimupt"])?;
+            self.read_dim_shorts(parsed, template, &["dimatfit"])?;
+        }
+
+        if self.r2007_plus() {
+            self.read_dim_bits(parsed, template, &["dimfxlon"])?;
+        }
+
+        if self.r2010_plus() {
+            self.read_dim_bits(parsed, template, &["dimtxtdirection"])?;
+            self.read_dim_doubles(parsed, template, &["dimaltmzf"])?;
+            self.read_dim_texts(parsed, template, &["dimaltmzs"])?;
+            self.read_dim_doubles(parsed, template, &["dimmzf"])?;
+            self.read_dim_texts(parsed, template, &["dimmzs"])?;
+        }
+
+        if r2000_plus {
+            self.read_dim_shorts(parsed, template, &["dimlwd", "dimlwe"])?;
+        }
+
+        // Unknown B 70: sets the 0-bit of the 70 group
+        let _unknown = parsed.object_reader.read_bit()?;
+
+        // 340 shapefile (DIMTXSTY) (hard pointer)
+        template
+            .handle_props
+            .insert("dimtxsty".to_string(), self.handle_reference(parsed, 0)?);
+
+        if r2000_plus {
+            for key in ["dimldrblk", "dimblk", "dimblk1", "dimblk2"] {
+                template
+                    .handle_props
+                    .insert(key.to_string(), self.handle_reference(parsed, 0)?);
+            }
+        }
+
+        if self.r2007_plus() {
+            for key in ["dimltype", "dimltex1", "dimltex2"] {
+                template
+                    .handle_props
+                    .insert(key.to_string(), self.handle_reference(parsed, 0)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_dim_bits(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject, keys: &[&str]) -> Result<()> {
+        for key in keys {
+            template
+                .bool_props
+                .insert(key.to_string(), parsed.object_reader.read_bit()?);
+        }
+        Ok(())
+    }
+
+    fn read_dim_bytes(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject, keys: &[&str]) -> Result<()> {
+        for key in keys {
+            template
+                .int_props
+                .insert(key.to_string(), parsed.object_reader.read_byte()? as i64);
+        }
+        Ok(())
+    }
+
+    fn read_dim_shorts(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject, keys: &[&str]) -> Result<()> {
+        for key in keys {
+            template
+                .int_props
+                .insert(key.to_string(), parsed.object_reader.read_bit_short()? as i64);
+        }
+        Ok(())
+    }
+
+    fn read_dim_doubles(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject, keys: &[&str]) -> Result<()> {
+        for key in keys {
+            template
+                .float_props
+                .insert(key.to_string(), parsed.object_reader.read_bit_double()?);
+        }
+        Ok(())
+    }
+
+    fn read_dim_texts(&mut self, parsed: &mut ParsedObjectStreams, template: &mut DwgRawObject, keys: &[&str]) -> Result<()> {
+        for key in keys {
+            template
+                .text_props
+                .insert(key.to_string(), parsed.text_reader().read_variable_text()?);
+        }
+        Ok(())
+    }
+
+    fn read_common_data(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+    ) -> Result<()> {
+        if self.version >= DxfVersion::AC1015 && self.version < DxfVersion::AC1024 {
+            self.update_handle_reader(parsed)?;
+        }
+
+        template.handle = parsed.object_reader.handle_reference()?;
+        self.read_extended_data(parsed, template)?;
+        Ok(())
+    }
+
+    fn read_common_entity_data(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+    ) -> Result<()> {
+        self.read_common_data(parsed, template)?;
+
+        // Graphic present flag.
+        if parsed.object_reader.read_bit()? {
+            let graphic_size = if self.version >= DxfVersion::AC1024 {
+                parsed.object_reader.read_bit_long_long()?
+            } else {
+                parsed.object_reader.read_raw_long()?
+            };
+            if graphic_size > 0 {
+                let graphics = parsed.object_reader.read_bytes(graphic_size as usize)?;
+                template
+                    .binary_props
+                    .insert("proxy_graphics".to_string(), graphics);
+            }
+        }
+
+        if self.r13_14_only() {
+            self.update_handle_reader(parsed)?;
+        }
+
+        self.read_entity_mode(parsed, template)
+    }
+
+    fn read_entity_mode(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+    ) -> Result<()> {
+        let ent_mode = parsed.object_reader.read_2_bits()?;
+        template
+            .int_props
+            .insert("entity_mode".to_string(), ent_mode as i64);
+
+        if ent_mode == 0 {
+            template.owner_handle = Some(parsed.handles_reader.handle_reference_from(template.handle)?);
+        }
+
+        self.read_reactors_and_dictionary_handle(parsed, template)?;
+
+        if self.r13_14_only() {
+            template
+                .handle_props
+                .insert("layer_handle".to_string(), self.handle_reference(parsed, 0)?);
+            if !parsed.object_reader.read_bit()? {
+                template
+                    .handle_props
+                    .insert("linetype_handle".to_string(), self.handle_reference(parsed, 0)?);
+            }
+        }
+
+        // R13-R2000 only: previous/next entity handles
+        // Nolinks B - 1 if major links are assumed +1/-1, else 0
         // For R2004+ this always has value 1 (links are not used)
         if self.version < DxfVersion::AC1018 {
             let no_links = parsed.object_reader.read_bit()?;
-            if !no_links {
+            let next_entity = if no_links {
+                template.handle + 1
+            } else {
                 // Previous entity (relative soft pointer)
                 let _prev_entity = parsed.handles_reader.handle_reference_from(template.handle)?;
                 // Next entity (relative soft pointer)
-                let _next_entity = parsed.handles_reader.handle_reference_from(template.handle)?;
+                parsed.handles_reader.handle_reference_from(template.handle)?
+            };
+            template
+                .handle_props
+                .insert("next_entity_handle".to_string(), next_entity);
+        }
+
+        let (color, transparency, color_flag) = parsed.object_reader.read_en_color()?;
+        template.color = Some(color);
+        template.transparency = Some(transparency);
+
+        if self.version >= DxfVersion::AC1018 && color_flag {
+            template
+                .handle_props
+                .insert("color_book_handle".to_string(), self.handle_reference(parsed, 0)?);
+        }
+
+        template.line_type_scale = Some(parsed.object_reader.read_bit_double()?);
+
+        // Pre-R2000: read invisibility and return early
+        if self.version < DxfVersion::AC1015 {
+            // Invisibility BS: bit 0 set when the entity is invisible
+            let invis = parsed.object_reader.read_bit_short()?;
+            template.bool_props.insert("invisible".to_string(), (invis & 1) != 0);
+            return Ok(());
+        }
+
+        // R2000+:
+        if self.version >= DxfVersion::AC1015 {
+            template
+                .handle_props
+                .insert("layer_handle".to_string(), self.handle_reference(parsed, 0)?);
+            // 00 = BYLAYER, 01 = BYBLOCK, 10 = CONTINUOUS, 11 = handle follows
+            let ltype_flags = parsed.object_reader.read_2_bits()?;
+            template
+                .int_props
+                .insert("linetype_flags".to_string(), ltype_flags as i64);
+            if ltype_flags == 3 {
+                template
+                    .handle_props
+                    .insert("linetype_handle".to_string(), self.handle_reference(parsed, 0)?);
+            }
+
+            if self.r2007_plus() {
+                let material_flags = parsed.object_reader.read_2_bits()?;
+                if material_flags == 3 {
+                    template
+                        .handle_props
+                        .insert("material_handle".to_string(), self.handle_reference(parsed, 0)?);
+                }
+                let _shadow_flags = parsed.object_reader.read_byte()?;
+            }
+
+            let plotstyle_flags = parsed.object_reader.read_2_bits()?;
+            if plotstyle_flags == 3 {
+                template
+                    .handle_props
+                    .insert("plotstyle_handle".to_string(), self.handle_reference(parsed, 0)?);
+            }
+
+            if self.r2010_plus() {
+                if parsed.object_reader.read_bit()? {
+                    let _ = self.handle_reference(parsed, 0)?;
+                }
+                if parsed.object_reader.read_bit()? {
+                    let _ = self.handle_reference(parsed, 0)?;
+                }
+                if parsed.object_reader.read_bit()? {
+                    let _ = self.handle_reference(parsed, 0)?;
+                }
+            }
+
+            let invis = parsed.object_reader.read_bit_short()?;
+            template.bool_props.insert("invisible".to_string(), (invis & 1) != 0);
+            template.line_weight = Some(parsed.object_reader.read_byte()? as i16);
+        }
+
+        Ok(())
+    }
+
+    fn read_common_non_entity_data(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+    ) -> Result<()> {
+        self.read_common_data(parsed, template)?;
+
+        if self.r13_14_only() {
+            self.update_handle_reader(parsed)?;
+        }
+
+        template.owner_handle = Some(self.handle_reference(parsed, template.handle)?);
+        self.read_reactors_and_dictionary_handle(parsed, template)
+    }
+
+    fn read_extended_data(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+    ) -> Result<()> {
+        let mut size = parsed.object_reader.read_bit_short()?;
+        while size != 0 {
+            let app_handle = parsed.object_reader.handle_reference()?;
+            let end_pos = parsed.object_reader.position()? + size as u64;
+
+            let records = self.read_extended_data_records(parsed, end_pos)?;
+            template.eed.insert(app_handle, records);
+
+            size = parsed.object_reader.read_bit_short()?;
+        }
+        Ok(())
+    }
+
+    fn read_extended_data_records(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        end_pos: u64,
+    ) -> Result<Vec<DwgExtendedDataRecord>> {
+        let mut records = Vec::new();
+
+        while parsed.object_reader.position()? < end_pos {
+            let dxf_code = 1000 + parsed.object_reader.read_byte()? as i32;
+            let mut record = DwgExtendedDataRecord {
+                code: dxf_code,
+                ..Default::default()
+            };
+
+            match dxf_code {
+                1000 | 1001 => {
+                    record.text = Some(parsed.object_reader.read_text_unicode()?);
+                }
+                1002 => {
+                    record.integer = Some(parsed.object_reader.read_byte()? as i64);
+                }
+                1003 | 1005 => {
+                    record.bytes = parsed.object_reader.read_bytes(8)?;
+                }
+                1004 => {
+                    let len = parsed.object_reader.read_byte()? as usize;
+                    record.bytes = parsed.object_reader.read_bytes(len)?;
+                }
+                1010..=1013 => {
+                    record.point = Some(Vector3::new(
+                        parsed.object_reader.read_double()?,
+                        parsed.object_reader.read_double()?,
+                        parsed.object_reader.read_double()?,
+                    ));
+                }
+                1040..=1042 => {
+                    record.number = Some(parsed.object_reader.read_double()?);
+                }
+                1070 => {
+                    record.integer = Some(parsed.object_reader.read_short()? as i64);
+                }
+                1071 => {
+                    record.integer = Some(parsed.object_reader.read_raw_long()?);
+                }
+                _ => {
+                    let remaining = (end_pos.saturating_sub(parsed.object_reader.position()?)) as usize;
+                    let _ = parsed.object_reader.read_bytes(remaining)?;
+                    records.push(record);
+                    break;
+                }
             }
+
+            records.push(record);
         }
 
-        let (color, transparency, color_flag) = parsed.object_reader.read_en_color()?;
-        template.color = Some(color);
-        template.transparency = Some(transparency);
+        Ok(records)
+    }
 
-        if self.version >= DxfVersion::AC1018 && color_flag {
-            let _ = self.handle_reference(parsed, 0)?;
+    fn read_reactors_and_dictionary_handle(
+        &mut self,
+        parsed: &mut ParsedObjectStreams,
+        template: &mut DwgRawObject,
+    ) -> Result<()> {
+        let reactor_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
+        for _ in 0..reactor_count {
+            template.reactors.push(self.handle_reference(parsed, 0)?);
         }
 
-        template.line_type_scale = Some(parsed.object_reader.read_bit_double()?);
+        let mut xdict_missing = false;
+        if self.r2004_plus() {
+            xdict_missing = parsed.object_reader.read_bit()?;
+        }
 
-        // Pre-R2000: read invisibility and return early
-        if self.version < DxfVersion::AC1015 {
-            let _invis = parsed.object_reader.read_bit_short()?;
-            // Note: pre-R2000 invisible when bit is 0, opposite of R2000+
-            return Ok(());
+        if !xdict_missing {
+            template.xdict_handle = Some(self.handle_reference(parsed, 0)?);
         }
 
-        // R2000+:
-        if self.version >= DxfVersion::AC1015 {
-            let _layer = self.handle_reference(parsed, 0)?;
-            let ltype_flags = parsed.object_reader.read_2_bits()?;
-            if ltype_flags == 3 {
-                let _ = self.handle_reference(parsed, 0)?;
+        if self.r2013_plus() {
+            let _has_ds_binary_data = parsed.object_reader.read_bit()?;
+        }
+
+        Ok(())
+    }
+
+    fn update_handle_reader(&self, parsed: &mut ParsedObjectStreams) -> Result<()> {
+        let size = parsed.object_reader.read_raw_long()?;
+        parsed
+            .handles_reader
+            .set_position_in_bits(size as u64 + parsed.object_initial_pos)?;
+
+        if let (DxfVersion::AC1021, Some(text_reader)) = (self.version, parsed.text_reader.as_mut()) {
+            let _ = text_reader.set_position_by_flag(size as u64 + parsed.object_initial_pos - 1);
+        }
+
+        Ok(())
+    }
+
+    fn handle_reference(&mut self, parsed: &mut ParsedObjectStreams, base: u64) -> Result<u64> {
+        let value = parsed.handles_reader.handle_reference_from(base)?;
+        if value != 0 && !self.read_objects.contains(&value) {
+            self.handles.push_back(value);
+        }
+        Ok(value)
+    }
+
+    #[inline]
+    fn r13_14_only(&self) -> bool {
+        matches!(self.version, DxfVersion::AC1012 | DxfVersion::AC1014)
+    }
+    #[inline]
+    fn r2004_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1018
+    }
+    #[inline]
+    fn r2007_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1021
+    }
+    #[inline]
+    fn r2010_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1024
+    }
+    #[inline]
+    fn r2013_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1027
+    }
+}
+
+struct ParsedObjectStreams {
+    object_initial_pos: u64,
+    size: u32,
+    object_type: DwgObjectType,
+    object_reader: DwgStreamReaderBase,
+    handles_reader: DwgStreamReaderBase,
+    /// Separate string stream (R2007+); `None` when strings are inline.
+    text_reader: Option<DwgStreamReaderBase>,
+}
+
+impl ParsedObjectStreams {
+    /// Reader for string data: the text stream for R2007+, the object
+    /// stream itself for older versions.
+    fn text_reader(&mut self) -> &mut DwgStreamReaderBase {
+        match self.text_reader.as_mut() {
+            Some(text_reader) => text_reader,
+            None => &mut self.object_reader,
+        }
+    }
+
+    fn empty() -> Self {
+        let stream = || {
+            DwgStreamReaderBase::get_stream_handler(DxfVersion::Unknown, Cursor::new(Vec::<u8>::new()))
+        };
+        Self {
+            object_initial_pos: 0,
+            size: 0,
+            object_type: DwgObjectType(0),
+            object_reader: stream(),
+            handles_reader: stream(),
+            text_reader: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::dwg::dwg_stream_readers::idwg_stream_reader::DwgReferenceType;
+    use crate::io::dwg::dwg_stream_writers::dwg_object_writer::{write_modular_char, write_modular_short};
+    use crate::io::dwg::dwg_stream_writers::dwg_stream_writer_base::DwgStreamWriterBase;
+    use crate::io::dwg::dwg_stream_writers::idwg_stream_writer::DwgStreamWriter;
+    use std::io::SeekFrom;
+
+    const HANDLE: u64 = 0x40;
+    const LAYER: u64 = 0x10;
+    const OWNER: u64 = 0x20;
+
+    const ALL_VERSIONS: [DxfVersion; 8] = [
+        DxfVersion::AC1012,
+        DxfVersion::AC1014,
+        DxfVersion::AC1015,
+        DxfVersion::AC1018,
+        DxfVersion::AC1021,
+        DxfVersion::AC1024,
+        DxfVersion::AC1027,
+        DxfVersion::AC1032,
+    ];
+
+    /// Encode one entity record as it is laid out in the objects section:
+    /// MS size, [MC handle stream size], type, common entity data for
+    /// `version`, the type-specific `payload` and the CRC.
+    fn encode_entity(
+        version: DxfVersion,
+        object_type: i16,
+        payload: impl FnOnce(&mut dyn DwgStreamWriter) -> Result<()>,
+    ) -> Vec<u8> {
+        let mut w = DwgStreamWriterBase::get_merged_writer(version, Box::new(Cursor::new(Vec::new())), "windows-1252");
+        let r13_14 = version < DxfVersion::AC1015;
+
+        w.write_object_type(object_type).unwrap();
+        if version >= DxfVersion::AC1015 && version < DxfVersion::AC1024 {
+            w.save_position_for_size().unwrap();
+        }
+        w.main_handle_reference(HANDLE).unwrap();
+        // No EED, no graphics
+        w.write_bit_short(0).unwrap();
+        w.write_bit(false).unwrap();
+        if r13_14 {
+            w.save_position_for_size().unwrap();
+        }
+
+        // Model space entity, no reactors, no extension dictionary
+        w.write_2_bits(2).unwrap();
+        w.write_bit_long(0).unwrap();
+        if version >= DxfVersion::AC1018 {
+            w.write_bit(true).unwrap();
+        } else {
+            w.handle_reference_typed(DwgReferenceType::HardOwnership, 0).unwrap();
+        }
+        if version >= DxfVersion::AC1027 {
+            w.write_bit(false).unwrap();
+        }
+        if r13_14 {
+            w.handle_reference_typed(DwgReferenceType::HardPointer, LAYER).unwrap();
+            w.write_bit(true).unwrap();
+        }
+        if version < DxfVersion::AC1018 {
+            w.write_bit(true).unwrap();
+        }
+
+        if version >= DxfVersion::AC1018 {
+            w.write_en_color_book(&Color::Index(3), &Transparency::OPAQUE, false).unwrap();
+        } else {
+            w.write_en_color(&Color::Index(3), &Transparency::OPAQUE).unwrap();
+        }
+        w.write_bit_double(1.5).unwrap();
+
+        if r13_14 {
+            w.write_bit_short(0).unwrap();
+        } else {
+            w.handle_reference_typed(DwgReferenceType::HardPointer, LAYER).unwrap();
+            w.write_2_bits(0).unwrap();
+            if version >= DxfVersion::AC1021 {
+                w.write_2_bits(0).unwrap();
+                w.write_byte(0).unwrap();
+            }
+            w.write_2_bits(0).unwrap();
+            if version >= DxfVersion::AC1024 {
+                w.write_bit(false).unwrap();
+                w.write_bit(false).unwrap();
+                w.write_bit(false).unwrap();
+            }
+            w.write_bit_short(0).unwrap();
+            w.write_byte(0).unwrap();
+        }
+
+        payload(w.as_mut()).unwrap();
+        finish_record(version, w)
+    }
+
+    /// Encode one non-graphical object record: type, common object data
+    /// owned by `OWNER` for `version`, the type-specific `payload` and the
+    /// CRC.
+    fn encode_object(
+        version: DxfVersion,
+        object_type: i16,
+        payload: impl FnOnce(&mut dyn DwgStreamWriter) -> Result<()>,
+    ) -> Vec<u8> {
+        let mut w = DwgStreamWriterBase::get_merged_writer(
+            version,
+            Box::new(Cursor::new(Vec::new())),
+            "windows-1252",
+        );
+
+        w.write_object_type(object_type).unwrap();
+        if version >= DxfVersion::AC1015 && version < DxfVersion::AC1024 {
+            w.save_position_for_size().unwrap();
+        }
+        w.main_handle_reference(HANDLE).unwrap();
+        // No EED
+        w.write_bit_short(0).unwrap();
+        if version < DxfVersion::AC1015 {
+            w.save_position_for_size().unwrap();
+        }
+
+        // Owner, no reactors, no extension dictionary
+        w.handle_reference_typed(DwgReferenceType::SoftPointer, OWNER)
+            .unwrap();
+        w.write_bit_long(0).unwrap();
+        if version >= DxfVersion::AC1018 {
+            w.write_bit(true).unwrap();
+        } else {
+            w.handle_reference_typed(DwgReferenceType::HardOwnership, 0)
+                .unwrap();
+        }
+        if version >= DxfVersion::AC1027 {
+            w.write_bit(false).unwrap();
+        }
+
+        payload(w.as_mut()).unwrap();
+        finish_record(version, w)
+    }
+
+    /// Close the streams of an encoded object and frame them as a record:
+    /// MS size, [MC handle stream size], data and CRC.
+    fn finish_record(version: DxfVersion, mut w: Box<dyn DwgStreamWriter>) -> Vec<u8> {
+        w.write_spear_shift().unwrap();
+        let handle_start_bits = w.saved_position_in_bits();
+        let stream = w.stream();
+        stream.seek(SeekFrom::Start(0)).unwrap();
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).unwrap();
+
+        let mut record = Vec::new();
+        write_modular_short(&mut record, data.len() as u32);
+        if version >= DxfVersion::AC1024 {
+            write_modular_char(&mut record, (data.len() as i64 * 8 - handle_start_bits) as u64);
+        }
+        record.extend_from_slice(&data);
+        let crc = crc8_value(0xC0C1, &record, 0, record.len());
+        record.extend_from_slice(&crc.to_le_bytes());
+        record
+    }
+
+    fn decode(version: DxfVersion, record: Vec<u8>) -> DwgRawObject {
+        let map = BTreeMap::from([(HANDLE, 0)]);
+        let object = DwgObjectReader::new(version, record, VecDeque::new(), map)
+            .with_crc_check(true)
+            .read_handle(HANDLE)
+            .unwrap()
+            .expect("object decoded");
+        assert_eq!(object.color, Some(Color::Index(3)), "{:?}", version);
+        assert_eq!(object.line_type_scale, Some(1.5), "{:?}", version);
+        assert_eq!(object.handle_props.get("layer_handle"), Some(&LAYER), "{:?}", version);
+        assert_eq!(object.int_props.get("entity_mode"), Some(&2), "{:?}", version);
+        object
+    }
+
+    fn decode_object(version: DxfVersion, record: Vec<u8>) -> DwgRawObject {
+        let map = BTreeMap::from([(HANDLE, 0)]);
+        let object = DwgObjectReader::new(version, record, VecDeque::new(), map)
+            .with_crc_check(true)
+            .read_handle(HANDLE)
+            .unwrap()
+            .expect("object decoded");
+        assert_eq!(object.owner_handle, Some(OWNER), "{:?}", version);
+        assert_eq!(
+            object.xdict_handle.is_some(),
+            version < DxfVersion::AC1018,
+            "{:?}",
+            version
+        );
+        object
+    }
+
+    fn roundtrip_object(
+        version: DxfVersion,
+        object_type: i16,
+        payload: impl FnOnce(&mut dyn DwgStreamWriter) -> Result<()>,
+    ) -> DwgRawObject {
+        decode_object(version, encode_object(version, object_type, payload))
+    }
+
+    /// Name, xref flags and xref block handle shared by every table entry
+    fn write_entry_common(
+        w: &mut dyn DwgStreamWriter,
+        version: DxfVersion,
+        name: &str,
+    ) -> Result<()> {
+        w.write_variable_text(name)?;
+        if version >= DxfVersion::AC1021 {
+            w.write_bit_short(0)?;
+        } else {
+            w.write_bit(true)?;
+            w.write_bit_short(0)?;
+            w.write_bit(false)?;
+        }
+        w.handle_reference_typed(DwgReferenceType::HardPointer, 0)
+    }
+
+    fn h(object: &DwgRawObject, key: &str) -> u64 {
+        object.handle_props[key]
+    }
+
+    fn b(object: &DwgRawObject, key: &str) -> bool {
+        object.bool_props[key]
+    }
+
+    fn t<'a>(object: &'a DwgRawObject, key: &str) -> &'a str {
+        &object.text_props[key]
+    }
+
+    fn roundtrip(
+        version: DxfVersion,
+        object_type: i16,
+        payload: impl FnOnce(&mut dyn DwgStreamWriter) -> Result<()>,
+    ) -> DwgRawObject {
+        decode(version, encode_entity(version, object_type, payload))
+    }
+
+    fn p3(object: &DwgRawObject, key: &str) -> Vector3 {
+        object.point3_props[key]
+    }
+
+    fn f(object: &DwgRawObject, key: &str) -> f64 {
+        object.float_props[key]
+    }
+
+    fn i(object: &DwgRawObject, key: &str) -> i64 {
+        object.int_props[key]
+    }
+
+    #[test]
+    fn test_read_line() {
+        let start = Vector3::new(1.0, 2.0, 3.0);
+        let end = Vector3::new(4.0, 5.0, 6.0);
+        for version in ALL_VERSIONS {
+            let line = roundtrip(version, 0x13, |w| {
+                if version < DxfVersion::AC1015 {
+                    // R13-R14: two 3BD
+                    w.write_3_bit_double(&start)?;
+                    w.write_3_bit_double(&end)?;
+                } else {
+                    // R2000+: Z's are zero bit, RD start, DD end per axis
+                    w.write_bit(false)?;
+                    w.write_raw_double(start.x)?;
+                    w.write_bit_double_with_default(start.x, end.x)?;
+                    w.write_raw_double(start.y)?;
+                    w.write_bit_double_with_default(start.y, end.y)?;
+                    w.write_raw_double(start.z)?;
+                    w.write_bit_double_with_default(start.z, end.z)?;
+                }
+                w.write_bit_thickness(0.5)?;
+                w.write_bit_extrusion(&Vector3::new(0.0, 0.0, 1.0))
+            });
+            assert_eq!(line.raw_type, Some(RawObjectType::Line));
+            assert_eq!(p3(&line, "start_point"), start, "{:?}", version);
+            assert_eq!(p3(&line, "end_point"), end, "{:?}", version);
+            assert_eq!(f(&line, "thickness"), 0.5, "{:?}", version);
+            assert_eq!(p3(&line, "normal"), Vector3::new(0.0, 0.0, 1.0), "{:?}", version);
+        }
+
+        // R2000+: flat line, the end X equal to the start X
+        let line = roundtrip(DxfVersion::AC1018, 0x13, |w| {
+            w.write_bit(true)?;
+            w.write_raw_double(7.0)?;
+            w.write_bit_double_with_default(7.0, 7.0)?;
+            w.write_raw_double(1.0)?;
+            w.write_bit_double_with_default(1.0, 9.0)?;
+            w.write_bit_thickness(0.0)?;
+            w.write_bit_extrusion(&Vector3::new(0.0, 0.0, 1.0))
+        });
+        assert_eq!(p3(&line, "start_point"), Vector3::new(7.0, 1.0, 0.0));
+        assert_eq!(p3(&line, "end_point"), Vector3::new(7.0, 9.0, 0.0));
+    }
+
+    #[test]
+    fn test_read_point_circle_arc() {
+        for version in ALL_VERSIONS {
+            let point = roundtrip(version, 0x1B, |w| {
+                w.write_3_bit_double(&Vector3::new(1.0, 2.0, 3.0))?;
+                w.write_bit_thickness(0.25)?;
+                w.write_bit_extrusion(&Vector3::new(0.0, 0.0, 1.0))?;
+                w.write_bit_double(0.5)
+            });
+            assert_eq!(p3(&point, "location"), Vector3::new(1.0, 2.0, 3.0), "{:?}", version);
+            assert_eq!(f(&point, "thickness"), 0.25);
+            assert_eq!(f(&point, "x_axis_angle"), 0.5);
+
+            let circle = roundtrip(version, 0x12, |w| {
+                w.write_3_bit_double(&Vector3::new(5.0, 5.0, 0.0))?;
+                w.write_bit_double(2.5)?;
+                w.write_bit_thickness(0.0)?;
+                w.write_bit_extrusion(&Vector3::new(0.0, 1.0, 0.0))
+            });
+            assert_eq!(p3(&circle, "center"), Vector3::new(5.0, 5.0, 0.0), "{:?}", version);
+            assert_eq!(f(&circle, "radius"), 2.5);
+            assert_eq!(p3(&circle, "normal"), Vector3::new(0.0, 1.0, 0.0));
+
+            let arc = roundtrip(version, 0x11, |w| {
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 0.0))?;
+                w.write_bit_double(1.0)?;
+                w.write_bit_thickness(0.0)?;
+                w.write_bit_extrusion(&Vector3::new(0.0, 0.0, 1.0))?;
+                w.write_bit_double(0.25)?;
+                w.write_bit_double(1.75)
+            });
+            assert_eq!(f(&arc, "radius"), 1.0, "{:?}", version);
+            assert_eq!(f(&arc, "start_angle"), 0.25);
+            assert_eq!(f(&arc, "end_angle"), 1.75);
+        }
+    }
+
+    #[test]
+    fn test_read_ellipse_ray_xline() {
+        for version in ALL_VERSIONS {
+            let ellipse = roundtrip(version, 0x23, |w| {
+                w.write_3_bit_double(&Vector3::new(1.0, 1.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(4.0, 0.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 1.0))?;
+                w.write_bit_double(0.5)?;
+                w.write_bit_double(0.0)?;
+                w.write_bit_double(std::f64::consts::PI)
+            });
+            assert_eq!(p3(&ellipse, "major_axis"), Vector3::new(4.0, 0.0, 0.0), "{:?}", version);
+            assert_eq!(f(&ellipse, "axis_ratio"), 0.5);
+            assert_eq!(f(&ellipse, "end_parameter"), std::f64::consts::PI);
+
+            for (code, raw_type) in [(0x28, RawObjectType::Ray), (0x29, RawObjectType::XLine)] {
+                let ray = roundtrip(version, code, |w| {
+                    w.write_3_bit_double(&Vector3::new(1.0, 2.0, 0.0))?;
+                    w.write_3_bit_double(&Vector3::new(0.0, 1.0, 0.0))
+                });
+                assert_eq!(ray.raw_type, Some(raw_type));
+                assert_eq!(p3(&ray, "base_point"), Vector3::new(1.0, 2.0, 0.0), "{:?}", version);
+                assert_eq!(p3(&ray, "direction"), Vector3::new(0.0, 1.0, 0.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_solid_and_trace() {
+        for version in ALL_VERSIONS {
+            for (code, raw_type) in [(0x1F, RawObjectType::Solid), (0x20, RawObjectType::Trace)] {
+                let solid = roundtrip(version, code, |w| {
+                    w.write_bit_thickness(1.0)?;
+                    // Elevation BD, then four 2RD corners
+                    w.write_bit_double(3.0)?;
+                    for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)] {
+                        w.write_2_raw_double(&Vector2::new(x, y))?;
+                    }
+                    w.write_bit_extrusion(&Vector3::new(0.0, 0.0, 1.0))
+                });
+                assert_eq!(solid.raw_type, Some(raw_type));
+                assert_eq!(f(&solid, "thickness"), 1.0, "{:?}", version);
+                assert_eq!(p3(&solid, "second_corner"), Vector3::new(1.0, 0.0, 3.0));
+                assert_eq!(p3(&solid, "fourth_corner"), Vector3::new(1.0, 1.0, 3.0));
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_face3d() {
+        let corners = [
+            Vector3::new(0.0, 0.0, 1.0),
+            Vector3::new(2.0, 0.0, 1.0),
+            Vector3::new(2.0, 2.0, 1.0),
+            Vector3::new(0.0, 2.0, 1.0),
+        ];
+        for version in ALL_VERSIONS {
+            let face = roundtrip(version, 0x1C, |w| {
+                if version < DxfVersion::AC1015 {
+                    // R13-R14: four 3BD and the invisible edge flags
+                    for corner in &corners {
+                        w.write_3_bit_double(corner)?;
+                    }
+                    return w.write_bit_short(5);
+                }
+                // R2000+: has no flag ind., Z is zero, RD first corner,
+                // DD corners against the previous one, flags
+                w.write_bit(false)?;
+                w.write_bit(false)?;
+                w.write_raw_double(corners[0].x)?;
+                w.write_raw_double(corners[0].y)?;
+                w.write_raw_double(corners[0].z)?;
+                for pair in corners.windows(2) {
+                    w.write_3_bit_double_with_default(&pair[0], &pair[1])?;
+                }
+                w.write_bit_short(5)
+            });
+            assert_eq!(p3(&face, "first_corner"), corners[0], "{:?}", version);
+            assert_eq!(p3(&face, "third_corner"), corners[2], "{:?}", version);
+            assert_eq!(p3(&face, "fourth_corner"), corners[3], "{:?}", version);
+            assert_eq!(i(&face, "invisible_edges"), 5, "{:?}", version);
+        }
+
+        // R2000+: no flags and a flat first corner
+        let face = roundtrip(DxfVersion::AC1024, 0x1C, |w| {
+            w.write_bit(true)?;
+            w.write_bit(true)?;
+            w.write_raw_double(1.0)?;
+            w.write_raw_double(1.0)?;
+            let first = Vector3::new(1.0, 1.0, 0.0);
+            w.write_3_bit_double_with_default(&first, &first)?;
+            w.write_3_bit_double_with_default(&first, &Vector3::new(3.0, 1.0, 0.0))?;
+            w.write_3_bit_double_with_default(&Vector3::new(3.0, 1.0, 0.0), &Vector3::new(3.0, 4.0, 0.0))
+        });
+        assert_eq!(p3(&face, "first_corner"), Vector3::new(1.0, 1.0, 0.0));
+        assert_eq!(p3(&face, "fourth_corner"), Vector3::new(3.0, 4.0, 0.0));
+        assert!(!face.int_props.contains_key("invisible_edges"));
+    }
+
+    #[test]
+    fn test_read_shape() {
+        for version in ALL_VERSIONS {
+            let shape = roundtrip(version, 0x21, |w| {
+                w.write_3_bit_double(&Vector3::new(1.0, 2.0, 0.0))?;
+                w.write_bit_double(2.0)?;
+                w.write_bit_double(0.5)?;
+                w.write_bit_double(1.0)?;
+                w.write_bit_double(0.0)?;
+                w.write_bit_double(0.0)?;
+                w.write_bit_short(42)?;
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 1.0))?;
+                w.handle_reference_typed(DwgReferenceType::HardPointer, 0x20)
+            });
+            assert_eq!(p3(&shape, "insert_point"), Vector3::new(1.0, 2.0, 0.0), "{:?}", version);
+            assert_eq!(f(&shape, "size"), 2.0);
+            assert_eq!(f(&shape, "rotation"), 0.5);
+            assert_eq!(i(&shape, "shape_number"), 42);
+            assert_eq!(shape.handle_props.get("style_handle"), Some(&0x20), "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_read_spline() {
+        // Control points and knots, before R2013
+        for version in &ALL_VERSIONS[..6] {
+            let spline = roundtrip(*version, 0x24, |w| {
+                w.write_bit_long(1)?;
+                w.write_bit_long(1)?;
+                // Rational, closed, periodic
+                w.write_bit(true)?;
+                w.write_bit(false)?;
+                w.write_bit(false)?;
+                w.write_bit_double(1e-7)?;
+                w.write_bit_double(1e-6)?;
+                w.write_bit_long(4)?;
+                w.write_bit_long(2)?;
+                w.write_bit(true)?;
+                for knot in [0.0, 0.0, 1.0, 1.0] {
+                    w.write_bit_double(knot)?;
+                }
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 0.0))?;
+                w.write_bit_double(1.0)?;
+                w.write_3_bit_double(&Vector3::new(5.0, 0.0, 0.0))?;
+                w.write_bit_double(0.5)
+            });
+            assert_eq!(i(&spline, "spline_scenario"), 1, "{:?}", version);
+            assert_eq!(i(&spline, "degree"), 1);
+            assert!(spline.bool_props["rational"]);
+            assert_eq!(spline.float_list_props["knots"], [0.0, 0.0, 1.0, 1.0]);
+            assert_eq!(spline.float_list_props["weights"], [1.0, 0.5]);
+            assert_eq!(spline.point3_list_props["control_points"][1], Vector3::new(5.0, 0.0, 0.0));
+            assert!(!spline.int_props.contains_key("spline_flags1"));
+        }
+
+        // R2013+: the spline flags select fit point data
+        for version in &ALL_VERSIONS[6..] {
+            let spline = roundtrip(*version, 0x24, |w| {
+                w.write_bit_long(1)?;
+                w.write_bit_long(1)?;
+                w.write_bit_long(0)?;
+                w.write_bit_long(3)?;
+                w.write_bit_double(0.01)?;
+                w.write_3_bit_double(&Vector3::new(1.0, 0.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(0.0, 1.0, 0.0))?;
+                w.write_bit_long(2)?;
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(3.0, 3.0, 0.0))
+            });
+            assert_eq!(i(&spline, "spline_flags1"), 1, "{:?}", version);
+            assert_eq!(i(&spline, "spline_scenario"), 2);
+            assert_eq!(i(&spline, "degree"), 3);
+            assert_eq!(f(&spline, "fit_tolerance"), 0.01);
+            assert_eq!(spline.point3_list_props["fit_points"][1], Vector3::new(3.0, 3.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_read_lw_polyline() {
+        // R14: raw points, constant width and bulges
+        let lwpoly = roundtrip(DxfVersion::AC1014, 0x4D, |w| {
+            w.write_bit_short(0x14)?;
+            w.write_bit_double(0.25)?;
+            w.write_bit_long(2)?;
+            w.write_bit_long(2)?;
+            w.write_2_raw_double(&Vector2::new(0.0, 0.0))?;
+            w.write_2_raw_double(&Vector2::new(4.0, 0.0))?;
+            w.write_bit_double(0.5)?;
+            w.write_bit_double(0.0)
+        });
+        assert_eq!(f(&lwpoly, "constant_width"), 0.25);
+        assert_eq!(lwpoly.point3_list_props["vertices"][1], Vector3::new(4.0, 0.0, 0.0));
+        assert_eq!(lwpoly.float_list_props["bulges"], [0.5, 0.0]);
+
+        // Flag 0x400 only adds vertex identifiers from R2010 on
+        for version in &ALL_VERSIONS[2..] {
+            let lwpoly = roundtrip(*version, 0x4D, |w| {
+                w.write_bit_short(0x420)?;
+                w.write_bit_long(2)?;
+                if *version >= DxfVersion::AC1024 {
+                    w.write_bit_long(2)?;
+                }
+                w.write_bit_long(1)?;
+                // R2000+: DD points against the previous one
+                w.write_2_raw_double(&Vector2::new(1.0, 1.0))?;
+                w.write_2_bit_double_with_default(&Vector2::new(1.0, 1.0), &Vector2::new(1.0, 6.0))?;
+                if *version >= DxfVersion::AC1024 {
+                    w.write_bit_long(7)?;
+                    w.write_bit_long(8)?;
+                }
+                w.write_bit_double(0.1)?;
+                w.write_bit_double(0.2)
+            });
+            assert_eq!(
+                lwpoly.point3_list_props["vertices"],
+                [Vector3::new(1.0, 1.0, 0.0), Vector3::new(1.0, 6.0, 0.0)],
+                "{:?}",
+                version
+            );
+            assert_eq!(lwpoly.float_list_props["widths"], [0.1, 0.2], "{:?}", version);
+        }
+    }
+
+    /// VIEWPORT payload with every version-dependent field set.
+    fn write_viewport(w: &mut dyn DwgStreamWriter, version: DxfVersion) -> Result<()> {
+        w.write_3_bit_double(&Vector3::new(5.0, 4.0, 0.0))?;
+        w.write_bit_double(10.0)?;
+        w.write_bit_double(8.0)?;
+
+        let r2000_plus = version >= DxfVersion::AC1015;
+        if r2000_plus {
+            w.write_3_bit_double(&Vector3::new(1.0, 1.0, 0.0))?;
+            w.write_3_bit_double(&Vector3::new(0.0, 0.0, 1.0))?;
+            for value in [0.0, 8.0, 50.0, 0.0, 0.0, 0.0] {
+                w.write_bit_double(value)?;
+            }
+            for point in [(5.0, 4.0), (0.0, 0.0), (0.5, 0.5), (1.0, 1.0)] {
+                w.write_2_raw_double(&Vector2::new(point.0, point.1))?;
+            }
+            w.write_bit_short(1000)?;
+        }
+        if version >= DxfVersion::AC1021 {
+            w.write_bit_short(5)?;
+        }
+        if r2000_plus {
+            w.write_bit_long(1)?;
+            w.write_bit_long(0x8000)?;
+            w.write_variable_text("mono.ctb")?;
+            w.write_byte(0)?;
+            w.write_bit(true)?;
+            w.write_bit(false)?;
+            w.write_3_bit_double(&Vector3::new(0.0, 0.0, 0.0))?;
+            w.write_3_bit_double(&Vector3::new(1.0, 0.0, 0.0))?;
+            w.write_3_bit_double(&Vector3::new(0.0, 1.0, 0.0))?;
+            w.write_bit_double(0.0)?;
+            w.write_bit_short(0)?;
+        }
+        if version >= DxfVersion::AC1018 {
+            w.write_bit_short(3)?;
+        }
+        if version >= DxfVersion::AC1021 {
+            w.write_bit(true)?;
+            w.write_byte(1)?;
+            w.write_bit_double(0.25)?;
+            w.write_bit_double(0.75)?;
+            w.write_cm_color(&Color::Index(250))?;
+        }
+        if !r2000_plus {
+            w.handle_reference_typed(DwgReferenceType::HardPointer, 0x30)?;
+        }
+        if r2000_plus {
+            w.handle_reference_typed(DwgReferenceType::HardPointer, 0x31)?;
+            w.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+        if version == DxfVersion::AC1015 {
+            w.handle_reference_typed(DwgReferenceType::HardPointer, 0x30)?;
+        }
+        if r2000_plus {
+            w.handle_reference_typed(DwgReferenceType::HardPointer, 0x32)?;
+            w.handle_reference_typed(DwgReferenceType::HardPointer, 0x33)?;
+        }
+        if version >= DxfVersion::AC1021 {
+            for handle in [0x34, 0x35, 0x36, 0x37] {
+                w.handle_reference_typed(DwgReferenceType::HardPointer, handle)?;
             }
+        }
+        Ok(())
+    }
 
-            if self.r2007_plus() {
-                let material_flags = parsed.object_reader.read_2_bits()?;
-                if material_flags == 3 {
-                    let _ = self.handle_reference(parsed, 0)?;
-                }
-                let _shadow_flags = parsed.object_reader.read_byte()?;
+    #[test]
+    fn test_read_viewport() {
+        for version in ALL_VERSIONS {
+            let viewport = roundtrip(version, 0x22, |w| write_viewport(w, version));
+            assert_eq!(p3(&viewport, "center"), Vector3::new(5.0, 4.0, 0.0), "{:?}", version);
+            assert_eq!(f(&viewport, "width"), 10.0);
+            assert_eq!(f(&viewport, "height"), 8.0);
+
+            if version < DxfVersion::AC1015 {
+                assert!(!viewport.point3_props.contains_key("view_target"), "{:?}", version);
+                assert!(!viewport.text_props.contains_key("style_sheet"));
+                continue;
             }
-
-            let plotstyle_flags = parsed.object_reader.read_2_bits()?;
-            if plotstyle_flags == 3 {
-                let _ = self.handle_reference(parsed, 0)?;
+            assert_eq!(f(&viewport, "lens_length"), 50.0, "{:?}", version);
+            assert_eq!(viewport.point2_props["grid_spacing"], Vector2::new(1.0, 1.0));
+            assert_eq!(i(&viewport, "circle_zoom"), 1000);
+            assert_eq!(i(&viewport, "status_flags"), 0x8000);
+            assert_eq!(viewport.text_props["style_sheet"], "mono.ctb", "{:?}", version);
+            assert!(viewport.bool_props["ucs_at_origin"]);
+            assert_eq!(viewport.handle_list_props["frozen_layer_handles"], [0x31]);
+            assert_eq!(viewport.handle_props["named_ucs_handle"], 0x32, "{:?}", version);
+            assert_eq!(viewport.handle_props["base_ucs_handle"], 0x33);
+
+            assert_eq!(
+                viewport.int_props.get("shade_plot_mode"),
+                (version >= DxfVersion::AC1018).then_some(&3),
+                "{:?}",
+                version
+            );
+            if version >= DxfVersion::AC1021 {
+                assert_eq!(i(&viewport, "grid_major"), 5, "{:?}", version);
+                assert!(viewport.bool_props["default_lighting"]);
+                assert_eq!(f(&viewport, "contrast"), 0.75);
+                assert_eq!(viewport.handle_props["sun_handle"], 0x37, "{:?}", version);
+            } else {
+                assert!(!viewport.int_props.contains_key("grid_major"), "{:?}", version);
+                assert!(!viewport.handle_props.contains_key("sun_handle"));
             }
+        }
+    }
 
-            if self.r2010_plus() {
-                if parsed.object_reader.read_bit()? {
-                    let _ = self.handle_reference(parsed, 0)?;
-                }
-                if parsed.object_reader.read_bit()? {
-                    let _ = self.handle_reference(parsed, 0)?;
+    #[test]
+    fn test_read_mline() {
+        for version in ALL_VERSIONS {
+            let mline = roundtrip(version, 0x2F, |w| {
+                w.write_bit_double(2.0)?;
+                w.write_byte(1)?;
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 0.0))?;
+                w.write_bit_extrusion(&Vector3::new(0.0, 0.0, 1.0))?;
+                w.write_bit_short(1)?;
+                // Two lines, two vertices
+                w.write_byte(2)?;
+                w.write_bit_short(2)?;
+                for x in [0.0, 10.0] {
+                    w.write_3_bit_double(&Vector3::new(x, 0.0, 0.0))?;
+                    w.write_3_bit_double(&Vector3::new(1.0, 0.0, 0.0))?;
+                    w.write_3_bit_double(&Vector3::new(0.0, 1.0, 0.0))?;
+                    for line in 0..2 {
+                        w.write_bit_short(2)?;
+                        w.write_bit_double(line as f64)?;
+                        w.write_bit_double(x)?;
+                        w.write_bit_short(0)?;
+                    }
                 }
-                if parsed.object_reader.read_bit()? {
-                    let _ = self.handle_reference(parsed, 0)?;
+                w.handle_reference_typed(DwgReferenceType::HardPointer, 0x18)
+            });
+            assert_eq!(f(&mline, "scale_factor"), 2.0, "{:?}", version);
+            assert_eq!(i(&mline, "justification"), 1);
+            assert_eq!(i(&mline, "line_count"), 2);
+            assert_eq!(mline.point3_list_props["vertices"][1], Vector3::new(10.0, 0.0, 0.0));
+            assert_eq!(mline.float_list_props["vertex_1_line_1_parameters"], [1.0, 10.0]);
+            assert!(mline.float_list_props["vertex_0_line_0_fill_parameters"].is_empty());
+            assert_eq!(mline.handle_props["mline_style_handle"], 0x18, "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_read_tolerance() {
+        for version in ALL_VERSIONS {
+            let tolerance = roundtrip(version, 0x2E, |w| {
+                if version < DxfVersion::AC1015 {
+                    // R13-R14: unknown BS, height and gap
+                    w.write_bit_short(0)?;
+                    w.write_bit_double(0.18)?;
+                    w.write_bit_double(0.09)?;
                 }
+                w.write_3_bit_double(&Vector3::new(2.0, 3.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(1.0, 0.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 1.0))?;
+                w.write_variable_text("{\\Fgdt;j}%%v0.1")?;
+                w.handle_reference_typed(DwgReferenceType::HardPointer, 0x27)
+            });
+            assert_eq!(p3(&tolerance, "insert_point"), Vector3::new(2.0, 3.0, 0.0), "{:?}", version);
+            assert_eq!(tolerance.text_props["value"], "{\\Fgdt;j}%%v0.1", "{:?}", version);
+            assert_eq!(tolerance.handle_props["dimstyle_handle"], 0x27);
+            if version < DxfVersion::AC1015 {
+                assert_eq!(f(&tolerance, "height"), 0.18, "{:?}", version);
+                assert_eq!(f(&tolerance, "dimension_gap"), 0.09);
+            } else {
+                assert!(!tolerance.float_props.contains_key("height"), "{:?}", version);
             }
-
-            let _invis = parsed.object_reader.read_bit_short()?;
-            template.line_weight = Some(parsed.object_reader.read_byte()? as i16);
         }
-
-        Ok(())
     }
 
-    fn read_common_non_entity_data(
-        &mut self,
-        parsed: &mut ParsedObjectStreams,
-        template: &mut DwgRawObject,
-    ) -> Result<()> {
-        self.read_common_data(parsed, template)?;
+    #[test]
+    fn test_read_ole2_frame() {
+        let data = [0xD0, 0xCF, 0x11, 0xE0, 0x01];
+        for version in ALL_VERSIONS {
+            let frame = roundtrip(version, 0x4A, |w| {
+                w.write_bit_short(2)?;
+                if version >= DxfVersion::AC1015 {
+                    w.write_bit_short(1)?;
+                }
+                w.write_bit_long(data.len() as i32)?;
+                w.write_bytes(&data)?;
+                if version >= DxfVersion::AC1015 {
+                    w.write_byte(0)?;
+                }
+                Ok(())
+            });
+            assert_eq!(i(&frame, "ole_version"), 2, "{:?}", version);
+            assert_eq!(frame.binary_props["ole_data"], data, "{:?}", version);
+            assert_eq!(
+                frame.int_props.get("ole_mode"),
+                (version >= DxfVersion::AC1015).then_some(&1),
+                "{:?}",
+                version
+            );
+        }
+    }
 
-        if self.r13_14_only() {
-            self.update_handle_reader(parsed)?;
+    #[test]
+    fn test_read_modeler_geometry() {
+        // SAT characters above 32 are stored as 159 - c
+        let sat: Vec<u8> = b"400 0 1 0".iter().map(|&c| if c <= 32 { c } else { 159 - c }).collect();
+        for version in &ALL_VERSIONS[2..] {
+            let region = roundtrip(*version, 0x25, |w| {
+                w.write_bit(false)?;
+                w.write_bit(false)?;
+                w.write_bit_short(1)?;
+                w.write_bit_long(sat.len() as i32)?;
+                w.write_bytes(&sat)?;
+                w.write_bit_long(0)?;
+                // Wireframe with a point of reference and one wire
+                w.write_bit(true)?;
+                w.write_bit(true)?;
+                w.write_3_bit_double(&Vector3::new(1.0, 1.0, 1.0))?;
+                w.write_bit_long(4)?;
+                w.write_bit(true)?;
+                w.write_bit_long(1)?;
+                w.write_byte(1)?;
+                w.write_bit_long(9)?;
+                // Wire color: BS before R2004, BL after
+                if *version >= DxfVersion::AC1018 {
+                    w.write_bit_long(256)?;
+                } else {
+                    w.write_bit_short(256)?;
+                }
+                w.write_bit_long(3)?;
+                w.write_bit_long(2)?;
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(1.0, 0.0, 0.0))?;
+                w.write_bit(false)?;
+                // No silhouettes
+                w.write_bit_long(0)?;
+                if *version >= DxfVersion::AC1021 {
+                    w.handle_reference_typed(DwgReferenceType::HardOwnership, 0x50)?;
+                }
+                Ok(())
+            });
+            assert_eq!(region.raw_type, Some(RawObjectType::Region));
+            assert_eq!(region.text_props["acis_sat_data"], "400 0 1 0", "{:?}", version);
+            assert_eq!(p3(&region, "point_of_reference"), Vector3::new(1.0, 1.0, 1.0));
+            assert_eq!(i(&region, "wire_count"), 1);
+            assert_eq!(i(&region, "wire_0_color"), 256, "{:?}", version);
+            assert_eq!(i(&region, "wire_0_acis_index"), 3, "{:?}", version);
+            assert_eq!(region.point3_list_props["wire_0_points"][1], Vector3::new(1.0, 0.0, 0.0));
+            assert_eq!(
+                region.handle_props.get("history_handle"),
+                (*version >= DxfVersion::AC1021).then_some(&0x50),
+                "{:?}",
+                version
+            );
         }
 
-        template.owner_handle = Some(self.handle_reference(parsed, template.handle)?);
-        self.read_reactors_and_dictionary_handle(parsed, template)
+        // Empty ACIS data
+        let solid = roundtrip(DxfVersion::AC1014, 0x26, |w| w.write_bit(true));
+        assert_eq!(solid.raw_type, Some(RawObjectType::Solid3D));
+        assert!(!solid.text_props.contains_key("acis_sat_data"));
     }
 
-    fn read_extended_data(
-        &mut self,
-        parsed: &mut ParsedObjectStreams,
-        template: &mut DwgRawObject,
-    ) -> Result<()> {
-        let mut size = parsed.object_reader.read_bit_short()?;
-        while size != 0 {
-            let app_handle = parsed.object_reader.handle_reference()?;
-            let end_pos = parsed.object_reader.position()? + size as u64;
-
-            let records = self.read_extended_data_records(parsed, end_pos)?;
-            template.eed.insert(app_handle, records);
+    #[test]
+    fn test_read_table_controls() {
+        for version in ALL_VERSIONS {
+            let layers = roundtrip_object(version, 0x32, |w| {
+                w.write_bit_long(3)?;
+                for entry in [0x70, 0, 0x71] {
+                    w.handle_reference_typed(DwgReferenceType::SoftOwnership, entry)?;
+                }
+                Ok(())
+            });
+            assert_eq!(layers.raw_type, Some(RawObjectType::LayerControl));
+            // Null entries are left out
+            assert_eq!(
+                layers.handle_list_props["entry_handles"],
+                [0x70, 0x71],
+                "{:?}",
+                version
+            );
 
-            size = parsed.object_reader.read_bit_short()?;
+            let blocks = roundtrip_object(version, 0x30, |w| {
+                w.write_bit_long(1)?;
+                w.handle_reference_typed(DwgReferenceType::SoftOwnership, 0x72)?;
+                w.handle_reference_typed(DwgReferenceType::HardOwnership, 0x73)?;
+                w.handle_reference_typed(DwgReferenceType::HardOwnership, 0x74)
+            });
+            assert_eq!(blocks.raw_type, Some(RawObjectType::BlockControl));
+            assert_eq!(blocks.handle_list_props["entry_handles"], [0x72]);
+            assert_eq!(h(&blocks, "model_space_handle"), 0x73, "{:?}", version);
+            assert_eq!(h(&blocks, "paper_space_handle"), 0x74, "{:?}", version);
+
+            let line_types = roundtrip_object(version, 0x38, |w| {
+                w.write_bit_long(1)?;
+                w.handle_reference_typed(DwgReferenceType::SoftOwnership, 0x75)?;
+                w.handle_reference_typed(DwgReferenceType::HardOwnership, 0x76)?;
+                w.handle_reference_typed(DwgReferenceType::HardOwnership, 0x77)
+            });
+            assert_eq!(line_types.handle_list_props["entry_handles"], [0x75]);
+            assert_eq!(h(&line_types, "bylayer_handle"), 0x76, "{:?}", version);
+            assert_eq!(h(&line_types, "byblock_handle"), 0x77, "{:?}", version);
+
+            // R2000+: extra handles after the DIMSTYLE entries
+            let dim_styles = roundtrip_object(version, 0x44, |w| {
+                w.write_bit_long(1)?;
+                if version >= DxfVersion::AC1015 {
+                    w.write_byte(1)?;
+                }
+                w.handle_reference_typed(DwgReferenceType::SoftOwnership, 0x78)?;
+                if version >= DxfVersion::AC1015 {
+                    w.handle_reference_typed(DwgReferenceType::HardOwnership, 0x79)?;
+                }
+                Ok(())
+            });
+            assert_eq!(dim_styles.raw_type, Some(RawObjectType::DimStyleControl));
+            assert_eq!(
+                dim_styles.handle_list_props["entry_handles"],
+                [0x78],
+                "{:?}",
+                version
+            );
         }
-        Ok(())
     }
 
-    fn read_extended_data_records(
-        &mut self,
-        parsed: &mut ParsedObjectStreams,
-        end_pos: u64,
-    ) -> Result<Vec<DwgExtendedDataRecord>> {
-        let mut records = Vec::new();
+    #[test]
+    fn test_read_layer() {
+        for version in ALL_VERSIONS {
+            let layer = roundtrip_object(version, 0x33, |w| {
+                write_entry_common(w, version, "Walls")?;
+                if version < DxfVersion::AC1015 {
+                    // Frozen, on, frozen in new, locked
+                    w.write_bit(false)?;
+                    w.write_bit(false)?;
+                    w.write_bit(false)?;
+                    w.write_bit(true)?;
+                } else {
+                    // Off, locked, plot, lineweight index 13
+                    w.write_bit_short(0x2 | 0x8 | 0x10 | (13 << 5))?;
+                }
+                w.write_cm_color(&Color::Index(5))?;
+                if version >= DxfVersion::AC1015 {
+                    w.handle_reference_typed(DwgReferenceType::HardPointer, 0x80)?;
+                }
+                if version >= DxfVersion::AC1021 {
+                    w.handle_reference_typed(DwgReferenceType::HardPointer, 0x81)?;
+                }
+                w.handle_reference_typed(DwgReferenceType::HardPointer, 0x82)?;
+                if version >= DxfVersion::AC1027 {
+                    w.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                }
+                Ok(())
+            });
+            assert_eq!(layer.raw_type, Some(RawObjectType::Layer));
+            assert_eq!(t(&layer, "entry_name"), "Walls", "{:?}", version);
+            assert!(!b(&layer, "entry_xref_dependent"), "{:?}", version);
+            assert!(!b(&layer, "layer_frozen"), "{:?}", version);
+            assert!(b(&layer, "layer_off"), "{:?}", version);
+            assert!(b(&layer, "layer_locked"), "{:?}", version);
+            assert_eq!(layer.color, Some(Color::Index(5)), "{:?}", version);
+            assert_eq!(h(&layer, "layer_linetype_handle"), 0x82, "{:?}", version);
+            if version >= DxfVersion::AC1015 {
+                assert!(b(&layer, "layer_plot"), "{:?}", version);
+                assert_eq!(layer.line_weight, Some(13), "{:?}", version);
+                assert_eq!(h(&layer, "layer_plot_style_handle"), 0x80, "{:?}", version);
+            }
+            if version >= DxfVersion::AC1021 {
+                assert_eq!(h(&layer, "layer_material_handle"), 0x81, "{:?}", version);
+            }
+        }
+    }
 
-        while parsed.object_reader.position()? < end_pos {
-            let dxf_code = 1000 + parsed.object_reader.read_byte()? as i32;
-            let mut record = DwgExtendedDataRecord {
-                code: dxf_code,
-                ..Default::default()
-            };
+    #[test]
+    fn test_read_text_style() {
+        for version in ALL_VERSIONS {
+            let style = roundtrip_object(version, 0x35, |w| {
+                write_entry_common(w, version, "Notes")?;
+                w.write_bit(false)?;
+                w.write_bit(true)?;
+                w.write_bit_double(2.5)?;
+                w.write_bit_double(0.8)?;
+                w.write_bit_double(0.1)?;
+                w.write_byte(4)?;
+                w.write_bit_double(3.0)?;
+                w.write_variable_text("arial.ttf")?;
+                w.write_variable_text("bigfont.shx")
+            });
+            assert_eq!(style.raw_type, Some(RawObjectType::TextStyle));
+            assert_eq!(t(&style, "entry_name"), "Notes", "{:?}", version);
+            assert!(!b(&style, "style_is_shape_file"));
+            assert!(b(&style, "style_vertical"), "{:?}", version);
+            assert_eq!(f(&style, "style_height"), 2.5, "{:?}", version);
+            assert_eq!(f(&style, "style_width_factor"), 0.8, "{:?}", version);
+            assert_eq!(f(&style, "style_oblique_angle"), 0.1, "{:?}", version);
+            assert_eq!(i(&style, "style_generation"), 4, "{:?}", version);
+            assert_eq!(f(&style, "style_last_height"), 3.0, "{:?}", version);
+            assert_eq!(t(&style, "style_font_file"), "arial.ttf", "{:?}", version);
+            assert_eq!(
+                t(&style, "style_big_font_file"),
+                "bigfont.shx",
+                "{:?}",
+                version
+            );
+        }
+    }
 
-            match dxf_code {
-                1000 | 1001 => {
-                    record.text = Some(parsed.object_reader.read_text_unicode()?);
-                }
-                1002 => {
-                    record.integer = Some(parsed.object_reader.read_byte()? as i64);
+    #[test]
+    fn test_read_line_type() {
+        for version in ALL_VERSIONS {
+            let line_type = roundtrip_object(version, 0x39, |w| {
+                write_entry_common(w, version, "Dashed")?;
+                w.write_variable_text("__ __ __")?;
+                w.write_bit_double(0.75)?;
+                w.write_byte(b'A')?;
+                w.write_byte(2)?;
+                for (length, offset) in [(0.5, 0.1), (-0.25, 0.0)] {
+                    w.write_bit_double(length)?;
+                    w.write_bit_short(0)?;
+                    w.write_raw_double(offset)?;
+                    w.write_raw_double(0.0)?;
+                    w.write_bit_double(1.0)?;
+                    w.write_bit_double(0.0)?;
+                    w.write_bit_short(0)?;
                 }
-                1003 | 1005 => {
-                    record.bytes = parsed.object_reader.read_bytes(8)?;
+                if version <= DxfVersion::AC1018 {
+                    w.write_bytes(&[0; 256])?;
                 }
-                1004 => {
-                    let len = parsed.object_reader.read_byte()? as usize;
-                    record.bytes = parsed.object_reader.read_bytes(len)?;
+                w.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                w.handle_reference_typed(DwgReferenceType::HardPointer, 0)
+            });
+            assert_eq!(line_type.raw_type, Some(RawObjectType::LineType));
+            assert_eq!(t(&line_type, "entry_name"), "Dashed", "{:?}", version);
+            assert_eq!(
+                t(&line_type, "linetype_description"),
+                "__ __ __",
+                "{:?}",
+                version
+            );
+            assert_eq!(
+                f(&line_type, "linetype_pattern_length"),
+                0.75,
+                "{:?}",
+                version
+            );
+            assert_eq!(i(&line_type, "linetype_alignment"), b'A' as i64);
+            assert_eq!(
+                line_type.float_list_props["linetype_dash_lengths"],
+                [0.5, -0.25],
+                "{:?}",
+                version
+            );
+            assert_eq!(
+                line_type.point2_props["linetype_dash_0_offset"],
+                Vector2::new(0.1, 0.0)
+            );
+            assert_eq!(
+                line_type.handle_list_props["linetype_dash_style_handles"],
+                [0, 0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_block_header() {
+        for version in ALL_VERSIONS {
+            let r2000_plus = version >= DxfVersion::AC1015;
+            let r2004_plus = version >= DxfVersion::AC1018;
+            let block = roundtrip_object(version, 0x31, |w| {
+                write_entry_common(w, version, "Door")?;
+                // Anonymous, has attributes, xref, overlay
+                w.write_bit(false)?;
+                w.write_bit(true)?;
+                w.write_bit(false)?;
+                w.write_bit(false)?;
+                if r2000_plus {
+                    w.write_bit(false)?;
                 }
-                1010..=1013 => {
-                    record.point = Some(Vector3::new(
-                        parsed.object_reader.read_double()?,
-                        parsed.object_reader.read_double()?,
-                        parsed.object_reader.read_double()?,
-                    ));
+                if r2004_plus {
+                    w.write_bit_long(2)?;
                 }
-                1040..=1042 => {
-                    record.number = Some(parsed.object_reader.read_double()?);
+                w.write_3_bit_double(&Vector3::new(1.0, 2.0, 0.0))?;
+                w.write_variable_text("")?;
+                if r2000_plus {
+                    // One insert, then the description and an empty preview
+                    w.write_byte(1)?;
+                    w.write_byte(0)?;
+                    w.write_variable_text("A door")?;
+                    w.write_bit_long(0)?;
                 }
-                1070 => {
-                    record.integer = Some(parsed.object_reader.read_short()? as i64);
+                if version >= DxfVersion::AC1021 {
+                    w.write_bit_short(4)?;
+                    w.write_bit(true)?;
+                    w.write_byte(0)?;
                 }
-                1071 => {
-                    record.integer = Some(parsed.object_reader.read_raw_long()?);
+                w.handle_reference_typed(DwgReferenceType::SoftPointer, 0x50)?;
+                for entity in [0x51, 0x52] {
+                    w.handle_reference_typed(DwgReferenceType::SoftPointer, entity)?;
                 }
-                _ => {
-                    let remaining = (end_pos.saturating_sub(parsed.object_reader.position()?)) as usize;
-                    let _ = parsed.object_reader.read_bytes(remaining)?;
-                    records.push(record);
-                    break;
+                w.handle_reference_typed(DwgReferenceType::SoftPointer, 0x53)?;
+                if r2000_plus {
+                    w.handle_reference_typed(DwgReferenceType::SoftPointer, 0x60)?;
+                    w.handle_reference_typed(DwgReferenceType::SoftPointer, 0x61)?;
                 }
+                Ok(())
+            });
+            assert_eq!(block.raw_type, Some(RawObjectType::BlockHeader));
+            assert_eq!(t(&block, "entry_name"), "Door", "{:?}", version);
+            assert!(!b(&block, "block_anonymous"));
+            assert!(b(&block, "block_has_attributes"), "{:?}", version);
+            assert!(!b(&block, "block_is_xref"));
+            assert_eq!(
+                block.point3_props["block_base_point"],
+                Vector3::new(1.0, 2.0, 0.0)
+            );
+            assert_eq!(h(&block, "block_entity_handle"), 0x50, "{:?}", version);
+            assert_eq!(h(&block, "block_end_handle"), 0x53, "{:?}", version);
+            if r2004_plus {
+                assert_eq!(
+                    block.handle_list_props["block_owned_handles"],
+                    [0x51, 0x52],
+                    "{:?}",
+                    version
+                );
+            } else {
+                assert_eq!(
+                    h(&block, "block_first_entity_handle"),
+                    0x51,
+                    "{:?}",
+                    version
+                );
+                assert_eq!(h(&block, "block_last_entity_handle"), 0x52, "{:?}", version);
+            }
+            if r2000_plus {
+                assert_eq!(t(&block, "block_description"), "A door", "{:?}", version);
+                assert_eq!(
+                    block.handle_list_props["block_insert_handles"],
+                    [0x60],
+                    "{:?}",
+                    version
+                );
+                assert_eq!(h(&block, "block_layout_handle"), 0x61, "{:?}", version);
+            }
+            if version >= DxfVersion::AC1021 {
+                assert_eq!(i(&block, "block_units"), 4, "{:?}", version);
+                assert!(b(&block, "block_explodable"), "{:?}", version);
+                assert!(b(&block, "block_scale_uniformly"), "{:?}", version);
             }
-
-            records.push(record);
         }
-
-        Ok(records)
     }
 
-    fn read_reactors_and_dictionary_handle(
-        &mut self,
-        parsed: &mut ParsedObjectStreams,
-        template: &mut DwgRawObject,
-    ) -> Result<()> {
-        let reactor_count = parsed.object_reader.read_bit_long()?.max(0) as usize;
-        for _ in 0..reactor_count {
-            template.reactors.push(self.handle_reference(parsed, 0)?);
-        }
-
-        let mut xdict_missing = false;
-        if self.r2004_plus() {
-            xdict_missing = parsed.object_reader.read_bit()?;
+    #[test]
+    fn test_read_app_id_and_ucs() {
+        for version in ALL_VERSIONS {
+            let app_id = roundtrip_object(version, 0x43, |w| {
+                write_entry_common(w, version, "ACME")?;
+                w.write_byte(0)
+            });
+            assert_eq!(app_id.raw_type, Some(RawObjectType::AppId));
+            assert_eq!(t(&app_id, "entry_name"), "ACME", "{:?}", version);
+
+            let ucs = roundtrip_object(version, 0x3F, |w| {
+                write_entry_common(w, version, "Front")?;
+                w.write_3_bit_double(&Vector3::new(1.0, 2.0, 3.0))?;
+                w.write_3_bit_double(&Vector3::new(1.0, 0.0, 0.0))?;
+                w.write_3_bit_double(&Vector3::new(0.0, 0.0, 1.0))?;
+                if version >= DxfVersion::AC1015 {
+                    w.write_bit_double(2.0)?;
+                    w.write_bit_short(1)?;
+                    w.write_bit_short(5)?;
+                    w.handle_reference_typed(DwgReferenceType::HardPointer, 0x90)?;
+                    w.handle_reference_typed(DwgReferenceType::HardPointer, 0x91)?;
+                }
+                Ok(())
+            });
+            assert_eq!(ucs.raw_type, Some(RawObjectType::Ucs));
+            assert_eq!(t(&ucs, "entry_name"), "Front", "{:?}", version);
+            assert_eq!(
+                p3(&ucs, "ucs_origin"),
+                Vector3::new(1.0, 2.0, 3.0),
+                "{:?}",
+                version
+            );
+            assert_eq!(
+                p3(&ucs, "ucs_y_axis"),
+                Vector3::new(0.0, 0.0, 1.0),
+                "{:?}",
+                version
+            );
+            if version >= DxfVersion::AC1015 {
+                assert_eq!(f(&ucs, "ucs_elevation"), 2.0, "{:?}", version);
+                assert_eq!(i(&ucs, "ucs_ortho_type"), 5, "{:?}", version);
+                assert_eq!(h(&ucs, "base_ucs_handle"), 0x90, "{:?}", version);
+                assert_eq!(h(&ucs, "named_ucs_handle"), 0x91, "{:?}", version);
+            }
         }
+    }
 
-        if !xdict_missing {
-            template.xdict_handle = Some(self.handle_reference(parsed, 0)?);
+    /// View data shared by VIEW and VPORT: size, center, target, direction,
+    /// lens, clipping, view mode, render mode and lighting
+    fn write_view_data(w: &mut dyn DwgStreamWriter, version: DxfVersion) -> Result<()> {
+        w.write_bit_double(10.0)?;
+        w.write_bit_double(20.0)?;
+        w.write_2_raw_double(&Vector2::new(5.0, 6.0))?;
+        w.write_3_bit_double(&Vector3::new(1.0, 1.0, 0.0))?;
+        w.write_3_bit_double(&Vector3::new(0.0, 0.0, 1.0))?;
+        w.write_bit_double(0.0)?;
+        w.write_bit_double(50.0)?;
+        w.write_bit_double(0.0)?;
+        w.write_bit_double(0.0)?;
+        // Perspective on, no clipping
+        w.write_bit(true)?;
+        w.write_bit(false)?;
+        w.write_bit(false)?;
+        w.write_bit(false)?;
+        if version >= DxfVersion::AC1015 {
+            w.write_byte(0)?;
         }
-
-        if self.r2013_plus() {
-            let _has_ds_binary_data = parsed.object_reader.read_bit()?;
+        if version >= DxfVersion::AC1021 {
+            w.write_bit(true)?;
+            w.write_byte(1)?;
+            w.write_bit_double(0.0)?;
+            w.write_bit_double(0.0)?;
+            w.write_cm_color(&Color::Index(250))?;
         }
-
         Ok(())
     }
 
-    fn update_handle_reader(&self, parsed: &mut ParsedObjectStreams) -> Result<()> {
-        let size = parsed.object_reader.read_raw_long()?;
-        parsed
-            .handles_reader
-            .set_position_in_bits(size as u64 + parsed.object_initial_pos)?;
-
-        if self.version == DxfVersion::AC1021 {
-            let _ = parsed
-                .text_reader
-                .set_position_by_flag(size as u64 + parsed.object_initial_pos - 1);
-        }
-
-        Ok(())
+    fn assert_view_data(object: &DwgRawObject, version: DxfVersion) {
+        assert_eq!(f(object, "view_height"), 10.0, "{:?}", version);
+        assert_eq!(f(object, "view_width"), 20.0, "{:?}", version);
+        assert_eq!(
+            object.point2_props["view_center"],
+            Vector2::new(5.0, 6.0),
+            "{:?}",
+            version
+        );
+        assert_eq!(
+            p3(object, "view_target"),
+            Vector3::new(1.0, 1.0, 0.0),
+            "{:?}",
+            version
+        );
+        assert_eq!(f(object, "view_lens_length"), 50.0, "{:?}", version);
+        assert_eq!(i(object, "view_mode"), 1, "{:?}", version);
     }
 
-    fn handle_reference(&mut self, parsed: &mut ParsedObjectStreams, base: u64) -> Result<u64> {
-        let value = parsed.handles_reader.handle_reference_from(base)?;
-        if value != 0 && !self.read_objects.contains(&value) {
-            self.handles.push_back(value);
+    #[test]
+    fn test_read_view() {
+        for version in ALL_VERSIONS {
+            let view = roundtrip_object(version, 0x3D, |w| {
+                write_entry_common(w, version, "Top")?;
+                write_view_data(w, version)?;
+                w.write_bit(true)?;
+                if version >= DxfVersion::AC1015 {
+                    // No associated UCS
+                    w.write_bit(false)?;
+                }
+                if version >= DxfVersion::AC1021 {
+                    w.write_bit(true)?;
+                    for _ in 0..4 {
+                        w.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                    }
+                }
+                Ok(())
+            });
+            assert_eq!(view.raw_type, Some(RawObjectType::View));
+            assert_eq!(t(&view, "entry_name"), "Top", "{:?}", version);
+            assert_view_data(&view, version);
+            assert!(b(&view, "view_paper_space"), "{:?}", version);
+            if version >= DxfVersion::AC1021 {
+                assert!(b(&view, "view_plottable"), "{:?}", version);
+                assert_eq!(i(&view, "default_lighting_type"), 1, "{:?}", version);
+            }
         }
-        Ok(value)
     }
 
-    #[inline]
-    fn r13_14_only(&self) -> bool {
-        matches!(self.version, DxfVersion::AC1012 | DxfVersion::AC1014)
-    }
-    #[inline]
-    fn r2004_plus(&self) -> bool {
-        self.version >= DxfVersion::AC1018
-    }
-    #[inline]
-    fn r2007_plus(&self) -> bool {
-        self.version >= DxfVersion::AC1021
-    }
-    #[inline]
-    fn r2010_plus(&self) -> bool {
-        self.version >= DxfVersion::AC1024
-    }
-    #[inline]
-    fn r2013_plus(&self) -> bool {
-        self.version >= DxfVersion::AC1027
+    #[test]
+    fn test_read_vport() {
+        for version in ALL_VERSIONS {
+            let vport = roundtrip_object(version, 0x41, |w| {
+                write_entry_common(w, version, "*Active")?;
+                write_view_data(w, version)?;
+                w.write_2_raw_double(&Vector2::new(0.0, 0.0))?;
+                w.write_2_raw_double(&Vector2::new(1.0, 1.0))?;
+                // UCS follow, circle zoom, fast zoom, UCS icon on / at origin, grid on
+                w.write_bit(true)?;
+                w.write_bit_short(1000)?;
+                w.write_bit(true)?;
+                w.write_bit(true)?;
+                w.write_bit(false)?;
+                w.write_bit(true)?;
+                w.write_2_raw_double(&Vector2::new(0.5, 0.5))?;
+                // Snap on, isometric, iso pair, rotation, base, spacing
+                w.write_bit(false)?;
+                w.write_bit(false)?;
+                w.write_bit_short(0)?;
+                w.write_bit_double(0.0)?;
+                w.write_2_raw_double(&Vector2::new(0.0, 0.0))?;
+                w.write_2_raw_double(&Vector2::new(0.25, 0.25))?;
+                if version >= DxfVersion::AC1015 {
+                    w.write_bit(false)?;
+                    w.write_bit(true)?;
+                    w.write_3_bit_double(&Vector3::new(0.0, 0.0, 0.0))?;
+                    w.write_3_bit_double(&Vector3::new(1.0, 0.0, 0.0))?;
+                    w.write_3_bit_double(&Vector3::new(0.0, 1.0, 0.0))?;
+                    w.write_bit_double(0.0)?;
+                    w.write_bit_short(0)?;
+                }
+                if version >= DxfVersion::AC1021 {
+                    w.write_bit_short(3)?;
+                    w.write_bit_short(5)?;
+                    for _ in 0..3 {
+                        w.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                    }
+                }
+                if version >= DxfVersion::AC1015 {
+                    w.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                    w.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                }
+                Ok(())
+            });
+            assert_eq!(vport.raw_type, Some(RawObjectType::VPort));
+            assert_eq!(t(&vport, "entry_name"), "*Active", "{:?}", version);
+            assert_view_data(&vport, version);
+            assert_eq!(
+                vport.point2_props["vport_upper_right"],
+                Vector2::new(1.0, 1.0)
+            );
+            assert!(b(&vport, "vport_ucs_follow"), "{:?}", version);
+            assert_eq!(i(&vport, "vport_circle_zoom"), 1000, "{:?}", version);
+            assert!(b(&vport, "vport_grid_on"), "{:?}", version);
+            assert_eq!(
+                vport.point2_props["vport_grid_spacing"],
+                Vector2::new(0.5, 0.5)
+            );
+            assert_eq!(
+                vport.point2_props["vport_snap_spacing"],
+                Vector2::new(0.25, 0.25)
+            );
+            if version >= DxfVersion::AC1015 {
+                assert!(b(&vport, "ucs_per_viewport"), "{:?}", version);
+                assert_eq!(p3(&vport, "ucs_y_axis"), Vector3::new(0.0, 1.0, 0.0));
+            }
+            if version >= DxfVersion::AC1021 {
+                assert_eq!(i(&vport, "vport_grid_major"), 5, "{:?}", version);
+            }
+        }
     }
-}
 
-struct ParsedObjectStreams {
-    object_initial_pos: u64,
-    size: u32,
-    object_type: DwgObjectType,
-    object_reader: DwgStreamReaderBase,
-    handles_reader: DwgStreamReaderBase,
-    text_reader: DwgStreamReaderBase,
-}
+    #[test]
+    fn test_read_dim_style() {
+        fn bits(w: &mut dyn DwgStreamWriter, values: &[bool]) -> Result<()> {
+            values.iter().try_for_each(|&v| w.write_bit(v))
+        }
+        fn shorts(w: &mut dyn DwgStreamWriter, values: &[i16]) -> Result<()> {
+            values.iter().try_for_each(|&v| w.write_bit_short(v))
+        }
+        fn doubles(w: &mut dyn DwgStreamWriter, values: &[f64]) -> Result<()> {
+            values.iter().try_for_each(|&v| w.write_bit_double(v))
+        }
+        fn texts(w: &mut dyn DwgStreamWriter, values: &[&str]) -> Result<()> {
+            values.iter().try_for_each(|v| w.write_variable_text(v))
+        }
 
-impl ParsedObjectStreams {
-    fn empty() -> Self {
-        let stream = || {
-            DwgStreamReaderBase::get_stream_handler(DxfVersion::Unknown, Cursor::new(Vec::<u8>::new()))
-        };
-        Self {
-            object_initial_pos: 0,
-            size: 0,
-            object_type: DwgObjectType(0),
-            object_reader: stream(),
-            handles_reader: stream(),
-            text_reader: stream(),
+        for version in ALL_VERSIONS {
+            let style = roundtrip_object(version, 0x45, |w| {
+                write_entry_common(w, version, "ISO-25")?;
+                if version < DxfVersion::AC1015 {
+                    // DIMTOL on, the other switches off
+                    let mut switches = [false; 11];
+                    switches[0] = true;
+                    bits(w, &switches)?;
+                    // DIMALTD, DIMZIN
+                    w.write_byte(2)?;
+                    w.write_byte(8)?;
+                    bits(w, &[false, false])?;
+                    for _ in 0..3 {
+                        w.write_byte(0)?;
+                    }
+                    bits(w, &[false])?;
+                    // DIMTZIN, DIMALTZ, DIMALTTZ, DIMTAD
+                    for value in [0, 0, 0, 1] {
+                        w.write_byte(value)?;
+                    }
+                    // DIMUNIT, DIMAUNIT, DIMDEC, DIMTDEC, DIMALTU, DIMALTTD
+                    shorts(w, &[2, 0, 3, 2, 2, 2])?;
+                    // DIMSCALE .. DIMTM, then DIMTXT .. DIMGAP
+                    doubles(w, &[2.0, 0.18, 0.0625, 0.38, 0.18, 0.0, 0.0, 0.0, 0.0])?;
+                    doubles(w, &[0.25, 0.09, 0.0, 25.4, 1.0, 0.0, 1.0, 0.09])?;
+                    texts(w, &["<> mm", "", "", "", ""])?;
+                    shorts(w, &[0, 0, 0])?;
+                } else {
+                    texts(w, &["<> mm", ""])?;
+                    // DIMSCALE .. DIMTM
+                    doubles(w, &[2.0, 0.18, 0.0625, 0.38, 0.18, 0.0, 0.0, 0.0, 0.0])?;
+                    if version >= DxfVersion::AC1021 {
+                        // DIMFXL, DIMJOGANG, DIMTFILL, DIMTFILLCLR
+                        doubles(w, &[1.5, 0.5])?;
+                        shorts(w, &[1])?;
+                        w.write_cm_color(&Color::ByBlock)?;
+                    }
+                    bits(w, &[true, false, false, false, false, false])?;
+                    // DIMTAD, DIMZIN, DIMAZIN
+                    shorts(w, &[1, 8, 0])?;
+                    if version >= DxfVersion::AC1021 {
+                        shorts(w, &[0])?;
+                    }
+                    // DIMTXT .. DIMALTRND
+                    doubles(w, &[0.25, 0.09, 0.0, 25.4, 1.0, 0.0, 1.0, 0.09, 0.0])?;
+                    bits(w, &[false])?;
+                    shorts(w, &[2])?;
+                    bits(w, &[false, false, false, false])?;
+                    // DIMCLRD .. DIMJUST, DIMDEC fifth
+                    shorts(w, &[0, 0, 0, 0, 3, 2, 2, 2, 0, 0, 2, 46, 0, 0])?;
+                    bits(w, &[false, false])?;
+                    shorts(w, &[0, 0, 0, 0])?;
+                    bits(w, &[false])?;
+                    // DIMATFIT
+                    shorts(w, &[3])?;
+                    if version >= DxfVersion::AC1021 {
+                        bits(w, &[true])?;
+                    }
+                    if version >= DxfVersion::AC1024 {
+                        bits(w, &[true])?;
+                        doubles(w, &[100.0])?;
+                        texts(w, &["cm"])?;
+                        doubles(w, &[100.0])?;
+                        texts(w, &["cm"])?;
+                    }
+                    // DIMLWD, DIMLWE
+                    shorts(w, &[-2, -2])?;
+                }
+                bits(w, &[false])?;
+                w.handle_reference_typed(DwgReferenceType::HardPointer, 0x30)?;
+                if version >= DxfVersion::AC1015 {
+                    for block in [0, 0x31, 0, 0] {
+                        w.handle_reference_typed(DwgReferenceType::HardPointer, block)?;
+                    }
+                }
+                if version >= DxfVersion::AC1021 {
+                    for line_type in [0x32, 0, 0] {
+                        w.handle_reference_typed(DwgReferenceType::HardPointer, line_type)?;
+                    }
+                }
+                Ok(())
+            });
+            assert_eq!(style.raw_type, Some(RawObjectType::DimStyle));
+            assert_eq!(t(&style, "entry_name"), "ISO-25", "{:?}", version);
+            assert!(b(&style, "dimtol"), "{:?}", version);
+            assert!(!b(&style, "dimlim"), "{:?}", version);
+            assert_eq!(i(&style, "dimtad"), 1, "{:?}", version);
+            assert_eq!(i(&style, "dimzin"), 8, "{:?}", version);
+            assert_eq!(i(&style, "dimdec"), 3, "{:?}", version);
+            assert_eq!(i(&style, "dimaltd"), 2, "{:?}", version);
+            assert_eq!(f(&style, "dimscale"), 2.0, "{:?}", version);
+            assert_eq!(f(&style, "dimtxt"), 0.25, "{:?}", version);
+            assert_eq!(f(&style, "dimaltf"), 25.4, "{:?}", version);
+            assert_eq!(f(&style, "dimgap"), 0.09, "{:?}", version);
+            assert_eq!(t(&style, "dimpost"), "<> mm", "{:?}", version);
+            assert_eq!(h(&style, "dimtxsty"), 0x30, "{:?}", version);
+            if version >= DxfVersion::AC1015 {
+                assert_eq!(i(&style, "dimdsep"), 46, "{:?}", version);
+                assert_eq!(i(&style, "dimatfit"), 3, "{:?}", version);
+                assert_eq!(i(&style, "dimlwd"), -2, "{:?}", version);
+                assert_eq!(h(&style, "dimblk"), 0x31, "{:?}", version);
+            }
+            if version >= DxfVersion::AC1021 {
+                assert_eq!(f(&style, "dimfxl"), 1.5, "{:?}", version);
+                assert_eq!(i(&style, "dimtfill"), 1, "{:?}", version);
+                assert!(b(&style, "dimfxlon"), "{:?}", version);
+                assert_eq!(h(&style, "dimltype"), 0x32, "{:?}", version);
+            }
+            if version >= DxfVersion::AC1024 {
+                assert!(b(&style, "dimtxtdirection"), "{:?}", version);
+                assert_eq!(t(&style, "dimmzs"), "cm", "{:?}", version);
+            }
         }
     }
 }
@@ -1,4 +1,5 @@
-use crate::error::Result;
+use crate::error::{DxfError, Result};
+use crate::io::dwg::dwg_preview_image::{decode_bmp, decode_png, encode_bmp, encode_png, PreviewImage};
 
 use super::idwg_stream_reader::DwgStreamReader;
 
@@ -33,6 +34,45 @@ pub struct DwgPreview {
     pub raw_image: Vec<u8>,
 }
 
+/// Size of the header data written in front of generated images.
+const PREVIEW_HEADER_SIZE: usize = 80;
+
+impl DwgPreview {
+    /// Decode the BMP or PNG image into RGBA pixels.
+    pub fn decode(&self) -> Result<PreviewImage> {
+        match self.code {
+            PreviewType::Bmp => decode_bmp(&self.raw_image),
+            PreviewType::Png => decode_png(&self.raw_image),
+            PreviewType::Wmf => Err(DxfError::NotImplemented(
+                "WMF preview images cannot be decoded".into(),
+            )),
+            PreviewType::Unknown => Err(DxfError::InvalidFormat(
+                "The DWG file has no preview image".into(),
+            )),
+        }
+    }
+
+    /// Build a preview from RGBA pixels, encoded as BMP or PNG.
+    pub fn from_image(image: &PreviewImage, code: PreviewType) -> Result<Self> {
+        let raw_image = match code {
+            PreviewType::Bmp => encode_bmp(image),
+            PreviewType::Png => encode_png(image)?,
+            _ => {
+                return Err(DxfError::NotImplemented(format!(
+                    "Preview images can only be encoded as BMP or PNG, not {:?}",
+                    code
+                )))
+            }
+        };
+
+        Ok(Self {
+            code,
+            raw_header: vec![0; PREVIEW_HEADER_SIZE],
+            raw_image,
+        })
+    }
+}
+
 pub const PREVIEW_START_SENTINEL: [u8; 16] = [
     0x1F, 0x25, 0x6D, 0x07, 0xD4, 0x36, 0x28, 0x28,
     0x9D, 0x57, 0xCA, 0x3F, 0x9D, 0x44, 0x10, 0x2B,
@@ -0,0 +1,38 @@
+use crate::document::RevisionHistory;
+use crate::error::{DxfError, Result};
+
+use super::idwg_stream_reader::DwgStreamReader;
+
+/// Reads the DWG revision history (AcDb:RevHistory, R2004+).
+///
+/// Layout: UInt32 unknown, UInt32 unknown, UInt32 entry count, then one
+/// UInt32 per entry.
+pub struct DwgRevHistoryReader;
+
+impl DwgRevHistoryReader {
+    /// Read the RevHistory section.
+    pub fn read(reader: &mut dyn DwgStreamReader) -> Result<RevisionHistory> {
+        let unknown1 = reader.read_uint()?;
+        let unknown2 = reader.read_uint()?;
+        let count = reader.read_uint()?;
+
+        // Each entry takes 4 bytes; reject counts no section could hold.
+        if count > i32::MAX as u32 / 4 {
+            return Err(DxfError::InvalidFormat(format!(
+                "Invalid RevHistory entry count {}",
+                count
+            )));
+        }
+
+        let mut entries = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            entries.push(reader.read_uint()?);
+        }
+
+        Ok(RevisionHistory {
+            unknown1,
+            unknown2,
+            entries,
+        })
+    }
+}
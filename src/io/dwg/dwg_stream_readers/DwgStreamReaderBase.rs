@@ -664,6 +664,9 @@ impl DwgStreamReader for DwgStreamReaderBase {
         if (last_byte & 0x80) != 0 {
             loop {
                 shift += 7;
+                if shift > 63 {
+                    return Err(DxfError::Parse("Modular char is too long".to_string()));
+                }
                 let last = self.read_byte()?;
                 value |= ((last & 0x7F) as u64) << shift;
                 if (last & 0x80) == 0 {
@@ -695,6 +698,9 @@ impl DwgStreamReader for DwgStreamReaderBase {
             let mut sum = (self.last_byte & 0x7F) as i64;
             loop {
                 total_shift += 7;
+                if total_shift > 56 {
+                    return Err(DxfError::Parse("Modular char is too long".to_string()));
+                }
                 self.advance_byte()?;
                 if (self.last_byte & 0x80) != 0 {
                     sum |= ((self.last_byte & 0x7F) as i64) << total_shift;
@@ -724,6 +730,9 @@ impl DwgStreamReader for DwgStreamReaderBase {
             let mut curr_byte;
             loop {
                 total_shift += 7;
+                if total_shift > 56 {
+                    return Err(DxfError::Parse("Modular char is too long".to_string()));
+                }
                 curr_byte = self.apply_shift_to_last_byte()?;
                 if (curr_byte & 0x80) != 0 {
                     sum |= ((curr_byte & 0x7F) as i64) << total_shift;
@@ -752,6 +761,9 @@ impl DwgStreamReader for DwgStreamReaderBase {
         let mut value = (b1 as i32) | (((b2 & 0x7F) as i32) << 8);
 
         while !flag {
+            if shift > 16 {
+                return Err(DxfError::Parse("Modular short is too long".to_string()));
+            }
             let b1 = self.read_byte()?;
             let b2 = self.read_byte()?;
             flag = (b2 & 0x80) == 0;
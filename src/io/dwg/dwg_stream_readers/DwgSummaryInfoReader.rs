@@ -1,29 +1,9 @@
-use std::collections::BTreeMap;
-
+pub use crate::document::CadSummaryInfo;
 use crate::error::Result;
 use crate::types::DxfVersion;
 
 use super::idwg_stream_reader::DwgStreamReader;
 
-/// Summary info data read from a DWG file.
-#[derive(Debug, Clone, Default)]
-pub struct CadSummaryInfo {
-    pub title: String,
-    pub subject: String,
-    pub author: String,
-    pub keywords: String,
-    pub comments: String,
-    pub last_saved_by: String,
-    pub revision_number: String,
-    pub hyperlink_base: String,
-    /// (julian_date, milliseconds)
-    pub created_date: (i32, i32),
-    /// (julian_date, milliseconds)
-    pub modified_date: (i32, i32),
-    /// Custom key/value properties.
-    pub properties: BTreeMap<String, String>,
-}
-
 /// Reads SUMMARYINFO section from a DWG file.
 /// Matches the C# DwgSummaryInfoReader implementation.
 pub struct DwgSummaryInfoReader;
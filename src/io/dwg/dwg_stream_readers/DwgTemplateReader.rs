@@ -0,0 +1,39 @@
+use crate::document::DrawingTemplate;
+use crate::error::Result;
+use crate::types::DxfVersion;
+
+use super::idwg_stream_reader::DwgStreamReader;
+
+/// Reads the DWG template section (AcDb:Template).
+///
+/// Layout: Int16 description length, the description (UTF-16 characters
+/// for R2007+, bytes before), then UInt16 MEASUREMENT.
+pub struct DwgTemplateReader;
+
+impl DwgTemplateReader {
+    /// Read the Template section.
+    pub fn read(reader: &mut dyn DwgStreamReader, version: DxfVersion) -> Result<DrawingTemplate> {
+        let length = reader.read_short()?;
+
+        let description = if length <= 0 {
+            String::new()
+        } else if version >= DxfVersion::AC1021 {
+            let bytes = reader.read_bytes(length as usize * 2)?;
+            let utf16: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                .collect();
+            String::from_utf16_lossy(&utf16).replace('\0', "")
+        } else {
+            let bytes = reader.read_bytes(length as usize)?;
+            String::from_utf8_lossy(&bytes).replace('\0', "")
+        };
+
+        let measurement = reader.read_short()?;
+
+        Ok(DrawingTemplate {
+            description,
+            measurement,
+        })
+    }
+}
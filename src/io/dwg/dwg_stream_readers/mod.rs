@@ -4,6 +4,8 @@
 pub mod dwg_app_info_reader;
 #[path = "DwgClassesReader.rs"]
 pub mod dwg_classes_reader;
+#[path = "DwgFileDepListReader.rs"]
+pub mod dwg_file_dep_list_reader;
 #[path = "DwgHandleReader.rs"]
 pub mod dwg_handle_reader;
 #[path = "DwgHeaderReader.rs"]
@@ -22,6 +24,8 @@ pub mod dwg_object_reader_entities;
 pub mod dwg_object_reader_objects;
 #[path = "DwgPreviewReader.rs"]
 pub mod dwg_preview_reader;
+#[path = "DwgRevHistoryReader.rs"]
+pub mod dwg_rev_history_reader;
 #[path = "DwgStreamReaderAC12.rs"]
 pub mod dwg_stream_reader_ac12;
 #[path = "DwgStreamReaderAC15.rs"]
@@ -36,13 +40,17 @@ pub mod dwg_stream_reader_ac24;
 pub mod dwg_stream_reader_base;
 #[path = "DwgSummaryInfoReader.rs"]
 pub mod dwg_summary_info_reader;
+#[path = "DwgTemplateReader.rs"]
+pub mod dwg_template_reader;
 #[path = "IDwgStreamReader.rs"]
 pub mod idwg_stream_reader;
 
 pub use dwg_app_info_reader::{DwgAppInfo, DwgAppInfoReader};
+pub use dwg_file_dep_list_reader::DwgFileDepListReader;
 pub use dwg_lz77_ac18_decompressor::DwgLz77Ac18Decompressor;
 pub use dwg_lz77_ac21_decompressor::DwgLz77Ac21Decompressor;
 pub use dwg_preview_reader::{DwgPreview, DwgPreviewReader, PreviewType};
+pub use dwg_rev_history_reader::DwgRevHistoryReader;
 pub use dwg_stream_reader_ac12::DwgStreamReaderAc12;
 pub use dwg_stream_reader_ac15::DwgStreamReaderAc15;
 pub use dwg_stream_reader_ac18::DwgStreamReaderAc18;
@@ -50,4 +58,5 @@ pub use dwg_stream_reader_ac21::DwgStreamReaderAc21;
 pub use dwg_stream_reader_ac24::DwgStreamReaderAc24;
 pub use dwg_stream_reader_base::DwgStreamReaderBase;
 pub use dwg_summary_info_reader::{CadSummaryInfo, DwgSummaryInfoReader};
+pub use dwg_template_reader::DwgTemplateReader;
 pub use idwg_stream_reader::{DwgObjectType, DwgReferenceType, DwgStreamReader};
@@ -19,7 +19,7 @@ struct SectionEntry {
 }
 
 pub struct DwgFileHeaderWriterAc15 {
-    stream: Box<dyn Write + Send>,
+    stream: Cursor<Vec<u8>>,
     version: DxfVersion,
     code_page: String,
     version_string: String,
@@ -30,7 +30,6 @@ pub struct DwgFileHeaderWriterAc15 {
 
 impl DwgFileHeaderWriterAc15 {
     pub fn new(
-        stream: Box<dyn Write + Send>,
         version: DxfVersion,
         version_string: String,
         code_page: String,
@@ -67,7 +66,7 @@ impl DwgFileHeaderWriterAc15 {
             .collect();
 
         Self {
-            stream,
+            stream: Cursor::new(Vec::new()),
             version,
             code_page,
             version_string,
@@ -161,6 +160,13 @@ impl DwgFileHeaderWriterAc15 {
     }
 }
 
+impl DwgFileHeaderWriterAc15 {
+    /// Consume and return the output bytes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.stream.into_inner()
+    }
+}
+
 impl DwgFileHeaderWriter for DwgFileHeaderWriterAc15 {
     fn handle_section_offset(&self) -> i32 {
         let mut offset = FILE_HEADER_SIZE;
@@ -198,4 +204,8 @@ impl DwgFileHeaderWriter for DwgFileHeaderWriterAc15 {
 
         Ok(())
     }
+
+    fn into_bytes(self: Box<Self>) -> Vec<u8> {
+        self.into_inner()
+    }
 }
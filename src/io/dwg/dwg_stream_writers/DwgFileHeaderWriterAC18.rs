@@ -594,4 +594,8 @@ impl DwgFileHeaderWriter for DwgFileHeaderWriterAc18 {
 
         Ok(())
     }
+
+    fn into_bytes(self: Box<Self>) -> Vec<u8> {
+        self.into_inner()
+    }
 }
@@ -1,45 +1,307 @@
-//! AC21 (R2007) file header writer — extends AC18 with 0x480 header and AC21 compressor.
+//! AC21 (R2007) file header writer.
 //!
-//! Note: The C# original is incomplete (LZ77 AC21 compressor is not implemented).
+//! R2007 files keep the AC18 page/section organisation but store every page
+//! LZ77-AC21 compressed and Reed-Solomon encoded, without page headers:
+//!
+//! - 0x000: version string and the same leading fields as AC18
+//! - 0x080: 0x400-byte file header, RS(255,239) encoded with a factor of 3,
+//!   holding the compressed 0x110-byte metadata block
+//! - 0x480: data pages (RS(255,251)), then the section map and the page map
+//!   (system pages, RS(255,239)), then a copy of the file header
+//!
+//! The file header and data pages carry CRC-64 (ECMA-182) values, and each
+//! data page the AC18 checksum of its stored bytes. The CRC fields of the
+//! page map and section map are left at zero.
 
-use std::io::{Cursor, Write};
+use std::io::{Cursor, Seek, SeekFrom, Write};
 
 use crate::error::Result;
-use crate::io::dwg::{DwgLocalSectionMap, DwgSectionDescriptor};
+use crate::io::dwg::{
+    calculate, crc64, Dwg21CompressedMetadata, DwgLocalSectionMap, DwgSectionDefinition, DwgSectionDescriptor,
+    DwgSectionHash,
+};
 use crate::types::DxfVersion;
 
-use super::dwg_file_header_writer_ac18::DwgFileHeaderWriterAc18;
-use super::dwg_file_header_writer_base::write_magic_number;
+use super::dwg_file_header_writer_base::{check_empty_bytes, get_file_code_page};
 use super::dwg_lz77_ac21_compressor::DwgLz77Ac21Compressor;
 use super::idwg_stream_writer::{Compressor, DwgFileHeaderWriter};
 
+/// Size of the file header: 0x80 leading bytes + 0x400 encoded bytes.
 const AC21_FILE_HEADER_SIZE: usize = 0x480;
 
+/// Data bytes per RS block of data pages.
+const DATA_BLOCK_SIZE: usize = 0xFB;
+/// Data bytes per RS block of system pages and the file header.
+const SYSTEM_BLOCK_SIZE: usize = 0xEF;
+
 pub struct DwgFileHeaderWriterAc21 {
-    inner: DwgFileHeaderWriterAc18,
+    stream: Cursor<Vec<u8>>,
+    version_string: String,
+    code_page: String,
+    maintenance_version: i16,
+    descriptors: Vec<DwgSectionDescriptor>,
+    /// Page map entries: (page id, size on disk).
+    pages: Vec<(i64, i64)>,
 }
 
 impl DwgFileHeaderWriterAc21 {
     pub fn new(
-        version: DxfVersion,
+        _version: DxfVersion,
         version_string: String,
         code_page: String,
         maintenance_version: i16,
     ) -> Self {
+        let mut stream = Cursor::new(Vec::with_capacity(0x10000));
+        // Reserve space for file header
+        let _ = stream.write_all(&[0u8; AC21_FILE_HEADER_SIZE]);
+
         Self {
-            inner: DwgFileHeaderWriterAc18::new(
-                version,
-                version_string,
-                code_page,
-                maintenance_version,
-            ),
+            stream,
+            version_string,
+            code_page,
+            maintenance_version,
+            descriptors: Vec::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    /// Consume and return the output bytes.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.stream.into_inner()
+    }
+
+    /// Compress, RS-encode and write one data page of a section.
+    fn create_local_section(
+        &mut self,
+        descriptor_index: usize,
+        data: &[u8],
+        offset: usize,
+        is_compressed: bool,
+    ) -> Result<()> {
+        let (compressed, compressed_size) = compress_page(data, is_compressed);
+        let encoded = reed_solomon_encode(&compressed, DATA_BLOCK_SIZE, 1);
+
+        let page_number = self.pages.len() as i32 + 1;
+        let seeker = self.stream.position() as i64;
+        self.stream.write_all(&encoded)?;
+
+        let mut local = DwgLocalSectionMap::new();
+        local.page_number = page_number;
+        local.offset = offset as u64;
+        local.seeker = seeker;
+        local.size = encoded.len() as i64;
+        local.decompressed_size = data.len() as u64;
+        local.compressed_size = compressed_size as u64;
+        local.checksum = calculate(0, &compressed, 0, compressed_size) as u64;
+        local.crc = crc64(0, data);
+
+        self.pages.push((page_number as i64, local.size));
+
+        let descriptor = &mut self.descriptors[descriptor_index];
+        descriptor.page_count += 1;
+        descriptor.local_sections.push(local);
+        Ok(())
+    }
+
+    /// Compress, RS-encode and write a system page (section map or page
+    /// map). Returns (page id, compressed size).
+    fn write_system_page(&mut self, data: &[u8]) -> Result<(i64, u64)> {
+        let (compressed, compressed_size) = compress_page(data, true);
+        let encoded = reed_solomon_encode(&compressed, SYSTEM_BLOCK_SIZE, 1);
+
+        let id = self.pages.len() as i64 + 1;
+        self.stream.write_all(&encoded)?;
+        self.pages.push((id, encoded.len() as i64));
+        Ok((id, compressed_size as u64))
+    }
+
+    fn write_section_map(&mut self, meta: &mut Dwg21CompressedMetadata) -> Result<()> {
+        let mut data = Vec::new();
+
+        // Leading empty section
+        push_section_entry(&mut data, &DwgSectionDescriptor::with_name(""), 0);
+
+        for descriptor in &self.descriptors {
+            let hash = section_hash(&descriptor.name);
+            push_section_entry(&mut data, descriptor, hash);
+        }
+
+        let (id, compressed_size) = self.write_system_page(&data)?;
+        meta.sections_amount = self.descriptors.len() as u64 + 1;
+        meta.sections_map_id = id as u64;
+        meta.sections_map2_id = id as u64;
+        meta.sections_map_size_compressed = compressed_size;
+        meta.sections_map_size_uncompressed = data.len() as u64;
+        meta.sections_map_correction_factor = 1;
+        Ok(())
+    }
+
+    fn write_page_map(&mut self, meta: &mut Dwg21CompressedMetadata) -> Result<()> {
+        let seeker = self.stream.position();
+        let id = self.pages.len() as i64 + 1;
+
+        // The page map lists itself: iterate until its own size is stable
+        let mut size = 0i64;
+        let (data, compressed, compressed_size) = loop {
+            let mut data = Vec::with_capacity((self.pages.len() + 1) * 16);
+            for &(page_id, page_size) in self.pages.iter().chain(std::iter::once(&(id, size))) {
+                data.extend_from_slice(&page_size.to_le_bytes());
+                data.extend_from_slice(&page_id.to_le_bytes());
+            }
+
+            let (compressed, compressed_size) = compress_page(&data, true);
+            let encoded = reed_solomon_encode(&compressed, SYSTEM_BLOCK_SIZE, 1);
+            if encoded.len() as i64 == size {
+                break (data, encoded, compressed_size);
+            }
+            size = encoded.len() as i64;
+        };
+
+        self.stream.write_all(&compressed)?;
+        self.pages.push((id, size));
+
+        meta.pages_map_offset = seeker - AC21_FILE_HEADER_SIZE as u64;
+        meta.pages_map_id = id as u64;
+        meta.map2_offset = meta.pages_map_offset;
+        meta.map2_id = id as u64;
+        meta.pages_map_size_compressed = compressed_size as u64;
+        meta.pages_map_size_uncompressed = data.len() as u64;
+        meta.pages_map_correction_factor = 1;
+        meta.pages_amount = self.pages.len() as u64;
+        meta.pages_max_id = id as u64;
+        Ok(())
+    }
+
+    /// RS-encoded file header block (0x400 bytes).
+    fn build_file_header(meta: &Dwg21CompressedMetadata) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(0x110);
+        for value in [
+            meta.header_size,
+            meta.file_size,
+            meta.pages_map_crc_compressed,
+            meta.pages_map_correction_factor,
+            meta.pages_map_crc_seed,
+            meta.map2_offset,
+            meta.map2_id,
+            meta.pages_map_offset,
+            meta.pages_map_id,
+            meta.header2_offset,
+            meta.pages_map_size_compressed,
+            meta.pages_map_size_uncompressed,
+            meta.pages_amount,
+            meta.pages_max_id,
+            meta.unknown_0x20,
+            meta.unknown_0x40,
+            meta.pages_map_crc_uncompressed,
+            meta.unknown_0xf800,
+            meta.unknown_4,
+            meta.unknown_1,
+            meta.sections_amount,
+            meta.sections_map_crc_uncompressed,
+            meta.sections_map_size_compressed,
+            meta.sections_map2_id,
+            meta.sections_map_id,
+            meta.sections_map_size_uncompressed,
+            meta.sections_map_crc_compressed,
+            meta.sections_map_correction_factor,
+            meta.sections_map_crc_seed,
+            meta.stream_version,
+            meta.crc_seed,
+            meta.crc_seed_encoded,
+            meta.random_seed,
+            0,
+        ] {
+            raw.extend_from_slice(&value.to_le_bytes());
         }
+        // Header CRC-64, computed with its own field zeroed
+        let header_crc64 = crc64(0, &raw);
+        raw[0x108..].copy_from_slice(&header_crc64.to_le_bytes());
+
+        let mut compressed = Vec::new();
+        DwgLz77Ac21Compressor::new().compress(&raw, 0, raw.len(), &mut compressed);
+
+        // CRC, unknown key, compressed data CRC, ComprLen, Length2
+        let mut data = Vec::with_capacity(3 * SYSTEM_BLOCK_SIZE);
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&crc64(0, &compressed).to_le_bytes());
+        data.extend_from_slice(&(compressed.len() as i32).to_le_bytes());
+        data.extend_from_slice(&0i32.to_le_bytes());
+        data.extend_from_slice(&compressed);
+        let crc = crc64(0, &data);
+        data[..8].copy_from_slice(&crc.to_le_bytes());
+        data.resize(3 * SYSTEM_BLOCK_SIZE, 0);
+
+        let mut encoded = reed_solomon_encode(&data, SYSTEM_BLOCK_SIZE, 1);
+        encoded.resize(0x400, 0);
+        encoded
+    }
+
+    fn first_page_seeker(&self, name: &str) -> u32 {
+        self.descriptors
+            .iter()
+            .find(|d| d.name == name)
+            .and_then(|d| d.local_sections.first())
+            .map(|ls| ls.seeker as u32)
+            .unwrap_or(0)
+    }
+
+    fn write_file_meta_data(&mut self, header: &[u8]) -> Result<()> {
+        self.stream.seek(SeekFrom::Start(0))?;
+        let ver_bytes = self.version_string.as_bytes();
+        self.stream.write_all(&ver_bytes[..6.min(ver_bytes.len())])?;
+
+        // 5 bytes of 0
+        self.stream.write_all(&[0u8; 5])?;
+        // Maintenance release version
+        self.stream.write_all(&[self.maintenance_version as u8])?;
+        // 0x03
+        self.stream.write_all(&[3])?;
+
+        // Preview address
+        let preview_seeker = self.first_page_seeker(DwgSectionDefinition::PREVIEW);
+        self.stream.write_all(&preview_seeker.to_le_bytes())?;
+
+        // DWG version
+        self.stream.write_all(&[31])?;
+        // App maintenance release version
+        self.stream.write_all(&[self.maintenance_version as u8])?;
+
+        // Codepage
+        let cp = get_file_code_page(&self.code_page);
+        self.stream.write_all(&cp.to_le_bytes())?;
+        // 3 zero bytes
+        self.stream.write_all(&[0u8; 3])?;
+
+        // SecurityType
+        self.stream.write_all(&0i32.to_le_bytes())?;
+        // Unknown long
+        self.stream.write_all(&0i32.to_le_bytes())?;
+
+        // Summary info address
+        let summary_seeker = self.first_page_seeker(DwgSectionDefinition::SUMMARY_INFO);
+        self.stream.write_all(&summary_seeker.to_le_bytes())?;
+
+        // VBA Project Addr (0)
+        self.stream.write_all(&0u32.to_le_bytes())?;
+        // 0x80
+        self.stream.write_all(&0x80i32.to_le_bytes())?;
+
+        // App info address
+        let app_seeker = self.first_page_seeker(DwgSectionDefinition::APP_INFO);
+        self.stream.write_all(&app_seeker.to_le_bytes())?;
+
+        // 0x80: encoded file header
+        self.stream.seek(SeekFrom::Start(0x80))?;
+        self.stream.write_all(header)?;
+
+        Ok(())
     }
 }
 
 impl DwgFileHeaderWriter for DwgFileHeaderWriterAc21 {
     fn handle_section_offset(&self) -> i32 {
-        self.inner.handle_section_offset()
+        0
     }
 
     fn add_section(
@@ -49,10 +311,256 @@ impl DwgFileHeaderWriter for DwgFileHeaderWriterAc21 {
         is_compressed: bool,
         decomp_size: usize,
     ) {
-        self.inner.add_section(name, stream, is_compressed, decomp_size);
+        let mut descriptor = DwgSectionDescriptor::with_name(name);
+        let decomp_size = if decomp_size == 0 { 0x7400 } else { decomp_size };
+        descriptor.decompressed_size = decomp_size as u64;
+        descriptor.compressed_size = stream.len() as u64;
+        descriptor.set_compressed_code(if is_compressed { 2 } else { 1 });
+        descriptor.hash_code = Some(section_hash(name) as u64);
+        // Every R2007 data page is Reed-Solomon encoded
+        descriptor.encoding = Some(4);
+
+        self.descriptors.push(descriptor);
+        let index = self.descriptors.len() - 1;
+
+        let mut offset = 0usize;
+        while offset < stream.len() {
+            let size = decomp_size.min(stream.len() - offset);
+            let is_last = offset + size == stream.len();
+            if !(is_last && offset > 0 && check_empty_bytes(&stream, offset, size)) {
+                let _ = self.create_local_section(
+                    index,
+                    &stream[offset..offset + size],
+                    offset,
+                    is_compressed,
+                );
+            }
+            offset += size;
+        }
     }
 
     fn write_file(&mut self) -> Result<()> {
-        self.inner.write_file()
+        let mut meta = Dwg21CompressedMetadata::new();
+
+        self.write_section_map(&mut meta)?;
+        self.write_page_map(&mut meta)?;
+
+        // Second copy of the file header at the end of the file
+        let header2 = self.stream.position();
+        meta.header2_offset = header2 - AC21_FILE_HEADER_SIZE as u64;
+        meta.file_size = header2 + 0x400;
+
+        let header = Self::build_file_header(&meta);
+        self.stream.write_all(&header)?;
+        self.write_file_meta_data(&header)?;
+
+        Ok(())
+    }
+
+    fn into_bytes(self: Box<Self>) -> Vec<u8> {
+        self.into_inner()
+    }
+}
+
+/// Compress a page and pad it to a multiple of 8 bytes. Pages that do not
+/// shrink are stored as is; readers decompress only when the compressed
+/// size is below the decompressed size. Returns (page data, compressed
+/// size).
+fn compress_page(data: &[u8], is_compressed: bool) -> (Vec<u8>, usize) {
+    let mut page = Vec::new();
+    if is_compressed {
+        DwgLz77Ac21Compressor::new().compress(data, 0, data.len(), &mut page);
+    }
+    if page.is_empty() || page.len() >= data.len() {
+        page = data.to_vec();
+    }
+
+    let size = page.len();
+    page.resize((size + 7) & !7, 0);
+    (page, size)
+}
+
+/// Section map entry: sizes, flags, UTF-16 name and the page list.
+fn push_section_entry(dest: &mut Vec<u8>, descriptor: &DwgSectionDescriptor, hash: i32) {
+    let name: Vec<u16> = descriptor.name.encode_utf16().collect();
+    let name_length = if name.is_empty() { 0 } else { (name.len() + 1) * 2 };
+
+    dest.extend_from_slice(&descriptor.compressed_size.to_le_bytes());
+    dest.extend_from_slice(&descriptor.decompressed_size.to_le_bytes());
+    dest.extend_from_slice(&(descriptor.encrypted as u64).to_le_bytes());
+    dest.extend_from_slice(&(hash as u32 as u64).to_le_bytes());
+    dest.extend_from_slice(&(name_length as u64).to_le_bytes());
+    // Unknown
+    dest.extend_from_slice(&0u64.to_le_bytes());
+    dest.extend_from_slice(&descriptor.encoding.unwrap_or(4).to_le_bytes());
+    dest.extend_from_slice(&(descriptor.page_count as u64).to_le_bytes());
+
+    if name_length > 0 {
+        for c in name.iter().chain(std::iter::once(&0)) {
+            dest.extend_from_slice(&c.to_le_bytes());
+        }
+    }
+
+    for local in &descriptor.local_sections {
+        dest.extend_from_slice(&local.offset.to_le_bytes());
+        dest.extend_from_slice(&(local.size as u64).to_le_bytes());
+        dest.extend_from_slice(&(local.page_number as u64).to_le_bytes());
+        dest.extend_from_slice(&local.decompressed_size.to_le_bytes());
+        dest.extend_from_slice(&local.compressed_size.to_le_bytes());
+        // Checksum, CRC
+        dest.extend_from_slice(&local.checksum.to_le_bytes());
+        dest.extend_from_slice(&local.crc.to_le_bytes());
+    }
+}
+
+fn section_hash(name: &str) -> i32 {
+    let hash = match name {
+        DwgSectionDefinition::HEADER => DwgSectionHash::AcDbHeader,
+        DwgSectionDefinition::CLASSES => DwgSectionHash::AcDbClasses,
+        DwgSectionDefinition::HANDLES => DwgSectionHash::AcDbHandles,
+        DwgSectionDefinition::ACDB_OBJECTS => DwgSectionHash::AcDbAcDbObjects,
+        DwgSectionDefinition::OBJ_FREE_SPACE => DwgSectionHash::AcDbObjFreeSpace,
+        DwgSectionDefinition::TEMPLATE => DwgSectionHash::AcDbTemplate,
+        DwgSectionDefinition::SUMMARY_INFO => DwgSectionHash::AcDbSummaryInfo,
+        DwgSectionDefinition::PREVIEW => DwgSectionHash::AcDbPreview,
+        DwgSectionDefinition::APP_INFO => DwgSectionHash::AcDbAppInfo,
+        DwgSectionDefinition::AUX_HEADER => DwgSectionHash::AcDbAuxHeader,
+        DwgSectionDefinition::REV_HISTORY => DwgSectionHash::AcDbRevHistory,
+        DwgSectionDefinition::FILE_DEP_LIST => DwgSectionHash::AcDbFileDepList,
+        _ => DwgSectionHash::AcDbUnknown,
+    };
+    hash.as_i32()
+}
+
+// ── Reed-Solomon encoding ─────────────────────────────────────────────────
+
+/// GF(256) exponent and logarithm tables for the primitive polynomial
+/// x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_tables() -> ([u8; 512], [u8; 256]) {
+    let mut exp = [0u8; 512];
+    let mut log = [0u8; 256];
+    let mut x: u16 = 1;
+    for (i, e) in exp.iter_mut().take(255).enumerate() {
+        *e = x as u8;
+        log[x as usize] = i as u8;
+        x <<= 1;
+        if x & 0x100 != 0 {
+            x ^= 0x11D;
+        }
+    }
+    for i in 255..512 {
+        exp[i] = exp[i - 255];
+    }
+    (exp, log)
+}
+
+/// Encode `data` with RS(255, `block_size`) and interleave the blocks: byte
+/// `j` of block `i` is stored at `i + j * block_count`. The data is
+/// repeated `factor` times before encoding. The result is padded to a
+/// multiple of 8 bytes.
+fn reed_solomon_encode(data: &[u8], block_size: usize, factor: usize) -> Vec<u8> {
+    let mut source = Vec::with_capacity(data.len() * factor);
+    for _ in 0..factor {
+        source.extend_from_slice(data);
+    }
+
+    let block_count = source.len().div_ceil(block_size).max(1);
+    source.resize(block_count * block_size, 0);
+
+    let parity_size = 255 - block_size;
+    let (exp, log) = gf_tables();
+
+    // Generator polynomial with roots a^1..a^parity_size, highest degree first
+    let mut generator = vec![1u8];
+    for i in 1..=parity_size {
+        let root = exp[i];
+        let mut next = vec![0u8; generator.len() + 1];
+        for (j, &g) in generator.iter().enumerate() {
+            next[j] ^= g;
+            if g != 0 {
+                next[j + 1] ^= exp[log[g as usize] as usize + log[root as usize] as usize];
+            }
+        }
+        generator = next;
+    }
+
+    let mut encoded = vec![0u8; block_count * 255];
+    for (i, block) in source.chunks(block_size).enumerate() {
+        let mut parity = vec![0u8; parity_size];
+        for &b in block {
+            let feedback = b ^ parity[0];
+            parity.rotate_left(1);
+            parity[parity_size - 1] = 0;
+            if feedback != 0 {
+                let lf = log[feedback as usize] as usize;
+                for (k, p) in parity.iter_mut().enumerate() {
+                    let g = generator[k + 1];
+                    if g != 0 {
+                        *p ^= exp[lf + log[g as usize] as usize];
+                    }
+                }
+            }
+        }
+
+        for (j, &b) in block.iter().chain(parity.iter()).enumerate() {
+            encoded[i + j * block_count] = b;
+        }
+    }
+
+    let len = encoded.len();
+    encoded.resize((len + 7) & !7, 0);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Evaluate a codeword (highest degree first) at `x`.
+    fn evaluate(codeword: &[u8], x: u8) -> u8 {
+        let (exp, log) = gf_tables();
+        let mut result = 0u8;
+        for &c in codeword {
+            result = if result == 0 {
+                0
+            } else {
+                exp[log[result as usize] as usize + log[x as usize] as usize]
+            };
+            result ^= c;
+        }
+        result
+    }
+
+    #[test]
+    fn test_reed_solomon_codeword_has_generator_roots() {
+        let data: Vec<u8> = (0..239u32).map(|i| (i * 7 + 3) as u8).collect();
+        let encoded = reed_solomon_encode(&data, SYSTEM_BLOCK_SIZE, 1);
+        assert_eq!(&encoded[..239], &data[..]);
+
+        let (exp, _) = gf_tables();
+        for &root in &exp[1..=16] {
+            assert_eq!(evaluate(&encoded[..255], root), 0);
+        }
+    }
+
+    #[test]
+    fn test_reed_solomon_interleaving() {
+        let data: Vec<u8> = (0..3 * 251).map(|i| (i % 251) as u8).collect();
+        let encoded = reed_solomon_encode(&data, DATA_BLOCK_SIZE, 1);
+        assert_eq!(encoded.len(), (3 * 255 + 7) & !7);
+        for j in 0..251 {
+            for i in 0..3 {
+                assert_eq!(encoded[i + j * 3], data[i * 251 + j]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_section_hash() {
+        assert_eq!(
+            section_hash(DwgSectionDefinition::HEADER),
+            DwgSectionHash::AcDbHeader.as_i32()
+        );
+        assert_eq!(section_hash("Unknown"), 0);
     }
 }
@@ -34,7 +34,7 @@ impl DwgHandleWriter {
         let mut offset: u64 = 0;
         let mut initial_loc: i64 = 0;
 
-        let last_position = self.stream.position();
+        let mut last_position = self.stream.position();
 
         self.stream.write_all(&[0, 0])?;
 
@@ -53,7 +53,7 @@ impl DwgHandleWriter {
                 self.process_position(last_position)?;
                 offset = 0;
                 initial_loc = 0;
-                let last_position_new = self.stream.position();
+                last_position = self.stream.position();
                 self.stream.write_all(&[0, 0])?;
                 handle_off = handle - offset;
 
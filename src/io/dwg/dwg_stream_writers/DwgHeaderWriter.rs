@@ -3,13 +3,13 @@
 //! This writes the CadHeader (HeaderVariables) section with version-conditional
 //! blocks matching the DWG binary spec for R13 through R2018+.
 
-use std::io::{Cursor, Read as StdRead, Seek, SeekFrom, Write};
+use std::io::{Cursor, Read as StdRead, SeekFrom};
 
 use crate::document::HeaderVariables;
 use crate::error::Result;
 use crate::io::dwg::dwg_section_io::DwgSectionContext;
 use crate::io::dwg::{crc8_value, DwgSectionDefinition, START_SENTINELS, END_SENTINELS};
-use crate::types::{DxfVersion, Handle, Vector3};
+use crate::types::DxfVersion;
 use crate::io::dwg::dwg_stream_readers::idwg_stream_reader::DwgReferenceType;
 
 use super::dwg_stream_writer_base::DwgStreamWriterBase;
@@ -22,10 +22,8 @@ impl DwgHeaderWriter {
     pub fn write(version: DxfVersion, header: &HeaderVariables) -> Result<Vec<u8>> {
         let ctx = DwgSectionContext::new(version, DwgSectionDefinition::HEADER);
 
-        let mut section_stream = Cursor::new(Vec::<u8>::new());
         let mut writer: Box<dyn DwgStreamWriter> = if ctx.r2007_plus {
-            let w = DwgStreamWriterBase::get_merged_writer(version, Box::new(Cursor::new(Vec::new())), "windows-1252");
-            w
+            DwgStreamWriterBase::get_merged_writer(version, Box::new(Cursor::new(Vec::new())), "windows-1252")
         } else {
             DwgStreamWriterBase::get_stream_writer(version, Box::new(Cursor::new(Vec::new())), "windows-1252")
         };
@@ -88,7 +86,7 @@ impl DwgHeaderWriter {
         }
 
         writer.write_bit(header.user_timer)?;
-        writer.write_bit(header.spline_frame)?; // SKPOLY
+        writer.write_bit(header.sketch_polylines)?; // SKPOLY
         writer.write_bit(header.angle_direction != 0)?; // ANGDIR
         writer.write_bit(header.spline_frame)?; // SPLFRAME
 
@@ -164,7 +162,7 @@ impl DwgHeaderWriter {
         writer.write_bit_short(header.spline_type)?;
         writer.write_bit_short(header.shade_edge)?;
         writer.write_bit_short(header.shade_diffuse)?;
-        writer.write_bit_short(0)?; // UNITMODE
+        writer.write_bit_short(header.unit_mode)?; // UNITMODE
         writer.write_bit_short(header.max_active_viewports)?;
         writer.write_bit_short(header.isolines)?;
         writer.write_bit_short(header.multiline_justification)?;
@@ -217,7 +215,7 @@ impl DwgHeaderWriter {
         writer.write_cm_color(&header.current_entity_color)?;
 
         // HANDSEED — writes to the main stream (not handle)
-        writer.handle_reference(header.handle_seed)?;
+        writer.main_handle_reference(header.handle_seed)?;
 
         // Handle references
         writer.handle_reference_typed(DwgReferenceType::HardPointer, header.current_layer_handle.value())?;
@@ -255,12 +253,12 @@ impl DwgHeaderWriter {
             writer.write_bit_short(header.paper_ucs_ortho_view)?;
             writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
             // Orthographic origins (PUCSORGTOP/BOTTOM/LEFT/RIGHT/FRONT/BACK)
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
+            writer.write_3_bit_double(&header.paper_space_ucs_origin_top)?;
+            writer.write_3_bit_double(&header.paper_space_ucs_origin_bottom)?;
+            writer.write_3_bit_double(&header.paper_space_ucs_origin_left)?;
+            writer.write_3_bit_double(&header.paper_space_ucs_origin_right)?;
+            writer.write_3_bit_double(&header.paper_space_ucs_origin_front)?;
+            writer.write_3_bit_double(&header.paper_space_ucs_origin_back)?;
         }
 
         // Model space extents
@@ -281,12 +279,12 @@ impl DwgHeaderWriter {
             writer.handle_reference_typed(DwgReferenceType::HardPointer, header.ucs_ortho_ref.value())?;
             writer.write_bit_short(header.ucs_ortho_view)?;
             writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
-            writer.write_3_bit_double(&Vector3::ZERO)?;
+            writer.write_3_bit_double(&header.model_space_ucs_origin_top)?;
+            writer.write_3_bit_double(&header.model_space_ucs_origin_bottom)?;
+            writer.write_3_bit_double(&header.model_space_ucs_origin_left)?;
+            writer.write_3_bit_double(&header.model_space_ucs_origin_right)?;
+            writer.write_3_bit_double(&header.model_space_ucs_origin_front)?;
+            writer.write_3_bit_double(&header.model_space_ucs_origin_back)?;
 
             writer.write_variable_text(&header.dim_post)?;
             writer.write_variable_text(&header.dim_alt_post)?;
@@ -340,10 +338,10 @@ impl DwgHeaderWriter {
 
         // R2007+ dimension extensions
         if ctx.r2007_plus {
-            writer.write_bit_double(0.0)?; // DIMFXL
-            writer.write_bit_double(std::f64::consts::FRAC_PI_4)?; // DIMJOGANG
-            writer.write_bit_short(0)?; // DIMTFILL
-            writer.write_cm_color(&crate::types::Color::ByBlock)?; // DIMTFILLCLR
+            writer.write_bit_double(header.dim_fixed_ext_line_length)?; // DIMFXL
+            writer.write_bit_double(header.dim_jog_angle)?; // DIMJOGANG
+            writer.write_bit_short(header.dim_text_fill)?; // DIMTFILL
+            writer.write_cm_color(&header.dim_text_fill_color)?; // DIMTFILLCLR
         }
 
         // R2000+ dimension flags
@@ -360,7 +358,7 @@ impl DwgHeaderWriter {
         }
 
         if ctx.r2007_plus {
-            writer.write_bit_short(0)?; // DIMARCSYM
+            writer.write_bit_short(header.dim_arc_symbol_position)?; // DIMARCSYM
         }
 
         // Common dim text/center/tick
@@ -419,11 +417,11 @@ impl DwgHeaderWriter {
         }
 
         if ctx.r2007_plus {
-            writer.write_bit(false)?; // DIMFXLON
+            writer.write_bit(header.dim_fixed_ext_line_enabled)?; // DIMFXLON
         }
 
         if version >= DxfVersion::AC1024 {
-            writer.write_bit(false)?; // DIMTXTDIRECTION
+            writer.write_bit(header.dim_text_direction)?; // DIMTXTDIRECTION
             writer.write_bit_double(0.0)?; // DIMALTMZF
             writer.write_variable_text("")?; // DIMALTMZS
             writer.write_bit_double(0.0)?; // DIMMZF
@@ -556,13 +554,13 @@ impl DwgHeaderWriter {
 
             writer.write_bit_double(header.steps_per_second)?;
             writer.write_bit_double(header.step_size)?;
-            writer.write_bit_double(0.0)?; // 3DDWFPREC
+            writer.write_bit_double(header.dwf_precision_3d)?; // 3DDWFPREC
             writer.write_bit_double(header.lens_length)?;
             writer.write_bit_double(header.camera_height)?;
-            writer.write_byte(0)?; // SOLIDHIST
-            writer.write_byte(0)?; // SHOWHIST
-            writer.write_bit_double(0.0)?; // PSOLWIDTH
-            writer.write_bit_double(0.0)?; // PSOLHEIGHT
+            writer.write_byte(header.solid_history as u8)?; // SOLIDHIST
+            writer.write_byte(header.show_history as u8)?; // SHOWHIST
+            writer.write_bit_double(header.polysolid_width)?; // PSOLWIDTH
+            writer.write_bit_double(header.polysolid_height)?; // PSOLHEIGHT
             writer.write_bit_double(header.loft_angle1)?;
             writer.write_bit_double(header.loft_angle2)?;
             writer.write_bit_double(header.loft_magnitude1)?;
@@ -573,21 +571,21 @@ impl DwgHeaderWriter {
             writer.write_bit_double(header.longitude)?;
             writer.write_bit_double(header.north_direction)?;
             writer.write_bit_long(header.timezone)?;
-            writer.write_byte(0)?; // LIGHTGLYPHDISPLAY
-            writer.write_byte(b'0')?; // TILEMODELIGHTSYNCH
-            writer.write_byte(0)?; // DWFFRAME
-            writer.write_byte(0)?; // DGNFRAME
+            writer.write_byte(header.light_glyph_display as u8)?; // LIGHTGLYPHDISPLAY
+            writer.write_byte(header.tile_model_light_sync as u8)?; // TILEMODELIGHTSYNCH
+            writer.write_byte(header.dwf_frame as u8)?; // DWFFRAME
+            writer.write_byte(header.dgn_frame as u8)?; // DGNFRAME
 
             writer.write_bit(false)?; // unknown
 
             // INTERFERECOLOR
-            writer.write_cm_color(&crate::types::Color::ByBlock)?;
+            writer.write_cm_color(&header.interference_color)?;
 
-            writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?; // INTERFEREOBJVS
-            writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?; // INTERFEREVPVS
-            writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?; // DRAGVS
+            writer.handle_reference_typed(DwgReferenceType::HardPointer, header.interference_object_visual_style_handle.value())?; // INTERFEREOBJVS
+            writer.handle_reference_typed(DwgReferenceType::HardPointer, header.interference_viewport_visual_style_handle.value())?; // INTERFEREVPVS
+            writer.handle_reference_typed(DwgReferenceType::HardPointer, header.drag_visual_style_handle.value())?; // DRAGVS
 
-            writer.write_byte(0)?; // CSHADOW
+            writer.write_byte(header.current_shadow_mode as u8)?; // CSHADOW
             writer.write_bit_double(header.shadow_plane_location)?;
         }
 
@@ -652,7 +650,7 @@ impl DwgHeaderWriter {
 
         let crc = crc8_value(0xC0C1, &crc_data, 0, crc_data.len());
         output.extend_from_slice(&crc_data);
-        output.extend_from_slice(&(crc as u16).to_le_bytes());
+        output.extend_from_slice(&crc.to_le_bytes());
 
         output.extend_from_slice(&end_sentinel);
 
@@ -1,28 +1,312 @@
 //! LZ77 compressor for DWG AC21 (R2007) format.
 //!
-//! The C# original throws `NotImplementedException` — this is preserved here.
+//! Produces the opcode stream read by `DwgLz77Ac21Decompressor`: a literal
+//! run of at least 8 bytes, then matches, each one optionally followed by a
+//! literal run. Literal bytes are stored in the reordered layout the
+//! decompressor undoes.
+
+use crate::io::dwg::dwg_stream_readers::DwgLz77Ac21Decompressor;
 
-use crate::error::DxfError;
 use super::idwg_stream_writer::Compressor;
 
-pub struct DwgLz77Ac21Compressor;
+/// Size of the match hash table.
+const HASH_SIZE: usize = 0x8000;
+/// Largest back-reference offset (two-byte offset opcode).
+const MAX_OFFSET: usize = 0xFFFF;
+/// Longest match written by a single opcode.
+const MAX_MATCH: usize = 0xFF;
+/// Shortest match worth encoding.
+const MIN_MATCH: usize = 3;
+
+/// One unit of the compressed stream.
+enum Token {
+    /// Literal bytes `start..end` of the source.
+    Literal(usize, usize),
+    /// Copy `length` bytes starting `offset` bytes back.
+    Match { offset: usize, length: usize },
+}
+
+pub struct DwgLz77Ac21Compressor {
+    block: Vec<i32>,
+}
 
 impl DwgLz77Ac21Compressor {
     pub fn new() -> Self {
-        Self
+        Self {
+            block: vec![-1i32; HASH_SIZE],
+        }
+    }
+
+    fn restart_block(&mut self) {
+        for v in self.block.iter_mut() {
+            *v = -1;
+        }
+    }
+
+    fn hash(data: &[u8], pos: usize) -> usize {
+        let v = (data[pos] as usize) | ((data[pos + 1] as usize) << 8) | ((data[pos + 2] as usize) << 16);
+        (v.wrapping_mul(0x9E37_79B1) >> 15) & (HASH_SIZE - 1)
+    }
+
+    /// Register `pos` in the hash table and return the longest match found
+    /// at the previous position with the same hash.
+    fn find_match(&mut self, data: &[u8], pos: usize) -> Option<(usize, usize)> {
+        let index = Self::hash(data, pos);
+        let candidate = self.block[index];
+        self.block[index] = pos as i32;
+
+        if candidate < 0 {
+            return None;
+        }
+        let candidate = candidate as usize;
+        let offset = pos - candidate;
+        if offset == 0 || offset > MAX_OFFSET {
+            return None;
+        }
+
+        let max = MAX_MATCH.min(data.len() - pos);
+        let mut length = 0;
+        while length < max && data[candidate + length] == data[pos + length] {
+            length += 1;
+        }
+
+        if length >= MIN_MATCH {
+            Some((offset, length))
+        } else {
+            None
+        }
+    }
+
+    fn tokenize(&mut self, data: &[u8]) -> Vec<Token> {
+        let mut tokens = Vec::new();
+        let mut literal_start = 0;
+
+        // The stream must open with a literal run of at least 8 bytes
+        for pos in 0..8.min(data.len().saturating_sub(MIN_MATCH - 1)) {
+            let index = Self::hash(data, pos);
+            self.block[index] = pos as i32;
+        }
+
+        let mut pos = 8;
+        while pos + MIN_MATCH <= data.len() {
+            match self.find_match(data, pos) {
+                Some((offset, length)) => {
+                    if pos > literal_start {
+                        tokens.push(Token::Literal(literal_start, pos));
+                    }
+                    tokens.push(Token::Match { offset, length });
+
+                    let end = pos + length;
+                    for p in (pos + 1)..end.min(data.len() - (MIN_MATCH - 1)) {
+                        let index = Self::hash(data, p);
+                        self.block[index] = p as i32;
+                    }
+                    pos = end;
+                    literal_start = pos;
+                }
+                None => pos += 1,
+            }
+        }
+
+        if literal_start < data.len() {
+            tokens.push(Token::Literal(literal_start, data.len()));
+        }
+        tokens
+    }
+
+    /// Length of a literal run of 8 bytes or more: `length - 8` in one
+    /// byte, extended by a byte and then 16-bit words for long runs.
+    fn write_literal_length(dest: &mut Vec<u8>, length: usize) {
+        debug_assert!(length >= 8);
+        if length < 0x17 {
+            dest.push((length - 8) as u8);
+            return;
+        }
+
+        dest.push(0x0F);
+        let mut rest = length - 0x17;
+        if rest < 0xFF {
+            dest.push(rest as u8);
+            return;
+        }
+
+        dest.push(0xFF);
+        rest -= 0xFF;
+        while rest >= 0xFFFF {
+            dest.extend_from_slice(&0xFFFFu16.to_le_bytes());
+            rest -= 0xFFFF;
+        }
+        dest.extend_from_slice(&(rest as u16).to_le_bytes());
+    }
+
+    /// Literal bytes, reordered in 32-byte chunks so that the
+    /// decompressor's copy restores the original order.
+    fn write_literal(dest: &mut Vec<u8>, literal: &[u8]) {
+        for chunk in literal.chunks(32) {
+            let n = chunk.len();
+            let identity: Vec<u8> = (0..n as u8).collect();
+            let mut order = vec![0u8; n];
+            DwgLz77Ac21Decompressor::copy_reordered(&identity, 0, &mut order, 0, n as u32);
+
+            let mut reordered = vec![0u8; n];
+            for (k, &source) in order.iter().enumerate() {
+                reordered[source as usize] = chunk[k];
+            }
+            dest.extend_from_slice(&reordered);
+        }
+    }
+
+    /// Match opcode. `chained` is set when the previous token was a match
+    /// without trailing literal, where a high nibble of 0 would start a
+    /// literal run and long matches are escaped with 0xF0. `literal` is the
+    /// length (0-7) of the literal run stored in the low bits of the last
+    /// opcode byte.
+    fn write_match(dest: &mut Vec<u8>, offset: usize, length: usize, chained: bool, literal: usize) {
+        let literal = literal as u8;
+
+        if length <= 14 && offset <= 0x200 {
+            // Short: length in the high nibble, 9-bit offset
+            let o = offset - 1;
+            dest.push(((length as u8) << 4) | (o & 0x0F) as u8);
+            dest.push((((o >> 4) & 0x1F) as u8) << 3 | literal);
+        } else if length <= 18 && offset <= 0x2000 {
+            // 0x1_: 13-bit offset
+            let o = offset - 1;
+            dest.push(0x10 | (length - 3) as u8);
+            dest.push((o & 0xFF) as u8);
+            dest.push((((o >> 8) & 0x1F) as u8) << 3 | literal);
+        } else if (0x13..=0x32).contains(&length) && offset <= 0x1000 {
+            // 0x0_ (0xF_ when chained): 12-bit offset
+            let o = offset - 1;
+            let l = length - 0x13;
+            let op = (l & 0x0F) as u8;
+            dest.push(if chained { 0xF0 | op } else { op });
+            dest.push((o & 0xFF) as u8);
+            dest.push((((l & 0x10) as u8) << 3) | ((((o >> 8) & 0x0F) as u8) << 3) | literal);
+        } else {
+            // 0x2_: 16-bit offset
+            dest.push(0x20 | (length & 0x07) as u8);
+            dest.extend_from_slice(&(offset as u16).to_le_bytes());
+            dest.push((length & 0xF8) as u8 | literal);
+        }
+    }
+}
+
+impl Default for DwgLz77Ac21Compressor {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl Compressor for DwgLz77Ac21Compressor {
     fn compress(
         &mut self,
-        _source: &[u8],
-        _offset: usize,
-        _total_size: usize,
-        _dest: &mut Vec<u8>,
+        source: &[u8],
+        offset: usize,
+        total_size: usize,
+        dest: &mut Vec<u8>,
     ) {
-        // The original C# implementation throws NotImplementedException.
-        // AC21 (R2007) compression is not yet implemented.
-        panic!("DwgLZ77AC21Compressor::compress is not implemented");
+        let data = &source[offset..offset + total_size];
+        if data.is_empty() {
+            return;
+        }
+
+        if data.len() < 8 {
+            // 0x2_ opener: skip 3 bytes, the low bits of the last one hold
+            // a literal length below 8
+            dest.extend_from_slice(&[0x20, 0, 0, data.len() as u8]);
+            Self::write_literal(dest, data);
+            return;
+        }
+
+        self.restart_block();
+        let tokens = self.tokenize(data);
+
+        let mut chained = false;
+        let mut i = 0;
+        while i < tokens.len() {
+            match tokens[i] {
+                Token::Literal(start, end) => {
+                    // Only the first token reaches here, later literal runs
+                    // are written together with their preceding match
+                    Self::write_literal_length(dest, end - start);
+                    Self::write_literal(dest, &data[start..end]);
+                    chained = false;
+                }
+                Token::Match { offset, length } => {
+                    let next = match tokens.get(i + 1) {
+                        Some(Token::Literal(start, end)) => Some((*start, *end)),
+                        _ => None,
+                    };
+
+                    match next {
+                        Some((start, end)) if end - start < 8 => {
+                            Self::write_match(dest, offset, length, chained, end - start);
+                            Self::write_literal(dest, &data[start..end]);
+                            chained = false;
+                            i += 1;
+                        }
+                        Some((start, end)) => {
+                            Self::write_match(dest, offset, length, chained, 0);
+                            Self::write_literal_length(dest, end - start);
+                            Self::write_literal(dest, &data[start..end]);
+                            chained = false;
+                            i += 1;
+                        }
+                        None => {
+                            Self::write_match(dest, offset, length, chained, 0);
+                            chained = true;
+                        }
+                    }
+                }
+            }
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(data: &[u8]) {
+        let mut compressed = Vec::new();
+        DwgLz77Ac21Compressor::new().compress(data, 0, data.len(), &mut compressed);
+
+        let mut output = vec![0u8; data.len()];
+        DwgLz77Ac21Decompressor::decompress(&compressed, 0, compressed.len() as u32, &mut output);
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn test_roundtrip_short_input() {
+        roundtrip(b"abc");
+        roundtrip(b"abcdefgh");
+    }
+
+    #[test]
+    fn test_roundtrip_literals_only() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8).collect();
+        roundtrip(&data);
+    }
+
+    #[test]
+    fn test_roundtrip_repetitive_data() {
+        let mut data = Vec::new();
+        for i in 0..2000u32 {
+            data.extend_from_slice(b"AcDb:Header");
+            data.push((i % 7) as u8);
+            data.extend(std::iter::repeat_n(0, (i % 40) as usize));
+        }
+        roundtrip(&data);
+    }
+
+    #[test]
+    fn test_compresses_zeroes() {
+        let data = vec![0u8; 0x7400];
+        let mut compressed = Vec::new();
+        DwgLz77Ac21Compressor::new().compress(&data, 0, data.len(), &mut compressed);
+        assert!(compressed.len() < data.len() / 10);
+        roundtrip(&data);
     }
 }
@@ -185,6 +185,10 @@ impl DwgStreamWriter for DwgMergedStreamWriter {
         self.handle_writer.handle_reference_typed(ref_type, handle)
     }
 
+    fn main_handle_reference(&mut self, handle: u64) -> Result<()> {
+        self.main_writer.handle_reference(handle)
+    }
+
     fn write_spear_shift(&mut self) -> Result<()> {
         let main_size_bits = self.main_writer.position_in_bits();
         let text_size_bits = self.text_writer.position_in_bits();
@@ -447,6 +451,10 @@ impl DwgStreamWriter for DwgMergedStreamWriterAc14 {
         self.handle_writer.handle_reference_typed(ref_type, handle)
     }
 
+    fn main_handle_reference(&mut self, handle: u64) -> Result<()> {
+        self.main_writer.handle_reference(handle)
+    }
+
     fn write_spear_shift(&mut self) -> Result<()> {
         let pos = self.main_writer.position_in_bits();
 
@@ -0,0 +1,1960 @@
+//! DWG object writer — entities.
+//!
+//! Rust equivalent of the C# `DwgObjectWriter.Entities` partial class.
+
+use crate::entities::{
+    Arc, AttributeDefinition, AttributeEntity, Body, BoundaryEdge, Circle, Dimension, Ellipse,
+    EntityCommon, EntityType, Face3D, Hatch, Insert, Leader, Line, LwPolyline, MLine, MText,
+    Ole2Frame, Point, PolyfaceMesh, Polyline, Polyline2D, Polyline3D, PolygonMeshEntity, Region,
+    Shape, Silhouette, Solid, Solid3D, Spline, Text, Tolerance, Viewport, Wire,
+};
+use crate::entities::{AcisData, HooklineDirection};
+use crate::error::Result;
+use crate::io::dwg::dwg_stream_readers::idwg_stream_reader::DwgReferenceType;
+use crate::types::{Color, DxfVersion, Handle, Vector2, Vector3};
+
+use super::dwg_object_writer::{object_type, DwgObjectWriter};
+
+/// Fixed object type code used to write an entity, or `None` when the
+/// entity type is not supported by the DWG writer.
+pub(super) fn entity_object_type(version: DxfVersion, entity: &EntityType) -> Option<i16> {
+    let code = match entity {
+        EntityType::Point(_) => object_type::POINT,
+        EntityType::Line(_) => object_type::LINE,
+        EntityType::Circle(_) => object_type::CIRCLE,
+        EntityType::Arc(_) => object_type::ARC,
+        EntityType::Ellipse(_) => object_type::ELLIPSE,
+        EntityType::Polyline(polyline) => {
+            if polyline.flags.is_3d() {
+                object_type::POLYLINE_3D
+            } else {
+                object_type::POLYLINE_2D
+            }
+        }
+        EntityType::Polyline2D(_) => object_type::POLYLINE_2D,
+        EntityType::Polyline3D(_) => object_type::POLYLINE_3D,
+        EntityType::PolyfaceMesh(_) => object_type::POLYLINE_PFACE,
+        EntityType::PolygonMesh(_) => object_type::POLYLINE_MESH,
+        EntityType::LwPolyline(_) => object_type::LWPOLYLINE,
+        EntityType::Text(_) => object_type::TEXT,
+        EntityType::MText(_) => object_type::MTEXT,
+        EntityType::Spline(_) => object_type::SPLINE,
+        EntityType::Dimension(dimension) => match dimension {
+            Dimension::Ordinate(_) => object_type::DIMENSION_ORDINATE,
+            Dimension::Linear(_) => object_type::DIMENSION_LINEAR,
+            Dimension::Aligned(_) => object_type::DIMENSION_ALIGNED,
+            Dimension::Angular3Pt(_) => object_type::DIMENSION_ANG_3_PT,
+            Dimension::Angular2Ln(_) => object_type::DIMENSION_ANG_2_LN,
+            Dimension::Radius(_) => object_type::DIMENSION_RADIUS,
+            Dimension::Diameter(_) => object_type::DIMENSION_DIAMETER,
+        },
+        // HATCH has no fixed type code before R2000
+        EntityType::Hatch(_) if version >= DxfVersion::AC1015 => object_type::HATCH,
+        EntityType::Solid(_) => object_type::SOLID,
+        EntityType::Face3D(_) => object_type::FACE_3D,
+        EntityType::Insert(insert) => {
+            if insert.column_count > 1 || insert.row_count > 1 {
+                object_type::MINSERT
+            } else {
+                object_type::INSERT
+            }
+        }
+        EntityType::Ray(_) => object_type::RAY,
+        EntityType::XLine(_) => object_type::XLINE,
+        EntityType::Viewport(_) => object_type::VIEWPORT,
+        EntityType::AttributeDefinition(_) => object_type::ATTDEF,
+        EntityType::AttributeEntity(_) => object_type::ATTRIB,
+        EntityType::Leader(_) => object_type::LEADER,
+        EntityType::MLine(_) => object_type::MLINE,
+        EntityType::Solid3D(_) => object_type::SOLID_3D,
+        EntityType::Region(_) => object_type::REGION,
+        EntityType::Body(_) => object_type::BODY,
+        EntityType::Tolerance(_) => object_type::TOLERANCE,
+        EntityType::Shape(_) => object_type::SHAPE,
+        EntityType::Ole2Frame(_) => object_type::OLE2FRAME,
+        EntityType::Unknown(unknown)
+            if unknown.proxy_graphics.is_some() && version >= DxfVersion::AC1015 =>
+        {
+            object_type::PROXY_ENTITY
+        }
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Object drawing format (AcDb::AcDbDwgVersion, maintenance release)
+/// recorded with proxy entities.
+fn proxy_drawing_format(version: DxfVersion) -> (i32, i32) {
+    match version {
+        DxfVersion::AC1015 => (23, 0),
+        DxfVersion::AC1018 => (25, 0),
+        DxfVersion::AC1021 => (27, 0),
+        DxfVersion::AC1024 => (29, 0),
+        DxfVersion::AC1027 => (31, 0),
+        _ => (33, 0),
+    }
+}
+
+/// Data shared by TEXT, ATTRIB and ATTDEF.
+struct TextData<'t> {
+    insertion_point: Vector3,
+    alignment_point: Option<Vector3>,
+    normal: Vector3,
+    oblique_angle: f64,
+    rotation: f64,
+    height: f64,
+    width_factor: f64,
+    value: &'t str,
+    generation: i16,
+    horizontal_alignment: i16,
+    vertical_alignment: i16,
+    style: &'t str,
+}
+
+impl<'a> DwgObjectWriter<'a> {
+    /// Write an entity owned by a block record, including its sub-entities
+    /// (attributes, vertices, faces and SEQEND).
+    pub(super) fn write_entity(
+        &mut self,
+        handle: u64,
+        entity: &'a EntityType,
+        entity_mode: u8,
+        owner: u64,
+    ) -> Result<()> {
+        let Some(code) = entity_object_type(self.version, entity) else {
+            return Ok(());
+        };
+
+        match entity {
+            EntityType::Insert(insert) => {
+                return self.write_insert(handle, code, insert, entity_mode, owner)
+            }
+            EntityType::Polyline2D(polyline) => {
+                return self.write_polyline_2d(handle, polyline, entity_mode, owner)
+            }
+            EntityType::Polyline(polyline) => {
+                return self.write_polyline(handle, polyline, entity_mode, owner)
+            }
+            EntityType::Polyline3D(polyline) => {
+                return self.write_polyline_3d(handle, polyline, entity_mode, owner)
+            }
+            EntityType::PolyfaceMesh(mesh) => {
+                return self.write_polyface_mesh(handle, mesh, entity_mode, owner)
+            }
+            EntityType::PolygonMesh(mesh) => {
+                return self.write_polygon_mesh(handle, mesh, entity_mode, owner)
+            }
+            _ => {}
+        }
+
+        let graphics = match entity {
+            EntityType::Unknown(unknown) => unknown.proxy_graphics.as_ref().map(|g| g.data.as_slice()),
+            _ => None,
+        };
+
+        self.begin_object(code)?;
+        self.write_common_entity_data_with_graphics(handle, entity_mode, owner, entity.common(), graphics)?;
+
+        match entity {
+            EntityType::Point(point) => self.write_point(point)?,
+            EntityType::Line(line) => self.write_line(line)?,
+            EntityType::Circle(circle) => self.write_circle(circle)?,
+            EntityType::Arc(arc) => self.write_arc(arc)?,
+            EntityType::Ellipse(ellipse) => self.write_ellipse(ellipse)?,
+            EntityType::LwPolyline(polyline) => self.write_lw_polyline(polyline)?,
+            EntityType::Text(text) => self.write_text(text)?,
+            EntityType::MText(mtext) => self.write_mtext(mtext)?,
+            EntityType::Spline(spline) => self.write_spline(spline)?,
+            EntityType::Dimension(dimension) => self.write_dimension(dimension)?,
+            EntityType::Hatch(hatch) => self.write_hatch(hatch)?,
+            EntityType::Solid(solid) => self.write_solid(solid)?,
+            EntityType::Face3D(face) => self.write_face_3d(face)?,
+            EntityType::Ray(ray) => {
+                self.writer.write_3_bit_double(&ray.base_point)?;
+                self.writer.write_3_bit_double(&ray.direction)?;
+            }
+            EntityType::XLine(xline) => {
+                self.writer.write_3_bit_double(&xline.base_point)?;
+                self.writer.write_3_bit_double(&xline.direction)?;
+            }
+            EntityType::Viewport(viewport) => self.write_viewport(viewport)?,
+            EntityType::AttributeDefinition(attdef) => self.write_attribute_definition(attdef)?,
+            EntityType::AttributeEntity(attrib) => self.write_attribute(attrib)?,
+            EntityType::Leader(leader) => self.write_leader(leader)?,
+            EntityType::MLine(mline) => self.write_mline(mline)?,
+            EntityType::Solid3D(solid) => self.write_solid_3d(solid)?,
+            EntityType::Region(region) => self.write_region(region)?,
+            EntityType::Body(body) => self.write_body(body)?,
+            EntityType::Tolerance(tolerance) => self.write_tolerance(tolerance)?,
+            EntityType::Shape(shape) => self.write_shape(shape)?,
+            EntityType::Ole2Frame(frame) => self.write_ole2_frame(frame)?,
+            EntityType::Unknown(_) => self.write_proxy_entity()?,
+            _ => {}
+        }
+
+        self.end_object(handle)
+    }
+
+    /// Proxy entity without data of its own, written in place of an
+    /// unknown entity so its proxy graphics are kept.
+    fn write_proxy_entity(&mut self) -> Result<()> {
+        // Class ID BL (AcDbProxyEntity)
+        self.writer.write_bit_long(498)?;
+
+        // R2004+: subclass TV
+        if self.version > DxfVersion::AC1015 {
+            self.writer.write_variable_text("")?;
+        }
+
+        let (version, maintenance) = proxy_drawing_format(self.version);
+        if self.r2018_plus() {
+            // Version BL, Maintenance version BL
+            self.writer.write_bit_long(version)?;
+            self.writer.write_bit_long(maintenance)?;
+        } else {
+            // Drawing format BL: version | maintenance << 16
+            self.writer.write_bit_long(version | (maintenance << 16))?;
+        }
+
+        // Original data is DXF B
+        self.writer.write_bit(false)
+    }
+
+    // ------------------------------------------------------------------
+    // Simple geometry
+    // ------------------------------------------------------------------
+
+    fn write_point(&mut self, point: &Point) -> Result<()> {
+        self.writer.write_3_bit_double(&point.location)?;
+        self.writer.write_bit_thickness(point.thickness)?;
+        self.writer.write_bit_extrusion(&point.normal)?;
+        // X-axis angle BD
+        self.writer.write_bit_double(0.0)
+    }
+
+    fn write_line(&mut self, line: &Line) -> Result<()> {
+        if self.r13_14_only() {
+            self.writer.write_3_bit_double(&line.start)?;
+            self.writer.write_3_bit_double(&line.end)?;
+        } else {
+            // Z's are zero bit B
+            let z_zero = line.start.z == 0.0 && line.end.z == 0.0;
+            self.writer.write_bit(z_zero)?;
+            self.writer.write_raw_double(line.start.x)?;
+            self.writer
+                .write_bit_double_with_default(line.start.x, line.end.x)?;
+            self.writer.write_raw_double(line.start.y)?;
+            self.writer
+                .write_bit_double_with_default(line.start.y, line.end.y)?;
+            if !z_zero {
+                self.writer.write_raw_double(line.start.z)?;
+                self.writer
+                    .write_bit_double_with_default(line.start.z, line.end.z)?;
+            }
+        }
+
+        self.writer.write_bit_thickness(line.thickness)?;
+        self.writer.write_bit_extrusion(&line.normal)
+    }
+
+    fn write_circle(&mut self, circle: &Circle) -> Result<()> {
+        self.writer.write_3_bit_double(&circle.center)?;
+        self.writer.write_bit_double(circle.radius)?;
+        self.writer.write_bit_thickness(circle.thickness)?;
+        self.writer.write_bit_extrusion(&circle.normal)
+    }
+
+    fn write_arc(&mut self, arc: &Arc) -> Result<()> {
+        self.writer.write_3_bit_double(&arc.center)?;
+        self.writer.write_bit_double(arc.radius)?;
+        self.writer.write_bit_thickness(arc.thickness)?;
+        self.writer.write_bit_extrusion(&arc.normal)?;
+        self.writer.write_bit_double(arc.start_angle)?;
+        self.writer.write_bit_double(arc.end_angle)
+    }
+
+    fn write_ellipse(&mut self, ellipse: &Ellipse) -> Result<()> {
+        self.writer.write_3_bit_double(&ellipse.center)?;
+        self.writer.write_3_bit_double(&ellipse.major_axis)?;
+        self.writer.write_3_bit_double(&ellipse.normal)?;
+        self.writer.write_bit_double(ellipse.minor_axis_ratio)?;
+        self.writer.write_bit_double(ellipse.start_parameter)?;
+        self.writer.write_bit_double(ellipse.end_parameter)
+    }
+
+    fn write_solid(&mut self, solid: &Solid) -> Result<()> {
+        self.writer.write_bit_thickness(solid.thickness)?;
+        // Elevation BD, 4 corners 2RD
+        self.writer.write_bit_double(solid.first_corner.z)?;
+        for corner in [
+            solid.first_corner,
+            solid.second_corner,
+            solid.third_corner,
+            solid.fourth_corner,
+        ] {
+            self.writer.write_2_raw_double(&Vector2::new(corner.x, corner.y))?;
+        }
+        self.writer.write_bit_extrusion(&solid.normal)
+    }
+
+    fn write_face_3d(&mut self, face: &Face3D) -> Result<()> {
+        let corners = [
+            face.first_corner,
+            face.second_corner,
+            face.third_corner,
+            face.fourth_corner,
+        ];
+        let invisible = face.invisible_edges.bits() as i16;
+
+        if self.r13_14_only() {
+            for corner in &corners {
+                self.writer.write_3_bit_double(corner)?;
+            }
+            return self.writer.write_bit_short(invisible);
+        }
+
+        // Has no flag ind. B, Z is zero bit B
+        let z_zero = face.first_corner.z == 0.0;
+        self.writer.write_bit(invisible == 0)?;
+        self.writer.write_bit(z_zero)?;
+
+        // 1st corner x RD, y RD, [z RD]
+        self.writer.write_raw_double(face.first_corner.x)?;
+        self.writer.write_raw_double(face.first_corner.y)?;
+        if !z_zero {
+            self.writer.write_raw_double(face.first_corner.z)?;
+        }
+
+        // 2nd-4th corners 3DD, each defaulting to the previous one
+        for pair in corners.windows(2) {
+            self.writer.write_3_bit_double_with_default(&pair[0], &pair[1])?;
+        }
+
+        // Invis flags BS
+        if invisible != 0 {
+            self.writer.write_bit_short(invisible)?;
+        }
+        Ok(())
+    }
+
+    fn write_shape(&mut self, shape: &Shape) -> Result<()> {
+        self.writer.write_3_bit_double(&shape.insertion_point)?;
+        self.writer.write_bit_double(shape.size)?;
+        self.writer.write_bit_double(shape.rotation)?;
+        self.writer.write_bit_double(shape.relative_x_scale)?;
+        self.writer.write_bit_double(shape.oblique_angle)?;
+        self.writer.write_bit_double(shape.thickness)?;
+        self.writer.write_bit_short(shape.shape_number as i16)?;
+        self.writer.write_3_bit_double(&shape.normal)?;
+
+        // Shapefile (hard pointer)
+        let style = match shape.style_handle {
+            Some(handle) if !handle.is_null() => handle.value(),
+            _ => self.text_style_handle(&shape.style_name),
+        };
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, style)
+    }
+
+    // ------------------------------------------------------------------
+    // Curves
+    // ------------------------------------------------------------------
+
+    fn write_lw_polyline(&mut self, polyline: &LwPolyline) -> Result<()> {
+        let has_bulges = polyline.vertices.iter().any(|v| v.bulge != 0.0);
+        let has_widths = polyline
+            .vertices
+            .iter()
+            .any(|v| v.start_width != 0.0 || v.end_width != 0.0);
+
+        let mut flags: i16 = 0;
+        if polyline.constant_width != 0.0 {
+            flags |= 0x4;
+        }
+        if polyline.elevation != 0.0 {
+            flags |= 0x8;
+        }
+        if polyline.thickness != 0.0 {
+            flags |= 0x2;
+        }
+        if polyline.normal != Vector3::UNIT_Z {
+            flags |= 0x1;
+        }
+        if has_bulges {
+            flags |= 0x10;
+        }
+        if has_widths {
+            flags |= 0x20;
+        }
+        if polyline.is_closed {
+            flags |= 0x200;
+        }
+        self.writer.write_bit_short(flags)?;
+
+        if polyline.constant_width != 0.0 {
+            self.writer.write_bit_double(polyline.constant_width)?;
+        }
+        if polyline.elevation != 0.0 {
+            self.writer.write_bit_double(polyline.elevation)?;
+        }
+        if polyline.thickness != 0.0 {
+            self.writer.write_bit_double(polyline.thickness)?;
+        }
+        if polyline.normal != Vector3::UNIT_Z {
+            self.writer.write_3_bit_double(&polyline.normal)?;
+        }
+
+        let count = polyline.vertices.len() as i32;
+        self.writer.write_bit_long(count)?;
+        if has_bulges {
+            self.writer.write_bit_long(count)?;
+        }
+        if has_widths {
+            self.writer.write_bit_long(count)?;
+        }
+
+        let mut previous: Option<Vector2> = None;
+        for vertex in &polyline.vertices {
+            match previous {
+                Some(prev) if !self.r13_14_only() => {
+                    self.writer
+                        .write_2_bit_double_with_default(&prev, &vertex.location)?;
+                }
+                _ => self.writer.write_2_raw_double(&vertex.location)?,
+            }
+            previous = Some(vertex.location);
+        }
+
+        if has_bulges {
+            for vertex in &polyline.vertices {
+                self.writer.write_bit_double(vertex.bulge)?;
+            }
+        }
+        if has_widths {
+            for vertex in &polyline.vertices {
+                self.writer.write_bit_double(vertex.start_width)?;
+                self.writer.write_bit_double(vertex.end_width)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_spline(&mut self, spline: &Spline) -> Result<()> {
+        // Scenario BL: 1 = control points / knots, 2 = fit points
+        let fit_only = spline.control_points.is_empty() && !spline.fit_points.is_empty();
+        self.writer.write_bit_long(if fit_only { 2 } else { 1 })?;
+
+        if self.r2013_plus() {
+            // Spline flags BL, Knot parameter BL (15 = custom knots)
+            self.writer.write_bit_long(if fit_only { 1 } else { 0 })?;
+            self.writer.write_bit_long(if fit_only { 0 } else { 15 })?;
+        }
+
+        self.writer.write_bit_long(spline.degree)?;
+
+        if fit_only {
+            // Fit tolerance BD, Beg tan vec 3BD, End tan vec 3BD
+            self.writer.write_bit_double(0.0)?;
+            self.writer.write_3_bit_double(&Vector3::ZERO)?;
+            self.writer.write_3_bit_double(&Vector3::ZERO)?;
+            self.writer.write_bit_long(spline.fit_points.len() as i32)?;
+            for point in &spline.fit_points {
+                self.writer.write_3_bit_double(point)?;
+            }
+            return Ok(());
+        }
+
+        let weighted =
+            spline.flags.rational && spline.weights.len() == spline.control_points.len();
+
+        // Rational B, Closed B, Periodic B, Knot tol BD, Ctrl tol BD
+        self.writer.write_bit(spline.flags.rational)?;
+        self.writer.write_bit(spline.flags.closed)?;
+        self.writer.write_bit(spline.flags.periodic)?;
+        self.writer.write_bit_double(0.0000001)?;
+        self.writer.write_bit_double(0.0000001)?;
+
+        // Numknots BL, Numctrlpts BL, Weight present B
+        self.writer.write_bit_long(spline.knots.len() as i32)?;
+        self.writer.write_bit_long(spline.control_points.len() as i32)?;
+        self.writer.write_bit(weighted)?;
+
+        for knot in &spline.knots {
+            self.writer.write_bit_double(*knot)?;
+        }
+        for (index, point) in spline.control_points.iter().enumerate() {
+            self.writer.write_3_bit_double(point)?;
+            if weighted {
+                self.writer.write_bit_double(spline.weights[index])?;
+            }
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // Text
+    // ------------------------------------------------------------------
+
+    fn write_text(&mut self, text: &Text) -> Result<()> {
+        self.write_text_data(&TextData {
+            insertion_point: text.insertion_point,
+            alignment_point: text.alignment_point,
+            normal: text.normal,
+            oblique_angle: text.oblique_angle,
+            rotation: text.rotation,
+            height: text.height,
+            width_factor: text.width_factor,
+            value: &text.value,
+            generation: 0,
+            horizontal_alignment: text.horizontal_alignment as i16,
+            vertical_alignment: text.vertical_alignment as i16,
+            style: &text.style,
+        })
+    }
+
+    fn write_attribute(&mut self, attrib: &AttributeEntity) -> Result<()> {
+        let aligned = attrib.horizontal_alignment.to_value() != 0
+            || attrib.vertical_alignment.to_value() != 0;
+        self.write_text_data(&TextData {
+            insertion_point: attrib.insertion_point,
+            alignment_point: aligned.then_some(attrib.alignment_point),
+            normal: attrib.normal,
+            oblique_angle: attrib.oblique_angle,
+            rotation: attrib.rotation,
+            height: attrib.height,
+            width_factor: attrib.width_factor,
+            value: &attrib.value,
+            generation: attrib.text_generation_flags,
+            horizontal_alignment: attrib.horizontal_alignment.to_value(),
+            vertical_alignment: attrib.vertical_alignment.to_value(),
+            style: &attrib.text_style,
+        })?;
+        self.write_common_att_data(
+            &attrib.tag,
+            attrib.field_length,
+            attrib.flags.to_bits() as u8,
+            attrib.mtext_flag.to_value(),
+            attrib.lock_position,
+        )
+    }
+
+    fn write_attribute_definition(&mut self, attdef: &AttributeDefinition) -> Result<()> {
+        let aligned = attdef.horizontal_alignment.to_value() != 0
+            || attdef.vertical_alignment.to_value() != 0;
+        self.write_text_data(&TextData {
+            insertion_point: attdef.insertion_point,
+            alignment_point: aligned.then_some(attdef.alignment_point),
+            normal: attdef.normal,
+            oblique_angle: attdef.oblique_angle,
+            rotation: attdef.rotation,
+            height: attdef.height,
+            width_factor: attdef.width_factor,
+            value: &attdef.default_value,
+            generation: attdef.text_generation_flags,
+            horizontal_alignment: attdef.horizontal_alignment.to_value(),
+            vertical_alignment: attdef.vertical_alignment.to_value(),
+            style: &attdef.text_style,
+        })?;
+        self.write_common_att_data(
+            &attdef.tag,
+            attdef.field_length,
+            attdef.flags.to_bits() as u8,
+            attdef.mtext_flag.to_value(),
+            attdef.lock_position,
+        )?;
+
+        // R2010+: Version RC
+        if self.r2010_plus() {
+            self.writer.write_byte(0)?;
+        }
+        // Prompt TV
+        self.writer.write_variable_text(&attdef.prompt)
+    }
+
+    fn write_text_data(&mut self, text: &TextData<'_>) -> Result<()> {
+        let insert = text.insertion_point;
+        let align = text.alignment_point.unwrap_or(insert);
+        let style = self.text_style_handle(text.style);
+
+        if self.r13_14_only() {
+            // Elevation BD, Insertion pt 2RD, Alignment pt 2RD, Extrusion 3BD,
+            // Thickness BD, Oblique ang BD, Rotation ang BD, Height BD,
+            // Width factor BD
+            self.writer.write_bit_double(insert.z)?;
+            self.writer.write_2_raw_double(&Vector2::new(insert.x, insert.y))?;
+            self.writer.write_2_raw_double(&Vector2::new(align.x, align.y))?;
+            self.writer.write_3_bit_double(&text.normal)?;
+            self.writer.write_bit_double(0.0)?;
+            self.writer.write_bit_double(text.oblique_angle)?;
+            self.writer.write_bit_double(text.rotation)?;
+            self.writer.write_bit_double(text.height)?;
+            self.writer.write_bit_double(text.width_factor)?;
+            // Text value TV, Generation BS, Horiz align BS, Vert align BS
+            self.writer.write_variable_text(text.value)?;
+            self.writer.write_bit_short(text.generation)?;
+            self.writer.write_bit_short(text.horizontal_alignment)?;
+            self.writer.write_bit_short(text.vertical_alignment)?;
+        } else {
+            let mut data_flags: u8 = 0;
+            if insert.z == 0.0 {
+                data_flags |= 0x1;
+            }
+            if text.alignment_point.is_none() {
+                data_flags |= 0x2;
+            }
+            if text.oblique_angle == 0.0 {
+                data_flags |= 0x4;
+            }
+            if text.rotation == 0.0 {
+                data_flags |= 0x8;
+            }
+            if text.width_factor == 1.0 {
+                data_flags |= 0x10;
+            }
+            if text.generation == 0 {
+                data_flags |= 0x20;
+            }
+            if text.horizontal_alignment == 0 {
+                data_flags |= 0x40;
+            }
+            if text.vertical_alignment == 0 {
+                data_flags |= 0x80;
+            }
+
+            // DataFlags RC
+            self.writer.write_byte(data_flags)?;
+            if data_flags & 0x1 == 0 {
+                self.writer.write_raw_double(insert.z)?;
+            }
+            // Insertion pt 2RD
+            self.writer.write_2_raw_double(&Vector2::new(insert.x, insert.y))?;
+            if data_flags & 0x2 == 0 {
+                // Alignment pt 2DD, defaulting to the insertion point
+                self.writer.write_bit_double_with_default(insert.x, align.x)?;
+                self.writer.write_bit_double_with_default(insert.y, align.y)?;
+            }
+            // Extrusion BE, Thickness BT
+            self.writer.write_bit_extrusion(&text.normal)?;
+            self.writer.write_bit_thickness(0.0)?;
+
+            if data_flags & 0x4 == 0 {
+                self.writer.write_raw_double(text.oblique_angle)?;
+            }
+            if data_flags & 0x8 == 0 {
+                self.writer.write_raw_double(text.rotation)?;
+            }
+            // Height RD
+            self.writer.write_raw_double(text.height)?;
+            if data_flags & 0x10 == 0 {
+                self.writer.write_raw_double(text.width_factor)?;
+            }
+            // Text value TV
+            self.writer.write_variable_text(text.value)?;
+            if data_flags & 0x20 == 0 {
+                self.writer.write_bit_short(text.generation)?;
+            }
+            if data_flags & 0x40 == 0 {
+                self.writer.write_bit_short(text.horizontal_alignment)?;
+            }
+            if data_flags & 0x80 == 0 {
+                self.writer.write_bit_short(text.vertical_alignment)?;
+            }
+        }
+
+        // Style (hard pointer)
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, style)
+    }
+
+    fn write_common_att_data(
+        &mut self,
+        tag: &str,
+        field_length: i16,
+        flags: u8,
+        mtext_flag: i16,
+        lock_position: bool,
+    ) -> Result<()> {
+        if self.r2010_plus() {
+            // Version RC
+            self.writer.write_byte(0)?;
+        }
+        if self.r2018_plus() {
+            // Attribute type RC: 1 single line, 2 multiline, 4 constant multiline
+            self.writer
+                .write_byte(if mtext_flag == 0 { 1 } else { mtext_flag as u8 })?;
+        }
+
+        // Tag TV, Field length BS, Flags RC
+        self.writer.write_variable_text(tag)?;
+        self.writer.write_bit_short(field_length)?;
+        self.writer.write_byte(flags)?;
+
+        if self.r2007_plus() {
+            // Lock position flag B
+            self.writer.write_bit(lock_position)?;
+        }
+        Ok(())
+    }
+
+    fn write_mtext(&mut self, mtext: &MText) -> Result<()> {
+        let x_axis = Vector3::new(mtext.rotation.cos(), mtext.rotation.sin(), 0.0);
+        let rect_height = mtext.rectangle_height.unwrap_or(0.0);
+        let attachment = mtext.attachment_point as i16 + 1;
+        let drawing_direction: i16 = match mtext.drawing_direction {
+            crate::entities::DrawingDirection::LeftToRight => 1,
+            crate::entities::DrawingDirection::TopToBottom => 3,
+            crate::entities::DrawingDirection::ByStyle => 5,
+        };
+
+        // Insertion pt 3BD, Extrusion 3BD, X-axis dir 3BD, Rect width BD
+        self.writer.write_3_bit_double(&mtext.insertion_point)?;
+        self.writer.write_3_bit_double(&mtext.normal)?;
+        self.writer.write_3_bit_double(&x_axis)?;
+        self.writer.write_bit_double(mtext.rectangle_width)?;
+        if self.r2007_plus() {
+            // Rect height BD
+            self.writer.write_bit_double(rect_height)?;
+        }
+
+        // Text height BD, Attachment BS, Drawing dir BS
+        self.writer.write_bit_double(mtext.height)?;
+        self.writer.write_bit_short(attachment)?;
+        self.writer.write_bit_short(drawing_direction)?;
+        // Extents ht BD, Extents wid BD
+        self.writer.write_bit_double(0.0)?;
+        self.writer.write_bit_double(0.0)?;
+        // Text TV
+        self.writer.write_variable_text(&mtext.value)?;
+
+        // Style (hard pointer)
+        let style = self.text_style_handle(&mtext.style);
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, style)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Linespacing style BS (1 = at least), Linespacing factor BD,
+            // Unknown bit B
+            self.writer.write_bit_short(1)?;
+            self.writer.write_bit_double(mtext.line_spacing_factor)?;
+            self.writer.write_bit(false)?;
+        }
+
+        if self.r2004_plus() {
+            // Background flags BL
+            self.writer.write_bit_long(0)?;
+        }
+
+        if self.r2018_plus() {
+            // Is NOT annotative B
+            self.writer.write_bit(true)?;
+            // Version BS, Default flag B
+            self.writer.write_bit_short(4)?;
+            self.writer.write_bit(true)?;
+            // Registered application (hard pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+            // Attachment point BL, X-axis dir 3BD, Insertion point 3BD,
+            // Rect width BD, Rect height BD, Extents width BD, Extents height BD
+            self.writer.write_bit_long(attachment as i32)?;
+            self.writer.write_3_bit_double(&x_axis)?;
+            self.writer.write_3_bit_double(&mtext.insertion_point)?;
+            self.writer.write_bit_double(mtext.rectangle_width)?;
+            self.writer.write_bit_double(rect_height)?;
+            self.writer.write_bit_double(0.0)?;
+            self.writer.write_bit_double(0.0)?;
+            // Column type BS: 0 = no columns
+            self.writer.write_bit_short(0)?;
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // Inserts and their attributes
+    // ------------------------------------------------------------------
+
+    fn write_insert(
+        &mut self,
+        handle: u64,
+        code: i16,
+        insert: &Insert,
+        entity_mode: u8,
+        owner: u64,
+    ) -> Result<()> {
+        let attributes = self.attributes.remove(&handle).unwrap_or_default();
+        let attribute_handles: Vec<u64> = attributes
+            .iter()
+            .map(|a| match a.common().handle.value() {
+                0 => self.allocate_handle(),
+                h => h,
+            })
+            .collect();
+        let has_attributes = !attributes.is_empty();
+        let seqend = if has_attributes { self.allocate_handle() } else { 0 };
+
+        self.begin_object(code)?;
+        self.write_common_entity_data(handle, entity_mode, owner, &insert.common)?;
+
+        // Ins pt 3BD
+        self.writer.write_3_bit_double(&insert.insert_point)?;
+
+        let (x, y, z) = (insert.x_scale, insert.y_scale, insert.z_scale);
+        if self.r13_14_only() {
+            self.writer.write_3_bit_double(&Vector3::new(x, y, z))?;
+        } else if x == 1.0 && y == 1.0 && z == 1.0 {
+            // Data flags BB: 11 = all scales 1.0
+            self.writer.write_2_bits(0b11)?;
+        } else if x == 1.0 {
+            // 01: x is 1.0, y and z default to 1.0
+            self.writer.write_2_bits(0b01)?;
+            self.writer.write_bit_double_with_default(1.0, y)?;
+            self.writer.write_bit_double_with_default(1.0, z)?;
+        } else if x == y && x == z {
+            // 10: uniform scale
+            self.writer.write_2_bits(0b10)?;
+            self.writer.write_raw_double(x)?;
+        } else {
+            self.writer.write_2_bits(0b00)?;
+            self.writer.write_raw_double(x)?;
+            self.writer.write_bit_double_with_default(x, y)?;
+            self.writer.write_bit_double_with_default(x, z)?;
+        }
+
+        // Rotation BD, Extrusion 3BD, Has ATTRIBs B
+        self.writer.write_bit_double(insert.rotation)?;
+        self.writer.write_3_bit_double(&insert.normal)?;
+        self.writer.write_bit(has_attributes)?;
+
+        if self.r2004_plus() && has_attributes {
+            // Owned Object Count BL
+            self.writer.write_bit_long(attribute_handles.len() as i32)?;
+        }
+
+        if code == object_type::MINSERT {
+            // Numcols BS, Numrows BS, Col spacing BD, Row spacing BD
+            self.writer.write_bit_short(insert.column_count as i16)?;
+            self.writer.write_bit_short(insert.row_count as i16)?;
+            self.writer.write_bit_double(insert.column_spacing)?;
+            self.writer.write_bit_double(insert.row_spacing)?;
+        }
+
+        // Block header (hard pointer)
+        let block = self.block_record_handle(&insert.block_name);
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, block)?;
+
+        if has_attributes {
+            self.write_owned_handles(&attribute_handles)?;
+            // Seqend (hard owner)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardOwnership, seqend)?;
+        }
+
+        self.end_object(handle)?;
+
+        if has_attributes {
+            self.prev_entity = 0;
+            self.next_entity = 0;
+            for (attribute, &attribute_handle) in attributes.iter().zip(&attribute_handles) {
+                if let EntityType::AttributeEntity(attrib) = attribute {
+                    self.begin_object(object_type::ATTRIB)?;
+                    self.write_common_entity_data(attribute_handle, 0, handle, &attrib.common)?;
+                    self.write_attribute(attrib)?;
+                    self.end_object(attribute_handle)?;
+                }
+            }
+            self.write_seqend(seqend, handle, &insert.common)?;
+        }
+        Ok(())
+    }
+
+    /// Owned sub-entity handles: first / last before R2004, the full
+    /// list afterwards.
+    fn write_owned_handles(&mut self, handles: &[u64]) -> Result<()> {
+        if self.r2004_plus() {
+            for &handle in handles {
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardOwnership, handle)?;
+            }
+        } else {
+            let first = handles.first().copied().unwrap_or(0);
+            let last = handles.last().copied().unwrap_or(0);
+            self.writer
+                .handle_reference_typed(DwgReferenceType::SoftPointer, first)?;
+            self.writer
+                .handle_reference_typed(DwgReferenceType::SoftPointer, last)?;
+        }
+        Ok(())
+    }
+
+    fn write_seqend(&mut self, handle: u64, owner: u64, parent: &EntityCommon) -> Result<()> {
+        let common = sub_entity_common(parent, &parent.layer);
+        self.begin_object(object_type::SEQEND)?;
+        self.write_common_entity_data(handle, 0, owner, &common)?;
+        self.end_object(handle)
+    }
+
+    // ------------------------------------------------------------------
+    // Polylines
+    // ------------------------------------------------------------------
+
+    /// Polyline header owned counts and handles shared by every
+    /// POLYLINE flavor, written after the type specific data.
+    fn write_polyline_owned(&mut self, owned: &[u64], seqend: u64) -> Result<()> {
+        self.write_owned_handles(owned)?;
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardOwnership, seqend)
+    }
+
+    fn write_owned_count(&mut self, owned: &[u64]) -> Result<()> {
+        if self.r2004_plus() {
+            // Owned Object Count BL
+            self.writer.write_bit_long(owned.len() as i32)?;
+        }
+        Ok(())
+    }
+
+    fn sub_entity_handles(&mut self, handles: impl Iterator<Item = Handle>) -> Vec<u64> {
+        handles
+            .map(|h| match h.value() {
+                0 => self.allocate_handle(),
+                h => h,
+            })
+            .collect()
+    }
+
+    fn write_polyline_2d(
+        &mut self,
+        handle: u64,
+        polyline: &Polyline2D,
+        entity_mode: u8,
+        owner: u64,
+    ) -> Result<()> {
+        let vertices = self.sub_entity_handles(polyline.vertices.iter().map(|_| Handle::NULL));
+        let seqend = self.allocate_handle();
+
+        self.begin_object(object_type::POLYLINE_2D)?;
+        self.write_common_entity_data(handle, entity_mode, owner, &polyline.common)?;
+
+        // Flags BS, Curve type BS, Start width BD, End width BD,
+        // Thickness BT, Elevation BD, Extrusion BE
+        self.writer.write_bit_short(polyline.flags.bits() as i16)?;
+        self.writer
+            .write_bit_short(polyline.smooth_surface as i16)?;
+        self.writer.write_bit_double(polyline.start_width)?;
+        self.writer.write_bit_double(polyline.end_width)?;
+        self.writer.write_bit_thickness(polyline.thickness)?;
+        self.writer.write_bit_double(polyline.elevation)?;
+        self.writer.write_bit_extrusion(&polyline.normal)?;
+        self.write_owned_count(&vertices)?;
+        self.write_polyline_owned(&vertices, seqend)?;
+        self.end_object(handle)?;
+
+        // Vertices are linked through the owned handles, not the entity chain.
+        self.prev_entity = 0;
+        self.next_entity = 0;
+
+        let common = sub_entity_common(&polyline.common, &polyline.common.layer);
+        for (vertex, &vertex_handle) in polyline.vertices.iter().zip(&vertices) {
+            self.begin_object(object_type::VERTEX_2D)?;
+            self.write_common_entity_data(vertex_handle, 0, handle, &common)?;
+
+            // Flags RC, Point 3BD
+            self.writer.write_byte(vertex.flags.bits())?;
+            self.writer.write_3_bit_double(&vertex.location)?;
+
+            // Start width BD (negative: end width is the same), End width BD
+            if vertex.start_width != 0.0 && vertex.start_width == vertex.end_width {
+                self.writer.write_bit_double(-vertex.start_width)?;
+            } else {
+                self.writer.write_bit_double(vertex.start_width)?;
+                self.writer.write_bit_double(vertex.end_width)?;
+            }
+
+            // Bulge BD, [R2010+ Vertex id BL], Tangent dir BD
+            self.writer.write_bit_double(vertex.bulge)?;
+            if self.r2010_plus() {
+                self.writer.write_bit_long(vertex.id)?;
+            }
+            self.writer.write_bit_double(vertex.curve_tangent)?;
+            self.end_object(vertex_handle)?;
+        }
+
+        self.write_seqend(seqend, handle, &polyline.common)
+    }
+
+    fn write_polyline(
+        &mut self,
+        handle: u64,
+        polyline: &Polyline,
+        entity_mode: u8,
+        owner: u64,
+    ) -> Result<()> {
+        if !polyline.flags.is_3d() {
+            let mut polyline_2d = Polyline2D::new();
+            polyline_2d.common = polyline.common.clone();
+            polyline_2d.flags = polyline.flags;
+            for vertex in &polyline.vertices {
+                let mut vertex_2d = crate::entities::Vertex2D::new(vertex.location);
+                vertex_2d.flags = vertex.flags;
+                polyline_2d.vertices.push(vertex_2d);
+            }
+            return self.write_polyline_2d(handle, &polyline_2d, entity_mode, owner);
+        }
+
+        let points: Vec<(Vector3, u8)> = polyline
+            .vertices
+            .iter()
+            .map(|v| (v.location, v.flags.bits()))
+            .collect();
+        let flags = if polyline.flags.is_closed() { 1 } else { 0 };
+        let curve = if polyline.flags.is_spline_fit() { 2 } else { 0 };
+        self.write_polyline_3d_data(
+            handle,
+            entity_mode,
+            owner,
+            &polyline.common,
+            (curve, flags),
+            &points,
+            &vec![Handle::NULL; points.len()],
+        )
+    }
+
+    fn write_polyline_3d(
+        &mut self,
+        handle: u64,
+        polyline: &Polyline3D,
+        entity_mode: u8,
+        owner: u64,
+    ) -> Result<()> {
+        let points: Vec<(Vector3, u8)> = polyline
+            .vertices
+            .iter()
+            .map(|v| (v.position, v.flags as u8))
+            .collect();
+        let handles: Vec<Handle> = polyline.vertices.iter().map(|v| v.handle).collect();
+
+        // Spline type: 1 = quadratic, 2 = cubic
+        let curve = match polyline.smooth_type.to_value() {
+            5 => 1,
+            6 => 2,
+            _ => 0,
+        };
+        let flags = if polyline.flags.closed { 1 } else { 0 };
+        self.write_polyline_3d_data(
+            handle,
+            entity_mode,
+            owner,
+            &polyline.common,
+            (curve, flags),
+            &points,
+            &handles,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn write_polyline_3d_data(
+        &mut self,
+        handle: u64,
+        entity_mode: u8,
+        owner: u64,
+        common: &EntityCommon,
+        (curve, flags): (u8, u8),
+        points: &[(Vector3, u8)],
+        handles: &[Handle],
+    ) -> Result<()> {
+        let vertices = self.sub_entity_handles(handles.iter().copied());
+        let seqend = self.allocate_handle();
+
+        self.begin_object(object_type::POLYLINE_3D)?;
+        self.write_common_entity_data(handle, entity_mode, owner, common)?;
+
+        // Flags RC (spline type), Flags RC (closed)
+        self.writer.write_byte(curve)?;
+        self.writer.write_byte(flags)?;
+        self.write_owned_count(&vertices)?;
+        self.write_polyline_owned(&vertices, seqend)?;
+        self.end_object(handle)?;
+
+        // Vertices are linked through the owned handles, not the entity chain.
+        self.prev_entity = 0;
+        self.next_entity = 0;
+
+        let vertex_common = sub_entity_common(common, &common.layer);
+        for (&(location, flags), &vertex_handle) in points.iter().zip(&vertices) {
+            self.begin_object(object_type::VERTEX_3D)?;
+            self.write_common_entity_data(vertex_handle, 0, handle, &vertex_common)?;
+            // Flags RC (32 = 3D polyline vertex), Point 3BD
+            self.writer.write_byte(flags | 0x20)?;
+            self.writer.write_3_bit_double(&location)?;
+            self.end_object(vertex_handle)?;
+        }
+
+        self.write_seqend(seqend, handle, common)
+    }
+
+    fn write_polyface_mesh(
+        &mut self,
+        handle: u64,
+        mesh: &PolyfaceMesh,
+        entity_mode: u8,
+        owner: u64,
+    ) -> Result<()> {
+        let vertices = self.sub_entity_handles(mesh.vertices.iter().map(|v| v.common.handle));
+        let faces = self.sub_entity_handles(mesh.faces.iter().map(|f| f.common.handle));
+        let seqend = match mesh.seqend_handle.map(|h| h.value()) {
+            Some(h) if h != 0 => h,
+            _ => self.allocate_handle(),
+        };
+        let owned: Vec<u64> = vertices.iter().chain(&faces).copied().collect();
+
+        self.begin_object(object_type::POLYLINE_PFACE)?;
+        self.write_common_entity_data(handle, entity_mode, owner, &mesh.common)?;
+
+        // Numverts BS, Numfaces BS
+        self.writer.write_bit_short(mesh.vertices.len() as i16)?;
+        self.writer.write_bit_short(mesh.faces.len() as i16)?;
+        self.write_owned_count(&owned)?;
+        self.write_polyline_owned(&owned, seqend)?;
+        self.end_object(handle)?;
+
+        // Vertices are linked through the owned handles, not the entity chain.
+        self.prev_entity = 0;
+        self.next_entity = 0;
+
+        for (vertex, &vertex_handle) in mesh.vertices.iter().zip(&vertices) {
+            self.begin_object(object_type::VERTEX_PFACE)?;
+            self.write_common_entity_data(vertex_handle, 0, handle, &vertex.common)?;
+            // Flags RC (128 + 64 = polyface mesh vertex), Point 3BD
+            self.writer
+                .write_byte(vertex.flags.bits() as u8 | 0xC0)?;
+            self.writer.write_3_bit_double(&vertex.location)?;
+            self.end_object(vertex_handle)?;
+        }
+
+        for (face, &face_handle) in mesh.faces.iter().zip(&faces) {
+            self.begin_object(object_type::VERTEX_PFACE_FACE)?;
+            self.write_common_entity_data(face_handle, 0, handle, &face.common)?;
+            // Vert index BS x4
+            for index in [face.index1, face.index2, face.index3, face.index4] {
+                self.writer.write_bit_short(index)?;
+            }
+            self.end_object(face_handle)?;
+        }
+
+        self.write_seqend(seqend, handle, &mesh.common)
+    }
+
+    fn write_polygon_mesh(
+        &mut self,
+        handle: u64,
+        mesh: &PolygonMeshEntity,
+        entity_mode: u8,
+        owner: u64,
+    ) -> Result<()> {
+        let vertices = self.sub_entity_handles(mesh.vertices.iter().map(|v| v.common.handle));
+        let seqend = self.allocate_handle();
+
+        self.begin_object(object_type::POLYLINE_MESH)?;
+        self.write_common_entity_data(handle, entity_mode, owner, &mesh.common)?;
+
+        // Flags BS, Curve type BS, M vert count BS, N vert count BS,
+        // M density BS, N density BS
+        self.writer.write_bit_short(mesh.flags.bits())?;
+        self.writer.write_bit_short(mesh.smooth_type as i16)?;
+        self.writer.write_bit_short(mesh.m_vertex_count)?;
+        self.writer.write_bit_short(mesh.n_vertex_count)?;
+        self.writer.write_bit_short(mesh.m_smooth_density)?;
+        self.writer.write_bit_short(mesh.n_smooth_density)?;
+        self.write_owned_count(&vertices)?;
+        self.write_polyline_owned(&vertices, seqend)?;
+        self.end_object(handle)?;
+
+        // Vertices are linked through the owned handles, not the entity chain.
+        self.prev_entity = 0;
+        self.next_entity = 0;
+
+        for (vertex, &vertex_handle) in mesh.vertices.iter().zip(&vertices) {
+            self.begin_object(object_type::VERTEX_MESH)?;
+            self.write_common_entity_data(vertex_handle, 0, handle, &vertex.common)?;
+            // Flags RC (64 = polygon mesh vertex), Point 3BD
+            self.writer.write_byte(vertex.flags as u8 | 0x40)?;
+            self.writer.write_3_bit_double(&vertex.location)?;
+            self.end_object(vertex_handle)?;
+        }
+
+        self.write_seqend(seqend, handle, &mesh.common)
+    }
+
+    // ------------------------------------------------------------------
+    // Dimensions
+    // ------------------------------------------------------------------
+
+    fn write_dimension(&mut self, dimension: &Dimension) -> Result<()> {
+        let base = dimension.base();
+
+        if self.r2010_plus() {
+            // Version RC
+            self.writer.write_byte(base.version)?;
+        }
+
+        // Extrusion 3BD, Text midpt 2RD, Elevation BD, Flags RC
+        self.writer.write_3_bit_double(&base.normal)?;
+        self.writer.write_2_raw_double(&Vector2::new(
+            base.text_middle_point.x,
+            base.text_middle_point.y,
+        ))?;
+        self.writer.write_bit_double(base.text_middle_point.z)?;
+        self.writer.write_byte(1)?;
+
+        // User text TV, Text rotation BD, Horiz dir BD,
+        // Ins X/Y/Z scale 3BD, Ins rotation BD
+        self.writer
+            .write_variable_text(base.user_text.as_deref().unwrap_or(""))?;
+        self.writer.write_bit_double(base.text_rotation)?;
+        self.writer.write_bit_double(base.horizontal_direction)?;
+        self.writer.write_3_bit_double(&Vector3::new(1.0, 1.0, 1.0))?;
+        self.writer.write_bit_double(0.0)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Attachment point BS, Linespacing style BS, Linespacing factor BD,
+            // Actual measurement BD
+            self.writer.write_bit_short(base.attachment_point as i16)?;
+            self.writer.write_bit_short(1)?;
+            self.writer.write_bit_double(base.line_spacing_factor)?;
+            self.writer.write_bit_double(base.actual_measurement)?;
+        }
+
+        if self.r2007_plus() {
+            // Unknown B, Flip arrow1 B, Flip arrow2 B
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+        }
+
+        // 12-pt 2RD
+        self.writer.write_2_raw_double(&Vector2::new(
+            base.insertion_point.x,
+            base.insertion_point.y,
+        ))?;
+
+        match dimension {
+            Dimension::Ordinate(dim) => {
+                self.writer.write_3_bit_double(&dim.definition_point)?;
+                self.writer.write_3_bit_double(&dim.feature_location)?;
+                self.writer.write_3_bit_double(&dim.leader_endpoint)?;
+                // Flags 2 RC: 1 = X-type ordinate
+                self.writer
+                    .write_byte(if dim.is_ordinate_type_x { 1 } else { 0 })?;
+            }
+            Dimension::Linear(dim) => {
+                self.writer.write_3_bit_double(&dim.first_point)?;
+                self.writer.write_3_bit_double(&dim.second_point)?;
+                self.writer.write_3_bit_double(&dim.definition_point)?;
+                self.writer.write_bit_double(dim.ext_line_rotation)?;
+                self.writer.write_bit_double(dim.rotation)?;
+            }
+            Dimension::Aligned(dim) => {
+                self.writer.write_3_bit_double(&dim.first_point)?;
+                self.writer.write_3_bit_double(&dim.second_point)?;
+                self.writer.write_3_bit_double(&dim.definition_point)?;
+                self.writer.write_bit_double(dim.ext_line_rotation)?;
+            }
+            Dimension::Angular3Pt(dim) => {
+                self.writer.write_3_bit_double(&dim.definition_point)?;
+                self.writer.write_3_bit_double(&dim.first_point)?;
+                self.writer.write_3_bit_double(&dim.second_point)?;
+                self.writer.write_3_bit_double(&dim.angle_vertex)?;
+            }
+            Dimension::Angular2Ln(dim) => {
+                self.writer.write_2_raw_double(&Vector2::new(
+                    dim.dimension_arc.x,
+                    dim.dimension_arc.y,
+                ))?;
+                self.writer.write_3_bit_double(&dim.first_point)?;
+                self.writer.write_3_bit_double(&dim.second_point)?;
+                self.writer.write_3_bit_double(&dim.angle_vertex)?;
+                self.writer.write_3_bit_double(&dim.definition_point)?;
+            }
+            Dimension::Radius(dim) => {
+                self.writer.write_3_bit_double(&dim.definition_point)?;
+                self.writer.write_3_bit_double(&dim.angle_vertex)?;
+                self.writer.write_bit_double(dim.leader_length)?;
+            }
+            Dimension::Diameter(dim) => {
+                self.writer.write_3_bit_double(&dim.definition_point)?;
+                self.writer.write_3_bit_double(&dim.angle_vertex)?;
+                self.writer.write_bit_double(dim.leader_length)?;
+            }
+        }
+
+        // Dimstyle, anonymous block (hard pointers)
+        let style = self.dim_style_handle(&base.style_name);
+        let block = self.block_record_handle(&base.block_name);
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, style)?;
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, block)
+    }
+
+    // ------------------------------------------------------------------
+    // Hatch
+    // ------------------------------------------------------------------
+
+    fn write_hatch(&mut self, hatch: &Hatch) -> Result<()> {
+        if self.r2004_plus() {
+            let gradient = &hatch.gradient_color;
+            // Is gradient fill BL, Reserved BL, Gradient angle BD, Gradient shift BD,
+            // Single color grad BL, Gradient tint BD, # of colors BL
+            self.writer
+                .write_bit_long(if gradient.enabled { 1 } else { 0 })?;
+            self.writer.write_bit_long(gradient.reserved)?;
+            self.writer.write_bit_double(gradient.angle)?;
+            self.writer.write_bit_double(gradient.shift)?;
+            self.writer
+                .write_bit_long(if gradient.is_single_color { 1 } else { 0 })?;
+            self.writer.write_bit_double(gradient.color_tint)?;
+            self.writer.write_bit_long(gradient.colors.len() as i32)?;
+            for entry in &gradient.colors {
+                self.writer.write_bit_double(entry.value)?;
+                self.writer.write_cm_color(&entry.color)?;
+            }
+            // Gradient name TV
+            self.writer.write_variable_text(&gradient.name)?;
+        }
+
+        // Z coord BD, Extrusion 3BD, Name TV, Solid fill B, Associative B
+        self.writer.write_bit_double(hatch.elevation)?;
+        self.writer.write_3_bit_double(&hatch.normal)?;
+        self.writer.write_variable_text(&hatch.pattern.name)?;
+        self.writer.write_bit(hatch.is_solid)?;
+        self.writer.write_bit(hatch.is_associative)?;
+
+        // Numpaths BL
+        self.writer.write_bit_long(hatch.paths.len() as i32)?;
+        let mut has_derived = false;
+        for path in &hatch.paths {
+            let polyline = path.edges.iter().find_map(|edge| match edge {
+                BoundaryEdge::Polyline(polyline) => Some(polyline),
+                _ => None,
+            });
+
+            let mut flags = path.flags.bits() as i32;
+            if polyline.is_some() {
+                flags |= 0x2;
+            } else {
+                flags &= !0x2;
+            }
+            has_derived |= flags & 0x4 != 0;
+
+            // Pathflag BL
+            self.writer.write_bit_long(flags)?;
+
+            if let Some(polyline) = polyline {
+                let has_bulges = polyline.has_bulge();
+                // Bulgespresent B, Closed B, Numpathsegs BL
+                self.writer.write_bit(has_bulges)?;
+                self.writer.write_bit(polyline.is_closed)?;
+                self.writer.write_bit_long(polyline.vertices.len() as i32)?;
+                for vertex in &polyline.vertices {
+                    self.writer
+                        .write_2_raw_double(&Vector2::new(vertex.x, vertex.y))?;
+                    if has_bulges {
+                        self.writer.write_bit_double(vertex.z)?;
+                    }
+                }
+            } else {
+                // Numpathsegs BL
+                self.writer.write_bit_long(path.edges.len() as i32)?;
+                for edge in &path.edges {
+                    self.write_hatch_edge(edge)?;
+                }
+            }
+
+            // Numboundaryobjhandles BL
+            self.writer
+                .write_bit_long(path.boundary_handles.len() as i32)?;
+        }
+
+        // Style BS, Patterntype BS
+        self.writer.write_bit_short(hatch.style as i16)?;
+        self.writer.write_bit_short(hatch.pattern_type as i16)?;
+
+        if !hatch.is_solid {
+            // Angle BD, Scale/spacing BD, Double flag B, Numdeflines BS
+            self.writer.write_bit_double(hatch.pattern_angle)?;
+            self.writer.write_bit_double(hatch.pattern_scale)?;
+            self.writer.write_bit(hatch.is_double)?;
+            self.writer
+                .write_bit_short(hatch.pattern.lines.len() as i16)?;
+            for line in &hatch.pattern.lines {
+                // Angle BD, Pt0 2BD, Offset 2BD, Numdashes BS, Dash lengths BD
+                self.writer.write_bit_double(line.angle)?;
+                self.writer.write_2_bit_double(&line.base_point)?;
+                self.writer.write_2_bit_double(&line.offset)?;
+                self.writer
+                    .write_bit_short(line.dash_lengths.len() as i16)?;
+                for dash in &line.dash_lengths {
+                    self.writer.write_bit_double(*dash)?;
+                }
+            }
+        }
+
+        if has_derived {
+            // Pixel size BD
+            self.writer.write_bit_double(hatch.pixel_size)?;
+        }
+
+        // Numseedpoints BL, Seed points 2RD
+        self.writer.write_bit_long(hatch.seed_points.len() as i32)?;
+        for seed in &hatch.seed_points {
+            self.writer.write_2_raw_double(seed)?;
+        }
+
+        for path in &hatch.paths {
+            for boundary in &path.boundary_handles {
+                // Boundary object (soft pointer)
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::SoftPointer, boundary.value())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_hatch_edge(&mut self, edge: &BoundaryEdge) -> Result<()> {
+        match edge {
+            BoundaryEdge::Line(line) => {
+                self.writer.write_byte(1)?;
+                self.writer.write_2_raw_double(&line.start)?;
+                self.writer.write_2_raw_double(&line.end)?;
+            }
+            BoundaryEdge::CircularArc(arc) => {
+                self.writer.write_byte(2)?;
+                self.writer.write_2_raw_double(&arc.center)?;
+                self.writer.write_bit_double(arc.radius)?;
+                self.writer.write_bit_double(arc.start_angle)?;
+                self.writer.write_bit_double(arc.end_angle)?;
+                self.writer.write_bit(arc.counter_clockwise)?;
+            }
+            BoundaryEdge::EllipticArc(ellipse) => {
+                self.writer.write_byte(3)?;
+                self.writer.write_2_raw_double(&ellipse.center)?;
+                self.writer.write_2_raw_double(&ellipse.major_axis_endpoint)?;
+                self.writer.write_bit_double(ellipse.minor_axis_ratio)?;
+                self.writer.write_bit_double(ellipse.start_angle)?;
+                self.writer.write_bit_double(ellipse.end_angle)?;
+                self.writer.write_bit(ellipse.counter_clockwise)?;
+            }
+            BoundaryEdge::Spline(spline) => {
+                self.writer.write_byte(4)?;
+                self.writer.write_bit_long(spline.degree)?;
+                self.writer.write_bit(spline.rational)?;
+                self.writer.write_bit(spline.periodic)?;
+                self.writer.write_bit_long(spline.knots.len() as i32)?;
+                self.writer
+                    .write_bit_long(spline.control_points.len() as i32)?;
+                for knot in &spline.knots {
+                    self.writer.write_bit_double(*knot)?;
+                }
+                for point in &spline.control_points {
+                    self.writer
+                        .write_2_raw_double(&Vector2::new(point.x, point.y))?;
+                    if spline.rational {
+                        self.writer.write_bit_double(point.z)?;
+                    }
+                }
+                if self.r2010_plus() {
+                    self.writer
+                        .write_bit_long(spline.fit_points.len() as i32)?;
+                    for point in &spline.fit_points {
+                        self.writer.write_2_raw_double(point)?;
+                    }
+                    if !spline.fit_points.is_empty() {
+                        self.writer.write_2_raw_double(&spline.start_tangent)?;
+                        self.writer.write_2_raw_double(&spline.end_tangent)?;
+                    }
+                }
+            }
+            // Polyline edges are written as a polyline path
+            BoundaryEdge::Polyline(_) => {}
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // Annotation
+    // ------------------------------------------------------------------
+
+    fn write_leader(&mut self, leader: &Leader) -> Result<()> {
+        // Unknown bit B, Annot type BS, path type BS
+        self.writer.write_bit(false)?;
+        self.writer
+            .write_bit_short(leader.creation_type.to_value())?;
+        self.writer.write_bit_short(leader.path_type.to_value())?;
+
+        // Numpts BL, points 3BD
+        self.writer.write_bit_long(leader.vertices.len() as i32)?;
+        for vertex in &leader.vertices {
+            self.writer.write_3_bit_double(vertex)?;
+        }
+
+        // Origin 3BD, Extrusion 3BD, x direction 3BD, offsettoblockinspt 3BD
+        let origin = leader.vertices.first().copied().unwrap_or(Vector3::ZERO);
+        self.writer.write_3_bit_double(&origin)?;
+        self.writer.write_3_bit_double(&leader.normal)?;
+        self.writer
+            .write_3_bit_double(&leader.horizontal_direction)?;
+        self.writer.write_3_bit_double(&leader.block_offset)?;
+
+        if self.version >= DxfVersion::AC1014 {
+            // Endptproj 3BD
+            self.writer.write_3_bit_double(&leader.annotation_offset)?;
+        }
+
+        if self.r13_14_only() {
+            // DIMGAP BD
+            self.writer.write_bit_double(0.0)?;
+        }
+
+        if self.version <= DxfVersion::AC1021 {
+            // Box height BD, Box width BD
+            self.writer.write_bit_double(leader.text_height)?;
+            self.writer.write_bit_double(leader.text_width)?;
+        }
+
+        // Hooklineonxdir B, Arrowheadon B
+        self.writer
+            .write_bit(leader.hookline_direction == HooklineDirection::Same)?;
+        self.writer.write_bit(leader.arrow_enabled)?;
+
+        if self.r13_14_only() {
+            // Arrowheadtype BS, Dimasz BD, Unknown B, Unknown B, Unknown BS,
+            // Byblockcolor BS, Unknown B, Unknown B
+            self.writer.write_bit_short(0)?;
+            self.writer.write_bit_double(0.0)?;
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+            self.writer.write_bit_short(0)?;
+            self.writer.write_bit_short(0)?;
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+        } else {
+            // Unknown BS, Unknown B, Unknown B
+            self.writer.write_bit_short(0)?;
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+        }
+
+        // Associated annotation (hard pointer), Dimstyle (hard pointer)
+        let style = self.dim_style_handle(&leader.dimension_style);
+        self.writer.handle_reference_typed(
+            DwgReferenceType::HardPointer,
+            leader.annotation_handle.value(),
+        )?;
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, style)
+    }
+
+    fn write_tolerance(&mut self, tolerance: &Tolerance) -> Result<()> {
+        if self.r13_14_only() {
+            // Unknown short BS, Height BD, Dimgap BD
+            self.writer.write_bit_short(0)?;
+            self.writer.write_bit_double(tolerance.text_height)?;
+            self.writer.write_bit_double(tolerance.dimension_gap)?;
+        }
+
+        // Ins pt 3BD, X direction 3BD, Extrusion 3BD, Text string TV
+        self.writer.write_3_bit_double(&tolerance.insertion_point)?;
+        self.writer.write_3_bit_double(&tolerance.direction)?;
+        self.writer.write_3_bit_double(&tolerance.normal)?;
+        self.writer.write_variable_text(&tolerance.text)?;
+
+        // Dimstyle (hard pointer)
+        let style = match tolerance.dimension_style_handle {
+            Some(handle) if !handle.is_null() => handle.value(),
+            _ => self.dim_style_handle(&tolerance.dimension_style_name),
+        };
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, style)
+    }
+
+    fn write_mline(&mut self, mline: &MLine) -> Result<()> {
+        let line_count = mline
+            .vertices
+            .first()
+            .map(|v| v.segments.len())
+            .unwrap_or(mline.style_element_count);
+
+        // Scale BD, Just RC, Base point 3BD, Extrusion BE
+        self.writer.write_bit_double(mline.scale_factor)?;
+        self.writer.write_byte(mline.justification as u8)?;
+        self.writer.write_3_bit_double(&mline.start_point)?;
+        self.writer.write_bit_extrusion(&mline.normal)?;
+
+        // Openclosed BS: 1 = open, 3 = closed
+        let closed = mline.flags.contains(crate::entities::MLineFlags::CLOSED);
+        self.writer.write_bit_short(if closed { 3 } else { 1 })?;
+
+        // Linesinstyle RC, Numverts BS
+        self.writer.write_byte(line_count as u8)?;
+        self.writer.write_bit_short(mline.vertices.len() as i16)?;
+
+        for vertex in &mline.vertices {
+            // Vertex 3BD, Vertex direction 3BD, Miter direction 3BD
+            self.writer.write_3_bit_double(&vertex.position)?;
+            self.writer.write_3_bit_double(&vertex.direction)?;
+            self.writer.write_3_bit_double(&vertex.miter)?;
+
+            for index in 0..line_count {
+                let segment = vertex.segments.get(index);
+                let parameters = segment.map(|s| s.parameters.as_slice()).unwrap_or(&[]);
+                let fills = segment
+                    .map(|s| s.area_fill_parameters.as_slice())
+                    .unwrap_or(&[]);
+
+                // Numsegparms BS, segparms BD
+                self.writer.write_bit_short(parameters.len() as i16)?;
+                for value in parameters {
+                    self.writer.write_bit_double(*value)?;
+                }
+                // Numareafillparms BS, areafillparms BD
+                self.writer.write_bit_short(fills.len() as i16)?;
+                for value in fills {
+                    self.writer.write_bit_double(*value)?;
+                }
+            }
+        }
+
+        // MLINESTYLE (hard pointer)
+        let style = mline
+            .style_handle
+            .map(|h| h.value())
+            .or_else(|| self.mline_style_handle(&mline.style_name))
+            .unwrap_or(0);
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, style)
+    }
+
+    // ------------------------------------------------------------------
+    // Viewport and OLE
+    // ------------------------------------------------------------------
+
+    fn write_viewport(&mut self, viewport: &Viewport) -> Result<()> {
+        // Center 3BD, Width BD, Height BD
+        self.writer.write_3_bit_double(&viewport.center)?;
+        self.writer.write_bit_double(viewport.width)?;
+        self.writer.write_bit_double(viewport.height)?;
+
+        let r2000_plus = self.version >= DxfVersion::AC1015;
+        if r2000_plus {
+            // View Target 3BD, View Direction 3BD, View Twist Angle BD,
+            // View Height BD, Lens Length BD, Front Clip Z BD, Back Clip Z BD,
+            // Snap Angle BD
+            self.writer.write_3_bit_double(&viewport.view_target)?;
+            self.writer.write_3_bit_double(&viewport.view_direction)?;
+            self.writer.write_bit_double(viewport.twist_angle)?;
+            self.writer.write_bit_double(viewport.view_height)?;
+            self.writer.write_bit_double(viewport.lens_length)?;
+            self.writer.write_bit_double(viewport.front_clip_z)?;
+            self.writer.write_bit_double(viewport.back_clip_z)?;
+            self.writer.write_bit_double(viewport.snap_angle)?;
+
+            // View Center 2RD, Snap Base 2RD, Snap Spacing 2RD, Grid Spacing 2RD
+            for point in [
+                viewport.view_center,
+                viewport.snap_base,
+                viewport.snap_spacing,
+                viewport.grid_spacing,
+            ] {
+                self.writer.write_2_raw_double(&Vector2::new(point.x, point.y))?;
+            }
+
+            // Circle Zoom BS
+            self.writer.write_bit_short(viewport.circle_sides)?;
+        }
+
+        if self.r2007_plus() {
+            // Grid Major BS
+            self.writer.write_bit_short(viewport.grid_major)?;
+        }
+
+        if r2000_plus {
+            // Frozen Layer Count BL, Status Flags BL, Style Sheet TV,
+            // Render Mode RC, UCS at origin B, UCS per Viewport B
+            self.writer
+                .write_bit_long(viewport.frozen_layers.len() as i32)?;
+            self.writer.write_bit_long(viewport.status.to_bits())?;
+            self.writer.write_variable_text("")?;
+            self.writer
+                .write_byte(viewport.render_mode.to_value() as u8)?;
+            self.writer
+                .write_bit(viewport.status.ucs_icon_at_origin)?;
+            self.writer.write_bit(viewport.ucs_per_viewport)?;
+
+            // UCS Origin 3BD, UCS X-axis 3BD, UCS Y-axis 3BD, UCS Elevation BD,
+            // UCS Ortho View Type BS
+            self.writer.write_3_bit_double(&viewport.ucs_origin)?;
+            self.writer.write_3_bit_double(&viewport.ucs_x_axis)?;
+            self.writer.write_3_bit_double(&viewport.ucs_y_axis)?;
+            self.writer.write_bit_double(viewport.elevation)?;
+            self.writer.write_bit_short(viewport.ucs_ortho_type)?;
+        }
+
+        if self.r2004_plus() {
+            // ShadePlot Mode BS
+            self.writer.write_bit_short(viewport.shade_plot_mode)?;
+        }
+
+        if self.r2007_plus() {
+            // Use def. lights B, Def. lighting type RC, Brightness BD,
+            // Contrast BD, Ambient light color CMC
+            self.writer.write_bit(viewport.default_lighting)?;
+            self.writer
+                .write_byte(viewport.default_lighting_type as u8)?;
+            self.writer.write_bit_double(viewport.brightness)?;
+            self.writer.write_bit_double(viewport.contrast)?;
+            self.writer
+                .write_cm_color(&Color::from_index(viewport.ambient_color as i16))?;
+        }
+
+        if self.r13_14_only() {
+            // Viewport entity header (hard pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+
+        if r2000_plus {
+            for layer in &viewport.frozen_layers {
+                // Frozen layer (hard pointer)
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardPointer, layer.value())?;
+            }
+            // Clip boundary (hard pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+
+        if self.version == DxfVersion::AC1015 {
+            // Viewport entity header (hard pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+
+        if r2000_plus {
+            // Named UCS, Base UCS (hard pointers)
+            self.writer.handle_reference_typed(
+                DwgReferenceType::HardPointer,
+                viewport.ucs_handle.value(),
+            )?;
+            self.writer.handle_reference_typed(
+                DwgReferenceType::HardPointer,
+                viewport.base_ucs_handle.value(),
+            )?;
+        }
+
+        if self.r2007_plus() {
+            // Background, Visual Style, Shadeplot, Sun (hard pointers)
+            for handle in [
+                viewport.background_handle,
+                viewport.visual_style_handle,
+                viewport.shade_plot_handle,
+                Handle::NULL,
+            ] {
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardPointer, handle.value())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_ole2_frame(&mut self, frame: &Ole2Frame) -> Result<()> {
+        // Flags BS
+        self.writer.write_bit_short(frame.version)?;
+        if self.version >= DxfVersion::AC1015 {
+            // Mode BS: 0 model space, 1 paper space
+            self.writer
+                .write_bit_short(if frame.is_paper_space { 1 } else { 0 })?;
+        }
+
+        // Data Length BL, Data
+        self.writer.write_bit_long(frame.binary_data.len() as i32)?;
+        self.writer.write_bytes(&frame.binary_data)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Unknown RC
+            self.writer.write_byte(0)?;
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // Modeler geometry
+    // ------------------------------------------------------------------
+
+    fn write_solid_3d(&mut self, solid: &Solid3D) -> Result<()> {
+        self.write_modeler_geometry(
+            &solid.acis_data,
+            &solid.point_of_reference,
+            &solid.wires,
+            &solid.silhouettes,
+        )?;
+        if self.r2007_plus() {
+            // History (hard pointer)
+            let history = solid.history_handle.map(|h| h.value()).unwrap_or(0);
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, history)?;
+        }
+        Ok(())
+    }
+
+    fn write_region(&mut self, region: &Region) -> Result<()> {
+        self.write_modeler_geometry(
+            &region.acis_data,
+            &region.point_of_reference,
+            &region.wires,
+            &region.silhouettes,
+        )?;
+        if self.r2007_plus() {
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+        Ok(())
+    }
+
+    fn write_body(&mut self, body: &Body) -> Result<()> {
+        self.write_modeler_geometry(
+            &body.acis_data,
+            &body.point_of_reference,
+            &body.wires,
+            &body.silhouettes,
+        )?;
+        if self.r2007_plus() {
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+        Ok(())
+    }
+
+    fn write_modeler_geometry(
+        &mut self,
+        acis: &AcisData,
+        point_of_reference: &Vector3,
+        wires: &[Wire],
+        silhouettes: &[Silhouette],
+    ) -> Result<()> {
+        let empty = acis.sat_data.is_empty() && acis.sab_data.is_empty();
+
+        // ACIS Empty bit B
+        self.writer.write_bit(empty)?;
+        if empty {
+            return Ok(());
+        }
+
+        // Unknown bit B, Version BS
+        self.writer.write_bit(false)?;
+        if acis.is_binary || acis.sat_data.is_empty() {
+            // SAB data runs up to the end of the object
+            self.writer.write_bit_short(2)?;
+            return self.writer.write_bytes(&acis.sab_data);
+        }
+
+        self.writer.write_bit_short(1)?;
+        // Block size BL, encrypted SAT data; a zero size ends the data
+        let encoded: Vec<u8> = acis
+            .sat_data
+            .bytes()
+            .map(|c| if c <= 32 { c } else { 159 - c })
+            .collect();
+        self.writer.write_bit_long(encoded.len() as i32)?;
+        self.writer.write_bytes(&encoded)?;
+        self.writer.write_bit_long(0)?;
+
+        // Wireframe data present B
+        let has_wires = !wires.is_empty() || !silhouettes.is_empty();
+        let has_point = *point_of_reference != Vector3::ZERO;
+        let wireframe = has_wires || has_point;
+        self.writer.write_bit(wireframe)?;
+        if !wireframe {
+            return Ok(());
+        }
+
+        // Point present B, [Point 3BD], Num isolines BL, Isoline present B
+        self.writer.write_bit(has_point)?;
+        if has_point {
+            self.writer.write_3_bit_double(point_of_reference)?;
+        }
+        self.writer.write_bit_long(0)?;
+        self.writer.write_bit(has_wires)?;
+        if !has_wires {
+            return Ok(());
+        }
+
+        // Num wires BL, wires
+        self.writer.write_bit_long(wires.len() as i32)?;
+        for wire in wires {
+            self.write_wire(wire)?;
+        }
+
+        // Num silhouettes BL
+        self.writer.write_bit_long(silhouettes.len() as i32)?;
+        for silhouette in silhouettes {
+            // VP id BL, VP Target 3BD, VP dir from target 3BD, VP up dir 3BD,
+            // VP perspective B, Num wires BL
+            self.writer.write_bit_long(silhouette.viewport_id as i32)?;
+            self.writer.write_3_bit_double(&silhouette.target)?;
+            self.writer.write_3_bit_double(&silhouette.view_direction)?;
+            self.writer.write_3_bit_double(&silhouette.up_vector)?;
+            self.writer.write_bit(silhouette.is_perspective)?;
+            self.writer.write_bit_long(silhouette.wires.len() as i32)?;
+            for wire in &silhouette.wires {
+                self.write_wire(wire)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_wire(&mut self, wire: &Wire) -> Result<()> {
+        // Wire type RC, Wire selection marker BL, Wire color BS / BL,
+        // Wire Acis Index BL
+        self.writer.write_byte(wire.wire_type as u8)?;
+        self.writer.write_bit_long(wire.selection_marker)?;
+        let color = wire.color.approximate_index();
+        if self.r2004_plus() {
+            self.writer.write_bit_long(color as i32)?;
+        } else {
+            self.writer.write_bit_short(color)?;
+        }
+        self.writer.write_bit_long(wire.acis_index)?;
+
+        // Num points BL, points 3BD
+        self.writer.write_bit_long(wire.points.len() as i32)?;
+        for point in &wire.points {
+            self.writer.write_3_bit_double(point)?;
+        }
+
+        // Transform present B
+        self.writer.write_bit(wire.has_transform)?;
+        if wire.has_transform {
+            self.writer.write_3_bit_double(&wire.x_axis)?;
+            self.writer.write_3_bit_double(&wire.y_axis)?;
+            self.writer.write_3_bit_double(&wire.z_axis)?;
+            self.writer.write_3_bit_double(&wire.translation)?;
+            self.writer
+                .write_3_bit_double(&Vector3::new(wire.scale, wire.scale, wire.scale))?;
+            self.writer.write_bit(wire.has_rotation)?;
+            self.writer.write_bit(wire.has_reflection)?;
+            self.writer.write_bit(wire.has_shear)?;
+        }
+        Ok(())
+    }
+}
+
+/// Common data for sub-entities (vertices, SEQEND) derived from their parent.
+fn sub_entity_common(parent: &EntityCommon, layer: &str) -> EntityCommon {
+    let mut common = EntityCommon::new();
+    common.layer = layer.to_string();
+    common.color = parent.color;
+    common.line_weight = parent.line_weight;
+    common.transparency = parent.transparency;
+    common.invisible = parent.invisible;
+    common
+}
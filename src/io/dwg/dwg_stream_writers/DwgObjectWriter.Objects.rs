@@ -0,0 +1,606 @@
+//! DWG object writer — non-graphical objects.
+//!
+//! Rust equivalent of the C# `DwgObjectWriter.Objects` partial class.
+
+use crate::error::Result;
+use crate::io::dwg::dwg_stream_readers::idwg_stream_reader::DwgReferenceType;
+use crate::notification::NotificationType;
+use crate::objects::{
+    Dictionary, DictionaryVariable, DictionaryWithDefault, Group, Layout, MLineStyle,
+    ObjectType, PlaceHolder, PlotSettings, Scale, SortEntitiesTable, XRecord, XRecordValue,
+};
+use crate::types::{DxfVersion, Handle, Vector2, Vector3};
+
+use super::dwg_object_writer::{object_type, DwgObjectWriter};
+
+impl<'a> DwgObjectWriter<'a> {
+    /// Write every object of the document, adding the header dictionaries
+    /// that have no object behind their handle.
+    pub(super) fn write_objects(&mut self) -> Result<()> {
+        let document = self.document;
+
+        let mut handles: Vec<&Handle> = document.objects.keys().collect();
+        handles.sort();
+        for handle in handles {
+            let object = &document.objects[handle];
+            self.write_object(handle.value(), object)?;
+        }
+
+        for dictionary in self.missing_dictionaries() {
+            self.write_dictionary(&dictionary)?;
+        }
+        Ok(())
+    }
+
+    fn write_object(&mut self, handle: u64, object: &ObjectType) -> Result<()> {
+        let r2000_plus = self.version >= DxfVersion::AC1015;
+        match object {
+            ObjectType::Dictionary(dictionary) => self.write_dictionary(dictionary),
+            ObjectType::DictionaryWithDefault(dictionary) => {
+                self.write_dictionary_with_default(dictionary)
+            }
+            ObjectType::XRecord(xrecord) => self.write_xrecord(xrecord),
+            ObjectType::Group(group) => self.write_group(group),
+            ObjectType::MLineStyle(style) => self.write_mline_style(style),
+            ObjectType::PlaceHolder(placeholder) => self.write_placeholder(placeholder),
+            ObjectType::Layout(layout) if r2000_plus => self.write_layout(layout),
+            ObjectType::PlotSettings(settings) if r2000_plus => {
+                self.write_plot_settings_object(settings)
+            }
+            ObjectType::Scale(scale) if r2000_plus => self.write_scale(scale),
+            ObjectType::SortEntitiesTable(table) if r2000_plus => {
+                self.write_sort_entities_table(table)
+            }
+            ObjectType::DictionaryVariable(variable) if r2000_plus => {
+                self.write_dictionary_variable(variable)
+            }
+            _ => {
+                self.notify(
+                    format!(
+                        "Object {:#X} of type {} not written: not implemented for {}",
+                        handle,
+                        object_type_name(object),
+                        self.version
+                    ),
+                    NotificationType::NotImplemented,
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Object type code of a class based object, `None` when the class is
+    /// not registered in the document.
+    fn class_object_type(&mut self, dxf_name: &str) -> Option<i16> {
+        let class_number = self
+            .document
+            .classes
+            .get_by_name(dxf_name)
+            .map(|c| c.class_number);
+        if class_number.is_none() {
+            self.notify(
+                format!("Class {} is not registered, objects of this type are skipped", dxf_name),
+                NotificationType::Warning,
+            );
+        }
+        class_number
+    }
+
+    /// Empty dictionaries for the header dictionary handles that have no
+    /// object, so that the named object dictionary tree is complete.
+    fn missing_dictionaries(&self) -> Vec<Dictionary> {
+        let header = &self.document.header;
+        let root = header.named_objects_dict_handle;
+        if root.is_null() {
+            return Vec::new();
+        }
+
+        let mut named = vec![
+            ("ACAD_GROUP", header.acad_group_dict_handle),
+            ("ACAD_MLINESTYLE", header.acad_mlinestyle_dict_handle),
+        ];
+        if self.version >= DxfVersion::AC1015 {
+            named.push(("ACAD_LAYOUT", header.acad_layout_dict_handle));
+            named.push(("ACAD_PLOTSETTINGS", header.acad_plotsettings_dict_handle));
+            named.push(("ACAD_PLOTSTYLENAME", header.acad_plotstylename_dict_handle));
+        }
+        named.retain(|(_, handle)| !handle.is_null());
+
+        let mut dictionaries = Vec::new();
+        if !self.document.objects.contains_key(&root) {
+            let mut dictionary = Dictionary::new();
+            dictionary.handle = root;
+            for (name, handle) in &named {
+                dictionary.add_entry(*name, *handle);
+            }
+            dictionaries.push(dictionary);
+        }
+
+        for (_, handle) in named {
+            if !self.document.objects.contains_key(&handle) {
+                let mut dictionary = Dictionary::new();
+                dictionary.handle = handle;
+                dictionary.owner = root;
+                dictionaries.push(dictionary);
+            }
+        }
+        dictionaries
+    }
+
+    // ------------------------------------------------------------------
+    // Dictionaries
+    // ------------------------------------------------------------------
+
+    fn write_dictionary(&mut self, dictionary: &Dictionary) -> Result<()> {
+        let handle = dictionary.handle.value();
+        self.begin_object(object_type::DICTIONARY)?;
+        self.write_common_non_entity_data(
+            handle,
+            dictionary.owner.value(),
+            &dictionary.reactors,
+            dictionary.xdictionary_handle,
+        )?;
+        self.write_dictionary_data(
+            &dictionary.entries,
+            dictionary.duplicate_cloning,
+            dictionary.hard_owner,
+        )?;
+        self.end_object(handle)
+    }
+
+    fn write_dictionary_with_default(&mut self, dictionary: &DictionaryWithDefault) -> Result<()> {
+        let Some(code) = self.class_object_type("DICTIONARYWDFLT") else {
+            return Ok(());
+        };
+
+        let handle = dictionary.handle.value();
+        self.begin_object(code)?;
+        self.write_common_non_entity_data(handle, dictionary.owner.value(), &[], None)?;
+        self.write_dictionary_data(
+            &dictionary.entries,
+            dictionary.duplicate_cloning,
+            dictionary.hard_owner,
+        )?;
+
+        // Default entry (hard pointer)
+        self.writer.handle_reference_typed(
+            DwgReferenceType::HardPointer,
+            dictionary.default_handle.value(),
+        )?;
+        self.end_object(handle)
+    }
+
+    fn write_dictionary_data(
+        &mut self,
+        entries: &[(String, Handle)],
+        cloning: i16,
+        hard_owner: bool,
+    ) -> Result<()> {
+        let entries: Vec<&(String, Handle)> = entries
+            .iter()
+            .filter(|(name, handle)| !name.is_empty() && !handle.is_null())
+            .collect();
+
+        // Numitems BL
+        self.writer.write_bit_long(entries.len() as i32)?;
+        if self.version == DxfVersion::AC1014 {
+            // Unknown R14 RC
+            self.writer.write_byte(0)?;
+        }
+        if self.version >= DxfVersion::AC1015 {
+            // Cloning flag BS, Hard Owner flag RC
+            self.writer.write_bit_short(cloning)?;
+            self.writer.write_byte(if hard_owner { 1 } else { 0 })?;
+        }
+
+        let reference = if hard_owner {
+            DwgReferenceType::HardOwnership
+        } else {
+            DwgReferenceType::SoftOwnership
+        };
+        for (name, handle) in entries {
+            // Text TV, item handle
+            self.writer.write_variable_text(name)?;
+            self.writer.handle_reference_typed(reference, handle.value())?;
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // XRecord
+    // ------------------------------------------------------------------
+
+    fn write_xrecord(&mut self, xrecord: &XRecord) -> Result<()> {
+        let handle = xrecord.handle.value();
+        self.begin_object(object_type::XRECORD)?;
+        self.write_common_non_entity_data(handle, xrecord.owner.value(), &[], None)?;
+
+        let mut data = Vec::new();
+        for entry in &xrecord.entries {
+            self.push_xrecord_entry(&mut data, entry.code, &entry.value);
+        }
+
+        // Numdatabytes BL, Databytes
+        self.writer.write_bit_long(data.len() as i32)?;
+        self.writer.write_bytes(&data)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Cloning flag BS
+            self.writer
+                .write_bit_short(xrecord.cloning_flags.to_value())?;
+        }
+        self.end_object(handle)
+    }
+
+    /// One XRecord item: RS group code followed by its raw value.
+    fn push_xrecord_entry(&self, data: &mut Vec<u8>, code: i32, value: &XRecordValue) {
+        data.extend_from_slice(&(code as i16).to_le_bytes());
+        match value {
+            XRecordValue::String(text) => self.push_eed_text(data, text),
+            XRecordValue::Double(value) => data.extend_from_slice(&value.to_le_bytes()),
+            XRecordValue::Point3D(x, y, z) => {
+                for value in [x, y, z] {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            XRecordValue::Int16(value) => data.extend_from_slice(&value.to_le_bytes()),
+            XRecordValue::Int32(value) => data.extend_from_slice(&value.to_le_bytes()),
+            XRecordValue::Int64(value) => data.extend_from_slice(&value.to_le_bytes()),
+            XRecordValue::Byte(value) => data.push(*value),
+            XRecordValue::Bool(value) => data.push(*value as u8),
+            XRecordValue::Handle(handle) => {
+                if code == 330 || code == 1005 {
+                    data.extend_from_slice(&handle.value().to_le_bytes());
+                } else {
+                    self.push_eed_text(data, &format!("{:X}", handle.value()));
+                }
+            }
+            XRecordValue::Chunk(bytes) => {
+                let bytes = &bytes[..bytes.len().min(u8::MAX as usize)];
+                data.push(bytes.len() as u8);
+                data.extend_from_slice(bytes);
+            }
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Group, MLineStyle, PlaceHolder
+    // ------------------------------------------------------------------
+
+    fn write_group(&mut self, group: &Group) -> Result<()> {
+        let handle = group.handle.value();
+        self.begin_object(object_type::GROUP)?;
+        self.write_common_non_entity_data(handle, group.owner.value(), &[], None)?;
+
+        // Str TV, Unnamed BS, Selectable BS, Numhandles BL
+        self.writer.write_variable_text(&group.description)?;
+        self.writer
+            .write_bit_short(if group.name.starts_with('*') { 1 } else { 0 })?;
+        self.writer
+            .write_bit_short(if group.selectable { 1 } else { 0 })?;
+        self.writer.write_bit_long(group.entities.len() as i32)?;
+
+        for entity in &group.entities {
+            // Entity (hard pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, entity.value())?;
+        }
+        self.end_object(handle)
+    }
+
+    fn write_mline_style(&mut self, style: &MLineStyle) -> Result<()> {
+        let handle = style.handle.value();
+        self.begin_object(object_type::MLINESTYLE)?;
+        self.write_common_non_entity_data(handle, style.owner.value(), &[], None)?;
+
+        // DWG flag bits differ from the DXF ones
+        let flags = &style.flags;
+        let mut bits: i16 = 0;
+        for (set, bit) in [
+            (flags.display_joints, 0x1),
+            (flags.fill_on, 0x2),
+            (flags.start_square_cap, 0x10),
+            (flags.start_round_cap, 0x20),
+            (flags.start_inner_arcs_cap, 0x40),
+            (flags.end_square_cap, 0x100),
+            (flags.end_round_cap, 0x200),
+            (flags.end_inner_arcs_cap, 0x400),
+        ] {
+            if set {
+                bits |= bit;
+            }
+        }
+
+        // Name TV, Desc TV, Flags BS, fillcolor CMC, startang BD, endang BD,
+        // linesinstyle RC
+        self.writer.write_variable_text(&style.name)?;
+        self.writer.write_variable_text(&style.description)?;
+        self.writer.write_bit_short(bits)?;
+        self.writer.write_cm_color(&style.fill_color)?;
+        self.writer.write_bit_double(style.start_angle)?;
+        self.writer.write_bit_double(style.end_angle)?;
+        self.writer.write_byte(style.elements.len() as u8)?;
+
+        for element in &style.elements {
+            // Offset BD, Color CMC
+            self.writer.write_bit_double(element.offset)?;
+            self.writer.write_cm_color(&element.color)?;
+            if self.r2018_plus() {
+                // Linetype (hard pointer)
+                let line_type = self.line_type_handle(&element.linetype);
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardPointer, line_type)?;
+            } else {
+                // Ltindex BS
+                let index = self.line_type_index(&element.linetype);
+                self.writer.write_bit_short(index)?;
+            }
+        }
+        self.end_object(handle)
+    }
+
+    /// Linetype index used by MLINESTYLE elements before R2018.
+    fn line_type_index(&self, name: &str) -> i16 {
+        if name.eq_ignore_ascii_case("BYLAYER") {
+            return 0x7FFF;
+        }
+        if name.eq_ignore_ascii_case("BYBLOCK") {
+            return 0x7FFE;
+        }
+        self.document
+            .line_types
+            .iter()
+            .filter(|l| {
+                !l.name.eq_ignore_ascii_case("BYLAYER") && !l.name.eq_ignore_ascii_case("BYBLOCK")
+            })
+            .position(|l| l.name.eq_ignore_ascii_case(name))
+            .map(|index| index as i16)
+            .unwrap_or(0x7FFF)
+    }
+
+    fn write_placeholder(&mut self, placeholder: &PlaceHolder) -> Result<()> {
+        let handle = placeholder.handle.value();
+        self.begin_object(object_type::ACDBPLACEHOLDER)?;
+        self.write_common_non_entity_data(handle, placeholder.owner.value(), &[], None)?;
+        self.end_object(handle)
+    }
+
+    // ------------------------------------------------------------------
+    // Layout and plot settings
+    // ------------------------------------------------------------------
+
+    fn write_layout(&mut self, layout: &Layout) -> Result<()> {
+        let handle = layout.handle.value();
+        self.begin_object(object_type::LAYOUT)?;
+        self.write_common_non_entity_data(
+            handle,
+            layout.owner.value(),
+            &layout.reactors,
+            layout.xdictionary_handle,
+        )?;
+
+        // Layouts carry their own plot settings, written with the defaults
+        let mut settings = PlotSettings::new(layout.name.clone());
+        settings.flags.model_type = layout.name.eq_ignore_ascii_case("Model");
+        self.write_plot_settings(&settings)?;
+
+        let (min_x, min_y) = layout.min_limits;
+        let (max_x, max_y) = layout.max_limits;
+        let (base_x, base_y, base_z) = layout.insertion_base;
+        let (ext_min_x, ext_min_y, ext_min_z) = layout.min_extents;
+        let (ext_max_x, ext_max_y, ext_max_z) = layout.max_extents;
+
+        // Layout name TV, Tab order BL, Layout flags BS, UCS origin 3BD,
+        // Limmin 2RD, Limmax 2RD, Inspoint 3BD, UCS X-axis 3BD, UCS Y-axis 3BD,
+        // Elevation BD, Orthoview type BS, Extmin 3BD, Extmax 3BD
+        self.writer.write_variable_text(&layout.name)?;
+        self.writer.write_bit_long(layout.tab_order as i32)?;
+        self.writer.write_bit_short(layout.flags)?;
+        self.writer.write_3_bit_double(&Vector3::ZERO)?;
+        self.writer.write_2_raw_double(&Vector2::new(min_x, min_y))?;
+        self.writer.write_2_raw_double(&Vector2::new(max_x, max_y))?;
+        self.writer
+            .write_3_bit_double(&Vector3::new(base_x, base_y, base_z))?;
+        self.writer.write_3_bit_double(&Vector3::UNIT_X)?;
+        self.writer.write_3_bit_double(&Vector3::UNIT_Y)?;
+        self.writer.write_bit_double(0.0)?;
+        self.writer.write_bit_short(0)?;
+        self.writer
+            .write_3_bit_double(&Vector3::new(ext_min_x, ext_min_y, ext_min_z))?;
+        self.writer
+            .write_3_bit_double(&Vector3::new(ext_max_x, ext_max_y, ext_max_z))?;
+
+        if self.r2004_plus() {
+            // Viewport count RL
+            self.writer.write_int(0)?;
+        }
+
+        // Paperspace block record (soft pointer), Last active viewport
+        // (soft pointer), Base UCS (hard pointer), Named UCS (hard pointer)
+        self.writer.handle_reference_typed(
+            DwgReferenceType::SoftPointer,
+            layout.block_record.value(),
+        )?;
+        self.writer
+            .handle_reference_typed(DwgReferenceType::SoftPointer, layout.viewport.value())?;
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+
+        self.end_object(handle)
+    }
+
+    fn write_plot_settings_object(&mut self, settings: &PlotSettings) -> Result<()> {
+        let Some(code) = self.class_object_type("PLOTSETTINGS") else {
+            return Ok(());
+        };
+
+        let handle = settings.handle.value();
+        self.begin_object(code)?;
+        self.write_common_non_entity_data(handle, settings.owner.value(), &[], None)?;
+        self.write_plot_settings(settings)?;
+        self.end_object(handle)
+    }
+
+    fn write_plot_settings(&mut self, settings: &PlotSettings) -> Result<()> {
+        // Page setup name TV, Printer/Config TV, Plot layout flags BS
+        self.writer.write_variable_text(&settings.page_name)?;
+        self.writer.write_variable_text(&settings.printer_name)?;
+        self.writer.write_bit_short(settings.flags.to_bits() as i16)?;
+
+        // Left, Bottom, Right, Top margins BD, Paper width BD, Paper height BD,
+        // Paper size TV, Plot origin 2BD
+        let margins = &settings.margins;
+        for value in [margins.left, margins.bottom, margins.right, margins.top] {
+            self.writer.write_bit_double(value)?;
+        }
+        self.writer.write_bit_double(settings.paper_width)?;
+        self.writer.write_bit_double(settings.paper_height)?;
+        self.writer.write_variable_text(&settings.paper_size)?;
+        self.writer
+            .write_2_bit_double(&Vector2::new(settings.origin_x, settings.origin_y))?;
+
+        // Paper units BS, Plot rotation BS, Plot type BS
+        self.writer.write_bit_short(settings.paper_units.to_code())?;
+        self.writer.write_bit_short(settings.rotation.to_code())?;
+        self.writer.write_bit_short(settings.plot_type.to_code())?;
+
+        // Window min 2BD, Window max 2BD
+        let window = &settings.plot_window;
+        self.writer
+            .write_2_bit_double(&Vector2::new(window.lower_left_x, window.lower_left_y))?;
+        self.writer
+            .write_2_bit_double(&Vector2::new(window.upper_right_x, window.upper_right_y))?;
+
+        if !self.r2004_plus() {
+            // Plot view name TV
+            self.writer.write_variable_text(&settings.plot_view_name)?;
+        }
+
+        // Real world units BD, Drawing units BD, Current style sheet TV,
+        // Scale type BS, Scale factor BD, Paper image origin 2BD
+        self.writer.write_bit_double(settings.scale_numerator)?;
+        self.writer.write_bit_double(settings.scale_denominator)?;
+        self.writer
+            .write_variable_text(&settings.current_style_sheet)?;
+        self.writer.write_bit_short(settings.scale_type.to_code())?;
+        self.writer.write_bit_double(
+            settings.scale_numerator / settings.scale_denominator.max(f64::EPSILON),
+        )?;
+        self.writer.write_2_bit_double(&Vector2::new(0.0, 0.0))?;
+
+        if self.r2004_plus() {
+            // Shade plot mode BS, Shade plot res level BS, Shade plot custom DPI BS,
+            // Plot view (hard pointer)
+            self.writer
+                .write_bit_short(settings.shade_plot_mode.to_code())?;
+            self.writer
+                .write_bit_short(settings.shade_plot_resolution.to_code())?;
+            self.writer.write_bit_short(settings.shade_plot_dpi)?;
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+
+        if self.r2007_plus() {
+            // Visual style (soft pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::SoftPointer, 0)?;
+        }
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    // Scale, SortEntsTable, DictionaryVar
+    // ------------------------------------------------------------------
+
+    fn write_scale(&mut self, scale: &Scale) -> Result<()> {
+        let Some(code) = self.class_object_type("SCALE") else {
+            return Ok(());
+        };
+
+        let handle = scale.handle.value();
+        self.begin_object(code)?;
+        self.write_common_non_entity_data(handle, scale.owner_handle.value(), &[], None)?;
+
+        // Unknown BS, Name TV, Paper units BD, Drawing units BD, Unit scale B
+        self.writer.write_bit_short(0)?;
+        self.writer.write_variable_text(&scale.name)?;
+        self.writer.write_bit_double(scale.paper_units)?;
+        self.writer.write_bit_double(scale.drawing_units)?;
+        self.writer.write_bit(scale.is_unit_scale)?;
+        self.end_object(handle)
+    }
+
+    fn write_sort_entities_table(&mut self, table: &SortEntitiesTable) -> Result<()> {
+        let Some(code) = self.class_object_type("SORTENTSTABLE") else {
+            return Ok(());
+        };
+
+        let handle = table.handle.value();
+        self.begin_object(code)?;
+        self.write_common_non_entity_data(handle, table.owner_handle.value(), &[], None)?;
+
+        // Block owner (soft pointer), Numentries BL
+        self.writer.handle_reference_typed(
+            DwgReferenceType::SoftPointer,
+            table.block_owner_handle.value(),
+        )?;
+        self.writer.write_bit_long(table.len() as i32)?;
+
+        for entry in table.entries() {
+            // Sort handle (code 0, main stream), Entity (soft pointer)
+            self.writer
+                .main_handle_reference(entry.sort_handle.value())?;
+            self.writer.handle_reference_typed(
+                DwgReferenceType::SoftPointer,
+                entry.entity_handle.value(),
+            )?;
+        }
+        self.end_object(handle)
+    }
+
+    fn write_dictionary_variable(&mut self, variable: &DictionaryVariable) -> Result<()> {
+        let Some(code) = self.class_object_type("DICTIONARYVAR") else {
+            return Ok(());
+        };
+
+        let handle = variable.handle.value();
+        self.begin_object(code)?;
+        self.write_common_non_entity_data(handle, variable.owner_handle.value(), &[], None)?;
+
+        // Intval RC, Str TV
+        self.writer.write_byte(variable.schema_number as u8)?;
+        self.writer.write_variable_text(&variable.value)?;
+        self.end_object(handle)
+    }
+}
+
+/// Display name of an object type for notifications.
+fn object_type_name(object: &ObjectType) -> &str {
+    match object {
+        ObjectType::Dictionary(_) => "DICTIONARY",
+        ObjectType::Layout(_) => "LAYOUT",
+        ObjectType::XRecord(_) => "XRECORD",
+        ObjectType::Group(_) => "GROUP",
+        ObjectType::MLineStyle(_) => "MLINESTYLE",
+        ObjectType::ImageDefinition(_) => "IMAGEDEF",
+        ObjectType::PlotSettings(_) => "PLOTSETTINGS",
+        ObjectType::MultiLeaderStyle(_) => "MLEADERSTYLE",
+        ObjectType::TableStyle(_) => "TABLESTYLE",
+        ObjectType::Scale(_) => "SCALE",
+        ObjectType::SortEntitiesTable(_) => "SORTENTSTABLE",
+        ObjectType::DictionaryVariable(_) => "DICTIONARYVAR",
+        ObjectType::VisualStyle(_) => "VISUALSTYLE",
+        ObjectType::Material(_) => "MATERIAL",
+        ObjectType::ImageDefinitionReactor(_) => "IMAGEDEF_REACTOR",
+        ObjectType::GeoData(_) => "GEODATA",
+        ObjectType::SpatialFilter(_) => "SPATIAL_FILTER",
+        ObjectType::RasterVariables(_) => "RASTERVARIABLES",
+        ObjectType::BookColor(_) => "DBCOLOR",
+        ObjectType::PlaceHolder(_) => "ACDBPLACEHOLDER",
+        ObjectType::DictionaryWithDefault(_) => "ACDBDICTIONARYWDFLT",
+        ObjectType::WipeoutVariables(_) => "WIPEOUTVARIABLES",
+        ObjectType::Unknown { type_name, .. } => type_name,
+    }
+}
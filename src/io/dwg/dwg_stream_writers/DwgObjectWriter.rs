@@ -0,0 +1,1616 @@
+//! DWG object writer — serializes the tables, block records, entities and
+//! non-graphical objects of a `CadDocument` into the AcDbObjects section.
+//!
+//! Rust equivalent of the C# `DwgObjectWriter` partial class. The common
+//! object framing and the table objects live here; entities and
+//! non-graphical objects are written by `DwgObjectWriter.Entities.rs` and
+//! `DwgObjectWriter.Objects.rs`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, SeekFrom};
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::error::Result;
+use crate::io::dwg::crc8_value;
+use crate::io::dwg::dwg_stream_readers::idwg_stream_reader::DwgReferenceType;
+use crate::notification::{Notification, NotificationType};
+use crate::tables::{BlockRecord, DimStyle, Layer, LineType, TableEntry, TextStyle, Ucs, VPort, View};
+use crate::types::{Color, DxfVersion, Handle, LineWeight, Vector2, Vector3};
+use crate::xdata::{ExtendedData, XDataValue};
+
+use super::dwg_object_writer_entities::entity_object_type;
+use super::dwg_stream_writer_base::DwgStreamWriterBase;
+use super::idwg_stream_writer::DwgStreamWriter;
+
+/// Fixed DWG object type codes (ACadSharp `ObjectType`).
+pub(super) mod object_type {
+    pub const TEXT: i16 = 0x01;
+    pub const ATTRIB: i16 = 0x02;
+    pub const ATTDEF: i16 = 0x03;
+    pub const BLOCK: i16 = 0x04;
+    pub const ENDBLK: i16 = 0x05;
+    pub const SEQEND: i16 = 0x06;
+    pub const INSERT: i16 = 0x07;
+    pub const MINSERT: i16 = 0x08;
+    pub const VERTEX_2D: i16 = 0x0A;
+    pub const VERTEX_3D: i16 = 0x0B;
+    pub const VERTEX_MESH: i16 = 0x0C;
+    pub const VERTEX_PFACE: i16 = 0x0D;
+    pub const VERTEX_PFACE_FACE: i16 = 0x0E;
+    pub const POLYLINE_2D: i16 = 0x0F;
+    pub const POLYLINE_3D: i16 = 0x10;
+    pub const ARC: i16 = 0x11;
+    pub const CIRCLE: i16 = 0x12;
+    pub const LINE: i16 = 0x13;
+    pub const DIMENSION_ORDINATE: i16 = 0x14;
+    pub const DIMENSION_LINEAR: i16 = 0x15;
+    pub const DIMENSION_ALIGNED: i16 = 0x16;
+    pub const DIMENSION_ANG_3_PT: i16 = 0x17;
+    pub const DIMENSION_ANG_2_LN: i16 = 0x18;
+    pub const DIMENSION_RADIUS: i16 = 0x19;
+    pub const DIMENSION_DIAMETER: i16 = 0x1A;
+    pub const POINT: i16 = 0x1B;
+    pub const FACE_3D: i16 = 0x1C;
+    pub const POLYLINE_PFACE: i16 = 0x1D;
+    pub const POLYLINE_MESH: i16 = 0x1E;
+    pub const SOLID: i16 = 0x1F;
+    pub const SHAPE: i16 = 0x21;
+    pub const VIEWPORT: i16 = 0x22;
+    pub const ELLIPSE: i16 = 0x23;
+    pub const SPLINE: i16 = 0x24;
+    pub const REGION: i16 = 0x25;
+    pub const SOLID_3D: i16 = 0x26;
+    pub const BODY: i16 = 0x27;
+    pub const RAY: i16 = 0x28;
+    pub const XLINE: i16 = 0x29;
+    pub const DICTIONARY: i16 = 0x2A;
+    pub const MTEXT: i16 = 0x2C;
+    pub const LEADER: i16 = 0x2D;
+    pub const TOLERANCE: i16 = 0x2E;
+    pub const MLINE: i16 = 0x2F;
+    pub const BLOCK_CONTROL: i16 = 0x30;
+    pub const BLOCK_HEADER: i16 = 0x31;
+    pub const LAYER_CONTROL: i16 = 0x32;
+    pub const LAYER: i16 = 0x33;
+    pub const STYLE_CONTROL: i16 = 0x34;
+    pub const STYLE: i16 = 0x35;
+    pub const LTYPE_CONTROL: i16 = 0x38;
+    pub const LTYPE: i16 = 0x39;
+    pub const VIEW_CONTROL: i16 = 0x3C;
+    pub const VIEW: i16 = 0x3D;
+    pub const UCS_CONTROL: i16 = 0x3E;
+    pub const UCS: i16 = 0x3F;
+    pub const VPORT_CONTROL: i16 = 0x40;
+    pub const VPORT: i16 = 0x41;
+    pub const APPID_CONTROL: i16 = 0x42;
+    pub const APPID: i16 = 0x43;
+    pub const DIMSTYLE_CONTROL: i16 = 0x44;
+    pub const DIMSTYLE: i16 = 0x45;
+    pub const GROUP: i16 = 0x48;
+    pub const MLINESTYLE: i16 = 0x49;
+    pub const OLE2FRAME: i16 = 0x4A;
+    pub const LWPOLYLINE: i16 = 0x4D;
+    pub const HATCH: i16 = 0x4E;
+    pub const XRECORD: i16 = 0x4F;
+    pub const ACDBPLACEHOLDER: i16 = 0x50;
+    pub const LAYOUT: i16 = 0x52;
+    pub const PROXY_ENTITY: i16 = 0x1F2;
+}
+
+/// Output of [`DwgObjectWriter::write`].
+#[derive(Debug, Default)]
+pub struct DwgObjectWriterResult {
+    /// Raw AcDbObjects section data.
+    pub data: Vec<u8>,
+    /// Object handle → offset of the object inside `data`.
+    pub handle_map: BTreeMap<u64, i64>,
+    /// First handle not used by the written objects (HANDSEED).
+    pub next_handle: u64,
+    /// Warnings raised while writing (skipped entities and objects).
+    pub notifications: Vec<Notification>,
+}
+
+/// Entities of one block record, in the order they are written.
+pub(super) struct BlockContents<'a> {
+    pub(super) record_handle: u64,
+    pub(super) name: String,
+    pub(super) begin_handle: u64,
+    pub(super) end_handle: u64,
+    /// Entity mode written for the owned entities (2 model, 1 paper, 0 block).
+    pub(super) entity_mode: u8,
+    pub(super) entities: Vec<(u64, &'a EntityType)>,
+}
+
+/// Writes every object of a document into the AcDbObjects section.
+pub struct DwgObjectWriter<'a> {
+    pub(super) version: DxfVersion,
+    pub(super) document: &'a CadDocument,
+    pub(super) writer: Box<dyn DwgStreamWriter>,
+    pub(super) notifications: Vec<Notification>,
+    /// ATTRIB entities keyed by the handle of the INSERT that owns them.
+    pub(super) attributes: HashMap<u64, Vec<&'a EntityType>>,
+    /// Previous / next entity of the entity being written (pre-R2004 links).
+    pub(super) prev_entity: u64,
+    pub(super) next_entity: u64,
+    section: Vec<u8>,
+    handle_map: BTreeMap<u64, i64>,
+    next_handle: u64,
+    blocks: Vec<BlockContents<'a>>,
+}
+
+impl<'a> DwgObjectWriter<'a> {
+    pub fn new(version: DxfVersion, document: &'a CadDocument) -> Self {
+        Self {
+            version,
+            document,
+            writer: Self::create_writer(version),
+            notifications: Vec::new(),
+            attributes: HashMap::new(),
+            prev_entity: 0,
+            next_entity: 0,
+            section: Vec::new(),
+            handle_map: BTreeMap::new(),
+            next_handle: document.next_handle().max(document.header.handle_seed),
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Write the objects section.
+    pub fn write(mut self) -> Result<DwgObjectWriterResult> {
+        if self.r2004_plus() {
+            // R2004+ start marker
+            self.section.extend_from_slice(&0x0DCAi32.to_le_bytes());
+        }
+
+        self.collect_blocks();
+
+        self.write_block_control()?;
+        self.write_layers()?;
+        self.write_text_styles()?;
+        self.write_line_types()?;
+        self.write_views()?;
+        self.write_ucss()?;
+        self.write_vports()?;
+        self.write_app_ids()?;
+        self.write_dim_styles()?;
+
+        let blocks = std::mem::take(&mut self.blocks);
+        for block in &blocks {
+            self.write_block_entities(block)?;
+        }
+
+        self.write_objects()?;
+
+        Ok(DwgObjectWriterResult {
+            data: self.section,
+            handle_map: self.handle_map,
+            next_handle: self.next_handle,
+            notifications: self.notifications,
+        })
+    }
+
+    // ------------------------------------------------------------------
+    // Object framing
+    // ------------------------------------------------------------------
+
+    fn create_writer(version: DxfVersion) -> Box<dyn DwgStreamWriter> {
+        DwgStreamWriterBase::get_merged_writer(version, Box::new(Cursor::new(Vec::new())), "windows-1252")
+    }
+
+    /// Start a new object record with the given type code.
+    pub(super) fn begin_object(&mut self, object_type: i16) -> Result<()> {
+        self.writer = Self::create_writer(self.version);
+        self.writer.write_object_type(object_type)?;
+
+        // R2000-R2007: RL size of the object data in bits
+        if self.version >= DxfVersion::AC1015 && self.version < DxfVersion::AC1024 {
+            self.writer.save_position_for_size()?;
+        }
+        Ok(())
+    }
+
+    /// Merge the object streams and append the record (MS size, [MC handle
+    /// stream size], data, CRC) to the section.
+    pub(super) fn end_object(&mut self, handle: u64) -> Result<()> {
+        self.writer.write_spear_shift()?;
+        let handle_start_bits = self.writer.saved_position_in_bits();
+
+        let stream = self.writer.stream();
+        stream.seek(SeekFrom::Start(0))?;
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data)?;
+
+        let mut record = Vec::with_capacity(data.len() + 8);
+        write_modular_short(&mut record, data.len() as u32);
+        if self.r2010_plus() {
+            // MC: size in bits of the handle stream
+            let handle_bits = (data.len() as i64 * 8 - handle_start_bits).max(0) as u64;
+            write_modular_char(&mut record, handle_bits);
+        }
+        record.extend_from_slice(&data);
+
+        let crc = crc8_value(0xC0C1, &record, 0, record.len());
+        record.extend_from_slice(&crc.to_le_bytes());
+
+        self.handle_map.insert(handle, self.section.len() as i64);
+        self.section.extend_from_slice(&record);
+        Ok(())
+    }
+
+    /// Object handle and extended data, common to entities and objects.
+    fn write_common_data(&mut self, handle: u64, extended_data: Option<&ExtendedData>) -> Result<()> {
+        self.writer.main_handle_reference(handle)?;
+        if let Some(data) = extended_data {
+            self.write_extended_data(handle, data)?;
+        }
+        // EED size 0 terminates the extended data
+        self.writer.write_bit_short(0)
+    }
+
+    /// Common data of non-graphical objects.
+    pub(super) fn write_common_non_entity_data(
+        &mut self,
+        handle: u64,
+        owner: u64,
+        reactors: &[Handle],
+        xdictionary: Option<Handle>,
+    ) -> Result<()> {
+        self.write_common_data(handle, None)?;
+
+        // R13-R14: RL size of the object data in bits
+        if self.r13_14_only() {
+            self.writer.save_position_for_size()?;
+        }
+
+        self.writer.handle_reference_typed(DwgReferenceType::SoftPointer, owner)?;
+        self.write_reactors_and_dictionary_handle(reactors, xdictionary)
+    }
+
+    pub(super) fn write_reactors_and_dictionary_handle(
+        &mut self,
+        reactors: &[Handle],
+        xdictionary: Option<Handle>,
+    ) -> Result<()> {
+        // Numreactors BL
+        self.writer.write_bit_long(reactors.len() as i32)?;
+        for reactor in reactors {
+            self.writer
+                .handle_reference_typed(DwgReferenceType::SoftPointer, reactor.value())?;
+        }
+
+        let xdictionary = xdictionary.map(|h| h.value()).unwrap_or(0);
+        if self.r2004_plus() {
+            // R2004+: XDic Missing Flag B
+            self.writer.write_bit(xdictionary == 0)?;
+            if xdictionary != 0 {
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardOwnership, xdictionary)?;
+            }
+        } else {
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardOwnership, xdictionary)?;
+        }
+
+        // R2013+: Has DS binary data B
+        if self.r2013_plus() {
+            self.writer.write_bit(false)?;
+        }
+        Ok(())
+    }
+
+    /// Common data of entities: handle, EED, entity mode, owner, links,
+    /// color, layer and visibility.
+    pub(super) fn write_common_entity_data(
+        &mut self,
+        handle: u64,
+        entity_mode: u8,
+        owner: u64,
+        common: &crate::entities::EntityCommon,
+    ) -> Result<()> {
+        self.write_common_entity_data_with_graphics(handle, entity_mode, owner, common, None)
+    }
+
+    /// Common data of entities, followed by proxy graphics when given.
+    pub(super) fn write_common_entity_data_with_graphics(
+        &mut self,
+        handle: u64,
+        entity_mode: u8,
+        owner: u64,
+        common: &crate::entities::EntityCommon,
+        graphics: Option<&[u8]>,
+    ) -> Result<()> {
+        self.write_common_data(handle, Some(&common.extended_data))?;
+
+        // Graphic present flag
+        match graphics {
+            Some(data) => {
+                self.writer.write_bit(true)?;
+                // Graphics size: R2010+ BLL, earlier RL
+                if self.r2010_plus() {
+                    self.writer.write_bit_long_long(data.len() as i64)?;
+                } else {
+                    self.writer.write_raw_long(data.len() as i64)?;
+                }
+                self.writer.write_bytes(data)?;
+            }
+            None => self.writer.write_bit(false)?,
+        }
+
+        // R13-R14: RL size of the object data in bits
+        if self.r13_14_only() {
+            self.writer.save_position_for_size()?;
+        }
+
+        // Entmode BB
+        self.writer.write_2_bits(entity_mode)?;
+        if entity_mode == 0 {
+            self.writer.handle_reference_typed(DwgReferenceType::SoftPointer, owner)?;
+        }
+
+        self.write_reactors_and_dictionary_handle(&common.reactors, common.xdictionary_handle)?;
+
+        let layer = self.layer_handle(&common.layer);
+        if self.r13_14_only() {
+            self.writer.handle_reference_typed(DwgReferenceType::HardPointer, layer)?;
+            // Isbylayerlt B
+            self.writer.write_bit(true)?;
+        }
+
+        // R13-R2000: Nolinks B, previous / next entity handles
+        if !self.r2004_plus() {
+            if self.prev_entity == 0 && self.next_entity == 0 {
+                self.writer.write_bit(true)?;
+            } else {
+                self.writer.write_bit(false)?;
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::SoftPointer, self.prev_entity)?;
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::SoftPointer, self.next_entity)?;
+            }
+        }
+
+        if self.r2004_plus() {
+            self.writer
+                .write_en_color_book(&common.color, &common.transparency, false)?;
+        } else {
+            self.writer.write_en_color(&common.color, &common.transparency)?;
+        }
+
+        // Ltype scale BD
+        self.writer.write_bit_double(1.0)?;
+
+        if self.version < DxfVersion::AC1015 {
+            // Invisibility BS
+            return self.writer.write_bit_short(if common.invisible { 1 } else { 0 });
+        }
+
+        self.writer.handle_reference_typed(DwgReferenceType::HardPointer, layer)?;
+
+        // Ltype flags BB: 00 = BYLAYER
+        self.writer.write_2_bits(0)?;
+
+        if self.r2007_plus() {
+            // Material flags BB: 00 = BYLAYER, Shadow flags RC
+            self.writer.write_2_bits(0)?;
+            self.writer.write_byte(0)?;
+        }
+
+        // Plotstyle flags BB: 00 = BYLAYER
+        self.writer.write_2_bits(0)?;
+
+        if self.r2010_plus() {
+            // Has full visual style, face visual style, edge visual style
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+        }
+
+        // Invisibility BS
+        self.writer.write_bit_short(if common.invisible { 1 } else { 0 })?;
+
+        // Lineweight RC
+        self.writer.write_byte(line_weight_index(common.line_weight))
+    }
+
+    /// Write the extended data of an object, one block per application.
+    fn write_extended_data(&mut self, handle: u64, data: &ExtendedData) -> Result<()> {
+        for record in data.records() {
+            let Some(app_handle) = self
+                .document
+                .app_ids
+                .get(&record.application_name)
+                .map(|app| app.handle.value())
+                .filter(|&h| h != 0)
+            else {
+                self.notify(
+                    format!(
+                        "Extended data of {:#X} skipped: application {} is not registered",
+                        handle, record.application_name
+                    ),
+                    NotificationType::Warning,
+                );
+                continue;
+            };
+
+            let bytes = self.extended_data_bytes(&record.values);
+            if bytes.is_empty() {
+                continue;
+            }
+
+            self.writer.write_bit_short(bytes.len() as i16)?;
+            self.writer.main_handle_reference(app_handle)?;
+            self.writer.write_bytes(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Byte encoding of the values of one extended data block.
+    fn extended_data_bytes(&self, values: &[XDataValue]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for value in values {
+            match value {
+                XDataValue::String(s) => {
+                    buf.push(0);
+                    self.push_eed_text(&mut buf, s);
+                }
+                XDataValue::ControlString(s) => {
+                    buf.push(2);
+                    buf.push(if s.trim() == "}" { 1 } else { 0 });
+                }
+                XDataValue::LayerName(name) => {
+                    buf.push(3);
+                    let layer = self.layer_handle(name);
+                    buf.extend_from_slice(&layer.to_be_bytes());
+                }
+                XDataValue::BinaryData(data) => {
+                    for chunk in data.chunks(255) {
+                        buf.push(4);
+                        buf.push(chunk.len() as u8);
+                        buf.extend_from_slice(chunk);
+                    }
+                }
+                XDataValue::Handle(h) => {
+                    buf.push(5);
+                    buf.extend_from_slice(&h.value().to_be_bytes());
+                }
+                XDataValue::Point3D(p) => push_eed_point(&mut buf, 10, p),
+                XDataValue::Position3D(p) => push_eed_point(&mut buf, 11, p),
+                XDataValue::Displacement3D(p) => push_eed_point(&mut buf, 12, p),
+                XDataValue::Direction3D(p) => push_eed_point(&mut buf, 13, p),
+                XDataValue::Real(v) => push_eed_real(&mut buf, 40, *v),
+                XDataValue::Distance(v) => push_eed_real(&mut buf, 41, *v),
+                XDataValue::ScaleFactor(v) => push_eed_real(&mut buf, 42, *v),
+                XDataValue::Integer16(v) => {
+                    buf.push(70);
+                    buf.extend_from_slice(&v.to_le_bytes());
+                }
+                XDataValue::Integer32(v) => {
+                    buf.push(71);
+                    buf.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+        }
+        buf
+    }
+
+    /// Extended data string: RS length + RC codepage + bytes before R2007,
+    /// RS length + UTF-16LE characters afterwards.
+    pub(super) fn push_eed_text(&self, buf: &mut Vec<u8>, value: &str) {
+        if self.r2007_plus() {
+            let chars: Vec<u16> = value.encode_utf16().collect();
+            buf.extend_from_slice(&(chars.len() as u16).to_le_bytes());
+            for c in chars {
+                buf.extend_from_slice(&c.to_le_bytes());
+            }
+        } else {
+            let bytes = value.as_bytes();
+            buf.extend_from_slice(&(bytes.len() as u16).to_le_bytes());
+            // ANSI_1252 code page
+            buf.push(30);
+            buf.extend_from_slice(bytes);
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // Tables
+    // ------------------------------------------------------------------
+
+    /// Table control object: entry count, entry handles and the special
+    /// entries that are not counted.
+    fn write_table_control(
+        &mut self,
+        object_type: i16,
+        handle: u64,
+        entries: &[u64],
+        special: &[u64],
+    ) -> Result<()> {
+        self.begin_object(object_type)?;
+        self.write_common_non_entity_data(handle, 0, &[], None)?;
+
+        // Numentries BL
+        self.writer.write_bit_long(entries.len() as i32)?;
+
+        // R2000+: DIMSTYLE_CONTROL has an undocumented count of extra handles
+        if object_type == object_type::DIMSTYLE_CONTROL && self.version >= DxfVersion::AC1015 {
+            self.writer.write_byte(0)?;
+        }
+
+        for &entry in entries {
+            self.writer
+                .handle_reference_typed(DwgReferenceType::SoftOwnership, entry)?;
+        }
+        for &entry in special {
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardOwnership, entry)?;
+        }
+
+        self.end_object(handle)
+    }
+
+    /// Common table entry data: name and xref flags.
+    fn write_common_table_data(&mut self, handle: u64, owner: u64, name: &str) -> Result<()> {
+        self.write_common_non_entity_data(handle, owner, &[], None)?;
+
+        self.writer.write_variable_text(name)?;
+
+        if self.r2007_plus() {
+            // xrefindex+1 BS
+            self.writer.write_bit_short(0)?;
+        } else {
+            // 64-flag B, xrefindex+1 BS, Xdep B
+            self.writer.write_bit(false)?;
+            self.writer.write_bit_short(0)?;
+            self.writer.write_bit(false)?;
+        }
+
+        // External reference block handle
+        self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)
+    }
+
+    fn write_block_control(&mut self) -> Result<()> {
+        let header = &self.document.header;
+        let control = header.block_control_handle.value();
+        let model_space = header.model_space_block_handle.value();
+        let paper_space = header.paper_space_block_handle.value();
+
+        let entries: Vec<u64> = self
+            .blocks
+            .iter()
+            .map(|b| b.record_handle)
+            .filter(|&h| h != model_space && h != paper_space)
+            .collect();
+        self.write_table_control(
+            object_type::BLOCK_CONTROL,
+            control,
+            &entries,
+            &[model_space, paper_space],
+        )?;
+
+        let records: Vec<&'a BlockRecord> = sorted_entries(self.document.block_records.iter());
+        for record in records {
+            let index = self
+                .blocks
+                .iter()
+                .position(|b| b.record_handle == record.handle.value());
+            if let Some(index) = index {
+                self.write_block_header(record, control, index)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_block_header(&mut self, record: &BlockRecord, control: u64, index: usize) -> Result<()> {
+        let handle = record.handle.value();
+        let is_xref = record.flags.is_xref;
+        let is_overlay = record.flags.is_xref_overlay;
+
+        self.begin_object(object_type::BLOCK_HEADER)?;
+        let name = self.blocks[index].name.clone();
+        self.write_common_table_data(handle, control, &name)?;
+
+        // Anonymous B, hasatts B, Blkisxref B, Xrefoverlaid B
+        self.writer.write_bit(record.flags.anonymous)?;
+        self.writer.write_bit(record.flags.has_attributes)?;
+        self.writer.write_bit(is_xref)?;
+        self.writer.write_bit(is_overlay)?;
+
+        // R2000+: Loaded Bit B
+        if self.version >= DxfVersion::AC1015 {
+            self.writer.write_bit(false)?;
+        }
+
+        let entities: Vec<u64> = self.blocks[index].entities.iter().map(|(h, _)| *h).collect();
+
+        // R2004+: Owned Object Count BL
+        if self.r2004_plus() && !is_xref && !is_overlay {
+            self.writer.write_bit_long(entities.len() as i32)?;
+        }
+
+        // Base pt 3BD, Xref pname TV
+        self.writer.write_3_bit_double(&Vector3::ZERO)?;
+        self.writer.write_variable_text("")?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Insert count RC list terminated by 0
+            self.writer.write_byte(0)?;
+            // Block description TV
+            self.writer.write_variable_text("")?;
+            // Size of preview data BL
+            self.writer.write_bit_long(0)?;
+        }
+
+        if self.r2007_plus() {
+            // Insert units BS, Explodable B, Block scaling RC
+            self.writer.write_bit_short(record.units)?;
+            self.writer.write_bit(record.explodable)?;
+            self.writer.write_byte(if record.scale_uniformly { 1 } else { 0 })?;
+        }
+
+        let begin = self.blocks[index].begin_handle;
+        let end = self.blocks[index].end_handle;
+
+        // Block entity (hard owner)
+        self.writer.handle_reference_typed(DwgReferenceType::HardOwnership, begin)?;
+
+        if !self.r2004_plus() && !is_xref && !is_overlay {
+            // First / last entity (soft pointers)
+            let first = entities.first().copied().unwrap_or(0);
+            let last = entities.last().copied().unwrap_or(0);
+            self.writer.handle_reference_typed(DwgReferenceType::SoftPointer, first)?;
+            self.writer.handle_reference_typed(DwgReferenceType::SoftPointer, last)?;
+        }
+
+        if self.r2004_plus() {
+            for &entity in &entities {
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardOwnership, entity)?;
+            }
+        }
+
+        // ENDBLK entity (hard owner)
+        self.writer.handle_reference_typed(DwgReferenceType::HardOwnership, end)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Layout handle (hard pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, record.layout.value())?;
+        }
+
+        self.end_object(handle)
+    }
+
+    fn write_layers(&mut self) -> Result<()> {
+        let control = self.document.header.layer_control_handle.value();
+        let layers: Vec<&'a Layer> = sorted_entries(self.document.layers.iter());
+        let entries: Vec<u64> = layers.iter().map(|l| l.handle.value()).collect();
+        self.write_table_control(object_type::LAYER_CONTROL, control, &entries, &[])?;
+
+        for layer in layers {
+            self.write_layer(layer, control)?;
+        }
+        Ok(())
+    }
+
+    fn write_layer(&mut self, layer: &Layer, control: u64) -> Result<()> {
+        let handle = layer.handle.value();
+        self.begin_object(object_type::LAYER)?;
+        self.write_common_table_data(handle, control, &layer.name)?;
+
+        if self.r13_14_only() {
+            // Frozen B, On B, Frz in new B, Locked B
+            self.writer.write_bit(layer.flags.frozen)?;
+            self.writer.write_bit(!layer.flags.off)?;
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(layer.flags.locked)?;
+        } else {
+            // Values BS: frozen 1, off 2, locked 8, plotting 0x10, lineweight << 5
+            let mut values: i16 = 0;
+            if layer.flags.frozen {
+                values |= 0x1;
+            }
+            if layer.flags.off {
+                values |= 0x2;
+            }
+            if layer.flags.locked {
+                values |= 0x8;
+            }
+            if layer.is_plottable {
+                values |= 0x10;
+            }
+            values |= (line_weight_index(layer.line_weight) as i16) << 5;
+            self.writer.write_bit_short(values)?;
+        }
+
+        // Color CMC
+        self.writer.write_cm_color(&layer.color)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Plotstyle (hard pointer)
+            self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+        if self.r2007_plus() {
+            // Material (hard pointer)
+            self.writer
+                .handle_reference_typed(DwgReferenceType::HardPointer, layer.material.value())?;
+        }
+
+        // Linetype (hard pointer)
+        let line_type = self.line_type_handle(&layer.line_type);
+        self.writer.handle_reference_typed(DwgReferenceType::HardPointer, line_type)?;
+
+        if self.r2013_plus() {
+            // Unknown handle (hard pointer)
+            self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+
+        self.end_object(handle)
+    }
+
+    fn write_text_styles(&mut self) -> Result<()> {
+        let control = self.document.header.style_control_handle.value();
+        let styles: Vec<&'a TextStyle> = sorted_entries(self.document.text_styles.iter());
+        let entries: Vec<u64> = styles.iter().map(|s| s.handle.value()).collect();
+        self.write_table_control(object_type::STYLE_CONTROL, control, &entries, &[])?;
+
+        for style in styles {
+            let handle = style.handle.value();
+            self.begin_object(object_type::STYLE)?;
+            self.write_common_table_data(handle, control, &style.name)?;
+
+            // Shape file B, Vertical B
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(false)?;
+            // Fixed height BD, Width factor BD, Oblique ang BD
+            self.writer.write_bit_double(style.height)?;
+            self.writer.write_bit_double(style.width_factor)?;
+            self.writer.write_bit_double(style.oblique_angle)?;
+
+            // Generation RC: 2 backwards, 4 upside down
+            let mut generation = 0u8;
+            if style.flags.backward {
+                generation |= 0x2;
+            }
+            if style.flags.upside_down {
+                generation |= 0x4;
+            }
+            self.writer.write_byte(generation)?;
+
+            // Last height BD, Font name TV, Bigfont name TV
+            self.writer.write_bit_double(style.height)?;
+            self.writer.write_variable_text(&style.font_file)?;
+            self.writer.write_variable_text(&style.big_font_file)?;
+
+            self.end_object(handle)?;
+        }
+        Ok(())
+    }
+
+    fn write_line_types(&mut self) -> Result<()> {
+        let header = &self.document.header;
+        let control = header.linetype_control_handle.value();
+        let by_layer = header.bylayer_linetype_handle.value();
+        let by_block = header.byblock_linetype_handle.value();
+
+        let line_types: Vec<&'a LineType> = sorted_entries(self.document.line_types.iter());
+        let entries: Vec<u64> = line_types
+            .iter()
+            .map(|l| l.handle.value())
+            .filter(|&h| h != by_layer && h != by_block)
+            .collect();
+        self.write_table_control(object_type::LTYPE_CONTROL, control, &entries, &[by_layer, by_block])?;
+
+        for line_type in line_types {
+            let handle = line_type.handle.value();
+            self.begin_object(object_type::LTYPE)?;
+            self.write_common_table_data(handle, control, &line_type.name)?;
+
+            // Description TV, Pattern Len BD, Alignment RC, Numdashes RC
+            self.writer.write_variable_text(&line_type.description)?;
+            self.writer.write_bit_double(line_type.pattern_length)?;
+            self.writer.write_byte(line_type.alignment as u8)?;
+            self.writer.write_byte(line_type.elements.len() as u8)?;
+
+            for element in &line_type.elements {
+                // Dash length BD, Complex shapecode BS, X-offset RD, Y-offset RD,
+                // Scale BD, Rotation BD, Shapeflag BS
+                self.writer.write_bit_double(element.length)?;
+                self.writer.write_bit_short(0)?;
+                self.writer.write_raw_double(0.0)?;
+                self.writer.write_raw_double(0.0)?;
+                self.writer.write_bit_double(1.0)?;
+                self.writer.write_bit_double(0.0)?;
+                self.writer.write_bit_short(0)?;
+            }
+
+            // Strings area: 256 bytes up to R2004, none for R2007+ without text
+            if self.version <= DxfVersion::AC1018 {
+                self.writer.write_bytes(&[0u8; 256])?;
+            }
+
+            for _ in &line_type.elements {
+                // Shape file (hard pointer)
+                self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+            }
+
+            self.end_object(handle)?;
+        }
+        Ok(())
+    }
+
+    fn write_views(&mut self) -> Result<()> {
+        let control = self.document.header.view_control_handle.value();
+        let views: Vec<&'a View> = sorted_entries(self.document.views.iter());
+        let entries: Vec<u64> = views.iter().map(|v| v.handle.value()).collect();
+        self.write_table_control(object_type::VIEW_CONTROL, control, &entries, &[])?;
+
+        for view in views {
+            let handle = view.handle.value();
+            self.begin_object(object_type::VIEW)?;
+            self.write_common_table_data(handle, control, &view.name)?;
+
+            // View height BD, View width BD, View center 2RD
+            self.writer.write_bit_double(view.height)?;
+            self.writer.write_bit_double(view.width)?;
+            self.writer
+                .write_2_raw_double(&Vector2::new(view.center.x, view.center.y))?;
+            // Target 3BD, View dir 3BD, Twist angle BD, Lens length BD,
+            // Front clip BD, Back clip BD
+            self.writer.write_3_bit_double(&view.target)?;
+            self.writer.write_3_bit_double(&view.direction)?;
+            self.writer.write_bit_double(view.twist_angle)?;
+            self.writer.write_bit_double(view.lens_length)?;
+            self.writer.write_bit_double(view.front_clip)?;
+            self.writer.write_bit_double(view.back_clip)?;
+
+            // View mode: perspective, front clip, back clip, front clip z
+            for _ in 0..4 {
+                self.writer.write_bit(false)?;
+            }
+
+            if self.version >= DxfVersion::AC1015 {
+                // Render Mode RC
+                self.writer.write_byte(0)?;
+            }
+
+            if self.r2007_plus() {
+                self.write_default_lighting()?;
+            }
+
+            // Pspace flag B
+            self.writer.write_bit(false)?;
+
+            if self.version >= DxfVersion::AC1015 {
+                // Associated UCS B
+                self.writer.write_bit(false)?;
+            }
+
+            if self.r2007_plus() {
+                // Camera plottable B
+                self.writer.write_bit(false)?;
+                // Background, visual style, sun (hard pointers)
+                for _ in 0..3 {
+                    self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                }
+                // Live section (soft pointer)
+                self.writer.handle_reference_typed(DwgReferenceType::SoftPointer, 0)?;
+            }
+
+            self.end_object(handle)?;
+        }
+        Ok(())
+    }
+
+    /// R2007+ lighting block shared by VIEW and VPORT entries.
+    pub(super) fn write_default_lighting(&mut self) -> Result<()> {
+        // Use default lights B, Default lighting type RC, Brightness BD,
+        // Contrast BD, Ambient color CMC
+        self.writer.write_bit(true)?;
+        self.writer.write_byte(1)?;
+        self.writer.write_bit_double(0.0)?;
+        self.writer.write_bit_double(0.0)?;
+        self.writer.write_cm_color(&Color::Index(250))
+    }
+
+    fn write_ucss(&mut self) -> Result<()> {
+        let control = self.document.header.ucs_control_handle.value();
+        let ucss: Vec<&'a Ucs> = sorted_entries(self.document.ucss.iter());
+        let entries: Vec<u64> = ucss.iter().map(|u| u.handle.value()).collect();
+        self.write_table_control(object_type::UCS_CONTROL, control, &entries, &[])?;
+
+        for ucs in ucss {
+            let handle = ucs.handle.value();
+            self.begin_object(object_type::UCS)?;
+            self.write_common_table_data(handle, control, &ucs.name)?;
+
+            // Origin 3BD, X-direction 3BD, Y-direction 3BD
+            self.writer.write_3_bit_double(&ucs.origin)?;
+            self.writer.write_3_bit_double(&ucs.x_axis)?;
+            self.writer.write_3_bit_double(&ucs.y_axis)?;
+
+            if self.version >= DxfVersion::AC1015 {
+                // Elevation BD, OrthographicViewType BS, OrthographicType BS
+                self.writer.write_bit_double(0.0)?;
+                self.writer.write_bit_short(0)?;
+                self.writer.write_bit_short(0)?;
+                // Base UCS, Named UCS (hard pointers)
+                self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+                self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+            }
+
+            self.end_object(handle)?;
+        }
+        Ok(())
+    }
+
+    fn write_vports(&mut self) -> Result<()> {
+        let control = self.document.header.vport_control_handle.value();
+        let vports: Vec<&'a VPort> = sorted_entries(self.document.vports.iter());
+        let entries: Vec<u64> = vports.iter().map(|v| v.handle.value()).collect();
+        self.write_table_control(object_type::VPORT_CONTROL, control, &entries, &[])?;
+
+        for vport in vports {
+            self.write_vport(vport, control)?;
+        }
+        Ok(())
+    }
+
+    fn write_vport(&mut self, vport: &VPort, control: u64) -> Result<()> {
+        let handle = vport.handle.value();
+        self.begin_object(object_type::VPORT)?;
+        self.write_common_table_data(handle, control, &vport.name)?;
+
+        // View height BD, Aspect ratio BD (stored as the view width)
+        self.writer.write_bit_double(vport.view_height)?;
+        self.writer
+            .write_bit_double(vport.aspect_ratio * vport.view_height)?;
+        // View Center 2RD, View target 3BD, View dir 3BD
+        self.writer.write_2_raw_double(&vport.view_center)?;
+        self.writer.write_3_bit_double(&vport.view_target)?;
+        self.writer.write_3_bit_double(&vport.view_direction)?;
+        // View twist BD, Lens length BD, Front clip BD, Back clip BD
+        self.writer.write_bit_double(0.0)?;
+        self.writer.write_bit_double(vport.lens_length)?;
+        self.writer.write_bit_double(0.0)?;
+        self.writer.write_bit_double(0.0)?;
+
+        // View mode: perspective, front clip, back clip, front clip z
+        for _ in 0..4 {
+            self.writer.write_bit(false)?;
+        }
+
+        if self.version >= DxfVersion::AC1015 {
+            // Render Mode RC
+            self.writer.write_byte(0)?;
+        }
+
+        if self.r2007_plus() {
+            self.write_default_lighting()?;
+        }
+
+        // Lower left 2RD, Upper right 2RD
+        self.writer.write_2_raw_double(&vport.lower_left)?;
+        self.writer.write_2_raw_double(&vport.upper_right)?;
+
+        // UCSFOLLOW B, Circle zoom BS, Fast zoom B
+        self.writer.write_bit(false)?;
+        self.writer.write_bit_short(1000)?;
+        self.writer.write_bit(true)?;
+        // UCSICON: on B, at origin B
+        self.writer.write_bit(true)?;
+        self.writer.write_bit(true)?;
+        // Grid on/off B, Grd spacing 2RD
+        self.writer.write_bit(false)?;
+        self.writer.write_2_raw_double(&vport.grid_spacing)?;
+        // Snap on/off B, Snap style B, Snap isopair BS, Snap rot BD,
+        // Snap base 2RD, Snp spacing 2RD
+        self.writer.write_bit(false)?;
+        self.writer.write_bit(false)?;
+        self.writer.write_bit_short(0)?;
+        self.writer.write_bit_double(0.0)?;
+        self.writer.write_2_raw_double(&vport.snap_base)?;
+        self.writer.write_2_raw_double(&vport.snap_spacing)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // Unknown B, UCS per Viewport B
+            self.writer.write_bit(false)?;
+            self.writer.write_bit(true)?;
+            // UCS Origin 3BD, UCS X-axis 3BD, UCS Y-axis 3BD
+            self.writer.write_3_bit_double(&Vector3::ZERO)?;
+            self.writer.write_3_bit_double(&Vector3::UNIT_X)?;
+            self.writer.write_3_bit_double(&Vector3::UNIT_Y)?;
+            // UCS elevation BD, UCS Orthographic type BS
+            self.writer.write_bit_double(0.0)?;
+            self.writer.write_bit_short(0)?;
+        }
+
+        if self.r2007_plus() {
+            // Grid flags BS, Grid major BS
+            self.writer.write_bit_short(0)?;
+            self.writer.write_bit_short(5)?;
+            // Background, visual style, sun (hard pointers)
+            for _ in 0..3 {
+                self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+            }
+        }
+
+        if self.version >= DxfVersion::AC1015 {
+            // Named UCS, Base UCS (hard pointers)
+            self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+            self.writer.handle_reference_typed(DwgReferenceType::HardPointer, 0)?;
+        }
+
+        self.end_object(handle)
+    }
+
+    fn write_app_ids(&mut self) -> Result<()> {
+        let control = self.document.header.appid_control_handle.value();
+        let app_ids: Vec<_> = sorted_entries(self.document.app_ids.iter());
+        let entries: Vec<u64> = app_ids.iter().map(|a| a.handle.value()).collect();
+        self.write_table_control(object_type::APPID_CONTROL, control, &entries, &[])?;
+
+        for app_id in app_ids {
+            let handle = app_id.handle.value();
+            self.begin_object(object_type::APPID)?;
+            self.write_common_table_data(handle, control, &app_id.name)?;
+            // Unknown RC
+            self.writer.write_byte(0)?;
+            self.end_object(handle)?;
+        }
+        Ok(())
+    }
+
+    fn write_dim_styles(&mut self) -> Result<()> {
+        let control = self.document.header.dimstyle_control_handle.value();
+        let styles: Vec<&'a DimStyle> = sorted_entries(self.document.dim_styles.iter());
+        let entries: Vec<u64> = styles.iter().map(|s| s.handle.value()).collect();
+        self.write_table_control(object_type::DIMSTYLE_CONTROL, control, &entries, &[])?;
+
+        for style in styles {
+            self.write_dim_style(style, control)?;
+        }
+        Ok(())
+    }
+
+    fn write_dim_style(&mut self, style: &DimStyle, control: u64) -> Result<()> {
+        let handle = style.handle.value();
+        self.begin_object(object_type::DIMSTYLE)?;
+        self.write_common_table_data(handle, control, &style.name)?;
+
+        let w = &mut self.writer;
+        if matches!(self.version, DxfVersion::AC1012 | DxfVersion::AC1014) {
+            for bit in [
+                style.dimtol,
+                style.dimlim,
+                style.dimtih,
+                style.dimtoh,
+                style.dimse1,
+                style.dimse2,
+                style.dimalt,
+                style.dimtofl,
+                style.dimsah,
+                style.dimtix,
+                style.dimsoxd,
+            ] {
+                w.write_bit(bit)?;
+            }
+            w.write_byte(style.dimaltd as u8)?;
+            w.write_byte(style.dimzin as u8)?;
+            w.write_bit(style.dimsd1)?;
+            w.write_bit(style.dimsd2)?;
+            w.write_byte(style.dimtolj as u8)?;
+            w.write_byte(style.dimjust as u8)?;
+            w.write_byte(style.dimfit as u8)?;
+            w.write_bit(style.dimupt)?;
+            w.write_byte(style.dimtzin as u8)?;
+            w.write_byte(style.dimaltz as u8)?;
+            w.write_byte(style.dimalttz as u8)?;
+            w.write_byte(style.dimtad as u8)?;
+            for value in [
+                style.dimunit,
+                style.dimaunit,
+                style.dimdec,
+                style.dimtdec,
+                style.dimaltu,
+                style.dimalttd,
+            ] {
+                w.write_bit_short(value)?;
+            }
+            for value in [
+                style.dimscale,
+                style.dimasz,
+                style.dimexo,
+                style.dimdli,
+                style.dimexe,
+                style.dimrnd,
+                style.dimdle,
+                style.dimtp,
+                style.dimtm,
+                style.dimtxt,
+                style.dimcen,
+                style.dimtsz,
+                style.dimaltf,
+                style.dimlfac,
+                style.dimtvp,
+                style.dimtfac,
+                style.dimgap,
+            ] {
+                w.write_bit_double(value)?;
+            }
+            w.write_variable_text(&style.dimpost)?;
+            w.write_variable_text(&style.dimapost)?;
+            // DIMBLK, DIMBLK1, DIMBLK2 names
+            for _ in 0..3 {
+                w.write_variable_text("")?;
+            }
+            w.write_bit_short(style.dimclrd)?;
+            w.write_bit_short(style.dimclre)?;
+            w.write_bit_short(style.dimclrt)?;
+        } else {
+            w.write_variable_text(&style.dimpost)?;
+            w.write_variable_text(&style.dimapost)?;
+            for value in [
+                style.dimscale,
+                style.dimasz,
+                style.dimexo,
+                style.dimdli,
+                style.dimexe,
+                style.dimrnd,
+                style.dimdle,
+                style.dimtp,
+                style.dimtm,
+            ] {
+                w.write_bit_double(value)?;
+            }
+
+            if self.version >= DxfVersion::AC1021 {
+                w.write_bit_double(style.dimfxl)?;
+                w.write_bit_double(style.dimjogang)?;
+                w.write_bit_short(style.dimtfill)?;
+                // DIMTFILLCLR CMC
+                w.write_cm_color(&Color::ByBlock)?;
+            }
+
+            for bit in [
+                style.dimtol,
+                style.dimlim,
+                style.dimtih,
+                style.dimtoh,
+                style.dimse1,
+                style.dimse2,
+            ] {
+                w.write_bit(bit)?;
+            }
+            w.write_bit_short(style.dimtad)?;
+            w.write_bit_short(style.dimzin)?;
+            w.write_bit_short(style.dimazin)?;
+
+            if self.version >= DxfVersion::AC1021 {
+                w.write_bit_short(style.dimarcsym)?;
+            }
+
+            for value in [
+                style.dimtxt,
+                style.dimcen,
+                style.dimtsz,
+                style.dimaltf,
+                style.dimlfac,
+                style.dimtvp,
+                style.dimtfac,
+                style.dimgap,
+                style.dimaltrnd,
+            ] {
+                w.write_bit_double(value)?;
+            }
+            w.write_bit(style.dimalt)?;
+            w.write_bit_short(style.dimaltd)?;
+            for bit in [style.dimtofl, style.dimsah, style.dimtix, style.dimsoxd] {
+                w.write_bit(bit)?;
+            }
+            for value in [
+                style.dimclrd,
+                style.dimclre,
+                style.dimclrt,
+                style.dimadec,
+                style.dimdec,
+                style.dimtdec,
+                style.dimaltu,
+                style.dimalttd,
+                style.dimaunit,
+                style.dimfrac,
+                style.dimlunit,
+                style.dimdsep,
+                style.dimtmove,
+                style.dimjust,
+            ] {
+                w.write_bit_short(value)?;
+            }
+            w.write_bit(style.dimsd1)?;
+            w.write_bit(style.dimsd2)?;
+            w.write_bit_short(style.dimtolj)?;
+            w.write_bit_short(style.dimtzin)?;
+            w.write_bit_short(style.dimaltz)?;
+            w.write_bit_short(style.dimalttz)?;
+            w.write_bit(style.dimupt)?;
+            w.write_bit_short(style.dimatfit)?;
+
+            if self.version >= DxfVersion::AC1021 {
+                w.write_bit(style.dimfxlon)?;
+            }
+
+            if self.version >= DxfVersion::AC1024 {
+                w.write_bit(style.dimtxtdirection)?;
+                // DIMALTMZF BD, DIMALTMZS TV, DIMMZF BD, DIMMZS TV
+                w.write_bit_double(0.0)?;
+                w.write_variable_text("")?;
+                w.write_bit_double(0.0)?;
+                w.write_variable_text("")?;
+            }
+
+            w.write_bit_short(style.dimlwd)?;
+            w.write_bit_short(style.dimlwe)?;
+        }
+
+        // Unknown B
+        self.writer.write_bit(false)?;
+
+        // DIMTXSTY (hard pointer)
+        let text_style = if style.dimtxsty_handle.is_null() {
+            self.text_style_handle(&style.dimtxsty)
+        } else {
+            style.dimtxsty_handle.value()
+        };
+        self.writer
+            .handle_reference_typed(DwgReferenceType::HardPointer, text_style)?;
+
+        if self.version >= DxfVersion::AC1015 {
+            // DIMLDRBLK, DIMBLK, DIMBLK1, DIMBLK2 (hard pointers)
+            for block in [style.dimldrblk, style.dimblk, style.dimblk1, style.dimblk2] {
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardPointer, block.value())?;
+            }
+        }
+
+        if self.r2007_plus() {
+            // DIMLTYPE, DIMLTEX1, DIMLTEX2 (hard pointers)
+            for line_type in [style.dimltex_handle, style.dimltex1_handle, style.dimltex2_handle] {
+                self.writer
+                    .handle_reference_typed(DwgReferenceType::HardPointer, line_type.value())?;
+            }
+        }
+
+        self.end_object(handle)
+    }
+
+    // ------------------------------------------------------------------
+    // Blocks
+    // ------------------------------------------------------------------
+
+    /// Gather the entities of every block record and assign the handles
+    /// that are still missing (block begin / end, unsaved entities).
+    fn collect_blocks(&mut self) {
+        let document = self.document;
+        let model_space = document.header.model_space_block_handle.value();
+        let paper_space = document.header.paper_space_block_handle.value();
+
+        // Document entities live in model or paper space; ATTRIBs hang off
+        // the INSERT that owns them.
+        let inserts: std::collections::HashSet<u64> = document
+            .entities()
+            .chain(document.block_records.iter().flat_map(|r| r.entities.iter()))
+            .filter(|e| matches!(e, EntityType::Insert(_)))
+            .map(|e| e.common().handle.value())
+            .collect();
+
+        let mut model_entities = Vec::new();
+        let mut paper_entities = Vec::new();
+        for entity in document.entities() {
+            if paper_space != 0 && entity.common().owner_handle.value() == paper_space {
+                paper_entities.push(entity);
+            } else {
+                model_entities.push(entity);
+            }
+        }
+
+        let mut attributes: HashMap<u64, Vec<&'a EntityType>> = HashMap::new();
+        let mut skipped: BTreeMap<&'static str, usize> = BTreeMap::new();
+
+        let records: Vec<&'a BlockRecord> = sorted_entries(document.block_records.iter());
+        for record in records {
+            let record_handle = record.handle.value();
+            let (entity_mode, mut entities) = if record_handle == model_space {
+                let mut entities = model_entities.clone();
+                entities.extend(record.entities.iter());
+                (2, entities)
+            } else if record_handle == paper_space {
+                let mut entities = paper_entities.clone();
+                entities.extend(record.entities.iter());
+                (1, entities)
+            } else {
+                (0, record.entities.iter().collect())
+            };
+            entities.retain(|e| {
+                let owner = e.common().owner_handle.value();
+                if matches!(e, EntityType::AttributeEntity(_)) && inserts.contains(&owner) {
+                    attributes.entry(owner).or_default().push(*e);
+                    return false;
+                }
+                if entity_object_type(self.version, e).is_some() {
+                    return true;
+                }
+                if !matches!(e, EntityType::Block(_) | EntityType::BlockEnd(_)) {
+                    *skipped.entry(e.as_entity().entity_type()).or_insert(0) += 1;
+                }
+                false
+            });
+            entities.sort_by_key(|e| e.common().handle.value());
+
+            let entities = entities
+                .into_iter()
+                .map(|e| {
+                    let handle = e.common().handle.value();
+                    let handle = if handle == 0 { self.allocate_handle() } else { handle };
+                    (handle, e)
+                })
+                .collect();
+
+            let name = if record_handle == model_space {
+                "*Model_Space".to_string()
+            } else if record_handle == paper_space {
+                "*Paper_Space".to_string()
+            } else {
+                record.name.clone()
+            };
+
+            let begin_handle = match record.block_entity_handle.value() {
+                0 => self.allocate_handle(),
+                h => h,
+            };
+            let end_handle = match record.block_end_handle.value() {
+                0 => self.allocate_handle(),
+                h => h,
+            };
+
+            self.blocks.push(BlockContents {
+                record_handle,
+                name,
+                begin_handle,
+                end_handle,
+                entity_mode,
+                entities,
+            });
+        }
+
+        for attributes in attributes.values_mut() {
+            attributes.sort_by_key(|e| e.common().handle.value());
+        }
+        self.attributes = attributes;
+
+        for (entity_type, count) in skipped {
+            self.notify(
+                format!("{} {} entities not written: not supported in DWG {}", count, entity_type, self.version),
+                NotificationType::NotImplemented,
+            );
+        }
+    }
+
+    /// BLOCK, the owned entities and ENDBLK of one block record.
+    fn write_block_entities(&mut self, block: &BlockContents<'a>) -> Result<()> {
+        let record = block.record_handle;
+        let common = crate::entities::EntityCommon::new();
+
+        self.prev_entity = 0;
+        self.next_entity = 0;
+
+        // BLOCK
+        self.begin_object(object_type::BLOCK)?;
+        self.write_common_entity_data(block.begin_handle, 0, record, &common)?;
+        self.writer.write_variable_text(&block.name)?;
+        self.end_object(block.begin_handle)?;
+
+        for (index, &(handle, entity)) in block.entities.iter().enumerate() {
+            self.prev_entity = if index > 0 { block.entities[index - 1].0 } else { 0 };
+            self.next_entity = block.entities.get(index + 1).map(|e| e.0).unwrap_or(0);
+            self.write_entity(handle, entity, block.entity_mode, record)?;
+        }
+
+        self.prev_entity = 0;
+        self.next_entity = 0;
+
+        // ENDBLK
+        self.begin_object(object_type::ENDBLK)?;
+        self.write_common_entity_data(block.end_handle, 0, record, &common)?;
+        self.end_object(block.end_handle)
+    }
+
+    // ------------------------------------------------------------------
+    // Helpers
+    // ------------------------------------------------------------------
+
+    pub(super) fn allocate_handle(&mut self) -> u64 {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        handle
+    }
+
+    pub(super) fn notify(&mut self, message: impl Into<String>, notification_type: NotificationType) {
+        self.notifications
+            .push(Notification::new(notification_type, message));
+    }
+
+    /// Handle of a layer by name, falling back to layer "0".
+    pub(super) fn layer_handle(&self, name: &str) -> u64 {
+        let layers = &self.document.layers;
+        layers
+            .get(name)
+            .or_else(|| layers.get("0"))
+            .map(|l| l.handle.value())
+            .unwrap_or(0)
+    }
+
+    /// Handle of a linetype by name, falling back to Continuous.
+    pub(super) fn line_type_handle(&self, name: &str) -> u64 {
+        let line_types = &self.document.line_types;
+        line_types
+            .get(name)
+            .or_else(|| line_types.get("Continuous"))
+            .map(|l| l.handle.value())
+            .unwrap_or(0)
+    }
+
+    /// Handle of a text style by name, falling back to Standard.
+    pub(super) fn text_style_handle(&self, name: &str) -> u64 {
+        let styles = &self.document.text_styles;
+        styles
+            .get(name)
+            .or_else(|| styles.get("Standard"))
+            .map(|s| s.handle.value())
+            .unwrap_or(0)
+    }
+
+    /// Handle of a dimension style by name, falling back to Standard.
+    pub(super) fn dim_style_handle(&self, name: &str) -> u64 {
+        let styles = &self.document.dim_styles;
+        styles
+            .get(name)
+            .or_else(|| styles.get("Standard"))
+            .map(|s| s.handle.value())
+            .unwrap_or(0)
+    }
+
+    /// Handle of a multiline style object by name.
+    pub(super) fn mline_style_handle(&self, name: &str) -> Option<u64> {
+        self.document.objects.iter().find_map(|(handle, object)| match object {
+            crate::objects::ObjectType::MLineStyle(style) if style.name.eq_ignore_ascii_case(name) => {
+                Some(handle.value())
+            }
+            _ => None,
+        })
+    }
+
+    /// Handle of a block record by name.
+    pub(super) fn block_record_handle(&self, name: &str) -> u64 {
+        self.document
+            .block_records
+            .get(name)
+            .map(|r| r.handle.value())
+            .unwrap_or(0)
+    }
+
+    #[inline]
+    pub(super) fn r13_14_only(&self) -> bool {
+        matches!(self.version, DxfVersion::AC1012 | DxfVersion::AC1014)
+    }
+    #[inline]
+    pub(super) fn r2004_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1018
+    }
+    #[inline]
+    pub(super) fn r2007_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1021
+    }
+    #[inline]
+    pub(super) fn r2010_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1024
+    }
+    #[inline]
+    pub(super) fn r2013_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1027
+    }
+    #[inline]
+    pub(super) fn r2018_plus(&self) -> bool {
+        self.version >= DxfVersion::AC1032
+    }
+}
+
+/// Table entries ordered by handle.
+fn sorted_entries<'a, T: TableEntry + 'a>(entries: impl Iterator<Item = &'a T>) -> Vec<&'a T> {
+    let mut entries: Vec<&T> = entries.collect();
+    entries.sort_by_key(|e| e.handle().value());
+    entries
+}
+
+/// Lineweight index stored in entities and layers.
+pub(super) fn line_weight_index(line_weight: LineWeight) -> u8 {
+    const WEIGHTS: [i16; 24] = [
+        0, 5, 9, 13, 15, 18, 20, 25, 30, 35, 40, 50, 53, 60, 70, 80, 90, 100, 106, 120, 140, 158,
+        200, 211,
+    ];
+    match line_weight {
+        LineWeight::ByLayer => 29,
+        LineWeight::ByBlock => 30,
+        LineWeight::Default => 31,
+        LineWeight::Value(value) => WEIGHTS
+            .iter()
+            .position(|&w| w == value)
+            .or_else(|| WEIGHTS.iter().position(|&w| w > value).map(|i| i.saturating_sub(1)))
+            .unwrap_or(WEIGHTS.len() - 1) as u8,
+    }
+}
+
+fn push_eed_point(buf: &mut Vec<u8>, code: u8, point: &Vector3) {
+    buf.push(code);
+    for value in [point.x, point.y, point.z] {
+        buf.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn push_eed_real(buf: &mut Vec<u8>, code: u8, value: f64) {
+    buf.push(code);
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Unsigned modular short (MS): 15-bit groups, high bit set on all but the last.
+pub(crate) fn write_modular_short(buf: &mut Vec<u8>, value: u32) {
+    if value < 0x8000 {
+        buf.extend_from_slice(&(value as u16).to_le_bytes());
+    } else {
+        let low = (value & 0x7FFF) as u16 | 0x8000;
+        let high = (value >> 15) as u16;
+        buf.extend_from_slice(&low.to_le_bytes());
+        buf.extend_from_slice(&high.to_le_bytes());
+    }
+}
+
+/// Unsigned modular char (MC): 7-bit groups, high bit set on all but the last.
+pub(crate) fn write_modular_char(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modular_encodings() {
+        let mut buf = Vec::new();
+        write_modular_short(&mut buf, 0x1234);
+        assert_eq!(buf, [0x34, 0x12]);
+
+        buf.clear();
+        write_modular_short(&mut buf, 0x12345);
+        assert_eq!(buf, [0x45, 0xA3, 0x02, 0x00]);
+
+        buf.clear();
+        write_modular_char(&mut buf, 300);
+        assert_eq!(buf, [0xAC, 0x02]);
+    }
+
+    #[test]
+    fn test_line_weight_index() {
+        assert_eq!(line_weight_index(LineWeight::ByLayer), 29);
+        assert_eq!(line_weight_index(LineWeight::ByBlock), 30);
+        assert_eq!(line_weight_index(LineWeight::Default), 31);
+        assert_eq!(line_weight_index(LineWeight::Value(0)), 0);
+        assert_eq!(line_weight_index(LineWeight::Value(25)), 7);
+        assert_eq!(line_weight_index(LineWeight::Value(211)), 23);
+    }
+}
@@ -10,12 +10,7 @@ use crate::types::DxfVersion;
 use super::dwg_stream_writer_base::DwgStreamWriterBase;
 use super::idwg_stream_writer::DwgStreamWriter;
 
-/// Preview data for DWG files.
-pub struct DwgPreview {
-    pub code: u8,
-    pub raw_header: Vec<u8>,
-    pub raw_image: Vec<u8>,
-}
+pub use crate::io::dwg::dwg_stream_readers::dwg_preview_reader::DwgPreview;
 
 pub struct DwgPreviewWriter;
 
@@ -68,14 +63,14 @@ impl DwgPreviewWriter {
 
         // Code RC = 1 (header)
         out.push(1);
-        // header data start
-        let header_offset = start_pos + out.len() as i64 + 12 + 5 + 32;
+        // header data start: after this entry (9 bytes) and the image one (9 bytes)
+        let header_offset = start_pos + out.len() as i64 + 8 + 9;
         out.extend_from_slice(&(header_offset as i32).to_le_bytes());
         // header data size
         out.extend_from_slice(&(preview.raw_header.len() as i32).to_le_bytes());
 
         // Code RC
-        out.push(preview.code);
+        out.push(preview.code as u8);
         // image data start
         let image_offset = header_offset + preview.raw_header.len() as i64;
         out.extend_from_slice(&(image_offset as i32).to_le_bytes());
@@ -4,7 +4,7 @@
 
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
-use crate::error::{DxfError, Result};
+use crate::error::Result;
 use crate::io::dwg::dwg_stream_readers::idwg_stream_reader::DwgReferenceType;
 use crate::types::{Color, DxfVersion, Transparency, Vector2, Vector3};
 
@@ -17,6 +17,9 @@ pub struct DwgStreamWriterBase {
     pub encoding_name: String,
     bit_shift: i32,
     last_byte: u8,
+    /// Byte position of the underlying stream, tracked so that
+    /// `position_in_bits` does not need mutable access to the stream.
+    position: u64,
 }
 
 impl DwgStreamWriterBase {
@@ -27,6 +30,7 @@ impl DwgStreamWriterBase {
             encoding_name: encoding_name.to_string(),
             bit_shift: 0,
             last_byte: 0,
+            position: 0,
         }
     }
 
@@ -187,7 +191,7 @@ impl DwgStreamWriterBase {
         if value {
             self.last_byte |= 1;
         }
-        self.stream.write_all(&[self.last_byte])?;
+        self.write_raw(&[self.last_byte])?;
         self.reset_shift();
         Ok(())
     }
@@ -198,12 +202,12 @@ impl DwgStreamWriterBase {
             self.bit_shift += 2;
         } else if self.bit_shift == 6 {
             self.last_byte |= value;
-            self.stream.write_all(&[self.last_byte])?;
+            self.write_raw(&[self.last_byte])?;
             self.reset_shift();
         } else {
             // bit_shift == 7
             self.last_byte |= value >> 1;
-            self.stream.write_all(&[self.last_byte])?;
+            self.write_raw(&[self.last_byte])?;
             self.last_byte = value << 7;
             self.bit_shift = 1;
         }
@@ -212,12 +216,11 @@ impl DwgStreamWriterBase {
 
     fn write_byte_impl(&mut self, value: u8) -> Result<()> {
         if self.bit_shift == 0 {
-            self.stream.write_all(&[value])?;
+            self.write_raw(&[value])?;
             return Ok(());
         }
         let shift = 8 - self.bit_shift;
-        self.stream
-            .write_all(&[self.last_byte | (value >> self.bit_shift as u32)])?;
+        self.write_raw(&[self.last_byte | (value >> self.bit_shift as u32)])?;
         self.last_byte = value << shift as u32;
         Ok(())
     }
@@ -225,14 +228,13 @@ impl DwgStreamWriterBase {
     fn write_bytes_impl(&mut self, arr: &[u8]) -> Result<()> {
         if self.bit_shift == 0 {
             for &b in arr {
-                self.stream.write_all(&[b])?;
+                self.write_raw(&[b])?;
             }
             return Ok(());
         }
         let num = 8 - self.bit_shift;
         for &b in arr {
-            self.stream
-                .write_all(&[self.last_byte | (b >> self.bit_shift as u32)])?;
+            self.write_raw(&[self.last_byte | (b >> self.bit_shift as u32)])?;
             self.last_byte = b << num as u32;
         }
         Ok(())
@@ -246,15 +248,14 @@ impl DwgStreamWriterBase {
     ) -> Result<()> {
         if self.bit_shift == 0 {
             for i in 0..length {
-                self.stream.write_all(&[arr[initial_index + i]])?;
+                self.write_raw(&[arr[initial_index + i]])?;
             }
             return Ok(());
         }
         let num = 8 - self.bit_shift;
         for i in 0..length {
             let b = arr[initial_index + i];
-            self.stream
-                .write_all(&[self.last_byte | (b >> self.bit_shift as u32)])?;
+            self.write_raw(&[self.last_byte | (b >> self.bit_shift as u32)])?;
             self.last_byte = b << num as u32;
         }
         Ok(())
@@ -339,8 +340,8 @@ impl DwgStreamWriterBase {
     fn write_text_unicode_impl(&mut self, value: &str) -> Result<()> {
         let bytes = value.as_bytes();
         self.write_raw_short_unsigned_impl((bytes.len() as u16) + 1)?;
-        self.stream.write_all(bytes)?;
-        self.stream.write_all(&[0])?;
+        self.write_raw(bytes)?;
+        self.write_raw(&[0])?;
         Ok(())
     }
 
@@ -462,7 +463,7 @@ impl DwgStreamWriterBase {
     fn write_en_color_book_impl(
         &mut self,
         color: &Color,
-        transparency: &Transparency,
+        _transparency: &Transparency,
         _is_book_color: bool,
     ) -> Result<()> {
         self.write_cm_color_impl(color)
@@ -521,24 +522,21 @@ impl DwgStreamWriterBase {
     }
 
     fn position_in_bits_impl(&self) -> i64 {
-        // Position depends on the stream position, which we need to query
-        // but we don't have &mut self here. We'll track it differently.
-        // Actually in the C# code: Position * 8 + BitShift
-        // where Position = stream.Position
-        // We'll need to store position externally — or just use stream_position() with &mut.
-        // For now we return 0 — actual usage of this method gets the real value via the trait.
-        0
+        self.position as i64 * 8 + self.bit_shift as i64
     }
 
-    /// Get byte position of the underlying stream.
-    fn stream_position(&mut self) -> Result<u64> {
-        Ok(self.stream.stream_position()?)
+    /// Write bytes to the underlying stream, keeping the byte position in sync.
+    fn write_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        self.stream.write_all(bytes)?;
+        self.position += bytes.len() as u64;
+        Ok(())
     }
 
     fn set_position_in_bits_impl(&mut self, pos_in_bits: i64) -> Result<()> {
         let byte_pos = pos_in_bits / 8;
         self.bit_shift = (pos_in_bits % 8) as i32;
         self.stream.seek(SeekFrom::Start(byte_pos as u64))?;
+        self.position = byte_pos as u64;
 
         if self.bit_shift > 0 {
             let mut buf = [0u8; 1];
@@ -561,6 +559,7 @@ impl DwgStreamWriterBase {
             let curr_value = self.last_byte | (last_value & mask);
             self.stream.seek(SeekFrom::Start(position))?;
             self.stream.write_all(&[curr_value])?;
+            self.position = position + 1;
         }
         Ok(())
     }
@@ -599,13 +598,7 @@ impl DwgStreamWriter for DwgStreamWriterAc12 {
     }
 
     fn position_in_bits(&self) -> i64 {
-        // Cannot query stream pos without &mut — use cached approach
-        // In practice, callers go through DwgMergedStreamWriter which tracks this.
-        // For base writers, we approximate using bit_shift only.
-        // Actual approach: the C# code uses stream.Position * 8 + bitShift
-        // We'll return a sentinel; real position_in_bits is handled by the merged writer.
-        // TODO: track position internally for non-merged usage
-        0
+        self.inner.position_in_bits_impl()
     }
 
     fn saved_position_in_bits(&self) -> i64 {
@@ -792,6 +785,7 @@ impl DwgStreamWriter for DwgStreamWriterAc12 {
 
     fn reset_stream(&mut self) -> Result<()> {
         self.inner.stream.seek(SeekFrom::Start(0))?;
+        self.inner.position = 0;
         self.inner.reset_shift();
         // Truncate by writing nothing from position 0
         // WriteSeek doesn't expose set_len, so we reset shift and position only
@@ -817,8 +811,7 @@ impl DwgStreamWriter for DwgStreamWriterAc12 {
 
 /// Helper to get stream position + bit shift as position in bits.
 fn position_in_bits_of(base: &mut DwgStreamWriterBase) -> i64 {
-    let pos = base.stream.stream_position().unwrap_or(0);
-    pos as i64 * 8 + base.bit_shift as i64
+    base.position_in_bits_impl()
 }
 
 // ─────────────────────────────── AC15 ───────────────────────────────
@@ -1141,7 +1134,7 @@ impl DwgStreamWriter for DwgStreamWriterAc18 {
                 arr[0] = *i;
             }
             Color::ByBlock => {
-                arr[3] = 0b1100_0000;
+                arr[3] = 0b1100_0001;
             }
         }
 
@@ -1174,7 +1167,10 @@ impl DwgStreamWriter for DwgStreamWriterAc18 {
             Color::Index(i) => {
                 size |= *i as u16;
             }
-            _ => {}
+            Color::ByLayer => {
+                size |= 256;
+            }
+            Color::ByBlock => {}
         }
 
         base.write_bit_short_impl(size as i16)?;
@@ -1225,7 +1221,10 @@ impl DwgStreamWriter for DwgStreamWriterAc18 {
                 Color::Index(i) => {
                     size |= *i as u16;
                 }
-                _ => {}
+                Color::ByLayer => {
+                    size |= 256;
+                }
+                Color::ByBlock => {}
             }
         }
 
@@ -1402,7 +1401,7 @@ impl DwgStreamWriter for DwgStreamWriterAc21 {
         let bytes: Vec<u8> = utf16.iter().flat_map(|c| c.to_le_bytes()).collect();
         base.write_bytes_impl(&bytes)?;
         // Null terminator (2 bytes for UTF-16)
-        base.stream.write_all(&[0, 0])?;
+        base.write_raw(&[0, 0])?;
         Ok(())
     }
 
@@ -1546,7 +1545,7 @@ impl DwgStreamWriter for DwgStreamWriterAc24 {
         if value <= 255 {
             base.write_2_bits_impl(0)?;
             base.write_byte_impl(value as u8)?;
-        } else if value >= 0x1F0 && value <= 0x2EF {
+        } else if (0x1F0..=0x2EF).contains(&value) {
             base.write_2_bits_impl(1)?;
             base.write_byte_impl((value - 0x1F0) as u8)?;
         } else {
@@ -8,7 +8,9 @@ use std::io::{Cursor, Seek, SeekFrom, Write};
 
 use crate::document::{CadDocument, HeaderVariables};
 use crate::error::{DxfError, Result};
+use crate::notification::NotificationCollection;
 use crate::types::DxfVersion;
+use crate::io::downgrade::DowngradePass;
 use crate::io::dwg::DwgFileHeader;
 use crate::io::dwg::DwgSectionDefinition;
 
@@ -17,8 +19,10 @@ use super::dwg_aux_header_writer::DwgAuxHeaderWriter;
 use super::dwg_classes_writer::DwgClassesWriter;
 use super::dwg_file_header_writer_ac15::DwgFileHeaderWriterAc15;
 use super::dwg_file_header_writer_ac18::DwgFileHeaderWriterAc18;
+use super::dwg_file_header_writer_ac21::DwgFileHeaderWriterAc21;
 use super::dwg_handle_writer::DwgHandleWriter;
 use super::dwg_header_writer::DwgHeaderWriter;
+use super::dwg_object_writer::DwgObjectWriter;
 use super::dwg_preview_writer::{DwgPreview, DwgPreviewWriter};
 use super::dwg_writer_configuration::DwgWriterConfiguration;
 use super::idwg_stream_writer::DwgFileHeaderWriter;
@@ -29,7 +33,10 @@ pub struct DwgWriter<W: Write + Seek> {
     document: CadDocument,
     config: DwgWriterConfiguration,
     preview: Option<DwgPreview>,
+    downgrade: DowngradePass,
     handles_map: HashMap<u64, i64>,
+    objects_data: Vec<u8>,
+    handle_seed: u64,
 }
 
 impl DwgWriter<std::io::BufWriter<std::fs::File>> {
@@ -52,7 +59,10 @@ impl<W: Write + Seek> DwgWriter<W> {
             document,
             config: DwgWriterConfiguration::default(),
             preview: None,
+            downgrade: DowngradePass::default(),
             handles_map: HashMap::new(),
+            objects_data: Vec::new(),
+            handle_seed: 0,
         }
     }
 
@@ -68,6 +78,19 @@ impl<W: Write + Seek> DwgWriter<W> {
         self
     }
 
+    /// Set the pass that converts the entities the document version cannot
+    /// store. The default pass uses all built-in converters.
+    pub fn with_downgrade_pass(mut self, downgrade: DowngradePass) -> Self {
+        self.downgrade = downgrade;
+        self
+    }
+
+    /// Notifications raised while writing (entities or objects that could
+    /// not be written).
+    pub fn notifications(&self) -> &NotificationCollection {
+        &self.document.notifications
+    }
+
     /// Write the DWG file.
     pub fn write(&mut self) -> Result<()> {
         let version = self.document.version;
@@ -79,18 +102,23 @@ impl<W: Write + Seek> DwgWriter<W> {
                 version
             )));
         }
-        if version == DxfVersion::AC1021 {
-            return Err(DxfError::UnsupportedVersion(
-                "AC1021 (2007) writing not currently supported".into(),
-            ));
-        }
+
+        // Convert the entities this version cannot store
+        self.downgrade.run(&mut self.document, version);
 
         let maint_ver = version.maintenance_version();
 
         let mut file_header_writer: Box<dyn DwgFileHeaderWriter> = match version {
             DxfVersion::AC1014 | DxfVersion::AC1015 => {
                 Box::new(DwgFileHeaderWriterAc15::new(
-                    Box::new(Cursor::new(Vec::new())),
+                    version,
+                    version.to_string(),
+                    "windows-1252".to_string(),
+                    maint_ver,
+                ))
+            }
+            DxfVersion::AC1021 => {
+                Box::new(DwgFileHeaderWriterAc21::new(
                     version,
                     version.to_string(),
                     "windows-1252".to_string(),
@@ -116,6 +144,11 @@ impl<W: Write + Seek> DwgWriter<W> {
             }
         };
 
+        // Serialize the objects first: the object writer allocates handles
+        // for sub-entities the document does not store, and both the header
+        // HANDSEED and the HANDLES section depend on its output.
+        self.serialize_objects(version)?;
+
         // Write all sections
         self.write_header(version, &mut *file_header_writer)?;
         self.write_classes(version, &mut *file_header_writer)?;
@@ -125,23 +158,16 @@ impl<W: Write + Seek> DwgWriter<W> {
         self.write_file_dep_list(version, &mut *file_header_writer)?;
         self.write_rev_history(version, &mut *file_header_writer)?;
         self.write_aux_header(version, &mut *file_header_writer)?;
-        // Objects section: writeObjects produces handles_map — currently a placeholder
         self.write_objects(version, &mut *file_header_writer)?;
         self.write_obj_free_space(version, &mut *file_header_writer)?;
         self.write_template(version, &mut *file_header_writer)?;
         self.write_handles(version, &mut *file_header_writer)?;
 
-        // Finalize: write file header + all section data to output stream
+        // Finalize: write file header + all section data, then copy the
+        // assembled file to the output stream
         file_header_writer.write_file()?;
-
-        // Copy file header writer output to our stream
-        // The file header writer already wrote everything to its internal stream.
-        // We need to transfer that data to the output.
-        // For now the file header writer writes to its own Cursor — we'd extract
-        // the bytes and write them to self.stream.
-        // This architecture detail depends on the file header writer design:
-        // In the current implementation, the writers take the output stream reference.
-        // TODO: integrate file header writer with the output stream directly.
+        let bytes = file_header_writer.into_bytes();
+        self.stream.write_all(&bytes).map_err(DxfError::Io)?;
 
         self.stream.flush().map_err(DxfError::Io)?;
 
@@ -153,7 +179,13 @@ impl<W: Write + Seek> DwgWriter<W> {
         version: DxfVersion,
         fhw: &mut dyn DwgFileHeaderWriter,
     ) -> Result<()> {
-        let data = DwgHeaderWriter::write(version, &self.document.header)?;
+        let data = if self.handle_seed > self.document.header.handle_seed {
+            let mut header = self.document.header.clone();
+            header.handle_seed = self.handle_seed;
+            DwgHeaderWriter::write(version, &header)?
+        } else {
+            DwgHeaderWriter::write(version, &self.document.header)?
+        };
         fhw.add_section(DwgSectionDefinition::HEADER, data, true, 0);
         Ok(())
     }
@@ -182,28 +214,39 @@ impl<W: Write + Seek> DwgWriter<W> {
             return Ok(());
         }
 
-        // Write summary info section: title, subject, author, etc.
+        let summary = &self.document.summary_info;
         let mut buf = Vec::new();
 
-        // Write empty summary for now (matching ODA minimal implementation)
         // Title, Subject, Author, Keywords, Comments, LastSavedBy, RevisionNumber, HyperlinkBase
-        for _ in 0..8 {
-            // Unicode string: u16 length + UTF-16LE data
-            buf.extend_from_slice(&0u16.to_le_bytes());
+        for value in [
+            &summary.title,
+            &summary.subject,
+            &summary.author,
+            &summary.keywords,
+            &summary.comments,
+            &summary.last_saved_by,
+            &summary.revision_number,
+            &summary.hyperlink_base,
+        ] {
+            write_summary_string(&mut buf, version, value);
         }
 
         // Total editing time (two zero Int32s)
         buf.extend_from_slice(&0i32.to_le_bytes());
         buf.extend_from_slice(&0i32.to_le_bytes());
 
-        // Created date / Modified date (8 bytes each)
-        buf.extend_from_slice(&0i32.to_le_bytes());
-        buf.extend_from_slice(&0i32.to_le_bytes());
-        buf.extend_from_slice(&0i32.to_le_bytes());
-        buf.extend_from_slice(&0i32.to_le_bytes());
+        // Created date / Modified date (julian day + milliseconds)
+        for (day, ms) in [summary.created_date, summary.modified_date] {
+            buf.extend_from_slice(&day.to_le_bytes());
+            buf.extend_from_slice(&ms.to_le_bytes());
+        }
 
-        // Property count = 0
-        buf.extend_from_slice(&0u16.to_le_bytes());
+        // Int16: property count, followed by key/value string pairs
+        buf.extend_from_slice(&(summary.properties.len() as i16).to_le_bytes());
+        for (key, value) in &summary.properties {
+            write_summary_string(&mut buf, version, key);
+            write_summary_string(&mut buf, version, value);
+        }
 
         // Padding
         buf.extend_from_slice(&0i32.to_le_bytes());
@@ -218,7 +261,11 @@ impl<W: Write + Seek> DwgWriter<W> {
         version: DxfVersion,
         fhw: &mut dyn DwgFileHeaderWriter,
     ) -> Result<()> {
-        let data = DwgPreviewWriter::write_empty(version)?;
+        // Image addresses are relative to the start of the section.
+        let data = match &self.preview {
+            Some(preview) => DwgPreviewWriter::write_with_preview(version, preview, 0)?,
+            None => DwgPreviewWriter::write_empty(version)?,
+        };
         fhw.add_section(DwgSectionDefinition::PREVIEW, data, false, 0x400);
         Ok(())
     }
@@ -246,11 +293,38 @@ impl<W: Write + Seek> DwgWriter<W> {
             return Ok(());
         }
 
+        let files = &self.document.file_dependencies;
+
+        // Feature names are stored once; each file references one by index.
+        let mut features: Vec<&str> = Vec::new();
+        for file in files {
+            if !features.contains(&file.feature.as_str()) {
+                features.push(&file.feature);
+            }
+        }
+
         let mut buf = Vec::new();
-        // Feature count: 0
-        buf.extend_from_slice(&0u32.to_le_bytes());
-        // File count: 0
-        buf.extend_from_slice(&0u32.to_le_bytes());
+        buf.extend_from_slice(&(features.len() as i32).to_le_bytes());
+        for feature in &features {
+            write_string32(&mut buf, feature);
+        }
+
+        buf.extend_from_slice(&(files.len() as i32).to_le_bytes());
+        for file in files {
+            write_string32(&mut buf, &file.full_filename);
+            write_string32(&mut buf, &file.found_path);
+            write_string32(&mut buf, &file.fingerprint_guid);
+            write_string32(&mut buf, &file.version_guid);
+            let feature_index = features
+                .iter()
+                .position(|f| *f == file.feature)
+                .unwrap_or(0);
+            buf.extend_from_slice(&(feature_index as i32).to_le_bytes());
+            buf.extend_from_slice(&file.timestamp.to_le_bytes());
+            buf.extend_from_slice(&file.file_size.to_le_bytes());
+            buf.extend_from_slice(&(file.affects_graphics as i16).to_le_bytes());
+            buf.extend_from_slice(&file.reference_count.to_le_bytes());
+        }
 
         fhw.add_section(DwgSectionDefinition::FILE_DEP_LIST, buf, false, 0x80);
         Ok(())
@@ -266,9 +340,21 @@ impl<W: Write + Seek> DwgWriter<W> {
         }
 
         let mut buf = Vec::new();
-        buf.extend_from_slice(&0u32.to_le_bytes());
-        buf.extend_from_slice(&0u32.to_le_bytes());
-        buf.extend_from_slice(&0u32.to_le_bytes());
+        match &self.document.revision_history {
+            Some(history) => {
+                buf.extend_from_slice(&history.unknown1.to_le_bytes());
+                buf.extend_from_slice(&history.unknown2.to_le_bytes());
+                buf.extend_from_slice(&(history.entries.len() as u32).to_le_bytes());
+                for entry in &history.entries {
+                    buf.extend_from_slice(&entry.to_le_bytes());
+                }
+            }
+            None => {
+                buf.extend_from_slice(&0u32.to_le_bytes());
+                buf.extend_from_slice(&0u32.to_le_bytes());
+                buf.extend_from_slice(&0u32.to_le_bytes());
+            }
+        }
 
         fhw.add_section(DwgSectionDefinition::REV_HISTORY, buf, true, 0);
         Ok(())
@@ -290,33 +376,34 @@ impl<W: Write + Seek> DwgWriter<W> {
             c_ms,
             u_jdate,
             u_ms,
-            header.handle_seed,
+            header.handle_seed.max(self.handle_seed),
         )?;
 
         fhw.add_section(DwgSectionDefinition::AUX_HEADER, data, true, 0);
         Ok(())
     }
 
+    fn serialize_objects(&mut self, version: DxfVersion) -> Result<()> {
+        let result = DwgObjectWriter::new(version, &self.document).write()?;
+
+        self.handles_map = result.handle_map.into_iter().collect();
+        self.objects_data = result.data;
+        self.handle_seed = result.next_handle;
+        for notification in result.notifications {
+            self.document
+                .notifications
+                .notify(notification.notification_type, notification.message);
+        }
+        Ok(())
+    }
+
     fn write_objects(
         &mut self,
-        version: DxfVersion,
+        _version: DxfVersion,
         fhw: &mut dyn DwgFileHeaderWriter,
     ) -> Result<()> {
-        // The DwgObjectWriter is a large multi-file partial class (1400+ lines in C#).
-        // It writes all table objects, block entities, and non-graphical objects.
-        // For now, write a minimal objects section.
-        let mut buf = Vec::new();
-
-        if version >= DxfVersion::AC1018 {
-            // R2004+ start marker
-            buf.extend_from_slice(&0x0DCAi32.to_le_bytes());
-        }
-
-        // The handles_map would be populated by DwgObjectWriter.
-        // Left empty for now — this means the HANDLES section will be empty too.
-        self.handles_map.clear();
-
-        fhw.add_section(DwgSectionDefinition::ACDB_OBJECTS, buf, true, 0);
+        let data = std::mem::take(&mut self.objects_data);
+        fhw.add_section(DwgSectionDefinition::ACDB_OBJECTS, data, true, 0);
         Ok(())
     }
 
@@ -357,15 +444,30 @@ impl<W: Write + Seek> DwgWriter<W> {
 
     fn write_template(
         &self,
-        _version: DxfVersion,
+        version: DxfVersion,
         fhw: &mut dyn DwgFileHeaderWriter,
     ) -> Result<()> {
+        let (description, measurement) = match &self.document.template {
+            Some(template) => (template.description.as_str(), template.measurement),
+            None => ("", 1),
+        };
+
         let mut buf = Vec::new();
 
-        // Int16: template description length = 0
-        buf.extend_from_slice(&0i16.to_le_bytes());
-        // UInt16: MEASUREMENT (1 = Metric)
-        buf.extend_from_slice(&1u16.to_le_bytes());
+        // Int16: template description length, then the description
+        // (UTF-16 for R2007+)
+        if version >= DxfVersion::AC1021 {
+            let utf16: Vec<u16> = description.encode_utf16().collect();
+            buf.extend_from_slice(&(utf16.len() as i16).to_le_bytes());
+            for unit in utf16 {
+                buf.extend_from_slice(&unit.to_le_bytes());
+            }
+        } else {
+            buf.extend_from_slice(&(description.len() as i16).to_le_bytes());
+            buf.extend_from_slice(description.as_bytes());
+        }
+        // UInt16: MEASUREMENT (defaults to 1 = Metric)
+        buf.extend_from_slice(&measurement.to_le_bytes());
 
         fhw.add_section(DwgSectionDefinition::TEMPLATE, buf, true, 0);
         Ok(())
@@ -416,3 +518,609 @@ fn julian_from_f64(julian: f64) -> (i32, i32) {
     let ms = (frac * 86_400_000.0) as i32;
     (day, ms)
 }
+
+/// Append a String32 (Int32 byte length followed by the bytes).
+fn write_string32(buf: &mut Vec<u8>, value: &str) {
+    buf.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    buf.extend_from_slice(value.as_bytes());
+}
+
+/// Append a summary info string: Int16 length (including the terminating
+/// null) followed by the bytes, UTF-16 for R2007+.
+fn write_summary_string(buf: &mut Vec<u8>, version: DxfVersion, value: &str) {
+    if value.is_empty() {
+        buf.extend_from_slice(&0i16.to_le_bytes());
+        return;
+    }
+
+    if version >= DxfVersion::AC1021 {
+        let utf16: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
+        buf.extend_from_slice(&(utf16.len() as i16).to_le_bytes());
+        for unit in utf16 {
+            buf.extend_from_slice(&unit.to_le_bytes());
+        }
+    } else {
+        buf.extend_from_slice(&(value.len() as i16 + 1).to_le_bytes());
+        buf.extend_from_slice(value.as_bytes());
+        buf.push(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, EntityType, Line};
+    use crate::io::dwg::{DwgReader, DwgReaderConfiguration};
+    use crate::types::Vector3;
+
+    /// Write `doc` to DWG in memory and read it back
+    fn roundtrip(doc: CadDocument) -> CadDocument {
+        let bytes = write_dwg_to_bytes(doc).unwrap();
+        DwgReader::read_from_stream(Cursor::new(bytes), DwgReaderConfiguration::default()).unwrap()
+    }
+
+    /// Document with one line and one circle
+    fn line_and_circle(version: DxfVersion) -> CadDocument {
+        let mut doc = CadDocument::new();
+        doc.version = version;
+        doc.add_entity(EntityType::Line(Line::from_points(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(10.0, 5.0, 0.0),
+        )))
+        .unwrap();
+        doc.add_entity(EntityType::Circle(Circle::from_center_radius(
+            Vector3::new(1.0, 2.0, 0.0),
+            3.0,
+        )))
+        .unwrap();
+        doc
+    }
+
+    #[test]
+    fn test_write_objects_roundtrip_ac1015() {
+        let doc = roundtrip(line_and_circle(DxfVersion::AC1015));
+        assert_eq!(doc.entities().count(), 2);
+        assert!(doc.layers.get("0").is_some());
+    }
+
+    #[test]
+    fn test_write_objects_roundtrip_ac1018() {
+        let doc = roundtrip(line_and_circle(DxfVersion::AC1018));
+        assert_eq!(doc.entities().count(), 2);
+        assert!(doc.layers.get("0").is_some());
+    }
+
+    #[test]
+    fn test_write_objects_roundtrip_ac1021() {
+        let doc = roundtrip(line_and_circle(DxfVersion::AC1021));
+        assert_eq!(doc.entities().count(), 2);
+        let line = doc.entities().find_map(|e| match e {
+            EntityType::Line(l) => Some(l),
+            _ => None,
+        });
+        assert_eq!(line.unwrap().end, Vector3::new(10.0, 5.0, 0.0));
+    }
+
+    const WRITABLE_VERSIONS: [DxfVersion; 7] = [
+        DxfVersion::AC1014,
+        DxfVersion::AC1015,
+        DxfVersion::AC1018,
+        DxfVersion::AC1021,
+        DxfVersion::AC1024,
+        DxfVersion::AC1027,
+        DxfVersion::AC1032,
+    ];
+
+    #[test]
+    fn test_write_default_document_roundtrip() {
+        for version in WRITABLE_VERSIONS {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            let expected = doc.clone();
+
+            let doc = roundtrip(doc);
+
+            assert!(
+                doc.notifications.is_empty(),
+                "{:?}: {:?}",
+                version,
+                doc.notifications.iter().map(|n| &n.message).collect::<Vec<_>>()
+            );
+            assert_eq!(doc.version, version);
+            assert_eq!(doc.entities().count(), 0, "{:?}", version);
+            assert!(doc.layers.get("0").is_some(), "{:?}", version);
+            assert!(doc.block_records.get("*Model_Space").is_some(), "{:?}", version);
+            if version >= DxfVersion::AC1018 {
+                assert_eq!(doc.summary_info, expected.summary_info, "{:?}", version);
+                assert_eq!(doc.file_dependencies, expected.file_dependencies, "{:?}", version);
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_tables_roundtrip() {
+        use crate::tables::{DimStyle, Layer, LineType, LineTypeElement, TextStyle};
+        use crate::types::{Color, LineWeight};
+
+        for version in WRITABLE_VERSIONS {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+
+            let mut line_type = LineType::new("DashDot");
+            line_type.handle = doc.allocate_handle();
+            line_type.description = "_ . _ .".into();
+            line_type.add_element(LineTypeElement::dash(0.5));
+            line_type.add_element(LineTypeElement::space(0.25));
+            line_type.add_element(LineTypeElement::dot());
+            line_type.add_element(LineTypeElement::space(0.25));
+            line_type.pattern_length = 1.0;
+            doc.line_types.add(line_type).unwrap();
+
+            let mut layer = Layer::new("Walls");
+            layer.handle = doc.allocate_handle();
+            layer.color = Color::Index(5);
+            layer.line_type = "DashDot".into();
+            layer.line_weight = LineWeight::from_value(50);
+            layer.lock();
+            doc.layers.add(layer).unwrap();
+
+            let mut style = TextStyle::new("Notes");
+            style.handle = doc.allocate_handle();
+            style.height = 2.5;
+            style.width_factor = 0.8;
+            style.oblique_angle = 0.2;
+            style.font_file = "romans.shx".into();
+            doc.text_styles.add(style).unwrap();
+
+            let mut dim_style = DimStyle::new("Metric");
+            dim_style.handle = doc.allocate_handle();
+            dim_style.dimscale = 2.0;
+            dim_style.dimtxt = 3.5;
+            dim_style.dimasz = 2.5;
+            dim_style.dimtad = 1;
+            dim_style.dimpost = "<> mm".into();
+            doc.dim_styles.add(dim_style).unwrap();
+
+            let mut line =
+                Line::from_points(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+            line.common.layer = "Walls".into();
+            doc.add_entity(EntityType::Line(line)).unwrap();
+
+            let doc = roundtrip(doc);
+
+            let line_type = doc
+                .line_types
+                .get("DashDot")
+                .unwrap_or_else(|| panic!("{:?}", version));
+            assert_eq!(line_type.description, "_ . _ .", "{:?}", version);
+            assert_eq!(line_type.elements.len(), 4, "{:?}", version);
+            assert_eq!(line_type.elements[0].length, 0.5, "{:?}", version);
+            assert_eq!(line_type.elements[1].length, -0.25, "{:?}", version);
+            assert_eq!(line_type.pattern_length, 1.0, "{:?}", version);
+
+            let layer = doc
+                .layers
+                .get("Walls")
+                .unwrap_or_else(|| panic!("{:?}", version));
+            assert_eq!(layer.color, Color::Index(5), "{:?}", version);
+            assert_eq!(layer.line_type, "DashDot", "{:?}", version);
+            assert!(layer.is_locked(), "{:?}", version);
+            if version >= DxfVersion::AC1015 {
+                assert_eq!(layer.line_weight, LineWeight::from_value(50), "{:?}", version);
+            }
+
+            let style = doc
+                .text_styles
+                .get("Notes")
+                .unwrap_or_else(|| panic!("{:?}", version));
+            assert_eq!(style.height, 2.5, "{:?}", version);
+            assert_eq!(style.width_factor, 0.8, "{:?}", version);
+            assert_eq!(style.oblique_angle, 0.2, "{:?}", version);
+            assert_eq!(style.font_file, "romans.shx", "{:?}", version);
+
+            let dim_style = doc
+                .dim_styles
+                .get("Metric")
+                .unwrap_or_else(|| panic!("{:?}", version));
+            assert_eq!(dim_style.dimscale, 2.0, "{:?}", version);
+            assert_eq!(dim_style.dimtxt, 3.5, "{:?}", version);
+            assert_eq!(dim_style.dimasz, 2.5, "{:?}", version);
+            assert_eq!(dim_style.dimtad, 1, "{:?}", version);
+            assert_eq!(dim_style.dimpost, "<> mm", "{:?}", version);
+
+            assert_eq!(doc.entities().count(), 1, "{:?}", version);
+            let line = doc.entities().next().unwrap();
+            assert_eq!(line.common().layer, "Walls", "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_write_entity_types_roundtrip() {
+        use crate::entities::{
+            Arc, Body, Ellipse, Face3D, LwPolyline, Ole2Frame, Point, Ray, Region, Shape, Solid,
+            Solid3D, Spline, Tolerance, XLine,
+        };
+        use crate::types::Vector2;
+
+        const SAT: &str = "400 0 1 0\n";
+
+        for version in WRITABLE_VERSIONS {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+
+            doc.add_entity(EntityType::Point(Point::from_coords(1.0, 2.0, 3.0))).unwrap();
+            doc.add_entity(EntityType::Arc(Arc::from_center_radius_angles(
+                Vector3::new(0.0, 0.0, 0.0),
+                2.0,
+                0.5,
+                2.5,
+            )))
+            .unwrap();
+            doc.add_entity(EntityType::Ellipse(Ellipse::from_center_axes(
+                Vector3::new(5.0, 5.0, 0.0),
+                Vector3::new(3.0, 0.0, 0.0),
+                0.5,
+            )))
+            .unwrap();
+            let mut spline = Spline::from_control_points(
+                2,
+                vec![
+                    Vector3::new(0.0, 0.0, 0.0),
+                    Vector3::new(1.0, 2.0, 0.0),
+                    Vector3::new(3.0, 0.0, 0.0),
+                ],
+            );
+            spline.knots = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0];
+            doc.add_entity(EntityType::Spline(spline)).unwrap();
+            let mut lwpolyline = LwPolyline::from_points(vec![
+                Vector2::new(0.0, 0.0),
+                Vector2::new(4.0, 0.0),
+                Vector2::new(4.0, 3.0),
+            ]);
+            lwpolyline.vertices[0].bulge = 0.25;
+            doc.add_entity(EntityType::LwPolyline(lwpolyline)).unwrap();
+            doc.add_entity(EntityType::Solid(Solid::triangle(
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            )))
+            .unwrap();
+            doc.add_entity(EntityType::Face3D(Face3D::new(
+                Vector3::new(0.0, 0.0, 1.0),
+                Vector3::new(2.0, 0.0, 1.0),
+                Vector3::new(2.0, 2.0, 1.0),
+                Vector3::new(0.0, 2.0, 1.0),
+            )))
+            .unwrap();
+            doc.add_entity(EntityType::Ray(Ray::new(
+                Vector3::new(1.0, 1.0, 0.0),
+                Vector3::new(1.0, 0.0, 0.0),
+            )))
+            .unwrap();
+            doc.add_entity(EntityType::XLine(XLine::new(
+                Vector3::new(2.0, 2.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+            )))
+            .unwrap();
+            doc.add_entity(EntityType::Shape(Shape::with_number(
+                Vector3::new(6.0, 1.0, 0.0),
+                12,
+                1.5,
+            )))
+            .unwrap();
+            doc.add_entity(EntityType::Tolerance(Tolerance::with_text(
+                Vector3::new(3.0, 4.0, 0.0),
+                "{\\Fgdt;j}%%v0.1",
+            )))
+            .unwrap();
+            let mut frame = Ole2Frame::new();
+            frame.binary_data = vec![0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1];
+            doc.add_entity(EntityType::Ole2Frame(frame)).unwrap();
+            doc.add_entity(EntityType::Region(Region::from_sat(SAT))).unwrap();
+            doc.add_entity(EntityType::Solid3D(Solid3D::from_sat(SAT))).unwrap();
+            doc.add_entity(EntityType::Body(Body::from_sat(SAT))).unwrap();
+
+            let doc = roundtrip(doc);
+            assert_eq!(doc.entities().count(), 15, "{:?}", version);
+
+            for entity in doc.entities() {
+                match entity {
+                    EntityType::Point(p) => assert_eq!(p.location, Vector3::new(1.0, 2.0, 3.0)),
+                    EntityType::Arc(a) => {
+                        assert_eq!((a.radius, a.start_angle, a.end_angle), (2.0, 0.5, 2.5));
+                    }
+                    EntityType::Ellipse(e) => {
+                        assert_eq!(e.major_axis, Vector3::new(3.0, 0.0, 0.0));
+                        assert_eq!(e.minor_axis_ratio, 0.5);
+                    }
+                    EntityType::Spline(s) => {
+                        assert_eq!(s.degree, 2, "{:?}", version);
+                        assert_eq!(s.control_points.len(), 3, "{:?}", version);
+                        assert_eq!(s.knots.len(), 6, "{:?}", version);
+                    }
+                    EntityType::LwPolyline(p) => {
+                        assert_eq!(p.vertices.len(), 3, "{:?}", version);
+                        assert_eq!(p.vertices[0].bulge, 0.25);
+                        assert_eq!(p.vertices[2].location, Vector2::new(4.0, 3.0));
+                    }
+                    EntityType::Solid(s) => {
+                        assert_eq!(s.fourth_corner, Vector3::new(0.0, 1.0, 0.0));
+                    }
+                    EntityType::Face3D(f) => {
+                        assert_eq!(f.third_corner, Vector3::new(2.0, 2.0, 1.0), "{:?}", version);
+                        assert_eq!(f.fourth_corner, Vector3::new(0.0, 2.0, 1.0), "{:?}", version);
+                    }
+                    EntityType::Ray(r) => assert_eq!(r.direction, Vector3::new(1.0, 0.0, 0.0)),
+                    EntityType::XLine(x) => assert_eq!(x.base_point, Vector3::new(2.0, 2.0, 0.0)),
+                    EntityType::Shape(s) => {
+                        assert_eq!(s.insertion_point, Vector3::new(6.0, 1.0, 0.0));
+                        assert_eq!(s.size, 1.5);
+                    }
+                    EntityType::Tolerance(t) => {
+                        assert_eq!(t.text, "{\\Fgdt;j}%%v0.1", "{:?}", version);
+                    }
+                    EntityType::Ole2Frame(f) => {
+                        assert_eq!(f.binary_data, [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1]);
+                    }
+                    EntityType::Region(r) => assert_eq!(r.acis_data.sat_data, SAT, "{:?}", version),
+                    EntityType::Solid3D(s) => assert_eq!(s.acis_data.sat_data, SAT, "{:?}", version),
+                    EntityType::Body(b) => assert_eq!(b.acis_data.sat_data, SAT, "{:?}", version),
+                    other => panic!("unexpected entity {:?}", other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_multi_page_sections_roundtrip() {
+        // Enough objects to span several pages and handle map blocks
+        for version in [DxfVersion::AC1018, DxfVersion::AC1021] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            for i in 0..2000 {
+                doc.add_entity(EntityType::Line(Line::from_points(
+                    Vector3::new(i as f64, 0.0, 0.0),
+                    Vector3::new(0.0, i as f64, 0.0),
+                )))
+                .unwrap();
+            }
+
+            let doc = roundtrip(doc);
+            assert_eq!(doc.entities().count(), 2000);
+        }
+    }
+
+    #[test]
+    fn test_write_objects_roundtrip_ac1032() {
+        let doc = roundtrip(line_and_circle(DxfVersion::AC1032));
+        assert_eq!(doc.entities().count(), 2);
+        let circle = doc.entities().find_map(|e| match e {
+            EntityType::Circle(c) => Some(c),
+            _ => None,
+        });
+        assert_eq!(circle.unwrap().radius, 3.0);
+    }
+
+    #[test]
+    fn test_write_proxy_graphics_roundtrip() {
+        use crate::entities::{
+            ProxyGraphicGeometry, ProxyGraphicPrimitive, ProxyGraphics, UnknownEntity,
+        };
+
+        let mut fill = ProxyGraphicPrimitive::new(ProxyGraphicGeometry::Polygon(vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(2.0, 0.0, 0.0),
+            Vector3::new(2.0, 2.0, 0.0),
+        ]));
+        fill.filled = true;
+        let graphics = ProxyGraphics::from_primitives(vec![fill]);
+
+        for version in [
+            DxfVersion::AC1015,
+            DxfVersion::AC1018,
+            DxfVersion::AC1021,
+            DxfVersion::AC1032,
+        ] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            let mut unknown = UnknownEntity::new("ACAD_PROXY_ENTITY");
+            unknown.proxy_graphics = Some(graphics.clone());
+            doc.add_entity(EntityType::Unknown(unknown)).unwrap();
+
+            let doc = roundtrip(doc);
+            let read = doc
+                .entities()
+                .find_map(|e| match e {
+                    EntityType::Unknown(u) => u.proxy_graphics.clone(),
+                    _ => None,
+                })
+                .unwrap_or_else(|| panic!("no proxy entity read back for {:?}", version));
+            assert_eq!(read, graphics, "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_write_file_dependencies_roundtrip() {
+        use crate::document::{DrawingTemplate, FileDependency, RevisionHistory};
+
+        for version in [DxfVersion::AC1018, DxfVersion::AC1021, DxfVersion::AC1032] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            doc.file_dependencies = vec![
+                FileDependency {
+                    feature: "Acad:Text".into(),
+                    full_filename: "txt.shx".into(),
+                    timestamp: 1234,
+                    file_size: 5678,
+                    affects_graphics: true,
+                    reference_count: 2,
+                    ..Default::default()
+                },
+                FileDependency {
+                    feature: "Acad:XRef".into(),
+                    full_filename: "site.dwg".into(),
+                    found_path: "C:\\drawings\\site.dwg".into(),
+                    fingerprint_guid: "{1A2B}".into(),
+                    version_guid: "{3C4D}".into(),
+                    reference_count: 1,
+                    ..Default::default()
+                },
+            ];
+            doc.revision_history = Some(RevisionHistory {
+                unknown1: 1,
+                unknown2: 2,
+                entries: vec![7, 8, 9],
+            });
+            doc.template = Some(DrawingTemplate {
+                description: "Metric template".into(),
+                measurement: 1,
+            });
+            let expected = doc.clone();
+
+            let doc = roundtrip(doc);
+
+            assert_eq!(doc.file_dependencies, expected.file_dependencies);
+            assert_eq!(doc.revision_history, expected.revision_history);
+            assert_eq!(doc.template, expected.template);
+        }
+
+        let doc = roundtrip(line_and_circle(DxfVersion::AC1015));
+        assert_eq!(doc.template.unwrap().measurement, 1);
+    }
+
+    #[test]
+    fn test_write_summary_info_roundtrip() {
+        for version in [DxfVersion::AC1018, DxfVersion::AC1021, DxfVersion::AC1032] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+            let summary = &mut doc.summary_info;
+            summary.title = "Ground floor".into();
+            summary.subject = "Layout".into();
+            summary.author = "J. Smith".into();
+            summary.keywords = "plan, floor".into();
+            summary.comments = "Issued for review".into();
+            summary.last_saved_by = "jsmith".into();
+            summary.revision_number = "C".into();
+            summary.hyperlink_base = "https://example.com/".into();
+            summary.created_date = (2460000, 43_200_000);
+            summary.modified_date = (2460100, 1_000);
+            summary.properties.insert("Project".into(), "P-100".into());
+            summary.properties.insert("Client".into(), "ACME".into());
+            let expected = doc.summary_info.clone();
+
+            let doc = roundtrip(doc);
+
+            assert_eq!(doc.summary_info, expected);
+        }
+    }
+
+    #[test]
+    fn test_write_polyline_vertex_sequences_roundtrip() {
+        use crate::entities::{
+            PolyfaceFace, PolyfaceMesh, PolyfaceVertex, PolygonMeshEntity, PolygonMeshVertex,
+            Polyline2D, Polyline3D, Vertex2D,
+        };
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1024] {
+            let mut doc = CadDocument::new();
+            doc.version = version;
+
+            let mut polyline_2d = Polyline2D::new();
+            polyline_2d.add_vertex(Vertex2D::new(Vector3::new(0.0, 0.0, 0.0)).with_bulge(0.5));
+            polyline_2d.add_vertex(Vertex2D::new(Vector3::new(4.0, 0.0, 0.0)).with_width(1.0, 2.0));
+            polyline_2d.add_vertex(Vertex2D::new(Vector3::new(4.0, 3.0, 0.0)));
+            polyline_2d.close();
+            doc.add_entity(EntityType::Polyline2D(polyline_2d)).unwrap();
+
+            let polyline_3d = Polyline3D::from_points(vec![
+                Vector3::new(0.0, 0.0, 0.0),
+                Vector3::new(1.0, 2.0, 3.0),
+                Vector3::new(4.0, 5.0, 6.0),
+                Vector3::new(7.0, 8.0, 9.0),
+            ]);
+            doc.add_entity(EntityType::Polyline3D(polyline_3d)).unwrap();
+
+            let mut pface = PolyfaceMesh::new();
+            pface.add_vertex(PolyfaceVertex::from_xyz(0.0, 0.0, 0.0));
+            pface.add_vertex(PolyfaceVertex::from_xyz(1.0, 0.0, 0.0));
+            pface.add_vertex(PolyfaceVertex::from_xyz(0.0, 1.0, 0.0));
+            pface.add_vertex(PolyfaceVertex::from_xyz(1.0, 1.0, 1.0));
+            pface.faces.push(PolyfaceFace::triangle(1, 2, 3));
+            pface.faces.push(PolyfaceFace::quad(1, 2, 4, -3));
+            doc.add_entity(EntityType::PolyfaceMesh(pface)).unwrap();
+
+            let mut mesh = PolygonMeshEntity::new();
+            mesh.m_vertex_count = 2;
+            mesh.n_vertex_count = 2;
+            for (x, y) in [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)] {
+                mesh.vertices.push(PolygonMeshVertex::at(Vector3::new(x, y, x * y)));
+            }
+            doc.add_entity(EntityType::PolygonMesh(mesh)).unwrap();
+
+            let doc = roundtrip(doc);
+            assert_eq!(doc.entities().count(), 4, "{:?}", version);
+
+            for entity in doc.entities() {
+                match entity {
+                    EntityType::Polyline2D(p) => {
+                        assert!(p.is_closed());
+                        assert_eq!(p.vertices.len(), 3);
+                        assert_eq!(p.vertices[0].bulge, 0.5);
+                        assert_eq!(p.vertices[1].start_width, 1.0);
+                        assert_eq!(p.vertices[1].end_width, 2.0);
+                        assert_eq!(p.vertices[2].location, Vector3::new(4.0, 3.0, 0.0));
+                    }
+                    EntityType::Polyline3D(p) => {
+                        assert_eq!(p.vertices.len(), 4);
+                        assert_eq!(p.vertices[3].position, Vector3::new(7.0, 8.0, 9.0));
+                    }
+                    EntityType::PolyfaceMesh(m) => {
+                        assert_eq!(m.vertices.len(), 4);
+                        assert_eq!(m.vertices[3].location, Vector3::new(1.0, 1.0, 1.0));
+                        assert_eq!(m.faces.len(), 2);
+                        let face = &m.faces[1];
+                        assert_eq!((face.index1, face.index2, face.index3, face.index4), (1, 2, 4, -3));
+                    }
+                    EntityType::PolygonMesh(m) => {
+                        assert_eq!((m.m_vertex_count, m.n_vertex_count), (2, 2));
+                        assert_eq!(m.vertices.len(), 4);
+                        assert_eq!(m.vertices[3].location, Vector3::new(1.0, 1.0, 1.0));
+                    }
+                    other => panic!("unexpected entity {:?}", other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_preview_roundtrip() {
+        use crate::io::dwg::dwg_stream_readers::PreviewType;
+        use crate::io::dwg::PreviewImage;
+
+        let mut pixels = Vec::new();
+        for y in 0..4u8 {
+            for x in 0..6u8 {
+                pixels.extend_from_slice(&[x * 40, y * 60, 90, 255]);
+            }
+        }
+        let image = PreviewImage::new(6, 4, pixels).unwrap();
+
+        for version in [DxfVersion::AC1015, DxfVersion::AC1018, DxfVersion::AC1021] {
+            for code in [PreviewType::Bmp, PreviewType::Png] {
+                let mut doc = CadDocument::new();
+                doc.version = version;
+                let preview = DwgPreview::from_image(&image, code).unwrap();
+
+                let mut cursor = Cursor::new(Vec::new());
+                DwgWriter::new(&mut cursor, doc).with_preview(preview).write().unwrap();
+
+                let mut reader =
+                    DwgReader::new(Cursor::new(cursor.into_inner()), DwgReaderConfiguration::default())
+                        .unwrap();
+                let preview = reader.read_preview().unwrap();
+                assert_eq!(preview.code, code);
+                assert_eq!(preview.decode().unwrap(), image, "{:?} {:?}", version, code);
+            }
+        }
+    }
+}
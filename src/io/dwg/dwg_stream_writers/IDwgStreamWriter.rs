@@ -57,6 +57,11 @@ pub trait DwgStreamWriter {
         ref_type: DwgReferenceType,
         handle: u64,
     ) -> Result<()>;
+    /// Write a handle reference into the main data stream, even when the
+    /// writer keeps a separate handle stream (used for HANDSEED).
+    fn main_handle_reference(&mut self, handle: u64) -> Result<()> {
+        self.handle_reference(handle)
+    }
 
     fn write_spear_shift(&mut self) -> Result<()>;
 
@@ -103,6 +108,9 @@ pub trait DwgFileHeaderWriter {
 
     /// Finalize: write all section data and file header to the output stream.
     fn write_file(&mut self) -> Result<()>;
+
+    /// Consume the writer and return the bytes produced by `write_file`.
+    fn into_bytes(self: Box<Self>) -> Vec<u8>;
 }
 
 /// Compressor trait (LZ77 variants).
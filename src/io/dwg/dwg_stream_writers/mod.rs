@@ -30,6 +30,12 @@ pub mod dwg_file_header_writer_ac15;
 pub mod dwg_file_header_writer_ac18;
 #[path = "DwgFileHeaderWriterAC21.rs"]
 pub mod dwg_file_header_writer_ac21;
+#[path = "DwgObjectWriter.rs"]
+pub mod dwg_object_writer;
+#[path = "DwgObjectWriter.Entities.rs"]
+mod dwg_object_writer_entities;
+#[path = "DwgObjectWriter.Objects.rs"]
+mod dwg_object_writer_objects;
 #[path = "DwgWriterConfiguration.rs"]
 pub mod dwg_writer_configuration;
 #[path = "DwgWriter.rs"]
@@ -37,6 +43,7 @@ pub mod dwg_writer;
 
 pub use idwg_stream_writer::{Compressor, DwgFileHeaderWriter, DwgStreamWriter, WriteSeek};
 pub use dwg_stream_writer_base::DwgStreamWriterBase;
+pub use dwg_object_writer::{DwgObjectWriter, DwgObjectWriterResult};
 pub use dwg_lz77_ac18_compressor::DwgLz77Ac18Compressor;
 pub use dwg_lz77_ac21_compressor::DwgLz77Ac21Compressor;
 pub use dwg_preview_writer::DwgPreview;
@@ -180,6 +180,9 @@ impl DwgFileHeader {
             DxfVersion::Unknown => {
                 return Err(DxfError::UnsupportedVersion("Unknown".into()));
             }
+            DxfVersion::AC1009 => {
+                return Err(DxfError::UnsupportedVersion("AC1009".into()));
+            }
             DxfVersion::AC1012 | DxfVersion::AC1014 | DxfVersion::AC1015 => {
                 DwgFileHeaderData::AC15(DwgFileHeaderAC15::default())
             }
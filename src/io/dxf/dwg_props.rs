@@ -0,0 +1,193 @@
+//! DWGPROPS XRecord — DXF storage of the drawing properties
+//!
+//! DXF has no summary info section; AutoCAD keeps the drawing properties in
+//! an XRecord stored under the `DWGPROPS` key of the named object dictionary:
+//!
+//! | Code    | Value                                  |
+//! |---------|----------------------------------------|
+//! | 1       | `DWGPROPS COOKIE`                      |
+//! | 2       | Title                                  |
+//! | 3       | Subject                                |
+//! | 4       | Author                                 |
+//! | 6       | Comments                               |
+//! | 7       | Keywords                               |
+//! | 8       | Last saved by                          |
+//! | 9       | Revision number                        |
+//! | 300-309 | Custom property as `name=value`        |
+//! | 40      | Total editing time (days)              |
+//! | 41      | Creation date (julian)                 |
+//! | 42      | Modification date (julian)             |
+//! | 1       | Hyperlink base                         |
+
+use crate::document::{CadDocument, CadSummaryInfo};
+use crate::objects::{ObjectType, XRecord, XRecordValue};
+use crate::types::Handle;
+
+/// Named object dictionary key of the DWGPROPS XRecord
+pub(crate) const DWGPROPS_KEY: &str = "DWGPROPS";
+
+/// First string of a DWGPROPS XRecord
+const DWGPROPS_COOKIE: &str = "DWGPROPS COOKIE";
+
+const MILLISECONDS_PER_DAY: f64 = 86_400_000.0;
+
+/// Find the handle of the DWGPROPS XRecord referenced by a dictionary
+pub(crate) fn find_dwgprops(document: &CadDocument) -> Option<Handle> {
+    document.objects.values().find_map(|object| match object {
+        ObjectType::Dictionary(dict) => dict
+            .entries
+            .iter()
+            .find(|(key, handle)| {
+                key == DWGPROPS_KEY
+                    && matches!(document.objects.get(handle), Some(ObjectType::XRecord(_)))
+            })
+            .map(|(_, handle)| *handle),
+        _ => None,
+    })
+}
+
+/// Read the drawing properties stored in the document's DWGPROPS XRecord
+pub(crate) fn read_summary_info(document: &CadDocument) -> Option<CadSummaryInfo> {
+    match document.objects.get(&find_dwgprops(document)?) {
+        Some(ObjectType::XRecord(xrecord)) => from_xrecord(xrecord),
+        _ => None,
+    }
+}
+
+/// Parse a DWGPROPS XRecord; `None` when the cookie is missing
+pub(crate) fn from_xrecord(xrecord: &XRecord) -> Option<CadSummaryInfo> {
+    let mut entries = xrecord.iter();
+    let cookie = entries.next()?;
+    if cookie.code != 1 || value_string(&cookie.value)? != DWGPROPS_COOKIE {
+        return None;
+    }
+
+    let mut info = CadSummaryInfo::default();
+    for entry in entries {
+        match entry.code {
+            1 => info.hyperlink_base = value_string(&entry.value).unwrap_or_default(),
+            2 => info.title = value_string(&entry.value).unwrap_or_default(),
+            3 => info.subject = value_string(&entry.value).unwrap_or_default(),
+            4 => info.author = value_string(&entry.value).unwrap_or_default(),
+            6 => info.comments = value_string(&entry.value).unwrap_or_default(),
+            7 => info.keywords = value_string(&entry.value).unwrap_or_default(),
+            8 => info.last_saved_by = value_string(&entry.value).unwrap_or_default(),
+            9 => info.revision_number = value_string(&entry.value).unwrap_or_default(),
+            300..=309 => {
+                // Unused slots are written as a bare "="
+                if let Some((name, value)) = value_string(&entry.value)
+                    .as_deref()
+                    .and_then(|s| s.split_once('='))
+                {
+                    if !name.is_empty() {
+                        info.properties.insert(name.to_string(), value.to_string());
+                    }
+                }
+            }
+            41 => {
+                if let Some(julian) = value_double(&entry.value) {
+                    info.created_date = julian_to_pair(julian);
+                }
+            }
+            42 => {
+                if let Some(julian) = value_double(&entry.value) {
+                    info.modified_date = julian_to_pair(julian);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(info)
+}
+
+/// Build the DWGPROPS XRecord for the given drawing properties
+pub(crate) fn to_xrecord(info: &CadSummaryInfo, handle: Handle, owner: Handle) -> XRecord {
+    let mut xrecord = XRecord::new();
+    xrecord.handle = handle;
+    xrecord.owner = owner;
+
+    xrecord.add_string(1, DWGPROPS_COOKIE);
+    xrecord.add_string(2, info.title.as_str());
+    xrecord.add_string(3, info.subject.as_str());
+    xrecord.add_string(4, info.author.as_str());
+    xrecord.add_string(6, info.comments.as_str());
+    xrecord.add_string(7, info.keywords.as_str());
+    xrecord.add_string(8, info.last_saved_by.as_str());
+    xrecord.add_string(9, info.revision_number.as_str());
+
+    // AutoCAD always writes the ten custom slots; extra properties reuse 309
+    let mut properties = info.properties.iter();
+    for slot in 0..10 {
+        let value = match properties.next() {
+            Some((name, value)) => format!("{}={}", name, value),
+            None => "=".to_string(),
+        };
+        xrecord.add_string(300 + slot, value);
+    }
+    for (name, value) in properties {
+        xrecord.add_string(309, format!("{}={}", name, value));
+    }
+
+    xrecord.add_double(40, 0.0);
+    xrecord.add_double(41, pair_to_julian(info.created_date));
+    xrecord.add_double(42, pair_to_julian(info.modified_date));
+    xrecord.add_string(1, info.hyperlink_base.as_str());
+
+    xrecord
+}
+
+fn value_string(value: &XRecordValue) -> Option<String> {
+    value.as_string().map(str::to_string)
+}
+
+/// XRecord values read from DXF are kept as strings; parse them on demand
+fn value_double(value: &XRecordValue) -> Option<f64> {
+    match value {
+        XRecordValue::Double(v) => Some(*v),
+        XRecordValue::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn julian_to_pair(julian: f64) -> (i32, i32) {
+    let day = julian.floor();
+    let ms = ((julian - day) * MILLISECONDS_PER_DAY).round() as i32;
+    (day as i32, ms)
+}
+
+fn pair_to_julian((day, ms): (i32, i32)) -> f64 {
+    day as f64 + ms as f64 / MILLISECONDS_PER_DAY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dwgprops_xrecord_roundtrip() {
+        let mut info = CadSummaryInfo {
+            title: "Title".into(),
+            author: "Author".into(),
+            hyperlink_base: "https://example.com/".into(),
+            created_date: (2460000, 43_200_000),
+            modified_date: (2460001, 1_500),
+            ..Default::default()
+        };
+        for i in 0..12 {
+            info.properties.insert(format!("Key{:02}", i), format!("Value {}", i));
+        }
+
+        let xrecord = to_xrecord(&info, Handle::new(0x40), Handle::new(0xC));
+        assert_eq!(xrecord.get_string(1), Some(DWGPROPS_COOKIE));
+        assert_eq!(from_xrecord(&xrecord), Some(info));
+    }
+
+    #[test]
+    fn test_dwgprops_requires_cookie() {
+        let mut xrecord = XRecord::new();
+        xrecord.add_string(1, "something else");
+        xrecord.add_string(2, "Title");
+        assert_eq!(from_xrecord(&xrecord), None);
+    }
+}
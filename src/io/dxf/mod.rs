@@ -1,6 +1,7 @@
 //! DXF (Drawing Exchange Format) reading and writing
 
 mod dxf_code;
+mod dwg_props;
 mod group_code_value;
 mod reader;
 mod writer;
@@ -8,8 +9,8 @@ pub mod code_page;
 
 pub use dxf_code::DxfCode;
 pub use group_code_value::GroupCodeValueType;
-pub use reader::{DxfReader, DxfReaderConfiguration};
-pub use writer::{DxfWriter, DxfStreamWriter, DxfStreamWriterExt, DxfTextWriter, DxfBinaryWriter, SectionWriter};
+pub use reader::{DxfEntityStream, DxfReader, DxfReaderConfiguration, DxfStreamItem, DxfTableEntry};
+pub use writer::{DxfWriter, DxfIncrementalWriter, DxfStreamWriter, DxfStreamWriterExt, DxfTextWriter, DxfBinaryWriter, SectionWriter};
 pub use writer::{write_dxf, write_binary_dxf, value_type_for_code};
 
 
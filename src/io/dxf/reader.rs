@@ -4,15 +4,20 @@ mod stream_reader;
 mod text_reader;
 mod binary_reader;
 mod section_reader;
+mod entity_stream;
+mod parallel;
 
 pub use stream_reader::DxfStreamReader;
 pub use text_reader::DxfTextReader;
 pub use binary_reader::DxfBinaryReader;
+pub use entity_stream::{DxfEntityStream, DxfStreamItem, DxfTableEntry};
 
 use section_reader::SectionReader;
 
 use crate::document::CadDocument;
 use crate::error::Result;
+use crate::io::probe::DrawingInfo;
+use crate::notification::NotificationType;
 use crate::types::DxfVersion;
 use std::fs::File;
 use std::io::{BufReader, Read, Seek};
@@ -39,6 +44,7 @@ pub struct DxfReader {
     reader: Box<dyn DxfStreamReader>,
     version: DxfVersion,
     config: DxfReaderConfiguration,
+    parallel: bool,
 }
 
 impl DxfReader {
@@ -62,6 +68,7 @@ impl DxfReader {
             reader,
             version: DxfVersion::Unknown,
             config: DxfReaderConfiguration::default(),
+            parallel: false,
         })
     }
 
@@ -86,6 +93,7 @@ impl DxfReader {
             reader,
             version: DxfVersion::Unknown,
             config: DxfReaderConfiguration::default(),
+            parallel: false,
         })
     }
     
@@ -114,15 +122,50 @@ impl DxfReader {
         self
     }
 
-    /// Read a DXF file and return a CadDocument
+    /// Parse entities on the rayon thread pool.
+    ///
+    /// The ENTITIES and BLOCKS sections are split into one span of code
+    /// pairs per entity and the spans are parsed in parallel. Entities end
+    /// up in the document in file order, as with a sequential read. Each
+    /// section is held in memory as code pairs while it is parsed.
+    ///
+    /// Default: `false`.
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Read a DXF file and return a CadDocument.
+    ///
+    /// Errors other than I/O errors, and the notifications of a failsafe
+    /// read, carry the place in the file where the problem was found (see
+    /// [`DxfError::location`]).
+    ///
+    /// [`DxfError::location`]: crate::error::DxfError::location
     pub fn read(mut self) -> Result<CadDocument> {
         // Find and read version from header
         self.read_version()?;
 
         // Create document
         let mut document = CadDocument::new();
-        
-        // Read all sections
+        self.read_sections(&mut document)
+            .map_err(|e| e.at(self.reader.location()))?;
+
+        stream_reader::notify_invalid_values(&mut document.notifications, self.reader.take_invalid_values());
+
+        // Post-read resolution: assign owner handles and update next_handle
+        document.resolve_references();
+
+        // Drawing properties are stored in the DWGPROPS XRecord
+        if let Some(summary_info) = super::dwg_props::read_summary_info(&document) {
+            document.summary_info = summary_info;
+        }
+
+        Ok(document)
+    }
+
+    /// Read all sections into `document`
+    fn read_sections(&mut self, document: &mut CadDocument) -> Result<()> {
         let failsafe = self.config.failsafe;
 
         while let Some(pair) = self.reader.read_pair()? {
@@ -132,15 +175,15 @@ impl DxfReader {
                     if section_pair.code == 2 {
                         let section_name = section_pair.value_string.clone();
                         let result = match section_name.as_str() {
-                            "HEADER" => self.read_header_section(&mut document),
-                            "CLASSES" => self.read_classes_section(&mut document),
-                            "TABLES" => self.read_tables_section(&mut document),
-                            "BLOCKS" => self.read_blocks_section(&mut document),
-                            "ENTITIES" => self.read_entities_section(&mut document),
-                            "OBJECTS" => self.read_objects_section(&mut document),
+                            "HEADER" => self.read_header_section(document),
+                            "CLASSES" => self.read_classes_section(document),
+                            "TABLES" => self.read_tables_section(document),
+                            "BLOCKS" => self.read_blocks_section(document),
+                            "ENTITIES" => self.read_entities_section(document),
+                            "OBJECTS" => self.read_objects_section(document),
                             "THUMBNAILIMAGE" => {
                                 document.notifications.notify(
-                                    crate::notification::NotificationType::NotImplemented,
+                                    NotificationType::NotImplemented,
                                     "THUMBNAILIMAGE section skipped",
                                 );
                                 self.skip_section()
@@ -153,10 +196,13 @@ impl DxfReader {
 
                         // In failsafe mode, catch errors and continue
                         if let Err(e) = result {
+                            let e = e.at(self.reader.location());
                             if failsafe {
-                                document.notifications.notify(
-                                    crate::notification::NotificationType::Error,
-                                    format!("Error reading {} section: {}", section_name, e),
+                                let (error, location) = e.into_parts();
+                                document.notifications.notify_at(
+                                    NotificationType::Error,
+                                    format!("Error reading {} section: {}", section_name, error),
+                                    location.unwrap_or_default(),
                                 );
                                 // Try to skip to the end of the section
                                 let _ = self.skip_section();
@@ -170,13 +216,44 @@ impl DxfReader {
                 break;
             }
         }
+        Ok(())
+    }
+    
+    /// Stream the entities of the file one at a time instead of building a
+    /// whole document; see [`DxfEntityStream`].
+    ///
+    /// HEADER, CLASSES and TABLES are read before this returns.
+    pub fn stream(mut self) -> Result<DxfEntityStream> {
+        self.read_version()?;
+        DxfEntityStream::new(self.reader, self.version, self.config)
+    }
 
-        // Post-read resolution: assign owner handles and update next_handle
-        document.resolve_references();
+    /// Read only the HEADER section and return the drawing metadata.
+    ///
+    /// Reading stops at the end of the HEADER section, so tables, blocks,
+    /// entities and objects are never parsed.
+    pub fn probe(mut self) -> Result<DrawingInfo> {
+        self.read_version()?;
 
-        Ok(document)
+        let mut document = CadDocument::new();
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 && pair.value_string == "SECTION" {
+                // HEADER is always the first section when present
+                if let Some(section_pair) = self.reader.read_pair()? {
+                    if section_pair.code == 2 && section_pair.value_string == "HEADER" {
+                        self.read_header_section(&mut document)
+                            .map_err(|e| e.at(self.reader.location()))?;
+                    }
+                }
+                break;
+            } else if pair.code == 0 && pair.value_string == "EOF" {
+                break;
+            }
+        }
+
+        Ok(DrawingInfo::from_header(self.version, &document.header))
     }
-    
+
     /// Pre-scan the HEADER section for $ACADVER and $DWGCODEPAGE.
     ///
     /// After this call the reader is reset to the beginning and `self.version`
@@ -261,12 +338,18 @@ impl DxfReader {
 
     /// Read the BLOCKS section
     fn read_blocks_section(&mut self, document: &mut CadDocument) -> Result<()> {
+        if self.parallel {
+            return parallel::read_blocks(&mut self.reader, document);
+        }
         let mut section_reader = SectionReader::new(&mut self.reader);
         section_reader.read_blocks(document)
     }
 
     /// Read the ENTITIES section
     fn read_entities_section(&mut self, document: &mut CadDocument) -> Result<()> {
+        if self.parallel {
+            return parallel::read_entities(&mut self.reader, document);
+        }
         let mut section_reader = SectionReader::new(&mut self.reader);
         section_reader.read_entities(document)
     }
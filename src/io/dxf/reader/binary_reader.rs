@@ -1,7 +1,7 @@
 //! DXF binary reader
 
-use super::stream_reader::{DxfCodePair, DxfStreamReader};
-use crate::error::{DxfError, Result};
+use super::stream_reader::{DxfCodePair, DxfStreamReader, LocationTracker};
+use crate::error::{DxfError, DxfLocation, DxfPosition, Result};
 use std::io::{BufReader, Read, Seek, SeekFrom};
 
 /// Sentinel for binary DXF files
@@ -11,7 +11,9 @@ pub const BINARY_SENTINEL: &[u8] = b"AutoCAD Binary DXF\r\n\x1a\x00";
 pub struct DxfBinaryReader<R: Read + Seek> {
     reader: BufReader<R>,
     position: u64,
-    peeked_pair: Option<DxfCodePair>,
+    /// Pair read ahead or pushed back, with the offset of its code
+    peeked_pair: Option<(DxfCodePair, u64)>,
+    tracker: LocationTracker,
     /// True for pre-AC1012 format (single-byte group codes)
     /// False for AC1012+ format (two-byte group codes)
     use_single_byte_codes: bool,
@@ -45,12 +47,15 @@ impl<R: Read + Seek> DxfBinaryReader<R> {
             reader,
             position: BINARY_SENTINEL.len() as u64,
             peeked_pair: None,
+            tracker: LocationTracker::default(),
             use_single_byte_codes,
         })
     }
     
-    /// Read a code/value pair from the binary stream
-    fn read_pair_internal(&mut self) -> Result<Option<DxfCodePair>> {
+    /// Read a code/value pair from the binary stream, with the offset of
+    /// its code
+    fn read_pair_internal(&mut self) -> Result<Option<(DxfCodePair, u64)>> {
+        let offset = self.position;
         let code = if self.use_single_byte_codes {
             // Pre-AC1012: single byte codes, with 255 as escape for extended codes
             let mut code_byte = [0u8; 1];
@@ -83,9 +88,18 @@ impl<R: Read + Seek> DxfBinaryReader<R> {
         };
         
         // Read value based on code type
-        let value = self.read_value_for_code(code)?;
+        let value = self.read_value_for_code(code).map_err(|e| {
+            // A value cut short is a truncated file, not a failing stream
+            let e = match e {
+                DxfError::Io(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    DxfError::Parse(format!("Unexpected EOF after code {}", code))
+                }
+                e => e,
+            };
+            e.at(self.tracker.location_at(DxfPosition::Offset(offset), Some(code), None))
+        })?;
         
-        Ok(Some(DxfCodePair::new(code, value)))
+        Ok(Some((DxfCodePair::new(code, value), offset)))
     }
     
     /// Read a value from the binary stream based on the group code
@@ -226,23 +240,26 @@ impl<R: Read + Seek> DxfBinaryReader<R> {
 impl<R: Read + Seek> DxfStreamReader for DxfBinaryReader<R> {
     fn read_pair(&mut self) -> Result<Option<DxfCodePair>> {
         // If we have a peeked pair, return it
-        if let Some(pair) = self.peeked_pair.take() {
-            return Ok(Some(pair));
-        }
-        
-        self.read_pair_internal()
+        let pair = match self.peeked_pair.take() {
+            Some(pair) => Some(pair),
+            None => self.read_pair_internal()?,
+        };
+        Ok(pair.map(|(pair, offset)| {
+            self.tracker.pair_read(DxfPosition::Offset(offset), &pair);
+            pair
+        }))
     }
     
     fn peek_code(&mut self) -> Result<Option<i32>> {
         // If we already have a peeked pair, return its code
-        if let Some(ref pair) = self.peeked_pair {
+        if let Some((ref pair, _)) = self.peeked_pair {
             return Ok(Some(pair.code));
         }
         
         // Read the next pair and store it
-        if let Some(pair) = self.read_pair_internal()? {
+        if let Some((pair, offset)) = self.read_pair_internal()? {
             let code = pair.code;
-            self.peeked_pair = Some(pair);
+            self.peeked_pair = Some((pair, offset));
             Ok(Some(code))
         } else {
             Ok(None)
@@ -250,13 +267,19 @@ impl<R: Read + Seek> DxfStreamReader for DxfBinaryReader<R> {
     }
 
     fn push_back(&mut self, pair: DxfCodePair) {
-        self.peeked_pair = Some(pair);
+        // The pair pushed back is the one read last
+        let offset = match self.tracker.position() {
+            Some(DxfPosition::Offset(offset)) => offset,
+            _ => self.position,
+        };
+        self.peeked_pair = Some((pair, offset));
     }
     
     fn reset(&mut self) -> Result<()> {
         self.reader.seek(SeekFrom::Start(0))?;
         self.position = 0;
         self.peeked_pair = None;
+        self.tracker = LocationTracker::default();
         
         // Re-verify sentinel
         let mut sentinel = vec![0u8; BINARY_SENTINEL.len()];
@@ -274,6 +297,18 @@ impl<R: Read + Seek> DxfStreamReader for DxfBinaryReader<R> {
         self.position = BINARY_SENTINEL.len() as u64;
         Ok(())
     }
+
+    fn position(&self) -> Option<DxfPosition> {
+        self.tracker.position()
+    }
+
+    fn location(&self) -> DxfLocation {
+        self.tracker.location()
+    }
+
+    fn take_invalid_values(&mut self) -> Vec<DxfLocation> {
+        self.tracker.take_invalid_values()
+    }
 }
 
 
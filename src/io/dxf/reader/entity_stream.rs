@@ -0,0 +1,423 @@
+//! Streaming access to the records of a DXF file
+
+use super::section_reader::SectionReader;
+use super::stream_reader::{notify_invalid_values, DxfStreamReader};
+use super::DxfReaderConfiguration;
+
+use crate::document::CadDocument;
+use crate::entities::{Block, EntityType};
+use crate::error::{DxfLocation, Result};
+use crate::notification::NotificationType;
+use crate::objects::ObjectType;
+use crate::tables::{AppId, BlockRecord, DimStyle, Layer, LineType, TextStyle, Ucs, VPort, View};
+use crate::types::DxfVersion;
+use std::collections::VecDeque;
+
+/// A table entry yielded by [`DxfEntityStream`]
+#[derive(Debug, Clone)]
+pub enum DxfTableEntry {
+    /// LAYER table entry
+    Layer(Layer),
+    /// LTYPE table entry
+    LineType(LineType),
+    /// STYLE table entry
+    TextStyle(TextStyle),
+    /// BLOCK_RECORD table entry
+    BlockRecord(BlockRecord),
+    /// DIMSTYLE table entry
+    DimStyle(Box<DimStyle>),
+    /// APPID table entry
+    AppId(AppId),
+    /// VIEW table entry
+    View(View),
+    /// VPORT table entry
+    VPort(VPort),
+    /// UCS table entry
+    Ucs(Ucs),
+}
+
+/// A record yielded by [`DxfEntityStream`]
+#[derive(Debug, Clone)]
+pub enum DxfStreamItem {
+    /// Entity of the ENTITIES section
+    Entity(EntityType),
+    /// Entity of a block definition in the BLOCKS section
+    BlockEntity {
+        /// Name of the block the entity belongs to
+        block: String,
+        /// The entity
+        entity: EntityType,
+    },
+    /// Object of the OBJECTS section
+    Object(ObjectType),
+    /// Entry of the TABLES section
+    TableEntry(DxfTableEntry),
+}
+
+/// Section the stream is positioned in
+enum StreamState {
+    /// Between sections
+    Sections,
+    Blocks,
+    Entities,
+    Objects,
+    Done,
+}
+
+/// Iterator over the entities of a DXF file, created by
+/// [`DxfReader::stream`](super::DxfReader::stream).
+///
+/// HEADER, CLASSES and TABLES are read up front into a context document,
+/// available through [`document`](Self::document). Entities are then parsed
+/// one at a time as the iterator advances and are not kept, so memory use
+/// does not grow with the size of the ENTITIES section.
+///
+/// Block entities, objects and table entries are skipped unless requested
+/// with [`with_block_entities`](Self::with_block_entities),
+/// [`with_objects`](Self::with_objects) and
+/// [`with_table_entries`](Self::with_table_entries).
+///
+/// ```rust,ignore
+/// use acadrust::io::dxf::{DxfReader, DxfStreamItem};
+///
+/// let stream = DxfReader::from_file("survey.dxf")?.stream()?;
+/// for item in stream {
+///     if let DxfStreamItem::Entity(entity) = item? {
+///         println!("{}", entity.dxf_name());
+///     }
+/// }
+/// ```
+pub struct DxfEntityStream {
+    reader: Box<dyn DxfStreamReader>,
+    version: DxfVersion,
+    config: DxfReaderConfiguration,
+    document: CadDocument,
+    state: StreamState,
+    /// Block definition being read, with its block-type flags
+    block: Option<(Block, i16)>,
+    pending: VecDeque<DxfStreamItem>,
+    block_entities: bool,
+    objects: bool,
+    table_entries: bool,
+}
+
+impl DxfEntityStream {
+    pub(super) fn new(
+        reader: Box<dyn DxfStreamReader>,
+        version: DxfVersion,
+        config: DxfReaderConfiguration,
+    ) -> Result<Self> {
+        let mut stream = Self {
+            reader,
+            version,
+            config,
+            document: CadDocument::new(),
+            state: StreamState::Sections,
+            block: None,
+            pending: VecDeque::new(),
+            block_entities: false,
+            objects: false,
+            table_entries: false,
+        };
+        stream.document.version = version;
+
+        // Read the context sections up to the first streamed one
+        while matches!(stream.state, StreamState::Sections) {
+            stream.next_section()?;
+        }
+        Ok(stream)
+    }
+
+    /// Also yield the entities of block definitions
+    pub fn with_block_entities(mut self, block_entities: bool) -> Self {
+        self.block_entities = block_entities;
+        self
+    }
+
+    /// Also yield the objects of the OBJECTS section
+    pub fn with_objects(mut self, objects: bool) -> Self {
+        self.objects = objects;
+        self
+    }
+
+    /// Also yield the entries of the tables read so far and of any TABLES
+    /// section found later
+    pub fn with_table_entries(mut self, table_entries: bool) -> Self {
+        if table_entries && !self.table_entries {
+            self.queue_table_entries();
+        }
+        self.table_entries = table_entries;
+        self
+    }
+
+    /// Version of the file
+    pub fn version(&self) -> DxfVersion {
+        self.version
+    }
+
+    /// Context read so far: header variables, classes, tables and block
+    /// records (without their entities), plus the notifications raised while
+    /// streaming
+    pub fn document(&self) -> &CadDocument {
+        &self.document
+    }
+
+    /// Consume the stream and return the context document
+    pub fn into_document(self) -> CadDocument {
+        self.document
+    }
+
+    /// Read up to the next `SECTION` and either read it into the context or
+    /// position the stream in it
+    fn next_section(&mut self) -> Result<()> {
+        let Some(pair) = self.reader.read_pair()? else {
+            self.state = StreamState::Done;
+            return Ok(());
+        };
+        if pair.code != 0 {
+            return Ok(());
+        }
+        if pair.value_string == "EOF" {
+            self.state = StreamState::Done;
+            return Ok(());
+        }
+        if pair.value_string != "SECTION" {
+            return Ok(());
+        }
+        let Some(section_pair) = self.reader.read_pair()? else {
+            self.state = StreamState::Done;
+            return Ok(());
+        };
+        if section_pair.code != 2 {
+            return Ok(());
+        }
+
+        let section_name = section_pair.value_string;
+        let result = match section_name.as_str() {
+            "HEADER" => SectionReader::new(&mut self.reader).read_header(&mut self.document),
+            "CLASSES" => SectionReader::new(&mut self.reader).read_classes(&mut self.document),
+            "TABLES" => {
+                let result = SectionReader::new(&mut self.reader).read_tables(&mut self.document);
+                if self.table_entries {
+                    self.queue_table_entries();
+                }
+                result
+            }
+            "BLOCKS" => {
+                self.state = StreamState::Blocks;
+                Ok(())
+            }
+            "ENTITIES" => {
+                self.state = StreamState::Entities;
+                Ok(())
+            }
+            "OBJECTS" if self.objects => {
+                self.state = StreamState::Objects;
+                Ok(())
+            }
+            _ => self.skip_section(),
+        };
+        result.or_else(|e| self.recover(&section_name, e))
+    }
+
+    /// Read the next record of the section the stream is positioned in
+    fn next_item(&mut self) -> Result<Option<DxfStreamItem>> {
+        let Some(pair) = self.reader.read_pair()? else {
+            self.state = StreamState::Done;
+            return Ok(None);
+        };
+        if pair.code != 0 {
+            return Ok(None);
+        }
+        if pair.value_string == "ENDSEC" {
+            self.state = StreamState::Sections;
+            return Ok(None);
+        }
+
+        let location = self.reader.location();
+        let mut section_reader = SectionReader::new(&mut self.reader);
+        match self.state {
+            StreamState::Blocks => match pair.value_string.as_str() {
+                "BLOCK" => {
+                    self.block = Some(section_reader.read_block_begin()?);
+                    Ok(None)
+                }
+                "ENDBLK" => {
+                    if let Some((block, flags)) = self.block.take() {
+                        section_reader.read_block_finish(&mut self.document, &block, flags, Vec::new())?;
+                    } else {
+                        section_reader.skip_entity()?;
+                    }
+                    Ok(None)
+                }
+                entity_type => {
+                    let block = match &self.block {
+                        Some((block, _)) if self.block_entities => block.name.clone(),
+                        _ => {
+                            section_reader.skip_entity()?;
+                            return Ok(None);
+                        }
+                    };
+                    Ok(section_reader
+                        .read_entity(entity_type)?
+                        .map(|entity| DxfStreamItem::BlockEntity { block, entity }))
+                }
+            },
+            StreamState::Entities => {
+                let entity = section_reader.read_entity(&pair.value_string)?;
+                if let Some(EntityType::Unknown(unknown)) = &entity {
+                    self.document.notifications.notify_at(
+                        NotificationType::NotImplemented,
+                        format!("Entity not supported, read as UnknownEntity: {}", unknown.dxf_name),
+                        DxfLocation { handle: Some(unknown.common.handle), ..location },
+                    );
+                }
+                Ok(entity.map(DxfStreamItem::Entity))
+            }
+            StreamState::Objects => {
+                let object = section_reader.read_object(&pair.value_string)?;
+                if let Some(ObjectType::Unknown { type_name, handle, .. }) = &object {
+                    self.document.notifications.notify_at(
+                        NotificationType::NotImplemented,
+                        format!("Object not supported, read as Unknown: {}", type_name),
+                        DxfLocation { handle: Some(*handle), ..location },
+                    );
+                }
+                Ok(object.map(DxfStreamItem::Object))
+            }
+            StreamState::Sections | StreamState::Done => Ok(None),
+        }
+    }
+
+    /// Handle an error in `section`: in failsafe mode it is reported and the
+    /// rest of the section skipped, otherwise the stream ends with it
+    fn recover(&mut self, section: &str, error: crate::error::DxfError) -> Result<()> {
+        let error = error.at(self.reader.location());
+        if !self.config.failsafe {
+            self.state = StreamState::Done;
+            return Err(error);
+        }
+        let (error, location) = error.into_parts();
+        self.document.notifications.notify_at(
+            NotificationType::Error,
+            format!("Error reading {} section: {}", section, error),
+            location.unwrap_or_default(),
+        );
+        self.state = StreamState::Sections;
+        self.skip_section()
+    }
+
+    fn section_name(&self) -> &'static str {
+        match self.state {
+            StreamState::Blocks => "BLOCKS",
+            StreamState::Objects => "OBJECTS",
+            _ => "ENTITIES",
+        }
+    }
+
+    fn skip_section(&mut self) -> Result<()> {
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 && pair.value_string == "ENDSEC" {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn queue_table_entries(&mut self) {
+        let document = &self.document;
+        let entries = document.layers.iter().cloned().map(DxfTableEntry::Layer)
+            .chain(document.line_types.iter().cloned().map(DxfTableEntry::LineType))
+            .chain(document.text_styles.iter().cloned().map(DxfTableEntry::TextStyle))
+            .chain(document.block_records.iter().cloned().map(DxfTableEntry::BlockRecord))
+            .chain(document.dim_styles.iter().cloned().map(|d| DxfTableEntry::DimStyle(Box::new(d))))
+            .chain(document.app_ids.iter().cloned().map(DxfTableEntry::AppId))
+            .chain(document.views.iter().cloned().map(DxfTableEntry::View))
+            .chain(document.vports.iter().cloned().map(DxfTableEntry::VPort))
+            .chain(document.ucss.iter().cloned().map(DxfTableEntry::Ucs));
+        self.pending.extend(entries.map(DxfStreamItem::TableEntry));
+    }
+}
+
+impl Iterator for DxfEntityStream {
+    type Item = Result<DxfStreamItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Some(Ok(item));
+            }
+            let result = match self.state {
+                StreamState::Done => return None,
+                StreamState::Sections => self.next_section().map(|_| None),
+                _ => self.next_item().or_else(|e| {
+                    let section = self.section_name();
+                    self.recover(section, e).map(|_| None)
+                }),
+            };
+            notify_invalid_values(&mut self.document.notifications, self.reader.take_invalid_values());
+            match result {
+                Ok(Some(item)) => return Some(Ok(item)),
+                Ok(None) => {}
+                Err(e) => {
+                    self.state = StreamState::Done;
+                    return Some(Err(e.at(self.reader.location())));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Circle, Line};
+    use crate::io::dxf::{DxfReader, DxfWriter};
+    use crate::types::Vector3;
+    use std::io::Cursor;
+
+    fn sample() -> Vec<u8> {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Line(Line::from_points(Vector3::ZERO, Vector3::new(1.0, 0.0, 0.0))))
+            .unwrap();
+        doc.add_entity(EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 2.0)))
+            .unwrap();
+        doc.layers.add(Layer::new("Survey")).unwrap();
+        DxfWriter::new(doc).write_to_vec().unwrap()
+    }
+
+    #[test]
+    fn test_stream_entities() {
+        let stream = DxfReader::from_reader(Cursor::new(sample())).unwrap().stream().unwrap();
+        assert_eq!(stream.version(), DxfVersion::AC1032);
+        assert!(stream.document().layers.get("Survey").is_some());
+
+        let mut names: Vec<String> = stream
+            .map(|item| match item.unwrap() {
+                DxfStreamItem::Entity(entity) => entity.dxf_name().to_string(),
+                other => panic!("unexpected item {:?}", other),
+            })
+            .collect();
+        names.sort();
+        assert_eq!(names, ["CIRCLE", "LINE"]);
+    }
+
+    #[test]
+    fn test_stream_options() {
+        let stream = DxfReader::from_reader(Cursor::new(sample()))
+            .unwrap()
+            .stream()
+            .unwrap()
+            .with_objects(true)
+            .with_table_entries(true)
+            .with_block_entities(true);
+
+        let items: Vec<DxfStreamItem> = stream.map(|item| item.unwrap()).collect();
+        assert!(items.iter().any(|item| matches!(
+            item,
+            DxfStreamItem::TableEntry(DxfTableEntry::Layer(layer)) if layer.name == "Survey"
+        )));
+        assert!(items.iter().any(|item| matches!(item, DxfStreamItem::Object(ObjectType::Dictionary(_)))));
+        assert_eq!(items.iter().filter(|item| matches!(item, DxfStreamItem::Entity(_))).count(), 2);
+    }
+}
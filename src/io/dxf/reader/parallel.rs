@@ -0,0 +1,400 @@
+//! Parallel parsing of the ENTITIES and BLOCKS sections
+//!
+//! The section is split into spans, one per entity, by reading its code
+//! pairs in order; values are kept as text. Chunks of spans are parsed on the
+//! rayon pool, each span by its own [`SectionReader`] over a [`SpanReader`],
+//! while the next chunk is being read. The results are added to the document
+//! in file order, so the outcome is the same as a sequential read.
+
+use std::thread;
+
+use rayon::prelude::*;
+
+use super::section_reader::SectionReader;
+use super::stream_reader::{notify_invalid_values, DxfCodePair, DxfStreamReader, LocationTracker};
+use crate::document::CadDocument;
+use crate::entities::{Block, EntityType};
+use crate::error::{DxfError, DxfLocation, DxfPosition, Result};
+use crate::notification::NotificationType;
+
+/// Spans read before they are handed to the pool
+const SPANS_PER_CHUNK: usize = 4096;
+
+/// Spans handed to a rayon task at a time; most entities are too small to
+/// be worth a task of their own
+const MIN_SPANS_PER_TASK: usize = 64;
+
+/// A code pair of a span, with the position it was read at
+type SpanPair = (i32, String, Option<DxfPosition>);
+
+/// Code pairs of one entity, after its `0` record. Values are kept as text
+/// until the span is parsed.
+struct Span {
+    entity_type: String,
+    /// Position of the `0` record
+    position: Option<DxfPosition>,
+    pairs: Vec<SpanPair>,
+}
+
+/// A span after the parallel step
+enum Record {
+    /// BLOCK or ENDBLK, handled in order during assembly
+    Marker(Span),
+    /// Parsed entity, `None` for records that yield no entity (SEQEND).
+    /// Boxed, as records are moved around in chunks.
+    Entity {
+        entity: Result<Option<Box<EntityType>>>,
+        /// Position of the `0` record
+        position: Option<DxfPosition>,
+        /// Values that could not be converted
+        invalid_values: Vec<DxfLocation>,
+    },
+}
+
+/// Stream reader over the pairs of a span
+struct SpanReader {
+    pairs: std::vec::IntoIter<SpanPair>,
+    pushed_back: Vec<(DxfCodePair, Option<DxfPosition>)>,
+    tracker: LocationTracker,
+}
+
+impl SpanReader {
+    fn new(section: &str, span: Span) -> Self {
+        Self {
+            pairs: span.pairs.into_iter(),
+            pushed_back: Vec::new(),
+            tracker: LocationTracker::in_record(section, &span.entity_type, span.position),
+        }
+    }
+}
+
+impl DxfStreamReader for SpanReader {
+    fn read_pair(&mut self) -> Result<Option<DxfCodePair>> {
+        let pair = match self.pushed_back.pop() {
+            Some(pair) => Some(pair),
+            None => self
+                .pairs
+                .next()
+                .map(|(code, value, position)| (DxfCodePair::new(code, value), position)),
+        };
+        Ok(pair.map(|(pair, position)| {
+            if let Some(position) = position {
+                self.tracker.pair_read(position, &pair);
+            }
+            pair
+        }))
+    }
+
+    fn peek_code(&mut self) -> Result<Option<i32>> {
+        if let Some((pair, _)) = self.pushed_back.last() {
+            return Ok(Some(pair.code));
+        }
+        Ok(self.pairs.as_slice().first().map(|(code, _, _)| *code))
+    }
+
+    fn push_back(&mut self, pair: DxfCodePair) {
+        self.pushed_back.push((pair, self.tracker.position()));
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        Err(DxfError::NotImplemented("A span of code pairs cannot be read again".to_string()))
+    }
+
+    fn position(&self) -> Option<DxfPosition> {
+        self.tracker.position()
+    }
+
+    fn location(&self) -> DxfLocation {
+        self.tracker.location()
+    }
+
+    fn take_invalid_values(&mut self) -> Vec<DxfLocation> {
+        self.tracker.take_invalid_values()
+    }
+}
+
+/// Splits a section into spans
+#[derive(Default)]
+struct SpanSplitter {
+    /// Span still receiving pairs
+    current: Option<Span>,
+    /// POLYLINE reads its VERTEX records and closing SEQEND itself
+    polyline_open: bool,
+}
+
+impl SpanSplitter {
+    /// Read up to `SPANS_PER_CHUNK` complete spans; the flag is set once the
+    /// section's ENDSEC (or the end of the stream) is reached
+    fn read_chunk(&mut self, reader: &mut Box<dyn DxfStreamReader>) -> Result<(Vec<Span>, bool)> {
+        let mut spans = Vec::with_capacity(SPANS_PER_CHUNK);
+
+        while let Some((code, value)) = reader.read_raw_pair()? {
+            if code == 0 && value == "ENDSEC" {
+                spans.extend(self.current.take());
+                return Ok((spans, true));
+            }
+            let merge = code != 0
+                || (self.polyline_open && (value == "VERTEX" || value == "SEQEND"));
+            if merge {
+                if code == 0 && value == "SEQEND" {
+                    self.polyline_open = false;
+                }
+                // Pairs before the first entity are skipped, as by the
+                // sequential reader
+                if let Some(span) = self.current.as_mut() {
+                    span.pairs.push((code, value, reader.position()));
+                }
+                continue;
+            }
+
+            self.polyline_open = value == "POLYLINE";
+            let span = Span {
+                entity_type: value,
+                position: reader.position(),
+                pairs: Vec::new(),
+            };
+            spans.extend(self.current.replace(span));
+            if spans.len() == SPANS_PER_CHUNK {
+                return Ok((spans, false));
+            }
+        }
+
+        spans.extend(self.current.take());
+        Ok((spans, true))
+    }
+}
+
+/// Read the rest of a section, passing its records to `assemble` in file
+/// order. Each chunk is parsed on the pool while the next one is read.
+fn read_section(
+    reader: &mut Box<dyn DxfStreamReader>,
+    section: &'static str,
+    mut assemble: impl FnMut(Record) -> Result<()>,
+) -> Result<()> {
+    let mut splitter = SpanSplitter::default();
+
+    thread::scope(|scope| {
+        let mut parsing: Option<thread::ScopedJoinHandle<'_, Vec<Record>>> = None;
+        loop {
+            let (spans, done) = splitter.read_chunk(reader)?;
+            if let Some(parsing) = parsing.take() {
+                let records = parsing.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+                records.into_iter().try_for_each(&mut assemble)?;
+            }
+            if done {
+                return parse_spans(section, spans).into_iter().try_for_each(&mut assemble);
+            }
+            parsing = Some(scope.spawn(move || parse_spans(section, spans)));
+        }
+    })
+}
+
+/// Read the ENTITIES section, parsing entities in parallel
+pub(super) fn read_entities(
+    reader: &mut Box<dyn DxfStreamReader>,
+    document: &mut CadDocument,
+) -> Result<()> {
+    read_section(reader, "ENTITIES", |record| {
+        // Markers only mean something in BLOCKS; sequentially they are read
+        // as unknown entities
+        let (entity, position) = match record {
+            Record::Entity { entity, position, invalid_values } => {
+                notify_invalid_values(&mut document.notifications, invalid_values);
+                (entity?.map(|entity| *entity), position)
+            }
+            Record::Marker(span) => {
+                let position = span.position;
+                let (entity, invalid_values) = parse_entity("ENTITIES", span);
+                notify_invalid_values(&mut document.notifications, invalid_values);
+                (entity?, position)
+            }
+        };
+        if let Some(entity) = entity {
+            if let EntityType::Unknown(unknown) = &entity {
+                // Common fields read, entity-specific codes kept as read
+                document.notifications.notify_at(
+                    NotificationType::NotImplemented,
+                    format!("Entity not supported, read as UnknownEntity: {}", unknown.dxf_name),
+                    DxfLocation {
+                        position,
+                        section: Some("ENTITIES".to_string()),
+                        entity_type: Some(unknown.dxf_name.clone()),
+                        handle: Some(unknown.common.handle),
+                        ..Default::default()
+                    },
+                );
+            }
+            let _ = document.add_entity(entity);
+        }
+        Ok(())
+    })
+}
+
+/// Read the BLOCKS section, parsing block entities in parallel
+pub(super) fn read_blocks(
+    reader: &mut Box<dyn DxfStreamReader>,
+    document: &mut CadDocument,
+) -> Result<()> {
+    let mut current: Option<(Block, i16, Vec<EntityType>)> = None;
+
+    read_section(reader, "BLOCKS", |record| {
+        match record {
+            Record::Marker(span) if span.entity_type == "BLOCK" && current.is_some() => {
+                // A BLOCK inside a definition is just an unknown entity
+                let (entity, invalid_values) = parse_entity("BLOCKS", span);
+                notify_invalid_values(&mut document.notifications, invalid_values);
+                if let Some(entity) = entity? {
+                    if let Some((_, _, entities)) = current.as_mut() {
+                        entities.push(entity);
+                    }
+                }
+            }
+            Record::Marker(span) if span.entity_type == "BLOCK" => {
+                let mut reader: Box<dyn DxfStreamReader> = Box::new(SpanReader::new("BLOCKS", span));
+                let (block, flags) = SectionReader::new(&mut reader)
+                    .read_block_begin()
+                    .map_err(|e| e.at(reader.location()))?;
+                current = Some((block, flags, Vec::new()));
+            }
+            Record::Marker(span) => {
+                // ENDBLK; outside a definition it is ignored, like any entity
+                if let Some((block, flags, entities)) = current.take() {
+                    let mut reader: Box<dyn DxfStreamReader> = Box::new(SpanReader::new("BLOCKS", span));
+                    SectionReader::new(&mut reader)
+                        .read_block_finish(document, &block, flags, entities)
+                        .map_err(|e| e.at(reader.location()))?;
+                }
+            }
+            Record::Entity { entity, invalid_values, .. } => {
+                notify_invalid_values(&mut document.notifications, invalid_values);
+                let entity = entity?;
+                if let (Some((_, _, entities)), Some(entity)) = (current.as_mut(), entity) {
+                    entities.push(*entity);
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Parse the entity spans on the rayon pool, keeping file order
+fn parse_spans(section: &'static str, spans: Vec<Span>) -> Vec<Record> {
+    spans
+        .into_par_iter()
+        .with_min_len(MIN_SPANS_PER_TASK)
+        .map(|span| match span.entity_type.as_str() {
+            "BLOCK" | "ENDBLK" => Record::Marker(span),
+            _ => {
+                let position = span.position;
+                let (entity, invalid_values) = parse_entity(section, span);
+                Record::Entity {
+                    entity: entity.map(|entity| entity.map(Box::new)),
+                    position,
+                    invalid_values,
+                }
+            }
+        })
+        .collect()
+}
+
+/// Parse the entity of a span, returning also the values that could not be
+/// converted
+fn parse_entity(section: &str, span: Span) -> (Result<Option<EntityType>>, Vec<DxfLocation>) {
+    let entity_type = span.entity_type.clone();
+    let mut reader: Box<dyn DxfStreamReader> = Box::new(SpanReader::new(section, span));
+    let entity = SectionReader::new(&mut reader)
+        .read_entity(&entity_type)
+        .map_err(|e| e.at(reader.location()));
+    (entity, reader.take_invalid_values())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::polyline::Vertex3D;
+    use crate::entities::{Circle, Insert, Line, LwPolyline, Polyline};
+    use crate::io::dxf::{DxfReader, DxfWriter};
+    use crate::tables::BlockRecord;
+    use crate::types::{DxfVersion, Vector2, Vector3};
+    use crate::{CadDocument, EntityType};
+    use std::io::Cursor;
+
+    fn read(bytes: &[u8], parallel: bool) -> CadDocument {
+        DxfReader::from_reader(Cursor::new(bytes.to_vec()))
+            .unwrap()
+            .with_parallel(parallel)
+            .read()
+            .unwrap()
+    }
+
+    /// Handle, type and vertex count of every entity, in document order.
+    fn summary(doc: &CadDocument) -> Vec<(u64, String, usize)> {
+        doc.entities()
+            .map(|e| {
+                let vertices = match e {
+                    EntityType::Polyline(p) => p.vertices.len(),
+                    _ => 0,
+                };
+                (e.common().handle.value(), e.dxf_name().to_string(), vertices)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        for version in [DxfVersion::AC1032, DxfVersion::AC1009] {
+            let mut doc = CadDocument::with_version(version);
+            for i in 0..1500 {
+                let x = i as f64;
+                doc.add_entity(EntityType::Line(Line::from_points(
+                    Vector3::new(x, 0.0, 0.0),
+                    Vector3::new(x, 1.0, 0.0),
+                )))
+                .unwrap();
+                let mut polyline = Polyline::new();
+                polyline.add_vertex(Vertex3D::new(Vector3::new(x, 0.0, 0.0)));
+                polyline.add_vertex(Vertex3D::new(Vector3::new(x, 2.0, 0.0)));
+                polyline.add_vertex(Vertex3D::new(Vector3::new(x + 1.0, 2.0, 0.0)));
+                doc.add_entity(EntityType::Polyline(polyline)).unwrap();
+                doc.add_entity(EntityType::Insert(Insert::new("MARK", Vector3::new(x, 5.0, 0.0))))
+                    .unwrap();
+            }
+            let mut block = BlockRecord::new("MARK");
+            block.handle = doc.allocate_handle();
+            block
+                .entities
+                .push(EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 1.0)));
+            block.entities.push(EntityType::LwPolyline(LwPolyline::from_points(vec![
+                Vector2::new(0.0, 0.0),
+                Vector2::new(1.0, 0.0),
+            ])));
+            doc.block_records.add(block).unwrap();
+            let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+
+            let sequential = read(&bytes, false);
+            let parallel = read(&bytes, true);
+            assert_eq!(summary(&parallel), summary(&sequential), "{}", version);
+            assert_eq!(parallel.entities().count(), 4500);
+
+            let block_names = |doc: &CadDocument| {
+                doc.block_records
+                    .get("MARK")
+                    .unwrap()
+                    .entities
+                    .iter()
+                    .map(|e| e.dxf_name().to_string())
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(block_names(&parallel), block_names(&sequential));
+            assert_eq!(block_names(&parallel).len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_parallel_unknown_entity() {
+        let dxf = "  0\nSECTION\n  2\nENTITIES\n  0\nACME_WIDGET\n  5\n2A\n  8\nParts\n  0\nLINE\n  5\n2B\n 10\n0.0\n 20\n0.0\n 11\n1.0\n 21\n1.0\n  0\nENDSEC\n  0\nEOF\n";
+        let doc = read(dxf.as_bytes(), true);
+        assert_eq!(doc.entities().count(), 2);
+        assert!(doc.entities().any(|e| matches!(e, EntityType::Unknown(u) if u.dxf_name == "ACME_WIDGET")));
+        assert_eq!(doc.notifications.len(), 1);
+    }
+}
@@ -3,7 +3,7 @@
 use super::stream_reader::{DxfStreamReader, PointReader};
 use crate::document::CadDocument;
 use crate::entities::*;
-use crate::error::Result;
+use crate::error::{DxfLocation, Result};
 use crate::objects::*;
 use crate::tables::*;
 use crate::tables::linetype::LineTypeElement;
@@ -23,12 +23,14 @@ enum MeshReadState {
 /// Section reader for parsing DXF sections
 pub struct SectionReader<'a> {
     reader: &'a mut Box<dyn DxfStreamReader>,
+    /// Last subclass marker (100) of the entity being read
+    subclass: Option<String>,
 }
 
 impl<'a> SectionReader<'a> {
     /// Create a new section reader
     pub fn new(reader: &'a mut Box<dyn DxfStreamReader>) -> Self {
-        Self { reader }
+        Self { reader, subclass: None }
     }
     
     /// Read the HEADER section
@@ -52,7 +54,7 @@ impl<'a> SectionReader<'a> {
                         document.version = DxfVersion::from_version_string(&p.value_string);
                     }
                 }
-                "$ACADMAINTVER" => { self.reader.read_pair()?; }
+                "$ACADMAINTVER" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i32() { hdr.maintenance_version = v; } } }
                 "$REQUIREDVERSIONS" => {
                     if let Some(p) = self.reader.read_pair()? {
                         if let Some(v) = p.as_int() { hdr.required_versions = v; }
@@ -108,10 +110,28 @@ impl<'a> SectionReader<'a> {
                 "$VISRETAIN" => { if let Some(p) = self.reader.read_pair()? { hdr.retain_xref_visibility = p.as_i16() == Some(1); } }
                 "$DISPSILH" => { if let Some(p) = self.reader.read_pair()? { hdr.display_silhouette = p.as_i16() == Some(1); } }
                 "$SPLFRAME" => { if let Some(p) = self.reader.read_pair()? { hdr.spline_frame = p.as_i16() == Some(1); } }
-                "$DELOBJ" => { if let Some(p) = self.reader.read_pair()? { hdr.delete_objects = p.as_i16() == Some(1); } }
                 "$BLIPMODE" => { if let Some(p) = self.reader.read_pair()? { hdr.blip_mode = p.as_i16() == Some(1); } }
                 "$ATTREQ" => { if let Some(p) = self.reader.read_pair()? { hdr.attribute_request = p.as_i16() == Some(1); } }
                 "$ATTDIA" => { if let Some(p) = self.reader.read_pair()? { hdr.attribute_dialog = p.as_i16() == Some(1); } }
+                "$SKPOLY" => { if let Some(p) = self.reader.read_pair()? { hdr.sketch_polylines = p.as_i16() == Some(1); } }
+                "$REALWORLDSCALE" => { if let Some(p) = self.reader.read_pair()? { hdr.real_world_scale = p.as_i16() == Some(1); } }
+
+                // ── R12 Drawing Settings ──
+                "$AXISMODE" => { if let Some(p) = self.reader.read_pair()? { hdr.axis_mode = p.as_i16() == Some(1); } }
+                "$AXISUNIT" => { self.read_header_point2(&mut hdr.axis_unit)?; }
+                "$FASTZOOM" => { if let Some(p) = self.reader.read_pair()? { hdr.fast_zoom = p.as_i16() == Some(1); } }
+                "$GRIDMODE" => { if let Some(p) = self.reader.read_pair()? { hdr.grid_mode = p.as_i16() == Some(1); } }
+                "$GRIDUNIT" => { self.read_header_point2(&mut hdr.grid_unit)?; }
+                "$HANDLING" => { if let Some(p) = self.reader.read_pair()? { hdr.handling = p.as_i16() == Some(1); } }
+                "$SNAPANG" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.snap_angle = v; } } }
+                "$SNAPBASE" => { self.read_header_point2(&mut hdr.snap_base)?; }
+                "$SNAPISOPAIR" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.snap_isopair = v; } } }
+                "$SNAPMODE" => { if let Some(p) = self.reader.read_pair()? { hdr.snap_mode = p.as_i16() == Some(1); } }
+                "$SNAPSTYLE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.snap_style = v; } } }
+                "$SNAPUNIT" => { self.read_header_point2(&mut hdr.snap_unit)?; }
+                "$VIEWCTR" => { self.read_header_point2(&mut hdr.view_center)?; }
+                "$VIEWDIR" => { self.read_header_point3(&mut hdr.view_direction)?; }
+                "$VIEWSIZE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.view_size = v; } } }
 
                 // ── Drawing Mode Integers ──
                 "$DRAGMODE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.drag_mode = v; } } }
@@ -144,7 +164,6 @@ impl<'a> SectionReader<'a> {
                 "$MAXACTVP" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.max_active_viewports = v; } } }
                 "$ISOLINES" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.isolines = v; } } }
                 "$CMLJUST" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.multiline_justification = v; } } }
-                "$TEXTQLTY" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.text_quality = v; } } }
                 "$SORTENTS" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.sort_entities = v; } } }
                 "$INDEXCTL" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.index_control = v; } } }
                 "$HIDETEXT" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.hide_text = v; } } }
@@ -158,6 +177,15 @@ impl<'a> SectionReader<'a> {
                 "$MEASUREMENT" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.measurement = v; } } }
                 "$PROXYGRAPHICS" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.proxy_graphics = v; } } }
                 "$TREEDEPTH" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.tree_depth = v; } } }
+                "$UNITMODE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.unit_mode = v; } } }
+                "$SOLIDHIST" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.solid_history = v; } } }
+                "$SHOWHIST" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.show_history = v; } } }
+                "$DWFFRAME" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dwf_frame = v; } } }
+                "$DGNFRAME" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dgn_frame = v; } } }
+                "$LIGHTGLYPHDISPLAY" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.light_glyph_display = v; } } }
+                "$TILEMODELIGHTSYNCH" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.tile_model_light_sync = v; } } }
+                "$CSHADOW" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.current_shadow_mode = v; } } }
+                "$INTERFERECOLOR" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.interference_color = Color::from_index(v); } } }
 
                 // ── Scale / Size Defaults ──
                 "$LTSCALE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.linetype_scale = v; } } }
@@ -168,7 +196,6 @@ impl<'a> SectionReader<'a> {
                 "$PDSIZE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.point_display_size = v; } } }
                 "$PLINEWID" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.polyline_width = v; } } }
                 "$CELTSCALE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.current_entity_linetype_scale = v; } } }
-                "$VIEWTWIST" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.view_twist = v; } } }
                 "$FILLETRAD" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.fillet_radius = v; } } }
                 "$CHAMFERA" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.chamfer_distance_a = v; } } }
                 "$CHAMFERB" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.chamfer_distance_b = v; } } }
@@ -202,6 +229,9 @@ impl<'a> SectionReader<'a> {
                 "$LENSLENGTH" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.lens_length = v; } } }
                 "$CAMERAHEIGHT" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.camera_height = v; } } }
                 "$CAMERADISPLAY" => { if let Some(p) = self.reader.read_pair()? { hdr.camera_display = p.as_i16() == Some(1); } }
+                "$3DDWFPREC" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.dwf_precision_3d = v; } } }
+                "$PSOLWIDTH" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.polysolid_width = v; } } }
+                "$PSOLHEIGHT" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.polysolid_height = v; } } }
 
                 // ── Current Entity Settings ──
                 "$CECOLOR" => {
@@ -211,6 +241,11 @@ impl<'a> SectionReader<'a> {
                 }
                 "$CELWEIGHT" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.current_line_weight = v; } } }
                 "$CEPSNTYPE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.current_plotstyle_type = v; } } }
+                "$CEPSNID" => { if let Some(p) = self.reader.read_pair()? { if let Some(h) = p.as_handle() { hdr.current_plotstyle_handle = Handle::new(h); } } }
+                "$CMATERIAL" => { if let Some(p) = self.reader.read_pair()? { if let Some(h) = p.as_handle() { hdr.current_material_handle = Handle::new(h); } } }
+                "$DRAGVS" => { if let Some(p) = self.reader.read_pair()? { if let Some(h) = p.as_handle() { hdr.drag_visual_style_handle = Handle::new(h); } } }
+                "$INTERFEREOBJVS" => { if let Some(p) = self.reader.read_pair()? { if let Some(h) = p.as_handle() { hdr.interference_object_visual_style_handle = Handle::new(h); } } }
+                "$INTERFEREVPVS" => { if let Some(p) = self.reader.read_pair()? { if let Some(h) = p.as_handle() { hdr.interference_viewport_visual_style_handle = Handle::new(h); } } }
                 "$ENDCAPS" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.end_caps = v; } } }
                 "$JOINSTYLE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.join_style = v; } } }
                 "$LWDISPLAY" => { if let Some(p) = self.reader.read_pair()? { hdr.lineweight_display = p.as_i16() == Some(1); } }
@@ -283,6 +318,17 @@ impl<'a> SectionReader<'a> {
                 "$DIMLDRBLK" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_leader_arrow_block = p.value_string.clone(); } }
                 "$DIMLWD" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_line_weight = v; } } }
                 "$DIMLWE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_ext_line_weight = v; } } }
+                "$DIMTXSTY" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_text_style_name = p.value_string.clone(); } }
+                "$DIMLTYPE" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_linetype_name = p.value_string.clone(); } }
+                "$DIMLTEX1" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_linetype1_name = p.value_string.clone(); } }
+                "$DIMLTEX2" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_linetype2_name = p.value_string.clone(); } }
+                "$DIMFXL" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.dim_fixed_ext_line_length = v; } } }
+                "$DIMFXLON" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_fixed_ext_line_enabled = p.as_i16() == Some(1); } }
+                "$DIMJOGANG" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.dim_jog_angle = v; } } }
+                "$DIMTFILL" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_text_fill = v; } } }
+                "$DIMTFILLCLR" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_text_fill_color = Color::from_index(v); } } }
+                "$DIMARCSYM" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.dim_arc_symbol_position = v; } } }
+                "$DIMTXTDIRECTION" => { if let Some(p) = self.reader.read_pair()? { hdr.dim_text_direction = p.as_i16() == Some(1); } }
 
                 // ── Name references ──
                 "$CLAYER" => { if let Some(p) = self.reader.read_pair()? { hdr.current_layer_name = p.value_string.clone(); } }
@@ -315,18 +361,33 @@ impl<'a> SectionReader<'a> {
                 "$PUCSYDIR" => { self.read_header_point3(&mut hdr.paper_space_ucs_y_axis)?; }
                 "$UCSORTHOVIEW" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.ucs_ortho_view = v; } } }
                 "$PUCSORTHOVIEW" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_i16() { hdr.paper_ucs_ortho_view = v; } } }
+                "$UCSORTHOREF" => { if let Some(p) = self.reader.read_pair()? { hdr.ucs_ortho_ref_name = p.value_string.clone(); } }
+                "$PUCSORTHOREF" => { if let Some(p) = self.reader.read_pair()? { hdr.paper_ucs_ortho_ref_name = p.value_string.clone(); } }
+                "$PUCSBASE" => { if let Some(p) = self.reader.read_pair()? { hdr.paper_ucs_base = p.value_string.clone(); } }
+                "$UCSORGTOP" => { self.read_header_point3(&mut hdr.model_space_ucs_origin_top)?; }
+                "$UCSORGBOTTOM" => { self.read_header_point3(&mut hdr.model_space_ucs_origin_bottom)?; }
+                "$UCSORGLEFT" => { self.read_header_point3(&mut hdr.model_space_ucs_origin_left)?; }
+                "$UCSORGRIGHT" => { self.read_header_point3(&mut hdr.model_space_ucs_origin_right)?; }
+                "$UCSORGFRONT" => { self.read_header_point3(&mut hdr.model_space_ucs_origin_front)?; }
+                "$UCSORGBACK" => { self.read_header_point3(&mut hdr.model_space_ucs_origin_back)?; }
+                "$PUCSORGTOP" => { self.read_header_point3(&mut hdr.paper_space_ucs_origin_top)?; }
+                "$PUCSORGBOTTOM" => { self.read_header_point3(&mut hdr.paper_space_ucs_origin_bottom)?; }
+                "$PUCSORGLEFT" => { self.read_header_point3(&mut hdr.paper_space_ucs_origin_left)?; }
+                "$PUCSORGRIGHT" => { self.read_header_point3(&mut hdr.paper_space_ucs_origin_right)?; }
+                "$PUCSORGFRONT" => { self.read_header_point3(&mut hdr.paper_space_ucs_origin_front)?; }
+                "$PUCSORGBACK" => { self.read_header_point3(&mut hdr.paper_space_ucs_origin_back)?; }
 
                 // ── Date / Time ──
                 "$TDCREATE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.create_date_julian = v; } } }
-                "$TDUCREATE" => { self.reader.read_pair()?; } // UTC variant: skip (no field)
+                "$TDUCREATE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.utc_create_date_julian = v; } } }
                 "$TDUPDATE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.update_date_julian = v; } } }
-                "$TDUUPDATE" => { self.reader.read_pair()?; }
+                "$TDUUPDATE" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.utc_update_date_julian = v; } } }
                 "$TDINDWG" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.total_editing_time = v; } } }
                 "$TDUSRTIMER" => { if let Some(p) = self.reader.read_pair()? { if let Some(v) = p.as_double() { hdr.user_elapsed_time = v; } } }
 
                 _ => {
-                    // Skip unknown header variable value(s) – consume until next code 9 or code 0
-                    self.skip_header_variable()?;
+                    let codes = self.read_unknown_header_variable()?;
+                    hdr.unknown_variables.insert(var_name, codes);
                 }
             }
         }
@@ -362,15 +423,18 @@ impl<'a> SectionReader<'a> {
         Ok(())
     }
 
-    /// Skip an unknown header variable — consume value pairs until the next $VAR (code 9) or ENDSEC (code 0)
-    fn skip_header_variable(&mut self) -> Result<()> {
+    /// Read the value pairs of a header variable no field stores, up to the
+    /// next $VAR (code 9) or ENDSEC (code 0)
+    fn read_unknown_header_variable(&mut self) -> Result<Vec<RawGroupCode>> {
+        let mut codes = Vec::new();
         while let Some(p) = self.reader.read_pair()? {
             if p.code == 9 || p.code == 0 {
                 self.reader.push_back(p);
                 break;
             }
+            codes.push(RawGroupCode::new(p.code, p.value_string));
         }
-        Ok(())
+        Ok(codes)
     }
     
     /// Read the CLASSES section
@@ -481,7 +545,28 @@ impl<'a> SectionReader<'a> {
 
     /// Read a single BLOCK...ENDBLK definition
     fn read_block(&mut self, document: &mut CadDocument) -> Result<()> {
-        use crate::entities::Block;
+        let (block, flags) = self.read_block_begin()?;
+        let mut block_entities: Vec<EntityType> = Vec::new();
+
+        // Read entities until ENDBLK
+        while let Some(pair) = self.reader.read_pair()? {
+            if pair.code == 0 {
+                if pair.value_string == "ENDBLK" {
+                    self.read_block_finish(document, &block, flags, block_entities)?;
+                    break;
+                }
+                if let Some(entity) = self.read_entity(&pair.value_string)? {
+                    block_entities.push(entity);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the BLOCK entity that opens a block definition, returning it
+    /// with its block-type flags (code 70)
+    pub(crate) fn read_block_begin(&mut self) -> Result<(Block, i16)> {
         use crate::types::Vector3;
 
         let mut block_name = String::new();
@@ -490,6 +575,7 @@ impl<'a> SectionReader<'a> {
         let mut xref_path = String::new();
         let mut layer = String::from("0");
         let mut handle = Handle::NULL;
+        let mut flags: i16 = 0;
 
         let mut point_reader = PointReader::new();
 
@@ -529,6 +615,10 @@ impl<'a> SectionReader<'a> {
                     // Layer
                     layer = pair.value_string.clone();
                 }
+                70 => {
+                    // Block-type flags
+                    flags = pair.as_i16().unwrap_or(0);
+                }
                 10 | 20 | 30 => {
                     // Base point coordinates
                     point_reader.add_coordinate(&pair);
@@ -547,211 +637,41 @@ impl<'a> SectionReader<'a> {
         block.description = description;
         block.xref_path = xref_path;
 
-        // Find the corresponding BlockRecord and add entities to it
-        let mut block_entities: Vec<EntityType> = Vec::new();
-
-        // Read entities until ENDBLK
-        while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 0 {
-                match pair.value_string.as_str() {
-                    "ENDBLK" => {
-                        // Read ENDBLK properties
-                        let _block_end = self.read_block_end()?;
-
-                        // Find the BlockRecord and add entities
-                        if let Some(block_record) = document.block_records.get_mut(&block_name) {
-                            block_record.entities = block_entities;
-                        }
+        Ok((block, flags))
+    }
 
-                        // Note: Block and BlockEnd are block definition markers, not drawing entities.
-                        // They are not added to the document's main entity list.
-                        // The block content is stored in the BlockRecord.
+    /// Read the ENDBLK entity closing `block` and store `entities` in its
+    /// block record
+    pub(crate) fn read_block_finish(
+        &mut self,
+        document: &mut CadDocument,
+        block: &Block,
+        flags: i16,
+        entities: Vec<EntityType>,
+    ) -> Result<()> {
+        let block_end = self.read_block_end()?;
+
+        // R12 files have no BLOCK_RECORD table; the BLOCK
+        // entity is the only definition of the block
+        if document.block_records.get(&block.name).is_none() {
+            let mut block_record = BlockRecord::new(block.name.clone());
+            block_record.flags.anonymous = flags & 1 != 0;
+            block_record.flags.has_attributes = flags & 2 != 0;
+            block_record.flags.is_xref = flags & 4 != 0;
+            block_record.flags.is_xref_overlay = flags & 8 != 0;
+            block_record.flags.is_external = flags & 16 != 0;
+            block_record.block_entity_handle = block.common.handle;
+            block_record.block_end_handle = block_end.common.handle;
+            let _ = document.block_records.add(block_record);
+        }
 
-                        break;
-                    }
-                    "POINT" => {
-                        if let Some(entity) = self.read_point()? {
-                            block_entities.push(EntityType::Point(entity));
-                        }
-                    }
-                    "LINE" => {
-                        if let Some(entity) = self.read_line()? {
-                            block_entities.push(EntityType::Line(entity));
-                        }
-                    }
-                    "CIRCLE" => {
-                        if let Some(entity) = self.read_circle()? {
-                            block_entities.push(EntityType::Circle(entity));
-                        }
-                    }
-                    "ARC" => {
-                        if let Some(entity) = self.read_arc()? {
-                            block_entities.push(EntityType::Arc(entity));
-                        }
-                    }
-                    "ELLIPSE" => {
-                        if let Some(entity) = self.read_ellipse()? {
-                            block_entities.push(EntityType::Ellipse(entity));
-                        }
-                    }
-                    "POLYLINE" => {
-                        if let Some(entity) = self.read_polyline()? {
-                            block_entities.push(EntityType::Polyline(entity));
-                        }
-                    }
-                    "LWPOLYLINE" => {
-                        if let Some(entity) = self.read_lwpolyline()? {
-                            block_entities.push(EntityType::LwPolyline(entity));
-                        }
-                    }
-                    "TEXT" => {
-                        if let Some(entity) = self.read_text()? {
-                            block_entities.push(EntityType::Text(entity));
-                        }
-                    }
-                    "MTEXT" => {
-                        if let Some(entity) = self.read_mtext()? {
-                            block_entities.push(EntityType::MText(entity));
-                        }
-                    }
-                    "SPLINE" => {
-                        if let Some(entity) = self.read_spline()? {
-                            block_entities.push(EntityType::Spline(entity));
-                        }
-                    }
-                    "DIMENSION" => {
-                        if let Some(entity) = self.read_dimension()? {
-                            block_entities.push(EntityType::Dimension(entity));
-                        }
-                    }
-                    "HATCH" => {
-                        if let Some(entity) = self.read_hatch()? {
-                            block_entities.push(EntityType::Hatch(entity));
-                        }
-                    }
-                    "SOLID" | "TRACE" => {
-                        if let Some(entity) = self.read_solid()? {
-                            block_entities.push(EntityType::Solid(entity));
-                        }
-                    }
-                    "3DFACE" => {
-                        if let Some(entity) = self.read_face3d()? {
-                            block_entities.push(EntityType::Face3D(entity));
-                        }
-                    }
-                    "INSERT" => {
-                        if let Some(entity) = self.read_insert()? {
-                            block_entities.push(EntityType::Insert(entity));
-                        }
-                    }
-                    "RAY" => {
-                        if let Some(entity) = self.read_ray()? {
-                            block_entities.push(EntityType::Ray(entity));
-                        }
-                    }
-                    "XLINE" => {
-                        if let Some(entity) = self.read_xline()? {
-                            block_entities.push(EntityType::XLine(entity));
-                        }
-                    }
-                    "ATTDEF" => {
-                        if let Some(entity) = self.read_attdef()? {
-                            block_entities.push(EntityType::AttributeDefinition(entity));
-                        }
-                    }
-                    "ATTRIB" => {
-                        if let Some(entity) = self.read_attrib()? {
-                            block_entities.push(EntityType::AttributeEntity(entity));
-                        }
-                    }
-                    "TOLERANCE" => {
-                        if let Some(entity) = self.read_tolerance()? {
-                            block_entities.push(EntityType::Tolerance(entity));
-                        }
-                    }
-                    "SHAPE" => {
-                        if let Some(entity) = self.read_shape()? {
-                            block_entities.push(EntityType::Shape(entity));
-                        }
-                    }
-                    "WIPEOUT" => {
-                        if let Some(entity) = self.read_wipeout()? {
-                            block_entities.push(EntityType::Wipeout(entity));
-                        }
-                    }
-                    "VIEWPORT" => {
-                        if let Some(entity) = self.read_viewport()? {
-                            block_entities.push(EntityType::Viewport(entity));
-                        }
-                    }
-                    "LEADER" => {
-                        if let Some(entity) = self.read_leader()? {
-                            block_entities.push(EntityType::Leader(entity));
-                        }
-                    }
-                    "MULTILEADER" | "MLEADER" => {
-                        if let Some(entity) = self.read_multileader()? {
-                            block_entities.push(EntityType::MultiLeader(entity));
-                        }
-                    }
-                    "MLINE" => {
-                        if let Some(entity) = self.read_mline()? {
-                            block_entities.push(EntityType::MLine(entity));
-                        }
-                    }
-                    "MESH" => {
-                        if let Some(entity) = self.read_mesh()? {
-                            block_entities.push(EntityType::Mesh(entity));
-                        }
-                    }
-                    "IMAGE" => {
-                        if let Some(entity) = self.read_raster_image()? {
-                            block_entities.push(EntityType::RasterImage(entity));
-                        }
-                    }
-                    "3DSOLID" => {
-                        if let Some(entity) = self.read_solid3d()? {
-                            block_entities.push(EntityType::Solid3D(entity));
-                        }
-                    }
-                    "REGION" => {
-                        if let Some(entity) = self.read_region()? {
-                            block_entities.push(EntityType::Region(entity));
-                        }
-                    }
-                    "BODY" => {
-                        if let Some(entity) = self.read_body()? {
-                            block_entities.push(EntityType::Body(entity));
-                        }
-                    }
-                    "ACAD_TABLE" | "TABLE" => {
-                        if let Some(entity) = self.read_table_entity()? {
-                            block_entities.push(EntityType::Table(entity));
-                        }
-                    }
-                    "PDFUNDERLAY" | "DWFUNDERLAY" | "DGNUNDERLAY" => {
-                        if let Some(entity) = self.read_underlay(&pair.value_string)? {
-                            block_entities.push(EntityType::Underlay(entity));
-                        }
-                    }
-                    "OLE2FRAME" => {
-                        if let Some(entity) = self.read_ole2frame()? {
-                            block_entities.push(EntityType::Ole2Frame(entity));
-                        }
-                    }
-                    "SEQEND" => {
-                        // Skip SEQEND in blocks — it's consumed by polyline/insert readers
-                        self.skip_entity()?;
-                    }
-                    _ => {
-                        // Read as unknown entity in block — common fields preserved
-                        let entity = self.read_unknown_entity(&pair.value_string)?;
-                        block_entities.push(EntityType::Unknown(entity));
-                    }
-                }
-            }
+        // Find the BlockRecord and add entities
+        if let Some(block_record) = document.block_records.get_mut(&block.name) {
+            block_record.entities = entities;
         }
 
+        // Block and BlockEnd are block definition markers, not drawing
+        // entities; the block content is stored in the BlockRecord
         Ok(())
     }
 
@@ -797,202 +717,76 @@ impl<'a> SectionReader<'a> {
             if pair.code == 0 && pair.value_string == "ENDSEC" {
                 break;
             }
-            
+
             // Entities start with code 0
             if pair.code == 0 {
-                let entity_type = pair.value_string.clone();
-                
-                match entity_type.as_str() {
-                    "POINT" => {
-                        if let Some(entity) = self.read_point()? {
-                            let _ = document.add_entity(EntityType::Point(entity));
-                        }
-                    }
-                    "LINE" => {
-                        if let Some(entity) = self.read_line()? {
-                            let _ = document.add_entity(EntityType::Line(entity));
-                        }
-                    }
-                    "CIRCLE" => {
-                        if let Some(entity) = self.read_circle()? {
-                            let _ = document.add_entity(EntityType::Circle(entity));
-                        }
-                    }
-                    "ARC" => {
-                        if let Some(entity) = self.read_arc()? {
-                            let _ = document.add_entity(EntityType::Arc(entity));
-                        }
-                    }
-                    "ELLIPSE" => {
-                        if let Some(entity) = self.read_ellipse()? {
-                            let _ = document.add_entity(EntityType::Ellipse(entity));
-                        }
-                    }
-                    "POLYLINE" => {
-                        if let Some(entity) = self.read_polyline()? {
-                            let _ = document.add_entity(EntityType::Polyline(entity));
-                        }
-                    }
-                    "LWPOLYLINE" => {
-                        if let Some(entity) = self.read_lwpolyline()? {
-                            let _ = document.add_entity(EntityType::LwPolyline(entity));
-                        }
-                    }
-                    "TEXT" => {
-                        if let Some(entity) = self.read_text()? {
-                            let _ = document.add_entity(EntityType::Text(entity));
-                        }
-                    }
-                    "MTEXT" => {
-                        if let Some(entity) = self.read_mtext()? {
-                            let _ = document.add_entity(EntityType::MText(entity));
-                        }
-                    }
-                    "SPLINE" => {
-                        if let Some(entity) = self.read_spline()? {
-                            let _ = document.add_entity(EntityType::Spline(entity));
-                        }
-                    }
-                    "DIMENSION" => {
-                        if let Some(entity) = self.read_dimension()? {
-                            let _ = document.add_entity(EntityType::Dimension(entity));
-                        }
-                    }
-                    "HATCH" => {
-                        if let Some(entity) = self.read_hatch()? {
-                            let _ = document.add_entity(EntityType::Hatch(entity));
-                        }
-                    }
-                    "SOLID" | "TRACE" => {
-                        if let Some(entity) = self.read_solid()? {
-                            let _ = document.add_entity(EntityType::Solid(entity));
-                        }
-                    }
-                    "3DFACE" => {
-                        if let Some(entity) = self.read_face3d()? {
-                            let _ = document.add_entity(EntityType::Face3D(entity));
-                        }
-                    }
-                    "INSERT" => {
-                        if let Some(entity) = self.read_insert()? {
-                            let _ = document.add_entity(EntityType::Insert(entity));
-                        }
-                    }
-                    "RAY" => {
-                        if let Some(entity) = self.read_ray()? {
-                            let _ = document.add_entity(EntityType::Ray(entity));
-                        }
-                    }
-                    "XLINE" => {
-                        if let Some(entity) = self.read_xline()? {
-                            let _ = document.add_entity(EntityType::XLine(entity));
-                        }
-                    }
-                    "ATTDEF" => {
-                        if let Some(entity) = self.read_attdef()? {
-                            let _ = document.add_entity(EntityType::AttributeDefinition(entity));
-                        }
-                    }
-                    "TOLERANCE" => {
-                        if let Some(entity) = self.read_tolerance()? {
-                            let _ = document.add_entity(EntityType::Tolerance(entity));
-                        }
-                    }
-                    "SHAPE" => {
-                        if let Some(entity) = self.read_shape()? {
-                            let _ = document.add_entity(EntityType::Shape(entity));
-                        }
-                    }
-                    "WIPEOUT" => {
-                        if let Some(entity) = self.read_wipeout()? {
-                            let _ = document.add_entity(EntityType::Wipeout(entity));
-                        }
-                    }
-                    "VIEWPORT" => {
-                        if let Some(entity) = self.read_viewport()? {
-                            let _ = document.add_entity(EntityType::Viewport(entity));
-                        }
-                    }
-                    "ATTRIB" => {
-                        if let Some(entity) = self.read_attrib()? {
-                            let _ = document.add_entity(EntityType::AttributeEntity(entity));
-                        }
-                    }
-                    "LEADER" => {
-                        if let Some(entity) = self.read_leader()? {
-                            let _ = document.add_entity(EntityType::Leader(entity));
-                        }
-                    }
-                    "MULTILEADER" | "MLEADER" => {
-                        if let Some(entity) = self.read_multileader()? {
-                            let _ = document.add_entity(EntityType::MultiLeader(entity));
-                        }
-                    }
-                    "MLINE" => {
-                        if let Some(entity) = self.read_mline()? {
-                            let _ = document.add_entity(EntityType::MLine(entity));
-                        }
-                    }
-                    "MESH" => {
-                        if let Some(entity) = self.read_mesh()? {
-                            let _ = document.add_entity(EntityType::Mesh(entity));
-                        }
-                    }
-                    "IMAGE" => {
-                        if let Some(entity) = self.read_raster_image()? {
-                            let _ = document.add_entity(EntityType::RasterImage(entity));
-                        }
-                    }
-                    "3DSOLID" => {
-                        if let Some(entity) = self.read_solid3d()? {
-                            let _ = document.add_entity(EntityType::Solid3D(entity));
-                        }
-                    }
-                    "REGION" => {
-                        if let Some(entity) = self.read_region()? {
-                            let _ = document.add_entity(EntityType::Region(entity));
-                        }
-                    }
-                    "BODY" => {
-                        if let Some(entity) = self.read_body()? {
-                            let _ = document.add_entity(EntityType::Body(entity));
-                        }
-                    }
-                    "ACAD_TABLE" | "TABLE" => {
-                        if let Some(entity) = self.read_table_entity()? {
-                            let _ = document.add_entity(EntityType::Table(entity));
-                        }
-                    }
-                    "PDFUNDERLAY" | "DWFUNDERLAY" | "DGNUNDERLAY" => {
-                        if let Some(entity) = self.read_underlay(&entity_type)? {
-                            let _ = document.add_entity(EntityType::Underlay(entity));
-                        }
-                    }
-                    "OLE2FRAME" => {
-                        if let Some(entity) = self.read_ole2frame()? {
-                            let _ = document.add_entity(EntityType::Ole2Frame(entity));
-                        }
-                    }
-                    "SEQEND" => {
-                        // Standalone SEQEND — skip (normally consumed by polyline/insert reader)
-                        self.skip_entity()?;
-                    }
-                    _ => {
-                        // Read as unknown entity — common fields preserved, entity-specific codes discarded
-                        document.notifications.notify(
+                let location = self.reader.location();
+                if let Some(entity) = self.read_entity(&pair.value_string)? {
+                    if let EntityType::Unknown(unknown) = &entity {
+                        // Common fields read, entity-specific codes kept as they are
+                        document.notifications.notify_at(
                             crate::notification::NotificationType::NotImplemented,
-                            format!("Entity not supported, read as UnknownEntity: {}", entity_type),
+                            format!("Entity not supported, read as UnknownEntity: {}", unknown.dxf_name),
+                            DxfLocation { handle: Some(unknown.common.handle), ..location },
                         );
-                        let entity = self.read_unknown_entity(&entity_type)?;
-                        let _ = document.add_entity(EntityType::Unknown(entity));
                     }
+                    let _ = document.add_entity(entity);
                 }
             }
         }
-        
+
         Ok(())
     }
-    
+
+    /// Read one entity whose `0` record has just been read. Unsupported
+    /// types are read as [`EntityType::Unknown`]; a stray SEQEND is skipped.
+    pub fn read_entity(&mut self, entity_type: &str) -> Result<Option<EntityType>> {
+        self.subclass = None;
+        let entity = match entity_type {
+            "POINT" => self.read_point()?.map(EntityType::Point),
+            "LINE" => self.read_line()?.map(EntityType::Line),
+            "CIRCLE" => self.read_circle()?.map(EntityType::Circle),
+            "ARC" => self.read_arc()?.map(EntityType::Arc),
+            "ELLIPSE" => self.read_ellipse()?.map(EntityType::Ellipse),
+            "POLYLINE" => self.read_polyline()?.map(EntityType::Polyline),
+            "LWPOLYLINE" => self.read_lwpolyline()?.map(EntityType::LwPolyline),
+            "TEXT" => self.read_text()?.map(EntityType::Text),
+            "MTEXT" => self.read_mtext()?.map(EntityType::MText),
+            "SPLINE" => self.read_spline()?.map(EntityType::Spline),
+            "DIMENSION" => self.read_dimension()?.map(EntityType::Dimension),
+            "HATCH" => self.read_hatch()?.map(EntityType::Hatch),
+            "SOLID" | "TRACE" => self.read_solid()?.map(EntityType::Solid),
+            "3DFACE" => self.read_face3d()?.map(EntityType::Face3D),
+            "INSERT" => self.read_insert()?.map(EntityType::Insert),
+            "RAY" => self.read_ray()?.map(EntityType::Ray),
+            "XLINE" => self.read_xline()?.map(EntityType::XLine),
+            "ATTDEF" => self.read_attdef()?.map(EntityType::AttributeDefinition),
+            "TOLERANCE" => self.read_tolerance()?.map(EntityType::Tolerance),
+            "SHAPE" => self.read_shape()?.map(EntityType::Shape),
+            "WIPEOUT" => self.read_wipeout()?.map(EntityType::Wipeout),
+            "VIEWPORT" => self.read_viewport()?.map(EntityType::Viewport),
+            "ATTRIB" => self.read_attrib()?.map(EntityType::AttributeEntity),
+            "LEADER" => self.read_leader()?.map(EntityType::Leader),
+            "MULTILEADER" | "MLEADER" => self.read_multileader()?.map(EntityType::MultiLeader),
+            "MLINE" => self.read_mline()?.map(EntityType::MLine),
+            "MESH" => self.read_mesh()?.map(EntityType::Mesh),
+            "IMAGE" => self.read_raster_image()?.map(EntityType::RasterImage),
+            "3DSOLID" => self.read_solid3d()?.map(EntityType::Solid3D),
+            "REGION" => self.read_region()?.map(EntityType::Region),
+            "BODY" => self.read_body()?.map(EntityType::Body),
+            "ACAD_TABLE" | "TABLE" => self.read_table_entity()?.map(EntityType::Table),
+            "PDFUNDERLAY" | "DWFUNDERLAY" | "DGNUNDERLAY" => self.read_underlay(entity_type)?.map(EntityType::Underlay),
+            "OLE2FRAME" => self.read_ole2frame()?.map(EntityType::Ole2Frame),
+            "SEQEND" => {
+                // Normally consumed by the polyline/insert reader
+                self.skip_entity()?;
+                None
+            }
+            _ => Some(EntityType::Unknown(self.read_unknown_entity(entity_type)?)),
+        };
+        Ok(entity)
+    }
+
     /// Read the OBJECTS section
     pub fn read_objects(&mut self, document: &mut CadDocument) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
@@ -1001,124 +795,20 @@ impl<'a> SectionReader<'a> {
             }
 
             if pair.code == 0 {
-                match pair.value_string.as_str() {
-                    "DICTIONARY" => {
-                        if let Some(obj) = self.read_dictionary()? {
-                            document.objects.insert(obj.handle, ObjectType::Dictionary(obj));
-                        }
-                    }
-                    "LAYOUT" => {
-                        if let Some(obj) = self.read_layout()? {
-                            document.objects.insert(obj.handle, ObjectType::Layout(obj));
-                        }
-                    }
-                    "XRECORD" => {
-                        if let Some(obj) = self.read_xrecord()? {
-                            document.objects.insert(obj.handle, ObjectType::XRecord(obj));
-                        }
-                    }
-                    "GROUP" => {
-                        if let Some(obj) = self.read_group()? {
-                            document.objects.insert(obj.handle, ObjectType::Group(obj));
-                        }
-                    }
-                    "MLINESTYLE" => {
-                        if let Some(obj) = self.read_mlinestyle_object()? {
-                            document.objects.insert(obj.handle, ObjectType::MLineStyle(obj));
-                        }
-                    }
-                    "IMAGEDEF" => {
-                        if let Some(obj) = self.read_image_definition()? {
-                            document.objects.insert(obj.handle, ObjectType::ImageDefinition(obj));
-                        }
-                    }
-                    "MLEADERSTYLE" => {
-                        if let Some(obj) = self.read_multileader_style()? {
-                            document.objects.insert(obj.handle, ObjectType::MultiLeaderStyle(obj));
-                        }
-                    }
-                    "PLOTSETTINGS" => {
-                        if let Some(obj) = self.read_plot_settings()? {
-                            document.objects.insert(obj.handle, ObjectType::PlotSettings(obj));
-                        }
-                    }
-                    "TABLESTYLE" => {
-                        if let Some(obj) = self.read_table_style()? {
-                            document.objects.insert(obj.handle, ObjectType::TableStyle(obj));
+                let location = self.reader.location();
+                if let Some(object) = self.read_object(&pair.value_string)? {
+                    let handle = match &object {
+                        ObjectType::Unknown { type_name, handle, .. } => {
+                            document.notifications.notify_at(
+                                crate::notification::NotificationType::NotImplemented,
+                                format!("Object not supported, read as Unknown: {}", type_name),
+                                DxfLocation { handle: Some(*handle), ..location },
+                            );
+                            *handle
                         }
-                    }
-                    "SCALE" => {
-                        if let Some(obj) = self.read_scale()? {
-                            document.objects.insert(obj.handle, ObjectType::Scale(obj));
-                        }
-                    }
-                    "SORTENTSTABLE" => {
-                        if let Some(obj) = self.read_sort_entities_table()? {
-                            document.objects.insert(obj.handle, ObjectType::SortEntitiesTable(obj));
-                        }
-                    }
-                    "DICTIONARYVAR" => {
-                        if let Some(obj) = self.read_dictionary_variable()? {
-                            document.objects.insert(obj.handle, ObjectType::DictionaryVariable(obj));
-                        }
-                    }
-                    "VISUALSTYLE" => {
-                        if let Some(obj) = self.read_visualstyle()? {
-                            document.objects.insert(obj.handle, ObjectType::VisualStyle(obj));
-                        }
-                    }
-                    "MATERIAL" => {
-                        if let Some(obj) = self.read_material()? {
-                            document.objects.insert(obj.handle, ObjectType::Material(obj));
-                        }
-                    }
-                    "IMAGEDEF_REACTOR" => {
-                        if let Some(obj) = self.read_imagedef_reactor()? {
-                            document.objects.insert(obj.handle, ObjectType::ImageDefinitionReactor(obj));
-                        }
-                    }
-                    "GEODATA" => {
-                        let obj = self.read_stub_object::<GeoData>()?;
-                        document.objects.insert(obj.handle, ObjectType::GeoData(obj));
-                    }
-                    "SPATIALFILTER" => {
-                        let obj = self.read_stub_object::<SpatialFilter>()?;
-                        document.objects.insert(obj.handle, ObjectType::SpatialFilter(obj));
-                    }
-                    "RASTERVARIABLES" => {
-                        if let Some(obj) = self.read_raster_variables()? {
-                            document.objects.insert(obj.handle, ObjectType::RasterVariables(obj));
-                        }
-                    }
-                    "DBCOLOR" => {
-                        if let Some(obj) = self.read_bookcolor()? {
-                            document.objects.insert(obj.handle, ObjectType::BookColor(obj));
-                        }
-                    }
-                    "ACDBPLACEHOLDER" => {
-                        let obj = self.read_stub_object::<PlaceHolder>()?;
-                        document.objects.insert(obj.handle, ObjectType::PlaceHolder(obj));
-                    }
-                    "ACDBDICTIONARYWDFLT" => {
-                        // Already handled as DICTIONARY above — this handles standalone cases
-                        if let Some(obj) = self.read_dict_with_default()? {
-                            document.objects.insert(obj.handle, ObjectType::DictionaryWithDefault(obj));
-                        }
-                    }
-                    "WIPEOUTVARIABLES" => {
-                        if let Some(obj) = self.read_wipeout_variables()? {
-                            document.objects.insert(obj.handle, ObjectType::WipeoutVariables(obj));
-                        }
-                    }
-                    _ => {
-                        document.notifications.notify(
-                            crate::notification::NotificationType::NotImplemented,
-                            format!("Object not supported, read as Unknown: {}", pair.value_string),
-                        );
-                        let type_name = pair.value_string.clone();
-                        let handle = self.read_unknown_object_handle()?;
-                        document.objects.insert(handle, ObjectType::Unknown { type_name, handle });
-                    }
+                        object => object.handle(),
+                    };
+                    document.objects.insert(handle, object);
                 }
             }
         }
@@ -1126,6 +816,40 @@ impl<'a> SectionReader<'a> {
         Ok(())
     }
 
+    /// Read one object whose `0` record has just been read. Unsupported
+    /// types are read as [`ObjectType::Unknown`].
+    pub fn read_object(&mut self, object_type: &str) -> Result<Option<ObjectType>> {
+        let object = match object_type {
+            "DICTIONARY" => self.read_dictionary()?.map(ObjectType::Dictionary),
+            "LAYOUT" => self.read_layout()?.map(ObjectType::Layout),
+            "XRECORD" => self.read_xrecord()?.map(ObjectType::XRecord),
+            "GROUP" => self.read_group()?.map(ObjectType::Group),
+            "MLINESTYLE" => self.read_mlinestyle_object()?.map(ObjectType::MLineStyle),
+            "IMAGEDEF" => self.read_image_definition()?.map(ObjectType::ImageDefinition),
+            "MLEADERSTYLE" => self.read_multileader_style()?.map(ObjectType::MultiLeaderStyle),
+            "PLOTSETTINGS" => self.read_plot_settings()?.map(ObjectType::PlotSettings),
+            "TABLESTYLE" => self.read_table_style()?.map(ObjectType::TableStyle),
+            "SCALE" => self.read_scale()?.map(ObjectType::Scale),
+            "SORTENTSTABLE" => self.read_sort_entities_table()?.map(ObjectType::SortEntitiesTable),
+            "DICTIONARYVAR" => self.read_dictionary_variable()?.map(ObjectType::DictionaryVariable),
+            "VISUALSTYLE" => self.read_visualstyle()?.map(ObjectType::VisualStyle),
+            "MATERIAL" => self.read_material()?.map(ObjectType::Material),
+            "IMAGEDEF_REACTOR" => self.read_imagedef_reactor()?.map(ObjectType::ImageDefinitionReactor),
+            "GEODATA" => Some(ObjectType::GeoData(self.read_stub_object::<GeoData>()?)),
+            "SPATIALFILTER" => Some(ObjectType::SpatialFilter(self.read_stub_object::<SpatialFilter>()?)),
+            "RASTERVARIABLES" => self.read_raster_variables()?.map(ObjectType::RasterVariables),
+            "DBCOLOR" => self.read_bookcolor()?.map(ObjectType::BookColor),
+            "ACDBPLACEHOLDER" => Some(ObjectType::PlaceHolder(self.read_stub_object::<PlaceHolder>()?)),
+            "ACDBDICTIONARYWDFLT" => self.read_dict_with_default()?.map(ObjectType::DictionaryWithDefault),
+            "WIPEOUTVARIABLES" => self.read_wipeout_variables()?.map(ObjectType::WipeoutVariables),
+            _ => {
+                let (handle, codes) = self.read_unknown_object()?;
+                Some(ObjectType::Unknown { type_name: object_type.to_string(), handle, codes })
+            }
+        };
+        Ok(object)
+    }
+
     /// Read a DICTIONARY object
     fn read_dictionary(&mut self) -> Result<Option<Dictionary>> {
         let mut dict = Dictionary::new();
@@ -1402,21 +1126,23 @@ impl<'a> SectionReader<'a> {
         Ok(obj)
     }
 
-    /// Read an unknown object, extracting only the handle, then skip remaining pairs.
-    fn read_unknown_object_handle(&mut self) -> Result<Handle> {
+    /// Read an unknown object: its handle, and all of its pairs as they are
+    fn read_unknown_object(&mut self) -> Result<(Handle, Vec<RawGroupCode>)> {
         let mut handle = Handle::NULL;
+        let mut codes = Vec::new();
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
                 self.reader.push_back(pair);
                 break;
             }
             if pair.code == 5 {
-                if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) {
+                if let Ok(h) = u64::from_str_radix(pair.value_string.trim(), 16) {
                     handle = Handle::new(h);
                 }
             }
+            codes.push(RawGroupCode::new(pair.code, pair.value_string));
         }
-        Ok(handle)
+        Ok((handle, codes))
     }
     
     /// Skip to ENDTAB
@@ -1465,6 +1191,11 @@ impl<'a> SectionReader<'a> {
                         layer.color = Color::from_index(color_index);
                     }
                 }
+                420 => {
+                    if let Some(v) = pair.as_i32() {
+                        layer.color = Color::from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8);
+                    }
+                }
                 6 => layer.line_type = pair.value_string.clone(),
                 70 => {
                     if let Some(flags) = pair.as_i16() {
@@ -1943,8 +1674,9 @@ impl<'a> SectionReader<'a> {
 
     // ===== Common Entity/Object Code Helpers =====
 
-    /// Try to read a common entity code (5, 60, 102, 330, 92, 160, 310).
-    /// Returns true if the code was consumed, false if not recognized.
+    /// Try to read a common entity code (5, 60, 100, 102, 330, 92, 160, 310,
+    /// XDATA). Returns true if the code was consumed, false if not
+    /// recognized; unrecognized codes are kept in `common.unknown_codes`.
     fn try_read_common_entity_code(
         &mut self,
         pair: &super::stream_reader::DxfCodePair,
@@ -1963,12 +1695,51 @@ impl<'a> SectionReader<'a> {
                 }
                 Ok(true)
             }
-            330 => {
+            // Properties of AcDbEntity, for readers that do not take them
+            // themselves
+            8 | 62 | 370 if matches!(self.subclass.as_deref(), None | Some("AcDbEntity")) => {
+                match pair.code {
+                    8 => common.layer = pair.value_string.clone(),
+                    62 => {
+                        if let Some(v) = pair.as_i16() {
+                            common.color = Color::from_index(v);
+                        }
+                    }
+                    _ => {
+                        if let Some(v) = pair.as_i16() {
+                            common.line_weight = LineWeight::from_value(v);
+                        }
+                    }
+                }
+                Ok(true)
+            }
+            // The owner comes before the first subclass; later 330s are data
+            330 if self.subclass.is_none() => {
                 if let Ok(h) = u64::from_str_radix(pair.value_string.trim(), 16) {
                     common.owner_handle = Handle::new(h);
                 }
                 Ok(true)
             }
+            100 => {
+                self.subclass = Some(pair.value_string.clone());
+                Ok(true)
+            }
+            // True color; the approximate index in 62 comes first
+            420 => {
+                if let Some(v) = pair.as_i32() {
+                    common.color = Color::from_rgb((v >> 16) as u8, (v >> 8) as u8, v as u8);
+                }
+                Ok(true)
+            }
+            // Transparency; 0x02000000 flags an explicit opacity in the low byte
+            440 => {
+                if let Some(v) = pair.as_i32() {
+                    if v & 0x0200_0000 != 0 {
+                        common.transparency = Transparency::new(255 - (v & 0xFF) as u8);
+                    }
+                }
+                Ok(true)
+            }
             102 => {
                 let val = pair.value_string.trim();
                 if val == "{ACAD_REACTORS" {
@@ -1976,18 +1747,50 @@ impl<'a> SectionReader<'a> {
                 } else if val == "{ACAD_XDICTIONARY" {
                     common.xdictionary_handle = self.read_xdictionary_handle()?;
                 } else if val.starts_with('{') {
-                    // Skip unknown defined groups
-                    self.skip_defined_group()?;
+                    // Groups of other applications are kept as they are
+                    self.read_unknown_group(pair, &mut common.unknown_codes)?;
                 }
                 // "}" closing tokens are handled inside the group readers
                 Ok(true)
             }
             // Proxy graphics — skip data (matches ACadSharp behavior)
-            92 | 160 | 310 => {
+            92 | 160 | 310 if self.subclass.as_deref() == Some("AcDbEntity") => {
                 Ok(true)
             }
-            _ => Ok(false),
+            1001 => {
+                self.reader.push_back(pair.clone());
+                let (extended_data, next_pair) = self.read_extended_data()?;
+                common.extended_data = extended_data;
+                if let Some(next_pair) = next_pair {
+                    self.reader.push_back(next_pair);
+                }
+                Ok(true)
+            }
+            _ => {
+                common.unknown_codes.push(
+                    self.subclass.as_deref(),
+                    RawGroupCode::new(pair.code, pair.value_string.clone()),
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    /// Read a code of the common entity data, for entities whose own data is
+    /// only partly read and written again in full from the model. Codes of
+    /// the entity's own subclasses that were not read are dropped rather
+    /// than kept, as writing them back would mix them with the new data.
+    fn read_common_entity_code_only(
+        &mut self,
+        pair: &super::stream_reader::DxfCodePair,
+        common: &mut EntityCommon,
+    ) -> Result<()> {
+        let own_data = !matches!(self.subclass.as_deref(), None | Some("AcDbEntity"))
+            && !matches!(pair.code, 100 | 102 | 1001);
+        if !own_data {
+            self.try_read_common_entity_code(pair, common)?;
         }
+        Ok(())
     }
 
     /// Read reactor handles from a {ACAD_REACTORS group.
@@ -2026,10 +1829,19 @@ impl<'a> SectionReader<'a> {
         Ok(handle)
     }
 
-    /// Skip an unknown defined group (reads pairs until closing "}")
-    fn skip_defined_group(&mut self) -> Result<()> {
+    /// Read the group of another application into `codes`, from its opening
+    /// "102 {NAME" to the closing "}"
+    fn read_unknown_group(
+        &mut self,
+        opening: &super::stream_reader::DxfCodePair,
+        codes: &mut UnknownCodes,
+    ) -> Result<()> {
+        let subclass = self.subclass.as_deref();
+        codes.push(subclass, RawGroupCode::new(opening.code, opening.value_string.clone()));
         while let Some(pair) = self.reader.read_pair()? {
-            if pair.code == 102 && pair.value_string.trim() == "}" {
+            let closing = pair.code == 102 && pair.value_string.trim() == "}";
+            codes.push(subclass, RawGroupCode::new(pair.code, pair.value_string));
+            if closing {
                 break;
             }
         }
@@ -2037,7 +1849,7 @@ impl<'a> SectionReader<'a> {
     }
 
     /// Skip all pairs for the current entity until the next entity (code 0) or section end
-    fn skip_entity(&mut self) -> Result<()> {
+    pub(crate) fn skip_entity(&mut self) -> Result<()> {
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
                 self.reader.push_back(pair);
@@ -2047,18 +1859,66 @@ impl<'a> SectionReader<'a> {
         Ok(())
     }
 
-    /// Read an unknown entity, capturing only common entity data.
+    /// Read an unknown entity, capturing common entity data and its proxy
+    /// graphics.
     ///
-    /// Entity-specific codes are discarded (matching ACadSharp behavior).
+    /// Everything from the entity's own subclasses on, and the proxy
+    /// graphics, is also kept as read in `common.unknown_codes`, subclass
+    /// markers included, so the entity can be written back unchanged.
     fn read_unknown_entity(&mut self, dxf_name: &str) -> Result<UnknownEntity> {
         let mut entity = UnknownEntity::new(dxf_name);
+        // Proxy graphics: a 92 (160 for R2010+) size followed by 310 chunks
+        let mut graphics: Option<Vec<u8>> = None;
+        let mut in_graphics = false;
+        let mut own_data = false;
+        // Graphics belong to AcDbEntity, or AcDbProxyEntity for a proxy
+        let mut graphics_subclass = true;
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
                 self.reader.push_back(pair);
                 break;
             }
-            // Try to read common entity codes; ignore entity-specific ones
-            let _ = self.try_read_common_entity_code(&pair, &mut entity.common)?;
+            if pair.code == 100 {
+                let subclass = pair.value_string.trim();
+                own_data |= subclass != "AcDbEntity";
+                graphics_subclass = matches!(subclass, "AcDbEntity" | "AcDbProxyEntity");
+            }
+            let graphics_code = match pair.code {
+                92 | 160 if graphics.is_none() && graphics_subclass => {
+                    graphics = Some(Vec::new());
+                    in_graphics = true;
+                    true
+                }
+                310 if in_graphics => {
+                    if let Some(data) = graphics.as_mut() {
+                        let hex = pair.value_string.trim();
+                        data.extend((0..hex.len() / 2).filter_map(|i| {
+                            u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()
+                        }));
+                    }
+                    true
+                }
+                _ => {
+                    in_graphics = false;
+                    false
+                }
+            };
+            if own_data || graphics_code {
+                entity.common.unknown_codes.push(
+                    self.subclass.as_deref(),
+                    RawGroupCode::new(pair.code, pair.value_string),
+                );
+            } else {
+                self.try_read_common_entity_code(&pair, &mut entity.common)?;
+            }
+        }
+
+        if let Some(data) = graphics.filter(|d| !d.is_empty()) {
+            // Undecodable graphics are kept so they are written back unchanged
+            entity.proxy_graphics = Some(
+                ProxyGraphics::from_data(data.clone())
+                    .unwrap_or_else(|_| ProxyGraphics::undecoded(data)),
+            );
         }
         Ok(entity)
     }
@@ -2180,13 +2040,6 @@ impl<'a> SectionReader<'a> {
                         line.thickness = thickness;
                     }
                 }
-                // Extended data - read and store
-                1001 => {
-                    // Push back the pair and read XDATA
-                    self.reader.push_back(pair);
-                    let (extended_data, _next_pair) = self.read_extended_data()?;
-                    line.common.extended_data = extended_data;
-                }
                 _ => { self.try_read_common_entity_code(&pair, &mut line.common)?; }
             }
         }
@@ -2417,6 +2270,10 @@ impl<'a> SectionReader<'a> {
                             }
                         }
                     }
+                    // Vertices-follow flag and dummy point; mesh and face
+                    // counts follow from the vertices, which are all read
+                    // as those of a 3D polyline
+                    66 | 10 | 20 | 30 | 71..=75 => {}
                     _ => { self.try_read_common_entity_code(&pair, &mut polyline.common)?; }
                 }
             }
@@ -2465,6 +2322,8 @@ impl<'a> SectionReader<'a> {
                         lwpolyline.elevation = elevation;
                     }
                 }
+                // Vertex count; the vertices follow
+                90 => {}
                 10 => {
                     if let Some(x) = pair.as_double() {
                         vertices_x.push(x);
@@ -2515,6 +2374,7 @@ impl<'a> SectionReader<'a> {
     fn read_text(&mut self) -> Result<Option<Text>> {
         let mut text = Text::new();
         let mut insertion = PointReader::new();
+        let mut alignment = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2557,6 +2417,29 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 7 => text.style = pair.value_string.clone(),
+                11 | 21 | 31 => { alignment.add_coordinate(&pair); }
+                72 => {
+                    if let Some(value) = pair.as_i16() {
+                        text.horizontal_alignment = match value {
+                            1 => TextHorizontalAlignment::Center,
+                            2 => TextHorizontalAlignment::Right,
+                            3 => TextHorizontalAlignment::Aligned,
+                            4 => TextHorizontalAlignment::Middle,
+                            5 => TextHorizontalAlignment::Fit,
+                            _ => TextHorizontalAlignment::Left,
+                        };
+                    }
+                }
+                73 => {
+                    if let Some(value) = pair.as_i16() {
+                        text.vertical_alignment = match value {
+                            1 => TextVerticalAlignment::Bottom,
+                            2 => TextVerticalAlignment::Middle,
+                            3 => TextVerticalAlignment::Top,
+                            _ => TextVerticalAlignment::Baseline,
+                        };
+                    }
+                }
                 _ => { self.try_read_common_entity_code(&pair, &mut text.common)?; }
             }
         }
@@ -2564,6 +2447,9 @@ impl<'a> SectionReader<'a> {
         if let Some(pt) = insertion.get_point() {
             text.insertion_point = pt;
         }
+        if let Some(pt) = alignment.get_point() {
+            text.alignment_point = Some(pt);
+        }
 
         Ok(Some(text))
     }
@@ -2612,6 +2498,35 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 7 => mtext.style = pair.value_string.clone(),
+                44 => {
+                    if let Some(factor) = pair.as_double() {
+                        mtext.line_spacing_factor = factor;
+                    }
+                }
+                71 => {
+                    if let Some(value) = pair.as_i16() {
+                        mtext.attachment_point = match value {
+                            2 => AttachmentPoint::TopCenter,
+                            3 => AttachmentPoint::TopRight,
+                            4 => AttachmentPoint::MiddleLeft,
+                            5 => AttachmentPoint::MiddleCenter,
+                            6 => AttachmentPoint::MiddleRight,
+                            7 => AttachmentPoint::BottomLeft,
+                            8 => AttachmentPoint::BottomCenter,
+                            9 => AttachmentPoint::BottomRight,
+                            _ => AttachmentPoint::TopLeft,
+                        };
+                    }
+                }
+                72 => {
+                    if let Some(value) = pair.as_i16() {
+                        mtext.drawing_direction = match value {
+                            3 => DrawingDirection::TopToBottom,
+                            5 => DrawingDirection::ByStyle,
+                            _ => DrawingDirection::LeftToRight,
+                        };
+                    }
+                }
                 _ => { self.try_read_common_entity_code(&pair, &mut mtext.common)?; }
             }
         }
@@ -2630,6 +2545,7 @@ impl<'a> SectionReader<'a> {
         let mut current_fit_point = PointReader::new();
         let mut reading_control = false;
         let mut reading_fit = false;
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2694,6 +2610,15 @@ impl<'a> SectionReader<'a> {
                     }
                     current_fit_point.add_coordinate(&pair);
                 }
+                41 => {
+                    if let Some(weight) = pair.as_double() {
+                        spline.weights.push(weight);
+                    }
+                }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
+                // Knot, control point and fit point counts; the values
+                // follow. Tolerances are written with fixed values.
+                72..=74 | 42..=44 => {}
                 _ => { self.try_read_common_entity_code(&pair, &mut spline.common)?; }
             }
         }
@@ -2711,6 +2636,9 @@ impl<'a> SectionReader<'a> {
                 spline.fit_points.push(pt);
             }
         }
+        if let Some(pt) = normal.get_point() {
+            spline.normal = pt;
+        }
 
         Ok(Some(spline))
     }
@@ -2735,6 +2663,8 @@ impl<'a> SectionReader<'a> {
         let mut rotation = 0.0;
         let mut actual_measurement = 0.0;
         let mut leader_length = 0.0;
+        let mut block_name = String::new();
+        let mut text_rotation = 0.0;
         let mut common = EntityCommon::new();
 
         while let Some(pair) = self.reader.read_pair()? {
@@ -2770,7 +2700,13 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 1 => text = pair.value_string.clone(),
+                2 => block_name = pair.value_string.clone(),
                 3 => style_name = pair.value_string.clone(),
+                53 => {
+                    if let Some(rot) = pair.as_double() {
+                        text_rotation = rot;
+                    }
+                }
                 10 | 20 | 30 => { definition_point.add_coordinate(&pair); }
                 11 | 21 | 31 => { text_middle_point.add_coordinate(&pair); }
                 12 | 22 | 32 => { insertion_point.add_coordinate(&pair); }
@@ -2898,6 +2834,10 @@ impl<'a> SectionReader<'a> {
             dc.common.reactors = common.reactors;
             dc.common.xdictionary_handle = common.xdictionary_handle;
             dc.common.invisible = common.invisible;
+            dc.common.extended_data = common.extended_data;
+            dc.common.unknown_codes = common.unknown_codes;
+            dc.block_name = block_name;
+            dc.text_rotation = text_rotation;
         }
 
         Ok(Some(dimension))
@@ -2908,14 +2848,8 @@ impl<'a> SectionReader<'a> {
         use crate::entities::hatch::*;
 
         let mut hatch = Hatch::new();
-        let mut pattern_name = String::from("SOLID");
-        let mut pattern_type = HatchPatternType::Predefined;
-        let mut layer = String::from("0");
-        let mut color = Color::ByLayer;
-        let mut line_weight = LineWeight::ByLayer;
-        let mut _num_boundary_paths = 0;
-        let mut current_path_edges: Vec<BoundaryEdge> = Vec::new();
-        let mut reading_boundary = false;
+        let mut elevation = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -2924,18 +2858,10 @@ impl<'a> SectionReader<'a> {
             }
 
             match pair.code {
-                8 => layer = pair.value_string.clone(),
-                62 => {
-                    if let Some(color_index) = pair.as_i16() {
-                        color = Color::from_index(color_index);
-                    }
-                }
-                370 => {
-                    if let Some(lw) = pair.as_i16() {
-                        line_weight = LineWeight::from_value(lw);
-                    }
-                }
-                2 => pattern_name = pair.value_string.clone(),
+                // Elevation point; only its Z is used
+                10 | 20 | 30 => { elevation.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
+                2 => hatch.pattern.name = pair.value_string.clone(),
                 70 => {
                     if let Some(solid_fill) = pair.as_i16() {
                         hatch.is_solid = solid_fill != 0;
@@ -2946,10 +2872,21 @@ impl<'a> SectionReader<'a> {
                         hatch.is_associative = associative != 0;
                     }
                 }
+                91 => {
+                    let count = pair.as_i32().unwrap_or(0);
+                    for _ in 0..count {
+                        match self.read_code(92)? {
+                            Some(flags) => {
+                                let flags = BoundaryPathFlags::from_bits(flags.as_i32().unwrap_or(0) as u32);
+                                hatch.paths.push(self.read_hatch_boundary_path(flags)?);
+                            }
+                            None => break,
+                        }
+                    }
+                }
                 75 => {
                     if let Some(style) = pair.as_i16() {
                         hatch.style = match style {
-                            0 => HatchStyleType::Normal,
                             1 => HatchStyleType::Outer,
                             2 => HatchStyleType::Ignore,
                             _ => HatchStyleType::Normal,
@@ -2958,107 +2895,216 @@ impl<'a> SectionReader<'a> {
                 }
                 76 => {
                     if let Some(ptype) = pair.as_i16() {
-                        pattern_type = match ptype {
+                        hatch.pattern_type = match ptype {
                             0 => HatchPatternType::UserDefined,
-                            1 => HatchPatternType::Predefined,
                             2 => HatchPatternType::Custom,
                             _ => HatchPatternType::Predefined,
                         };
                     }
                 }
-                91 => {
-                    if let Some(num_paths) = pair.as_i32() {
-                        _num_boundary_paths = num_paths;
+                52 => {
+                    if let Some(angle) = pair.as_double() {
+                        hatch.pattern_angle = angle.to_radians();
                     }
                 }
-                92 => {
-                    // Boundary path type flags - indicates start of a new boundary path
-                    if reading_boundary && !current_path_edges.is_empty() {
-                        // Save previous path
-                        let path = BoundaryPath {
-                            flags: BoundaryPathFlags::new(),
-                            edges: current_path_edges.clone(),
-                            boundary_handles: Vec::new(),
-                        };
-                        hatch.paths.push(path);
-                        current_path_edges.clear();
+                41 => {
+                    if let Some(scale) = pair.as_double() {
+                        hatch.pattern_scale = scale;
                     }
-                    reading_boundary = true;
                 }
-                72 => {
-                    // Edge type - indicates start of a new edge
-                    if let Some(edge_type) = pair.as_i16() {
-                        match edge_type {
-                            1 => {
-                                // Line edge - will be populated by subsequent codes
-                                current_path_edges.push(BoundaryEdge::Line(LineEdge {
-                                    start: Vector2::new(0.0, 0.0),
-                                    end: Vector2::new(0.0, 0.0),
-                                }));
-                            }
-                            2 => {
-                                // Circular arc edge
-                                current_path_edges.push(BoundaryEdge::CircularArc(CircularArcEdge {
-                                    center: Vector2::new(0.0, 0.0),
-                                    radius: 0.0,
-                                    start_angle: 0.0,
-                                    end_angle: 0.0,
-                                    counter_clockwise: true,
-                                }));
-                            }
-                            3 => {
-                                // Elliptic arc edge
-                                current_path_edges.push(BoundaryEdge::EllipticArc(EllipticArcEdge {
-                                    center: Vector2::new(0.0, 0.0),
-                                    major_axis_endpoint: Vector2::new(1.0, 0.0),
-                                    minor_axis_ratio: 1.0,
-                                    start_angle: 0.0,
-                                    end_angle: 0.0,
-                                    counter_clockwise: true,
-                                }));
+                77 => {
+                    if let Some(double) = pair.as_i16() {
+                        hatch.is_double = double != 0;
+                    }
+                }
+                78 => {
+                    let count = pair.as_i16().unwrap_or(0);
+                    for _ in 0..count {
+                        match self.read_code(53)? {
+                            Some(angle) => {
+                                let line = self.read_hatch_pattern_line(angle.as_double().unwrap_or(0.0))?;
+                                hatch.pattern.lines.push(line);
                             }
-                            4 => {
-                                // Spline edge
-                                current_path_edges.push(BoundaryEdge::Spline(SplineEdge {
-                                    degree: 3,
-                                    rational: false,
-                                    periodic: false,
-                                    knots: Vec::new(),
-                                    control_points: Vec::new(),
-                                    fit_points: Vec::new(),
-                                    start_tangent: Vector2::new(0.0, 0.0),
-                                    end_tangent: Vector2::new(0.0, 0.0),
-                                }));
+                            None => break,
+                        }
+                    }
+                }
+                98 => {
+                    let count = pair.as_i32().unwrap_or(0);
+                    for _ in 0..count {
+                        match self.read_code(10)? {
+                            Some(x) => {
+                                let y = self.read_code_f64(20)?;
+                                hatch.seed_points.push(Vector2::new(x.as_double().unwrap_or(0.0), y));
                             }
-                            _ => {}
+                            None => break,
                         }
                     }
                 }
-                // Note: Full hatch reading would require reading all edge data (codes 10-40, etc.)
-                // For now, we create a basic hatch structure
                 _ => { self.try_read_common_entity_code(&pair, &mut hatch.common)?; }
             }
         }
 
-        // Save last boundary path if any
-        if reading_boundary && !current_path_edges.is_empty() {
-            let path = BoundaryPath {
-                flags: BoundaryPathFlags::new(),
-                edges: current_path_edges,
-                boundary_handles: Vec::new(),
-            };
-            hatch.paths.push(path);
+        if let Some(pt) = elevation.get_point() {
+            hatch.elevation = pt.z;
+        }
+        if let Some(pt) = normal.get_point() {
+            hatch.normal = pt;
         }
-
-        hatch.common.layer = layer;
-        hatch.common.color = color;
-        hatch.common.line_weight = line_weight;
-        hatch.pattern.name = pattern_name;
-        hatch.pattern_type = pattern_type;
 
         Ok(Some(hatch))
     }
 
+    /// Read a hatch boundary path, after its `92` flags
+    fn read_hatch_boundary_path(
+        &mut self,
+        flags: crate::entities::hatch::BoundaryPathFlags,
+    ) -> Result<crate::entities::hatch::BoundaryPath> {
+        use crate::entities::hatch::*;
+
+        let mut path = BoundaryPath::with_flags(flags);
+        if flags.is_polyline() {
+            let has_bulge = self.read_code_i32(72)? != 0;
+            let is_closed = self.read_code_i32(73)? != 0;
+            let count = self.read_code_i32(93)?;
+            let mut polyline = PolylineEdge::new(Vec::new(), is_closed);
+            for _ in 0..count {
+                let x = self.read_code_f64(10)?;
+                let y = self.read_code_f64(20)?;
+                let bulge = if has_bulge { self.read_code_f64(42)? } else { 0.0 };
+                polyline.add_vertex(Vector2::new(x, y), bulge);
+            }
+            path.edges.push(BoundaryEdge::Polyline(polyline));
+        } else {
+            let count = self.read_code_i32(93)?;
+            for _ in 0..count {
+                let Some(edge_type) = self.read_code(72)? else {
+                    break;
+                };
+                let edge = match edge_type.as_i16().unwrap_or(0) {
+                    1 => BoundaryEdge::Line(LineEdge {
+                        start: Vector2::new(self.read_code_f64(10)?, self.read_code_f64(20)?),
+                        end: Vector2::new(self.read_code_f64(11)?, self.read_code_f64(21)?),
+                    }),
+                    2 => BoundaryEdge::CircularArc(CircularArcEdge {
+                        center: Vector2::new(self.read_code_f64(10)?, self.read_code_f64(20)?),
+                        radius: self.read_code_f64(40)?,
+                        start_angle: self.read_code_f64(50)?.to_radians(),
+                        end_angle: self.read_code_f64(51)?.to_radians(),
+                        counter_clockwise: self.read_code_i32(73)? != 0,
+                    }),
+                    3 => BoundaryEdge::EllipticArc(EllipticArcEdge {
+                        center: Vector2::new(self.read_code_f64(10)?, self.read_code_f64(20)?),
+                        major_axis_endpoint: Vector2::new(self.read_code_f64(11)?, self.read_code_f64(21)?),
+                        minor_axis_ratio: self.read_code_f64(40)?,
+                        start_angle: self.read_code_f64(50)?.to_radians(),
+                        end_angle: self.read_code_f64(51)?.to_radians(),
+                        counter_clockwise: self.read_code_i32(73)? != 0,
+                    }),
+                    4 => BoundaryEdge::Spline(self.read_hatch_spline_edge()?),
+                    _ => break,
+                };
+                path.edges.push(edge);
+            }
+        }
+
+        let count = self.read_code_i32(97)?;
+        for _ in 0..count {
+            match self.read_code(330)? {
+                Some(handle) => {
+                    if let Some(h) = handle.as_handle() {
+                        path.boundary_handles.push(Handle::new(h));
+                    }
+                }
+                None => break,
+            }
+        }
+        Ok(path)
+    }
+
+    /// Read a spline edge of a hatch boundary path, after its `72` type
+    fn read_hatch_spline_edge(&mut self) -> Result<crate::entities::hatch::SplineEdge> {
+        let rational = self.read_code_i32(73)? != 0;
+        let periodic = self.read_code_i32(74)? != 0;
+        let degree = self.read_code_i32(94)?;
+        let knot_count = self.read_code_i32(95)?;
+        let point_count = self.read_code_i32(96)?;
+        let mut edge = crate::entities::hatch::SplineEdge {
+            degree,
+            rational,
+            periodic,
+            knots: Vec::new(),
+            control_points: Vec::new(),
+            fit_points: Vec::new(),
+            start_tangent: Vector2::new(0.0, 0.0),
+            end_tangent: Vector2::new(0.0, 0.0),
+        };
+        for _ in 0..knot_count {
+            edge.knots.push(self.read_code_f64(40)?);
+        }
+        for _ in 0..point_count {
+            let x = self.read_code_f64(10)?;
+            let y = self.read_code_f64(20)?;
+            let weight = if rational { self.read_code_f64(42)? } else { 1.0 };
+            edge.control_points.push(Vector3::new(x, y, weight));
+        }
+
+        // Fit data, written from AC1021 on. Its count shares code 97 with the
+        // count of boundary objects that ends the path; a zero read here
+        // leaves nothing for the path to read, which comes to the same.
+        if self.reader.peek_code()? == Some(97) {
+            let fit_count = self.read_code_i32(97)?;
+            if fit_count > 0 {
+                for _ in 0..fit_count {
+                    edge.fit_points.push(Vector2::new(self.read_code_f64(11)?, self.read_code_f64(21)?));
+                }
+                edge.start_tangent = Vector2::new(self.read_code_f64(12)?, self.read_code_f64(22)?);
+                edge.end_tangent = Vector2::new(self.read_code_f64(13)?, self.read_code_f64(23)?);
+            }
+        }
+        Ok(edge)
+    }
+
+    /// Read a line of a hatch pattern definition, after its `53` angle
+    fn read_hatch_pattern_line(&mut self, angle: f64) -> Result<crate::entities::hatch::HatchPatternLine> {
+        let base_point = Vector2::new(self.read_code_f64(43)?, self.read_code_f64(44)?);
+        let offset = Vector2::new(self.read_code_f64(45)?, self.read_code_f64(46)?);
+        let count = self.read_code_i32(79)?;
+        let mut dash_lengths = Vec::new();
+        for _ in 0..count {
+            dash_lengths.push(self.read_code_f64(49)?);
+        }
+        Ok(crate::entities::hatch::HatchPatternLine {
+            angle: angle.to_radians(),
+            base_point,
+            offset,
+            dash_lengths,
+        })
+    }
+
+    /// Read the next pair if it has the given code; otherwise it is left
+    /// for the caller
+    fn read_code(&mut self, code: i32) -> Result<Option<super::stream_reader::DxfCodePair>> {
+        match self.reader.read_pair()? {
+            Some(pair) if pair.code == code => Ok(Some(pair)),
+            Some(pair) => {
+                self.reader.push_back(pair);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Read the next pair as a double if it has the given code, 0 if not
+    fn read_code_f64(&mut self, code: i32) -> Result<f64> {
+        Ok(self.read_code(code)?.and_then(|pair| pair.as_double()).unwrap_or(0.0))
+    }
+
+    /// Read the next pair as an integer if it has the given code, 0 if not
+    fn read_code_i32(&mut self, code: i32) -> Result<i32> {
+        Ok(self.read_code(code)?.and_then(|pair| pair.as_i32()).unwrap_or(0))
+    }
+
     /// Read a SOLID entity
     fn read_solid(&mut self) -> Result<Option<Solid>> {
         let mut corner1 = PointReader::new();
@@ -3110,6 +3156,8 @@ impl<'a> SectionReader<'a> {
         solid.common.reactors = common.reactors;
         solid.common.xdictionary_handle = common.xdictionary_handle;
         solid.common.invisible = common.invisible;
+        solid.common.extended_data = common.extended_data;
+        solid.common.unknown_codes = common.unknown_codes;
 
         Ok(Some(solid))
     }
@@ -3179,6 +3227,8 @@ impl<'a> SectionReader<'a> {
         face.common.reactors = common.reactors;
         face.common.xdictionary_handle = common.xdictionary_handle;
         face.common.invisible = common.invisible;
+        face.common.extended_data = common.extended_data;
+        face.common.unknown_codes = common.unknown_codes;
 
         Ok(Some(face))
     }
@@ -3282,6 +3332,8 @@ impl<'a> SectionReader<'a> {
         insert.common.reactors = common.reactors;
         insert.common.xdictionary_handle = common.xdictionary_handle;
         insert.common.invisible = common.invisible;
+        insert.common.extended_data = common.extended_data;
+        insert.common.unknown_codes = common.unknown_codes;
 
         Ok(Some(insert))
     }
@@ -3323,6 +3375,8 @@ impl<'a> SectionReader<'a> {
         ray.common.reactors = common.reactors;
         ray.common.xdictionary_handle = common.xdictionary_handle;
         ray.common.invisible = common.invisible;
+        ray.common.extended_data = common.extended_data;
+        ray.common.unknown_codes = common.unknown_codes;
 
         Ok(Some(ray))
     }
@@ -3364,6 +3418,8 @@ impl<'a> SectionReader<'a> {
         xline.common.reactors = common.reactors;
         xline.common.xdictionary_handle = common.xdictionary_handle;
         xline.common.invisible = common.invisible;
+        xline.common.extended_data = common.extended_data;
+        xline.common.unknown_codes = common.unknown_codes;
 
         Ok(Some(xline))
     }
@@ -3379,6 +3435,9 @@ impl<'a> SectionReader<'a> {
         let mut layer = String::from("0");
         let mut color = Color::ByLayer;
         let mut common = EntityCommon::new();
+        let mut attdef = AttributeDefinition::new(String::new(), String::new(), String::new());
+        let mut alignment_point = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -3407,12 +3466,42 @@ impl<'a> SectionReader<'a> {
                         rotation = r;
                     }
                 }
+                7 => attdef.text_style = pair.value_string.clone(),
+                11 | 21 | 31 => { alignment_point.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
+                41 => { if let Some(v) = pair.as_double() { attdef.width_factor = v; } }
+                51 => { if let Some(v) = pair.as_double() { attdef.oblique_angle = v; } }
+                70 => {
+                    if let Some(v) = pair.as_i16() {
+                        attdef.flags = crate::entities::attribute_definition::AttributeFlags::from_bits(v as i32);
+                    }
+                }
+                71 => { if let Some(v) = pair.as_i16() { attdef.text_generation_flags = v; } }
+                72 => {
+                    if let Some(v) = pair.as_i16() {
+                        attdef.horizontal_alignment = crate::entities::attribute_definition::HorizontalAlignment::from_value(v);
+                    }
+                }
+                73 => { if let Some(v) = pair.as_i16() { attdef.field_length = v; } }
+                74 => {
+                    if let Some(v) = pair.as_i16() {
+                        attdef.vertical_alignment = crate::entities::attribute_definition::VerticalAlignment::from_value(v);
+                    }
+                }
                 _ => { self.try_read_common_entity_code(&pair, &mut common)?; }
             }
         }
 
-        let mut attdef = AttributeDefinition::new(tag, prompt, default_value);
+        attdef.tag = tag;
+        attdef.prompt = prompt;
+        attdef.default_value = default_value;
         attdef.insertion_point = insertion_point.get_point().unwrap_or(Vector3::zero());
+        if let Some(pt) = alignment_point.get_point() {
+            attdef.alignment_point = pt;
+        }
+        if let Some(pt) = normal.get_point() {
+            attdef.normal = pt;
+        }
         attdef.height = height;
         attdef.rotation = rotation;
         attdef.common.layer = layer;
@@ -3422,6 +3511,8 @@ impl<'a> SectionReader<'a> {
         attdef.common.reactors = common.reactors;
         attdef.common.xdictionary_handle = common.xdictionary_handle;
         attdef.common.invisible = common.invisible;
+        attdef.common.extended_data = common.extended_data;
+        attdef.common.unknown_codes = common.unknown_codes;
 
         Ok(Some(attdef))
     }
@@ -3431,6 +3522,7 @@ impl<'a> SectionReader<'a> {
         let mut tolerance = Tolerance::new();
         let mut insertion_point = PointReader::new();
         let mut direction = PointReader::new();
+        let mut normal = PointReader::new();
 
         while let Some(pair) = self.reader.read_pair()? {
             if pair.code == 0 {
@@ -3449,12 +3541,16 @@ impl<'a> SectionReader<'a> {
                 3 => tolerance.dimension_style_name = pair.value_string.clone(),
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
                 11 | 21 | 31 => { direction.add_coordinate(&pair); }
+                210 | 220 | 230 => { normal.add_coordinate(&pair); }
                 _ => { self.try_read_common_entity_code(&pair, &mut tolerance.common)?; }
             }
         }
 
         tolerance.insertion_point = insertion_point.get_point().unwrap_or(Vector3::zero());
         tolerance.direction = direction.get_point().unwrap_or(Vector3::new(1.0, 0.0, 0.0));
+        if let Some(pt) = normal.get_point() {
+            tolerance.normal = pt;
+        }
 
         Ok(Some(tolerance))
     }
@@ -3521,6 +3617,53 @@ impl<'a> SectionReader<'a> {
                 10 | 20 | 30 => { insertion_point.add_coordinate(&pair); }
                 11 | 21 | 31 => { u_vector.add_coordinate(&pair); }
                 12 | 22 | 32 => { v_vector.add_coordinate(&pair); }
+                13 => {
+                    if let Some(x) = pair.as_double() {
+                        wipeout.size.x = x;
+                    }
+                }
+                23 => {
+                    if let Some(y) = pair.as_double() {
+                        wipeout.size.y = y;
+                    }
+                }
+                90 => {
+                    if let Some(version) = pair.as_i32() {
+                        wipeout.class_version = version;
+                    }
+                }
+                70 => {
+                    if let Some(flags) = pair.as_i16() {
+                        wipeout.flags = WipeoutDisplayFlags::from_bits_truncate(flags);
+                    }
+                }
+                280 => {
+                    if let Some(clipping) = pair.as_i16() {
+                        wipeout.clipping_enabled = clipping != 0;
+                    }
+                }
+                281 => {
+                    if let Some(v) = pair.as_i16() {
+                        wipeout.brightness = v as u8;
+                    }
+                }
+                282 => {
+                    if let Some(v) = pair.as_i16() {
+                        wipeout.contrast = v as u8;
+                    }
+                }
+                283 => {
+                    if let Some(v) = pair.as_i16() {
+                        wipeout.fade = v as u8;
+                    }
+                }
+                71 => {
+                    if let Some(clip_type) = pair.as_i16() {
+                        wipeout.clip_type = WipeoutClipType::from(clip_type);
+                    }
+                }
+                // Vertex count; the vertices follow
+                91 => {}
                 14 => {
                     if let Some(x) = pair.as_double() {
                         wipeout.clip_boundary_vertices.push(Vector2::new(x, 0.0));
@@ -3602,39 +3745,21 @@ impl<'a> SectionReader<'a> {
                         }
                     }
                 }
-                // 3D point (1010, 1020, 1030)
-                1010 | 1020 | 1030 => {
-                    if let Some(ref mut record) = current_record {
-                        point_reader.add_coordinate(&pair);
-                        if let Some(pt) = point_reader.get_point() {
-                            record.add_value(XDataValue::Point3D(pt));
-                        }
-                    }
-                }
-                // 3D position (1011, 1021, 1031)
-                1011 | 1021 | 1031 => {
-                    if let Some(ref mut record) = current_record {
-                        point_reader.add_coordinate(&pair);
-                        if let Some(pt) = point_reader.get_point() {
-                            record.add_value(XDataValue::Position3D(pt));
-                        }
-                    }
-                }
-                // 3D displacement (1012, 1022, 1032)
-                1012 | 1022 | 1032 => {
-                    if let Some(ref mut record) = current_record {
-                        point_reader.add_coordinate(&pair);
-                        if let Some(pt) = point_reader.get_point() {
-                            record.add_value(XDataValue::Displacement3D(pt));
-                        }
+                // 3D point, position, displacement and direction: X starts
+                // a point, Z completes it
+                1010..=1013 | 1020..=1023 | 1030..=1033 => {
+                    if pair.code < 1020 {
+                        point_reader = PointReader::new();
                     }
-                }
-                // 3D direction (1013, 1023, 1033)
-                1013 | 1023 | 1033 => {
-                    if let Some(ref mut record) = current_record {
-                        point_reader.add_coordinate(&pair);
-                        if let Some(pt) = point_reader.get_point() {
-                            record.add_value(XDataValue::Direction3D(pt));
+                    point_reader.add_coordinate(&pair);
+                    if pair.code >= 1030 {
+                        if let (Some(record), Some(pt)) = (current_record.as_mut(), point_reader.get_point()) {
+                            record.add_value(match pair.code {
+                                1030 => XDataValue::Point3D(pt),
+                                1031 => XDataValue::Position3D(pt),
+                                1032 => XDataValue::Displacement3D(pt),
+                                _ => XDataValue::Direction3D(pt),
+                            });
                         }
                     }
                 }
@@ -3726,6 +3851,8 @@ impl<'a> SectionReader<'a> {
                 41 => { if let Some(v) = pair.as_double() { vp.height = v; } }
                 90 => { if let Some(v) = pair.as_i32() { vp.status = crate::entities::viewport::ViewportStatusFlags::from_bits(v); } }
                 69 => { if let Some(v) = pair.as_i16() { vp.id = v; } }
+                // On/off status; also in the status flags
+                68 => {}
                 12 => { if let Some(v) = pair.as_double() { view_center.add_coordinate(&pair); let _ = v; } }
                 22 => { view_center.add_coordinate(&pair); }
                 13 => { snap_base_x = pair.as_double(); }
@@ -3844,6 +3971,8 @@ impl<'a> SectionReader<'a> {
                 62 => { if let Some(v) = pair.as_i16() { leader.common.color = Color::from_index(v); } }
                 370 => { if let Some(v) = pair.as_i16() { leader.common.line_weight = LineWeight::from_value(v); } }
                 3 => leader.dimension_style = pair.value_string.clone(),
+                // Vertex count; the vertices follow
+                76 => {}
                 71 => { if let Some(v) = pair.as_i16() { leader.arrow_enabled = v != 0; } }
                 72 => {
                     if let Some(v) = pair.as_i16() {
@@ -3934,7 +4063,7 @@ impl<'a> SectionReader<'a> {
                 291 => { if let Some(v) = pair.as_bool() { ml.enable_dogleg = v; } }
                 290 => { if let Some(v) = pair.as_bool() { ml.enable_landing = v; } }
                 292 => { if let Some(v) = pair.as_bool() { ml.text_frame = v; } }
-                _ => { self.try_read_common_entity_code(&pair, &mut ml.common)?; }
+                _ => { self.read_common_entity_code_only(&pair, &mut ml.common)?; }
             }
         }
 
@@ -3970,6 +4099,8 @@ impl<'a> SectionReader<'a> {
                 70 => { if let Some(v) = pair.as_i16() { mline.justification = MLineJustification::from(v); } }
                 71 => { if let Some(v) = pair.as_i16() { mline.flags = MLineFlags::from_bits_truncate(v); } }
                 73 => { if let Some(v) = pair.as_i16() { num_elements = v as usize; } }
+                // Vertex count; the vertices follow
+                72 => {}
                 10 | 20 | 30 => { start_point.add_coordinate(&pair); }
                 210 | 220 | 230 => { normal.add_coordinate(&pair); }
                 340 => {
@@ -4179,7 +4310,11 @@ impl<'a> SectionReader<'a> {
                 23 => { if let Some(v) = pair.as_double() { img.size.y = v; } }
                 340 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { img.definition_handle = Some(Handle::new(h)); } }
                 360 => { if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { img.definition_reactor_handle = Some(Handle::new(h)); } }
-                70 => { /* display properties flags */ }
+                90 => { if let Some(v) = pair.as_i32() { img.class_version = v; } }
+                70 => { if let Some(v) = pair.as_i16() { img.flags = ImageDisplayFlags::from_bits_truncate(v); } }
+                71 => { if let Some(v) = pair.as_i16() { img.clip_boundary.clip_type = ClipType::from(v); } }
+                // Vertex count; the vertices follow
+                91 => {}
                 280 => { if let Some(v) = pair.as_i16() { img.clipping_enabled = v != 0; } }
                 281 => { if let Some(v) = pair.as_i16() { img.brightness = v as u8; } }
                 282 => { if let Some(v) = pair.as_i16() { img.contrast = v as u8; } }
@@ -4275,7 +4410,7 @@ impl<'a> SectionReader<'a> {
                 343 => {
                     if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { table.block_record_handle = Some(Handle::new(h)); }
                 }
-                _ => { self.try_read_common_entity_code(&pair, &mut table.common)?; }
+                _ => { self.read_common_entity_code_only(&pair, &mut table.common)?; }
             }
         }
 
@@ -4309,6 +4444,9 @@ impl<'a> SectionReader<'a> {
                 50 => { if let Some(v) = pair.as_double() { underlay.rotation = v; } }
                 281 => { if let Some(v) = pair.as_i16() { underlay.contrast = v as u8; } }
                 282 => { if let Some(v) = pair.as_i16() { underlay.fade = v as u8; } }
+                280 => { if let Some(v) = pair.as_i16() { underlay.flags = UnderlayDisplayFlags::from_bits_truncate(v as u8); } }
+                // Vertex count; the vertices follow
+                91 => {}
                 340 => {
                     if let Ok(h) = u64::from_str_radix(&pair.value_string, 16) { underlay.definition_handle = Handle::new(h); }
                 }
@@ -4346,6 +4484,7 @@ impl<'a> SectionReader<'a> {
                     }
                 }
                 102 => {} // Skip extension dictionaries / reactors groups
+                100 if pair.value_string == "AcDbXrecord" => {} // Subclass marker, written by the writer
                 _ => {
                     // All other codes are data entries
                     xr.entries.push(XRecordEntry {
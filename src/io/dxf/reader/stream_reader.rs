@@ -1,8 +1,9 @@
 //! DXF stream reader trait and common types
 
-use crate::error::Result;
+use crate::error::{DxfLocation, DxfPosition, Result};
 use crate::io::dxf::{DxfCode, GroupCodeValueType};
-use crate::types::Vector3;
+use crate::notification::{NotificationCollection, NotificationType};
+use crate::types::{Handle, Vector3};
 
 /// A DXF code/value pair
 #[derive(Debug, Clone)]
@@ -107,6 +108,19 @@ impl DxfCodePair {
     pub fn as_handle(&self) -> Option<u64> {
         u64::from_str_radix(self.value_string.trim(), 16).ok()
     }
+
+    /// Check if the value could not be converted to the type of its code
+    pub fn has_invalid_value(&self) -> bool {
+        match self.value_type {
+            GroupCodeValueType::Int16
+            | GroupCodeValueType::Int32
+            | GroupCodeValueType::Int64
+            | GroupCodeValueType::Byte => self.value_int.is_none(),
+            GroupCodeValueType::Double => self.value_double.is_none(),
+            GroupCodeValueType::Bool => self.value_bool.is_none(),
+            _ => false,
+        }
+    }
 }
 
 /// Trait for reading DXF code/value pairs from a stream
@@ -114,6 +128,12 @@ pub trait DxfStreamReader {
     /// Read the next code/value pair
     fn read_pair(&mut self) -> Result<Option<DxfCodePair>>;
 
+    /// Read the next code and its value as text, leaving the conversion to
+    /// [`DxfCodePair::new`] for later
+    fn read_raw_pair(&mut self) -> Result<Option<(i32, String)>> {
+        Ok(self.read_pair()?.map(|pair| (pair.code, pair.value_string)))
+    }
+
     /// Peek at the next code without consuming it
     #[allow(dead_code)]
     fn peek_code(&mut self) -> Result<Option<i32>>;
@@ -132,6 +152,140 @@ pub trait DxfStreamReader {
     fn set_encoding(&mut self, _encoding: &'static encoding_rs::Encoding) {
         // Default: no-op
     }
+
+    /// Position of the last pair read, if the stream knows it
+    fn position(&self) -> Option<DxfPosition> {
+        None
+    }
+
+    /// Where the last pair read came from, and the record it belongs to
+    fn location(&self) -> DxfLocation {
+        DxfLocation::default()
+    }
+
+    /// Take the locations of the values read so far that could not be
+    /// converted to the type of their group code
+    fn take_invalid_values(&mut self) -> Vec<DxfLocation> {
+        Vec::new()
+    }
+}
+
+/// Follows the sections and records of a stream as pairs are read, to tell
+/// where the last pair came from
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LocationTracker {
+    position: Option<DxfPosition>,
+    code: i32,
+    /// Reused for every pair, to avoid an allocation per pair
+    value: String,
+    section: Option<String>,
+    /// A `0 SECTION` was read; the following `2` names the section
+    section_start: bool,
+    record: Option<String>,
+    handle: Option<Handle>,
+    invalid_values: Vec<DxfLocation>,
+}
+
+impl LocationTracker {
+    /// Tracker for the pairs of a record whose `0` pair was already read,
+    /// at `position`
+    pub fn in_record(section: &str, record: &str, position: Option<DxfPosition>) -> Self {
+        Self {
+            position,
+            value: record.to_string(),
+            section: Some(section.to_string()),
+            record: Some(record.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Note a pair returned by the stream
+    pub fn pair_read(&mut self, position: DxfPosition, pair: &DxfCodePair) {
+        self.raw_pair_read(position, pair.code, &pair.value_string);
+        // A pair pushed back is read again
+        let repeated = self.invalid_values.last().is_some_and(|l| l.position == Some(position));
+        if pair.has_invalid_value() && !repeated {
+            self.invalid_values.push(self.location());
+        }
+    }
+
+    /// Note a pair returned by the stream without conversion of its value
+    pub fn raw_pair_read(&mut self, position: DxfPosition, code: i32, value: &str) {
+        self.position = Some(position);
+        self.code = code;
+        self.value.clear();
+        self.value.push_str(value);
+
+        let in_header = self.section.as_deref() == Some("HEADER");
+        let section_start = std::mem::take(&mut self.section_start);
+        match code {
+            0 => {
+                self.section_start = value == "SECTION";
+                match value {
+                    "SECTION" | "ENDSEC" => {
+                        self.section = None;
+                        self.record = None;
+                    }
+                    _ => self.record = Some(value.to_string()),
+                }
+                self.handle = None;
+            }
+            2 if section_start => {
+                self.section = Some(value.to_string());
+            }
+            9 if in_header => {
+                self.record = Some(value.to_string());
+            }
+            // 105 is the handle of DIMSTYLE entries
+            5 | 105 if !in_header && self.handle.is_none() && self.record.is_some() => {
+                self.handle = u64::from_str_radix(value.trim(), 16).ok().map(Handle::new);
+            }
+            _ => {}
+        }
+    }
+
+    /// Position of the last pair
+    pub fn position(&self) -> Option<DxfPosition> {
+        self.position
+    }
+
+    /// Location of the last pair
+    pub fn location(&self) -> DxfLocation {
+        DxfLocation {
+            position: self.position,
+            section: self.section.clone(),
+            entity_type: self.record.clone(),
+            handle: self.handle,
+            code: self.position.map(|_| self.code),
+            value: self.position.map(|_| self.value.clone()),
+        }
+    }
+
+    /// Location of a pair that could not be read, at `position`
+    pub fn location_at(&self, position: DxfPosition, code: Option<i32>, value: Option<String>) -> DxfLocation {
+        DxfLocation {
+            position: Some(position),
+            code,
+            value,
+            ..self.location()
+        }
+    }
+
+    /// Take the locations of the values that could not be converted
+    pub fn take_invalid_values(&mut self) -> Vec<DxfLocation> {
+        std::mem::take(&mut self.invalid_values)
+    }
+}
+
+/// Report values that could not be converted; they were read as if absent
+pub(crate) fn notify_invalid_values(notifications: &mut NotificationCollection, invalid_values: Vec<DxfLocation>) {
+    for location in invalid_values {
+        notifications.notify_at(
+            NotificationType::Error,
+            format!("Invalid value for group code {}", location.code.unwrap_or_default()),
+            location,
+        );
+    }
 }
 
 /// Helper for reading 3D points from consecutive code pairs
@@ -1,15 +1,19 @@
 //! DXF ASCII text reader
 
-use super::stream_reader::{DxfCodePair, DxfStreamReader};
-use crate::error::{DxfError, Result};
+use super::stream_reader::{DxfCodePair, DxfStreamReader, LocationTracker};
+use crate::error::{DxfError, DxfLocation, DxfPosition, Result};
 use encoding_rs::Encoding;
-use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 
 /// DXF ASCII text file reader
 pub struct DxfTextReader<R: Read + Seek> {
     reader: BufReader<R>,
     line_number: usize,
-    peeked_pair: Option<DxfCodePair>,
+    /// Pair read ahead or pushed back, with the line of its code
+    peeked_pair: Option<(DxfCodePair, usize)>,
+    tracker: LocationTracker,
+    /// Buffer reused for code lines, which are parsed without allocating
+    code_line: Vec<u8>,
     /// Non-UTF8 fallback encoding.  `None` means use Latin-1 (byte-to-char).
     encoding: Option<&'static Encoding>,
 }
@@ -21,6 +25,8 @@ impl<R: Read + Seek> DxfTextReader<R> {
             reader,
             line_number: 0,
             peeked_pair: None,
+            tracker: LocationTracker::default(),
+            code_line: Vec::new(),
             encoding: None,
         })
     }
@@ -29,34 +35,19 @@ impl<R: Read + Seek> DxfTextReader<R> {
     /// Uses the configured encoding for fallback, or Latin-1 if none set.
     fn read_line(&mut self) -> Result<Option<String>> {
         let mut bytes = Vec::new();
-        
+
         // Read bytes until newline
-        loop {
-            let mut byte = [0u8; 1];
-            match self.reader.read(&mut byte) {
-                Ok(0) => {
-                    // EOF
-                    if bytes.is_empty() {
-                        return Ok(None);
-                    }
-                    break;
-                }
-                Ok(_) => {
-                    if byte[0] == b'\n' {
-                        break;
-                    }
-                    bytes.push(byte[0]);
-                }
-                Err(e) => return Err(e.into()),
-            }
+        if self.reader.read_until(b'\n', &mut bytes)? == 0 {
+            return Ok(None);
         }
-        
+
         self.line_number += 1;
-        
+
         // Try UTF-8 first, then use configured encoding or Latin-1 fallback
-        let line = match String::from_utf8(bytes.clone()) {
+        let line = match String::from_utf8(bytes) {
             Ok(s) => s,
-            Err(_) => {
+            Err(e) => {
+                let bytes = e.into_bytes();
                 if let Some(enc) = self.encoding {
                     let (decoded, _, _) = enc.decode(&bytes);
                     decoded.into_owned()
@@ -66,38 +57,64 @@ impl<R: Read + Seek> DxfTextReader<R> {
                 }
             }
         };
-        
+
         // Trim whitespace and newlines (including \r)
-        let trimmed = line.trim().to_string();
-        Ok(Some(trimmed))
+        let trimmed = line.trim();
+        if trimmed.len() == line.len() {
+            return Ok(Some(line));
+        }
+        Ok(Some(trimmed.to_string()))
     }
     
-    /// Read a code/value pair from the stream
-    fn read_pair_internal(&mut self) -> Result<Option<DxfCodePair>> {
+    /// Read a code/value pair from the stream, with the line of its code
+    fn read_pair_internal(&mut self) -> Result<Option<(DxfCodePair, usize)>> {
+        Ok(self
+            .read_raw_pair_internal()?
+            .map(|(code, value, line)| (DxfCodePair::new(code, value), line)))
+    }
+
+    /// Read a code and its value line from the stream, with the line of the
+    /// code
+    fn read_raw_pair_internal(&mut self) -> Result<Option<(i32, String, usize)>> {
         // Read code line
-        let code_line = match self.read_line()? {
-            Some(line) => line,
-            None => return Ok(None),
-        };
-        
+        self.code_line.clear();
+        if self.reader.read_until(b'\n', &mut self.code_line)? == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+        let line = self.line_number;
+
         // Parse code
-        let code = code_line.trim().parse::<i32>()
-            .map_err(|_| DxfError::Parse(format!("Invalid DXF code at line {}: '{}'", self.line_number, code_line)))?;
+        let code_line = self.code_line.trim_ascii();
+        let code = std::str::from_utf8(code_line)
+            .ok()
+            .and_then(|code| code.parse::<i32>().ok())
+            .ok_or_else(|| {
+                let text = String::from_utf8_lossy(code_line).into_owned();
+                DxfError::Parse(format!("Invalid DXF code '{}'", text))
+                    .at(self.tracker.location_at(DxfPosition::Line(line), None, Some(text)))
+            })?;
         
         // Read value line
         let value_line = match self.read_line()? {
             Some(line) => line,
-            None => return Err(DxfError::Parse(format!("Unexpected EOF after code {} at line {}", code, self.line_number))),
+            None => {
+                return Err(DxfError::Parse(format!("Unexpected EOF after code {}", code))
+                    .at(self.tracker.location_at(DxfPosition::Line(line), Some(code), None)))
+            }
         };
         
         // Process special character sequences in strings
-        let value = self.process_string_value(&value_line);
+        let value = self.process_string_value(value_line);
         
-        Ok(Some(DxfCodePair::new(code, value)))
+        Ok(Some((code, value, line)))
     }
     
     /// Process special character sequences in DXF strings
-    fn process_string_value(&self, value: &str) -> String {
+    fn process_string_value(&self, value: String) -> String {
+        if !value.contains('^') {
+            return value;
+        }
         value
             .replace("^J", "\n")
             .replace("^M", "\r")
@@ -109,23 +126,37 @@ impl<R: Read + Seek> DxfTextReader<R> {
 impl<R: Read + Seek> DxfStreamReader for DxfTextReader<R> {
     fn read_pair(&mut self) -> Result<Option<DxfCodePair>> {
         // If we have a peeked pair, return it
-        if let Some(pair) = self.peeked_pair.take() {
-            return Ok(Some(pair));
-        }
-        
-        self.read_pair_internal()
+        let pair = match self.peeked_pair.take() {
+            Some(pair) => Some(pair),
+            None => self.read_pair_internal()?,
+        };
+        Ok(pair.map(|(pair, line)| {
+            self.tracker.pair_read(DxfPosition::Line(line), &pair);
+            pair
+        }))
+    }
+
+    fn read_raw_pair(&mut self) -> Result<Option<(i32, String)>> {
+        let pair = match self.peeked_pair.take() {
+            Some((pair, line)) => Some((pair.code, pair.value_string, line)),
+            None => self.read_raw_pair_internal()?,
+        };
+        Ok(pair.map(|(code, value, line)| {
+            self.tracker.raw_pair_read(DxfPosition::Line(line), code, &value);
+            (code, value)
+        }))
     }
     
     fn peek_code(&mut self) -> Result<Option<i32>> {
         // If we already have a peeked pair, return its code
-        if let Some(ref pair) = self.peeked_pair {
+        if let Some((ref pair, _)) = self.peeked_pair {
             return Ok(Some(pair.code));
         }
         
         // Read the next pair and store it
-        if let Some(pair) = self.read_pair_internal()? {
+        if let Some((pair, line)) = self.read_pair_internal()? {
             let code = pair.code;
-            self.peeked_pair = Some(pair);
+            self.peeked_pair = Some((pair, line));
             Ok(Some(code))
         } else {
             Ok(None)
@@ -133,19 +164,37 @@ impl<R: Read + Seek> DxfStreamReader for DxfTextReader<R> {
     }
 
     fn push_back(&mut self, pair: DxfCodePair) {
-        self.peeked_pair = Some(pair);
+        // The pair pushed back is the one read last
+        let line = match self.tracker.position() {
+            Some(DxfPosition::Line(line)) => line,
+            _ => self.line_number,
+        };
+        self.peeked_pair = Some((pair, line));
     }
     
     fn reset(&mut self) -> Result<()> {
         self.reader.seek(SeekFrom::Start(0))?;
         self.line_number = 0;
         self.peeked_pair = None;
+        self.tracker = LocationTracker::default();
         Ok(())
     }
 
     fn set_encoding(&mut self, encoding: &'static Encoding) {
         self.encoding = Some(encoding);
     }
+
+    fn position(&self) -> Option<DxfPosition> {
+        self.tracker.position()
+    }
+
+    fn location(&self) -> DxfLocation {
+        self.tracker.location()
+    }
+
+    fn take_invalid_values(&mut self) -> Vec<DxfLocation> {
+        self.tracker.take_invalid_values()
+    }
 }
 
 #[cfg(test)]
@@ -217,6 +266,31 @@ mod tests {
         let pair = reader.read_pair().unwrap().unwrap();
         assert_eq!(pair.value_string, "Line1\nLine2\rLine3");
     }
-}
+    
+    #[test]
+    fn test_location_of_pairs() {
+        let data = "0\nSECTION\n2\nENTITIES\n0\nLINE\n5\n2A\n10\nabc\nX\n1.0\n";
+        let mut reader = DxfTextReader::new(BufReader::new(Cursor::new(data.as_bytes()))).unwrap();
+        for _ in 0..4 {
+            reader.read_pair().unwrap();
+        }
+        let location = reader.location();
+        assert_eq!(location.position, Some(DxfPosition::Line(7)));
+        assert_eq!(location.section.as_deref(), Some("ENTITIES"));
+        assert_eq!(location.entity_type.as_deref(), Some("LINE"));
+        assert_eq!(location.handle, Some(crate::types::Handle::new(0x2A)));
 
+        // A pair pushed back keeps its position
+        let pair = reader.read_pair().unwrap().unwrap();
+        reader.push_back(pair);
+        reader.read_pair().unwrap();
+        assert_eq!(reader.location().position, Some(DxfPosition::Line(9)));
+        assert_eq!(reader.take_invalid_values().len(), 1);
 
+        let error = reader.read_pair().unwrap_err();
+        let location = error.location().unwrap();
+        assert_eq!(location.position, Some(DxfPosition::Line(11)));
+        assert_eq!(location.value.as_deref(), Some("X"));
+        assert_eq!(location.handle, Some(crate::types::Handle::new(0x2A)));
+    }
+}
@@ -21,6 +21,11 @@ impl<W: Write> DxfBinaryWriter<W> {
         writer.write_all(BINARY_DXF_SENTINEL)?;
         Ok(Self { writer })
     }
+
+    /// Continue a binary DXF stream whose sentinel is already written
+    pub(crate) fn resume(writer: W) -> Self {
+        Self { writer }
+    }
     
     /// Write a DXF code as 16-bit little-endian
     fn write_code(&mut self, code: i32) -> Result<()> {
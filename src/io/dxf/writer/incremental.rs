@@ -0,0 +1,423 @@
+//! Incremental DXF output
+
+use std::collections::BTreeMap;
+use std::io::{Seek, SeekFrom, Write};
+
+use crate::document::CadDocument;
+use crate::entities::EntityType;
+use crate::error::Result;
+use crate::io::downgrade::DowngradePass;
+use crate::notification::NotificationType;
+use crate::types::{DxfVersion, Handle};
+
+use super::binary_writer::DxfBinaryWriter;
+use super::r12::R12StreamWriter;
+use super::section_writer::SectionWriter;
+use super::stream_writer::DxfStreamWriter;
+use super::text_writer::DxfTextWriter;
+
+/// `$HANDSEED` written with the header and replaced on [`finish`]; it
+/// reserves the widest handle so the final value fits in its place.
+///
+/// [`finish`]: DxfIncrementalWriter::finish
+const HANDSEED_PLACEHOLDER: u64 = u64::MAX;
+
+/// DXF writer that takes entities one at a time.
+///
+/// HEADER, CLASSES, TABLES and BLOCKS are written from a small document when
+/// the writer is created, along with any entity the document already holds.
+/// Entities passed to [`add_entity`](Self::add_entity) then go straight to
+/// the ENTITIES section, and [`finish`](Self::finish) writes the OBJECTS
+/// section of the document and the end of file.
+///
+/// Entities without a handle get one from the writer, as do entities whose
+/// handle was already written, which is reported in the notifications of
+/// [`document`](Self::document). `$HANDSEED` is not known until the last
+/// entity is written, so it is patched into the header by `finish`, padded
+/// with zeros to the width it was reserved with.
+///
+/// ```rust,ignore
+/// use acadrust::io::dxf::DxfIncrementalWriter;
+///
+/// let file = std::io::BufWriter::new(std::fs::File::create("survey.dxf")?);
+/// let mut writer = DxfIncrementalWriter::new(file, CadDocument::new())?;
+/// for line in lines_from_database() {
+///     writer.add_entity(EntityType::Line(line))?;
+/// }
+/// writer.finish()?;
+/// ```
+pub struct DxfIncrementalWriter<W: Write + Seek> {
+    output: W,
+    document: CadDocument,
+    binary: bool,
+    version: DxfVersion,
+    next_handle: u64,
+    written: HandleRanges,
+    model_space: Handle,
+    seed_position: Option<u64>,
+    downgrade: DowngradePass,
+}
+
+impl<W: Write + Seek> DxfIncrementalWriter<W> {
+    /// Start an ASCII DXF file on `output`
+    pub fn new(output: W, document: CadDocument) -> Result<Self> {
+        Self::start(output, document, false, DowngradePass::default())
+    }
+
+    /// Start a binary DXF file on `output`
+    pub fn new_binary(output: W, document: CadDocument) -> Result<Self> {
+        Self::start(output, document, true, DowngradePass::default())
+    }
+
+    /// Start an ASCII DXF file on `output`, converting the entities the
+    /// version cannot store with `downgrade` instead of the default pass
+    pub fn with_downgrade_pass(
+        output: W,
+        document: CadDocument,
+        downgrade: DowngradePass,
+    ) -> Result<Self> {
+        Self::start(output, document, false, downgrade)
+    }
+
+    /// Start a binary DXF file on `output`, converting the entities the
+    /// version cannot store with `downgrade` instead of the default pass
+    pub fn binary_with_downgrade_pass(
+        output: W,
+        document: CadDocument,
+        downgrade: DowngradePass,
+    ) -> Result<Self> {
+        Self::start(output, document, true, downgrade)
+    }
+
+    fn start(
+        mut output: W,
+        mut document: CadDocument,
+        binary: bool,
+        downgrade: DowngradePass,
+    ) -> Result<Self> {
+        // Documents without a known version are written as the newest release
+        let version = match document.version {
+            DxfVersion::Unknown => DxfVersion::AC1032,
+            version => version,
+        };
+        downgrade.run(&mut document, version);
+
+        if binary {
+            DxfBinaryWriter::new(&mut output)?;
+        }
+
+        let model_space = document
+            .block_records
+            .get("*Model_Space")
+            .map(|b| b.handle)
+            .unwrap_or(Handle::new(0x1F));
+        let mut writer = Self {
+            output,
+            binary,
+            version,
+            next_handle: document.next_handle(),
+            written: HandleRanges::default(),
+            model_space,
+            seed_position: None,
+            downgrade,
+            document,
+        };
+        writer.write_head()?;
+        // Everything the document and the head sections use lies below the
+        // next free handle
+        writer.written.insert(0, writer.next_handle);
+        Ok(writer)
+    }
+
+    /// Write every section up to the start of ENTITIES, and the entities of
+    /// the document
+    fn write_head(&mut self) -> Result<()> {
+        let version = self.version;
+        let document = &self.document;
+        let model_space = self.model_space;
+
+        // The header is small; it is assembled in memory to find where the
+        // $HANDSEED placeholder lands
+        let mut header = Vec::new();
+        write_sections(&mut header, self.binary, version, &mut self.next_handle, |w| {
+            w.write_header(document)
+        })?;
+        let start = self.output.stream_position()?;
+        self.seed_position = find_handle_seed(&header).map(|offset| start + offset as u64);
+        self.output.write_all(&header)?;
+
+        write_sections(&mut self.output, self.binary, version, &mut self.next_handle, |w| {
+            if version != DxfVersion::AC1009 {
+                w.write_classes(document)?;
+            }
+            w.write_tables(document)?;
+            w.write_blocks(document)?;
+            w.begin_section("ENTITIES")?;
+            if let Some(block_record) = document.block_records.get("*Model_Space") {
+                for entity in &block_record.entities {
+                    w.write_entity_with_owner(entity, model_space)?;
+                }
+            }
+            for entity in document.entities() {
+                w.write_entity_with_owner(entity, model_space)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Write an entity to model space.
+    ///
+    /// An entity the target version cannot store is converted first, as by
+    /// [`DxfWriter`](super::DxfWriter). An entity whose handle was already
+    /// written gets a new one. Returns the handle of the (first) written
+    /// entity, or a null handle if it was dropped.
+    pub fn add_entity(&mut self, entity: EntityType) -> Result<Handle> {
+        let block_count = self.document.block_records.len();
+        let entities = self.downgrade.downgrade(entity, self.version, &mut self.document);
+        if self.document.block_records.len() != block_count {
+            // BLOCKS is already written
+            self.document.notifications.notify(
+                NotificationType::Warning,
+                "A converted entity references a block created after the BLOCKS section",
+            );
+        }
+
+        let mut first = Handle::NULL;
+        for mut entity in entities {
+            let handle = entity.common().handle;
+            if handle.is_null() || self.written.contains(handle.value()) {
+                entity.common_mut().handle = Handle::new(self.next_handle);
+                self.next_handle += 1;
+                if !handle.is_null() {
+                    self.document.notifications.notify(
+                        NotificationType::Warning,
+                        format!(
+                            "{} {} reuses a written handle and was written as {}",
+                            entity.dxf_name(),
+                            handle,
+                            entity.common().handle
+                        ),
+                    );
+                }
+            } else if handle.value() >= self.next_handle {
+                self.next_handle = handle.value() + 1;
+            }
+            let handle = entity.common().handle;
+            if first.is_null() {
+                first = handle;
+            }
+
+            let start = self.next_handle;
+            let model_space = self.model_space;
+            write_sections(&mut self.output, self.binary, self.version, &mut self.next_handle, |w| {
+                w.write_entity_with_owner(&entity, model_space)
+            })?;
+            self.written.insert(handle.value(), handle.value() + 1);
+            // Vertices and the like take handles while the entity is written
+            self.written.insert(start, self.next_handle);
+        }
+        Ok(first)
+    }
+
+    /// Document the writer was created with; conversions made while writing
+    /// are reported in its notifications
+    pub fn document(&self) -> &CadDocument {
+        &self.document
+    }
+
+    /// Version the file is written as
+    pub fn version(&self) -> DxfVersion {
+        self.version
+    }
+
+    /// Close ENTITIES, write OBJECTS and the end of file, set `$HANDSEED`
+    /// and return the output
+    pub fn finish(mut self) -> Result<W> {
+        let version = self.version;
+        let document = &self.document;
+        write_sections(&mut self.output, self.binary, version, &mut self.next_handle, |w| {
+            w.end_section()?;
+            if version != DxfVersion::AC1009 {
+                w.write_objects(document)?;
+            }
+            w.write_eof()
+        })?;
+
+        if let Some(position) = self.seed_position {
+            let end = self.output.stream_position()?;
+            self.output.seek(SeekFrom::Start(position))?;
+            write!(self.output, "{:016X}", self.next_handle)?;
+            self.output.seek(SeekFrom::Start(end))?;
+        }
+        self.output.flush()?;
+        Ok(self.output)
+    }
+}
+
+/// Handles written so far, as disjoint ranges keyed by their start. Entities
+/// mostly take consecutive handles, so this stays small for long files.
+#[derive(Default)]
+struct HandleRanges(BTreeMap<u64, u64>);
+
+impl HandleRanges {
+    /// Add the handles `start..end`
+    fn insert(&mut self, mut start: u64, mut end: u64) {
+        if start >= end {
+            return;
+        }
+        if let Some((&s, &e)) = self.0.range(..=start).next_back() {
+            if e >= start {
+                start = s;
+                end = end.max(e);
+            }
+        }
+        while let Some((&s, &e)) = self.0.range(start..=end).next() {
+            end = end.max(e);
+            self.0.remove(&s);
+        }
+        self.0.insert(start, end);
+    }
+
+    fn contains(&self, handle: u64) -> bool {
+        self.0.range(..=handle).next_back().is_some_and(|(_, &end)| handle < end)
+    }
+}
+
+/// Run `write` with a section writer over `output` that continues handle
+/// allocation from `next_handle`
+fn write_sections<O: Write>(
+    output: &mut O,
+    binary: bool,
+    version: DxfVersion,
+    next_handle: &mut u64,
+    write: impl FnOnce(&mut SectionWriter<'_, dyn DxfStreamWriter + '_>) -> Result<()>,
+) -> Result<()> {
+    let mut text_writer;
+    let mut binary_writer;
+    let stream: &mut dyn DxfStreamWriter = if binary {
+        binary_writer = DxfBinaryWriter::resume(output);
+        &mut binary_writer
+    } else {
+        text_writer = DxfTextWriter::new(output);
+        &mut text_writer
+    };
+    let mut r12_writer;
+    let stream: &mut dyn DxfStreamWriter = if version == DxfVersion::AC1009 {
+        r12_writer = R12StreamWriter::new(stream);
+        &mut r12_writer
+    } else {
+        stream
+    };
+
+    let mut section_writer =
+        SectionWriter::new(stream, *next_handle, HANDSEED_PLACEHOLDER).with_version(version);
+    let result = write(&mut section_writer);
+    *next_handle = section_writer.next_handle();
+    result
+}
+
+/// Offset of the `$HANDSEED` value in a written HEADER section
+fn find_handle_seed(header: &[u8]) -> Option<usize> {
+    let placeholder = format!("{:X}", HANDSEED_PLACEHOLDER);
+    let variable = find(header, b"$HANDSEED")?;
+    find(&header[variable..], placeholder.as_bytes()).map(|offset| variable + offset)
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{Line, LwPolyline};
+    use crate::io::dxf::DxfReader;
+    use crate::types::{Vector2, Vector3};
+    use std::collections::HashSet;
+    use std::io::Cursor;
+
+    fn write_lines(binary: bool, version: DxfVersion) -> Vec<u8> {
+        let document = CadDocument::with_version(version);
+        let output = Cursor::new(Vec::new());
+        let mut writer = if binary {
+            DxfIncrementalWriter::new_binary(output, document).unwrap()
+        } else {
+            DxfIncrementalWriter::new(output, document).unwrap()
+        };
+        for i in 0..200 {
+            let x = i as f64;
+            writer
+                .add_entity(EntityType::Line(Line::from_points(
+                    Vector3::new(x, 0.0, 0.0),
+                    Vector3::new(x, 1.0, 0.0),
+                )))
+                .unwrap();
+        }
+        writer
+            .add_entity(EntityType::LwPolyline(LwPolyline::from_points(vec![
+                Vector2::new(0.0, 0.0),
+                Vector2::new(1.0, 1.0),
+            ])))
+            .unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_handle_seed_follows_entities() {
+        for binary in [false, true] {
+            let bytes = write_lines(binary, DxfVersion::AC1032);
+            let doc = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+            assert_eq!(doc.entities().count(), 201);
+
+            let handles: HashSet<u64> = doc.entities().map(|e| e.common().handle.value()).collect();
+            assert_eq!(handles.len(), 201);
+            let max_handle = doc.objects.keys().map(|h| h.value()).chain(handles).max().unwrap();
+            assert!(doc.header.handle_seed > max_handle);
+            assert!(doc.header.handle_seed < max_handle + 8);
+        }
+    }
+
+    #[test]
+    fn test_r12_output() {
+        let bytes = write_lines(false, DxfVersion::AC1009);
+        let text = String::from_utf8(bytes.clone()).unwrap();
+        assert!(!text.contains("$HANDSEED"));
+        assert!(!text.contains("LWPOLYLINE"));
+
+        let doc = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+        assert_eq!(doc.version, DxfVersion::AC1009);
+        assert_eq!(doc.entities().count(), 201);
+    }
+
+    #[test]
+    fn test_written_handles_are_not_reused() {
+        let document = CadDocument::new();
+        let layer = document.layers.get("0").unwrap().handle;
+        let mut writer = DxfIncrementalWriter::new(Cursor::new(Vec::new()), document).unwrap();
+
+        let line = |handle: u64| {
+            let mut line = Line::from_points(Vector3::ZERO, Vector3::new(1.0, 0.0, 0.0));
+            line.common.handle = Handle::new(handle);
+            EntityType::Line(line)
+        };
+        let first = writer.add_entity(line(0x5000)).unwrap();
+        let second = writer.add_entity(line(0x5000)).unwrap();
+        let third = writer.add_entity(line(layer.value())).unwrap();
+        let fourth = writer.add_entity(line(0x4000)).unwrap();
+        assert_eq!(first, Handle::new(0x5000));
+        assert_ne!(second, first);
+        assert_ne!(third, layer);
+        // Handles below the highest one written are fine while unused
+        assert_eq!(fourth, Handle::new(0x4000));
+
+        let warnings = writer.document().notifications.of_type(NotificationType::Warning);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].message.contains(&format!("written as {}", second)));
+
+        let bytes = writer.finish().unwrap().into_inner();
+        let doc = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+        let handles: HashSet<Handle> = doc.entities().map(|e| e.common().handle).collect();
+        assert_eq!(handles, HashSet::from([first, second, third, fourth]));
+        assert_eq!(doc.layers.get("0").unwrap().handle, layer);
+    }
+}
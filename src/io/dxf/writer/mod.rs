@@ -4,15 +4,20 @@ mod stream_writer;
 mod text_writer;
 mod binary_writer;
 mod section_writer;
+mod r12;
+mod incremental;
 
 pub use stream_writer::{DxfStreamWriter, DxfStreamWriterExt, value_type_for_code};
 pub use text_writer::DxfTextWriter;
 pub use binary_writer::DxfBinaryWriter;
 pub use section_writer::SectionWriter;
+pub use incremental::DxfIncrementalWriter;
 
 use crate::document::CadDocument;
 use crate::entities::EntityType;
 use crate::error::Result;
+use crate::io::downgrade::DowngradePass;
+use crate::types::DxfVersion;
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
@@ -26,18 +31,29 @@ pub struct DxfWriter {
 
 impl DxfWriter {
     /// Create a new DXF writer for ASCII output
+    ///
+    /// Entities the document version cannot store are converted or dropped
+    /// right away by the default [`DowngradePass`]; each change is reported
+    /// in `document().notifications`.
     pub fn new(document: CadDocument) -> Self {
-        Self {
-            document,
-            binary: false,
-        }
+        Self::with_downgrade_pass(document, DowngradePass::default())
     }
 
     /// Create a new DXF writer for binary output
     pub fn new_binary(document: CadDocument) -> Self {
+        let mut writer = Self::new(document);
+        writer.binary = true;
+        writer
+    }
+
+    /// Create a new DXF writer for ASCII output that converts the entities
+    /// the document version cannot store with `downgrade`
+    pub fn with_downgrade_pass(mut document: CadDocument, downgrade: DowngradePass) -> Self {
+        let version = document.version;
+        downgrade.run(&mut document, version);
         Self {
             document,
-            binary: true,
+            binary: false,
         }
     }
 
@@ -76,18 +92,35 @@ impl DxfWriter {
 
     /// Write DXF content to a stream writer
     fn write_dxf<W: DxfStreamWriter>(&self, writer: &mut W) -> Result<()> {
+        if self.document.version == DxfVersion::AC1009 {
+            return self.write_sections(&mut r12::R12StreamWriter::new(writer));
+        }
+        self.write_sections(writer)
+    }
+
+    fn write_sections<W: DxfStreamWriter>(&self, writer: &mut W) -> Result<()> {
+        // Documents without a known version are written as the newest release
+        let version = match self.document.version {
+            DxfVersion::Unknown => DxfVersion::AC1032,
+            version => version,
+        };
         let handle_start = self.document.next_handle();
         let extra_handles = count_extra_handles(&self.document);
         let handle_seed = handle_start + extra_handles;
-        let mut section_writer = SectionWriter::new(writer, handle_start, handle_seed);
+        let mut section_writer =
+            SectionWriter::new(writer, handle_start, handle_seed).with_version(version);
 
-        // Write all sections
+        // Write all sections; R12 has no CLASSES or OBJECTS
         section_writer.write_header(&self.document)?;
-        section_writer.write_classes(&self.document)?;
+        if version != DxfVersion::AC1009 {
+            section_writer.write_classes(&self.document)?;
+        }
         section_writer.write_tables(&self.document)?;
         section_writer.write_blocks(&self.document)?;
         section_writer.write_entities(&self.document)?;
-        section_writer.write_objects(&self.document)?;
+        if version != DxfVersion::AC1009 {
+            section_writer.write_objects(&self.document)?;
+        }
 
         // Write EOF
         writer.write_string(0, "EOF")?;
@@ -107,6 +140,11 @@ fn count_extra_handles(document: &CadDocument) -> u64 {
     // Root dictionary in OBJECTS
     count += 1;
 
+    // DWGPROPS XRecord added to the root dictionary
+    if !document.summary_info.is_empty() && super::dwg_props::find_dwgprops(document).is_none() {
+        count += 1;
+    }
+
     for entity in document.entities() {
         match entity {
             EntityType::Polyline(polyline) => {
@@ -0,0 +1,130 @@
+//! AutoCAD R12 (AC1009) output
+//!
+//! R12 predates handles on by default, subclass markers, the OBJECTS and
+//! CLASSES sections and most entity types. Entities R12 lacks are rewritten
+//! by the [`DowngradePass`](crate::io::downgrade::DowngradePass) before
+//! writing; [`R12StreamWriter`] then wraps the stream writer and discards the
+//! group codes R12 readers do not know (handles, 100/102 markers, 90-99,
+//! 280-369, ...), so the section writer can emit its usual records.
+
+use crate::error::Result;
+use crate::types::{Color, Handle};
+
+use super::stream_writer::DxfStreamWriter;
+
+/// Whether a group code exists in R12 DXF
+pub(super) fn is_r12_code(code: i32) -> bool {
+    matches!(
+        code,
+        0..=59 | 62..=79 | 140..=147 | 170..=178 | 210..=239 | 999 | 1000..=1071
+    )
+}
+
+/// Stream writer that drops everything an R12 reader would reject
+///
+/// Handles are never written: R12 files are written with `$HANDLING` off.
+/// True colors (420) are replaced by the nearest color index (62).
+pub(crate) struct R12StreamWriter<'a, W: DxfStreamWriter + ?Sized> {
+    inner: &'a mut W,
+}
+
+impl<'a, W: DxfStreamWriter + ?Sized> R12StreamWriter<'a, W> {
+    pub(crate) fn new(inner: &'a mut W) -> Self {
+        Self { inner }
+    }
+}
+
+impl<W: DxfStreamWriter + ?Sized> DxfStreamWriter for R12StreamWriter<'_, W> {
+    fn write_string(&mut self, code: i32, value: &str) -> Result<()> {
+        if is_r12_code(code) {
+            self.inner.write_string(code, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_byte(&mut self, code: i32, value: u8) -> Result<()> {
+        if is_r12_code(code) {
+            self.inner.write_byte(code, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_i16(&mut self, code: i32, value: i16) -> Result<()> {
+        if is_r12_code(code) {
+            self.inner.write_i16(code, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_i32(&mut self, code: i32, value: i32) -> Result<()> {
+        if code == 420 {
+            let color = Color::from_rgb((value >> 16) as u8, (value >> 8) as u8, value as u8);
+            return self.inner.write_i16(62, color.approximate_index());
+        }
+        if is_r12_code(code) {
+            self.inner.write_i32(code, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_i64(&mut self, code: i32, value: i64) -> Result<()> {
+        if is_r12_code(code) {
+            self.inner.write_i64(code, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_double(&mut self, code: i32, value: f64) -> Result<()> {
+        if is_r12_code(code) {
+            self.inner.write_double(code, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_bool(&mut self, code: i32, value: bool) -> Result<()> {
+        if is_r12_code(code) {
+            self.inner.write_bool(code, value)?;
+        }
+        Ok(())
+    }
+
+    fn write_handle(&mut self, _code: i32, _handle: Handle) -> Result<()> {
+        Ok(())
+    }
+
+    fn write_binary(&mut self, code: i32, data: &[u8]) -> Result<()> {
+        if is_r12_code(code) {
+            self.inner.write_binary(code, data)?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_r12_filter_drops_newer_codes() {
+        let mut buffer = Vec::new();
+        {
+            let mut text = super::super::DxfTextWriter::new(&mut buffer);
+            let mut writer = R12StreamWriter::new(&mut text);
+            writer.write_string(0, "LINE").unwrap();
+            writer.write_handle(5, Handle::new(0x2A)).unwrap();
+            writer.write_string(100, "AcDbEntity").unwrap();
+            writer.write_string(8, "0").unwrap();
+            writer.write_i16(370, 25).unwrap();
+            writer.write_i32(420, 0xFF0000).unwrap();
+            writer.flush().unwrap();
+        }
+        let output = String::from_utf8(buffer).unwrap();
+        let codes: Vec<&str> = output.lines().step_by(2).map(str::trim).collect();
+        assert_eq!(codes, ["0", "8", "62"]);
+        assert!(output.contains("62\n1\n"));
+    }
+}
@@ -3,6 +3,8 @@
 //! This module contains writers for each section of a DXF file:
 //! HEADER, CLASSES, TABLES, BLOCKS, ENTITIES, and OBJECTS.
 
+use std::collections::HashSet;
+
 use crate::document::CadDocument;
 use crate::entities::*;
 use crate::error::Result;
@@ -13,9 +15,16 @@ use crate::objects::{
     TableStyle, VisualStyle, BookColor, WipeoutVariables, XRecord,
 };
 use crate::tables::*;
-use crate::types::{Color, Handle, Vector3};
+use crate::types::{
+    Color, DxfVersion, Handle, RawGroupCode, Transparency, UnknownCodeGroup, UnknownCodes, Vector3,
+};
 use crate::xdata::{ExtendedData, XDataValue};
+use crate::io::dxf::GroupCodeValueType;
 
+use crate::io::downgrade::introduced_in;
+
+use super::super::dwg_props;
+use super::r12::is_r12_code;
 use super::stream_writer::{DxfStreamWriter, DxfStreamWriterExt};
 
 /// Standard table handles (well-known values used by AutoCAD)
@@ -30,43 +39,172 @@ const HANDLE_APPID_TABLE: u64 = 0x9;
 const HANDLE_DIMSTYLE_TABLE: u64 = 0xA;
 const HANDLE_BLOCK_RECORD_TABLE: u64 = 0x1;
 
+/// Unknown group codes and extended data of the entity being written
+struct PendingCodes {
+    /// Subclass marker written last
+    subclass: Option<String>,
+    /// Number of codes the entity had when that marker was written
+    subclass_start: usize,
+    /// Application groups and runs of codes of a subclass, not written yet
+    groups: Vec<UnknownCodeGroup>,
+    /// Codes read before any subclass marker other than application groups,
+    /// which only files without markers (R12) have; written last
+    trailing: Vec<RawGroupCode>,
+    /// Written as read, even where the entity writer wrote the same codes
+    verbatim: bool,
+    extended_data: ExtendedData,
+}
+
+/// Stream writer that notes the codes of the entity being written, so that
+/// kept codes are not written a second time
+struct RecordingWriter<'a, W: DxfStreamWriter + ?Sized> {
+    inner: &'a mut W,
+    /// Codes written since the start of the entity, while recording
+    codes: Option<Vec<i32>>,
+}
+
+impl<W: DxfStreamWriter + ?Sized> RecordingWriter<'_, W> {
+    fn note(&mut self, code: i32) {
+        if let Some(codes) = self.codes.as_mut() {
+            codes.push(code);
+        }
+    }
+
+    fn recorded(&self) -> &[i32] {
+        self.codes.as_deref().unwrap_or_default()
+    }
+}
+
+impl<W: DxfStreamWriter + ?Sized> DxfStreamWriter for RecordingWriter<'_, W> {
+    fn write_string(&mut self, code: i32, value: &str) -> Result<()> {
+        self.note(code);
+        self.inner.write_string(code, value)
+    }
+
+    fn write_byte(&mut self, code: i32, value: u8) -> Result<()> {
+        self.note(code);
+        self.inner.write_byte(code, value)
+    }
+
+    fn write_i16(&mut self, code: i32, value: i16) -> Result<()> {
+        self.note(code);
+        self.inner.write_i16(code, value)
+    }
+
+    fn write_i32(&mut self, code: i32, value: i32) -> Result<()> {
+        self.note(code);
+        self.inner.write_i32(code, value)
+    }
+
+    fn write_i64(&mut self, code: i32, value: i64) -> Result<()> {
+        self.note(code);
+        self.inner.write_i64(code, value)
+    }
+
+    fn write_double(&mut self, code: i32, value: f64) -> Result<()> {
+        self.note(code);
+        self.inner.write_double(code, value)
+    }
+
+    fn write_bool(&mut self, code: i32, value: bool) -> Result<()> {
+        self.note(code);
+        self.inner.write_bool(code, value)
+    }
+
+    fn write_handle(&mut self, code: i32, handle: Handle) -> Result<()> {
+        self.note(code);
+        self.inner.write_handle(code, handle)
+    }
+
+    fn write_binary(&mut self, code: i32, data: &[u8]) -> Result<()> {
+        self.note(code);
+        self.inner.write_binary(code, data)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Writes all DXF sections
-pub struct SectionWriter<'a, W: DxfStreamWriter> {
-    writer: &'a mut W,
+pub struct SectionWriter<'a, W: DxfStreamWriter + ?Sized> {
+    writer: RecordingWriter<'a, W>,
     next_handle: u64,
     handle_seed: u64,
+    version: DxfVersion,
+    pending: Option<PendingCodes>,
 }
 
-impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
+impl<'a, W: DxfStreamWriter + ?Sized> SectionWriter<'a, W> {
     /// Create a new section writer
     pub fn new(writer: &'a mut W, handle_start: u64, handle_seed: u64) -> Self {
         Self {
-            writer,
+            writer: RecordingWriter { inner: writer, codes: None },
             next_handle: handle_start,
             handle_seed,
+            version: DxfVersion::AC1032,
+            pending: None,
         }
     }
 
+    /// Set the target version (defaults to AC1032)
+    pub fn with_version(mut self, version: DxfVersion) -> Self {
+        self.version = version;
+        self
+    }
+
     fn allocate_handle(&mut self) -> Handle {
         let handle = Handle::new(self.next_handle);
         self.next_handle += 1;
         handle
     }
 
+    /// Next handle the writer will allocate
+    pub(crate) fn next_handle(&self) -> u64 {
+        self.next_handle
+    }
+
+    /// Start a section whose records are written one at a time
+    pub(crate) fn begin_section(&mut self, name: &str) -> Result<()> {
+        self.writer.write_section_start(name)
+    }
+
+    /// End the section started with [`begin_section`](Self::begin_section)
+    pub(crate) fn end_section(&mut self) -> Result<()> {
+        self.writer.write_section_end()
+    }
+
+    /// Write the end of file marker
+    pub(crate) fn write_eof(&mut self) -> Result<()> {
+        self.writer.write_eof()
+    }
+
     /// Write the HEADER section
     pub fn write_header(&mut self, document: &CadDocument) -> Result<()> {
         self.writer.write_section_start("HEADER")?;
         let hdr = &document.header;
+        let version = self.version;
 
         // === Version & maintenance ===
         self.write_header_variable("$ACADVER", |w| {
-            w.write_string(1, document.version.to_dxf_string())
+            w.write_string(1, version.to_dxf_string())
+        })?;
+        // The maintenance version became a 32-bit value in R2018
+        self.write_header_variable_since(DxfVersion::AC1012, "$ACADMAINTVER", |w| {
+            if version >= DxfVersion::AC1032 {
+                w.write_i32(90, hdr.maintenance_version)
+            } else {
+                w.write_i16(70, hdr.maintenance_version as i16)
+            }
         })?;
-        self.write_header_variable("$ACADMAINTVER", |w| w.write_i16(70, 0))?;
         self.write_header_variable("$DWGCODEPAGE", |w| w.write_string(3, &hdr.code_page))?;
+        self.write_header_variable_since(DxfVersion::AC1018, "$LASTSAVEDBY", |w| w.write_string(1, &hdr.last_saved_by))?;
+        self.write_header_variable_since(DxfVersion::AC1027, "$REQUIREDVERSIONS", |w| w.write_i64(160, hdr.required_versions))?;
 
         let handle_seed = self.handle_seed;
-        self.write_header_variable("$HANDSEED", |w| w.write_handle(5, Handle::new(handle_seed)))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$HANDSEED", |w| w.write_handle(5, Handle::new(handle_seed)))?;
+        // R12 files are written without handles
+        self.write_header_variable_until(DxfVersion::AC1009, "$HANDLING", |w| w.write_i16(70, 0))?;
 
         // === Drawing extents & limits ===
         self.write_header_variable("$INSBASE", |w| {
@@ -90,32 +228,62 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             w.write_double(10, v.x)?; w.write_double(20, v.y)
         })?;
 
+        // === R12 view, snap and grid (in the *ACTIVE viewport from R13 on) ===
+        self.write_header_variable_until(DxfVersion::AC1009, "$VIEWCTR", |w| w.write_point2d(10, hdr.view_center))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$VIEWSIZE", |w| w.write_double(40, hdr.view_size))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$SNAPMODE", |w| w.write_i16(70, if hdr.snap_mode { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$SNAPUNIT", |w| w.write_point2d(10, hdr.snap_unit))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$SNAPBASE", |w| w.write_point2d(10, hdr.snap_base))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$SNAPANG", |w| w.write_double(50, hdr.snap_angle))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$SNAPSTYLE", |w| w.write_i16(70, hdr.snap_style))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$SNAPISOPAIR", |w| w.write_i16(70, hdr.snap_isopair))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$GRIDMODE", |w| w.write_i16(70, if hdr.grid_mode { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$GRIDUNIT", |w| w.write_point2d(10, hdr.grid_unit))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$AXISMODE", |w| w.write_i16(70, if hdr.axis_mode { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$AXISUNIT", |w| w.write_point2d(10, hdr.axis_unit))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$FASTZOOM", |w| w.write_i16(70, if hdr.fast_zoom { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$VIEWDIR", |w| w.write_point3d(10, hdr.view_direction))?;
+
         // === Drawing modes ===
         self.write_header_variable("$ORTHOMODE", |w| w.write_i16(70, if hdr.ortho_mode { 1 } else { 0 }))?;
         self.write_header_variable("$REGENMODE", |w| w.write_i16(70, if hdr.regen_mode { 1 } else { 0 }))?;
         self.write_header_variable("$FILLMODE", |w| w.write_i16(70, if hdr.fill_mode { 1 } else { 0 }))?;
         self.write_header_variable("$QTEXTMODE", |w| w.write_i16(70, if hdr.quick_text_mode { 1 } else { 0 }))?;
         self.write_header_variable("$MIRRTEXT", |w| w.write_i16(70, if hdr.mirror_text { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$DRAGMODE", |w| w.write_i16(70, hdr.drag_mode))?;
         self.write_header_variable("$LTSCALE", |w| w.write_double(40, hdr.linetype_scale))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$OSMODE", |w| w.write_i16(70, hdr.object_snap_mode as i16))?;
         self.write_header_variable("$ATTMODE", |w| w.write_i16(70, hdr.attribute_visibility))?;
         self.write_header_variable("$TEXTSIZE", |w| w.write_double(40, hdr.text_height))?;
         self.write_header_variable("$TRACEWID", |w| w.write_double(40, hdr.trace_width))?;
         self.write_header_variable("$TEXTSTYLE", |w| w.write_string(7, &hdr.current_text_style_name))?;
-        self.write_header_variable("$CMLSTYLE", |w| w.write_string(2, &hdr.multiline_style))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$CMLSTYLE", |w| w.write_string(2, &hdr.multiline_style))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$CMLJUST", |w| w.write_i16(70, hdr.multiline_justification))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$CMLSCALE", |w| w.write_double(40, hdr.multiline_scale))?;
         self.write_header_variable("$CLAYER", |w| w.write_string(8, &hdr.current_layer_name))?;
         self.write_header_variable("$CELTYPE", |w| w.write_string(6, &hdr.current_linetype_name))?;
         self.write_header_variable("$CECOLOR", |w| w.write_i16(62, hdr.current_entity_color.approximate_index()))?;
-        self.write_header_variable("$CELWEIGHT", |w| w.write_i16(370, hdr.current_line_weight))?;
-        self.write_header_variable("$CELTSCALE", |w| w.write_double(40, hdr.current_entity_linetype_scale))?;
-        self.write_header_variable("$DISPSILH", |w| w.write_i16(70, if hdr.display_silhouette { 1 } else { 0 }))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$CELWEIGHT", |w| w.write_i16(370, hdr.current_line_weight))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$CELTSCALE", |w| w.write_double(40, hdr.current_entity_linetype_scale))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DISPSILH", |w| w.write_i16(70, if hdr.display_silhouette { 1 } else { 0 }))?;
+        self.write_header_variable("$LIMCHECK", |w| w.write_i16(70, if hdr.limit_check { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$BLIPMODE", |w| w.write_i16(70, if hdr.blip_mode { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$COORDS", |w| w.write_i16(70, hdr.coords_mode))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$ATTDIA", |w| w.write_i16(70, if hdr.attribute_dialog { 1 } else { 0 }))?;
+        self.write_header_variable_until(DxfVersion::AC1009, "$ATTREQ", |w| w.write_i16(70, if hdr.attribute_request { 1 } else { 0 }))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$PICKSTYLE", |w| w.write_i16(70, hdr.pick_style))?;
+        self.write_header_variable("$SKPOLY", |w| w.write_i16(70, if hdr.sketch_polylines { 1 } else { 0 }))?;
 
         // === Units ===
         self.write_header_variable("$LUNITS", |w| w.write_i16(70, hdr.linear_unit_format))?;
         self.write_header_variable("$LUPREC", |w| w.write_i16(70, hdr.linear_unit_precision))?;
         self.write_header_variable("$AUNITS", |w| w.write_i16(70, hdr.angular_unit_format))?;
         self.write_header_variable("$AUPREC", |w| w.write_i16(70, hdr.angular_unit_precision))?;
-        self.write_header_variable("$MEASUREMENT", |w| w.write_i16(70, hdr.measurement))?;
-        self.write_header_variable("$INSUNITS", |w| w.write_i16(70, hdr.insertion_units))?;
+        self.write_header_variable("$ANGBASE", |w| w.write_double(50, hdr.angle_base))?;
+        self.write_header_variable("$ANGDIR", |w| w.write_i16(70, hdr.angle_direction))?;
+        self.write_header_variable("$UNITMODE", |w| w.write_i16(70, hdr.unit_mode))?;
+        self.write_header_variable_since(DxfVersion::AC1014, "$MEASUREMENT", |w| w.write_i16(70, hdr.measurement))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$INSUNITS", |w| w.write_i16(70, hdr.insertion_units))?;
 
         // === Point display ===
         self.write_header_variable("$PDMODE", |w| w.write_i16(70, hdr.point_display_mode))?;
@@ -123,6 +291,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.write_header_variable("$PLINEGEN", |w| w.write_i16(70, if hdr.polyline_linetype_generation { 1 } else { 0 }))?;
         self.write_header_variable("$PSLTSCALE", |w| w.write_i16(70, if hdr.paper_space_linetype_scaling { 1 } else { 0 }))?;
 
+        // === Drawing defaults ===
+        self.write_header_variable("$SKETCHINC", |w| w.write_double(40, hdr.sketch_increment))?;
+        self.write_header_variable("$FILLETRAD", |w| w.write_double(40, hdr.fillet_radius))?;
+        self.write_header_variable("$CHAMFERA", |w| w.write_double(40, hdr.chamfer_distance_a))?;
+        self.write_header_variable("$CHAMFERB", |w| w.write_double(40, hdr.chamfer_distance_b))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$CHAMFERC", |w| w.write_double(40, hdr.chamfer_length))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$CHAMFERD", |w| w.write_double(40, hdr.chamfer_angle))?;
+        self.write_header_variable("$ELEVATION", |w| w.write_double(40, hdr.elevation))?;
+        self.write_header_variable("$THICKNESS", |w| w.write_double(40, hdr.thickness))?;
+        self.write_header_variable("$MENU", |w| w.write_string(1, &hdr.menu_name))?;
+
         // === Dimension variables ===
         self.write_header_variable("$DIMSCALE", |w| w.write_double(40, hdr.dim_scale))?;
         self.write_header_variable("$DIMASZ", |w| w.write_double(40, hdr.dim_arrow_size))?;
@@ -144,6 +323,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.write_header_variable("$DIMSE2", |w| w.write_i16(70, if hdr.dim_suppress_ext2 { 1 } else { 0 }))?;
         self.write_header_variable("$DIMTAD", |w| w.write_i16(70, hdr.dim_text_above))?;
         self.write_header_variable("$DIMZIN", |w| w.write_i16(70, hdr.dim_zero_suppression))?;
+        self.write_header_variable("$DIMBLK", |w| w.write_string(1, &hdr.dim_arrow_block))?;
+        self.write_header_variable("$DIMASO", |w| w.write_i16(70, if hdr.associate_dimensions { 1 } else { 0 }))?;
+        self.write_header_variable("$DIMSHO", |w| w.write_i16(70, if hdr.update_dimensions_while_dragging { 1 } else { 0 }))?;
         self.write_header_variable("$DIMCLRD", |w| w.write_i16(70, hdr.dim_line_color.approximate_index()))?;
         self.write_header_variable("$DIMCLRE", |w| w.write_i16(70, hdr.dim_ext_line_color.approximate_index()))?;
         self.write_header_variable("$DIMCLRT", |w| w.write_i16(70, hdr.dim_text_color.approximate_index()))?;
@@ -157,30 +339,52 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.write_header_variable("$DIMTIX", |w| w.write_i16(70, if hdr.dim_force_text_inside { 1 } else { 0 }))?;
         self.write_header_variable("$DIMSOXD", |w| w.write_i16(70, if hdr.dim_suppress_outside_ext { 1 } else { 0 }))?;
         self.write_header_variable("$DIMSAH", |w| w.write_i16(70, if hdr.dim_separate_arrows { 1 } else { 0 }))?;
+        self.write_header_variable("$DIMBLK1", |w| w.write_string(1, &hdr.dim_arrow_block1))?;
+        self.write_header_variable("$DIMBLK2", |w| w.write_string(1, &hdr.dim_arrow_block2))?;
         self.write_header_variable("$DIMPOST", |w| w.write_string(1, &hdr.dim_post))?;
         self.write_header_variable("$DIMAPOST", |w| w.write_string(1, &hdr.dim_alt_post))?;
         self.write_header_variable("$DIMSTYLE", |w| w.write_string(2, &hdr.current_dimstyle_name))?;
-        self.write_header_variable("$DIMLUNIT", |w| w.write_i16(70, hdr.dim_linear_unit_format))?;
-        self.write_header_variable("$DIMDEC", |w| w.write_i16(70, hdr.dim_decimal_places))?;
-        self.write_header_variable("$DIMTDEC", |w| w.write_i16(70, hdr.dim_tolerance_decimal_places))?;
-        self.write_header_variable("$DIMALTU", |w| w.write_i16(70, hdr.dim_alt_units_format))?;
-        self.write_header_variable("$DIMALTTD", |w| w.write_i16(70, hdr.dim_alt_tolerance_decimal_places))?;
-        self.write_header_variable("$DIMAUNIT", |w| w.write_i16(70, hdr.dim_angular_units))?;
-        self.write_header_variable("$DIMADEC", |w| w.write_i16(70, hdr.dim_angular_decimal_places))?;
-        self.write_header_variable("$DIMJUST", |w| w.write_i16(70, hdr.dim_horizontal_justification))?;
-        self.write_header_variable("$DIMSD1", |w| w.write_i16(70, if hdr.dim_suppress_line1 { 1 } else { 0 }))?;
-        self.write_header_variable("$DIMSD2", |w| w.write_i16(70, if hdr.dim_suppress_line2 { 1 } else { 0 }))?;
-        self.write_header_variable("$DIMTOLJ", |w| w.write_i16(70, hdr.dim_tolerance_justification))?;
-        self.write_header_variable("$DIMTZIN", |w| w.write_i16(70, hdr.dim_tolerance_zero_suppression))?;
-        self.write_header_variable("$DIMALTZ", |w| w.write_i16(70, hdr.dim_alt_tolerance_zero_suppression))?;
-        self.write_header_variable("$DIMALTTZ", |w| w.write_i16(70, hdr.dim_alt_tolerance_zero_tight))?;
-        self.write_header_variable("$DIMATFIT", |w| w.write_i16(70, hdr.dim_fit))?;
-        self.write_header_variable("$DIMDSEP", |w| w.write_i16(70, hdr.dim_decimal_separator as i16))?;
-        self.write_header_variable("$DIMTMOVE", |w| w.write_i16(70, hdr.dim_text_movement))?;
-        self.write_header_variable("$DIMFRAC", |w| w.write_i16(70, hdr.dim_fraction_format))?;
-        self.write_header_variable("$DIMLWD", |w| w.write_i16(70, hdr.dim_line_weight))?;
-        self.write_header_variable("$DIMLWE", |w| w.write_i16(70, hdr.dim_ext_line_weight))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMTXSTY", |w| w.write_string(7, &hdr.dim_text_style_name))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMLUNIT", |w| w.write_i16(70, hdr.dim_linear_unit_format))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMDEC", |w| w.write_i16(70, hdr.dim_decimal_places))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMTDEC", |w| w.write_i16(70, hdr.dim_tolerance_decimal_places))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMALTU", |w| w.write_i16(70, hdr.dim_alt_units_format))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMALTTD", |w| w.write_i16(70, hdr.dim_alt_tolerance_decimal_places))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMAUNIT", |w| w.write_i16(70, hdr.dim_angular_units))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMADEC", |w| w.write_i16(70, hdr.dim_angular_decimal_places))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMALTRND", |w| w.write_double(40, hdr.dim_alt_rounding))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMAZIN", |w| w.write_i16(70, hdr.dim_alt_zero_suppression))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMJUST", |w| w.write_i16(70, hdr.dim_horizontal_justification))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMSD1", |w| w.write_i16(70, if hdr.dim_suppress_line1 { 1 } else { 0 }))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMSD2", |w| w.write_i16(70, if hdr.dim_suppress_line2 { 1 } else { 0 }))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMTOLJ", |w| w.write_i16(70, hdr.dim_tolerance_justification))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMTZIN", |w| w.write_i16(70, hdr.dim_tolerance_zero_suppression))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMALTZ", |w| w.write_i16(70, hdr.dim_alt_tolerance_zero_suppression))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMALTTZ", |w| w.write_i16(70, hdr.dim_alt_tolerance_zero_tight))?;
+        // R13 and R14 store the fit options as $DIMFIT; R2000 replaced it with $DIMATFIT
+        if version < DxfVersion::AC1015 {
+            self.write_header_variable_since(DxfVersion::AC1012, "$DIMFIT", |w| w.write_i16(70, hdr.dim_fit))?;
+        }
+        self.write_header_variable_since(DxfVersion::AC1012, "$DIMUPT", |w| w.write_i16(70, if hdr.dim_user_positioned_text { 1 } else { 0 }))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMATFIT", |w| w.write_i16(70, hdr.dim_fit))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMDSEP", |w| w.write_i16(70, hdr.dim_decimal_separator as i16))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMTMOVE", |w| w.write_i16(70, hdr.dim_text_movement))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMFRAC", |w| w.write_i16(70, hdr.dim_fraction_format))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMLDRBLK", |w| w.write_string(1, &hdr.dim_leader_arrow_block))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMLWD", |w| w.write_i16(70, hdr.dim_line_weight))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMLWE", |w| w.write_i16(70, hdr.dim_ext_line_weight))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$DIMASSOC", |w| w.write_i16(280, hdr.dimension_associativity))?;
         self.write_header_variable("$DIMTFAC", |w| w.write_double(40, hdr.dim_tolerance_scale))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMLTYPE", |w| w.write_string(6, &hdr.dim_linetype_name))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMLTEX1", |w| w.write_string(6, &hdr.dim_linetype1_name))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMLTEX2", |w| w.write_string(6, &hdr.dim_linetype2_name))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMFXL", |w| w.write_double(40, hdr.dim_fixed_ext_line_length))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMFXLON", |w| w.write_i16(70, if hdr.dim_fixed_ext_line_enabled { 1 } else { 0 }))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMJOGANG", |w| w.write_double(40, hdr.dim_jog_angle))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMTFILL", |w| w.write_i16(70, hdr.dim_text_fill))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMTFILLCLR", |w| w.write_i16(70, hdr.dim_text_fill_color.approximate_index()))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DIMARCSYM", |w| w.write_i16(70, hdr.dim_arc_symbol_position))?;
+        self.write_header_variable_since(DxfVersion::AC1024, "$DIMTXTDIRECTION", |w| w.write_i16(70, if hdr.dim_text_direction { 1 } else { 0 }))?;
 
         // === Misc ===
         self.write_header_variable("$SPLFRAME", |w| w.write_i16(70, if hdr.spline_frame { 1 } else { 0 }))?;
@@ -191,20 +395,117 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.write_header_variable("$SURFTYPE", |w| w.write_i16(70, hdr.surface_type))?;
         self.write_header_variable("$SURFU", |w| w.write_i16(70, hdr.surface_u_density))?;
         self.write_header_variable("$SURFV", |w| w.write_i16(70, hdr.surface_v_density))?;
+        self.write_header_variable("$USERI1", |w| w.write_i16(70, hdr.user_int1))?;
+        self.write_header_variable("$USERI2", |w| w.write_i16(70, hdr.user_int2))?;
+        self.write_header_variable("$USERI3", |w| w.write_i16(70, hdr.user_int3))?;
+        self.write_header_variable("$USERI4", |w| w.write_i16(70, hdr.user_int4))?;
+        self.write_header_variable("$USERI5", |w| w.write_i16(70, hdr.user_int5))?;
+        self.write_header_variable("$USERR1", |w| w.write_double(40, hdr.user_real1))?;
+        self.write_header_variable("$USERR2", |w| w.write_double(40, hdr.user_real2))?;
+        self.write_header_variable("$USERR3", |w| w.write_double(40, hdr.user_real3))?;
+        self.write_header_variable("$USERR4", |w| w.write_double(40, hdr.user_real4))?;
+        self.write_header_variable("$USERR5", |w| w.write_double(40, hdr.user_real5))?;
         self.write_header_variable("$WORLDVIEW", |w| w.write_i16(70, if hdr.world_view { 1 } else { 0 }))?;
+        self.write_header_variable("$SHADEDGE", |w| w.write_i16(70, hdr.shade_edge))?;
+        self.write_header_variable("$SHADEDIF", |w| w.write_i16(70, hdr.shade_diffuse))?;
         self.write_header_variable("$PELEVATION", |w| w.write_double(40, hdr.paper_elevation))?;
         self.write_header_variable("$PLINEWID", |w| w.write_double(40, hdr.polyline_width))?;
         self.write_header_variable("$MAXACTVP", |w| w.write_i16(70, hdr.max_active_viewports))?;
         self.write_header_variable("$TILEMODE", |w| w.write_i16(70, if hdr.show_model_space { 1 } else { 0 }))?;
         self.write_header_variable("$PLIMCHECK", |w| w.write_i16(70, if hdr.paper_space_limit_check { 1 } else { 0 }))?;
         self.write_header_variable("$VISRETAIN", |w| w.write_i16(70, if hdr.retain_xref_visibility { 1 } else { 0 }))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$TREEDEPTH", |w| w.write_i16(70, hdr.tree_depth))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$ISOLINES", |w| w.write_i16(70, hdr.isolines))?;
+        self.write_header_variable_since(DxfVersion::AC1012, "$FACETRES", |w| w.write_double(40, hdr.facet_resolution))?;
+        self.write_header_variable_since(DxfVersion::AC1014, "$PROXYGRAPHICS", |w| w.write_i16(70, hdr.proxy_graphics))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PSVPSCALE", |w| w.write_double(40, hdr.viewport_scale_factor))?;
+
+        // === Plot styles, lineweights and R2000 display settings ===
+        self.write_header_variable_since(DxfVersion::AC1015, "$CEPSNTYPE", |w| w.write_i16(380, hdr.current_plotstyle_type))?;
+        if !hdr.current_plotstyle_handle.is_null() {
+            self.write_header_variable_since(DxfVersion::AC1015, "$CEPSNID", |w| w.write_handle(390, hdr.current_plotstyle_handle))?;
+        }
+        self.write_header_variable_since(DxfVersion::AC1015, "$ENDCAPS", |w| w.write_i16(280, hdr.end_caps))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$JOINSTYLE", |w| w.write_i16(280, hdr.join_style))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$LWDISPLAY", |w| w.write_bool(290, hdr.lineweight_display))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$XEDIT", |w| w.write_bool(290, hdr.xedit))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$EXTNAMES", |w| w.write_bool(290, hdr.extended_names))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PSTYLEMODE", |w| w.write_bool(290, hdr.plotstyle_mode))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$OLESTARTUP", |w| w.write_bool(290, hdr.ole_startup))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$SORTENTS", |w| w.write_i16(280, hdr.sort_entities))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$INDEXCTL", |w| w.write_i16(280, hdr.index_control))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$HIDETEXT", |w| w.write_i16(280, hdr.hide_text))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$XCLIPFRAME", |w| w.write_i16(280, hdr.xclip_frame))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$HALOGAP", |w| w.write_i16(280, hdr.halo_gap))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$OBSCOLOR", |w| w.write_i16(70, hdr.obscured_color))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$OBSLTYPE", |w| w.write_i16(280, hdr.obscured_linetype))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$INTERSECTIONDISPLAY", |w| w.write_i16(280, hdr.intersection_display))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$INTERSECTIONCOLOR", |w| w.write_i16(70, hdr.intersection_color))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PROJECTNAME", |w| w.write_string(1, &hdr.project_name))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$HYPERLINKBASE", |w| w.write_string(1, &hdr.hyperlink_base))?;
+        self.write_header_variable_since(DxfVersion::AC1018, "$STYLESHEET", |w| w.write_string(1, &hdr.stylesheet))?;
+        // AutoCAD assigns GUIDs to files saved without them
+        if !hdr.fingerprint_guid.is_empty() {
+            self.write_header_variable_since(DxfVersion::AC1015, "$FINGERPRINTGUID", |w| w.write_string(2, &hdr.fingerprint_guid))?;
+        }
+        if !hdr.version_guid.is_empty() {
+            self.write_header_variable_since(DxfVersion::AC1015, "$VERSIONGUID", |w| w.write_string(2, &hdr.version_guid))?;
+        }
+
+        // === R2007 rendering, navigation and modeling settings ===
+        if !hdr.current_material_handle.is_null() {
+            self.write_header_variable_since(DxfVersion::AC1021, "$CMATERIAL", |w| w.write_handle(347, hdr.current_material_handle))?;
+        }
+        self.write_header_variable_since(DxfVersion::AC1021, "$CAMERADISPLAY", |w| w.write_bool(290, hdr.camera_display))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LENSLENGTH", |w| w.write_double(40, hdr.lens_length))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$CAMERAHEIGHT", |w| w.write_double(40, hdr.camera_height))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$STEPSPERSEC", |w| w.write_double(40, hdr.steps_per_second))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$STEPSIZE", |w| w.write_double(40, hdr.step_size))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$3DDWFPREC", |w| w.write_double(40, hdr.dwf_precision_3d))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$PSOLWIDTH", |w| w.write_double(40, hdr.polysolid_width))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$PSOLHEIGHT", |w| w.write_double(40, hdr.polysolid_height))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LOFTANG1", |w| w.write_double(40, hdr.loft_angle1))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LOFTANG2", |w| w.write_double(40, hdr.loft_angle2))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LOFTMAG1", |w| w.write_double(40, hdr.loft_magnitude1))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LOFTMAG2", |w| w.write_double(40, hdr.loft_magnitude2))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LOFTPARAM", |w| w.write_i16(70, hdr.loft_param))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LOFTNORMALS", |w| w.write_i16(280, hdr.loft_normals))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LATITUDE", |w| w.write_double(40, hdr.latitude))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LONGITUDE", |w| w.write_double(40, hdr.longitude))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$NORTHDIRECTION", |w| w.write_double(40, hdr.north_direction))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$TIMEZONE", |w| w.write_i16(70, hdr.timezone as i16))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$LIGHTGLYPHDISPLAY", |w| w.write_i16(280, hdr.light_glyph_display))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$TILEMODELIGHTSYNCH", |w| w.write_i16(280, hdr.tile_model_light_sync))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$SOLIDHIST", |w| w.write_i16(280, hdr.solid_history))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$SHOWHIST", |w| w.write_i16(280, hdr.show_history))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DWFFRAME", |w| w.write_i16(280, hdr.dwf_frame))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$DGNFRAME", |w| w.write_i16(280, hdr.dgn_frame))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$REALWORLDSCALE", |w| w.write_bool(290, hdr.real_world_scale))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$INTERFERECOLOR", |w| w.write_i16(62, hdr.interference_color.approximate_index()))?;
+        if !hdr.interference_object_visual_style_handle.is_null() {
+            self.write_header_variable_since(DxfVersion::AC1021, "$INTERFEREOBJVS", |w| w.write_handle(345, hdr.interference_object_visual_style_handle))?;
+        }
+        if !hdr.interference_viewport_visual_style_handle.is_null() {
+            self.write_header_variable_since(DxfVersion::AC1021, "$INTERFEREVPVS", |w| w.write_handle(346, hdr.interference_viewport_visual_style_handle))?;
+        }
+        if !hdr.drag_visual_style_handle.is_null() {
+            self.write_header_variable_since(DxfVersion::AC1021, "$DRAGVS", |w| w.write_handle(349, hdr.drag_visual_style_handle))?;
+        }
+        self.write_header_variable_since(DxfVersion::AC1021, "$CSHADOW", |w| w.write_i16(280, hdr.current_shadow_mode))?;
+        self.write_header_variable_since(DxfVersion::AC1021, "$SHADOWPLANELOCATION", |w| w.write_double(40, hdr.shadow_plane_location))?;
 
         // === Time ===
         self.write_header_variable("$TDCREATE", |w| w.write_double(40, hdr.create_date_julian))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$TDUCREATE", |w| w.write_double(40, hdr.utc_create_date_julian))?;
         self.write_header_variable("$TDUPDATE", |w| w.write_double(40, hdr.update_date_julian))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$TDUUPDATE", |w| w.write_double(40, hdr.utc_update_date_julian))?;
         self.write_header_variable("$TDINDWG", |w| w.write_double(40, hdr.total_editing_time))?;
+        self.write_header_variable("$TDUSRTIMER", |w| w.write_double(40, hdr.user_elapsed_time))?;
+        self.write_header_variable("$USRTIMER", |w| w.write_i16(70, if hdr.user_timer { 1 } else { 0 }))?;
 
         // === UCS ===
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSBASE", |w| w.write_string(2, &hdr.ucs_base))?;
+        self.write_header_variable("$UCSNAME", |w| w.write_string(2, &hdr.model_space_ucs_name))?;
         self.write_header_variable("$UCSORG", |w| {
             let v = &hdr.model_space_ucs_origin;
             w.write_double(10, v.x)?; w.write_double(20, v.y)?; w.write_double(30, v.z)
@@ -217,6 +518,47 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             let v = &hdr.model_space_ucs_y_axis;
             w.write_double(10, v.x)?; w.write_double(20, v.y)?; w.write_double(30, v.z)
         })?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORTHOREF", |w| w.write_string(2, &hdr.ucs_ortho_ref_name))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORTHOVIEW", |w| w.write_i16(70, hdr.ucs_ortho_view))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORGTOP", |w| w.write_point3d(10, hdr.model_space_ucs_origin_top))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORGBOTTOM", |w| w.write_point3d(10, hdr.model_space_ucs_origin_bottom))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORGLEFT", |w| w.write_point3d(10, hdr.model_space_ucs_origin_left))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORGRIGHT", |w| w.write_point3d(10, hdr.model_space_ucs_origin_right))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORGFRONT", |w| w.write_point3d(10, hdr.model_space_ucs_origin_front))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$UCSORGBACK", |w| w.write_point3d(10, hdr.model_space_ucs_origin_back))?;
+
+        // === Paper space ===
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSBASE", |w| w.write_string(2, &hdr.paper_ucs_base))?;
+        self.write_header_variable("$PUCSNAME", |w| w.write_string(2, &hdr.paper_space_ucs_name))?;
+        self.write_header_variable("$PUCSORG", |w| w.write_point3d(10, hdr.paper_space_ucs_origin))?;
+        self.write_header_variable("$PUCSXDIR", |w| w.write_point3d(10, hdr.paper_space_ucs_x_axis))?;
+        self.write_header_variable("$PUCSYDIR", |w| w.write_point3d(10, hdr.paper_space_ucs_y_axis))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORTHOREF", |w| w.write_string(2, &hdr.paper_ucs_ortho_ref_name))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORTHOVIEW", |w| w.write_i16(70, hdr.paper_ucs_ortho_view))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORGTOP", |w| w.write_point3d(10, hdr.paper_space_ucs_origin_top))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORGBOTTOM", |w| w.write_point3d(10, hdr.paper_space_ucs_origin_bottom))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORGLEFT", |w| w.write_point3d(10, hdr.paper_space_ucs_origin_left))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORGRIGHT", |w| w.write_point3d(10, hdr.paper_space_ucs_origin_right))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORGFRONT", |w| w.write_point3d(10, hdr.paper_space_ucs_origin_front))?;
+        self.write_header_variable_since(DxfVersion::AC1015, "$PUCSORGBACK", |w| w.write_point3d(10, hdr.paper_space_ucs_origin_back))?;
+        self.write_header_variable("$PINSBASE", |w| w.write_point3d(10, hdr.paper_space_insertion_base))?;
+        self.write_header_variable("$PEXTMIN", |w| w.write_point3d(10, hdr.paper_space_extents_min))?;
+        self.write_header_variable("$PEXTMAX", |w| w.write_point3d(10, hdr.paper_space_extents_max))?;
+        self.write_header_variable("$PLIMMIN", |w| w.write_point2d(10, hdr.paper_space_limits_min))?;
+        self.write_header_variable("$PLIMMAX", |w| w.write_point2d(10, hdr.paper_space_limits_max))?;
+
+        // === Variables read but not modelled, as read ===
+        for (name, codes) in &hdr.unknown_variables {
+            // The R12 stream writer drops codes R12 does not know, which
+            // would leave the variable without a value
+            if version == DxfVersion::AC1009 && !codes.iter().all(|code| is_r12_code(code.code)) {
+                continue;
+            }
+            self.writer.write_string(9, name)?;
+            for code in codes {
+                self.write_raw_code(code)?;
+            }
+        }
 
         self.writer.write_section_end()?;
         Ok(())
@@ -228,7 +570,31 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         F: FnOnce(&mut W) -> Result<()>,
     {
         self.writer.write_string(9, name)?;
-        write_value(self.writer)
+        write_value(self.writer.inner)
+    }
+
+    /// Write a header variable introduced in `version`; skipped when
+    /// writing an older version
+    fn write_header_variable_since<F>(&mut self, version: DxfVersion, name: &str, write_value: F) -> Result<()>
+    where
+        F: FnOnce(&mut W) -> Result<()>,
+    {
+        if self.version < version {
+            return Ok(());
+        }
+        self.write_header_variable(name, write_value)
+    }
+
+    /// Write a header variable dropped after `version`; skipped when
+    /// writing a newer version
+    fn write_header_variable_until<F>(&mut self, version: DxfVersion, name: &str, write_value: F) -> Result<()>
+    where
+        F: FnOnce(&mut W) -> Result<()>,
+    {
+        if self.version > version {
+            return Ok(());
+        }
+        self.write_header_variable(name, write_value)
     }
 
     /// Write the CLASSES section
@@ -236,12 +602,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_section_start("CLASSES")?;
 
         for class in document.classes.iter() {
+            if introduced_in(&class.dxf_name) > self.version {
+                continue;
+            }
             self.writer.write_string(0, "CLASS")?;
             self.writer.write_string(1, &class.dxf_name)?;
             self.writer.write_string(2, &class.cpp_class_name)?;
             self.writer.write_string(3, &class.application_name)?;
             self.writer.write_i32(90, class.proxy_flags.0 as i32)?;
-            self.writer.write_i32(91, class.instance_count)?;
+            if self.version >= DxfVersion::AC1018 {
+                self.writer.write_i32(91, class.instance_count)?;
+            }
             self.writer.write_byte(280, if class.was_zombie { 1 } else { 0 })?;
             self.writer.write_byte(281, if class.is_an_entity { 1 } else { 0 })?;
         }
@@ -263,7 +634,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.write_ucs_table(document)?;
         self.write_appid_table(document)?;
         self.write_dimstyle_table(document)?;
-        self.write_block_record_table(document)?;
+        if self.version >= DxfVersion::AC1012 {
+            self.write_block_record_table(document)?;
+        }
 
         self.writer.write_section_end()?;
         Ok(())
@@ -284,8 +657,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_vport_entry(&mut self, vport: &VPort, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "VPORT")?;
         self.write_common_table_data(vport.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbViewportTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbViewportTableRecord")?;
         self.writer.write_string(2, vport.name())?;
         self.writer.write_i16(70, 0)?;
 
@@ -359,8 +732,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_ltype_entry(&mut self, ltype: &LineType, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "LTYPE")?;
         self.write_common_table_data(ltype.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbLinetypeTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbLinetypeTableRecord")?;
         self.writer.write_string(2, ltype.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_string(3, &ltype.description)?;
@@ -370,7 +743,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
         for element in &ltype.elements {
             self.writer.write_double(49, element.length)?;
-            self.writer.write_i16(74, 0)?;
+            if self.version >= DxfVersion::AC1012 {
+                self.writer.write_i16(74, 0)?;
+            }
         }
 
         Ok(())
@@ -391,8 +766,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_layer_entry(&mut self, layer: &Layer, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "LAYER")?;
         self.write_common_table_data(layer.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbLayerTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbLayerTableRecord")?;
         self.writer.write_string(2, layer.name())?;
 
         // Flags
@@ -410,23 +785,30 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             Color::Index(i) => i as i16,
             Color::ByLayer => 7,
             Color::ByBlock => 0,
-            Color::Rgb { .. } => 7,
+            Color::Rgb { .. } => layer.color.approximate_index(),
         };
         if !layer.is_off() {
             self.writer.write_i16(62, color_index)?;
         } else {
             self.writer.write_i16(62, -color_index)?;
         }
+        if let Some(true_color) = true_color_value(layer.color) {
+            if self.version >= DxfVersion::AC1018 {
+                self.writer.write_i32(420, true_color)?;
+            }
+        }
 
         // Linetype name
         self.writer.write_string(6, &layer.line_type)?;
 
-        // Lineweight
-        self.writer.write_i16(370, layer.line_weight.value())?;
+        if self.version >= DxfVersion::AC1015 {
+            // Plot flag (code 290 is Bool type - single byte in binary)
+            self.writer
+                .write_bool(290, layer.is_plottable)?;
 
-        // Plot flag (code 290 is Bool type - single byte in binary)
-        self.writer
-            .write_bool(290, layer.is_plottable)?;
+            // Lineweight
+            self.writer.write_i16(370, layer.line_weight.value())?;
+        }
 
         Ok(())
     }
@@ -446,8 +828,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_style_entry(&mut self, style: &TextStyle, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "STYLE")?;
         self.write_common_table_data(style.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbTextStyleTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbTextStyleTableRecord")?;
         self.writer.write_string(2, style.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_double(40, style.height)?;
@@ -478,8 +860,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_view_entry(&mut self, view: &View, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "VIEW")?;
         self.write_common_table_data(view.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbViewTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbViewTableRecord")?;
         self.writer.write_string(2, view.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_double(40, view.height)?;
@@ -515,8 +897,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_ucs_entry(&mut self, ucs: &Ucs, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "UCS")?;
         self.write_common_table_data(ucs.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbUCSTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbUCSTableRecord")?;
         self.writer.write_string(2, ucs.name())?;
         self.writer.write_i16(70, 0)?;
         self.writer.write_double(10, ucs.origin.x)?;
@@ -547,8 +929,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_appid_entry(&mut self, appid: &AppId, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "APPID")?;
         self.write_common_table_data(appid.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbRegAppTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbRegAppTableRecord")?;
         self.writer.write_string(2, appid.name())?;
         self.writer.write_i16(70, 0)?;
 
@@ -558,7 +940,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     /// Write DIMSTYLE table
     fn write_dimstyle_table(&mut self, document: &CadDocument) -> Result<()> {
         self.write_table_header("DIMSTYLE", document.dim_styles.len(), Handle::new(HANDLE_DIMSTYLE_TABLE))?;
-        self.writer.write_subclass("AcDbDimStyleTable")?;
+        self.write_subclass("AcDbDimStyleTable")?;
 
         for dimstyle in document.dim_styles.iter() {
             self.write_dimstyle_entry(dimstyle, Handle::new(HANDLE_DIMSTYLE_TABLE))?;
@@ -572,8 +954,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "DIMSTYLE")?;
         self.writer.write_handle(105, dimstyle.handle())?;
         self.writer.write_handle(330, owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbDimStyleTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbDimStyleTableRecord")?;
         self.writer.write_string(2, dimstyle.name())?;
         self.writer.write_i16(70, 0)?;
 
@@ -591,8 +973,10 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(46, dimstyle.dimdle)?;
         self.writer.write_double(47, dimstyle.dimtp)?;
         self.writer.write_double(48, dimstyle.dimtm)?;
-        if dimstyle.dimfxl != 1.0 { self.writer.write_double(49, dimstyle.dimfxl)?; }
-        if dimstyle.dimjogang != std::f64::consts::FRAC_PI_4 { self.writer.write_double(50, dimstyle.dimjogang)?; }
+        if self.version >= DxfVersion::AC1021 {
+            if dimstyle.dimfxl != 1.0 { self.writer.write_double(49, dimstyle.dimfxl)?; }
+            if dimstyle.dimjogang != std::f64::consts::FRAC_PI_4 { self.writer.write_double(50, dimstyle.dimjogang)?; }
+        }
 
         // Floats 140-148
         self.writer.write_double(140, dimstyle.dimtxt)?;
@@ -603,10 +987,14 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(145, dimstyle.dimtvp)?;
         self.writer.write_double(146, dimstyle.dimtfac)?;
         self.writer.write_double(147, dimstyle.dimgap)?;
-        if dimstyle.dimaltrnd != 0.0 { self.writer.write_double(148, dimstyle.dimaltrnd)?; }
+        if dimstyle.dimaltrnd != 0.0 && self.version >= DxfVersion::AC1015 {
+            self.writer.write_double(148, dimstyle.dimaltrnd)?;
+        }
 
         // Int16 flags (69-79)
-        if dimstyle.dimtfill != 0 { self.writer.write_i16(69, dimstyle.dimtfill)?; }
+        if dimstyle.dimtfill != 0 && self.version >= DxfVersion::AC1021 {
+            self.writer.write_i16(69, dimstyle.dimtfill)?;
+        }
         self.writer.write_i16(71, if dimstyle.dimtol { 1 } else { 0 })?;
         self.writer.write_i16(72, if dimstyle.dimlim { 1 } else { 0 })?;
         self.writer.write_i16(73, if dimstyle.dimtih { 1 } else { 0 })?;
@@ -615,10 +1003,14 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_i16(76, if dimstyle.dimse2 { 1 } else { 0 })?;
         self.writer.write_i16(77, dimstyle.dimtad)?;
         self.writer.write_i16(78, dimstyle.dimzin)?;
-        self.writer.write_i16(79, dimstyle.dimazin)?;
+        if self.version >= DxfVersion::AC1015 {
+            self.writer.write_i16(79, dimstyle.dimazin)?;
+        }
 
         // Int16 / Int32 (90, 170-179)
-        if dimstyle.dimarcsym != 0 { self.writer.write_i32(90, dimstyle.dimarcsym as i32)?; }
+        if dimstyle.dimarcsym != 0 && self.version >= DxfVersion::AC1021 {
+            self.writer.write_i32(90, dimstyle.dimarcsym as i32)?;
+        }
         self.writer.write_i16(170, if dimstyle.dimalt { 1 } else { 0 })?;
         self.writer.write_i16(171, dimstyle.dimaltd)?;
         self.writer.write_i16(172, if dimstyle.dimtofl { 1 } else { 0 })?;
@@ -628,7 +1020,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_i16(176, dimstyle.dimclrd)?;
         self.writer.write_i16(177, dimstyle.dimclre)?;
         self.writer.write_i16(178, dimstyle.dimclrt)?;
-        self.writer.write_i16(179, dimstyle.dimadec)?;
+        if self.version >= DxfVersion::AC1015 {
+            self.writer.write_i16(179, dimstyle.dimadec)?;
+        }
 
         // Int16 (270-290)
         self.writer.write_i16(271, dimstyle.dimdec)?;
@@ -636,10 +1030,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_i16(273, dimstyle.dimaltu)?;
         self.writer.write_i16(274, dimstyle.dimalttd)?;
         self.writer.write_i16(275, dimstyle.dimaunit)?;
-        self.writer.write_i16(276, dimstyle.dimfrac)?;
-        self.writer.write_i16(277, dimstyle.dimlunit)?;
-        self.writer.write_i16(278, dimstyle.dimdsep)?;
-        self.writer.write_i16(279, dimstyle.dimtmove)?;
+        if self.version >= DxfVersion::AC1015 {
+            self.writer.write_i16(276, dimstyle.dimfrac)?;
+            self.writer.write_i16(277, dimstyle.dimlunit)?;
+            self.writer.write_i16(278, dimstyle.dimdsep)?;
+            self.writer.write_i16(279, dimstyle.dimtmove)?;
+        }
         self.writer.write_i16(280, dimstyle.dimjust)?;
         self.writer.write_i16(281, if dimstyle.dimsd1 { 1 } else { 0 })?;
         self.writer.write_i16(282, if dimstyle.dimsd2 { 1 } else { 0 })?;
@@ -647,23 +1043,35 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_i16(284, dimstyle.dimtzin)?;
         self.writer.write_i16(285, dimstyle.dimaltz)?;
         self.writer.write_i16(286, dimstyle.dimalttz)?;
-        self.writer.write_i16(289, dimstyle.dimatfit)?;
-        if dimstyle.dimfxlon { self.writer.write_bool(290, true)?; }
-        if dimstyle.dimtxtdirection { self.writer.write_bool(295, true)?; }
+        if self.version >= DxfVersion::AC1015 {
+            self.writer.write_i16(289, dimstyle.dimatfit)?;
+        }
+        if dimstyle.dimfxlon && self.version >= DxfVersion::AC1021 {
+            self.writer.write_bool(290, true)?;
+        }
+        if dimstyle.dimtxtdirection && self.version >= DxfVersion::AC1024 {
+            self.writer.write_bool(295, true)?;
+        }
 
         // Handle references
         if !dimstyle.dimtxsty_handle.is_null() { self.writer.write_handle(340, dimstyle.dimtxsty_handle)?; }
-        if !dimstyle.dimldrblk.is_null() { self.writer.write_handle(341, dimstyle.dimldrblk)?; }
-        if !dimstyle.dimblk.is_null() { self.writer.write_handle(342, dimstyle.dimblk)?; }
-        if !dimstyle.dimblk1.is_null() { self.writer.write_handle(343, dimstyle.dimblk1)?; }
-        if !dimstyle.dimblk2.is_null() { self.writer.write_handle(344, dimstyle.dimblk2)?; }
-        if !dimstyle.dimltex_handle.is_null() { self.writer.write_handle(345, dimstyle.dimltex_handle)?; }
-        if !dimstyle.dimltex1_handle.is_null() { self.writer.write_handle(346, dimstyle.dimltex1_handle)?; }
-        if !dimstyle.dimltex2_handle.is_null() { self.writer.write_handle(347, dimstyle.dimltex2_handle)?; }
+        if self.version >= DxfVersion::AC1015 {
+            if !dimstyle.dimldrblk.is_null() { self.writer.write_handle(341, dimstyle.dimldrblk)?; }
+            if !dimstyle.dimblk.is_null() { self.writer.write_handle(342, dimstyle.dimblk)?; }
+            if !dimstyle.dimblk1.is_null() { self.writer.write_handle(343, dimstyle.dimblk1)?; }
+            if !dimstyle.dimblk2.is_null() { self.writer.write_handle(344, dimstyle.dimblk2)?; }
+        }
+        if self.version >= DxfVersion::AC1021 {
+            if !dimstyle.dimltex_handle.is_null() { self.writer.write_handle(345, dimstyle.dimltex_handle)?; }
+            if !dimstyle.dimltex1_handle.is_null() { self.writer.write_handle(346, dimstyle.dimltex1_handle)?; }
+            if !dimstyle.dimltex2_handle.is_null() { self.writer.write_handle(347, dimstyle.dimltex2_handle)?; }
+        }
 
         // Line weights
-        self.writer.write_i16(371, dimstyle.dimlwd)?;
-        self.writer.write_i16(372, dimstyle.dimlwe)?;
+        if self.version >= DxfVersion::AC1015 {
+            self.writer.write_i16(371, dimstyle.dimlwd)?;
+            self.writer.write_i16(372, dimstyle.dimlwe)?;
+        }
 
         Ok(())
     }
@@ -683,16 +1091,18 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     fn write_block_record_entry(&mut self, block_record: &BlockRecord, owner: Handle) -> Result<()> {
         self.writer.write_string(0, "BLOCK_RECORD")?;
         self.write_common_table_data(block_record.handle(), owner)?;
-        self.writer.write_subclass("AcDbSymbolTableRecord")?;
-        self.writer.write_subclass("AcDbBlockTableRecord")?;
+        self.write_subclass("AcDbSymbolTableRecord")?;
+        self.write_subclass("AcDbBlockTableRecord")?;
         self.writer.write_string(2, block_record.name())?;
-        self.writer.write_i16(70, block_record.units)?;
-        self.writer
-            .write_byte(280, if block_record.explodable { 1 } else { 0 })?;
-        self.writer.write_i16(
-            281,
-            if block_record.scale_uniformly { 1 } else { 0 },
-        )?;
+        if self.version >= DxfVersion::AC1015 {
+            self.writer.write_i16(70, block_record.units)?;
+            self.writer
+                .write_byte(280, if block_record.explodable { 1 } else { 0 })?;
+            self.writer.write_i16(
+                281,
+                if block_record.scale_uniformly { 1 } else { 0 },
+            )?;
+        }
 
         Ok(())
     }
@@ -703,7 +1113,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(2, name)?;
         self.writer.write_handle(5, table_handle)?;
         self.writer.write_handle(330, Handle::new(0))?; // Tables owned by document root (handle 0)
-        self.writer.write_subclass("AcDbSymbolTable")?;
+        self.write_subclass("AcDbSymbolTable")?;
         self.writer.write_i16(70, count as i16)?;
         Ok(())
     }
@@ -725,6 +1135,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_section_start("BLOCKS")?;
 
         for block_record in document.block_records.iter() {
+            // R12 keeps model and paper space entities in ENTITIES only
+            if self.version < DxfVersion::AC1012
+                && (block_record.is_model_space() || block_record.is_paper_space())
+            {
+                continue;
+            }
             self.write_block_definition(block_record)?;
         }
 
@@ -747,13 +1163,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "BLOCK")?;
         self.writer.write_handle(5, block_record.block_entity_handle)?;
         self.writer.write_handle(330, owner)?;
-        self.writer.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbEntity")?;
         // Paper space flag (group code 67) - 1 for paper space
         if block_record.is_paper_space() {
             self.writer.write_i16(67, 1)?;
         }
         self.writer.write_string(8, "0")?;
-        self.writer.write_subclass("AcDbBlockBegin")?;
+        self.write_subclass("AcDbBlockBegin")?;
         self.writer.write_string(2, block_record.name())?;
         self.writer.write_i16(70, flags)?;
         self.writer.write_double(10, 0.0)?;
@@ -774,13 +1190,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "ENDBLK")?;
         self.writer.write_handle(5, block_record.block_end_handle)?;
         self.writer.write_handle(330, owner)?;
-        self.writer.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbEntity")?;
         // Paper space flag for ENDBLK too
         if block_record.is_paper_space() {
             self.writer.write_i16(67, 1)?;
         }
         self.writer.write_string(8, "0")?;
-        self.writer.write_subclass("AcDbBlockEnd")?;
+        self.write_subclass("AcDbBlockEnd")?;
 
         Ok(())
     }
@@ -810,7 +1226,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     /// Write an entity with explicit owner
-    fn write_entity_with_owner(&mut self, entity: &EntityType, owner: Handle) -> Result<()> {
+    pub(crate) fn write_entity_with_owner(&mut self, entity: &EntityType, owner: Handle) -> Result<()> {
+        self.write_entity_records(entity, owner)?;
+        self.write_pending_codes()
+    }
+
+    fn write_entity_records(&mut self, entity: &EntityType, owner: Handle) -> Result<()> {
         match entity {
             EntityType::Point(e) => self.write_point(e, owner),
             EntityType::Line(e) => self.write_line(e, owner),
@@ -853,12 +1274,168 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             EntityType::Seqend(e) => self.write_seqend(e, owner),
             EntityType::Ole2Frame(e) => self.write_ole2frame(e, owner),
             EntityType::PolygonMesh(e) => self.write_polygon_mesh(e, owner),
-            EntityType::Unknown(_) => Ok(()), // Unknown entities are never written back
+            EntityType::Unknown(e) => self.write_unknown_entity(e, owner),
+        }
+    }
+
+    /// Start an entity record, after the unknown codes and extended data of
+    /// the previous one
+    fn write_entity_type(&mut self, entity_type: &str) -> Result<()> {
+        self.write_pending_codes()?;
+        self.writer.write_entity_type(entity_type)
+    }
+
+    /// Write a subclass marker. The unknown codes read after the previous
+    /// marker go first, at the end of their subclass.
+    fn write_subclass(&mut self, marker: &str) -> Result<()> {
+        if let Some(pending) = self.pending.as_mut() {
+            let previous = pending.subclass.replace(marker.to_string());
+            let (ended, rest) = std::mem::take(&mut pending.groups)
+                .into_iter()
+                .partition::<Vec<_>, _>(|group| group.subclass == previous);
+            pending.groups = rest;
+            let from = pending.subclass_start;
+            for group in &ended {
+                self.write_kept_codes(&group.codes, from)?;
+            }
+            if let Some(pending) = self.pending.as_mut() {
+                pending.subclass_start = self.writer.recorded().len();
+            }
+        }
+        self.writer.write_subclass(marker)
+    }
+
+    /// Write what is left of the unknown codes of the entity just written,
+    /// then its extended data. Codes of a subclass the entity is no longer
+    /// written with are dropped: the entity was converted.
+    fn write_pending_codes(&mut self) -> Result<()> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+        if pending.verbatim {
+            self.writer.codes = None;
+        }
+        for group in pending.groups.iter().filter(|group| group.subclass == pending.subclass) {
+            self.write_kept_codes(&group.codes, pending.subclass_start)?;
         }
+        self.write_kept_codes(&pending.trailing, 0)?;
+        self.writer.codes = None;
+        self.write_xdata(&pending.extended_data)
+    }
+
+    /// Write group codes kept as read, leaving out those the entity writer
+    /// wrote itself since code number `from` of the entity. R12 has no
+    /// application groups, so those are left out there.
+    fn write_kept_codes(&mut self, codes: &[RawGroupCode], from: usize) -> Result<()> {
+        let written = self.writer.recorded().get(from..).unwrap_or_default().to_vec();
+        let mut in_group = false;
+        for code in codes {
+            if code.code == 102 {
+                in_group = code.value.starts_with('{');
+                if self.version != DxfVersion::AC1009 {
+                    self.write_raw_code(code)?;
+                }
+                continue;
+            }
+            if in_group {
+                if self.version != DxfVersion::AC1009 {
+                    self.write_raw_code(code)?;
+                }
+            } else if !written.contains(&code.code) {
+                self.write_raw_code(code)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a group code kept as read, converting the text to the type of
+    /// the code; values that do not parse are written as text
+    fn write_raw_code(&mut self, code: &RawGroupCode) -> Result<()> {
+        let value = code.value.trim();
+        match GroupCodeValueType::from_raw_code(code.code) {
+            GroupCodeValueType::Double | GroupCodeValueType::Point3D => {
+                if let Ok(v) = value.parse() {
+                    return self.writer.write_double(code.code, v);
+                }
+            }
+            GroupCodeValueType::Int16 | GroupCodeValueType::Byte => {
+                if let Ok(v) = value.parse() {
+                    return self.writer.write_i16(code.code, v);
+                }
+            }
+            GroupCodeValueType::Int32 => {
+                if let Ok(v) = value.parse() {
+                    return self.writer.write_i32(code.code, v);
+                }
+            }
+            GroupCodeValueType::Int64 => {
+                if let Ok(v) = value.parse() {
+                    return self.writer.write_i64(code.code, v);
+                }
+            }
+            GroupCodeValueType::Bool => {
+                if let Ok(v) = value.parse::<i32>() {
+                    return self.writer.write_bool(code.code, v != 0);
+                }
+            }
+            GroupCodeValueType::Handle => {
+                if let Ok(v) = u64::from_str_radix(value, 16) {
+                    return self.writer.write_handle(code.code, Handle::new(v));
+                }
+            }
+            GroupCodeValueType::BinaryData if value.len().is_multiple_of(2) => {
+                let data: Option<Vec<u8>> = (0..value.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+                    .collect();
+                if let Some(data) = data {
+                    return self.writer.write_binary(code.code, &data);
+                }
+            }
+            _ => {}
+        }
+        self.writer.write_string(code.code, &code.value)
     }
 
     /// Write common entity data with owner
     fn write_common_entity_data(&mut self, common: &EntityCommon, owner: Handle) -> Result<()> {
+        self.pending = None;
+        self.writer.codes = None;
+        if !common.unknown_codes.is_empty() || !common.extended_data.is_empty() {
+            let mut groups = Vec::new();
+            let mut trailing = Vec::new();
+            for group in common.unknown_codes.groups() {
+                if group.subclass.is_some() {
+                    groups.push(group.clone());
+                    continue;
+                }
+                // Application groups stay before the first marker
+                let mut application_group: Option<UnknownCodeGroup> = None;
+                for code in &group.codes {
+                    if code.code == 102 && code.value.starts_with('{') {
+                        groups.extend(application_group.take());
+                        application_group = Some(UnknownCodeGroup { subclass: None, codes: Vec::new() });
+                    }
+                    match application_group.as_mut() {
+                        Some(application_group) => application_group.codes.push(code.clone()),
+                        None => trailing.push(code.clone()),
+                    }
+                    if code.code == 102 && code.value.trim() == "}" {
+                        groups.extend(application_group.take());
+                    }
+                }
+                groups.extend(application_group);
+            }
+            self.pending = Some(PendingCodes {
+                subclass: None,
+                subclass_start: 0,
+                groups,
+                trailing,
+                verbatim: false,
+                extended_data: common.extended_data.clone(),
+            });
+            self.writer.codes = Some(Vec::new());
+        }
         self.writer.write_handle(5, common.handle)?;
         self.writer.write_handle(330, owner)?;
 
@@ -880,19 +1457,24 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_string(102, "}")?;
         }
 
-        self.writer.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbEntity")?;
         self.writer.write_string(8, &common.layer)?;
 
         // Write color only if not ByLayer (default)
         if common.color != Color::ByLayer {
-            self.writer.write_color(62, common.color)?;
+            self.write_entity_color(common.color)?;
         }
 
         // Write lineweight if not default
-        if common.line_weight != crate::types::LineWeight::ByLayer {
+        if common.line_weight != crate::types::LineWeight::ByLayer && self.version >= DxfVersion::AC1015 {
             self.writer.write_i16(370, common.line_weight.value())?;
         }
 
+        // Write transparency if not opaque
+        if !common.transparency.is_opaque() && self.version >= DxfVersion::AC1024 {
+            self.writer.write_i32(440, transparency_value(common.transparency))?;
+        }
+
         // Write visibility
         if common.invisible {
             self.writer.write_i16(60, 1)?;
@@ -901,11 +1483,26 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
+    /// Write an entity color: the color index (62) and, from AC1018, the
+    /// true color (420)
+    fn write_entity_color(&mut self, color: Color) -> Result<()> {
+        match true_color_value(color) {
+            Some(true_color) => {
+                self.writer.write_i16(62, color.approximate_index())?;
+                if self.version >= DxfVersion::AC1018 {
+                    self.writer.write_i32(420, true_color)?;
+                }
+                Ok(())
+            }
+            None => self.writer.write_color(62, color),
+        }
+    }
+
     /// Write POINT entity
     fn write_point(&mut self, point: &Point, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("POINT")?;
+        self.write_entity_type("POINT")?;
         self.write_common_entity_data(&point.common, owner)?;
-        self.writer.write_subclass("AcDbPoint")?;
+        self.write_subclass("AcDbPoint")?;
         self.writer.write_point3d(10, point.location)?;
         if point.thickness != 0.0 {
             self.writer.write_double(39, point.thickness)?;
@@ -915,9 +1512,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write LINE entity
     fn write_line(&mut self, line: &Line, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("LINE")?;
+        self.write_entity_type("LINE")?;
         self.write_common_entity_data(&line.common, owner)?;
-        self.writer.write_subclass("AcDbLine")?;
+        self.write_subclass("AcDbLine")?;
         self.writer.write_point3d(10, line.start)?;
         self.writer.write_point3d(11, line.end)?;
         if line.thickness != 0.0 {
@@ -928,9 +1525,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write CIRCLE entity
     fn write_circle(&mut self, circle: &Circle, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("CIRCLE")?;
+        self.write_entity_type("CIRCLE")?;
         self.write_common_entity_data(&circle.common, owner)?;
-        self.writer.write_subclass("AcDbCircle")?;
+        self.write_subclass("AcDbCircle")?;
         self.writer.write_point3d(10, circle.center)?;
         self.writer.write_double(40, circle.radius)?;
         if circle.thickness != 0.0 {
@@ -941,15 +1538,15 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write ARC entity
     fn write_arc(&mut self, arc: &Arc, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("ARC")?;
+        self.write_entity_type("ARC")?;
         self.write_common_entity_data(&arc.common, owner)?;
-        self.writer.write_subclass("AcDbCircle")?;
+        self.write_subclass("AcDbCircle")?;
         self.writer.write_point3d(10, arc.center)?;
         self.writer.write_double(40, arc.radius)?;
         if arc.thickness != 0.0 {
             self.writer.write_double(39, arc.thickness)?;
         }
-        self.writer.write_subclass("AcDbArc")?;
+        self.write_subclass("AcDbArc")?;
         self.writer.write_double(50, arc.start_angle.to_degrees())?;
         self.writer.write_double(51, arc.end_angle.to_degrees())?;
         Ok(())
@@ -957,9 +1554,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write ELLIPSE entity
     fn write_ellipse(&mut self, ellipse: &Ellipse, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("ELLIPSE")?;
+        self.write_entity_type("ELLIPSE")?;
         self.write_common_entity_data(&ellipse.common, owner)?;
-        self.writer.write_subclass("AcDbEllipse")?;
+        self.write_subclass("AcDbEllipse")?;
         self.writer.write_point3d(10, ellipse.center)?;
         self.writer.write_point3d(11, ellipse.major_axis)?;
         self.writer.write_double(40, ellipse.minor_axis_ratio)?;
@@ -970,9 +1567,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write POLYLINE entity (3D polyline)
     fn write_polyline(&mut self, polyline: &Polyline, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("POLYLINE")?;
+        self.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&polyline.common, owner)?;
-        self.writer.write_subclass("AcDb3dPolyline")?;
+        self.write_subclass("AcDb3dPolyline")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -994,28 +1591,28 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Write vertices (allocate unique handles to avoid collisions)
         for vertex in polyline.vertices.iter() {
             let vertex_handle = self.allocate_handle();
-            self.writer.write_entity_type("VERTEX")?;
+            self.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, polyline_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &polyline.common.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if polyline.common.color != Color::ByLayer {
-                self.writer.write_color(62, polyline.common.color)?;
+                self.write_entity_color(polyline.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDb3dPolylineVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDb3dPolylineVertex")?;
             self.writer.write_point3d(10, vertex.location)?;
             self.writer.write_i16(70, 32)?; // 3D polyline vertex
         }
 
         // Write SEQEND
         let seqend_handle = self.allocate_handle();
-        self.writer.write_entity_type("SEQEND")?;
+        self.write_entity_type("SEQEND")?;
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, polyline_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbSequenceEnd")?;
         self.writer.write_string(8, &polyline.common.layer)?;
 
         Ok(())
@@ -1023,9 +1620,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     
     /// Write POLYLINE entity (2D polyline)
     fn write_polyline2d(&mut self, polyline: &Polyline2D, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("POLYLINE")?;
+        self.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&polyline.common, owner)?;
-        self.writer.write_subclass("AcDb2dPolyline")?;
+        self.write_subclass("AcDb2dPolyline")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -1046,6 +1643,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         if polyline.end_width != 0.0 {
             self.writer.write_double(41, polyline.end_width)?;
         }
+        if polyline.normal != Vector3::UNIT_Z {
+            self.writer.write_point3d(210, polyline.normal)?;
+        }
 
         // VERTEX and SEQEND are owned by the polyline entity
         let polyline_handle = polyline.common.handle;
@@ -1053,17 +1653,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Write vertices (allocate unique handles to avoid collisions)
         for vertex in polyline.vertices.iter() {
             let vertex_handle = self.allocate_handle();
-            self.writer.write_entity_type("VERTEX")?;
+            self.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, polyline_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &polyline.common.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if polyline.common.color != Color::ByLayer {
-                self.writer.write_color(62, polyline.common.color)?;
+                self.write_entity_color(polyline.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDb2dVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDb2dVertex")?;
             self.writer.write_point3d(10, vertex.location)?;
             if vertex.start_width != 0.0 {
                 self.writer.write_double(40, vertex.start_width)?;
@@ -1079,11 +1679,11 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
         // Write SEQEND
         let seqend_handle = self.allocate_handle();
-        self.writer.write_entity_type("SEQEND")?;
+        self.write_entity_type("SEQEND")?;
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, polyline_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbSequenceEnd")?;
         self.writer.write_string(8, &polyline.common.layer)?;
 
         Ok(())
@@ -1091,9 +1691,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write LWPOLYLINE entity
     fn write_lwpolyline(&mut self, lwpoly: &LwPolyline, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("LWPOLYLINE")?;
+        self.write_entity_type("LWPOLYLINE")?;
         self.write_common_entity_data(&lwpoly.common, owner)?;
-        self.writer.write_subclass("AcDbPolyline")?;
+        self.write_subclass("AcDbPolyline")?;
         self.writer.write_i32(90, lwpoly.vertices.len() as i32)?;
 
         let mut flags: i16 = 0;
@@ -1121,9 +1721,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write TEXT entity
     fn write_text(&mut self, text: &Text, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("TEXT")?;
+        self.write_entity_type("TEXT")?;
         self.write_common_entity_data(&text.common, owner)?;
-        self.writer.write_subclass("AcDbText")?;
+        self.write_subclass("AcDbText")?;
         self.writer.write_point3d(10, text.insertion_point)?;
         self.writer.write_double(40, text.height)?;
         self.writer.write_string(1, &text.value)?;
@@ -1141,21 +1741,26 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         if let Some(align_pt) = text.alignment_point {
             self.writer.write_point3d(11, align_pt)?;
         }
-        self.writer.write_subclass("AcDbText")?;
+        self.write_subclass("AcDbText")?;
         self.writer.write_i16(73, text.vertical_alignment as i16)?;
         Ok(())
     }
 
     /// Write MTEXT entity
     fn write_mtext(&mut self, mtext: &MText, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("MTEXT")?;
+        self.write_entity_type("MTEXT")?;
         self.write_common_entity_data(&mtext.common, owner)?;
-        self.writer.write_subclass("AcDbMText")?;
+        self.write_subclass("AcDbMText")?;
         self.writer.write_point3d(10, mtext.insertion_point)?;
         self.writer.write_double(40, mtext.height)?;
         self.writer.write_double(41, mtext.rectangle_width)?;
-        self.writer.write_i16(71, mtext.attachment_point as i16)?;
-        self.writer.write_i16(72, mtext.drawing_direction as i16)?;
+        // Attachment points are numbered from 1
+        self.writer.write_i16(71, mtext.attachment_point as i16 + 1)?;
+        self.writer.write_i16(72, match mtext.drawing_direction {
+            DrawingDirection::LeftToRight => 1,
+            DrawingDirection::TopToBottom => 3,
+            DrawingDirection::ByStyle => 5,
+        })?;
 
         // Write text value (may need to be split for long text)
         let text = &mtext.value;
@@ -1182,14 +1787,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write SPLINE entity
     fn write_spline(&mut self, spline: &Spline, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("SPLINE")?;
+        self.write_entity_type("SPLINE")?;
         self.write_common_entity_data(&spline.common, owner)?;
-        self.writer.write_subclass("AcDbSpline")?;
+        self.write_subclass("AcDbSpline")?;
 
         // Normal vector
-        self.writer.write_double(210, 0.0)?;
-        self.writer.write_double(220, 0.0)?;
-        self.writer.write_double(230, 1.0)?;
+        self.writer.write_point3d(210, spline.normal)?;
 
         // Flags
         let mut flags: i16 = 0;
@@ -1220,9 +1823,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             self.writer.write_double(40, *knot)?;
         }
 
-        // Control points
-        for point in &spline.control_points {
+        // Control points, with their weights if any
+        for (i, point) in spline.control_points.iter().enumerate() {
             self.writer.write_point3d(10, *point)?;
+            if let Some(weight) = spline.weights.get(i) {
+                self.writer.write_double(41, *weight)?;
+            }
         }
 
         // Fit points
@@ -1247,30 +1853,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     fn write_dimension_base(&mut self, base: &DimensionBase, type_flags: i16, owner: Handle) -> Result<()> {
-        self.writer.write_handle(5, base.common.handle)?;
-        self.writer.write_handle(330, owner)?;
-
-        // Write xdictionary group
-        if let Some(xdict) = base.common.xdictionary_handle {
-            if xdict != Handle::NULL {
-                self.writer.write_string(102, "{ACAD_XDICTIONARY")?;
-                self.writer.write_handle(360, xdict)?;
-                self.writer.write_string(102, "}")?;
-            }
-        }
-
-        // Write reactor group
-        if !base.common.reactors.is_empty() {
-            self.writer.write_string(102, "{ACAD_REACTORS")?;
-            for reactor in &base.common.reactors {
-                self.writer.write_handle(330, *reactor)?;
-            }
-            self.writer.write_string(102, "}")?;
-        }
-
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_string(8, &base.common.layer)?;
-        self.writer.write_subclass("AcDbDimension")?;
+        self.write_common_entity_data(&base.common, owner)?;
+        self.write_subclass("AcDbDimension")?;
         self.writer.write_string(2, &base.block_name)?;
         self.writer.write_point3d(10, base.definition_point)?;
         self.writer.write_point3d(11, base.text_middle_point)?;
@@ -1284,47 +1868,47 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     fn write_dimension_aligned(&mut self, dim: &DimensionAligned, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("DIMENSION")?;
+        self.write_entity_type("DIMENSION")?;
         self.write_dimension_base(&dim.base, 1, owner)?; // Aligned = 1
-        self.writer.write_subclass("AcDbAlignedDimension")?;
+        self.write_subclass("AcDbAlignedDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
         Ok(())
     }
 
     fn write_dimension_linear(&mut self, dim: &DimensionLinear, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("DIMENSION")?;
+        self.write_entity_type("DIMENSION")?;
         self.write_dimension_base(&dim.base, 0, owner)?; // Linear = 0
-        self.writer.write_subclass("AcDbAlignedDimension")?;
+        self.write_subclass("AcDbAlignedDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
         self.writer.write_double(50, dim.rotation)?;
-        self.writer.write_subclass("AcDbRotatedDimension")?;
+        self.write_subclass("AcDbRotatedDimension")?;
         Ok(())
     }
 
     fn write_dimension_radius(&mut self, dim: &DimensionRadius, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("DIMENSION")?;
+        self.write_entity_type("DIMENSION")?;
         self.write_dimension_base(&dim.base, 4, owner)?; // Radius = 4
-        self.writer.write_subclass("AcDbRadialDimension")?;
+        self.write_subclass("AcDbRadialDimension")?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
         self.writer.write_double(40, dim.leader_length)?;
         Ok(())
     }
 
     fn write_dimension_diameter(&mut self, dim: &DimensionDiameter, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("DIMENSION")?;
+        self.write_entity_type("DIMENSION")?;
         self.write_dimension_base(&dim.base, 3, owner)?; // Diameter = 3
-        self.writer.write_subclass("AcDbDiametricDimension")?;
+        self.write_subclass("AcDbDiametricDimension")?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
         self.writer.write_double(40, dim.leader_length)?;
         Ok(())
     }
 
     fn write_dimension_angular_2line(&mut self, dim: &DimensionAngular2Ln, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("DIMENSION")?;
+        self.write_entity_type("DIMENSION")?;
         self.write_dimension_base(&dim.base, 2, owner)?; // Angular = 2
-        self.writer.write_subclass("AcDb2LineAngularDimension")?;
+        self.write_subclass("AcDb2LineAngularDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
@@ -1333,9 +1917,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     fn write_dimension_angular_3point(&mut self, dim: &DimensionAngular3Pt, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("DIMENSION")?;
+        self.write_entity_type("DIMENSION")?;
         self.write_dimension_base(&dim.base, 5, owner)?; // 3-point angular = 5
-        self.writer.write_subclass("AcDb3PointAngularDimension")?;
+        self.write_subclass("AcDb3PointAngularDimension")?;
         self.writer.write_point3d(13, dim.first_point)?;
         self.writer.write_point3d(14, dim.second_point)?;
         self.writer.write_point3d(15, dim.angle_vertex)?;
@@ -1343,10 +1927,10 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     fn write_dimension_ordinate(&mut self, dim: &DimensionOrdinate, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("DIMENSION")?;
+        self.write_entity_type("DIMENSION")?;
         let type_flags = if dim.is_ordinate_type_x { 64 } else { 128 }; // Ordinate with X/Y flag
         self.write_dimension_base(&dim.base, 6 | type_flags, owner)?;
-        self.writer.write_subclass("AcDbOrdinateDimension")?;
+        self.write_subclass("AcDbOrdinateDimension")?;
         self.writer.write_point3d(13, dim.feature_location)?;
         self.writer.write_point3d(14, dim.leader_endpoint)?;
         Ok(())
@@ -1354,9 +1938,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write HATCH entity
     fn write_hatch(&mut self, hatch: &Hatch, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("HATCH")?;
+        self.write_entity_type("HATCH")?;
         self.write_common_entity_data(&hatch.common, owner)?;
-        self.writer.write_subclass("AcDbHatch")?;
+        self.write_subclass("AcDbHatch")?;
 
         // Elevation point
         self.writer.write_double(10, 0.0)?;
@@ -1466,8 +2050,8 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
                 self.writer.write_double(11, ellipse.major_axis_endpoint.x)?;
                 self.writer.write_double(21, ellipse.major_axis_endpoint.y)?;
                 self.writer.write_double(40, ellipse.minor_axis_ratio)?;
-                self.writer.write_double(50, ellipse.start_angle)?;
-                self.writer.write_double(51, ellipse.end_angle)?;
+                self.writer.write_double(50, ellipse.start_angle.to_degrees())?;
+                self.writer.write_double(51, ellipse.end_angle.to_degrees())?;
                 self.writer
                     .write_i16(73, if ellipse.counter_clockwise { 1 } else { 0 })?;
             }
@@ -1487,6 +2071,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
                 for point in &spline.control_points {
                     self.writer.write_double(10, point.x)?;
                     self.writer.write_double(20, point.y)?;
+                    if spline.rational {
+                        self.writer.write_double(42, point.z)?; // z stores weight
+                    }
                 }
             }
             BoundaryEdge::Polyline(poly) => {
@@ -1509,9 +2096,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write SOLID entity
     fn write_solid(&mut self, solid: &Solid, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("SOLID")?;
+        self.write_entity_type("SOLID")?;
         self.write_common_entity_data(&solid.common, owner)?;
-        self.writer.write_subclass("AcDbTrace")?;
+        self.write_subclass("AcDbTrace")?;
         self.writer.write_point3d(10, solid.first_corner)?;
         self.writer.write_point3d(11, solid.second_corner)?;
         self.writer.write_point3d(12, solid.third_corner)?;
@@ -1524,9 +2111,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write 3DFACE entity
     fn write_face3d(&mut self, face: &Face3D, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("3DFACE")?;
+        self.write_entity_type("3DFACE")?;
         self.write_common_entity_data(&face.common, owner)?;
-        self.writer.write_subclass("AcDbFace")?;
+        self.write_subclass("AcDbFace")?;
         self.writer.write_point3d(10, face.first_corner)?;
         self.writer.write_point3d(11, face.second_corner)?;
         self.writer.write_point3d(12, face.third_corner)?;
@@ -1540,9 +2127,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write INSERT entity
     fn write_insert(&mut self, insert: &Insert, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("INSERT")?;
+        self.write_entity_type("INSERT")?;
         self.write_common_entity_data(&insert.common, owner)?;
-        self.writer.write_subclass("AcDbBlockReference")?;
+        self.write_subclass("AcDbBlockReference")?;
         self.writer.write_string(2, &insert.block_name)?;
         self.writer.write_point3d(10, insert.insert_point)?;
         if insert.x_scale != 1.0 {
@@ -1574,9 +2161,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write BLOCK entity
     fn write_block_entity(&mut self, block: &Block, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("BLOCK")?;
+        self.write_entity_type("BLOCK")?;
         self.write_common_entity_data(&block.common, owner)?;
-        self.writer.write_subclass("AcDbBlockBegin")?;
+        self.write_subclass("AcDbBlockBegin")?;
         self.writer.write_string(2, &block.name)?;
         self.writer.write_i16(70, 0)?; // Block flags
         self.writer.write_point3d(10, block.base_point)?;
@@ -1592,17 +2179,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write ENDBLK entity
     fn write_block_end(&mut self, block_end: &BlockEnd, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("ENDBLK")?;
+        self.write_entity_type("ENDBLK")?;
         self.write_common_entity_data(&block_end.common, owner)?;
-        self.writer.write_subclass("AcDbBlockEnd")?;
+        self.write_subclass("AcDbBlockEnd")?;
         Ok(())
     }
 
     /// Write RAY entity
     fn write_ray(&mut self, ray: &Ray, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("RAY")?;
+        self.write_entity_type("RAY")?;
         self.write_common_entity_data(&ray.common, owner)?;
-        self.writer.write_subclass("AcDbRay")?;
+        self.write_subclass("AcDbRay")?;
         self.writer.write_point3d(10, ray.base_point)?;
         self.writer.write_point3d(11, ray.direction)?;
         Ok(())
@@ -1610,9 +2197,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write XLINE entity
     fn write_xline(&mut self, xline: &XLine, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("XLINE")?;
+        self.write_entity_type("XLINE")?;
         self.write_common_entity_data(&xline.common, owner)?;
-        self.writer.write_subclass("AcDbXline")?;
+        self.write_subclass("AcDbXline")?;
         self.writer.write_point3d(10, xline.base_point)?;
         self.writer.write_point3d(11, xline.direction)?;
         Ok(())
@@ -1620,9 +2207,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write POLYLINE (3D) entity
     fn write_polyline3d(&mut self, polyline: &Polyline3D, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("POLYLINE")?;
+        self.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&polyline.common, owner)?;
-        self.writer.write_subclass("AcDb3dPolyline")?;
+        self.write_subclass("AcDb3dPolyline")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -1643,28 +2230,28 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             } else {
                 vertex.handle
             };
-            self.writer.write_entity_type("VERTEX")?;
+            self.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, polyline_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &vertex.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if polyline.common.color != Color::ByLayer {
-                self.writer.write_color(62, polyline.common.color)?;
+                self.write_entity_color(polyline.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDb3dPolylineVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDb3dPolylineVertex")?;
             self.writer.write_point3d(10, vertex.position)?;
             self.writer.write_i16(70, vertex.flags as i16)?;
         }
         
         // SEQEND
-        self.writer.write_entity_type("SEQEND")?;
+        self.write_entity_type("SEQEND")?;
         let seqend_handle = self.allocate_handle();
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, polyline_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbSequenceEnd")?;
         self.writer.write_string(8, &polyline.common.layer)?;
         
         Ok(())
@@ -1672,9 +2259,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write VIEWPORT entity
     fn write_viewport(&mut self, viewport: &Viewport, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("VIEWPORT")?;
+        self.write_entity_type("VIEWPORT")?;
         self.write_common_entity_data(&viewport.common, owner)?;
-        self.writer.write_subclass("AcDbViewport")?;
+        self.write_subclass("AcDbViewport")?;
         
         // Center point
         self.writer.write_point3d(10, viewport.center)?;
@@ -1683,8 +2270,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_double(40, viewport.width)?;
         self.writer.write_double(41, viewport.height)?;
         
-        // Viewport ID
-        self.writer.write_i16(68, viewport.id)?;
+        // On/off status and viewport ID
+        self.writer.write_i16(68, if viewport.status.is_on { 1 } else { 0 })?;
+        self.writer.write_i16(69, viewport.id)?;
         
         // Status
         self.writer.write_i32(90, viewport.status.to_bits())?;
@@ -1740,9 +2328,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write ATTDEF entity
     fn write_attdef(&mut self, attdef: &AttributeDefinition, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("ATTDEF")?;
+        self.write_entity_type("ATTDEF")?;
         self.write_common_entity_data(&attdef.common, owner)?;
-        self.writer.write_subclass("AcDbText")?;
+        self.write_subclass("AcDbText")?;
         
         // Insertion point
         self.writer.write_point3d(10, attdef.insertion_point)?;
@@ -1777,7 +2365,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Normal
         self.writer.write_point3d(210, attdef.normal)?;
         
-        self.writer.write_subclass("AcDbAttributeDefinition")?;
+        self.write_subclass("AcDbAttributeDefinition")?;
         
         // Tag
         self.writer.write_string(2, &attdef.tag)?;
@@ -1799,9 +2387,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write ATTRIB entity
     fn write_attrib(&mut self, attrib: &AttributeEntity, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("ATTRIB")?;
+        self.write_entity_type("ATTRIB")?;
         self.write_common_entity_data(&attrib.common, owner)?;
-        self.writer.write_subclass("AcDbText")?;
+        self.write_subclass("AcDbText")?;
         
         // Insertion point
         self.writer.write_point3d(10, attrib.insertion_point)?;
@@ -1836,7 +2424,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Normal
         self.writer.write_point3d(210, attrib.normal)?;
         
-        self.writer.write_subclass("AcDbAttribute")?;
+        self.write_subclass("AcDbAttribute")?;
         
         // Tag
         self.writer.write_string(2, &attrib.tag)?;
@@ -1855,9 +2443,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write LEADER entity
     fn write_leader(&mut self, leader: &Leader, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("LEADER")?;
+        self.write_entity_type("LEADER")?;
         self.write_common_entity_data(&leader.common, owner)?;
-        self.writer.write_subclass("AcDbLeader")?;
+        self.write_subclass("AcDbLeader")?;
         
         // Dimension style
         self.writer.write_string(3, &leader.dimension_style)?;
@@ -1913,13 +2501,45 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Write root dictionary
         let mut root_dict = Dictionary::new();
         root_dict.handle = self.allocate_handle();
-        self.write_dictionary(&root_dict)?;
+
+        // The drawing properties replace the contents of an existing DWGPROPS
+        // XRecord, or get a new one in the root dictionary
+        let dwgprops = dwg_props::find_dwgprops(document);
+        if dwgprops.is_none() && !document.summary_info.is_empty() {
+            let handle = self.allocate_handle();
+            root_dict.entries.push((dwg_props::DWGPROPS_KEY.to_string(), handle));
+            self.write_dictionary(&root_dict)?;
+            self.write_xrecord(&dwg_props::to_xrecord(&document.summary_info, handle, root_dict.handle))?;
+        } else {
+            self.write_dictionary(&root_dict)?;
+        }
+
+        // Objects the target version does not know are left out, along with
+        // the dictionary entries pointing at them
+        let skipped: HashSet<Handle> = document
+            .objects
+            .values()
+            .map(object_handle_and_name)
+            .filter(|(_, name)| introduced_in(name) > self.version)
+            .map(|(handle, _)| handle)
+            .collect();
 
         // Write other objects
         for object in document.objects.values() {
+            if skipped.contains(&object_handle_and_name(object).0) {
+                continue;
+            }
             match object {
+                ObjectType::Dictionary(dict) if dict.entries.iter().any(|(_, h)| skipped.contains(h)) => {
+                    let mut dict = dict.clone();
+                    dict.entries.retain(|(_, h)| !skipped.contains(h));
+                    self.write_dictionary(&dict)?
+                }
                 ObjectType::Dictionary(dict) => self.write_dictionary(dict)?,
                 ObjectType::Layout(layout) => self.write_layout(layout)?,
+                ObjectType::XRecord(xrecord) if Some(xrecord.handle) == dwgprops => {
+                    self.write_xrecord(&dwg_props::to_xrecord(&document.summary_info, xrecord.handle, xrecord.owner))?
+                }
                 ObjectType::XRecord(xrecord) => self.write_xrecord(xrecord)?,
                 ObjectType::Group(group) => self.write_group(group)?,
                 ObjectType::MLineStyle(mlinestyle) => self.write_mlinestyle(mlinestyle)?,
@@ -1938,8 +2558,16 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
                 ObjectType::RasterVariables(obj) => self.write_raster_variables(obj)?,
                 ObjectType::BookColor(obj) => self.write_bookcolor(obj)?,
                 ObjectType::PlaceHolder(obj) => self.write_stub_handle_only("ACDBPLACEHOLDER", obj.handle, obj.owner)?,
+                ObjectType::DictionaryWithDefault(obj) if obj.entries.iter().any(|(_, h)| skipped.contains(h)) => {
+                    let mut obj = obj.clone();
+                    obj.entries.retain(|(_, h)| !skipped.contains(h));
+                    self.write_dict_with_default(&obj)?
+                }
                 ObjectType::DictionaryWithDefault(obj) => self.write_dict_with_default(obj)?,
                 ObjectType::WipeoutVariables(obj) => self.write_wipeout_variables(obj)?,
+                ObjectType::Unknown { type_name, handle, codes } if !codes.is_empty() => {
+                    self.write_unknown_object(type_name, *handle, codes)?
+                }
                 ObjectType::Unknown { .. } => {}
             }
         }
@@ -1948,11 +2576,24 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
+    /// Write an unknown object with the codes kept from the file
+    fn write_unknown_object(&mut self, type_name: &str, handle: Handle, codes: &[RawGroupCode]) -> Result<()> {
+        self.writer.write_string(0, type_name)?;
+        for code in codes {
+            if code.code == 5 {
+                self.writer.write_handle(5, handle)?;
+            } else {
+                self.write_raw_code(code)?;
+            }
+        }
+        Ok(())
+    }
+
     fn write_dictionary(&mut self, dict: &Dictionary) -> Result<()> {
         self.writer.write_string(0, "DICTIONARY")?;
         self.writer.write_handle(5, dict.handle)?;
         self.writer.write_handle(330, dict.owner)?;
-        self.writer.write_subclass("AcDbDictionary")?;
+        self.write_subclass("AcDbDictionary")?;
         self.writer
             .write_byte(280, if dict.hard_owner { 1 } else { 0 })?;
         self.writer.write_byte(281, dict.duplicate_cloning as u8)?;
@@ -1969,7 +2610,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "LAYOUT")?;
         self.writer.write_handle(5, layout.handle)?;
         self.writer.write_handle(330, layout.owner)?;
-        self.writer.write_subclass("AcDbPlotSettings")?;
+        self.write_subclass("AcDbPlotSettings")?;
 
         // Minimal plot settings
         self.writer.write_string(1, "")?; // Page setup name
@@ -1995,7 +2636,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_i16(73, 0)?; // Plot rotation
         self.writer.write_i16(74, 0)?; // Plot type
 
-        self.writer.write_subclass("AcDbLayout")?;
+        self.write_subclass("AcDbLayout")?;
         self.writer.write_string(1, &layout.name)?;
         self.writer.write_i16(70, layout.flags)?;
         self.writer.write_i16(71, layout.tab_order)?;
@@ -2024,7 +2665,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "XRECORD")?;
         self.writer.write_handle(5, xrecord.handle)?;
         self.writer.write_handle(330, xrecord.owner)?;
-        self.writer.write_subclass("AcDbXrecord")?;
+        self.write_subclass("AcDbXrecord")?;
         self.writer.write_byte(280, xrecord.cloning_flags.to_code() as u8)?;
 
         // Write each entry's group code and value
@@ -2073,7 +2714,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "GROUP")?;
         self.writer.write_handle(5, group.handle)?;
         self.writer.write_handle(330, group.owner)?;
-        self.writer.write_subclass("AcDbGroup")?;
+        self.write_subclass("AcDbGroup")?;
 
         // Group description (code 300)
         self.writer.write_string(300, &group.description)?;
@@ -2098,7 +2739,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "MLINESTYLE")?;
         self.writer.write_handle(5, style.handle)?;
         self.writer.write_handle(330, style.owner)?;
-        self.writer.write_subclass("AcDbMlineStyle")?;
+        self.write_subclass("AcDbMlineStyle")?;
 
         // Style name (code 2)
         self.writer.write_string(2, &style.name)?;
@@ -2152,7 +2793,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "IMAGEDEF")?;
         self.writer.write_handle(5, imagedef.handle)?;
         self.writer.write_handle(330, imagedef.owner)?;
-        self.writer.write_subclass("AcDbRasterImageDef")?;
+        self.write_subclass("AcDbRasterImageDef")?;
 
         // Class version (code 90)
         self.writer.write_i32(90, imagedef.class_version)?;
@@ -2185,7 +2826,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "PLOTSETTINGS")?;
         self.writer.write_handle(5, settings.handle)?;
         self.writer.write_handle(330, settings.owner)?;
-        self.writer.write_subclass("AcDbPlotSettings")?;
+        self.write_subclass("AcDbPlotSettings")?;
 
         // Page setup name (code 1)
         self.writer.write_string(1, &settings.page_name)?;
@@ -2264,7 +2905,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "MLEADERSTYLE")?;
         self.writer.write_handle(5, style.handle)?;
         self.writer.write_handle(330, style.owner_handle)?;
-        self.writer.write_subclass("AcDbMLeaderStyle")?;
+        self.write_subclass("AcDbMLeaderStyle")?;
 
         // Content type
         self.writer.write_i16(170, style.content_type as i16)?;
@@ -2401,7 +3042,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "TABLESTYLE")?;
         self.writer.write_handle(5, style.handle)?;
         self.writer.write_handle(330, style.owner_handle)?;
-        self.writer.write_subclass("AcDbTableStyle")?;
+        self.write_subclass("AcDbTableStyle")?;
 
         // Version
         self.writer.write_byte(280, style.version as u8)?;
@@ -2467,7 +3108,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "SCALE")?;
         self.writer.write_handle(5, scale.handle)?;
         self.writer.write_handle(330, scale.owner_handle)?;
-        self.writer.write_subclass("AcDbScale")?;
+        self.write_subclass("AcDbScale")?;
 
         // Scale name
         self.writer.write_string(300, &scale.name)?;
@@ -2489,7 +3130,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "SORTENTSTABLE")?;
         self.writer.write_handle(5, table.handle)?;
         self.writer.write_handle(330, table.owner_handle)?;
-        self.writer.write_subclass("AcDbSortentsTable")?;
+        self.write_subclass("AcDbSortentsTable")?;
 
         // Block owner handle
         self.writer.write_handle(330, table.block_owner_handle)?;
@@ -2508,7 +3149,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "DICTIONARYVAR")?;
         self.writer.write_handle(5, var.handle)?;
         self.writer.write_handle(330, var.owner_handle)?;
-        self.writer.write_subclass("DictionaryVariables")?;
+        self.write_subclass("DictionaryVariables")?;
 
         // Schema number
         self.writer.write_byte(280, var.schema_number as u8)?;
@@ -2524,7 +3165,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "VISUALSTYLE")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbVisualStyle")?;
+        self.write_subclass("AcDbVisualStyle")?;
         self.writer.write_string(2, &obj.description)?;
         self.writer.write_i16(70, obj.style_type)?;
         self.writer.write_i16(71, obj.face_lighting_model)?;
@@ -2544,7 +3185,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "MATERIAL")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbMaterial")?;
+        self.write_subclass("AcDbMaterial")?;
         self.writer.write_string(1, &obj.name)?;
         if !obj.description.is_empty() {
             self.writer.write_string(2, &obj.description)?;
@@ -2557,7 +3198,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "IMAGEDEF_REACTOR")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbRasterImageDefReactor")?;
+        self.write_subclass("AcDbRasterImageDefReactor")?;
         self.writer.write_i32(90, 2)?; // class version
         self.writer.write_handle(330, obj.image_handle)?;
         Ok(())
@@ -2568,7 +3209,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "RASTERVARIABLES")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbRasterVariables")?;
+        self.write_subclass("AcDbRasterVariables")?;
         self.writer.write_i32(90, obj.class_version)?;
         self.writer.write_i16(70, obj.display_image_frame)?;
         self.writer.write_i16(71, obj.image_quality)?;
@@ -2581,7 +3222,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "DBCOLOR")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbColor")?;
+        self.write_subclass("AcDbColor")?;
         if !obj.color_name.is_empty() {
             self.writer.write_string(1, &obj.color_name)?;
         }
@@ -2596,13 +3237,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "ACDBDICTIONARYWDFLT")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbDictionary")?;
+        self.write_subclass("AcDbDictionary")?;
         self.writer.write_i16(281, obj.duplicate_cloning)?;
         for (key, handle) in &obj.entries {
             self.writer.write_string(3, key)?;
             self.writer.write_handle(350, *handle)?;
         }
-        self.writer.write_subclass("AcDbDictionaryWithDefault")?;
+        self.write_subclass("AcDbDictionaryWithDefault")?;
         self.writer.write_handle(340, obj.default_handle)?;
         Ok(())
     }
@@ -2612,7 +3253,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         self.writer.write_string(0, "WIPEOUTVARIABLES")?;
         self.writer.write_handle(5, obj.handle)?;
         self.writer.write_handle(330, obj.owner)?;
-        self.writer.write_subclass("AcDbWipeoutVariables")?;
+        self.write_subclass("AcDbWipeoutVariables")?;
         self.writer.write_i16(70, obj.display_frame)?;
         Ok(())
     }
@@ -2651,7 +3292,6 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     }
 
     /// Write extended data (XDATA)
-    #[allow(dead_code)]
     fn write_xdata(&mut self, xdata: &ExtendedData) -> Result<()> {
         if xdata.is_empty() {
             return Ok(());
@@ -2721,15 +3361,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write MULTILEADER entity
     fn write_multileader(&mut self, mleader: &crate::entities::MultiLeader, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("MULTILEADER")?;
+        self.write_entity_type("MULTILEADER")?;
         self.write_common_entity_data(&mleader.common, owner)?;
-        self.writer.write_subclass("AcDbMLeader")?;
+        self.write_subclass("AcDbMLeader")?;
 
-        // Class version (hardcoded to 2 for R2010+)
-        self.writer.write_i16(270, 2)?;
+        // Class version 2, written from R2010 on
+        if self.version >= DxfVersion::AC1024 {
+            self.writer.write_i16(270, 2)?;
+        }
 
         // Context data - write the annotation context
-        self.writer.write_subclass("CONTEXT_DATA{")?;
+        self.write_subclass("CONTEXT_DATA{")?;
 
         // Content scale
         self.writer.write_double(40, mleader.context.scale_factor)?;
@@ -2884,9 +3526,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
     /// Write MLINE entity
     fn write_mline(&mut self, mline: &crate::entities::MLine, owner: Handle) -> Result<()> {
         
-        self.writer.write_entity_type("MLINE")?;
+        self.write_entity_type("MLINE")?;
         self.write_common_entity_data(&mline.common, owner)?;
-        self.writer.write_subclass("AcDbMline")?;
+        self.write_subclass("AcDbMline")?;
 
         // Style name
         self.writer.write_string(2, &mline.style_name)?;
@@ -2952,9 +3594,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write MESH entity
     fn write_mesh(&mut self, mesh: &crate::entities::Mesh, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("MESH")?;
+        self.write_entity_type("MESH")?;
         self.write_common_entity_data(&mesh.common, owner)?;
-        self.writer.write_subclass("AcDbSubDMesh")?;
+        self.write_subclass("AcDbSubDMesh")?;
 
         // Version
         self.writer.write_i16(71, mesh.version)?;
@@ -3011,9 +3653,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write IMAGE (RasterImage) entity
     fn write_raster_image(&mut self, image: &crate::entities::RasterImage, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("IMAGE")?;
+        self.write_entity_type("IMAGE")?;
         self.write_common_entity_data(&image.common, owner)?;
-        self.writer.write_subclass("AcDbRasterImage")?;
+        self.write_subclass("AcDbRasterImage")?;
 
         // Class version
         self.writer.write_i32(90, image.class_version)?;
@@ -3073,9 +3715,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write 3DSOLID entity
     fn write_solid3d(&mut self, solid: &Solid3D, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("3DSOLID")?;
+        self.write_entity_type("3DSOLID")?;
         self.write_common_entity_data(&solid.common, owner)?;
-        self.writer.write_subclass("AcDbModelerGeometry")?;
+        self.write_subclass("AcDbModelerGeometry")?;
 
         // Version
         self.writer.write_i16(70, solid.acis_data.version as i16)?;
@@ -3083,7 +3725,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Write ACIS data
         self.write_acis_data(&solid.acis_data)?;
 
-        self.writer.write_subclass("AcDb3dSolid")?;
+        self.write_subclass("AcDb3dSolid")?;
 
         // History handle
         if let Some(h) = solid.history_handle {
@@ -3095,9 +3737,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write REGION entity
     fn write_region(&mut self, region: &Region, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("REGION")?;
+        self.write_entity_type("REGION")?;
         self.write_common_entity_data(&region.common, owner)?;
-        self.writer.write_subclass("AcDbModelerGeometry")?;
+        self.write_subclass("AcDbModelerGeometry")?;
 
         // Version
         self.writer.write_i16(70, region.acis_data.version as i16)?;
@@ -3110,9 +3752,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write BODY entity
     fn write_body(&mut self, body: &Body, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("BODY")?;
+        self.write_entity_type("BODY")?;
         self.write_common_entity_data(&body.common, owner)?;
-        self.writer.write_subclass("AcDbModelerGeometry")?;
+        self.write_subclass("AcDbModelerGeometry")?;
 
         // Version
         self.writer.write_i16(70, body.acis_data.version as i16)?;
@@ -3153,9 +3795,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write ACAD_TABLE entity
     fn write_acad_table(&mut self, table: &table::Table, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("ACAD_TABLE")?;
+        self.write_entity_type("ACAD_TABLE")?;
         self.write_common_entity_data(&table.common, owner)?;
-        self.writer.write_subclass("AcDbBlockReference")?;
+        self.write_subclass("AcDbBlockReference")?;
 
         // Block record handle
         if let Some(h) = table.block_record_handle {
@@ -3165,7 +3807,7 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         // Insertion point
         self.writer.write_point3d(10, table.insertion_point)?;
 
-        self.writer.write_subclass("AcDbTable")?;
+        self.write_subclass("AcDbTable")?;
 
         // Table style handle
         if let Some(h) = table.table_style_handle {
@@ -3284,9 +3926,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write a Tolerance entity
     fn write_tolerance(&mut self, tolerance: &Tolerance, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("TOLERANCE")?;
+        self.write_entity_type("TOLERANCE")?;
         self.write_common_entity_data(&tolerance.common, owner)?;
-        self.writer.write_subclass("AcDbFcf")?;
+        self.write_subclass("AcDbFcf")?;
 
         // Dimension style name
         self.writer.write_string(3, &tolerance.dimension_style_name)?;
@@ -3314,9 +3956,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write a PolyfaceMesh entity
     fn write_polyface_mesh(&mut self, mesh: &PolyfaceMesh, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("POLYLINE")?;
+        self.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&mesh.common, owner)?;
-        self.writer.write_subclass("AcDbPolyFaceMesh")?;
+        self.write_subclass("AcDbPolyFaceMesh")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -3341,13 +3983,13 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             } else {
                 vertex.common.handle
             };
-            self.writer.write_entity_type("VERTEX")?;
+            self.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, mesh.common.handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &vertex.common.layer)?;
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDbPolyFaceMeshVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDbPolyFaceMeshVertex")?;
 
             self.writer.write_double(10, vertex.location.x)?;
             self.writer.write_double(20, vertex.location.y)?;
@@ -3364,12 +4006,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             } else {
                 face.common.handle
             };
-            self.writer.write_entity_type("VERTEX")?;
+            self.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, face_handle)?;
             self.writer.write_handle(330, mesh.common.handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &face.common.layer)?;
-            self.writer.write_subclass("AcDbFaceRecord")?;
+            self.write_subclass("AcDbFaceRecord")?;
 
             // Dummy position
             self.writer.write_double(10, 0.0)?;
@@ -3392,12 +4034,12 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         }
 
         // Write SEQEND
-        self.writer.write_entity_type("SEQEND")?;
+        self.write_entity_type("SEQEND")?;
         let seqend_handle = mesh.seqend_handle.unwrap_or_else(|| self.allocate_handle());
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, mesh.common.handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbSequenceEnd")?;
         self.writer.write_string(8, &mesh.common.layer)?;
 
         Ok(())
@@ -3405,9 +4047,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write a Wipeout entity
     fn write_wipeout(&mut self, wipeout: &Wipeout, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("WIPEOUT")?;
+        self.write_entity_type("WIPEOUT")?;
         self.write_common_entity_data(&wipeout.common, owner)?;
-        self.writer.write_subclass("AcDbWipeout")?;
+        self.write_subclass("AcDbWipeout")?;
 
         // Class version
         self.writer.write_i32(90, wipeout.class_version)?;
@@ -3457,9 +4099,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write a Shape entity
     fn write_shape(&mut self, shape: &Shape, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("SHAPE")?;
+        self.write_entity_type("SHAPE")?;
         self.write_common_entity_data(&shape.common, owner)?;
-        self.writer.write_subclass("AcDbShape")?;
+        self.write_subclass("AcDbShape")?;
 
         // Thickness
         if shape.thickness.abs() > 1e-10 {
@@ -3504,9 +4146,9 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write an Underlay entity (PDF, DWF, or DGN)
     fn write_underlay(&mut self, underlay: &Underlay, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type(underlay.entity_name())?;
+        self.write_entity_type(underlay.entity_name())?;
         self.write_common_entity_data(&underlay.common, owner)?;
-        self.writer.write_subclass("AcDbUnderlayReference")?;
+        self.write_subclass("AcDbUnderlayReference")?;
 
         // Definition handle
         self.writer.write_handle(340, underlay.definition_handle)?;
@@ -3552,16 +4194,16 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
     /// Write SEQEND entity (end-of-sequence marker)
     fn write_seqend(&mut self, seqend: &Seqend, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("SEQEND")?;
+        self.write_entity_type("SEQEND")?;
         self.write_common_entity_data(&seqend.common, owner)?;
         Ok(())
     }
 
     /// Write OLE2FRAME entity
     fn write_ole2frame(&mut self, ole: &Ole2Frame, owner: Handle) -> Result<()> {
-        self.writer.write_entity_type("OLE2FRAME")?;
+        self.write_entity_type("OLE2FRAME")?;
         self.write_common_entity_data(&ole.common, owner)?;
-        self.writer.write_subclass("AcDbOle2Frame")?;
+        self.write_subclass("AcDbOle2Frame")?;
         self.writer.write_i16(70, ole.version)?;
         if !ole.source_application.is_empty() {
             self.writer.write_string(3, &ole.source_application)?;
@@ -3587,13 +4229,52 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
         Ok(())
     }
 
+    /// Write an unknown entity. One read from a file is written back with
+    /// the codes kept from it, its proxy graphics taken from
+    /// `proxy_graphics`; otherwise one that carries proxy graphics is
+    /// written as an ACAD_PROXY_ENTITY, so its geometry is kept. Unknown
+    /// entities with neither are never written back.
+    fn write_unknown_entity(&mut self, entity: &UnknownEntity, owner: Handle) -> Result<()> {
+        if !entity.common.unknown_codes.is_empty() {
+            let mut common = entity.common.clone();
+            common.unknown_codes = replace_proxy_graphics(
+                &entity.common.unknown_codes,
+                entity.proxy_graphics.as_ref(),
+                self.version,
+            );
+            self.write_entity_type(&entity.dxf_name)?;
+            self.write_common_entity_data(&common, owner)?;
+            if let Some(pending) = self.pending.as_mut() {
+                pending.verbatim = true;
+            }
+            return Ok(());
+        }
+        let Some(graphics) = &entity.proxy_graphics else {
+            return Ok(());
+        };
+
+        self.write_entity_type("ACAD_PROXY_ENTITY")?;
+        self.write_common_entity_data(&entity.common, owner)?;
+        self.write_subclass("AcDbProxyEntity")?;
+        self.writer.write_i32(90, 498)?; // proxy entity class ID
+        self.writer.write_i32(91, 500)?; // application entity class ID
+        self.writer.write_i32(92, graphics.data.len() as i32)?;
+        for chunk in graphics.data.chunks(127) {
+            self.writer.write_binary(310, chunk)?;
+        }
+        self.writer.write_i32(93, 0)?; // entity data size in bits
+        self.writer.write_i32(94, 0)?; // end of object IDs
+        self.writer.write_i16(70, 0)?; // original data format: DWG
+        Ok(())
+    }
+
     /// Write PolygonMesh entity (POLYLINE with flag bit 16)
     fn write_polygon_mesh(&mut self, mesh: &PolygonMeshEntity, owner: Handle) -> Result<()> {
         use crate::entities::polygon_mesh::PolygonMeshFlags;
 
-        self.writer.write_entity_type("POLYLINE")?;
+        self.write_entity_type("POLYLINE")?;
         self.write_common_entity_data(&mesh.common, owner)?;
-        self.writer.write_subclass("AcDbPolygonMesh")?;
+        self.write_subclass("AcDbPolygonMesh")?;
 
         // Entities follow flag (VERTEX records follow)
         self.writer.write_i16(66, 1)?;
@@ -3627,17 +4308,17 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
             } else {
                 vertex.common.handle
             };
-            self.writer.write_entity_type("VERTEX")?;
+            self.write_entity_type("VERTEX")?;
             self.writer.write_handle(5, vertex_handle)?;
             self.writer.write_handle(330, mesh_handle)?;
-            self.writer.write_subclass("AcDbEntity")?;
+            self.write_subclass("AcDbEntity")?;
             self.writer.write_string(8, &vertex.common.layer)?;
             // Propagate parent color to vertex so CAD doesn't flag mismatch
             if mesh.common.color != Color::ByLayer {
-                self.writer.write_color(62, mesh.common.color)?;
+                self.write_entity_color(mesh.common.color)?;
             }
-            self.writer.write_subclass("AcDbVertex")?;
-            self.writer.write_subclass("AcDbPolygonMeshVertex")?;
+            self.write_subclass("AcDbVertex")?;
+            self.write_subclass("AcDbPolygonMeshVertex")?;
             self.writer.write_point3d(10, vertex.location)?;
             if vertex.flags != 0 {
                 self.writer.write_i16(70, vertex.flags)?;
@@ -3646,17 +4327,131 @@ impl<'a, W: DxfStreamWriter> SectionWriter<'a, W> {
 
         // Write SEQEND
         let seqend_handle = self.allocate_handle();
-        self.writer.write_entity_type("SEQEND")?;
+        self.write_entity_type("SEQEND")?;
         self.writer.write_handle(5, seqend_handle)?;
         self.writer.write_handle(330, mesh_handle)?;
-        self.writer.write_subclass("AcDbEntity")?;
-        self.writer.write_subclass("AcDbSequenceEnd")?;
+        self.write_subclass("AcDbEntity")?;
+        self.write_subclass("AcDbSequenceEnd")?;
         self.writer.write_string(8, &mesh.common.layer)?;
 
         Ok(())
     }
 }
 
+/// Handle and DXF name of an object
+/// Kept codes of an unknown entity with the proxy graphics codes that were
+/// read (92 or 160 size, then 310 chunks, in AcDbEntity or AcDbProxyEntity)
+/// replaced by `graphics`. Graphics set on an entity read without any go
+/// before its own subclasses, after the class IDs of a proxy entity.
+fn replace_proxy_graphics(
+    codes: &UnknownCodes,
+    graphics: Option<&ProxyGraphics>,
+    version: DxfVersion,
+) -> UnknownCodes {
+    let mut flat: Vec<(Option<String>, RawGroupCode)> = codes
+        .groups()
+        .iter()
+        .flat_map(|g| g.codes.iter().map(move |c| (g.subclass.clone(), c.clone())))
+        .collect();
+
+    let mut subclass = "AcDbEntity";
+    let mut run = None;
+    let mut insert_at = None;
+    for (i, (_, code)) in flat.iter().enumerate() {
+        match code.code {
+            100 => {
+                subclass = code.value.trim();
+                if insert_at.is_none() && !matches!(subclass, "AcDbEntity" | "AcDbProxyEntity") {
+                    insert_at = Some(i);
+                }
+            }
+            91 if subclass == "AcDbProxyEntity" && insert_at.is_none() => insert_at = Some(i + 1),
+            92 | 160 if matches!(subclass, "AcDbEntity" | "AcDbProxyEntity") => {
+                let chunks = flat[i + 1..].iter().take_while(|(_, c)| c.code == 310).count();
+                run = Some((i, i + 1 + chunks, code.code, subclass == "AcDbProxyEntity"));
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    let (start, end, size_code, in_proxy) = match run {
+        Some(run) => run,
+        None => {
+            let at = insert_at.unwrap_or(flat.len());
+            let in_proxy = subclass == "AcDbProxyEntity";
+            let size_code = if in_proxy || version < DxfVersion::AC1024 { 92 } else { 160 };
+            (at, at, size_code, in_proxy)
+        }
+    };
+    let group = flat
+        .get(start)
+        .or_else(|| flat.last())
+        .and_then(|(group, _)| group.clone());
+
+    let mut replacement = Vec::new();
+    match graphics {
+        Some(graphics) => {
+            replacement.push(RawGroupCode::new(size_code, graphics.data.len().to_string()));
+            for chunk in graphics.data.chunks(127) {
+                let hex: String = chunk.iter().map(|b| format!("{:02X}", b)).collect();
+                replacement.push(RawGroupCode::new(310, hex));
+            }
+        }
+        // A proxy entity always has the size of its graphics
+        None if in_proxy && run.is_some() => replacement.push(RawGroupCode::new(size_code, "0")),
+        None => {}
+    }
+    flat.splice(start..end, replacement.into_iter().map(|c| (group.clone(), c)));
+
+    let mut result = UnknownCodes::new();
+    for (subclass, code) in flat {
+        result.push(subclass.as_deref(), code);
+    }
+    result
+}
+
+fn object_handle_and_name(object: &ObjectType) -> (Handle, &str) {
+    match object {
+        ObjectType::Dictionary(o) => (o.handle, "DICTIONARY"),
+        ObjectType::Layout(o) => (o.handle, "LAYOUT"),
+        ObjectType::XRecord(o) => (o.handle, "XRECORD"),
+        ObjectType::Group(o) => (o.handle, "GROUP"),
+        ObjectType::MLineStyle(o) => (o.handle, "MLINESTYLE"),
+        ObjectType::ImageDefinition(o) => (o.handle, "IMAGEDEF"),
+        ObjectType::PlotSettings(o) => (o.handle, "PLOTSETTINGS"),
+        ObjectType::MultiLeaderStyle(o) => (o.handle, "MLEADERSTYLE"),
+        ObjectType::TableStyle(o) => (o.handle, "TABLESTYLE"),
+        ObjectType::Scale(o) => (o.handle, "SCALE"),
+        ObjectType::SortEntitiesTable(o) => (o.handle, "SORTENTSTABLE"),
+        ObjectType::DictionaryVariable(o) => (o.handle, "DICTIONARYVAR"),
+        ObjectType::VisualStyle(o) => (o.handle, "VISUALSTYLE"),
+        ObjectType::Material(o) => (o.handle, "MATERIAL"),
+        ObjectType::ImageDefinitionReactor(o) => (o.handle, "IMAGEDEF_REACTOR"),
+        ObjectType::GeoData(o) => (o.handle, "GEODATA"),
+        ObjectType::SpatialFilter(o) => (o.handle, "SPATIAL_FILTER"),
+        ObjectType::RasterVariables(o) => (o.handle, "RASTERVARIABLES"),
+        ObjectType::BookColor(o) => (o.handle, "DBCOLOR"),
+        ObjectType::PlaceHolder(o) => (o.handle, "ACDBPLACEHOLDER"),
+        ObjectType::DictionaryWithDefault(o) => (o.handle, "ACDBDICTIONARYWDFLT"),
+        ObjectType::WipeoutVariables(o) => (o.handle, "WIPEOUTVARIABLES"),
+        ObjectType::Unknown { type_name, handle, .. } => (*handle, type_name),
+    }
+}
+
+/// True color value (code 420) of an RGB color
+fn true_color_value(color: Color) -> Option<i32> {
+    color
+        .rgb()
+        .map(|(r, g, b)| ((r as i32) << 16) | ((g as i32) << 8) | (b as i32))
+}
+
+/// DXF transparency value (code 440): 0x02000000 flags an explicit alpha,
+/// stored in the low byte as opacity (255 = opaque)
+fn transparency_value(transparency: Transparency) -> i32 {
+    0x0200_0000 | (255 - transparency.alpha()) as i32
+}
+
 /// Helper to extract invisible edge bits
 fn get_invisible_edge_bits(flags: &InvisibleEdgeFlags) -> u8 {
     let mut bits = 0u8;
@@ -3676,3 +4471,151 @@ fn get_boundary_path_bits(flags: &BoundaryPathFlags) -> u32 {
     bits
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classes::DxfClass;
+    use super::super::text_writer::DxfTextWriter;
+
+    const VERSIONS: [DxfVersion; 7] = [
+        DxfVersion::AC1012,
+        DxfVersion::AC1014,
+        DxfVersion::AC1015,
+        DxfVersion::AC1018,
+        DxfVersion::AC1021,
+        DxfVersion::AC1024,
+        DxfVersion::AC1032,
+    ];
+
+    /// Write one section of `document` for `version` and return its pairs
+    fn write_section(
+        document: &CadDocument,
+        version: DxfVersion,
+        write: impl Fn(
+            &mut SectionWriter<'_, DxfTextWriter<&mut Vec<u8>>>,
+            &CadDocument,
+        ) -> Result<()>,
+    ) -> Vec<(i32, String)> {
+        let mut buf = Vec::new();
+        {
+            let mut writer = DxfTextWriter::new(&mut buf);
+            let start = document.next_handle();
+            let mut section_writer =
+                SectionWriter::new(&mut writer, start, start + 1000).with_version(version);
+            write(&mut section_writer, document).unwrap();
+        }
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        lines
+            .chunks(2)
+            .map(|p| (p[0].trim().parse().unwrap(), p[1].trim().to_string()))
+            .collect()
+    }
+
+    /// Codes of the first record of the given type whose name (code 2),
+    /// class name (code 1) or handle (code 5) matches
+    fn record_codes(pairs: &[(i32, String)], record: &str, name: &str) -> Vec<i32> {
+        let starts: Vec<usize> = (0..pairs.len()).filter(|&i| pairs[i].0 == 0).collect();
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(pairs.len());
+            let codes = &pairs[start + 1..end];
+            if pairs[start].1 == record
+                && codes.iter().any(|(c, v)| matches!(c, 1 | 2 | 5) && v == name)
+            {
+                return codes.iter().map(|(c, _)| *c).collect();
+            }
+        }
+        Vec::new()
+    }
+
+    #[test]
+    fn test_layer_codes_gated_by_version() {
+        let mut doc = CadDocument::new();
+        let mut layer = Layer::new("Walls");
+        layer.handle = doc.allocate_handle();
+        layer.color = Color::from_rgb(10, 200, 30);
+        doc.layers.add(layer).unwrap();
+
+        for version in VERSIONS {
+            let pairs = write_section(&doc, version, |w, d| w.write_tables(d));
+            let codes = record_codes(&pairs, "LAYER", "Walls");
+            assert!(codes.contains(&62), "{:?}", version);
+            assert_eq!(codes.contains(&290), version >= DxfVersion::AC1015, "{:?}", version);
+            assert_eq!(codes.contains(&370), version >= DxfVersion::AC1015, "{:?}", version);
+            assert_eq!(codes.contains(&420), version >= DxfVersion::AC1018, "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_dimstyle_codes_gated_by_version() {
+        let mut doc = CadDocument::new();
+        let mut style = DimStyle::new("Gated");
+        style.handle = doc.allocate_handle();
+        style.dimfxl = 2.0;
+        style.dimtfill = 1;
+        style.dimtxtdirection = true;
+        doc.dim_styles.add(style).unwrap();
+
+        for version in VERSIONS {
+            let pairs = write_section(&doc, version, |w, d| w.write_tables(d));
+            let codes = record_codes(&pairs, "DIMSTYLE", "Gated");
+            assert!(codes.contains(&140), "{:?}", version);
+            assert_eq!(codes.contains(&79), version >= DxfVersion::AC1015, "{:?}", version);
+            assert_eq!(codes.contains(&179), version >= DxfVersion::AC1015, "{:?}", version);
+            assert_eq!(codes.contains(&371), version >= DxfVersion::AC1015, "{:?}", version);
+            assert_eq!(codes.contains(&49), version >= DxfVersion::AC1021, "{:?}", version);
+            assert_eq!(codes.contains(&69), version >= DxfVersion::AC1021, "{:?}", version);
+            assert_eq!(codes.contains(&295), version >= DxfVersion::AC1024, "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_block_record_codes_gated_by_version() {
+        let doc = CadDocument::new();
+
+        for version in VERSIONS {
+            let pairs = write_section(&doc, version, |w, d| w.write_tables(d));
+            let codes = record_codes(&pairs, "BLOCK_RECORD", "*Model_Space");
+            assert!(codes.contains(&2), "{:?}", version);
+            assert_eq!(codes.contains(&70), version >= DxfVersion::AC1015, "{:?}", version);
+            assert_eq!(codes.contains(&280), version >= DxfVersion::AC1015, "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_entity_codes_gated_by_version() {
+        let mut doc = CadDocument::new();
+        let mut line =
+            Line::from_points(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0));
+        line.common.color = Color::from_rgb(10, 200, 30);
+        line.common.transparency = Transparency::T_50;
+        line.common.line_weight = crate::types::LineWeight::from_value(50);
+        let handle = doc.add_entity(EntityType::Line(line)).unwrap();
+
+        for version in VERSIONS {
+            let pairs = write_section(&doc, version, |w, d| w.write_entities(d));
+            let codes = record_codes(&pairs, "LINE", &format!("{:X}", handle.value()));
+            assert!(codes.contains(&62), "{:?}", version);
+            assert_eq!(codes.contains(&370), version >= DxfVersion::AC1015, "{:?}", version);
+            assert_eq!(codes.contains(&420), version >= DxfVersion::AC1018, "{:?}", version);
+            assert_eq!(codes.contains(&440), version >= DxfVersion::AC1024, "{:?}", version);
+        }
+    }
+
+    #[test]
+    fn test_classes_gated_by_version() {
+        let mut doc = CadDocument::new();
+        doc.classes.add_or_update(DxfClass::new("MLEADERSTYLE", "AcDbMLeaderStyle"));
+        doc.classes.add_or_update(DxfClass::new("XRECORD", "AcDbXrecord"));
+
+        for version in VERSIONS {
+            let pairs = write_section(&doc, version, |w, d| w.write_classes(d));
+            let xrecord = record_codes(&pairs, "CLASS", "XRECORD");
+            assert!(xrecord.contains(&90), "{:?}", version);
+            assert_eq!(xrecord.contains(&91), version >= DxfVersion::AC1018, "{:?}", version);
+            let mleader_style = record_codes(&pairs, "CLASS", "MLEADERSTYLE");
+            assert_eq!(!mleader_style.is_empty(), version >= DxfVersion::AC1021, "{:?}", version);
+        }
+    }
+}
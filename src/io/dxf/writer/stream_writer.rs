@@ -97,7 +97,7 @@ pub trait DxfStreamWriterExt: DxfStreamWriter {
 }
 
 // Auto-implement the extension trait for all stream writers
-impl<T: DxfStreamWriter> DxfStreamWriterExt for T {}
+impl<T: DxfStreamWriter + ?Sized> DxfStreamWriterExt for T {}
 
 /// Helper to determine value type from code for writing
 pub fn value_type_for_code(code: i32) -> GroupCodeValueType {
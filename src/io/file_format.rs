@@ -0,0 +1,218 @@
+//! File format detection and format-agnostic open/save
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use crate::document::CadDocument;
+use crate::error::{DxfError, Result};
+use crate::io::dwg::{write_dwg_to_bytes, DwgReader, DwgReaderConfiguration};
+use crate::io::dxf::{DxfReader, DxfWriter};
+use crate::types::DxfVersion;
+
+/// Sentinel at the start of binary DXF files
+const BINARY_DXF_SENTINEL: &[u8] = b"AutoCAD Binary DXF";
+
+/// CAD file formats supported for reading and writing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CadFileFormat {
+    /// ASCII DXF
+    Dxf,
+    /// Binary DXF
+    DxfBinary,
+    /// DWG
+    Dwg,
+}
+
+impl CadFileFormat {
+    /// Detect the format of a stream from its signature: the "AC10xx" DWG
+    /// version string or the binary DXF sentinel. Anything else is assumed
+    /// to be ASCII DXF. The stream is rewound to where it started.
+    pub fn detect<R: Read + Seek>(stream: &mut R) -> Result<Self> {
+        let start = stream.stream_position()?;
+        let mut signature = Vec::with_capacity(BINARY_DXF_SENTINEL.len());
+        stream
+            .by_ref()
+            .take(BINARY_DXF_SENTINEL.len() as u64)
+            .read_to_end(&mut signature)?;
+        stream.seek(SeekFrom::Start(start))?;
+
+        let format = if signature.starts_with(BINARY_DXF_SENTINEL) {
+            CadFileFormat::DxfBinary
+        } else if signature.len() >= 6
+            && signature.starts_with(b"AC10")
+            && signature[4..6].iter().all(u8::is_ascii_digit)
+        {
+            CadFileFormat::Dwg
+        } else {
+            CadFileFormat::Dxf
+        };
+        Ok(format)
+    }
+
+    /// Format matching a file extension (`.dxf` or `.dwg`, case-insensitive).
+    /// DXF files are written as ASCII.
+    pub fn from_extension<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "dxf" => Some(CadFileFormat::Dxf),
+            "dwg" => Some(CadFileFormat::Dwg),
+            _ => None,
+        }
+    }
+
+    /// Versions the writer for this format can produce
+    pub fn supported_versions(&self) -> &'static [DxfVersion] {
+        match self {
+            CadFileFormat::Dxf | CadFileFormat::DxfBinary => &[
+                DxfVersion::AC1009,
+                DxfVersion::AC1012,
+                DxfVersion::AC1014,
+                DxfVersion::AC1015,
+                DxfVersion::AC1018,
+                DxfVersion::AC1021,
+                DxfVersion::AC1024,
+                DxfVersion::AC1027,
+                DxfVersion::AC1032,
+            ],
+            CadFileFormat::Dwg => &[
+                DxfVersion::AC1014,
+                DxfVersion::AC1015,
+                DxfVersion::AC1018,
+                DxfVersion::AC1021,
+                DxfVersion::AC1024,
+                DxfVersion::AC1027,
+                DxfVersion::AC1032,
+            ],
+        }
+    }
+
+    /// Whether the writer for this format can produce `version`
+    pub fn supports_version(&self, version: DxfVersion) -> bool {
+        self.supported_versions().contains(&version)
+    }
+}
+
+impl CadDocument {
+    /// Open a DXF (ASCII or binary) or DWG file, detecting the format from
+    /// the file signature rather than the extension
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        match CadFileFormat::detect(&mut reader)? {
+            CadFileFormat::Dwg => {
+                DwgReader::read_from_stream(reader, DwgReaderConfiguration::default())
+            }
+            CadFileFormat::Dxf | CadFileFormat::DxfBinary => DxfReader::from_reader(reader)?.read(),
+        }
+    }
+
+    /// Save the document as `version` in the given format, or in the format
+    /// matching the file extension when `format` is `None`
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+        format: Option<CadFileFormat>,
+        version: DxfVersion,
+    ) -> Result<()> {
+        let path = path.as_ref();
+        let format = format
+            .or_else(|| CadFileFormat::from_extension(path))
+            .ok_or_else(|| {
+                DxfError::InvalidFormat(format!(
+                    "Cannot determine the file format of {}",
+                    path.display()
+                ))
+            })?;
+        if !format.supports_version(version) {
+            return Err(DxfError::UnsupportedVersion(format!(
+                "{} cannot be written as {:?}",
+                version, format
+            )));
+        }
+
+        let mut document = self.clone();
+        document.version = version;
+        match format {
+            CadFileFormat::Dxf => DxfWriter::new(document).write_to_file(path),
+            CadFileFormat::DxfBinary => DxfWriter::new_binary(document).write_to_file(path),
+            CadFileFormat::Dwg => {
+                // Serialize fully before touching the file so a failed write
+                // does not leave a truncated drawing behind
+                let bytes = write_dwg_to_bytes(document)?;
+                std::fs::write(path, bytes)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{EntityType, Line};
+    use crate::types::Vector3;
+    use std::io::Cursor;
+
+    fn sample_document() -> CadDocument {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Line(Line::from_points(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(5.0, 5.0, 0.0),
+        )))
+        .unwrap();
+        doc
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let mut dwg = Cursor::new(b"AC1032\x00\x00".to_vec());
+        assert_eq!(CadFileFormat::detect(&mut dwg).unwrap(), CadFileFormat::Dwg);
+        assert_eq!(dwg.position(), 0);
+
+        let mut binary = Cursor::new(b"AutoCAD Binary DXF\r\n\x1a\x00".to_vec());
+        assert_eq!(CadFileFormat::detect(&mut binary).unwrap(), CadFileFormat::DxfBinary);
+
+        let mut ascii = Cursor::new(b"  0\nSECTION\n".to_vec());
+        assert_eq!(CadFileFormat::detect(&mut ascii).unwrap(), CadFileFormat::Dxf);
+
+        assert_eq!(CadFileFormat::from_extension("a/b.DWG"), Some(CadFileFormat::Dwg));
+        assert_eq!(CadFileFormat::from_extension("drawing.dxf"), Some(CadFileFormat::Dxf));
+        assert_eq!(CadFileFormat::from_extension("drawing.pdf"), None);
+    }
+
+    #[test]
+    fn test_save_and_open_every_format() {
+        let dir = std::env::temp_dir();
+        let cases = [
+            ("acadrust_save_ascii.dxf", None, DxfVersion::AC1015),
+            ("acadrust_save_binary.dxf", Some(CadFileFormat::DxfBinary), DxfVersion::AC1018),
+            ("acadrust_save.dwg", None, DxfVersion::AC1024),
+        ];
+        for (name, format, version) in cases {
+            let path = dir.join(name);
+            sample_document().save(&path, format, version).unwrap();
+            let doc = CadDocument::open(&path);
+            let _ = std::fs::remove_file(&path);
+
+            let doc = doc.unwrap();
+            assert_eq!(doc.version, version, "{}", name);
+            assert_eq!(doc.entities().count(), 1, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_save_rejects_unsupported_target() {
+        let doc = sample_document();
+        let path = std::env::temp_dir().join("acadrust_save_rejected.dwg");
+
+        let err = doc.save(&path, None, DxfVersion::AC1012).unwrap_err();
+        assert!(matches!(err, DxfError::UnsupportedVersion(_)));
+        let err = doc.save(&path, None, DxfVersion::Unknown).unwrap_err();
+        assert!(matches!(err, DxfError::UnsupportedVersion(_)));
+        let err = doc
+            .save(path.with_extension("txt"), None, DxfVersion::AC1015)
+            .unwrap_err();
+        assert!(matches!(err, DxfError::InvalidFormat(_)));
+        assert!(!path.exists());
+    }
+}
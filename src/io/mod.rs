@@ -2,7 +2,13 @@
 
 pub mod dxf;
 pub mod dwg;
+pub mod downgrade;
+pub mod file_format;
+pub mod probe;
 
 pub use dxf::{DxfReader, DxfWriter};
 pub use dwg::{DwgWriter, DwgWriterConfiguration, write_dwg, write_dwg_to_bytes};
+pub use downgrade::{DowngradePass, EntityConverter};
+pub use file_format::CadFileFormat;
+pub use probe::{probe_file, DrawingInfo};
 
@@ -0,0 +1,74 @@
+//! Quick metadata probe for DXF and DWG files
+//!
+//! Probing reads only what is needed to describe a drawing — the DXF HEADER
+//! section, or the DWG file header, header variables, summary info and
+//! preview — and skips tables, blocks, entities and objects.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::document::{CadSummaryInfo, HeaderVariables};
+use crate::error::Result;
+use crate::io::dwg::{DwgPreview, DwgReader, DwgReaderConfiguration};
+use crate::io::dxf::DxfReader;
+use crate::io::file_format::CadFileFormat;
+use crate::types::{DxfVersion, Vector3};
+
+/// Drawing metadata returned by a probe
+#[derive(Debug, Clone)]
+pub struct DrawingInfo {
+    /// File format version
+    pub version: DxfVersion,
+    /// Drawing code page ($DWGCODEPAGE)
+    pub code_page: String,
+    /// Model space extents minimum ($EXTMIN)
+    pub extents_min: Vector3,
+    /// Model space extents maximum ($EXTMAX)
+    pub extents_max: Vector3,
+    /// Insertion units ($INSUNITS)
+    pub insertion_units: i16,
+    /// Creation date as a julian date ($TDCREATE)
+    pub create_date_julian: f64,
+    /// Last update date as a julian date ($TDUPDATE)
+    pub update_date_julian: f64,
+    /// Drawing properties. DXF keeps them in the OBJECTS section, which a
+    /// probe does not read, so only the values mirrored in the HEADER
+    /// section ($LASTSAVEDBY, $HYPERLINKBASE) are set for DXF files.
+    pub summary_info: CadSummaryInfo,
+    /// Raw preview image (DWG only)
+    pub preview: Option<DwgPreview>,
+}
+
+impl DrawingInfo {
+    /// Build the metadata from the header variables of a drawing
+    pub(crate) fn from_header(version: DxfVersion, header: &HeaderVariables) -> Self {
+        Self {
+            version,
+            code_page: header.code_page.clone(),
+            extents_min: header.model_space_extents_min,
+            extents_max: header.model_space_extents_max,
+            insertion_units: header.insertion_units,
+            create_date_julian: header.create_date_julian,
+            update_date_julian: header.update_date_julian,
+            summary_info: CadSummaryInfo {
+                last_saved_by: header.last_saved_by.clone(),
+                hyperlink_base: header.hyperlink_base.clone(),
+                ..Default::default()
+            },
+            preview: None,
+        }
+    }
+}
+
+/// Probe a DXF (text or binary) or DWG file, choosing the reader from the
+/// file signature
+pub fn probe_file<P: AsRef<Path>>(path: P) -> Result<DrawingInfo> {
+    let mut file = BufReader::new(File::open(path)?);
+    let format = CadFileFormat::detect(&mut file)?;
+
+    match format {
+        CadFileFormat::Dwg => DwgReader::new(file, DwgReaderConfiguration::default())?.probe(),
+        CadFileFormat::Dxf | CadFileFormat::DxfBinary => DxfReader::from_reader(file)?.probe(),
+    }
+}
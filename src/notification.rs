@@ -7,6 +7,7 @@
 //! After a read/write operation the caller can inspect
 //! [`CadDocument::notifications`] to see what was encountered.
 
+use crate::error::DxfLocation;
 use std::fmt;
 
 /// Severity level of a notification.
@@ -40,6 +41,8 @@ pub struct Notification {
     pub notification_type: NotificationType,
     /// A human-readable description of the issue.
     pub message: String,
+    /// Where in the file the issue was found, when reading a DXF file.
+    pub location: Option<DxfLocation>,
 }
 
 impl Notification {
@@ -48,13 +51,24 @@ impl Notification {
         Self {
             notification_type,
             message: message.into(),
+            location: None,
         }
     }
+
+    /// Set where in the file the issue was found.
+    pub fn at(mut self, location: DxfLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
 }
 
 impl fmt::Display for Notification {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[{}] {}", self.notification_type, self.message)
+        write!(f, "[{}] {}", self.notification_type, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " at {}", location)?;
+        }
+        Ok(())
     }
 }
 
@@ -75,6 +89,16 @@ impl NotificationCollection {
         self.items.push(Notification::new(notification_type, message));
     }
 
+    /// Record a notification with the place in the file it was found.
+    pub fn notify_at(
+        &mut self,
+        notification_type: NotificationType,
+        message: impl Into<String>,
+        location: DxfLocation,
+    ) {
+        self.items.push(Notification::new(notification_type, message).at(location));
+    }
+
     /// Check if there are any notifications.
     pub fn is_empty(&self) -> bool {
         self.items.is_empty()
@@ -154,5 +178,12 @@ mod tests {
     fn test_display() {
         let n = Notification::new(NotificationType::NotImplemented, "THUMBNAILIMAGE section");
         assert_eq!(format!("{}", n), "[NotImplemented] THUMBNAILIMAGE section");
+
+        let location = DxfLocation {
+            section: Some("OBJECTS".to_string()),
+            ..Default::default()
+        };
+        let n = Notification::new(NotificationType::Error, "bad value").at(location);
+        assert_eq!(format!("{}", n), "[Error] bad value at section OBJECTS");
     }
 }
@@ -215,9 +215,42 @@ pub enum ObjectType {
         type_name: String,
         /// Object handle
         handle: Handle,
+        /// Group codes after the type name, as read; written back unchanged
+        codes: Vec<crate::types::RawGroupCode>,
     },
 }
 
+impl ObjectType {
+    /// Get the object handle
+    pub fn handle(&self) -> Handle {
+        match self {
+            ObjectType::Dictionary(o) => o.handle,
+            ObjectType::Layout(o) => o.handle,
+            ObjectType::XRecord(o) => o.handle,
+            ObjectType::Group(o) => o.handle,
+            ObjectType::MLineStyle(o) => o.handle,
+            ObjectType::ImageDefinition(o) => o.handle,
+            ObjectType::PlotSettings(o) => o.handle,
+            ObjectType::MultiLeaderStyle(o) => o.handle,
+            ObjectType::TableStyle(o) => o.handle,
+            ObjectType::Scale(o) => o.handle,
+            ObjectType::SortEntitiesTable(o) => o.handle,
+            ObjectType::DictionaryVariable(o) => o.handle,
+            ObjectType::VisualStyle(o) => o.handle,
+            ObjectType::Material(o) => o.handle,
+            ObjectType::ImageDefinitionReactor(o) => o.handle,
+            ObjectType::GeoData(o) => o.handle,
+            ObjectType::SpatialFilter(o) => o.handle,
+            ObjectType::RasterVariables(o) => o.handle,
+            ObjectType::BookColor(o) => o.handle,
+            ObjectType::PlaceHolder(o) => o.handle,
+            ObjectType::DictionaryWithDefault(o) => o.handle,
+            ObjectType::WipeoutVariables(o) => o.handle,
+            ObjectType::Unknown { handle, .. } => *handle,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,104 @@
+//! Group codes kept as they were read
+//!
+//! Files written by vertical applications carry group codes the library does
+//! not model. They are kept with the entity or object they belong to, so a
+//! read/write cycle gives them back unchanged.
+
+/// A group code and its value, as text in the form used by ASCII DXF
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawGroupCode {
+    /// Group code
+    pub code: i32,
+    /// Value as written in the file
+    pub value: String,
+}
+
+impl RawGroupCode {
+    /// Create a new group code/value pair
+    pub fn new(code: i32, value: impl Into<String>) -> Self {
+        Self {
+            code,
+            value: value.into(),
+        }
+    }
+}
+
+/// A run of unknown group codes read after the same subclass marker
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownCodeGroup {
+    /// Subclass marker (100) the codes followed; `None` before the first one
+    pub subclass: Option<String>,
+    /// Codes in file order
+    pub codes: Vec<RawGroupCode>,
+}
+
+/// Group codes of an entity that no field of the entity stores.
+///
+/// Codes are grouped by the subclass marker they followed, so a writer can
+/// put them back at the end of the same subclass.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnknownCodes {
+    groups: Vec<UnknownCodeGroup>,
+}
+
+impl UnknownCodes {
+    /// Create an empty collection
+    pub fn new() -> Self {
+        Self { groups: Vec::new() }
+    }
+
+    /// Add a code read after the `subclass` marker
+    pub fn push(&mut self, subclass: Option<&str>, code: RawGroupCode) {
+        match self.groups.last_mut() {
+            Some(group) if group.subclass.as_deref() == subclass => group.codes.push(code),
+            _ => self.groups.push(UnknownCodeGroup {
+                subclass: subclass.map(str::to_string),
+                codes: vec![code],
+            }),
+        }
+    }
+
+    /// Runs of codes in file order
+    pub fn groups(&self) -> &[UnknownCodeGroup] {
+        &self.groups
+    }
+
+    /// All codes in file order
+    pub fn codes(&self) -> impl Iterator<Item = &RawGroupCode> {
+        self.groups.iter().flat_map(|group| group.codes.iter())
+    }
+
+    /// Number of codes
+    pub fn len(&self) -> usize {
+        self.groups.iter().map(|group| group.codes.len()).sum()
+    }
+
+    /// Check if there are no codes
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Remove all codes
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_grouped_by_subclass() {
+        let mut codes = UnknownCodes::new();
+        codes.push(None, RawGroupCode::new(102, "{MYAPP"));
+        codes.push(None, RawGroupCode::new(102, "}"));
+        codes.push(Some("AcDbLine"), RawGroupCode::new(1, "note"));
+        codes.push(Some("AcDbLine"), RawGroupCode::new(40, "2.5"));
+
+        assert_eq!(codes.len(), 4);
+        assert_eq!(codes.groups().len(), 2);
+        assert_eq!(codes.groups()[1].subclass.as_deref(), Some("AcDbLine"));
+        assert_eq!(codes.codes().map(|c| c.code).collect::<Vec<_>>(), [102, 102, 1, 40]);
+    }
+}
@@ -2,6 +2,7 @@
 
 pub mod bounds;
 pub mod color;
+pub mod group_codes;
 pub mod handle;
 pub mod line_weight;
 pub mod transform;
@@ -10,6 +11,7 @@ pub mod vector;
 
 pub use bounds::{BoundingBox2D, BoundingBox3D};
 pub use color::Color;
+pub use group_codes::{RawGroupCode, UnknownCodeGroup, UnknownCodes};
 pub use handle::Handle;
 pub use line_weight::LineWeight;
 pub use transform::{Matrix3, Matrix4, Transform, rotate_point_2d, is_zero_angle};
@@ -21,6 +23,8 @@ pub use vector::{Vector2, Vector3};
 pub enum DxfVersion {
     /// Unknown version
     Unknown,
+    /// AutoCAD R12 (AC1009), DXF only
+    AC1009,
     /// AutoCAD R13 (AC1012)
     AC1012,
     /// AutoCAD R14 (AC1014)
@@ -44,6 +48,7 @@ impl DxfVersion {
     pub fn as_str(&self) -> &'static str {
         match self {
             DxfVersion::Unknown => "UNKNOWN",
+            DxfVersion::AC1009 => "AC1009",
             DxfVersion::AC1012 => "AC1012",
             DxfVersion::AC1014 => "AC1014",
             DxfVersion::AC1015 => "AC1015",
@@ -63,6 +68,7 @@ impl DxfVersion {
     /// Parse version from string (e.g., "AC1015")
     pub fn parse(s: &str) -> Option<Self> {
         match s {
+            "AC1009" => Some(DxfVersion::AC1009),
             "AC1012" => Some(DxfVersion::AC1012),
             "AC1014" => Some(DxfVersion::AC1014),
             "AC1015" => Some(DxfVersion::AC1015),
@@ -84,6 +90,7 @@ impl DxfVersion {
     pub fn version_code(&self) -> u16 {
         match self {
             DxfVersion::Unknown => 0,
+            DxfVersion::AC1009 => 1009,
             DxfVersion::AC1012 => 1012,
             DxfVersion::AC1014 => 1014,
             DxfVersion::AC1015 => 1015,
@@ -98,6 +105,7 @@ impl DxfVersion {
     /// Create version from numeric code
     pub fn from_version_code(code: u16) -> Self {
         match code {
+            1009 => DxfVersion::AC1009,
             1012 => DxfVersion::AC1012,
             1014 => DxfVersion::AC1014,
             1015 => DxfVersion::AC1015,
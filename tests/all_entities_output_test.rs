@@ -6,6 +6,12 @@ use acadrust::types::{Color, Vector2, Vector3};
 use acadrust::{CadDocument, DxfWriter};
 use std::f64::consts::PI;
 
+/// Path in the system temp directory for a test output file, so running the
+/// tests leaves the working tree untouched
+fn output_path(name: &str) -> String {
+    std::env::temp_dir().join(name).to_string_lossy().into_owned()
+}
+
 /// Create a document with examples of all supported entity types
 fn create_all_entities_document() -> CadDocument {
     let mut doc = CadDocument::new();
@@ -406,13 +412,14 @@ fn test_write_all_entities_ascii() {
     
     // Write ASCII DXF
     let writer = DxfWriter::new(doc.clone());
-    let result = writer.write_to_file("test_output_all_entities_ascii.dxf");
+    let path = output_path("test_output_all_entities_ascii.dxf");
+    let result = writer.write_to_file(&path);
     
     assert!(result.is_ok(), "Failed to write ASCII DXF: {:?}", result.err());
     
     // Verify file was created
     assert!(
-        std::path::Path::new("test_output_all_entities_ascii.dxf").exists(),
+        std::path::Path::new(&path).exists(),
         "ASCII DXF file was not created"
     );
     
@@ -425,13 +432,14 @@ fn test_write_all_entities_binary() {
     
     // Write Binary DXF
     let writer = DxfWriter::new_binary(doc.clone());
-    let result = writer.write_to_file("test_output_all_entities_binary.dxb");
+    let path = output_path("test_output_all_entities_binary.dxb");
+    let result = writer.write_to_file(&path);
     
     assert!(result.is_ok(), "Failed to write Binary DXF: {:?}", result.err());
     
     // Verify file was created
     assert!(
-        std::path::Path::new("test_output_all_entities_binary.dxb").exists(),
+        std::path::Path::new(&path).exists(),
         "Binary DXF file was not created"
     );
     
@@ -509,7 +517,7 @@ fn test_write_all_versions_ascii() {
         let mut doc = create_all_entities_document();
         doc.version = version;
         
-        let filename = format!("test_output_version_{}_ascii.dxf", name);
+        let filename = output_path(&format!("test_output_version_{}_ascii.dxf", name));
         let writer = DxfWriter::new(doc.clone());
         let result = writer.write_to_file(&filename);
         
@@ -547,7 +555,7 @@ fn test_write_all_versions_binary() {
         let mut doc = create_all_entities_document();
         doc.version = version;
         
-        let filename = format!("test_output_version_{}_binary.dxb", name);
+        let filename = output_path(&format!("test_output_version_{}_binary.dxb", name));
         let writer = DxfWriter::new_binary(doc.clone());
         let result = writer.write_to_file(&filename);
         
@@ -590,3 +590,34 @@ fn generate_all_version_dxf_files() {
     }
     println!("\nTotal: {} files generated", VERSIONS.len() * 2);
 }
+
+/// Every group code the writer puts out is read back into a field, so none
+/// end up kept as unknown codes (which would be written a second time)
+#[test]
+fn test_written_codes_are_all_read() {
+    for (ver, name) in &VERSIONS {
+        for binary in [false, true] {
+            let mut doc = create_all_entities_document();
+            doc.version = *ver;
+            let writer = if binary { DxfWriter::new_binary(doc) } else { DxfWriter::new(doc) };
+            let bytes = writer.write_to_vec().unwrap();
+            let rdoc = DxfReader::from_reader(std::io::Cursor::new(bytes)).unwrap().read().unwrap();
+
+            let block_entities = rdoc.block_records.iter().flat_map(|b| b.entities.iter());
+            for entity in rdoc.entities().chain(block_entities) {
+                let codes: Vec<i32> = entity.common().unknown_codes.codes().map(|c| c.code).collect();
+                assert!(codes.is_empty(), "{} {} binary={}: {:?}", name, entity.dxf_name(), binary, codes);
+            }
+            for object in rdoc.objects.values() {
+                assert!(!matches!(object, ObjectType::Unknown { .. }), "{} binary={}: {:?}", name, binary, object);
+            }
+            let errors: Vec<String> = rdoc
+                .notifications
+                .of_type(acadrust::notification::NotificationType::Error)
+                .iter()
+                .map(|n| n.to_string())
+                .collect();
+            assert!(errors.is_empty(), "{} binary={}: {:?}", name, binary, errors);
+        }
+    }
+}
@@ -8,6 +8,12 @@ use acadrust::{CadDocument, DxfWriter};
 use std::collections::BTreeMap;
 use std::f64::consts::PI;
 
+/// Path in the system temp directory for a test output file, so running the
+/// tests leaves the working tree untouched
+fn output_path(name: &str) -> String {
+    std::env::temp_dir().join(name).to_string_lossy().into_owned()
+}
+
 // ---------------------------------------------------------------------------
 // Document creation — every entity type the writer handles
 // ---------------------------------------------------------------------------
@@ -494,7 +500,7 @@ fn test_entity_types_present() {
 #[test]
 fn test_ascii_roundtrip() {
     let doc = create_all_entities_document();
-    let path = "test_writer_all_ascii.dxf";
+    let path = &output_path("test_writer_all_ascii.dxf");
 
     // Write
     let writer = DxfWriter::new(doc.clone());
@@ -532,7 +538,7 @@ fn test_ascii_roundtrip() {
 #[test]
 fn test_binary_roundtrip() {
     let doc = create_all_entities_document();
-    let path = "test_writer_all_binary.dxf";
+    let path = &output_path("test_writer_all_binary.dxf");
 
     // Write binary
     let writer = DxfWriter::new_binary(doc.clone());
@@ -581,7 +587,7 @@ fn test_all_versions_ascii() {
     for (ver, name) in &versions {
         let mut doc = create_all_entities_document();
         doc.version = *ver;
-        let path = format!("test_writer_{}_ascii.dxf", name);
+        let path = output_path(&format!("test_writer_{}_ascii.dxf", name));
         let writer = DxfWriter::new(doc.clone());
         let result = writer.write_to_file(&path);
         assert!(result.is_ok(), "Failed to write {} ASCII: {:?}", name, result.err());
@@ -607,7 +613,7 @@ fn test_all_versions_binary() {
     for (ver, name) in &versions {
         let mut doc = create_all_entities_document();
         doc.version = *ver;
-        let path = format!("test_writer_{}_binary.dxf", name);
+        let path = output_path(&format!("test_writer_{}_binary.dxf", name));
         let writer = DxfWriter::new_binary(doc.clone());
         let result = writer.write_to_file(&path);
         assert!(result.is_ok(), "Failed to write {} Binary: {:?}", name, result.err());
@@ -621,7 +627,7 @@ fn test_all_versions_binary() {
 #[test]
 fn test_ascii_geometry_roundtrip() {
     let doc = create_all_entities_document();
-    let path = "test_writer_geom_ascii.dxf";
+    let path = &output_path("test_writer_geom_ascii.dxf");
     DxfWriter::new(doc).write_to_file(path).unwrap();
     let rdoc = read_back(path);
 
@@ -692,7 +698,7 @@ fn test_ascii_geometry_roundtrip() {
 #[test]
 fn test_binary_geometry_roundtrip() {
     let doc = create_all_entities_document();
-    let path = "test_writer_geom_binary.dxf";
+    let path = &output_path("test_writer_geom_binary.dxf");
     DxfWriter::new_binary(doc).write_to_file(path).unwrap();
     let rdoc = read_back(path);
 
@@ -264,3 +264,984 @@ EOF
 }
 
 
+
+/// Test that proxy graphics of unknown entities survive a DXF round trip
+#[test]
+fn test_proxy_graphics_roundtrip() {
+    use acadrust::entities::{
+        EntityType, ProxyGraphicGeometry, ProxyGraphicPrimitive, ProxyGraphics, UnknownEntity,
+    };
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter};
+
+    let primitives = vec![
+        ProxyGraphicPrimitive::new(ProxyGraphicGeometry::Polyline(vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(4.0, 3.0, 0.0),
+        ])),
+        ProxyGraphicPrimitive::new(ProxyGraphicGeometry::Circle {
+            center: Vector3::new(2.0, 2.0, 0.0),
+            radius: 1.5,
+            normal: Vector3::new(0.0, 0.0, 1.0),
+        }),
+    ];
+
+    let mut doc = CadDocument::new();
+    let mut unknown = UnknownEntity::new("AECC_COGO_POINT");
+    unknown.proxy_graphics = Some(ProxyGraphics::from_primitives(primitives.clone()));
+    doc.add_entity(EntityType::Unknown(unknown)).unwrap();
+
+    let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+    let doc = DxfReader::from_reader(std::io::Cursor::new(bytes))
+        .unwrap()
+        .read()
+        .unwrap();
+
+    let graphics = doc
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Unknown(u) => u.proxy_graphics.clone(),
+            _ => None,
+        })
+        .expect("proxy entity with graphics");
+    assert_eq!(graphics.primitives, primitives);
+}
+
+/// Test that the proxy graphics of an unknown entity written back with its
+/// kept codes come from `proxy_graphics`, not from the codes read
+#[test]
+fn test_unknown_entity_writes_updated_proxy_graphics() {
+    use acadrust::entities::{
+        EntityType, ProxyGraphicGeometry, ProxyGraphicPrimitive, ProxyGraphics, UnknownEntity,
+    };
+    use acadrust::types::Vector3;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let line = |x: f64| {
+        ProxyGraphics::from_primitives(vec![ProxyGraphicPrimitive::new(
+            ProxyGraphicGeometry::Polyline(vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(x, 1.0, 0.0)]),
+        )])
+    };
+    let original = line(1.0);
+    let hex: String = original.data.iter().map(|b| format!("{:02X}", b)).collect();
+    let size = original.data.len().to_string();
+
+    let entity = |with_graphics: bool| {
+        let mut pairs = vec![
+            (0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1032"), (0, "ENDSEC"),
+            (0, "SECTION"), (2, "ENTITIES"),
+            (0, "ACME_WIDGET"), (5, "2B"), (330, "1F"), (100, "AcDbEntity"), (8, "0"),
+        ];
+        if with_graphics {
+            pairs.extend([(160, size.as_str()), (310, hex.as_str())]);
+        }
+        pairs.extend([(100, "AcDbAcmeWidget"), (1, "gear"), (0, "ENDSEC"), (0, "EOF")]);
+        let doc = DxfReader::from_reader(Cursor::new(dxf_from_pairs(&pairs)))
+            .unwrap()
+            .read()
+            .unwrap();
+        let widget = match doc.entities().next().unwrap() {
+            EntityType::Unknown(widget) => widget.clone(),
+            other => panic!("unexpected entity {:?}", other),
+        };
+        widget
+    };
+    let roundtrip = |widget: UnknownEntity| {
+        let mut doc = CadDocument::new();
+        doc.add_entity(EntityType::Unknown(widget)).unwrap();
+        let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+        let doc = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+        let widget = match doc.entities().next().unwrap() {
+            EntityType::Unknown(widget) => widget.clone(),
+            other => panic!("unexpected entity {:?}", other),
+        };
+        assert_eq!(widget.dxf_name, "ACME_WIDGET");
+        assert!(widget.common.unknown_codes.codes().any(|c| c.code == 1 && c.value == "gear"));
+        widget
+    };
+
+    let mut widget = entity(true);
+    assert_eq!(widget.proxy_graphics.as_ref().unwrap().primitives, original.primitives);
+    assert_eq!(roundtrip(widget.clone()).proxy_graphics.unwrap().primitives, original.primitives);
+
+    widget.proxy_graphics = Some(line(5.0));
+    assert_eq!(roundtrip(widget.clone()).proxy_graphics.unwrap().primitives, line(5.0).primitives);
+
+    widget.proxy_graphics = None;
+    assert!(roundtrip(widget).proxy_graphics.is_none());
+
+    let mut widget = entity(false);
+    assert!(widget.proxy_graphics.is_none());
+    widget.proxy_graphics = Some(line(3.0));
+    assert_eq!(roundtrip(widget).proxy_graphics.unwrap().primitives, line(3.0).primitives);
+}
+
+/// Test that the drawing properties survive a DXF round trip through the
+/// DWGPROPS XRecord, including a second write of the read document
+#[test]
+fn test_summary_info_dwgprops_roundtrip() {
+    use acadrust::objects::ObjectType;
+    use acadrust::{CadDocument, DxfWriter};
+
+    let mut doc = CadDocument::new();
+    doc.summary_info.title = "Ground floor".into();
+    doc.summary_info.author = "J. Smith".into();
+    doc.summary_info.keywords = "plan".into();
+    doc.summary_info.hyperlink_base = "https://example.com/".into();
+    doc.summary_info.created_date = (2460000, 43_200_000);
+    doc.summary_info.properties.insert("Project".into(), "P-100".into());
+    let expected = doc.summary_info.clone();
+
+    let read = |bytes: Vec<u8>| {
+        DxfReader::from_reader(std::io::Cursor::new(bytes))
+            .unwrap()
+            .read()
+            .unwrap()
+    };
+
+    let mut doc = read(DxfWriter::new(doc).write_to_vec().unwrap());
+    assert_eq!(doc.summary_info, expected);
+
+    doc.summary_info.revision_number = "B".into();
+    let expected = doc.summary_info.clone();
+    let doc = read(DxfWriter::new(doc).write_to_vec().unwrap());
+    assert_eq!(doc.summary_info, expected);
+
+    let dwgprops = doc
+        .objects
+        .values()
+        .filter(|o| matches!(o, ObjectType::XRecord(x) if x.get_string(1) == Some("DWGPROPS COOKIE")))
+        .count();
+    assert_eq!(dwgprops, 1);
+}
+
+/// Test that probing a DXF file stops after the HEADER section
+#[test]
+fn test_probe_dxf_header_only() {
+    use acadrust::io::probe_file;
+    use acadrust::types::{DxfVersion, Vector3};
+
+    // The ENTITIES section is malformed: a full read fails, a probe does not
+    let dxf_content = "  0\nSECTION\n  2\nHEADER\n  9\n$ACADVER\n  1\nAC1018\n  9\n$DWGCODEPAGE\n  3\nANSI_1251\n  9\n$EXTMIN\n 10\n1.0\n 20\n2.0\n 30\n0.0\n  9\n$EXTMAX\n 10\n30.0\n 20\n40.0\n 30\n0.0\n  9\n$INSUNITS\n 70\n6\n  9\n$TDCREATE\n 40\n2460000.25\n  9\n$LASTSAVEDBY\n  1\njsmith\n  0\nENDSEC\n  0\nSECTION\n  2\nENTITIES\n  0\nLINE\nnot a group code\n";
+
+    let result = DxfReader::from_reader(std::io::Cursor::new(dxf_content.as_bytes().to_vec()))
+        .unwrap()
+        .read();
+    assert!(result.is_err());
+
+    let temp_path = "test_probe.dxf";
+    fs::write(temp_path, dxf_content).unwrap();
+    let info = probe_file(temp_path);
+    let _ = fs::remove_file(temp_path);
+    let info = info.unwrap();
+
+    assert_eq!(info.version, DxfVersion::AC1018);
+    assert_eq!(info.code_page, "ANSI_1251");
+    assert_eq!(info.extents_min, Vector3::new(1.0, 2.0, 0.0));
+    assert_eq!(info.extents_max, Vector3::new(30.0, 40.0, 0.0));
+    assert_eq!(info.insertion_units, 6);
+    assert_eq!(info.create_date_julian, 2460000.25);
+    assert_eq!(info.summary_info.last_saved_by, "jsmith");
+    assert!(info.preview.is_none());
+}
+
+/// Test that probe_file recognises DWG files by their signature
+#[test]
+fn test_probe_file_dwg() {
+    use acadrust::io::{probe_file, write_dwg_to_bytes};
+    use acadrust::types::DxfVersion;
+    use acadrust::CadDocument;
+
+    let mut doc = CadDocument::new();
+    doc.version = DxfVersion::AC1018;
+    doc.header.insertion_units = 4;
+    doc.summary_info.author = "J. Smith".into();
+
+    let temp_path = "test_probe.dwg";
+    fs::write(temp_path, write_dwg_to_bytes(doc).unwrap()).unwrap();
+    let info = probe_file(temp_path);
+    let _ = fs::remove_file(temp_path);
+    let info = info.unwrap();
+
+    assert_eq!(info.version, DxfVersion::AC1018);
+    assert_eq!(info.insertion_units, 4);
+    assert_eq!(info.summary_info.author, "J. Smith");
+}
+
+/// Build DXF text from code/value pairs
+fn dxf_from_pairs(pairs: &[(i32, &str)]) -> Vec<u8> {
+    pairs
+        .iter()
+        .map(|(code, value)| format!("{:3}\n{}\n", code, value))
+        .collect::<String>()
+        .into_bytes()
+}
+
+/// Test reading an R12 file: no handles, no subclass markers and no
+/// BLOCK_RECORD table
+#[test]
+fn test_read_r12_without_handles() {
+    use acadrust::types::DxfVersion;
+    use acadrust::TableEntry;
+
+    let dxf = dxf_from_pairs(&[
+        (0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1009"), (0, "ENDSEC"),
+        (0, "SECTION"), (2, "TABLES"),
+        (0, "TABLE"), (2, "LAYER"), (70, "1"),
+        (0, "LAYER"), (2, "WALLS"), (70, "0"), (62, "1"), (6, "CONTINUOUS"),
+        (0, "ENDTAB"), (0, "ENDSEC"),
+        (0, "SECTION"), (2, "BLOCKS"),
+        (0, "BLOCK"), (8, "0"), (2, "DOOR"), (70, "2"), (10, "0.0"), (20, "0.0"), (30, "0.0"),
+        (0, "LINE"), (8, "0"), (10, "0.0"), (20, "0.0"), (30, "0.0"), (11, "0.0"), (21, "9.0"), (31, "0.0"),
+        (0, "ENDBLK"), (8, "0"),
+        (0, "ENDSEC"),
+        (0, "SECTION"), (2, "ENTITIES"),
+        (0, "LINE"), (8, "WALLS"), (10, "0.0"), (20, "0.0"), (30, "0.0"), (11, "10.0"), (21, "0.0"), (31, "0.0"),
+        (0, "INSERT"), (8, "0"), (2, "DOOR"), (10, "5.0"), (20, "0.0"), (30, "0.0"),
+        (0, "ENDSEC"), (0, "EOF"),
+    ]);
+
+    let doc = DxfReader::from_reader(std::io::Cursor::new(dxf))
+        .unwrap()
+        .read()
+        .unwrap();
+
+    assert_eq!(doc.version, DxfVersion::AC1009);
+    assert_eq!(doc.entities().count(), 2);
+    assert!(doc.entities().all(|e| !e.common().handle.is_null()));
+
+    let layer = doc.layers.get("WALLS").expect("layer read");
+    assert!(!layer.handle().is_null());
+
+    let door = doc.block_records.get("DOOR").expect("block record created");
+    assert!(!door.handle().is_null());
+    assert!(door.flags.has_attributes);
+    assert_eq!(door.entities.len(), 1);
+    assert!(!door.entities[0].common().handle.is_null());
+    assert_eq!(door.entities[0].common().owner_handle, door.handle());
+}
+
+/// Test writing R12: no CLASSES/OBJECTS, no handles or subclass markers,
+/// LWPOLYLINE as POLYLINE/VERTEX and notifications for converted or dropped
+/// entities
+#[test]
+fn test_write_r12() {
+    use acadrust::entities::{Ellipse, EntityType, Hatch, LwPolyline, MText};
+    use acadrust::notification::NotificationType;
+    use acadrust::types::{DxfVersion, Vector2, Vector3};
+    use acadrust::{BlockRecord, CadDocument, DxfWriter};
+
+    let mut doc = CadDocument::with_version(DxfVersion::AC1009);
+    let mut lwpoly = LwPolyline::from_points(vec![
+        Vector2::new(0.0, 0.0),
+        Vector2::new(10.0, 0.0),
+        Vector2::new(10.0, 5.0),
+    ]);
+    lwpoly.vertices[1].bulge = 0.5;
+    lwpoly.close();
+    doc.add_entity(EntityType::LwPolyline(lwpoly)).unwrap();
+    doc.add_entity(EntityType::Ellipse(Ellipse::from_center_axes(
+        Vector3::new(5.0, 5.0, 0.0),
+        Vector3::new(4.0, 0.0, 0.0),
+        0.5,
+    )))
+    .unwrap();
+    doc.add_entity(EntityType::Hatch(Hatch::new())).unwrap();
+
+    let mut block = BlockRecord::new("LABEL");
+    block.handle = doc.allocate_handle();
+    block.entities.push(EntityType::MText(MText::with_value(
+        "{\\fArial|b1;Room}\\P12 m²",
+        Vector3::new(0.0, 0.0, 0.0),
+    )));
+    doc.block_records.add(block).unwrap();
+
+    let writer = DxfWriter::new(doc);
+    let notifications: Vec<_> = writer.document().notifications.iter().collect();
+    assert_eq!(notifications.len(), 4);
+    assert_eq!(
+        notifications
+            .iter()
+            .filter(|n| n.notification_type == NotificationType::NotSupported)
+            .count(),
+        1
+    );
+
+    let bytes = writer.write_to_vec().unwrap();
+    let text = String::from_utf8(bytes.clone()).unwrap();
+    let codes: Vec<i32> = text.lines().step_by(2).map(|c| c.trim().parse().unwrap()).collect();
+    assert!(codes.iter().all(|&c| c < 90 || (140..=239).contains(&c) || c == 999));
+    for absent in ["CLASSES", "OBJECTS", "BLOCK_RECORD", "LWPOLYLINE", "ELLIPSE", "HATCH", "MTEXT", "*Model_Space"] {
+        assert!(!text.contains(&format!("\n{}\n", absent)), "{} written", absent);
+    }
+
+    let doc = DxfReader::from_reader(std::io::Cursor::new(bytes))
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(doc.version, DxfVersion::AC1009);
+    let polylines: Vec<_> = doc
+        .entities()
+        .filter(|e| matches!(e, EntityType::Polyline(_) | EntityType::Polyline2D(_)))
+        .collect();
+    assert_eq!(polylines.len(), 2);
+    assert_eq!(doc.entities().count(), 2);
+
+    let label = doc.block_records.get("LABEL").unwrap();
+    let lines: Vec<&str> = label
+        .entities
+        .iter()
+        .filter_map(|e| match e {
+            EntityType::Text(t) => Some(t.value.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(lines, ["Room", "12 m²"]);
+}
+
+/// Test that group codes, classes and objects follow the target version:
+/// true color from R2004, transparency from R2010, lineweight from R2000
+#[test]
+fn test_write_gated_by_version() {
+    use acadrust::entities::{EntityType, Line};
+    use acadrust::objects::{MultiLeaderStyle, ObjectType};
+    use acadrust::types::{Color, DxfVersion, LineWeight, Transparency, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+
+    let write = |version: DxfVersion| {
+        let mut doc = CadDocument::with_version(version);
+        let mut line = Line::from_points(Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0));
+        line.common.color = Color::from_rgb(10, 200, 30);
+        line.common.transparency = Transparency::T_50;
+        line.common.line_weight = LineWeight::from_value(50);
+        doc.add_entity(EntityType::Line(line)).unwrap();
+        let mut style = MultiLeaderStyle::new("Gated");
+        style.handle = doc.allocate_handle();
+        doc.objects.insert(style.handle, ObjectType::MultiLeaderStyle(style));
+        DxfWriter::new(doc).write_to_vec().unwrap()
+    };
+    let pairs = |bytes: &[u8]| -> Vec<(i32, String)> {
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        lines
+            .chunks(2)
+            .map(|p| (p[0].trim().parse().unwrap(), p[1].to_string()))
+            .collect()
+    };
+
+    let r2000 = pairs(&write(DxfVersion::AC1015));
+    assert!(r2000.iter().any(|(c, v)| *c == 370 && v.trim() == "50"));
+    assert!(!r2000.iter().any(|(c, _)| *c == 420 || *c == 440));
+    assert!(!r2000.iter().any(|(c, v)| *c == 0 && v == "MLEADERSTYLE"));
+    assert!(!r2000.iter().any(|(c, v)| *c == 1 && v == "MLEADERSTYLE"));
+
+    let r2004 = pairs(&write(DxfVersion::AC1018));
+    assert!(r2004.iter().any(|(c, _)| *c == 420));
+    assert!(!r2004.iter().any(|(c, _)| *c == 440));
+
+    let bytes = write(DxfVersion::AC1024);
+    assert!(pairs(&bytes).iter().any(|(c, v)| *c == 0 && v == "MLEADERSTYLE"));
+    let doc = DxfReader::from_reader(std::io::Cursor::new(bytes))
+        .unwrap()
+        .read()
+        .unwrap();
+    let line = doc
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Line(l) => Some(l),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(line.common.color, Color::from_rgb(10, 200, 30));
+    assert_eq!(line.common.transparency, Transparency::T_50);
+}
+
+/// Test that saving as R2000 converts MULTILEADER, MESH and ACAD_TABLE,
+/// drops underlays and reports each change, for both DXF and DWG
+#[test]
+fn test_write_r2000_downgrades_entities() {
+    use acadrust::entities::{EntityType, Mesh, MultiLeader, TableBuilder, Underlay};
+    use acadrust::io::DwgWriter;
+    use acadrust::notification::NotificationType;
+    use acadrust::types::{DxfVersion, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+
+    let document = || {
+        let mut doc = CadDocument::with_version(DxfVersion::AC1015);
+        doc.add_entity(EntityType::MultiLeader(MultiLeader::with_text(
+            "Detail A",
+            Vector3::new(10.0, 10.0, 0.0),
+            vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(9.0, 10.0, 0.0)],
+        )))
+        .unwrap();
+        doc.add_entity(EntityType::Mesh(Mesh::create_unit_cube())).unwrap();
+        let table = TableBuilder::new(2, 2)
+            .at(Vector3::new(20.0, 0.0, 0.0))
+            .header(&["Part", "Qty"])
+            .build();
+        doc.add_entity(EntityType::Table(table)).unwrap();
+        doc.add_entity(EntityType::Underlay(Underlay::pdf())).unwrap();
+        doc
+    };
+
+    let writer = DxfWriter::new(document());
+    let notifications: Vec<_> = writer.document().notifications.iter().collect();
+    assert_eq!(notifications.len(), 4);
+    assert!(notifications.iter().all(|n| n.notification_type == NotificationType::Warning));
+    assert!(notifications.iter().any(|n| n.message.contains("PDFUNDERLAY") && n.message.contains("dropped")));
+
+    let bytes = writer.write_to_vec().unwrap();
+    assert!(String::from_utf8_lossy(&bytes).contains("\nAcDbPolyFaceMesh\n"));
+    let doc = DxfReader::from_reader(std::io::Cursor::new(bytes))
+        .unwrap()
+        .read()
+        .unwrap();
+    let names: Vec<&str> = doc.entities().map(|e| e.dxf_name()).collect();
+    for absent in ["MULTILEADER", "MESH", "ACAD_TABLE", "PDFUNDERLAY"] {
+        assert!(!names.contains(&absent), "{} written", absent);
+    }
+    assert!(doc.entities().any(|e| matches!(e, EntityType::MText(m) if m.value == "Detail A")));
+    assert!(doc.entities().any(|e| matches!(e, EntityType::Leader(_))));
+    let insert = doc
+        .entities()
+        .find_map(|e| match e {
+            EntityType::Insert(i) => Some(i),
+            _ => None,
+        })
+        .unwrap();
+    let block = doc.block_records.get(&insert.block_name).unwrap();
+    assert!(block.entities.iter().any(|e| matches!(e, EntityType::Text(t) if t.value == "Qty")));
+
+    let mut writer = DwgWriter::new(std::io::Cursor::new(Vec::new()), document());
+    writer.write().unwrap();
+    assert_eq!(writer.notifications().iter().filter(|n| n.message.contains("AC1015")).count(), 4);
+}
+
+/// Converter replacing each MESH by a POINT, to tell a custom pass from the
+/// default one that writes a polyface mesh
+struct MeshToPoint;
+
+impl acadrust::io::EntityConverter for MeshToPoint {
+    fn convert(
+        &self,
+        entity: &acadrust::entities::EntityType,
+        _: acadrust::types::DxfVersion,
+        _: &mut acadrust::CadDocument,
+    ) -> Option<Vec<acadrust::entities::EntityType>> {
+        use acadrust::entities::{EntityType, Point};
+
+        let EntityType::Mesh(mesh) = entity else {
+            return None;
+        };
+        let mut point = Point::from_coords(1.0, 2.0, 0.0);
+        point.common.handle = mesh.common.handle;
+        Some(vec![EntityType::Point(point)])
+    }
+}
+
+/// Test that each writer takes a custom downgrade pass and keeps the
+/// converted entities in place
+#[test]
+fn test_write_with_custom_downgrade_pass() {
+    use acadrust::entities::{Circle, EntityType, Line, Mesh};
+    use acadrust::io::dwg::{DwgReader, DwgReaderConfiguration};
+    use acadrust::io::dxf::DxfIncrementalWriter;
+    use acadrust::io::{DowngradePass, DwgWriter};
+    use acadrust::types::{DxfVersion, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let pass = || DowngradePass::new().with_converter(MeshToPoint);
+    let document = || {
+        let mut doc = CadDocument::with_version(DxfVersion::AC1015);
+        doc.add_entity(EntityType::Line(Line::new())).unwrap();
+        doc.add_entity(EntityType::Mesh(Mesh::create_unit_cube())).unwrap();
+        doc.add_entity(EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 1.0)))
+            .unwrap();
+        doc
+    };
+    let names = |doc: &CadDocument| -> Vec<String> {
+        doc.entities().map(|e| e.dxf_name().to_string()).collect()
+    };
+
+    let writer = DxfWriter::with_downgrade_pass(document(), pass());
+    assert_eq!(names(writer.document()), ["LINE", "POINT", "CIRCLE"]);
+    let doc = DxfReader::from_reader(Cursor::new(writer.write_to_vec().unwrap()))
+        .unwrap()
+        .read()
+        .unwrap();
+    assert_eq!(names(&doc), ["LINE", "POINT", "CIRCLE"]);
+
+    let mut writer = DxfIncrementalWriter::with_downgrade_pass(
+        Cursor::new(Vec::new()),
+        CadDocument::with_version(DxfVersion::AC1015),
+        pass(),
+    )
+    .unwrap();
+    writer.add_entity(EntityType::Mesh(Mesh::create_unit_cube())).unwrap();
+    let bytes = writer.finish().unwrap().into_inner();
+    let doc = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    assert_eq!(names(&doc), ["POINT"]);
+
+    let mut cursor = Cursor::new(Vec::new());
+    DwgWriter::new(&mut cursor, document())
+        .with_downgrade_pass(pass())
+        .write()
+        .unwrap();
+    let doc = DwgReader::read_from_stream(
+        Cursor::new(cursor.into_inner()),
+        DwgReaderConfiguration::default(),
+    )
+    .unwrap();
+    assert_eq!(names(&doc), ["LINE", "POINT", "CIRCLE"]);
+}
+
+#[test]
+fn test_stream_matches_full_read() {
+    use acadrust::entities::{Circle, EntityType, Line, Point};
+    use acadrust::io::dxf::DxfStreamItem;
+    use acadrust::types::{DxfVersion, Vector3};
+    use acadrust::{BlockRecord, CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    for version in [DxfVersion::AC1032, DxfVersion::AC1009] {
+        let mut doc = CadDocument::with_version(version);
+        for i in 0..50 {
+            let x = i as f64;
+            doc.add_entity(EntityType::Line(Line::from_points(
+                Vector3::new(x, 0.0, 0.0),
+                Vector3::new(x, 1.0, 0.0),
+            )))
+            .unwrap();
+            doc.add_entity(EntityType::Point(Point::new())).unwrap();
+        }
+        let mut block = BlockRecord::new("MARK");
+        block.handle = doc.allocate_handle();
+        block
+            .entities
+            .push(EntityType::Circle(Circle::from_center_radius(Vector3::ZERO, 1.0)));
+        doc.block_records.add(block).unwrap();
+        let bytes = DxfWriter::new(doc).write_to_vec().unwrap();
+
+        let full = DxfReader::from_reader(Cursor::new(bytes.clone()))
+            .unwrap()
+            .read()
+            .unwrap();
+        let stream = DxfReader::from_reader(Cursor::new(bytes))
+            .unwrap()
+            .stream()
+            .unwrap()
+            .with_block_entities(true);
+        assert_eq!(stream.version(), version);
+
+        let mut entities = 0;
+        let mut block_entities = Vec::new();
+        for item in stream {
+            match item.unwrap() {
+                DxfStreamItem::Entity(_) => entities += 1,
+                DxfStreamItem::BlockEntity { block, entity } => {
+                    block_entities.push((block, entity.dxf_name().to_string()))
+                }
+                other => panic!("unexpected item {:?}", other),
+            }
+        }
+        assert_eq!(entities, full.entities().count(), "{}", version);
+        assert_eq!(entities, 100);
+        assert!(block_entities.contains(&("MARK".to_string(), "CIRCLE".to_string())));
+    }
+}
+
+/// Test that group codes written by a vertical application survive a
+/// read/write cycle, in the place they were read from
+#[test]
+fn test_unknown_codes_roundtrip() {
+    use acadrust::entities::EntityType;
+    use acadrust::objects::ObjectType;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let dxf = dxf_from_pairs(&[
+        (0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1032"), (0, "ENDSEC"),
+        (0, "SECTION"), (2, "ENTITIES"),
+        (0, "LINE"), (5, "2A"), (102, "{MYAPP"), (1, "tag"), (102, "}"), (330, "1F"),
+        (100, "AcDbEntity"), (8, "0"),
+        (100, "AcDbLine"), (10, "0.0"), (20, "0.0"), (30, "0.0"), (11, "1.0"), (21, "1.0"), (31, "0.0"),
+        (1, "note"), (40, "2.5"),
+        (1001, "MYAPP"), (1000, "hello"),
+        (0, "ACME_WIDGET"), (5, "2B"), (330, "1F"), (100, "AcDbEntity"), (8, "0"),
+        (100, "AcDbAcmeWidget"), (1, "gear"), (40, "1.5"),
+        (0, "ENDSEC"),
+        (0, "SECTION"), (2, "OBJECTS"),
+        (0, "ACME_DATA"), (5, "2C"), (330, "0"), (100, "AcDbAcmeData"), (90, "7"), (1, "payload"),
+        (0, "ENDSEC"), (0, "EOF"),
+    ]);
+
+    let read = |bytes: Vec<u8>| DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let codes = |doc: &CadDocument, name: &str| -> Vec<(Option<String>, i32, String)> {
+        let entity = doc.entities().find(|e| e.dxf_name() == name).unwrap();
+        entity
+            .common()
+            .unknown_codes
+            .groups()
+            .iter()
+            .flat_map(|g| g.codes.iter().map(move |c| (g.subclass.clone(), c.code, c.value.clone())))
+            .collect()
+    };
+    let object_codes = |doc: &CadDocument| {
+        doc.objects
+            .values()
+            .find_map(|o| match o {
+                ObjectType::Unknown { type_name, codes, .. } if type_name == "ACME_DATA" => {
+                    Some(codes.iter().map(|c| (c.code, c.value.clone())).collect::<Vec<_>>())
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+
+    let first = read(dxf);
+    let line_codes = codes(&first, "LINE");
+    assert!(line_codes.contains(&(None, 102, "{MYAPP".to_string())));
+    assert!(line_codes.contains(&(Some("AcDbLine".to_string()), 40, "2.5".to_string())));
+    assert!(!codes(&first, "ACME_WIDGET").is_empty());
+    assert!(object_codes(&first).contains(&(1, "payload".to_string())));
+
+    let bytes = DxfWriter::new(first.clone()).write_to_vec().unwrap();
+    let text = String::from_utf8(bytes.clone()).unwrap();
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let line_start = lines.iter().position(|l| *l == "LINE").unwrap();
+    let find = |value: &str| line_start + lines[line_start..].iter().position(|l| *l == value).unwrap();
+    assert!(find("{MYAPP") < find("AcDbEntity"));
+    assert!(find("AcDbLine") < find("note"));
+    assert!(find("note") < find("MYAPP"));
+
+    let second = read(bytes);
+    assert_eq!(codes(&second, "LINE"), line_codes);
+    assert_eq!(codes(&second, "ACME_WIDGET"), codes(&first, "ACME_WIDGET"));
+    assert_eq!(object_codes(&second), object_codes(&first));
+    let line = second.entities().find(|e| e.dxf_name() == "LINE").unwrap();
+    assert!(matches!(line, EntityType::Line(_)));
+    assert!(!line.common().extended_data.is_empty());
+}
+
+/// Test that 92/160 and 310 codes of an unknown entity's own subclass are
+/// kept as its data, not taken for proxy graphics
+#[test]
+fn test_unknown_entity_own_codes_are_not_proxy_graphics() {
+    use acadrust::entities::EntityType;
+    use std::io::Cursor;
+
+    let dxf = dxf_from_pairs(&[
+        (0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1032"), (0, "ENDSEC"),
+        (0, "SECTION"), (2, "ENTITIES"),
+        (0, "ACME_WIDGET"), (5, "2B"), (330, "1F"), (100, "AcDbEntity"), (8, "0"),
+        (100, "AcDbAcmeWidget"), (92, "3"), (310, "0A0B0C"), (160, "3"), (1, "gear"),
+        (0, "ENDSEC"), (0, "EOF"),
+    ]);
+
+    let doc = DxfReader::from_reader(Cursor::new(dxf)).unwrap().read().unwrap();
+    let EntityType::Unknown(widget) = doc.entities().next().unwrap() else {
+        panic!("ACME_WIDGET not read as an unknown entity");
+    };
+    assert!(widget.proxy_graphics.is_none());
+    let codes: Vec<(i32, &str)> = widget
+        .common
+        .unknown_codes
+        .groups()
+        .iter()
+        .flat_map(|g| g.codes.iter().map(|c| (c.code, c.value.as_str())))
+        .collect();
+    assert_eq!(
+        codes,
+        [(100, "AcDbAcmeWidget"), (92, "3"), (310, "0A0B0C"), (160, "3"), (1, "gear")]
+    );
+}
+
+/// Test that entities are read and written in file order, not handle order
+#[test]
+fn test_entity_order_roundtrip() {
+    use acadrust::entities::{Circle, EntityType, Line, Wipeout};
+    use acadrust::types::{DxfVersion, Handle, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    for version in [DxfVersion::AC1009, DxfVersion::AC1015, DxfVersion::AC1032] {
+        let mut doc = CadDocument::with_version(version);
+        for i in 0..50u64 {
+            let mut line = Line::from_points(Vector3::new(i as f64, 0.0, 0.0), Vector3::new(i as f64, 1.0, 0.0));
+            line.common.handle = Handle::new(0x500 - i * 3);
+            doc.add_entity(EntityType::Line(line)).unwrap();
+        }
+        let circle = doc.add_entity(EntityType::Circle(Circle::new())).unwrap();
+        doc.send_entity_to_back(circle);
+        if version != DxfVersion::AC1009 {
+            doc.add_entity(EntityType::Wipeout(Wipeout::new())).unwrap();
+        }
+        let order: Vec<u64> = doc.entities().map(|e| e.common().handle.value()).collect();
+
+        for binary in [false, true] {
+            let writer = if binary { DxfWriter::new_binary(doc.clone()) } else { DxfWriter::new(doc.clone()) };
+            let bytes = writer.write_to_vec().unwrap();
+            let read = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+            let read_order: Vec<u64> = read.entities().map(|e| e.common().handle.value()).collect();
+            if version == DxfVersion::AC1009 {
+                // R12 files are written without handles
+                assert_eq!(read.entities().count(), order.len());
+                assert_eq!(read.entities().next().unwrap().dxf_name(), "CIRCLE");
+            } else {
+                assert_eq!(read_order, order, "{} binary={}", version, binary);
+            }
+        }
+    }
+}
+
+/// Test that reading errors and notifications say where in the file the
+/// problem is
+#[test]
+fn test_error_locations() {
+    use acadrust::error::DxfPosition;
+    use acadrust::io::dxf::DxfReaderConfiguration;
+    use acadrust::notification::NotificationType;
+    use acadrust::types::Handle;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut pairs = vec![
+        (0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1032"), (0, "ENDSEC"),
+        (0, "SECTION"), (2, "ENTITIES"),
+        (0, "LINE"), (5, "2A"), (100, "AcDbEntity"), (8, "0"),
+        (100, "AcDbLine"), (10, "1.5"), (20, "abc"), (30, "0.0"), (11, "1.0"), (21, "1.0"), (31, "0.0"),
+    ];
+    let read = |bytes: Vec<u8>, failsafe: bool, parallel: bool| {
+        DxfReader::from_reader(Cursor::new(bytes))
+            .unwrap()
+            .with_configuration(DxfReaderConfiguration { failsafe })
+            .with_parallel(parallel)
+            .read()
+    };
+
+    // A value that cannot be converted is reported with the record it is in
+    for parallel in [false, true] {
+        let mut complete = pairs.clone();
+        complete.extend([(0, "ENDSEC"), (0, "EOF")]);
+        let doc = read(dxf_from_pairs(&complete), false, parallel).unwrap();
+        let errors = doc.notifications.of_type(NotificationType::Error);
+        assert_eq!(errors.len(), 1, "parallel={}", parallel);
+        let location = errors[0].location.as_ref().unwrap();
+        assert_eq!(location.position, Some(DxfPosition::Line(27)));
+        assert_eq!(location.section.as_deref(), Some("ENTITIES"));
+        assert_eq!(location.entity_type.as_deref(), Some("LINE"));
+        assert_eq!(location.handle, Some(Handle::new(0x2A)));
+        assert_eq!((location.code, location.value.as_deref()), (Some(20), Some("abc")));
+    }
+
+    // A file cut after a group code
+    let mut truncated = dxf_from_pairs(&pairs);
+    truncated.extend_from_slice(b" 40\n");
+    let error = read(truncated.clone(), false, false).unwrap_err();
+    let location = error.location().unwrap();
+    assert_eq!(location.position, Some(DxfPosition::Line(37)));
+    assert_eq!(location.handle, Some(Handle::new(0x2A)));
+    assert_eq!(location.code, Some(40));
+    assert!(error.to_string().contains("line 37, section ENTITIES, LINE 2A"), "{}", error);
+
+    let doc = read(truncated, true, false).unwrap();
+    let notification = doc
+        .notifications
+        .iter()
+        .find(|n| n.message.starts_with("Error reading ENTITIES section"))
+        .unwrap();
+    assert_eq!(notification.location.as_ref().unwrap().position, Some(DxfPosition::Line(37)));
+
+    // A bad group code line
+    pairs.push((0, "ENDSEC"));
+    let mut bad_code = dxf_from_pairs(&pairs);
+    bad_code.extend_from_slice(b"EOF\n");
+    let error = read(bad_code, false, false).unwrap_err();
+    let location = error.location().unwrap();
+    assert_eq!(location.position, Some(DxfPosition::Line(39)));
+    assert_eq!(location.value.as_deref(), Some("EOF"));
+
+    // Binary files give byte offsets
+    let bytes = DxfWriter::new_binary(CadDocument::new()).write_to_vec().unwrap();
+    let error = read(bytes[..bytes.len() - 3].to_vec(), false, false).unwrap_err();
+    assert!(matches!(error.location().unwrap().position, Some(DxfPosition::Offset(_))), "{}", error);
+}
+
+/// Test that an I/O error while reading is returned as `DxfError::Io`,
+/// without a file location
+#[test]
+fn test_io_errors_are_not_located() {
+    use acadrust::error::DxfError;
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+
+    /// Stream that fails once reading gets past `limit`
+    struct FailingReader {
+        inner: Cursor<Vec<u8>>,
+        limit: u64,
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let left = self.limit.saturating_sub(self.inner.position()) as usize;
+            if left == 0 {
+                return Err(std::io::Error::other("device unplugged"));
+            }
+            let len = buf.len().min(left);
+            self.inner.read(&mut buf[..len])
+        }
+    }
+
+    impl Seek for FailingReader {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    let bytes = dxf_from_pairs(&[
+        (0, "SECTION"), (2, "HEADER"), (9, "$ACADVER"), (1, "AC1032"), (0, "ENDSEC"),
+        (0, "SECTION"), (2, "ENTITIES"),
+        (0, "LINE"), (5, "2A"), (100, "AcDbEntity"), (8, "0"),
+        (100, "AcDbLine"), (10, "0.0"), (20, "0.0"), (30, "0.0"), (11, "1.0"), (21, "1.0"), (31, "0.0"),
+        (0, "ENDSEC"), (0, "EOF"),
+    ]);
+    let limit = bytes.len() as u64 / 2;
+    let error = DxfReader::from_reader(FailingReader { inner: Cursor::new(bytes), limit })
+        .unwrap()
+        .read()
+        .unwrap_err();
+    assert!(error.location().is_none(), "{}", error);
+    match error.into_parts() {
+        (DxfError::Io(e), None) => assert_eq!(e.to_string(), "device unplugged"),
+        (other, location) => panic!("expected an I/O error, got {} at {:?}", other, location),
+    }
+}
+
+/// Test that HEADER variables round-trip, with variables no field models
+/// written back unchanged and the rest gated by the target version
+#[test]
+fn test_header_variables_roundtrip() {
+    use acadrust::types::{DxfVersion, Vector3};
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let dxf = dxf_from_pairs(&[
+        (0, "SECTION"), (2, "HEADER"),
+        (9, "$ACADVER"), (1, "AC1032"),
+        (9, "$ACADMAINTVER"), (90, "45"),
+        (9, "$ACME_SETTINGS"), (1, "metric"), (40, "2.5"), (70, "3"),
+        (9, "$DIMTXSTY"), (7, "Notes"),
+        (9, "$UCSORGTOP"), (10, "1.0"), (20, "2.0"), (30, "3.0"),
+        (9, "$TEXTQLTY"), (70, "50"),
+        (0, "ENDSEC"), (0, "EOF"),
+    ]);
+
+    let read = |bytes: Vec<u8>| DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+    let unknown = |doc: &CadDocument| -> Vec<(String, Vec<(i32, String)>)> {
+        doc.header
+            .unknown_variables
+            .iter()
+            .map(|(name, codes)| (name.clone(), codes.iter().map(|c| (c.code, c.value.clone())).collect()))
+            .collect()
+    };
+
+    let first = read(dxf);
+    assert_eq!(first.header.maintenance_version, 45);
+    assert_eq!(first.header.dim_text_style_name, "Notes");
+    assert_eq!(first.header.model_space_ucs_origin_top, Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(
+        unknown(&first),
+        [
+            (
+                "$ACME_SETTINGS".to_string(),
+                vec![(1, "metric".to_string()), (40, "2.5".to_string()), (70, "3".to_string())]
+            ),
+            ("$TEXTQLTY".to_string(), vec![(70, "50".to_string())]),
+        ]
+    );
+
+    let second = read(DxfWriter::new(first.clone()).write_to_vec().unwrap());
+    assert_eq!(second.header.maintenance_version, 45);
+    assert_eq!(second.header.dim_text_style_name, "Notes");
+    assert_eq!(second.header.model_space_ucs_origin_top, Vector3::new(1.0, 2.0, 3.0));
+    assert_eq!(unknown(&second), unknown(&first));
+
+    let variables = |version: DxfVersion| -> Vec<String> {
+        let mut doc = first.clone();
+        doc.version = version;
+        let text = String::from_utf8(DxfWriter::new(doc).write_to_vec().unwrap()).unwrap();
+        let lines: Vec<&str> = text.lines().map(str::trim).collect();
+        lines
+            .chunks(2)
+            .filter(|p| p[0] == "9")
+            .map(|p| p[1].to_string())
+            .collect()
+    };
+
+    let r12 = variables(DxfVersion::AC1009);
+    for present in ["$SNAPMODE", "$VIEWCTR", "$HANDLING", "$ACME_SETTINGS", "$TEXTQLTY"] {
+        assert!(r12.iter().any(|v| v == present), "{} missing", present);
+    }
+    for absent in ["$HANDSEED", "$CELWEIGHT", "$DIMTXSTY", "$UCSORGTOP", "$DIMTXTDIRECTION"] {
+        assert!(!r12.iter().any(|v| v == absent), "{} written", absent);
+    }
+
+    let r2018 = variables(DxfVersion::AC1032);
+    for present in ["$DIMTXTDIRECTION", "$REQUIREDVERSIONS", "$UCSORGTOP", "$ACME_SETTINGS"] {
+        assert!(r2018.iter().any(|v| v == present), "{} missing", present);
+    }
+    assert!(!r2018.iter().any(|v| v == "$SNAPMODE" || v == "$HANDLING"));
+    let mut unique = r2018.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), r2018.len());
+}
+
+/// Test that unknown header variables set on a document are written and
+/// read back unchanged and in order, in text and binary DXF
+#[test]
+fn test_unknown_header_variables_write_read() {
+    use acadrust::types::RawGroupCode;
+    use acadrust::{CadDocument, DxfWriter};
+    use std::io::Cursor;
+
+    let mut doc = CadDocument::new();
+    doc.header.unknown_variables.insert(
+        "$ZZ_VENDOR".to_string(),
+        vec![RawGroupCode::new(1, "ACME"), RawGroupCode::new(70, "7")],
+    );
+    doc.header.unknown_variables.insert(
+        "$AA_ORIGIN".to_string(),
+        vec![
+            RawGroupCode::new(10, "1.5"),
+            RawGroupCode::new(20, "-2.25"),
+            RawGroupCode::new(30, "0.0"),
+        ],
+    );
+
+    let names = |doc: &CadDocument| -> Vec<String> {
+        doc.header.unknown_variables.keys().cloned().collect()
+    };
+
+    for binary in [false, true] {
+        let writer = if binary {
+            DxfWriter::new_binary(doc.clone())
+        } else {
+            DxfWriter::new(doc.clone())
+        };
+        let bytes = writer.write_to_vec().unwrap();
+        let read = DxfReader::from_reader(Cursor::new(bytes)).unwrap().read().unwrap();
+
+        assert_eq!(names(&read), ["$ZZ_VENDOR", "$AA_ORIGIN"], "binary: {}", binary);
+        let vendor = &read.header.unknown_variables["$ZZ_VENDOR"];
+        assert_eq!(vendor[0], RawGroupCode::new(1, "ACME"), "binary: {}", binary);
+        assert_eq!(vendor[1].code, 70);
+        assert_eq!(vendor[1].value.trim(), "7");
+        let origin: Vec<(i32, f64)> = read.header.unknown_variables["$AA_ORIGIN"]
+            .iter()
+            .map(|c| (c.code, c.value.trim().parse().unwrap()))
+            .collect();
+        assert_eq!(origin, [(10, 1.5), (20, -2.25), (30, 0.0)], "binary: {}", binary);
+    }
+}
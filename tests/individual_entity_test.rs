@@ -6,6 +6,12 @@ use acadrust::types::{Color, Vector2, Vector3};
 use acadrust::{CadDocument, DxfWriter};
 use std::f64::consts::PI;
 
+/// Path in the system temp directory for a test output file, so running the
+/// tests leaves the working tree untouched
+fn output_path(name: &str) -> String {
+    std::env::temp_dir().join(name).to_string_lossy().into_owned()
+}
+
 /// Create a document with a single entity type
 fn create_single_entity_doc(entity_name: &str) -> Option<CadDocument> {
     let mut doc = CadDocument::new();
@@ -269,7 +275,7 @@ fn test_individual_entities_r13() {
         if let Some(mut doc) = create_single_entity_doc(entity_name) {
             doc.version = DxfVersion::AC1012;
             
-            let filename = format!("entity_R13_{}.dxf", entity_name);
+            let filename = output_path(&format!("entity_R13_{}.dxf", entity_name));
             let writer = DxfWriter::new(doc);
             let result = writer.write_to_file(&filename);
             
@@ -310,7 +316,7 @@ fn test_individual_entities_r14() {
         if let Some(mut doc) = create_single_entity_doc(entity_name) {
             doc.version = DxfVersion::AC1014;
             
-            let filename = format!("entity_R14_{}.dxf", entity_name);
+            let filename = output_path(&format!("entity_R14_{}.dxf", entity_name));
             let writer = DxfWriter::new(doc);
             let result = writer.write_to_file(&filename);
             
@@ -351,7 +357,7 @@ fn test_individual_entities_2010() {
         if let Some(mut doc) = create_single_entity_doc(entity_name) {
             doc.version = DxfVersion::AC1024;
             
-            let filename = format!("entity_2010_{}.dxf", entity_name);
+            let filename = output_path(&format!("entity_2010_{}.dxf", entity_name));
             let writer = DxfWriter::new(doc);
             let result = writer.write_to_file(&filename);
             
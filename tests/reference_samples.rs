@@ -1,4 +1,8 @@
+use acadrust::io::dwg::{write_dwg_to_bytes, DwgReader, DwgReaderConfiguration};
 use acadrust::io::dxf::{DxfReader, DxfReaderConfiguration};
+use acadrust::notification::NotificationType;
+use acadrust::types::DxfVersion;
+use std::io::Cursor;
 use std::path::Path;
 
 fn read_sample(path: &str) -> String {
@@ -78,3 +82,51 @@ fn test_reference_samples() {
         panic!("{} files failed to read", failures.len());
     }
 }
+
+/// DWG files saved by AutoCAD, one per release the writer targets. They
+/// live next to the DXF samples in `reference_samples/`.
+const REFERENCE_DWG_SAMPLES: [(&str, DxfVersion); 3] = [
+    ("sample_AC1015.dwg", DxfVersion::AC1015),
+    ("sample_AC1018.dwg", DxfVersion::AC1018),
+    ("sample_AC1024.dwg", DxfVersion::AC1024),
+];
+
+#[test]
+#[ignore = "needs the DWG files in reference_samples/"]
+fn test_reference_dwg_samples() {
+    let sample_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("reference_samples");
+
+    for (name, version) in REFERENCE_DWG_SAMPLES {
+        let path = sample_dir.join(name);
+        let doc = DwgReader::read_from_file(&path, DwgReaderConfiguration::default())
+            .unwrap_or_else(|e| panic!("{name}: {e}"));
+
+        assert_eq!(doc.version, version, "{name}");
+        assert!(
+            doc.notifications.of_type(NotificationType::Error).is_empty(),
+            "{name}: {:?}",
+            doc.notifications.iter().map(|n| &n.message).collect::<Vec<_>>()
+        );
+        assert!(doc.layers.get("0").is_some(), "{name}");
+        assert!(doc.line_types.get("Continuous").is_some(), "{name}");
+        assert!(doc.text_styles.get("Standard").is_some(), "{name}");
+        assert!(doc.block_records.get("*Model_Space").is_some(), "{name}");
+        assert!(doc.block_records.get("*Paper_Space").is_some(), "{name}");
+        assert!(doc.entities().count() > 0, "{name}");
+        for entity in doc.entities() {
+            let layer = &entity.common().layer;
+            let name_of = entity.dxf_name();
+            assert!(doc.layers.get(layer).is_some(), "{name}: {name_of} on missing layer {layer}");
+        }
+
+        // What was read from a file another tool wrote survives our writer
+        let entities = doc.entities().count();
+        let layers: Vec<String> = doc.layers.iter().map(|l| l.name.clone()).collect();
+        let bytes = write_dwg_to_bytes(doc).unwrap();
+        let doc = DwgReader::read_from_stream(Cursor::new(bytes), DwgReaderConfiguration::default())
+            .unwrap_or_else(|e| panic!("{name} rewritten: {e}"));
+        assert_eq!(doc.version, version, "{name}");
+        assert_eq!(doc.entities().count(), entities, "{name}");
+        assert_eq!(doc.layers.iter().map(|l| l.name.clone()).collect::<Vec<_>>(), layers, "{name}");
+    }
+}